@@ -0,0 +1,155 @@
+use super::*;
+use std::fmt;
+
+/// Something that makes content hard to use with a screen reader or without color vision.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AccessibilityIssue {
+    ImageWithoutAltText { src: String },
+    TableWithoutHeader,
+    MathOnlyLink, // no text to announce as the link's name
+    ColorOnlyAnnotation { annotation_id: String }, // styled with colors but carrying no text
+}
+
+/// An accessibility problem found by `MathDocument::accessibility_lint`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccessibilityWarning {
+    pub issue: AccessibilityIssue,
+    pub path: Vec<LocationStep>, // enclosing documents, sections and panels, outermost first
+}
+
+impl MathDocument {
+    /// Content that excludes some readers, embedded and tooltip documents included, for authors
+    /// to fix:
+    /// - images without alt text (an empty `alt_text` marks an image as decorative and is fine)
+    /// - tables without header rows, which screen readers cannot announce cells against
+    /// - links whose visible content is only math
+    /// - annotations that convey their meaning only through their colors
+    pub fn accessibility_lint(&self) -> Vec<AccessibilityWarning> {
+        let mut linter = AccessibilityLinter::default();
+        linter.visit_document(self);
+        linter.warnings
+    }
+}
+
+#[derive(Default)]
+struct AccessibilityLinter {
+    path: Vec<LocationStep>,
+    warnings: Vec<AccessibilityWarning>,
+}
+
+impl AccessibilityLinter {
+    fn warn(&mut self, issue: AccessibilityIssue) {
+        self.warnings.push(AccessibilityWarning {
+            issue,
+            path: self.path.clone(),
+        });
+    }
+}
+
+impl<'doc> DocumentVisitor<'doc> for AccessibilityLinter {
+    fn visit_document(&mut self, document: &'doc MathDocument) {
+        self.path.push(LocationStep::Document(document.id.clone()));
+        walk_document(self, document);
+        self.path.pop();
+    }
+
+    fn visit_section(&mut self, section: &'doc Section) {
+        self.path.push(LocationStep::Section(section.id.clone()));
+        walk_section(self, section);
+        self.path.pop();
+    }
+
+    fn visit_panel(&mut self, panel: &'doc Panel) {
+        self.path.push(LocationStep::Panel(panel.id.clone()));
+        walk_panel(self, panel);
+        self.path.pop();
+    }
+
+    fn visit_content_node(&mut self, node: &'doc SectionContentNode) {
+        match node {
+            SectionContentNode::Image(image) if image.alt_text.is_none() => {
+                self.warn(AccessibilityIssue::ImageWithoutAltText {
+                    src: image.src.clone(),
+                })
+            }
+            SectionContentNode::Table(table) if table.header_rows.is_empty() => {
+                self.warn(AccessibilityIssue::TableWithoutHeader)
+            }
+            SectionContentNode::AnnotationOverlay(overlay) => {
+                for annotation in &overlay.annotations {
+                    if is_color_only(annotation) {
+                        self.warn(AccessibilityIssue::ColorOnlyAnnotation {
+                            annotation_id: annotation.id.clone(),
+                        });
+                    }
+                }
+            }
+            _ => {}
+        }
+        walk_content_node(self, node);
+    }
+
+    fn visit_segment(&mut self, segment: &'doc RichTextSegment) {
+        if let RichTextSegment::Link { content, .. } = segment
+            && is_math_only(content)
+        {
+            self.warn(AccessibilityIssue::MathOnlyLink);
+        }
+        walk_segment(self, segment);
+    }
+}
+
+/// Whether `segments` hold math and nothing else but whitespace.
+fn is_math_only(segments: &[RichTextSegment]) -> bool {
+    let mut has_math = false;
+    for segment in segments {
+        match segment {
+            RichTextSegment::Math(_) => has_math = true,
+            RichTextSegment::Text(text) | RichTextSegment::StyledText { text, .. }
+                if text.trim().is_empty() => {}
+            _ => return false,
+        }
+    }
+    has_math
+}
+
+fn is_color_only(annotation: &Annotation) -> bool {
+    let Some(styling) = &annotation.styling else {
+        return false;
+    };
+    let colored = styling.color.is_some()
+        || styling.background_color.is_some()
+        || styling.border_color.is_some();
+    let has_text = annotation
+        .annotation_content
+        .iter()
+        .any(|segment| match segment {
+            RichTextSegment::Text(text) | RichTextSegment::StyledText { text, .. } => {
+                !text.trim().is_empty()
+            }
+            _ => true,
+        });
+    colored && !has_text
+}
+
+impl fmt::Display for AccessibilityWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = LocationStep::path_string(&self.path);
+        match &self.issue {
+            AccessibilityIssue::ImageWithoutAltText { src } => {
+                write!(f, "image `{}` in `{}` has no alt text", src, path)
+            }
+            AccessibilityIssue::TableWithoutHeader => {
+                write!(f, "table in `{}` has no header row", path)
+            }
+            AccessibilityIssue::MathOnlyLink => {
+                write!(f, "link in `{}` has only math as its text", path)
+            }
+            AccessibilityIssue::ColorOnlyAnnotation { annotation_id } => write!(
+                f,
+                "annotation `{}` in `{}` is distinguished only by color",
+                annotation_id, path
+            ),
+        }
+    }
+}
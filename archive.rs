@@ -0,0 +1,193 @@
+/// Minimal ZIP writer for the container formats the exporters produce (XLSX, ODS).
+///
+/// Entries are stored uncompressed, which every ZIP reader accepts and which ODS requires for its
+/// leading `mimetype` entry anyway. Entries are written in insertion order.
+#[derive(Debug, Clone, Default)]
+pub struct ZipWriter {
+    data: Vec<u8>,
+    entries: Vec<ZipEntry>,
+}
+
+#[derive(Debug, Clone)]
+struct ZipEntry {
+    name: String,
+    crc: u32,
+    size: u32,
+    offset: u32,
+}
+
+impl ZipWriter {
+    pub fn new() -> Self {
+        ZipWriter::default()
+    }
+
+    pub fn add_file(&mut self, name: &str, contents: &[u8]) {
+        let entry = ZipEntry {
+            name: name.to_string(),
+            crc: crc32(contents),
+            size: contents.len() as u32,
+            offset: self.data.len() as u32,
+        };
+        self.data.extend(0x0403_4b50u32.to_le_bytes()); // local file header
+        self.data.extend(20u16.to_le_bytes()); // version needed
+        self.entry_fields(&entry);
+        self.data.extend(name.as_bytes());
+        self.data.extend(contents);
+        self.entries.push(entry);
+    }
+
+    pub fn finish(mut self) -> Vec<u8> {
+        let directory_offset = self.data.len() as u32;
+        for entry in std::mem::take(&mut self.entries) {
+            self.data.extend(0x0201_4b50u32.to_le_bytes()); // central directory header
+            self.data.extend(20u16.to_le_bytes()); // version made by
+            self.data.extend(20u16.to_le_bytes()); // version needed
+            self.entry_fields(&entry);
+            self.data.extend(0u16.to_le_bytes()); // comment length
+            self.data.extend(0u16.to_le_bytes()); // disk number
+            self.data.extend(0u16.to_le_bytes()); // internal attributes
+            self.data.extend(0u32.to_le_bytes()); // external attributes
+            self.data.extend(entry.offset.to_le_bytes());
+            self.data.extend(entry.name.as_bytes());
+            self.entries.push(entry);
+        }
+        let directory_size = self.data.len() as u32 - directory_offset;
+        let count = self.entries.len() as u16;
+        self.data.extend(0x0605_4b50u32.to_le_bytes()); // end of central directory
+        self.data.extend(0u16.to_le_bytes()); // this disk
+        self.data.extend(0u16.to_le_bytes()); // directory disk
+        self.data.extend(count.to_le_bytes());
+        self.data.extend(count.to_le_bytes());
+        self.data.extend(directory_size.to_le_bytes());
+        self.data.extend(directory_offset.to_le_bytes());
+        self.data.extend(0u16.to_le_bytes()); // comment length
+        self.data
+    }
+
+    /// The fields shared by local and central headers, from the flags to the extra field length.
+    fn entry_fields(&mut self, entry: &ZipEntry) {
+        self.data.extend(0x0800u16.to_le_bytes()); // flags: UTF-8 names
+        self.data.extend(0u16.to_le_bytes()); // method: stored
+        self.data.extend(0u16.to_le_bytes()); // modification time
+        self.data.extend(0x0021u16.to_le_bytes()); // modification date: 1980-01-01
+        self.data.extend(entry.crc.to_le_bytes());
+        self.data.extend(entry.size.to_le_bytes()); // compressed size
+        self.data.extend(entry.size.to_le_bytes());
+        self.data.extend((entry.name.len() as u16).to_le_bytes());
+        self.data.extend(0u16.to_le_bytes()); // extra field length
+    }
+}
+
+/// Reads the entries of a ZIP archive in directory order. Only stored entries are supported,
+/// as `ZipWriter` writes them; checksums are verified.
+pub fn read_zip(bytes: &[u8]) -> Result<Vec<(String, Vec<u8>)>, ZipError> {
+    let u16_at = |offset: usize| -> Result<usize, ZipError> {
+        let field = bytes.get(offset..offset + 2).ok_or(ZipError::Malformed)?;
+        Ok(u16::from_le_bytes([field[0], field[1]]) as usize)
+    };
+    let u32_at = |offset: usize| -> Result<u32, ZipError> {
+        let field = bytes.get(offset..offset + 4).ok_or(ZipError::Malformed)?;
+        Ok(u32::from_le_bytes([field[0], field[1], field[2], field[3]]))
+    };
+    // The end of central directory record is last, followed by a comment of up to 64 KiB.
+    let end = (0..bytes.len().saturating_sub(21))
+        .rev()
+        .take(0x10000 + 22)
+        .find(|&offset| u32_at(offset) == Ok(0x0605_4b50))
+        .ok_or(ZipError::Malformed)?;
+    let count = u16_at(end + 10)?;
+    let mut offset = u32_at(end + 16)? as usize;
+
+    let mut entries = Vec::with_capacity(count);
+    for _ in 0..count {
+        if u32_at(offset)? != 0x0201_4b50 {
+            return Err(ZipError::Malformed);
+        }
+        let name_length = u16_at(offset + 28)?;
+        let name = bytes
+            .get(offset + 46..offset + 46 + name_length)
+            .ok_or(ZipError::Malformed)?;
+        let name = String::from_utf8_lossy(name).into_owned();
+        if u16_at(offset + 10)? != 0 {
+            return Err(ZipError::Compressed { name });
+        }
+        let crc = u32_at(offset + 16)?;
+        let size = u32_at(offset + 20)? as usize;
+        let local = u32_at(offset + 42)? as usize;
+        if u32_at(local)? != 0x0403_4b50 {
+            return Err(ZipError::Malformed);
+        }
+        let start = local + 30 + u16_at(local + 26)? + u16_at(local + 28)?;
+        let contents = bytes
+            .get(start..start + size)
+            .ok_or(ZipError::Malformed)?
+            .to_vec();
+        if crc32(&contents) != crc {
+            return Err(ZipError::BadChecksum { name });
+        }
+        entries.push((name, contents));
+        offset += 46 + name_length + u16_at(offset + 30)? + u16_at(offset + 32)?;
+    }
+    Ok(entries)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ZipError {
+    Malformed,
+    Compressed { name: String },
+    BadChecksum { name: String },
+}
+
+impl std::fmt::Display for ZipError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ZipError::Malformed => write!(f, "not a ZIP archive, or a damaged one"),
+            ZipError::Compressed { name } => {
+                write!(
+                    f,
+                    "ZIP entry `{}` is compressed, which is not supported",
+                    name
+                )
+            }
+            ZipError::BadChecksum { name } => {
+                write!(f, "ZIP entry `{}` does not match its checksum", name)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ZipError {}
+
+/// CRC-32 (IEEE) as used by ZIP.
+pub fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+/// Escapes `text` for use in XML content and attribute values.
+pub fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // Control characters other than tab and newlines are not allowed in XML 1.0.
+            c if c.is_control() && !matches!(c, '\t' | '\n' | '\r') => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
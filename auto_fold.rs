@@ -0,0 +1,338 @@
+use super::*;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use ts_rs::TS;
+
+/// Limits beyond which `MathNode::auto_fold` collapses parts of an expression.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct AutoFoldOptions {
+    pub max_nodes: usize, // Visible nodes, placeholders included
+    pub max_terms: usize, // Visible operands of a single sum, product or connective
+}
+
+impl Default for AutoFoldOptions {
+    fn default() -> Self {
+        AutoFoldOptions {
+            max_nodes: 40,
+            max_terms: 6,
+        }
+    }
+}
+
+impl MathNode {
+    /// Collapses the parts of a large expression into `Folded` placeholders that renderers show
+    /// as their summary until expanded:
+    ///
+    /// - an operation with more than `max_terms` operands keeps the first `max_terms - 1` and
+    ///   folds the rest into one operand, e.g. `a + b + …(12 terms)`
+    /// - if the expression still has more than `max_nodes` visible nodes, it is shown down to the
+    ///   deepest level that fits and every deeper subtree becomes a `…` placeholder
+    ///
+    /// Existing placeholders are left as they are, so folding twice changes nothing.
+    pub fn auto_fold(&self, options: &AutoFoldOptions) -> MathNode {
+        let folded = self.transform(&mut TermFolder {
+            max_terms: options.max_terms.max(2),
+        });
+        let levels = visible_levels(&folded);
+        let total: usize = levels.iter().sum();
+        if total <= options.max_nodes {
+            return folded;
+        }
+        // Shown levels plus the placeholder level below them must fit the budget.
+        let mut shown_depth = 0;
+        let mut visible = levels[0];
+        while shown_depth + 2 < levels.len()
+            && visible + levels[shown_depth + 1] + levels[shown_depth + 2] <= options.max_nodes
+        {
+            shown_depth += 1;
+            visible += levels[shown_depth];
+        }
+        collapse_below(&folded, 0, shown_depth).unwrap_or(folded)
+    }
+
+    /// Expands every `Folded` placeholder, restoring the expression `auto_fold` started from.
+    pub fn unfold_all(&self) -> MathNode {
+        let unfolded = self.transform(&mut Unfolder);
+        match unfolded.content.as_ref() {
+            MathNodeContent::Folded { content, .. } => content.as_ref().clone(),
+            _ => unfolded,
+        }
+    }
+
+    pub fn is_folded(&self) -> bool {
+        matches!(*self.content, MathNodeContent::Folded { .. })
+    }
+}
+
+impl MathDocument {
+    /// Applies `MathNode::auto_fold` to every expression in the document, returning the number
+    /// of expressions that were folded.
+    pub fn auto_fold(&mut self, options: &AutoFoldOptions) -> usize {
+        let mut folder = DocumentFolder { options, count: 0 };
+        folder.visit_document_mut(self);
+        folder.count
+    }
+}
+
+struct DocumentFolder<'a> {
+    options: &'a AutoFoldOptions,
+    count: usize,
+}
+
+impl DocumentVisitorMut for DocumentFolder<'_> {
+    fn visit_math_node_mut(&mut self, node: &mut MathNode) {
+        let folded = node.auto_fold(self.options);
+        if folded != *node {
+            *node = folded;
+            self.count += 1;
+        }
+    }
+}
+
+/// Number of visible nodes at each depth; placeholders count as one node and hide their content.
+fn visible_levels(node: &MathNode) -> Vec<usize> {
+    let mut levels = vec![];
+    let mut level = vec![node];
+    while !level.is_empty() {
+        levels.push(level.len());
+        level = level
+            .into_iter()
+            .filter(|node| !node.is_folded())
+            .flat_map(|node| node.content.children_with_paths())
+            .map(|(_, child)| child)
+            .collect();
+    }
+    levels
+}
+
+/// Folds every non-leaf subtree deeper than `shown_depth`; `None` if nothing was folded.
+fn collapse_below(node: &MathNode, depth: usize, shown_depth: usize) -> Option<MathNode> {
+    if node.is_folded() {
+        return None;
+    }
+    if depth > shown_depth {
+        if node.content.children_with_paths().is_empty() {
+            return None;
+        }
+        return Some(folded_node(
+            format!("{}-folded", node.id),
+            "…".to_string(),
+            node.clone(),
+        ));
+    }
+    node.content
+        .map_children(|child| collapse_below(child, depth + 1, shown_depth))
+        .map(|content| MathNode {
+            id: node.id.clone(),
+            content: Arc::new(content),
+        })
+}
+
+fn folded_node(id: String, summary: String, content: MathNode) -> MathNode {
+    MathNode {
+        id,
+        content: Arc::new(MathNodeContent::Folded {
+            summary,
+            content: Arc::new(content),
+        }),
+    }
+}
+
+/// Folds the trailing operands of operations with too many of them.
+struct TermFolder {
+    max_terms: usize,
+}
+
+impl MathNodeTransformer for TermFolder {
+    fn enter_node(&mut self, node: &MathNode) -> bool {
+        !node.is_folded()
+    }
+
+    fn transform_node(&mut self, node: &MathNode) -> Option<MathNode> {
+        let keep = self.max_terms - 1;
+        let content = match node.content.as_ref() {
+            MathNodeContent::BinaryOperation {
+                operation_type,
+                terms,
+            } => MathNodeContent::BinaryOperation {
+                operation_type: operation_type.clone(),
+                terms: fold_terms(&node.id, terms, keep, |hidden| {
+                    MathNodeContent::BinaryOperation {
+                        operation_type: operation_type.clone(),
+                        terms: hidden,
+                    }
+                })?,
+            },
+            MathNodeContent::Additions { terms } => MathNodeContent::Additions {
+                terms: fold_terms(&node.id, terms, keep, |hidden| MathNodeContent::Additions {
+                    terms: hidden,
+                })?,
+            },
+            MathNodeContent::Multiplications { terms } => MathNodeContent::Multiplications {
+                terms: fold_terms(&node.id, terms, keep, |hidden| {
+                    MathNodeContent::Multiplications { terms: hidden }
+                })?,
+            },
+            MathNodeContent::And(operands) => MathNodeContent::And(fold_operands(
+                &node.id,
+                operands,
+                keep,
+                MathNodeContent::And,
+            )?),
+            MathNodeContent::Or(operands) => MathNodeContent::Or(fold_operands(
+                &node.id,
+                operands,
+                keep,
+                MathNodeContent::Or,
+            )?),
+            _ => return None,
+        };
+        Some(MathNode {
+            id: node.id.clone(),
+            content: Arc::new(content),
+        })
+    }
+}
+
+/// Keeps the first `keep` terms and folds the rest into one term. The folded term takes the
+/// operator of the first hidden term, which renderers do not draw inside the folded operation,
+/// so expanding the placeholder shows the original sequence.
+fn fold_terms<Op: Clone>(
+    id: &str,
+    terms: &[(Op, MathNode)],
+    keep: usize,
+    rebuild: impl FnOnce(Vec<(Op, MathNode)>) -> MathNodeContent,
+) -> Option<Vec<(Op, MathNode)>> {
+    if terms.len() <= keep + 1 {
+        return None;
+    }
+    let hidden = terms[keep..].to_vec();
+    let operator = hidden[0].0.clone();
+    let mut kept = terms[..keep].to_vec();
+    kept.push((operator, folded_terms(id, hidden.len(), rebuild(hidden))));
+    Some(kept)
+}
+
+fn fold_operands(
+    id: &str,
+    operands: &[MathNode],
+    keep: usize,
+    rebuild: impl FnOnce(Vec<MathNode>) -> MathNodeContent,
+) -> Option<Vec<MathNode>> {
+    if operands.len() <= keep + 1 {
+        return None;
+    }
+    let hidden = operands[keep..].to_vec();
+    let mut kept = operands[..keep].to_vec();
+    kept.push(folded_terms(id, hidden.len(), rebuild(hidden)));
+    Some(kept)
+}
+
+fn folded_terms(id: &str, count: usize, operation: MathNodeContent) -> MathNode {
+    folded_node(
+        format!("{}-folded", id),
+        format!("…({} terms)", count),
+        MathNode {
+            id: format!("{}-folded-terms", id),
+            content: Arc::new(operation),
+        },
+    )
+}
+
+/// Replaces placeholders by their content, splicing folded operands back into their operation.
+struct Unfolder;
+
+impl MathNodeTransformer for Unfolder {
+    fn transform_node(&mut self, node: &MathNode) -> Option<MathNode> {
+        let spliced = match node.content.as_ref() {
+            MathNodeContent::BinaryOperation {
+                operation_type,
+                terms,
+            } => splice_terms(terms, |content| match content {
+                MathNodeContent::BinaryOperation {
+                    operation_type: inner_type,
+                    terms,
+                } if inner_type == operation_type => Some(terms),
+                _ => None,
+            })
+            .map(|terms| MathNodeContent::BinaryOperation {
+                operation_type: operation_type.clone(),
+                terms,
+            }),
+            MathNodeContent::Additions { terms } => splice_terms(terms, |content| match content {
+                MathNodeContent::Additions { terms } => Some(terms),
+                _ => None,
+            })
+            .map(|terms| MathNodeContent::Additions { terms }),
+            MathNodeContent::Multiplications { terms } => {
+                splice_terms(terms, |content| match content {
+                    MathNodeContent::Multiplications { terms } => Some(terms),
+                    _ => None,
+                })
+                .map(|terms| MathNodeContent::Multiplications { terms })
+            }
+            MathNodeContent::And(operands) => splice_operands(operands, |content| match content {
+                MathNodeContent::And(operands) => Some(operands),
+                _ => None,
+            })
+            .map(MathNodeContent::And),
+            MathNodeContent::Or(operands) => splice_operands(operands, |content| match content {
+                MathNodeContent::Or(operands) => Some(operands),
+                _ => None,
+            })
+            .map(MathNodeContent::Or),
+            _ => None,
+        };
+        let content = spliced.as_ref().unwrap_or(&node.content);
+        let unwrapped = content.map_children(|child| match child.content.as_ref() {
+            MathNodeContent::Folded { content, .. } => Some(content.as_ref().clone()),
+            _ => None,
+        });
+        unwrapped.or(spliced).map(|content| MathNode {
+            id: node.id.clone(),
+            content: Arc::new(content),
+        })
+    }
+}
+
+/// Folded terms holding an operation of the same kind, replaced by that operation's terms; the
+/// first of them takes the operator of the folded term. `None` if there are none.
+fn splice_terms<Op: Clone>(
+    terms: &[(Op, MathNode)],
+    same_kind: impl Fn(&MathNodeContent) -> Option<&Vec<(Op, MathNode)>>,
+) -> Option<Vec<(Op, MathNode)>> {
+    let mut changed = false;
+    let mut spliced = vec![];
+    for (operator, term) in terms {
+        if let MathNodeContent::Folded { content, .. } = term.content.as_ref()
+            && let Some([(_, first), rest @ ..]) = same_kind(&content.content).map(Vec::as_slice)
+        {
+            spliced.push((operator.clone(), first.clone()));
+            spliced.extend(rest.iter().cloned());
+            changed = true;
+        } else {
+            spliced.push((operator.clone(), term.clone()));
+        }
+    }
+    changed.then_some(spliced)
+}
+
+fn splice_operands(
+    operands: &[MathNode],
+    same_kind: impl Fn(&MathNodeContent) -> Option<&Vec<MathNode>>,
+) -> Option<Vec<MathNode>> {
+    let mut changed = false;
+    let mut spliced = vec![];
+    for operand in operands {
+        if let MathNodeContent::Folded { content, .. } = operand.content.as_ref()
+            && let Some(inner) = same_kind(&content.content)
+        {
+            spliced.extend(inner.iter().cloned());
+            changed = true;
+        } else {
+            spliced.push(operand.clone());
+        }
+    }
+    changed.then_some(spliced)
+}
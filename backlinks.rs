@@ -0,0 +1,215 @@
+use super::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use ts_rs::TS;
+
+/// Which documents point at which ids, built from the `LinkTarget`s in a set of documents and the
+/// `CrossReference`s they declare.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct BacklinkIndex {
+    pub backlinks: BTreeMap<String, Vec<Backlink>>, // keyed by target id
+}
+
+/// One reference to `target_id`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct Backlink {
+    pub source_document_id: String,
+    pub source_title: Option<String>,
+    pub source_section_id: Option<String>, // innermost section holding the link; None for cross references
+    pub target_id: String,
+    pub kind: BacklinkKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub enum BacklinkKind {
+    Link,
+    CrossReference(CrossReferenceType),
+}
+
+impl LinkTarget {
+    /// The id of the page, section, definition, theorem or equation this link points at, if it points
+    /// at something inside the corpus.
+    pub fn target_id(&self) -> Option<&str> {
+        match self {
+            LinkTarget::InternalPageId(id)
+            | LinkTarget::TheoremId(id)
+            | LinkTarget::Equation(id) => Some(id),
+            LinkTarget::DefinitionId { term_id, .. }
+            | LinkTarget::DefinitionAspect { term_id, .. } => Some(term_id),
+            LinkTarget::ObjectConstructorTemplate { template_id, .. } => Some(template_id),
+            LinkTarget::Url(_)
+            | LinkTarget::GlossaryTerm(_)
+            | LinkTarget::BibliographyKey(_)
+            | LinkTarget::InteractiveElementId(_)
+            | LinkTarget::TooltipDocument(_)
+            | LinkTarget::AnimationTrigger { .. } => None,
+        }
+    }
+}
+
+impl BacklinkIndex {
+    pub fn new() -> Self {
+        BacklinkIndex::default()
+    }
+
+    pub fn build<'a>(documents: impl IntoIterator<Item = &'a MathDocument>) -> Self {
+        let mut index = BacklinkIndex::new();
+        for document in documents {
+            index.add_document(document);
+        }
+        index
+    }
+
+    /// Records every link and cross reference in `document`, including those in embedded and
+    /// tooltip documents, which are attributed to the document they appear in.
+    pub fn add_document(&mut self, document: &MathDocument) {
+        let mut collector = BacklinkCollector {
+            documents: vec![],
+            sections: vec![],
+            backlinks: vec![],
+        };
+        collector.visit_document(document);
+        for backlink in collector.backlinks {
+            self.backlinks
+                .entry(backlink.target_id.clone())
+                .or_default()
+                .push(backlink);
+        }
+    }
+
+    pub fn backlinks_to(&self, target_id: &str) -> &[Backlink] {
+        self.backlinks
+            .get(target_id)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// References from other documents to `document` or any of its sections,
+    /// one per referring document, ordered by title and then id.
+    pub fn referenced_by(&self, document: &MathDocument) -> Vec<&Backlink> {
+        let mut section_ids = SectionIdCollector::default();
+        section_ids.visit_document(document);
+
+        let mut seen = HashSet::new();
+        let mut referring: Vec<&Backlink> = std::iter::once(document.id.as_str())
+            .chain(section_ids.ids)
+            .flat_map(|id| self.backlinks_to(id))
+            .filter(|backlink| backlink.source_document_id != document.id)
+            .filter(|backlink| seen.insert(backlink.source_document_id.as_str()))
+            .collect();
+        referring.sort_by(|a, b| {
+            (&a.source_title, &a.source_document_id).cmp(&(&b.source_title, &b.source_document_id))
+        });
+        referring
+    }
+
+    /// A "Referenced by" section listing links to every document that refers to `document`,
+    /// or `None` when nothing does.
+    pub fn referenced_by_section(&self, document: &MathDocument) -> Option<Section> {
+        let referring = self.referenced_by(document);
+        if referring.is_empty() {
+            return None;
+        }
+        let items = referring
+            .into_iter()
+            .map(|backlink| {
+                let label = backlink
+                    .source_title
+                    .clone()
+                    .unwrap_or_else(|| backlink.source_document_id.clone());
+                ListItemNode {
+                    content: vec![SectionContentNode::RichText(RichText {
+                        segments: vec![RichTextSegment::Link {
+                            content: vec![RichTextSegment::Text(label)],
+                            target: LinkTarget::InternalPageId(backlink.source_document_id.clone()),
+                            tooltip: None,
+                        }],
+                        alignment: None,
+                    })],
+                }
+            })
+            .collect();
+        Some(Section {
+            id: format!("{}-referenced-by", document.id),
+            title: Some(RichText::text("Referenced by".to_string())),
+            content: SectionContentNode::List(ListNode {
+                items,
+                style: ListStyle::Unordered(UnorderedListStyle::Disc),
+                start_index: None,
+            }),
+            metadata: vec![("generated".to_string(), "backlinks".to_string())],
+            display_options: None,
+            difficulty: None,
+            variant_seed: None,
+        })
+    }
+}
+
+struct BacklinkCollector {
+    documents: Vec<(String, Option<String>)>, // (id, title) of the enclosing documents
+    sections: Vec<String>,
+    backlinks: Vec<Backlink>,
+}
+
+impl BacklinkCollector {
+    fn push(&mut self, target_id: &str, kind: BacklinkKind, in_section: bool) {
+        let Some((source_document_id, source_title)) = self.documents.first().cloned() else {
+            return;
+        };
+        self.backlinks.push(Backlink {
+            source_document_id,
+            source_title,
+            source_section_id: self.sections.last().filter(|_| in_section).cloned(),
+            target_id: target_id.to_string(),
+            kind,
+        });
+    }
+}
+
+impl<'doc> DocumentVisitor<'doc> for BacklinkCollector {
+    fn visit_document(&mut self, document: &'doc MathDocument) {
+        let content_type = &document.content_type;
+        self.documents.push((
+            document.id.clone(),
+            content_type.title().map(str::to_string),
+        ));
+        if let Some(relationships) = content_type.relationships() {
+            for reference in &relationships.cross_references {
+                let kind = BacklinkKind::CrossReference(reference.reference_type.clone());
+                self.push(&reference.target_id, kind, false);
+            }
+        }
+        walk_document(self, document);
+        self.documents.pop();
+    }
+
+    fn visit_section(&mut self, section: &'doc Section) {
+        self.sections.push(section.id.clone());
+        walk_section(self, section);
+        self.sections.pop();
+    }
+
+    fn visit_segment(&mut self, segment: &'doc RichTextSegment) {
+        if let RichTextSegment::Link { target, .. } = segment
+            && let Some(target_id) = target.target_id()
+        {
+            self.push(target_id, BacklinkKind::Link, true);
+        }
+        walk_segment(self, segment);
+    }
+}
+
+#[derive(Default)]
+struct SectionIdCollector<'doc> {
+    ids: Vec<&'doc str>,
+}
+
+impl<'doc> DocumentVisitor<'doc> for SectionIdCollector<'doc> {
+    fn visit_section(&mut self, section: &'doc Section) {
+        self.ids.push(&section.id);
+        walk_section(self, section);
+    }
+}
@@ -0,0 +1,127 @@
+use super::*;
+use std::fmt;
+
+impl BibEntry {
+    /// The fields BibTeX requires for `entry_type`, each as its accepted alternatives (a book
+    /// needs an author or an editor). Unknown entry types require nothing.
+    pub fn required_fields(entry_type: &str) -> &'static [&'static [&'static str]] {
+        match entry_type.to_ascii_lowercase().as_str() {
+            "article" => &[&["author"], &["title"], &["journal"], &["year"]],
+            "book" => &[&["author", "editor"], &["title"], &["publisher"], &["year"]],
+            "inproceedings" | "conference" => &[&["author"], &["title"], &["booktitle"], &["year"]],
+            "incollection" => &[
+                &["author"],
+                &["title"],
+                &["booktitle"],
+                &["publisher"],
+                &["year"],
+            ],
+            "phdthesis" | "mastersthesis" => &[&["author"], &["title"], &["school"], &["year"]],
+            "techreport" => &[&["author"], &["title"], &["institution"], &["year"]],
+            "unpublished" => &[&["author"], &["title"], &["note"]],
+            _ => &[],
+        }
+    }
+
+    /// The value of `name`, matched case-insensitively, if it is not blank.
+    pub fn field(&self, name: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(field, value)| field.eq_ignore_ascii_case(name) && !value.trim().is_empty())
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// The required fields this entry lacks, alternatives joined with `/` (`author/editor`).
+    pub fn missing_fields(&self) -> Vec<String> {
+        BibEntry::required_fields(&self.entry_type)
+            .iter()
+            .filter(|alternatives| alternatives.iter().all(|name| self.field(name).is_none()))
+            .map(|alternatives| alternatives.join("/"))
+            .collect()
+    }
+}
+
+impl MathDocument {
+    /// Problems with the bibliography of a structured document: entries missing fields their
+    /// type requires, keys used by more than one entry, and entries no citation link in the
+    /// document points at. Citations in embedded documents count towards their own
+    /// bibliographies, not this one; citations of unknown keys are `validate_references`' job.
+    pub fn check_bibliography(&self) -> Vec<BibliographyIssue> {
+        let Some(structure) = self.content_type.structure() else {
+            return vec![];
+        };
+        let mut collector = CitationCollector::default();
+        collector.visit_document(self);
+
+        let mut issues = vec![];
+        for (i, entry) in structure.bibliography.iter().enumerate() {
+            for field in entry.missing_fields() {
+                issues.push(BibliographyIssue::MissingField {
+                    key: entry.key.clone(),
+                    field,
+                });
+            }
+            let earlier = &structure.bibliography[..i];
+            if earlier.iter().any(|e| e.key == entry.key) {
+                issues.push(BibliographyIssue::DuplicateKey {
+                    key: entry.key.clone(),
+                });
+            } else if !collector.cited.contains(&entry.key) {
+                issues.push(BibliographyIssue::UnusedEntry {
+                    key: entry.key.clone(),
+                });
+            }
+        }
+        issues
+    }
+}
+
+/// Collects the bibliography keys cited by the outermost document, skipping embedded ones.
+#[derive(Default)]
+struct CitationCollector {
+    depth: usize,
+    cited: Vec<String>,
+}
+
+impl<'doc> DocumentVisitor<'doc> for CitationCollector {
+    fn visit_document(&mut self, document: &'doc MathDocument) {
+        self.depth += 1;
+        walk_document(self, document);
+        self.depth -= 1;
+    }
+
+    fn visit_segment(&mut self, segment: &'doc RichTextSegment) {
+        if let RichTextSegment::Link {
+            target: LinkTarget::BibliographyKey(key),
+            ..
+        } = segment
+            && self.depth == 1
+        {
+            self.cited.push(key.clone());
+        }
+        walk_segment(self, segment);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BibliographyIssue {
+    MissingField { key: String, field: String },
+    DuplicateKey { key: String },
+    UnusedEntry { key: String },
+}
+
+impl fmt::Display for BibliographyIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BibliographyIssue::MissingField { key, field } => {
+                write!(f, "bibliography entry `{}` has no `{}`", key, field)
+            }
+            BibliographyIssue::DuplicateKey { key } => {
+                write!(f, "bibliography key `{}` is used by several entries", key)
+            }
+            BibliographyIssue::UnusedEntry { key } => {
+                write!(f, "bibliography entry `{}` is never cited", key)
+            }
+        }
+    }
+}
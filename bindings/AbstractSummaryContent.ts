@@ -6,8 +6,8 @@ import type { SourceReference } from "./SourceReference";
 
 export type AbstractSummaryContent = {
   abstraction_level: number;
-  key_properties: Array<string>;
-  source_references: Array<SourceReference>;
+  key_properties?: Array<string>;
+  source_references?: Array<SourceReference>;
   derivation_metadata: DerivationMetadata;
   content: SimplifiedContentStructure;
   presentation_config: PresentationConfig;
@@ -1,10 +1,11 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { ArrowStyle } from "./ArrowStyle";
+import type { UnitInterval } from "./UnitInterval";
 
 export type AlignmentArrow = {
   from_concept: string;
   to_concept: string;
-  alignment_strength: number;
+  alignment_strength: UnitInterval;
   alignment_type: string;
   visual_style: ArrowStyle;
 };
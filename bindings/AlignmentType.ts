@@ -4,6 +4,6 @@ import type { AlignmentPrecision } from "./AlignmentPrecision";
 export type AlignmentType = {
   alignment_id: string;
   name: string;
-  description: string | null;
+  description?: string | null;
   precision_level: AlignmentPrecision;
 };
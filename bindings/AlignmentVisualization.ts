@@ -4,7 +4,7 @@ import type { VisualizationType } from "./VisualizationType";
 
 export type AlignmentVisualization = {
   visualization_type: VisualizationType;
-  source_elements: Array<string>;
-  target_elements: Array<string>;
-  alignment_arrows: Array<AlignmentArrow>;
+  source_elements?: Array<string>;
+  target_elements?: Array<string>;
+  alignment_arrows?: Array<AlignmentArrow>;
 };
@@ -2,6 +2,6 @@
 
 export type AllowedInteraction = {
   interaction_type: string;
-  permissions: Array<string>;
-  restrictions: Array<string> | null;
+  permissions?: Array<string>;
+  restrictions?: Array<string> | null;
 };
@@ -9,9 +9,9 @@ export type AnimatedPresentationContent = {
   title: string;
   slide_count: number;
   auto_advance: boolean;
-  base_content: Array<Section>;
+  base_content?: Array<Section>;
   interaction_system: InteractionSystem;
   animation_timeline: AnimationTimeline;
-  control_bindings: Array<ControlBinding>;
-  interaction_points: Array<InteractionPoint>;
+  control_bindings?: Array<ControlBinding>;
+  interaction_points?: Array<InteractionPoint>;
 };
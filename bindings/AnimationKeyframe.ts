@@ -4,7 +4,7 @@ import type { EasingFunction } from "./EasingFunction";
 
 export type AnimationKeyframe = {
   time: number;
-  target_elements: Array<string>;
+  target_elements?: Array<string>;
   animation_type: AnimationType;
   properties: { [key in string]?: string };
   easing: EasingFunction;
@@ -5,7 +5,7 @@ import type { SyncGroup } from "./SyncGroup";
 
 export type AnimationTimeline = {
   total_duration: number;
-  keyframes: Array<AnimationKeyframe>;
-  interaction_points: Array<InteractionPoint>;
-  synchronization_groups: Array<SyncGroup>;
+  keyframes?: Array<AnimationKeyframe>;
+  interaction_points?: Array<InteractionPoint>;
+  synchronization_groups?: Array<SyncGroup>;
 };
@@ -3,12 +3,14 @@ import type { AnnotationPosition } from "./AnnotationPosition";
 import type { AnnotationStyling } from "./AnnotationStyling";
 import type { AnnotationType } from "./AnnotationType";
 import type { RichTextSegment } from "./RichTextSegment";
+import type { TextQuoteAnchor } from "./TextQuoteAnchor";
 
 export type Annotation = {
   id: string;
   target_selector: string;
-  annotation_content: Array<RichTextSegment>;
+  text_anchor?: TextQuoteAnchor | null;
+  annotation_content?: Array<RichTextSegment>;
   annotation_type: AnnotationType;
-  position: AnnotationPosition | null;
-  styling: AnnotationStyling | null;
+  position?: AnnotationPosition | null;
+  styling?: AnnotationStyling | null;
 };
@@ -4,7 +4,7 @@ import type { OverlayStyle } from "./OverlayStyle";
 import type { SectionContentNode } from "./SectionContentNode";
 
 export type AnnotationOverlay = {
-  base_content: Array<SectionContentNode>;
-  annotations: Array<Annotation>;
+  base_content?: Array<SectionContentNode>;
+  annotations?: Array<Annotation>;
   overlay_style: OverlayStyle;
 };
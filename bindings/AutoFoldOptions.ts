@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Limits beyond which `MathNode::auto_fold` collapses parts of an expression.
+ */
+export type AutoFoldOptions = { max_nodes: number; max_terms: number };
@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BacklinkKind } from "./BacklinkKind";
+
+/**
+ * One reference to `target_id`.
+ */
+export type Backlink = {
+  source_document_id: string;
+  source_title: string | null;
+  source_section_id: string | null;
+  target_id: string;
+  kind: BacklinkKind;
+};
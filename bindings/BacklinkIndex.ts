@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Backlink } from "./Backlink";
+
+/**
+ * Which documents point at which ids, built from the `LinkTarget`s in a set of documents and the
+ * `CrossReference`s they declare.
+ */
+export type BacklinkIndex = {
+  backlinks: { [key in string]?: Array<Backlink> };
+};
@@ -1,3 +1,7 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
-export type BibEntry = { entry_type: string; fields: Array<[string, string]> };
+export type BibEntry = {
+  key: string;
+  entry_type: string;
+  fields?: Array<[string, string]>;
+};
@@ -12,9 +12,9 @@ export type BlogPostContent = {
   writing_style: WritingStyle;
   target_audience: AudienceLevel;
   examples_included: boolean;
-  source_references: Array<SourceReference>;
+  source_references?: Array<SourceReference>;
   derivation_metadata: DerivationMetadata;
   content: SimplifiedContentStructure;
   presentation_config: PresentationConfig;
-  academic_metadata: AcademicMetadata | null;
+  academic_metadata?: AcademicMetadata | null;
 };
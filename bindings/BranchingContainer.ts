@@ -10,7 +10,7 @@ import type { ContainerType } from "./ContainerType";
 export type BranchingContainer = {
   container_id: string;
   container_type: ContainerType;
-  nodes: Array<BranchingNode>;
-  layout_config: ContainerLayout | null;
-  container_metadata: Array<[string, string]>;
+  nodes?: Array<BranchingNode>;
+  layout_config?: ContainerLayout | null;
+  container_metadata?: Array<[string, string]>;
 };
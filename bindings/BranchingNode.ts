@@ -8,10 +8,10 @@ import type { SectionContentNode } from "./SectionContentNode";
  */
 export type BranchingNode = {
   node_id: string;
-  parent_id: string | null;
+  parent_id?: string | null;
   node_type: NodeType;
-  content: Array<SectionContentNode>;
-  node_metadata: Array<[string, string]>;
-  children: Array<string>;
+  content?: Array<SectionContentNode>;
+  node_metadata?: Array<[string, string]>;
+  children?: Array<string>;
   node_state: NodeState;
 };
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * `manifest.json` of a `.turndoc` bundle: a ZIP archive holding the document, the images it
+ * shows and its tooltip documents. In `document.json` each tooltip document is left without
+ * its sections, which are in the tooltip's own entry; tooltips are listed in document order,
+ * each one before the tooltips in its link text and inside it. The entry of a tooltip that
+ * could not be loaded holds `null`.
+ */
+export type BundleManifest = {
+  format_version: number;
+  document: string;
+  images: Array<string>;
+  tooltips: Array<string>;
+};
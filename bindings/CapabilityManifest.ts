@@ -0,0 +1,17 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { InteractionFeature } from "./InteractionFeature";
+import type { VisualizationType } from "./VisualizationType";
+
+/**
+ * The interactive features a document relies on, computed from its content.
+ * Host applications compare it against what they support before embedding the document,
+ * and degrade the listed features instead of failing on unknown nodes.
+ */
+export type CapabilityManifest = {
+  document_types: Array<string>;
+  interaction_features: Array<InteractionFeature>;
+  animation_types: Array<string>;
+  visualization_types: Array<VisualizationType>;
+  custom_components: Array<string>;
+  diagram_types: Array<string>;
+};
@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ChemicalTerm } from "./ChemicalTerm";
+import type { ReactionStep } from "./ReactionStep";
+
+/**
+ * A chemical formula or reaction as written with mhchem's `\ce{...}`: species with their
+ * coefficients, charges and states, joined by `+` and by reaction arrows with optional
+ * conditions. Parses from the same syntax, `"2H2 + O2 -> 2H2O"`, `"Fe^3+ + e- <=> Fe^2+"`.
+ */
+export type ChemicalExpression = {
+  terms: Array<ChemicalTerm>;
+  steps?: Array<ReactionStep>;
+};
@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { FormulaPart } from "./FormulaPart";
+import type { PhysicalState } from "./PhysicalState";
+
+export type ChemicalSpecies = {
+  parts: Array<FormulaPart>;
+  charge: number;
+  state?: PhysicalState | null;
+};
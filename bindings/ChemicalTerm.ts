@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ChemicalSpecies } from "./ChemicalSpecies";
+
+export type ChemicalTerm = {
+  coefficient?: string | null;
+  species: ChemicalSpecies;
+};
@@ -3,9 +3,9 @@ import type { RichText } from "./RichText";
 
 export type CodeBlockNode = {
   code: string;
-  language: string | null;
-  caption: RichText | null;
-  show_line_numbers: boolean | null;
-  highlight_lines: Array<number>;
-  is_executable: boolean | null;
+  language?: string | null;
+  caption?: RichText | null;
+  show_line_numbers?: boolean | null;
+  highlight_lines?: Array<number>;
+  is_executable?: boolean | null;
 };
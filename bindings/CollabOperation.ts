@@ -0,0 +1,23 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { MathDocument } from "./MathDocument";
+import type { Section } from "./Section";
+import type { SectionParent } from "./SectionParent";
+import type { Timestamp } from "./Timestamp";
+
+/**
+ * A change to a `CollabDocument`, as sent between replicas. Operations commute and can be
+ * applied more than once, so replicas that have applied the same operations, in any order,
+ * hold the same document.
+ */
+export type CollabOperation =
+  | { "SetDocument": { stamp: Timestamp; document: MathDocument } }
+  | {
+    "PlaceSection": {
+      stamp: Timestamp;
+      section_id: string;
+      parent: SectionParent;
+      position: Array<number>;
+    };
+  }
+  | { "SetSection": { stamp: Timestamp; section: Section } }
+  | { "RemoveSection": { stamp: Timestamp; section_id: string } };
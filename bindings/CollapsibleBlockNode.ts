@@ -3,7 +3,7 @@ import type { RichTextSegment } from "./RichTextSegment";
 import type { SectionContentNode } from "./SectionContentNode";
 
 export type CollapsibleBlockNode = {
-  summary: Array<RichTextSegment>;
-  details: Array<SectionContentNode>;
-  initially_collapsed: boolean | null;
+  summary?: Array<RichTextSegment>;
+  details?: Array<SectionContentNode>;
+  initially_collapsed?: boolean | null;
 };
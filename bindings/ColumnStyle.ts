@@ -2,6 +2,6 @@
 import type { TextAlignment } from "./TextAlignment";
 
 export type ColumnStyle = {
-  width: string | null;
-  alignment: TextAlignment | null;
+  width?: string | null;
+  alignment?: TextAlignment | null;
 };
@@ -2,7 +2,7 @@
 import type { SectionContentNode } from "./SectionContentNode";
 
 export type ColumnsNode = {
-  columns_content: Array<Array<SectionContentNode>>;
-  column_widths: Array<string>;
-  gap: string | null;
+  columns_content?: Array<Array<SectionContentNode>>;
+  column_widths?: Array<string>;
+  gap?: string | null;
 };
@@ -3,6 +3,6 @@
 export type ComparisonCriterion = {
   criterion_id: string;
   name: string;
-  description: string | null;
-  weight: number | null;
+  description?: string | null;
+  weight?: number | null;
 };
@@ -6,10 +6,10 @@ import type { TheoryReference } from "./TheoryReference";
 
 export type ComparisonPageContent = {
   title: string;
-  comparison_criteria: Array<ComparisonCriterion>;
+  comparison_criteria?: Array<ComparisonCriterion>;
   highlight_differences: boolean;
   synchronized_navigation: boolean;
-  theories_involved: Array<TheoryReference>;
+  theories_involved?: Array<TheoryReference>;
   relationship_metadata: RelationshipMetadata;
   comparison_structure: ComparisonStructure;
 };
@@ -5,5 +5,5 @@ import type { ComparisonSection } from "./ComparisonSection";
 export type ComparisonStructure = {
   comparison_type: string;
   layout: ComparisonLayout;
-  sections: Array<ComparisonSection>;
+  sections?: Array<ComparisonSection>;
 };
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Properties passed to a `CustomComponent`, optionally tied to a registered schema.
+ */
+export type ComponentProps = { values: unknown; schema_ref: string | null };
@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ComponentSchema } from "./ComponentSchema";
+
+/**
+ * The custom components a host knows how to render, keyed by component name.
+ */
+export type ComponentRegistry = {
+  components: { [key in string]?: ComponentSchema };
+};
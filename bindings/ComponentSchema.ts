@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PropSchema } from "./PropSchema";
+
+/**
+ * The props a known component accepts.
+ */
+export type ComponentSchema = {
+  component_name: string;
+  schema_id: string;
+  props: Array<PropSchema>;
+  allow_unknown_props: boolean;
+};
@@ -8,8 +8,8 @@ import type { TheoryReference } from "./TheoryReference";
 export type ConceptAlignmentContent = {
   title: string;
   alignment_type: AlignmentType;
-  correspondence_mappings: Array<ConceptCorrespondence>;
-  theories_involved: Array<TheoryReference>;
+  correspondence_mappings?: Array<ConceptCorrespondence>;
+  theories_involved?: Array<TheoryReference>;
   relationship_metadata: RelationshipMetadata;
-  alignment_visualizations: Array<AlignmentVisualization>;
+  alignment_visualizations?: Array<AlignmentVisualization>;
 };
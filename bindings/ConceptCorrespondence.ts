@@ -5,5 +5,5 @@ export type ConceptCorrespondence = {
   source_concept: string;
   target_concept: string;
   correspondence_type: CorrespondenceType;
-  confidence: number | null;
+  confidence?: number | null;
 };
@@ -6,7 +6,7 @@ import type { ViewportConfig } from "./ViewportConfig";
 
 export type ConceptExtractContent = {
   source_document_id: string;
-  extracted_concepts: Array<string>;
+  extracted_concepts?: Array<string>;
   context_preservation: ContextPreservationLevel;
   extraction_metadata: ExtractionMetadata;
   viewport_config: ViewportConfig;
@@ -7,8 +7,8 @@ import type { SourceReference } from "./SourceReference";
 
 export type ConceptMapContent = {
   central_concept: string;
-  relationship_types: Array<RelationshipType>;
-  source_references: Array<SourceReference>;
+  relationship_types?: Array<RelationshipType>;
+  source_references?: Array<SourceReference>;
   derivation_metadata: DerivationMetadata;
   content: SimplifiedContentStructure;
   presentation_config: PresentationConfig;
@@ -5,5 +5,5 @@ export type ConceptRelationship = {
   source_concept: string;
   target_concept: string;
   relationship_type: ConceptRelationType;
-  description: string | null;
+  description?: string | null;
 };
@@ -3,6 +3,6 @@ import type { ConstraintType } from "./ConstraintType";
 
 export type Constraint = {
   constraint_type: ConstraintType;
-  parameters_involved: Array<string>;
+  parameters_involved?: Array<string>;
   formula: string;
 };
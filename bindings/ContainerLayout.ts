@@ -9,8 +9,8 @@ import type { LayoutType } from "./LayoutType";
 export type ContainerLayout = {
   layout_type: LayoutType;
   direction: LayoutDirection;
-  spacing: string | null;
-  alignment: LayoutAlignment | null;
-  max_depth: number | null;
-  collapse_branches: boolean | null;
+  spacing?: string | null;
+  alignment?: LayoutAlignment | null;
+  max_depth?: number | null;
+  collapse_branches?: boolean | null;
 };
@@ -4,5 +4,5 @@ import type { ValueTransformation } from "./ValueTransformation";
 export type ControlBinding = {
   control_id: string;
   target_variable: string;
-  transformation: ValueTransformation | null;
+  transformation?: ValueTransformation | null;
 };
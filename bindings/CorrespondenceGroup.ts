@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { RichText } from "./RichText";
+
+/**
+ * Nodes on the left and right of a comparison that correspond to each other.
+ * Hovering any member highlights every member of the group on both sides.
+ */
+export type CorrespondenceGroup = {
+  id: string;
+  label: RichText | null;
+  color: string | null;
+  left_ids: Array<string>;
+  right_ids: Array<string>;
+};
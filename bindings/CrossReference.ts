@@ -5,5 +5,5 @@ export type CrossReference = {
   reference_id: string;
   target_id: string;
   reference_type: CrossReferenceType;
-  display_text: string | null;
+  display_text?: string | null;
 };
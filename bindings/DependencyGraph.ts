@@ -3,7 +3,7 @@ import type { DependencyEdge } from "./DependencyEdge";
 import type { DependencyNode } from "./DependencyNode";
 
 export type DependencyGraph = {
-  nodes: Array<DependencyNode>;
-  edges: Array<DependencyEdge>;
-  graph_metadata: { [key in string]?: string } | null;
+  nodes?: Array<DependencyNode>;
+  edges?: Array<DependencyEdge>;
+  graph_metadata?: { [key in string]?: string } | null;
 };
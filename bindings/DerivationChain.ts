@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DerivationStep } from "./DerivationStep";
+import type { MathNode } from "./MathNode";
+
+/**
+ * A worked solution or derivation as a chain of expressions, `a = b = c ≤ d`, written one step
+ * per line with the relation to the previous line on the left and the reason for the step on
+ * the right, as in an `align` environment.
+ */
+export type DerivationChain = {
+  id: string;
+  start: MathNode;
+  steps?: Array<DerivationStep>;
+};
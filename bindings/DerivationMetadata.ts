@@ -3,7 +3,7 @@ import type { AccuracyMetrics } from "./AccuracyMetrics";
 
 export type DerivationMetadata = {
   derived_at: string;
-  derivation_rules: Array<string>;
+  derivation_rules?: Array<string>;
   human_reviewed: boolean;
-  accuracy_metrics: AccuracyMetrics | null;
+  accuracy_metrics?: AccuracyMetrics | null;
 };
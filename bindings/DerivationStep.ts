@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { MathNode } from "./MathNode";
+import type { RelationOperatorNode } from "./RelationOperatorNode";
+import type { RichText } from "./RichText";
+
+export type DerivationStep = {
+  relation: RelationOperatorNode;
+  expression: MathNode;
+  justification?: RichText | null;
+};
@@ -0,0 +1,17 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { LocationStep } from "./LocationStep";
+import type { NodePath } from "./NodePath";
+import type { Severity } from "./Severity";
+
+/**
+ * A problem reported by one of the validators, in a shape editors and other tools can show
+ * without knowing which validator produced it.
+ */
+export type Diagnostic = {
+  severity: Severity;
+  code: string;
+  message: string;
+  location: Array<LocationStep>;
+  node_path: NodePath | null;
+  offending_id: string | null;
+};
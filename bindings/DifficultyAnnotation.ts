@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CognitiveLoad } from "./CognitiveLoad";
+import type { ContentRole } from "./ContentRole";
+import type { DifficultyLevel } from "./DifficultyLevel";
+
+/**
+ * How hard a section, example or exercise is, for adaptive-learning consumers that sequence
+ * content by difficulty.
+ */
+export type DifficultyAnnotation = {
+  level: DifficultyLevel;
+  cognitive_load?: CognitiveLoad | null;
+  role: ContentRole;
+  estimated_minutes?: number | null;
+};
@@ -0,0 +1,17 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DifficultyLevel } from "./DifficultyLevel";
+
+/**
+ * Difficulty rollup of one chapter (a top-level body section) and everything nested in it.
+ */
+export type DifficultyProfile = {
+  section_id: string;
+  annotated_count: number;
+  level_counts?: Array<[DifficultyLevel, number]>;
+  mean_level?: number | null;
+  peak_level?: DifficultyLevel | null;
+  high_load_count: number;
+  example_count: number;
+  exercise_count: number;
+  estimated_minutes: number;
+};
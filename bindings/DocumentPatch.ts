@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PatchOperation } from "./PatchOperation";
+
+/**
+ * Changes to a document, addressed by section id and by expression id and `NodePath`, as
+ * `MathDocument::diff` produces them and `MathDocument::apply` applies them. Operations
+ * apply in order: `diff` inserts and moves sections in the new document's order, after the
+ * sibling they follow there, and removes sections last, once the sections that survive have
+ * been moved out of them.
+ */
+export type DocumentPatch = { operations: Array<PatchOperation> };
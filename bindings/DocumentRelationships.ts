@@ -4,9 +4,9 @@ import type { CrossReference } from "./CrossReference";
 import type { DependencyGraph } from "./DependencyGraph";
 
 export type DocumentRelationships = {
-  parent_documents: Array<string>;
-  child_documents: Array<string>;
-  related_concepts: Array<ConceptReference>;
-  cross_references: Array<CrossReference>;
-  dependency_graph: DependencyGraph | null;
+  parent_documents?: Array<string>;
+  child_documents?: Array<string>;
+  related_concepts?: Array<ConceptReference>;
+  cross_references?: Array<CrossReference>;
+  dependency_graph?: DependencyGraph | null;
 };
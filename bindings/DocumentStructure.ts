@@ -4,10 +4,10 @@ import type { Section } from "./Section";
 import type { TocNode } from "./TocNode";
 
 export type DocumentStructure = {
-  abstract_content: Section | null;
-  table_of_contents: TocNode | null;
-  body: Array<Section>;
-  footnotes: Array<Section>;
-  glossary: Array<Section>;
-  bibliography: Array<BibEntry>;
+  abstract_content?: Section | null;
+  table_of_contents?: TocNode | null;
+  body?: Array<Section>;
+  footnotes?: Array<Section>;
+  glossary?: Array<Section>;
+  bibliography?: Array<BibEntry>;
 };
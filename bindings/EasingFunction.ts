@@ -3,5 +3,5 @@ import type { EasingType } from "./EasingType";
 
 export type EasingFunction = {
   function_type: EasingType;
-  parameters: Array<number> | null;
+  parameters?: Array<number> | null;
 };
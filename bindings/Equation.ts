@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EquationNumbering } from "./EquationNumbering";
+import type { MathNode } from "./MathNode";
+
+/**
+ * Display math with an equation number or tag at the right margin, which prose refers to with
+ * `LinkTarget::Equation`. Display math without one is plain `SectionContentNode::Math`.
+ */
+export type Equation = {
+  id: string;
+  math: MathNode;
+  numbering: EquationNumbering;
+  number?: string | null;
+};
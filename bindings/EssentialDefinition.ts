@@ -4,7 +4,7 @@ import type { RichTextSegment } from "./RichTextSegment";
 
 export type EssentialDefinition = {
   term: string;
-  simplified_definition: Array<RichTextSegment>;
-  formal_definition: MathNode | null;
-  intuitive_explanation: Array<RichTextSegment> | null;
+  simplified_definition?: Array<RichTextSegment>;
+  formal_definition?: MathNode | null;
+  intuitive_explanation?: Array<RichTextSegment> | null;
 };
@@ -3,7 +3,7 @@
 export type ExtractionMetadata = {
   extracted_at: string;
   extraction_method: string;
-  source_version: string | null;
-  extraction_rules: Array<string>;
-  quality_metrics: { [key in string]?: number } | null;
+  source_version?: string | null;
+  extraction_rules?: Array<string>;
+  quality_metrics?: { [key in string]?: number } | null;
 };
@@ -0,0 +1,15 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { FeedbackResponseKind } from "./FeedbackResponseKind";
+import type { RichText } from "./RichText";
+
+/**
+ * Inline request for reader feedback, e.g. "Was this proof clear?".
+ * The renderer reports answers as `FeedbackResponse`s keyed by `id`.
+ */
+export type FeedbackNode = {
+  id: string;
+  prompt: RichText;
+  response_kind: FeedbackResponseKind;
+  allow_comment: boolean;
+  target_id: string | null;
+};
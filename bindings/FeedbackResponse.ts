@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { FeedbackValue } from "./FeedbackValue";
+
+/**
+ * A reader's answer to a `FeedbackNode`, as sent back by the renderer.
+ */
+export type FeedbackResponse = {
+  feedback_id: string;
+  value: FeedbackValue;
+  comment: string | null;
+};
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type FeedbackResponseKind =
+  | "YesNo"
+  | { "EmojiScale": { options: Array<string> } }
+  | { "Rating": { min: number; max: number } }
+  | { "FreeText": { placeholder: string | null; max_length: number | null } }
+  | { "Choice": { options: Array<string>; multiple: boolean } };
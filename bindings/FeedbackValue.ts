@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type FeedbackValue =
+  | { "YesNo": boolean }
+  | { "Scale": number }
+  | { "Rating": number }
+  | { "Text": string }
+  | { "Choices": Array<number> };
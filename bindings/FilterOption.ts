@@ -4,5 +4,5 @@ import type { FilterType } from "./FilterType";
 export type FilterOption = {
   name: string;
   filter_type: FilterType;
-  options: Array<string>;
+  options?: Array<string>;
 };
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { BracketStyle } from "./BracketStyle";
+
+export type FormulaPart =
+  | { "Element": { symbol: string; count: number } }
+  | {
+    "Group": {
+      parts: Array<FormulaPart>;
+      bracket: BracketStyle;
+      count: number;
+    };
+  };
@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { MathNode } from "./MathNode";
+
+/**
+ * One case of a `FunctionDefinition` given by clauses: `f(arguments) = definition`, when
+ * `condition` holds. The arguments are patterns such as `0` or `n`, whose identifiers are bound
+ * in the condition and the definition.
+ */
+export type FunctionClause = {
+  arguments: Array<MathNode>;
+  condition?: MathNode | null;
+  definition: MathNode;
+};
@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SectionContentNode } from "./SectionContentNode";
+
+/**
+ * Content a generation pipeline failed to produce, rendered as an inline error in its place so
+ * that the rest of the document can still be shown.
+ */
+export type GenerationErrorNode = {
+  code: string;
+  message: string;
+  generator: string;
+  fallback_content: Array<SectionContentNode>;
+};
@@ -3,8 +3,8 @@ import type { SectionContentNode } from "./SectionContentNode";
 
 export type GridItemNode = {
   content: SectionContentNode;
-  col_start: number | null;
-  col_end: number | null;
-  row_start: number | null;
-  row_end: number | null;
+  col_start?: number | null;
+  col_end?: number | null;
+  row_start?: number | null;
+  row_end?: number | null;
 };
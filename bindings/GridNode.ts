@@ -2,11 +2,11 @@
 import type { GridItemNode } from "./GridItemNode";
 
 export type GridNode = {
-  items: Array<GridItemNode>;
+  items?: Array<GridItemNode>;
   /**
    * Number of columns, or CSS grid-template-columns string.
    */
   column_template: string;
-  row_gap: string | null;
-  column_gap: string | null;
+  row_gap?: string | null;
+  column_gap?: string | null;
 };
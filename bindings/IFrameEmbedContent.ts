@@ -6,7 +6,7 @@ import type { ViewportConfig } from "./ViewportConfig";
 
 export type IFrameEmbedContent = {
   source_document_id: string;
-  sandbox_permissions: Array<SandboxPermission>;
+  sandbox_permissions?: Array<SandboxPermission>;
   responsive_scaling: boolean;
   extraction_metadata: ExtractionMetadata;
   viewport_config: ViewportConfig;
@@ -4,9 +4,9 @@ import type { RichText } from "./RichText";
 
 export type ImageNode = {
   src: string;
-  alt_text: string | null;
-  caption: RichText | null;
-  width: string | null;
-  height: string | null;
-  alignment: HorizontalAlignment | null;
+  alt_text?: string | null;
+  caption?: RichText | null;
+  width?: string | null;
+  height?: string | null;
+  alignment?: HorizontalAlignment | null;
 };
@@ -8,4 +8,5 @@ export type InteractionFeature =
   | "Animations"
   | "TypeAnnotations"
   | "HighlightCorrespondence"
-  | "ParameterAdjustment";
+  | "ParameterAdjustment"
+  | "FeedbackCollection";
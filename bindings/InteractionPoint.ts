@@ -7,5 +7,5 @@ export type InteractionPoint = {
   interaction_type: InteractionType;
   target_element: string;
   required_action: UserAction;
-  timeout: number | null;
+  timeout?: number | null;
 };
@@ -4,7 +4,7 @@ import type { EventHandler } from "./EventHandler";
 import type { StateVariable } from "./StateVariable";
 
 export type InteractionSystem = {
-  controls: Array<Control>;
-  event_handlers: Array<EventHandler>;
-  state_variables: Array<StateVariable>;
+  controls?: Array<Control>;
+  event_handlers?: Array<EventHandler>;
+  state_variables?: Array<StateVariable>;
 };
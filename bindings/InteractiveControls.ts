@@ -3,7 +3,7 @@ import type { Control } from "./Control";
 import type { ControlLayout } from "./ControlLayout";
 
 export type InteractiveControls = {
-  controls: Array<Control>;
-  target_content_ids: Array<string>;
+  controls?: Array<Control>;
+  target_content_ids?: Array<string>;
   layout: ControlLayout;
 };
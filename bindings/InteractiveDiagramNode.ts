@@ -4,6 +4,6 @@ import type { RichText } from "./RichText";
 export type InteractiveDiagramNode = {
   diagram_type_id: string;
   data: string;
-  caption: RichText | null;
-  config_options: string | null;
+  caption?: RichText | null;
+  config_options?: string | null;
 };
@@ -9,10 +9,10 @@ import type { VisualizationType } from "./VisualizationType";
 export type InteractivePlaygroundContent = {
   title: string;
   parameter_space: ParameterSpace;
-  visualization_types: Array<VisualizationType>;
+  visualization_types?: Array<VisualizationType>;
   real_time_feedback: boolean;
-  base_content: Array<Section>;
+  base_content?: Array<Section>;
   interaction_system: InteractionSystem;
-  control_bindings: Array<ControlBinding>;
-  animation_timeline: AnimationTimeline | null;
+  control_bindings?: Array<ControlBinding>;
+  animation_timeline?: AnimationTimeline | null;
 };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { RichText } from "./RichText";
+import type { VariableTypeInfo } from "./VariableTypeInfo";
+
+export type InteractiveVariableDeclaration = {
+  variable_id: string;
+  display_name: string;
+  variable_type?: VariableTypeInfo | null;
+  description?: RichText | null;
+  scope?: string | null;
+};
@@ -4,7 +4,7 @@ import type { RichTextSegment } from "./RichTextSegment";
 
 export type KeyPoint = {
   id: string;
-  content: Array<RichTextSegment>;
+  content?: Array<RichTextSegment>;
   importance_level: ImportanceLevel;
-  source_section_id: string | null;
+  source_section_id?: string | null;
 };
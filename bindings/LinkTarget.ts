@@ -2,6 +2,7 @@
 import type { AnimationTriggerType } from "./AnimationTriggerType";
 import type { MathDocument } from "./MathDocument";
 import type { MathNode } from "./MathNode";
+import type { TheoryId } from "./TheoryId";
 
 /**
  * Defines various targets a link can point to, enabling rich interactivity.
@@ -9,15 +10,16 @@ import type { MathNode } from "./MathNode";
 export type LinkTarget =
   | { "Url": string }
   | { "InternalPageId": string }
-  | { "DefinitionId": { term_id: string; theory_context: string | null } }
+  | { "DefinitionId": { term_id: string; theory_context: TheoryId | null } }
   | {
     "DefinitionAspect": {
       term_id: string;
       aspect_id: string;
-      theory_context: string | null;
+      theory_context: TheoryId | null;
     };
   }
   | { "TheoremId": string }
+  | { "Equation": string }
   | {
     "ObjectConstructorTemplate": {
       template_id: string;
@@ -5,5 +5,5 @@ export type ListItemNode = {
   /**
    * Content of a list item can be complex, allowing nested structures.
    */
-  content: Array<SectionContentNode>;
+  content?: Array<SectionContentNode>;
 };
@@ -3,7 +3,7 @@ import type { ListItemNode } from "./ListItemNode";
 import type { ListStyle } from "./ListStyle";
 
 export type ListNode = {
-  items: Array<ListItemNode>;
+  items?: Array<ListItemNode>;
   style: ListStyle;
-  start_index: number | null;
+  start_index?: number | null;
 };
@@ -7,7 +7,7 @@ import type { ViewportConfig } from "./ViewportConfig";
 export type LiveEmbedContent = {
   source_document_id: string;
   sync_with_source: boolean;
-  allowed_interactions: Array<AllowedInteraction>;
+  allowed_interactions?: Array<AllowedInteraction>;
   extraction_metadata: ExtractionMetadata;
   viewport_config: ViewportConfig;
   interaction_level: InteractionLevel;
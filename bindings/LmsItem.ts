@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * One launchable document of a package.
+ */
+export type LmsItem = {
+  document_id: string;
+  title: string;
+  document_path: string;
+  score_maximum: number | null;
+  mastery_score: number | null;
+};
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { LmsItem } from "./LmsItem";
+
+/**
+ * A course or assessment made of documents, described for learning management systems: as a
+ * SCORM 2004 content package or as LTI 1.3 deep-linking content items.
+ */
+export type LmsPackage = {
+  identifier: string;
+  title: string;
+  items: Array<LmsItem>;
+  player_files: Array<string>;
+  launch_page: string;
+};
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * One level of the location of a link.
+ */
+export type LocationStep = { "Document": string } | { "Section": string } | {
+  "Panel": string;
+};
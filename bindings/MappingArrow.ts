@@ -5,5 +5,5 @@ export type MappingArrow = {
   from: string;
   to: string;
   arrow_style: ArrowStyle;
-  label: string | null;
+  label?: string | null;
 };
@@ -4,7 +4,7 @@ import type { VisualizationType } from "./VisualizationType";
 
 export type MappingVisualization = {
   visualization_type: VisualizationType;
-  source_elements: Array<string>;
-  target_elements: Array<string>;
-  mapping_arrows: Array<MappingArrow>;
+  source_elements?: Array<string>;
+  target_elements?: Array<string>;
+  mapping_arrows?: Array<MappingArrow>;
 };
@@ -1,7 +1,16 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { MathDocumentType } from "./MathDocumentType";
+import type { QuantityFormat } from "./QuantityFormat";
+import type { RevisionHistory } from "./RevisionHistory";
+import type { VariableContext } from "./VariableContext";
 
 /**
  * The main container for mathematical content with a unique ID and content type
  */
-export type MathDocument = { id: string; content_type: MathDocumentType };
+export type MathDocument = {
+  id: string;
+  content_type: MathDocumentType;
+  variable_context?: VariableContext | null;
+  revision_history?: RevisionHistory | null;
+  quantity_format?: QuantityFormat | null;
+};
@@ -25,24 +25,24 @@ import type { WikiPageContent } from "./WikiPageContent";
  * Each variant represents a distinct document type with its own specialized structure and behavior
  */
 export type MathDocumentType =
-  | { "WikiPage": WikiPageContent }
-  | { "Textbook": TextbookContent }
-  | { "ScientificPaper": ScientificPaperContent }
-  | { "PersonalNotes": PersonalNotesContent }
-  | { "MathematicianNotes": MathematicianNotesContent }
-  | { "StudyNotes": StudyNotesContent }
-  | { "TooltipSummary": TooltipSummaryContent }
-  | { "BlogPost": BlogPostContent }
-  | { "AbstractSummary": AbstractSummaryContent }
-  | { "ConceptMap": ConceptMapContent }
-  | { "AnimatedPresentation": AnimatedPresentationContent }
-  | { "InteractivePlayground": InteractivePlaygroundContent }
-  | { "TypeMappingDisplay": TypeMappingDisplayContent }
-  | { "ResourcePanel": ResourcePanelContent }
-  | { "ComparisonPage": ComparisonPageContent }
-  | { "TransformationMapping": TransformationMappingContent }
-  | { "ConceptAlignment": ConceptAlignmentContent }
-  | { "StaticPreview": StaticPreviewContent }
-  | { "LiveEmbed": LiveEmbedContent }
-  | { "ConceptExtract": ConceptExtractContent }
-  | { "IFrameEmbed": IFrameEmbedContent };
+  | { "kind": "WikiPage"; "data": WikiPageContent }
+  | { "kind": "Textbook"; "data": TextbookContent }
+  | { "kind": "ScientificPaper"; "data": ScientificPaperContent }
+  | { "kind": "PersonalNotes"; "data": PersonalNotesContent }
+  | { "kind": "MathematicianNotes"; "data": MathematicianNotesContent }
+  | { "kind": "StudyNotes"; "data": StudyNotesContent }
+  | { "kind": "TooltipSummary"; "data": TooltipSummaryContent }
+  | { "kind": "BlogPost"; "data": BlogPostContent }
+  | { "kind": "AbstractSummary"; "data": AbstractSummaryContent }
+  | { "kind": "ConceptMap"; "data": ConceptMapContent }
+  | { "kind": "AnimatedPresentation"; "data": AnimatedPresentationContent }
+  | { "kind": "InteractivePlayground"; "data": InteractivePlaygroundContent }
+  | { "kind": "TypeMappingDisplay"; "data": TypeMappingDisplayContent }
+  | { "kind": "ResourcePanel"; "data": ResourcePanelContent }
+  | { "kind": "ComparisonPage"; "data": ComparisonPageContent }
+  | { "kind": "TransformationMapping"; "data": TransformationMappingContent }
+  | { "kind": "ConceptAlignment"; "data": ConceptAlignmentContent }
+  | { "kind": "StaticPreview"; "data": StaticPreviewContent }
+  | { "kind": "LiveEmbed"; "data": LiveEmbedContent }
+  | { "kind": "ConceptExtract"; "data": ConceptExtractContent }
+  | { "kind": "IFrameEmbed"; "data": IFrameEmbedContent };
@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Size and shape of a `MathNode` tree, for layout decisions and difficulty estimation.
+ */
+export type MathMetrics = {
+  node_count: number;
+  depth: number;
+  leaf_count: number;
+  identifier_count: number;
+  operator_histogram: Array<[string, number]>;
+  visual_width: number;
+};
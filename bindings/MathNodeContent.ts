@@ -1,45 +1,81 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AngleUnit } from "./AngleUnit";
 import type { BinaryOperationType } from "./BinaryOperationType";
 import type { BinaryOperator } from "./BinaryOperator";
+import type { BinomialStyle } from "./BinomialStyle";
+import type { BracePosition } from "./BracePosition";
 import type { BracketSize } from "./BracketSize";
 import type { BracketStyle } from "./BracketStyle";
+import type { CancelStyle } from "./CancelStyle";
+import type { ChemicalExpression } from "./ChemicalExpression";
+import type { DerivativeNotation } from "./DerivativeNotation";
 import type { DifferentialStyle } from "./DifferentialStyle";
 import type { DivisionStyle } from "./DivisionStyle";
+import type { FunctionClause } from "./FunctionClause";
 import type { Identifier } from "./Identifier";
 import type { MathNode } from "./MathNode";
+import type { MathEmphasis } from "./MathEmphasis";
+import type { MathSpace } from "./MathSpace";
 import type { MathTextSegment } from "./MathTextSegment";
+import type { MatrixDelimiters } from "./MatrixDelimiters";
+import type { MatrixHighlight } from "./MatrixHighlight";
+import type { PhantomExtent } from "./PhantomExtent";
 import type { QuantificationNode } from "./QuantificationNode";
 import type { RefinedAddOrSubOperator } from "./RefinedAddOrSubOperator";
 import type { RefinedMulOrDivOperation } from "./RefinedMulOrDivOperation";
 import type { RelationOperatorNode } from "./RelationOperatorNode";
 import type { ScientificNotationStyle } from "./ScientificNotationStyle";
+import type { Turnstile } from "./Turnstile";
 import type { UnaryRelationOperatorNode } from "./UnaryRelationOperatorNode";
+import type { VectorDecoration } from "./VectorDecoration";
 
 export type MathNodeContent =
-  | "Empty"
-  | { "Text": string }
-  | { "String": string }
-  | { "Bracketed": { inner: MathNode; style: BracketStyle; size: BracketSize } }
-  | { "Matrix": { rows: Array<Array<MathNode>> } }
+  | { "kind": "Empty" }
+  | { "kind": "Text"; "data": string }
+  | { "kind": "String"; "data": string }
+  | { "kind": "Phantom"; "data": { inner: MathNode; extent: PhantomExtent } }
+  | { "kind": "Space"; "data": MathSpace }
   | {
-    "BinaryOperation": {
+    "kind": "Bracketed";
+    "data": { inner: MathNode; style: BracketStyle; size: BracketSize };
+  }
+  | {
+    "kind": "Matrix";
+    "data": {
+      rows: Array<Array<MathNode>>;
+      delimiters?: MatrixDelimiters | null;
+      augmented_after?: number | null;
+      row_labels?: Array<MathNode>;
+      column_labels?: Array<MathNode>;
+      highlights?: Array<MatrixHighlight>;
+    };
+  }
+  | {
+    "kind": "BinaryOperation";
+    "data": {
       operation_type: BinaryOperationType;
       terms: Array<[BinaryOperator, MathNode]>;
     };
   }
   | {
-    "Multiplications": { terms: Array<[RefinedMulOrDivOperation, MathNode]> };
+    "kind": "Multiplications";
+    "data": { terms: Array<[RefinedMulOrDivOperation, MathNode]> };
+  }
+  | {
+    "kind": "Additions";
+    "data": { terms: Array<[RefinedAddOrSubOperator, MathNode]> };
   }
-  | { "Additions": { terms: Array<[RefinedAddOrSubOperator, MathNode]> } }
   | {
-    "Division": {
+    "kind": "Division";
+    "data": {
       numerator: MathNode;
       denominator: MathNode;
       style: DivisionStyle;
     };
   }
   | {
-    "SumNotation": {
+    "kind": "SumNotation";
+    "data": {
       summand: MathNode;
       variable: MathNode | null;
       lower_limit: MathNode | null;
@@ -47,86 +83,177 @@ export type MathNodeContent =
     };
   }
   | {
-    "ProductNotation": {
+    "kind": "ProductNotation";
+    "data": {
       multiplicand: MathNode;
       variable: MathNode | null;
       lower_limit: MathNode | null;
       upper_limit: MathNode | null;
     };
   }
-  | { "Fraction": { numerator: MathNode; denominator: MathNode } }
-  | { "Power": { base: MathNode; exponent: MathNode } }
-  | { "UnaryPostfixOperation": { parameter: MathNode; operator: MathNode } }
-  | { "UnaryPrefixOperation": { parameter: MathNode; operator: MathNode } }
-  | { "Abs": { parameter: MathNode } }
-  | { "FunctionCall": { name: MathNode; parameters: Array<MathNode> } }
   | {
-    "Quantity": {
+    "kind": "Fraction";
+    "data": { numerator: MathNode; denominator: MathNode };
+  }
+  | { "kind": "Power"; "data": { base: MathNode; exponent: MathNode } }
+  | {
+    "kind": "Radical";
+    "data": { radicand: MathNode; index: MathNode | null };
+  }
+  | {
+    "kind": "Binomial";
+    "data": { upper: MathNode; lower: MathNode; style: BinomialStyle };
+  }
+  | { "kind": "Cases"; "data": { branches: Array<[MathNode, MathNode]> } }
+  | {
+    "kind": "UnaryPostfixOperation";
+    "data": { parameter: MathNode; operator: MathNode };
+  }
+  | {
+    "kind": "UnaryPrefixOperation";
+    "data": { parameter: MathNode; operator: MathNode };
+  }
+  | { "kind": "Abs"; "data": { parameter: MathNode } }
+  | { "kind": "Norm"; "data": { inner: MathNode; subscript: MathNode | null } }
+  | {
+    "kind": "Decorated";
+    "data": { inner: MathNode; decoration: VectorDecoration };
+  }
+  | {
+    "kind": "Brace";
+    "data": { inner: MathNode; label: MathNode; position: BracePosition };
+  }
+  | {
+    "kind": "Styled";
+    "data": {
+      inner: MathNode;
+      color?: string | null;
+      background?: string | null;
+      emphasis?: MathEmphasis | null;
+    };
+  }
+  | {
+    "kind": "Cancelled";
+    "data": {
+      inner: MathNode;
+      cancel_style: CancelStyle;
+      replacement?: MathNode | null;
+    };
+  }
+  | { "kind": "AngleMeasure"; "data": { value: MathNode; unit: AngleUnit } }
+  | { "kind": "AngleSymbol"; "data": { vertices: Array<MathNode> } }
+  | {
+    "kind": "Coordinates";
+    "data": { point?: MathNode | null; components: Array<MathNode> };
+  }
+  | { "kind": "Bra"; "data": { state: MathNode } }
+  | { "kind": "Ket"; "data": { state: MathNode } }
+  | {
+    "kind": "BraKet";
+    "data": { bra: MathNode; operator: MathNode | null; ket: MathNode };
+  }
+  | {
+    "kind": "Expectation";
+    "data": { operator: MathNode; state: MathNode | null };
+  }
+  | {
+    "kind": "FunctionCall";
+    "data": { name: MathNode; parameters: Array<MathNode> };
+  }
+  | {
+    "kind": "Quantity";
+    "data": {
       number: string;
       scientific_notation: MathNode | null;
       unit: MathNode | null;
     };
   }
   | {
-    "ScientificNotation": {
-      magnitude: MathNode;
-      style: ScientificNotationStyle;
-    };
+    "kind": "ScientificNotation";
+    "data": { magnitude: MathNode; style: ScientificNotationStyle };
   }
-  | { "Identifier": Identifier }
-  | { "Unit": { original_form: MathNode; flattened_form: MathNode } }
+  | { "kind": "Identifier"; "data": Identifier }
   | {
-    "Relationship": {
-      lhs: MathNode;
-      rhs: MathNode;
-      operator: RelationOperatorNode;
-    };
+    "kind": "Unit";
+    "data": { original_form: MathNode; flattened_form: MathNode };
   }
+  | { "kind": "ChemicalExpression"; "data": ChemicalExpression }
   | {
-    "UnaryRelationship": {
-      subject: MathNode;
-      predicate: UnaryRelationOperatorNode;
-    };
+    "kind": "Relationship";
+    "data": { lhs: MathNode; rhs: MathNode; operator: RelationOperatorNode };
+  }
+  | {
+    "kind": "UnaryRelationship";
+    "data": { subject: MathNode; predicate: UnaryRelationOperatorNode };
   }
-  | { "VariableDefinition": { name: MathNode; definition: MathNode | null } }
   | {
-    "FunctionDefinition": {
+    "kind": "VariableDefinition";
+    "data": { name: MathNode; definition: MathNode | null };
+  }
+  | {
+    "kind": "FunctionDefinition";
+    "data": {
       custom_function: MathNode;
       definition: MathNode | null;
+      clauses?: Array<FunctionClause>;
     };
   }
   | {
-    "Limit": {
+    "kind": "Limit";
+    "data": {
       function: MathNode;
       variable: string;
       approaching_value: MathNode;
     };
   }
   | {
-    "Differential": {
+    "kind": "Differential";
+    "data": {
       target: MathNode;
       order: MathNode;
       diff_style: DifferentialStyle;
+      notation: DerivativeNotation;
+      variable?: MathNode | null;
     };
   }
   | {
-    "Integration": {
+    "kind": "Integration";
+    "data": {
       integrand: MathNode;
       differentials: Array<[MathNode, MathNode | null, MathNode | null]>;
       domain: MathNode | null;
     };
   }
   | {
-    "QuantifiedExpression": {
+    "kind": "QuantifiedExpression";
+    "data": {
       quantifier: QuantificationNode;
       variables: Array<MathNode>;
       domain: MathNode | null;
       predicate: MathNode | null;
     };
   }
-  | { "RichTextContent": Array<MathTextSegment> }
-  | { "And": Array<MathNode> }
-  | { "Or": Array<MathNode> }
-  | { "Not": MathNode }
-  | "True"
-  | "False";
+  | { "kind": "SetExplicit"; "data": { elements: Array<MathNode> } }
+  | {
+    "kind": "SetBuilder";
+    "data": {
+      variable: MathNode;
+      domain: MathNode | null;
+      predicate: MathNode;
+    };
+  }
+  | { "kind": "RichTextContent"; "data": Array<MathTextSegment> }
+  | { "kind": "And"; "data": Array<MathNode> }
+  | { "kind": "Or"; "data": Array<MathNode> }
+  | { "kind": "Not"; "data": MathNode }
+  | { "kind": "True" }
+  | { "kind": "False" }
+  | {
+    "kind": "Sequent";
+    "data": {
+      context: Array<MathNode>;
+      turnstile: Turnstile;
+      conclusion: MathNode;
+    };
+  }
+  | { "kind": "Folded"; "data": { summary: string; content: MathNode } };
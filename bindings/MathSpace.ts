@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type MathSpace =
+  | "NegativeThin"
+  | "Thin"
+  | "Medium"
+  | "Thick"
+  | "Quad"
+  | "Qquad"
+  | { "Custom": string };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { MathNode } from "./MathNode";
+import type { MatrixAxis } from "./MatrixAxis";
+
+/**
+ * A row or column of a `Matrix` picked out, with a note beside a row (after the matrix) or
+ * below a column, such as the row operation about to be applied to it.
+ */
+export type MatrixHighlight = {
+  axis: MatrixAxis;
+  index: number;
+  annotation?: MathNode | null;
+  color?: string | null;
+};
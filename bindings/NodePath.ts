@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PathSegment } from "./PathSegment";
+
+/**
+ * Location of a node below some root `MathNode`, as the field names and indices to follow.
+ * Displayed as e.g. `numerator.terms[1]`; the empty path is the root itself.
+ */
+export type NodePath = { segments: Array<PathSegment> };
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How numbers are written in a locale, used to recognise numeric table cells and to pick the
+ * number format they get in the exported spreadsheet, and to write quantities (see
+ * `QuantityFormat`).
+ */
+export type NumberLocale = {
+  decimal_separator: string;
+  group_separator: string | null;
+};
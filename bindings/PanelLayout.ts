@@ -2,9 +2,11 @@
 import type { Panel } from "./Panel";
 import type { PanelControls } from "./PanelControls";
 import type { PanelLayoutType } from "./PanelLayoutType";
+import type { PanelRenderingHints } from "./PanelRenderingHints";
 
 export type PanelLayout = {
-  panels: Array<Panel>;
+  panels?: Array<Panel>;
   layout_type: PanelLayoutType;
-  panel_controls: PanelControls | null;
+  panel_controls?: PanelControls | null;
+  default_rendering_hints?: PanelRenderingHints | null;
 };
@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { RenderPriority } from "./RenderPriority";
+
+/**
+ * Hints that let a frontend defer rendering panels that are offscreen or hidden behind a tab.
+ */
+export type PanelRenderingHints = {
+  priority?: RenderPriority | null;
+  lazy_load?: boolean | null;
+  estimated_height?: string | null;
+  estimated_width?: string | null;
+  estimated_node_count?: number | null;
+};
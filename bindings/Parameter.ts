@@ -6,5 +6,5 @@ export type Parameter = {
   name: string;
   parameter_type: ParameterType;
   range: ParameterRange;
-  description: string | null;
+  description?: string | null;
 };
@@ -1,7 +1,7 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
 export type ParameterRange = {
-  min: number | null;
-  max: number | null;
-  step: number | null;
+  min?: number | null;
+  max?: number | null;
+  step?: number | null;
 };
@@ -3,7 +3,8 @@ import type { Constraint } from "./Constraint";
 import type { Parameter } from "./Parameter";
 
 export type ParameterSpace = {
-  parameters: Array<Parameter>;
-  constraints: Array<Constraint>;
+  parameters?: Array<Parameter>;
+  constraints?: Array<Constraint>;
   default_values: { [key in string]?: number };
+  variant_seed?: number | null;
 };
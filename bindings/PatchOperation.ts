@@ -0,0 +1,26 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { MathDocument } from "./MathDocument";
+import type { MathNode } from "./MathNode";
+import type { NodePath } from "./NodePath";
+import type { Section } from "./Section";
+import type { SectionParent } from "./SectionParent";
+
+export type PatchOperation =
+  | { "UpdateDocument": { document: MathDocument } }
+  | {
+    "InsertSection": {
+      parent: SectionParent;
+      after: string | null;
+      section: Section;
+    };
+  }
+  | {
+    "MoveSection": {
+      section_id: string;
+      parent: SectionParent;
+      after: string | null;
+    };
+  }
+  | { "UpdateSection": { section_id: string; section: Section } }
+  | { "ReplaceMath": { root_id: string; path: NodePath; node: MathNode } }
+  | { "RemoveSection": { section_id: string } };
@@ -7,8 +7,8 @@ import type { LayoutStyle } from "./LayoutStyle";
 
 export type PresentationConfig = {
   layout_style: LayoutStyle;
-  interaction_features: Array<InteractionFeature>;
+  interaction_features?: Array<InteractionFeature>;
   target_audience: AudienceLevel;
   formality_level: FormalityLevel;
-  animation_config: AnimationConfig | null;
+  animation_config?: AnimationConfig | null;
 };
@@ -0,0 +1,9 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PropType } from "./PropType";
+
+export type PropSchema = {
+  name: string;
+  prop_type: PropType;
+  required: boolean;
+  description: string | null;
+};
@@ -9,6 +9,6 @@ export type QuantifiedObject = {
   variable: string;
   quantification: QuantifierType;
   object_type: string;
-  constraints: Array<MathNode>;
-  description: string | null;
+  constraints?: Array<MathNode>;
+  description?: string | null;
 };
@@ -0,0 +1,19 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { NumberLocale } from "./NumberLocale";
+import type { NumberNotation } from "./NumberNotation";
+import type { ScientificNotationStyle } from "./ScientificNotationStyle";
+
+/**
+ * How the numbers of `Quantity` nodes are written. Set for a whole document as
+ * `MathDocument::quantity_format`, which every render backend applies to every quantity;
+ * `format_number` is the reference implementation.
+ *
+ * The default writes numbers as they are stored.
+ */
+export type QuantityFormat = {
+  significant_figures?: number | null;
+  notation: NumberNotation;
+  exponent_style: ScientificNotationStyle;
+  locale: NumberLocale;
+  group_digits: boolean;
+};
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ChemicalTerm } from "./ChemicalTerm";
+import type { ReactionArrow } from "./ReactionArrow";
+
+export type ReactionStep = {
+  arrow: ReactionArrow;
+  above?: string | null;
+  below?: string | null;
+  terms: Array<ChemicalTerm>;
+};
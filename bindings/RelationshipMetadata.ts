@@ -2,7 +2,7 @@
 
 export type RelationshipMetadata = {
   relationship_type: string;
-  strength: number | null;
-  bidirectional: boolean | null;
+  strength?: number | null;
+  bidirectional?: boolean | null;
   properties: { [key in string]?: string };
 };
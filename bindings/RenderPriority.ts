@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Order in which panels should be rendered; higher priorities first.
+ */
+export type RenderPriority =
+  | "Deferred"
+  | "Low"
+  | "Normal"
+  | "High"
+  | "Critical";
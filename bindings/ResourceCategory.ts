@@ -3,7 +3,7 @@ import type { ResourceItem } from "./ResourceItem";
 
 export type ResourceCategory = {
   name: string;
-  icon: string | null;
-  color: string | null;
-  items: Array<ResourceItem>;
+  icon?: string | null;
+  color?: string | null;
+  items?: Array<ResourceItem>;
 };
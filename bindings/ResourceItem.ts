@@ -4,7 +4,7 @@ import type { ResourceType } from "./ResourceType";
 export type ResourceItem = {
   id: string;
   title: string;
-  description: string | null;
+  description?: string | null;
   resource_type: ResourceType;
-  link: string | null;
+  link?: string | null;
 };
@@ -8,10 +8,10 @@ import type { Section } from "./Section";
 
 export type ResourcePanelContent = {
   title: string;
-  resource_categories: Array<ResourceCategory>;
+  resource_categories?: Array<ResourceCategory>;
   search_capabilities: SearchCapabilities;
-  filtering_options: Array<FilterOption>;
-  base_content: Array<Section>;
+  filtering_options?: Array<FilterOption>;
+  base_content?: Array<Section>;
   interaction_system: InteractionSystem;
-  control_bindings: Array<ControlBinding>;
+  control_bindings?: Array<ControlBinding>;
 };
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DocumentPatch } from "./DocumentPatch";
+
+export type Revision = {
+  author: string | null;
+  timestamp: string;
+  summary: string | null;
+  /**
+   * Turns this revision back into the one before it.
+   */
+  undo: DocumentPatch;
+};
@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Revision } from "./Revision";
+
+/**
+ * The recorded edits of a document, oldest first. Revision `n` is the document after the first
+ * `n` edits, so revision 0 is the document as it was before the first one and the document
+ * itself is revision `revisions.len()`. Each edit stores the patch undoing it, so the current
+ * document stays cheap to load and older revisions are rebuilt from it.
+ */
+export type RevisionHistory = { revisions: Array<Revision> };
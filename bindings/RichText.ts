@@ -6,6 +6,6 @@ import type { TextAlignment } from "./TextAlignment";
  * Represents a paragraph of rich text. It doesn't have line breaks
  */
 export type RichText = {
-  segments: Array<RichTextSegment>;
-  alignment: TextAlignment | null;
+  segments?: Array<RichTextSegment>;
+  alignment?: TextAlignment | null;
 };
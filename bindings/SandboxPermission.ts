@@ -3,5 +3,5 @@
 export type SandboxPermission = {
   permission_type: string;
   allowed: boolean;
-  restrictions: Array<string> | null;
+  restrictions?: Array<string> | null;
 };
@@ -0,0 +1,12 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * The exported TypeScript types with a hash of each declaration, written next to the
+ * bindings as `schema_manifest.json`. A frontend ships the manifest its bindings came with and
+ * compares it with the backend's `SchemaManifest::current()` at startup, rather than failing
+ * somewhere down the line on data of an unexpected shape.
+ */
+export type SchemaManifest = {
+  version: string;
+  types: { [key in string]?: string };
+};
@@ -8,7 +8,7 @@ import type { PaperType } from "./PaperType";
 export type ScientificPaperContent = {
   title: string;
   paper_type: PaperType;
-  venue: string | null;
+  venue?: string | null;
   peer_reviewed: boolean;
   content_metadata: ContentMetadata;
   academic_metadata: AcademicMetadata;
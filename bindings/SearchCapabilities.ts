@@ -5,5 +5,5 @@ export type SearchCapabilities = {
   full_text_search: boolean;
   semantic_search: boolean;
   filter_by_type: boolean;
-  sort_options: Array<SortOption>;
+  sort_options?: Array<SortOption>;
 };
@@ -1,7 +1,9 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DifficultyAnnotation } from "./DifficultyAnnotation";
 import type { RichText } from "./RichText";
 import type { SectionContentNode } from "./SectionContentNode";
 import type { SectionDisplayOptions } from "./SectionDisplayOptions";
+import type { VariantSeed } from "./VariantSeed";
 
 /**
  * A `SectionNode` represents a major, navigable part of a document (like a chapter or a named section).
@@ -9,8 +11,10 @@ import type { SectionDisplayOptions } from "./SectionDisplayOptions";
  */
 export type Section = {
   id: string;
-  title: RichText | null;
+  title?: RichText | null;
   content: SectionContentNode;
-  metadata: Array<[string, string]>;
-  display_options: SectionDisplayOptions | null;
+  metadata?: Array<[string, string]>;
+  display_options?: SectionDisplayOptions | null;
+  difficulty?: DifficultyAnnotation | null;
+  variant_seed?: VariantSeed | null;
 };
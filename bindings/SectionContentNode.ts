@@ -5,6 +5,11 @@ import type { BranchingContainer } from "./BranchingContainer";
 import type { CodeBlockNode } from "./CodeBlockNode";
 import type { CollapsibleBlockNode } from "./CollapsibleBlockNode";
 import type { ColumnsNode } from "./ColumnsNode";
+import type { ComponentProps } from "./ComponentProps";
+import type { DerivationChain } from "./DerivationChain";
+import type { Equation } from "./Equation";
+import type { FeedbackNode } from "./FeedbackNode";
+import type { GenerationErrorNode } from "./GenerationErrorNode";
 import type { GridNode } from "./GridNode";
 import type { ImageNode } from "./ImageNode";
 import type { InteractiveControls } from "./InteractiveControls";
@@ -25,33 +30,44 @@ import type { ThematicBreakNode } from "./ThematicBreakNode";
  * This is the primary building block for document content.
  */
 export type SectionContentNode =
-  | { "SubSection": Array<Section> }
-  | { "RichText": RichText }
-  | { "Math": MathNode }
-  | { "SecondOrderMath": SecondOrderMathNode }
-  | { "InteractiveDiagram": InteractiveDiagramNode }
-  | "Theorem"
-  | { "List": ListNode }
-  | { "Table": TableNode }
-  | { "CodeBlock": CodeBlockNode }
-  | { "Image": ImageNode }
-  | { "CollapsibleBlock": CollapsibleBlockNode }
-  | { "Grid": GridNode }
-  | { "Columns": ColumnsNode }
-  | { "ThematicBreak": ThematicBreakNode }
-  | { "QuoteBlock": { content: Array<RichText>; attribution: RichText | null } }
-  | { "AlertBox": { style: AlertBoxStyle; content: Array<SectionContentNode> } }
+  | { "kind": "SubSection"; "data": Array<Section> }
+  | { "kind": "RichText"; "data": RichText }
+  | { "kind": "Math"; "data": MathNode }
+  | { "kind": "SecondOrderMath"; "data": SecondOrderMathNode }
+  | { "kind": "InteractiveDiagram"; "data": InteractiveDiagramNode }
+  | { "kind": "Theorem" }
+  | { "kind": "List"; "data": ListNode }
+  | { "kind": "Table"; "data": TableNode }
+  | { "kind": "CodeBlock"; "data": CodeBlockNode }
+  | { "kind": "Image"; "data": ImageNode }
+  | { "kind": "CollapsibleBlock"; "data": CollapsibleBlockNode }
+  | { "kind": "Grid"; "data": GridNode }
+  | { "kind": "Columns"; "data": ColumnsNode }
+  | { "kind": "ThematicBreak"; "data": ThematicBreakNode }
   | {
-    "CustomComponent": {
+    "kind": "QuoteBlock";
+    "data": { content: Array<RichText>; attribution: RichText | null };
+  }
+  | {
+    "kind": "AlertBox";
+    "data": { style: AlertBoxStyle; content: Array<SectionContentNode> };
+  }
+  | {
+    "kind": "CustomComponent";
+    "data": {
       component_name: string;
-      props: string | null;
+      props: ComponentProps | null;
       fallback_content: Array<SectionContentNode>;
     };
   }
-  | { "EmbeddedSectionRef": string }
-  | { "SideBySideLayout": SideBySideLayout }
-  | { "PanelLayout": PanelLayout }
-  | { "AnnotationOverlay": AnnotationOverlay }
-  | { "InteractiveControls": InteractiveControls }
-  | { "EmbeddedDocument": MathDocument }
-  | { "BranchingContainer": BranchingContainer };
+  | { "kind": "EmbeddedSectionRef"; "data": string }
+  | { "kind": "SideBySideLayout"; "data": SideBySideLayout }
+  | { "kind": "PanelLayout"; "data": PanelLayout }
+  | { "kind": "AnnotationOverlay"; "data": AnnotationOverlay }
+  | { "kind": "InteractiveControls"; "data": InteractiveControls }
+  | { "kind": "EmbeddedDocument"; "data": MathDocument }
+  | { "kind": "BranchingContainer"; "data": BranchingContainer }
+  | { "kind": "Feedback"; "data": FeedbackNode }
+  | { "kind": "GenerationError"; "data": GenerationErrorNode }
+  | { "kind": "DerivationChain"; "data": DerivationChain }
+  | { "kind": "Equation"; "data": Equation };
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * How math-heavy a section is, counting everything nested in it.
+ */
+export type SectionMetrics = {
+  section_id: string;
+  word_count: number;
+  expression_count: number;
+  math_node_count: number;
+  max_depth: number;
+  widest_expression: number;
+  math_per_100_words: number;
+};
@@ -0,0 +1,14 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A list sections are kept in: one of the document's top-level lists, or the `list`th
+ * `SubSection` list in the content of a section, counted in document order without looking
+ * into nested sections or embedded documents.
+ */
+export type SectionParent =
+  | "Abstract"
+  | "Body"
+  | "Footnotes"
+  | "Glossary"
+  | "BaseContent"
+  | { "Section": { section_id: string; list: number } };
@@ -6,8 +6,8 @@
 export type SelectableProperty = {
   name: string;
   current_variant: string;
-  all_variants: Array<string>;
-  description: string | null;
-  variant_descriptions: { [key in string]?: string } | null;
-  property_type_def_id: string | null;
+  all_variants?: Array<string>;
+  description?: string | null;
+  variant_descriptions?: { [key in string]?: string } | null;
+  property_type_def_id?: string | null;
 };
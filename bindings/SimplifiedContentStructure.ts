@@ -5,8 +5,8 @@ import type { EssentialDefinition } from "./EssentialDefinition";
 import type { KeyPoint } from "./KeyPoint";
 
 export type SimplifiedContentStructure = {
-  key_points: Array<KeyPoint>;
-  essential_definitions: Array<EssentialDefinition>;
-  core_examples: Array<CoreExample>;
-  concept_relationships: Array<ConceptRelationship>;
+  key_points?: Array<KeyPoint>;
+  essential_definitions?: Array<EssentialDefinition>;
+  core_examples?: Array<CoreExample>;
+  concept_relationships?: Array<ConceptRelationship>;
 };
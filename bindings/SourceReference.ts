@@ -1,10 +1,11 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { DerivationMethod } from "./DerivationMethod";
+import type { UnitInterval } from "./UnitInterval";
 
 export type SourceReference = {
   source_id: string;
   source_type: string;
-  specific_sections: Array<string>;
+  specific_sections?: Array<string>;
   derivation_method: DerivationMethod;
-  confidence_level: number;
+  confidence_level: UnitInterval;
 };
@@ -1,6 +1,7 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { ContentMetadata } from "./ContentMetadata";
 import type { CourseLevel } from "./CourseLevel";
+import type { DifficultyProfile } from "./DifficultyProfile";
 import type { DocumentRelationships } from "./DocumentRelationships";
 import type { DocumentStructure } from "./DocumentStructure";
 
@@ -12,4 +13,5 @@ export type StudyNotesContent = {
   content_metadata: ContentMetadata;
   structure: DocumentStructure;
   relationships: DocumentRelationships;
+  difficulty_profiles?: Array<DifficultyProfile>;
 };
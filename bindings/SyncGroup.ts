@@ -3,6 +3,6 @@ import type { SyncType } from "./SyncType";
 
 export type SyncGroup = {
   group_id: string;
-  elements: Array<string>;
+  elements?: Array<string>;
   sync_type: SyncType;
 };
@@ -4,9 +4,9 @@ import type { TableCellType } from "./TableCellType";
 import type { TextAlignment } from "./TextAlignment";
 
 export type TableCellNode = {
-  content: Array<SectionContentNode>;
-  col_span: number | null;
-  row_span: number | null;
+  content?: Array<SectionContentNode>;
+  col_span?: number | null;
+  row_span?: number | null;
   cell_type: TableCellType;
-  alignment: TextAlignment | null;
+  alignment?: TextAlignment | null;
 };
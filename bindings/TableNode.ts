@@ -5,10 +5,10 @@ import type { TableRowNode } from "./TableRowNode";
 import type { TableStyleOptions } from "./TableStyleOptions";
 
 export type TableNode = {
-  caption: RichText | null;
-  header_rows: Array<TableRowNode>;
-  body_rows: Array<TableRowNode>;
-  footer_rows: Array<TableRowNode>;
-  column_styles: Array<ColumnStyle>;
-  table_style_options: TableStyleOptions | null;
+  caption?: RichText | null;
+  header_rows?: Array<TableRowNode>;
+  body_rows?: Array<TableRowNode>;
+  footer_rows?: Array<TableRowNode>;
+  column_styles?: Array<ColumnStyle>;
+  table_style_options?: TableStyleOptions | null;
 };
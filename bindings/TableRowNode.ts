@@ -1,4 +1,4 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { TableCellNode } from "./TableCellNode";
 
-export type TableRowNode = { cells: Array<TableCellNode> };
+export type TableRowNode = { cells?: Array<TableCellNode> };
@@ -1,6 +1,6 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 
 export type TableStyleOptions = {
-  borders: boolean | null;
-  striped_rows: boolean | null;
+  borders?: boolean | null;
+  striped_rows?: boolean | null;
 };
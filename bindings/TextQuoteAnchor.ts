@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A passage of text identified by its exact wording, with some surrounding text to tell
+ * repeated occurrences apart.
+ */
+export type TextQuoteAnchor = {
+  exact: string;
+  prefix?: string | null;
+  suffix?: string | null;
+};
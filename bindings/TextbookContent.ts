@@ -3,16 +3,18 @@ import type { AcademicMetadata } from "./AcademicMetadata";
 import type { ChapterInfo } from "./ChapterInfo";
 import type { ContentMetadata } from "./ContentMetadata";
 import type { CourseLevel } from "./CourseLevel";
+import type { DifficultyProfile } from "./DifficultyProfile";
 import type { DocumentRelationships } from "./DocumentRelationships";
 import type { DocumentStructure } from "./DocumentStructure";
 
 export type TextbookContent = {
   title: string;
   course_level: CourseLevel;
-  chapter_info: ChapterInfo | null;
-  prerequisites: Array<string>;
+  chapter_info?: ChapterInfo | null;
+  prerequisites?: Array<string>;
   content_metadata: ContentMetadata;
   academic_metadata: AcademicMetadata;
   structure: DocumentStructure;
   relationships: DocumentRelationships;
+  difficulty_profiles?: Array<DifficultyProfile>;
 };
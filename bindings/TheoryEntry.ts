@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TheoryId } from "./TheoryId";
+
+/**
+ * A theory known to a `TheoryRegistry`.
+ */
+export type TheoryEntry = {
+  id: TheoryId;
+  name: string;
+  aliases: Array<string>;
+};
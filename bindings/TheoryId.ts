@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Identifier of a mathematical theory, e.g. `GroupTheory` or `ZFC`. Serialized as a plain
+ * string; check it against a `TheoryRegistry` to catch unknown theories.
+ */
+export type TheoryId = string;
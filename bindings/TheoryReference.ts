@@ -1,7 +1,8 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TheoryId } from "./TheoryId";
 
 export type TheoryReference = {
-  theory_id: string;
+  theory_id: TheoryId;
   theory_name: string;
-  version: string | null;
+  version?: string | null;
 };
@@ -0,0 +1,7 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Orders operations: a Lamport clock, with the replica breaking ties between concurrent
+ * operations the same way on every replica.
+ */
+export type Timestamp = { counter: number; replica: string };
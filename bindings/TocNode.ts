@@ -3,5 +3,5 @@
 export type TocNode = {
   title: string;
   target_id: string;
-  children: Array<TocNode>;
+  children?: Array<TocNode>;
 };
@@ -7,9 +7,9 @@ import type { SummarizationLevel } from "./SummarizationLevel";
 
 export type TooltipSummaryContent = {
   summarization_level: SummarizationLevel;
-  max_length: number | null;
-  focus_concepts: Array<string>;
-  source_references: Array<SourceReference>;
+  max_length?: number | null;
+  focus_concepts?: Array<string>;
+  source_references?: Array<SourceReference>;
   derivation_metadata: DerivationMetadata;
   content: SimplifiedContentStructure;
   presentation_config: PresentationConfig;
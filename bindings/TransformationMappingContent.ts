@@ -1,5 +1,6 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { RelationshipMetadata } from "./RelationshipMetadata";
+import type { TheoryId } from "./TheoryId";
 import type { TheoryReference } from "./TheoryReference";
 import type { TransformationStep } from "./TransformationStep";
 import type { TransformationType } from "./TransformationType";
@@ -9,9 +10,9 @@ export type TransformationMappingContent = {
   transformation_type: TransformationType;
   step_by_step: boolean;
   bidirectional: boolean;
-  source_theory: string;
-  target_theory: string;
-  transformation_steps: Array<TransformationStep>;
-  theories_involved: Array<TheoryReference>;
+  source_theory: TheoryId;
+  target_theory: TheoryId;
+  transformation_steps?: Array<TransformationStep>;
+  theories_involved?: Array<TheoryReference>;
   relationship_metadata: RelationshipMetadata;
 };
@@ -3,10 +3,10 @@ import type { RichTextSegment } from "./RichTextSegment";
 
 export type TransformationStep = {
   step_number: number;
-  description: Array<RichTextSegment>;
+  description?: Array<RichTextSegment>;
   source_concept: string;
   target_concept: string;
   transformation_rule: string;
-  visual_representation: string | null;
-  interactive_demo: string | null;
+  visual_representation?: string | null;
+  interactive_demo?: string | null;
 };
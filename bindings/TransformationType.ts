@@ -3,6 +3,6 @@
 export type TransformationType = {
   transformation_id: string;
   name: string;
-  description: string | null;
+  description?: string | null;
   reversible: boolean;
 };
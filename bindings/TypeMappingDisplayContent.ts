@@ -4,14 +4,15 @@ import type { ControlBinding } from "./ControlBinding";
 import type { InteractionSystem } from "./InteractionSystem";
 import type { MappingVisualization } from "./MappingVisualization";
 import type { Section } from "./Section";
+import type { TheoryId } from "./TheoryId";
 
 export type TypeMappingDisplayContent = {
   title: string;
-  source_theory: string;
-  target_theory: string;
-  mapping_visualizations: Array<MappingVisualization>;
-  base_content: Array<Section>;
+  source_theory: TheoryId;
+  target_theory: TheoryId;
+  mapping_visualizations?: Array<MappingVisualization>;
+  base_content?: Array<Section>;
   interaction_system: InteractionSystem;
-  animation_timeline: AnimationTimeline | null;
-  control_bindings: Array<ControlBinding>;
+  animation_timeline?: AnimationTimeline | null;
+  control_bindings?: Array<ControlBinding>;
 };
@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A number between 0.0 and 1.0 inclusive, for confidences, strengths and similar weights.
+ * Serialized as a plain number; deserializing a number outside the interval fails, so
+ * out-of-range data never reaches the frontend.
+ */
+export type UnitInterval = number;
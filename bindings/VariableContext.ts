@@ -0,0 +1,13 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { InteractiveVariableDeclaration } from "./InteractiveVariableDeclaration";
+import type { MathNode } from "./MathNode";
+
+/**
+ * Document-level store backing `RichTextSegment::InteractiveVariable`.
+ * Every interactive variable id in the document should resolve to one of `declarations`,
+ * so hovering the same variable shows the same information in every section.
+ */
+export type VariableContext = {
+  declarations?: Array<InteractiveVariableDeclaration>;
+  bindings: { [key in string]?: MathNode };
+};
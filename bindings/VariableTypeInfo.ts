@@ -0,0 +1,8 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { MathNode } from "./MathNode";
+import type { RichText } from "./RichText";
+
+/**
+ * The type of an interactive variable, either described in prose or as a math expression.
+ */
+export type VariableTypeInfo = { "Text": RichText } | { "Math": MathNode };
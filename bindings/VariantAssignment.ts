@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { VariantValue } from "./VariantValue";
+
+/**
+ * The values drawn for one variant, in parameter order.
+ */
+export type VariantAssignment = {
+  document_id: string;
+  seed: number;
+  values: Array<[string, VariantValue]>;
+};
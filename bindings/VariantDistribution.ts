@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type VariantDistribution =
+  | { "Integer": { min: number; max: number; exclude: Array<number> } }
+  | { "Decimal": { min: number; max: number; decimals: number } }
+  | { "Choice": { options: Array<string> } };
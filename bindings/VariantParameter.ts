@@ -0,0 +1,10 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { VariantDistribution } from "./VariantDistribution";
+
+/**
+ * A parameter of a variant; identifiers with this name in the exercise's math are substituted.
+ */
+export type VariantParameter = {
+  name: string;
+  distribution: VariantDistribution;
+};
@@ -0,0 +1,11 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { VariantParameter } from "./VariantParameter";
+
+/**
+ * Compact description of a randomized exercise variant: a seed plus how to draw each parameter.
+ *
+ * Values are generated deterministically from the document id, the seed and the parameter name,
+ * so the same homework set can be reproduced from `(document id, seed)` alone, and adding a
+ * parameter does not change the values drawn for the others.
+ */
+export type VariantSeed = { seed: number; parameters: Array<VariantParameter> };
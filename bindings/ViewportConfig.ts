@@ -2,9 +2,9 @@
 import type { ScrollBehavior } from "./ScrollBehavior";
 
 export type ViewportConfig = {
-  width: string | null;
-  height: string | null;
-  responsive: boolean | null;
-  scroll_behavior: ScrollBehavior | null;
-  zoom_level: number | null;
+  width?: string | null;
+  height?: string | null;
+  responsive?: boolean | null;
+  scroll_behavior?: ScrollBehavior | null;
+  zoom_level?: number | null;
 };
@@ -3,12 +3,13 @@ import type { CompletenessLevel } from "./CompletenessLevel";
 import type { ContentMetadata } from "./ContentMetadata";
 import type { DocumentRelationships } from "./DocumentRelationships";
 import type { DocumentStructure } from "./DocumentStructure";
+import type { TheoryId } from "./TheoryId";
 
 export type WikiPageContent = {
   title: string;
-  theory_domain: string;
+  theory_domain: TheoryId;
   completeness_level: CompletenessLevel;
-  maintainer: string | null;
+  maintainer?: string | null;
   content_metadata: ContentMetadata;
   structure: DocumentStructure;
   relationships: DocumentRelationships;
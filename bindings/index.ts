@@ -0,0 +1,948 @@
+// This file was generated by turn-render. Do not edit this file manually.
+export type { AccuracyMetrics } from "./AccuracyMetrics";
+export type { DerivationMetadata } from "./DerivationMetadata";
+export type { DerivationMethod } from "./DerivationMethod";
+export type { AnimationConfig } from "./AnimationConfig";
+export type { AudienceLevel } from "./AudienceLevel";
+export type { FormalityLevel } from "./FormalityLevel";
+export type { InteractionFeature } from "./InteractionFeature";
+export type { LayoutStyle } from "./LayoutStyle";
+export type { PresentationConfig } from "./PresentationConfig";
+export type { AlertBoxStyle } from "./AlertBoxStyle";
+export type { AngleUnit } from "./AngleUnit";
+export type { PositionAnchor } from "./PositionAnchor";
+export type { AnnotationPosition } from "./AnnotationPosition";
+export type { AnnotationStyling } from "./AnnotationStyling";
+export type { AnnotationType } from "./AnnotationType";
+export type { AnimationTriggerType } from "./AnimationTriggerType";
+export type { BinaryOperationType } from "./BinaryOperationType";
+export type { BinaryOperator } from "./BinaryOperator";
+export type { BinomialStyle } from "./BinomialStyle";
+export type { BracePosition } from "./BracePosition";
+export type { BracketSize } from "./BracketSize";
+export type { BracketStyle } from "./BracketStyle";
+export type { CancelStyle } from "./CancelStyle";
+export type { FormulaPart } from "./FormulaPart";
+export type { PhysicalState } from "./PhysicalState";
+export type { ChemicalSpecies } from "./ChemicalSpecies";
+export type { ChemicalTerm } from "./ChemicalTerm";
+export type { ReactionArrow } from "./ReactionArrow";
+export type { ReactionStep } from "./ReactionStep";
+export type { ChemicalExpression } from "./ChemicalExpression";
+export type { DerivativeNotation } from "./DerivativeNotation";
+export type { DifferentialStyle } from "./DifferentialStyle";
+export type { DivisionStyle } from "./DivisionStyle";
+export type { MathEmphasis } from "./MathEmphasis";
+export type { MathSpace } from "./MathSpace";
+export type { TextStyle } from "./TextStyle";
+export type { MathTextSegment } from "./MathTextSegment";
+export type { MatrixAxis } from "./MatrixAxis";
+export type { MatrixDelimiters } from "./MatrixDelimiters";
+export type { PhantomExtent } from "./PhantomExtent";
+export type { QuantificationNode } from "./QuantificationNode";
+export type { RefinedAddOrSubOperator } from "./RefinedAddOrSubOperator";
+export type { DivSymbol } from "./DivSymbol";
+export type { MulSymbol } from "./MulSymbol";
+export type { RefinedMulOrDivOperation } from "./RefinedMulOrDivOperation";
+export type { RelationOperatorNode } from "./RelationOperatorNode";
+export type { ScientificNotationStyle } from "./ScientificNotationStyle";
+export type { Turnstile } from "./Turnstile";
+export type { UnaryRelationOperatorNode } from "./UnaryRelationOperatorNode";
+export type { VectorDecoration } from "./VectorDecoration";
+export type { MatrixHighlight } from "./MatrixHighlight";
+export type { MathNodeContent } from "./MathNodeContent";
+export type { MathNode } from "./MathNode";
+export type { ScriptNode } from "./ScriptNode";
+export type { SpecialMiddleScriptContentTypeNode } from "./SpecialMiddleScriptContentTypeNode";
+export type { SpecialMiddleScriptNode } from "./SpecialMiddleScriptNode";
+export type { Identifier } from "./Identifier";
+export type { FunctionClause } from "./FunctionClause";
+export type { TextAlignment } from "./TextAlignment";
+export type { RichTextSegment } from "./RichTextSegment";
+export type { RichText } from "./RichText";
+export type { VariableTypeInfo } from "./VariableTypeInfo";
+export type { InteractiveVariableDeclaration } from "./InteractiveVariableDeclaration";
+export type { DirectionType } from "./DirectionType";
+export type { Direction } from "./Direction";
+export type { AnimationType } from "./AnimationType";
+export type { EasingType } from "./EasingType";
+export type { EasingFunction } from "./EasingFunction";
+export type { AnimationKeyframe } from "./AnimationKeyframe";
+export type { InteractionType } from "./InteractionType";
+export type { UserAction } from "./UserAction";
+export type { InteractionPoint } from "./InteractionPoint";
+export type { SyncType } from "./SyncType";
+export type { SyncGroup } from "./SyncGroup";
+export type { AnimationTimeline } from "./AnimationTimeline";
+export type { CognitiveLoad } from "./CognitiveLoad";
+export type { ContentRole } from "./ContentRole";
+export type { ValueTransformation } from "./ValueTransformation";
+export type { ControlBinding } from "./ControlBinding";
+export type { DifficultyLevel } from "./DifficultyLevel";
+export type { DifficultyAnnotation } from "./DifficultyAnnotation";
+export type { ControlType } from "./ControlType";
+export type { Control } from "./Control";
+export type { InteractionAction } from "./InteractionAction";
+export type { EventHandler } from "./EventHandler";
+export type { StateVariableType } from "./StateVariableType";
+export type { StateVariable } from "./StateVariable";
+export type { InteractionSystem } from "./InteractionSystem";
+export type { CodeBlockNode } from "./CodeBlockNode";
+export type { ComponentProps } from "./ComponentProps";
+export type { DerivationStep } from "./DerivationStep";
+export type { DerivationChain } from "./DerivationChain";
+export type { EquationNumbering } from "./EquationNumbering";
+export type { Equation } from "./Equation";
+export type { FeedbackResponseKind } from "./FeedbackResponseKind";
+export type { FeedbackNode } from "./FeedbackNode";
+export type { GridItemNode } from "./GridItemNode";
+export type { GridNode } from "./GridNode";
+export type { HorizontalAlignment } from "./HorizontalAlignment";
+export type { ImageNode } from "./ImageNode";
+export type { ControlLayout } from "./ControlLayout";
+export type { InteractiveControls } from "./InteractiveControls";
+export type { InteractiveDiagramNode } from "./InteractiveDiagramNode";
+export type { RenderPriority } from "./RenderPriority";
+export type { PanelRenderingHints } from "./PanelRenderingHints";
+export type { PanelRole } from "./PanelRole";
+export type { ContextType } from "./ContextType";
+export type { ExpressionPosition } from "./ExpressionPosition";
+export type { HandlerType } from "./HandlerType";
+export type { InstantiationDirection } from "./InstantiationDirection";
+export type { InstantiationMap } from "./InstantiationMap";
+export type { InteractionHandler } from "./InteractionHandler";
+export type { InteractiveElementType } from "./InteractiveElementType";
+export type { InteractiveElement } from "./InteractiveElement";
+export type { ProofExpressionInteractionType } from "./ProofExpressionInteractionType";
+export type { InteractiveExpression } from "./InteractiveExpression";
+export type { PatternMatch } from "./PatternMatch";
+export type { ContextVariableDisplay } from "./ContextVariableDisplay";
+export type { GoalVisualStyle } from "./GoalVisualStyle";
+export type { GoalDisplay } from "./GoalDisplay";
+export type { ProofLayoutType } from "./ProofLayoutType";
+export type { ProofNodeVisualState } from "./ProofNodeVisualState";
+export type { TacticVisualStyle } from "./TacticVisualStyle";
+export type { TransformationWorkflowStage } from "./TransformationWorkflowStage";
+export type { TacticDisplay } from "./TacticDisplay";
+export type { TransformationAnimationConfig } from "./TransformationAnimationConfig";
+export type { VisualConnection } from "./VisualConnection";
+export type { TransformationDisplay } from "./TransformationDisplay";
+export type { ProofNodeDisplay } from "./ProofNodeDisplay";
+export type { ProofVisualStyle } from "./ProofVisualStyle";
+export type { ProofForestDisplay } from "./ProofForestDisplay";
+export type { ProofInteractionConfig } from "./ProofInteractionConfig";
+export type { ProofTransformationData } from "./ProofTransformationData";
+export type { ProofColorScheme } from "./ProofColorScheme";
+export type { ProofVisualConfig } from "./ProofVisualConfig";
+export type { InteractiveProofDisplay } from "./InteractiveProofDisplay";
+export type { LogicalNode } from "./LogicalNode";
+export type { VariableDeclaration } from "./VariableDeclaration";
+export type { QuantifiedVariableDeclarationGroup } from "./QuantifiedVariableDeclarationGroup";
+export type { Judgement } from "./Judgement";
+export type { SectionDisplayOptions } from "./SectionDisplayOptions";
+export type { VariantDistribution } from "./VariantDistribution";
+export type { VariantParameter } from "./VariantParameter";
+export type { VariantSeed } from "./VariantSeed";
+export type { Solution } from "./Solution";
+export type { SecondOrderMathNode } from "./SecondOrderMathNode";
+export type { CorrespondenceGroup } from "./CorrespondenceGroup";
+export type { SideBySideConfig } from "./SideBySideConfig";
+export type { SideBySideLayout } from "./SideBySideLayout";
+export type { ColumnStyle } from "./ColumnStyle";
+export type { TableCellType } from "./TableCellType";
+export type { ThematicBreakNode } from "./ThematicBreakNode";
+export type { TableCellNode } from "./TableCellNode";
+export type { TableRowNode } from "./TableRowNode";
+export type { TableStyleOptions } from "./TableStyleOptions";
+export type { TableNode } from "./TableNode";
+export type { Panel } from "./Panel";
+export type { PanelControls } from "./PanelControls";
+export type { PanelLayoutType } from "./PanelLayoutType";
+export type { PanelLayout } from "./PanelLayout";
+export type { ListItemNode } from "./ListItemNode";
+export type { OrderedListStyle } from "./OrderedListStyle";
+export type { UnorderedListStyle } from "./UnorderedListStyle";
+export type { ListStyle } from "./ListStyle";
+export type { ListNode } from "./ListNode";
+export type { GenerationErrorNode } from "./GenerationErrorNode";
+export type { ColumnsNode } from "./ColumnsNode";
+export type { CollapsibleBlockNode } from "./CollapsibleBlockNode";
+export type { NodeState } from "./NodeState";
+export type { NodeType } from "./NodeType";
+export type { BranchingNode } from "./BranchingNode";
+export type { LayoutAlignment } from "./LayoutAlignment";
+export type { LayoutDirection } from "./LayoutDirection";
+export type { LayoutType } from "./LayoutType";
+export type { ContainerLayout } from "./ContainerLayout";
+export type { ContainerType } from "./ContainerType";
+export type { BranchingContainer } from "./BranchingContainer";
+export type { SectionContentNode } from "./SectionContentNode";
+export type { Section } from "./Section";
+export type { AnimatedPresentationContent } from "./AnimatedPresentationContent";
+export type { AcademicMetadata } from "./AcademicMetadata";
+export type { UnitInterval } from "./UnitInterval";
+export type { SourceReference } from "./SourceReference";
+export type { WritingStyle } from "./WritingStyle";
+export type { BlogPostContent } from "./BlogPostContent";
+export type { ComparisonCriterion } from "./ComparisonCriterion";
+export type { ComparisonLayout } from "./ComparisonLayout";
+export type { ComparisonSection } from "./ComparisonSection";
+export type { ComparisonStructure } from "./ComparisonStructure";
+export type { RelationshipMetadata } from "./RelationshipMetadata";
+export type { TheoryId } from "./TheoryId";
+export type { TheoryReference } from "./TheoryReference";
+export type { ComparisonPageContent } from "./ComparisonPageContent";
+export type { ArrowStyle } from "./ArrowStyle";
+export type { AlignmentArrow } from "./AlignmentArrow";
+export type { AlignmentPrecision } from "./AlignmentPrecision";
+export type { AlignmentType } from "./AlignmentType";
+export type { VisualizationType } from "./VisualizationType";
+export type { AlignmentVisualization } from "./AlignmentVisualization";
+export type { CorrespondenceType } from "./CorrespondenceType";
+export type { ConceptCorrespondence } from "./ConceptCorrespondence";
+export type { ConceptAlignmentContent } from "./ConceptAlignmentContent";
+export type { ContextPreservationLevel } from "./ContextPreservationLevel";
+export type { ExtractionMetadata } from "./ExtractionMetadata";
+export type { InteractionLevel } from "./InteractionLevel";
+export type { ScrollBehavior } from "./ScrollBehavior";
+export type { ViewportConfig } from "./ViewportConfig";
+export type { ConceptExtractContent } from "./ConceptExtractContent";
+export type { RelationshipType } from "./RelationshipType";
+export type { ConceptMapContent } from "./ConceptMapContent";
+export type { SandboxPermission } from "./SandboxPermission";
+export type { IFrameEmbedContent } from "./IFrameEmbedContent";
+export type { ConstraintType } from "./ConstraintType";
+export type { Constraint } from "./Constraint";
+export type { ParameterRange } from "./ParameterRange";
+export type { ParameterType } from "./ParameterType";
+export type { Parameter } from "./Parameter";
+export type { ParameterSpace } from "./ParameterSpace";
+export type { InteractivePlaygroundContent } from "./InteractivePlaygroundContent";
+export type { AllowedInteraction } from "./AllowedInteraction";
+export type { LiveEmbedContent } from "./LiveEmbedContent";
+export type { ContentMetadata } from "./ContentMetadata";
+export type { ConceptRelationType } from "./ConceptRelationType";
+export type { ConceptReference } from "./ConceptReference";
+export type { CrossReferenceType } from "./CrossReferenceType";
+export type { CrossReference } from "./CrossReference";
+export type { DependencyType } from "./DependencyType";
+export type { DependencyEdge } from "./DependencyEdge";
+export type { DependencyNodeType } from "./DependencyNodeType";
+export type { DependencyNode } from "./DependencyNode";
+export type { DependencyGraph } from "./DependencyGraph";
+export type { DocumentRelationships } from "./DocumentRelationships";
+export type { BibEntry } from "./BibEntry";
+export type { TocNode } from "./TocNode";
+export type { DocumentStructure } from "./DocumentStructure";
+export type { MathematicianNotesContent } from "./MathematicianNotesContent";
+export type { NoteStyle } from "./NoteStyle";
+export type { PersonalNotesContent } from "./PersonalNotesContent";
+export type { FilterType } from "./FilterType";
+export type { FilterOption } from "./FilterOption";
+export type { ResourceType } from "./ResourceType";
+export type { ResourceItem } from "./ResourceItem";
+export type { ResourceCategory } from "./ResourceCategory";
+export type { SortOption } from "./SortOption";
+export type { SearchCapabilities } from "./SearchCapabilities";
+export type { ResourcePanelContent } from "./ResourcePanelContent";
+export type { PaperType } from "./PaperType";
+export type { ScientificPaperContent } from "./ScientificPaperContent";
+export type { StaticPreviewContent } from "./StaticPreviewContent";
+export type { CourseLevel } from "./CourseLevel";
+export type { DifficultyProfile } from "./DifficultyProfile";
+export type { StudyNotesContent } from "./StudyNotesContent";
+export type { ChapterInfo } from "./ChapterInfo";
+export type { TextbookContent } from "./TextbookContent";
+export type { SummarizationLevel } from "./SummarizationLevel";
+export type { TooltipSummaryContent } from "./TooltipSummaryContent";
+export type { TransformationStep } from "./TransformationStep";
+export type { TransformationType } from "./TransformationType";
+export type { TransformationMappingContent } from "./TransformationMappingContent";
+export type { MappingArrow } from "./MappingArrow";
+export type { MappingVisualization } from "./MappingVisualization";
+export type { TypeMappingDisplayContent } from "./TypeMappingDisplayContent";
+export type { CompletenessLevel } from "./CompletenessLevel";
+export type { WikiPageContent } from "./WikiPageContent";
+export type { MathDocumentType } from "./MathDocumentType";
+export type { PathSegment } from "./PathSegment";
+export type { NodePath } from "./NodePath";
+export type { NumberLocale } from "./NumberLocale";
+export type { NumberNotation } from "./NumberNotation";
+export type { QuantityFormat } from "./QuantityFormat";
+export type { Revision } from "./Revision";
+export type { RevisionHistory } from "./RevisionHistory";
+export type { SectionParent } from "./SectionParent";
+export type { VariableContext } from "./VariableContext";
+export type { PatchOperation } from "./PatchOperation";
+export type { DocumentPatch } from "./DocumentPatch";
+export type { MathDocument } from "./MathDocument";
+export type { LinkTarget } from "./LinkTarget";
+export type { TextQuoteAnchor } from "./TextQuoteAnchor";
+export type { Annotation } from "./Annotation";
+export type { OverlayStyle } from "./OverlayStyle";
+export type { AnnotationOverlay } from "./AnnotationOverlay";
+export type { ConceptRelationship } from "./ConceptRelationship";
+export type { CoreExample } from "./CoreExample";
+export type { EssentialDefinition } from "./EssentialDefinition";
+export type { ImportanceLevel } from "./ImportanceLevel";
+export type { KeyPoint } from "./KeyPoint";
+export type { SimplifiedContentStructure } from "./SimplifiedContentStructure";
+export type { AbstractSummaryContent } from "./AbstractSummaryContent";
+export type { AbstractionMetadata } from "./AbstractionMetadata";
+export type { AutoFoldOptions } from "./AutoFoldOptions";
+export type { BacklinkKind } from "./BacklinkKind";
+export type { Backlink } from "./Backlink";
+export type { BacklinkIndex } from "./BacklinkIndex";
+export type { BaseUnitTypeNode } from "./BaseUnitTypeNode";
+export type { BindingType } from "./BindingType";
+export type { CapabilityManifest } from "./CapabilityManifest";
+export type { Timestamp } from "./Timestamp";
+export type { CollabOperation } from "./CollabOperation";
+export type { PropType } from "./PropType";
+export type { PropSchema } from "./PropSchema";
+export type { ComponentSchema } from "./ComponentSchema";
+export type { ComponentRegistry } from "./ComponentRegistry";
+export type { LocationStep } from "./LocationStep";
+export type { Severity } from "./Severity";
+export type { Diagnostic } from "./Diagnostic";
+export type { FeedbackValue } from "./FeedbackValue";
+export type { FeedbackResponse } from "./FeedbackResponse";
+export type { InequalityType } from "./InequalityType";
+export type { IntegralDomain } from "./IntegralDomain";
+export type { IntegralType } from "./IntegralType";
+export type { LmsItem } from "./LmsItem";
+export type { LmsPackage } from "./LmsPackage";
+export type { MathMetrics } from "./MathMetrics";
+export type { NumberType } from "./NumberType";
+export type { OperationType } from "./OperationType";
+export type { QuantifierType } from "./QuantifierType";
+export type { QuantifiedObject } from "./QuantifiedObject";
+export type { QuantifierGroup } from "./QuantifierGroup";
+export type { SchemaManifest } from "./SchemaManifest";
+export type { SectionMetrics } from "./SectionMetrics";
+export type { SelectableProperty } from "./SelectableProperty";
+export type { SetType } from "./SetType";
+export type { TheoremLikeKind } from "./TheoremLikeKind";
+export type { TheoryEntry } from "./TheoryEntry";
+export type { TransformationFlow } from "./TransformationFlow";
+export type { TurnTextLineNode } from "./TurnTextLineNode";
+export type { UnitComponent } from "./UnitComponent";
+export type { VariableBinding } from "./VariableBinding";
+export type { VariantValue } from "./VariantValue";
+export type { VariantAssignment } from "./VariantAssignment";
+
+import type { MathNodeContent } from "./MathNodeContent";
+import type { SectionContentNode } from "./SectionContentNode";
+
+/**
+ * The variant of a `MathNodeContent`.
+ */
+export type MathNodeContentTag =
+  | "Empty"
+  | "Text"
+  | "String"
+  | "Phantom"
+  | "Space"
+  | "Bracketed"
+  | "Matrix"
+  | "BinaryOperation"
+  | "Multiplications"
+  | "Additions"
+  | "Division"
+  | "SumNotation"
+  | "ProductNotation"
+  | "Fraction"
+  | "Power"
+  | "Radical"
+  | "Binomial"
+  | "Cases"
+  | "UnaryPostfixOperation"
+  | "UnaryPrefixOperation"
+  | "Abs"
+  | "Norm"
+  | "Decorated"
+  | "Brace"
+  | "Styled"
+  | "Cancelled"
+  | "AngleMeasure"
+  | "AngleSymbol"
+  | "Coordinates"
+  | "Bra"
+  | "Ket"
+  | "BraKet"
+  | "Expectation"
+  | "FunctionCall"
+  | "Quantity"
+  | "ScientificNotation"
+  | "Identifier"
+  | "Unit"
+  | "ChemicalExpression"
+  | "Relationship"
+  | "UnaryRelationship"
+  | "VariableDefinition"
+  | "FunctionDefinition"
+  | "Limit"
+  | "Differential"
+  | "Integration"
+  | "QuantifiedExpression"
+  | "SetExplicit"
+  | "SetBuilder"
+  | "RichTextContent"
+  | "And"
+  | "Or"
+  | "Not"
+  | "True"
+  | "False"
+  | "Sequent"
+  | "Folded";
+
+export function mathNodeContentTag(value: MathNodeContent): MathNodeContentTag {
+  return value.kind;
+}
+
+export function isMathNodeContentEmpty(
+  value: MathNodeContent,
+): value is Extract<MathNodeContent, { kind: "Empty" }> {
+  return value.kind === "Empty";
+}
+
+export function isMathNodeContentText(
+  value: MathNodeContent,
+): value is Extract<MathNodeContent, { kind: "Text" }> {
+  return value.kind === "Text";
+}
+
+export function isMathNodeContentString(
+  value: MathNodeContent,
+): value is Extract<MathNodeContent, { kind: "String" }> {
+  return value.kind === "String";
+}
+
+export function isMathNodeContentPhantom(
+  value: MathNodeContent,
+): value is Extract<MathNodeContent, { kind: "Phantom" }> {
+  return value.kind === "Phantom";
+}
+
+export function isMathNodeContentSpace(
+  value: MathNodeContent,
+): value is Extract<MathNodeContent, { kind: "Space" }> {
+  return value.kind === "Space";
+}
+
+export function isMathNodeContentBracketed(
+  value: MathNodeContent,
+): value is Extract<MathNodeContent, { kind: "Bracketed" }> {
+  return value.kind === "Bracketed";
+}
+
+export function isMathNodeContentMatrix(
+  value: MathNodeContent,
+): value is Extract<MathNodeContent, { kind: "Matrix" }> {
+  return value.kind === "Matrix";
+}
+
+export function isMathNodeContentBinaryOperation(
+  value: MathNodeContent,
+): value is Extract<MathNodeContent, { kind: "BinaryOperation" }> {
+  return value.kind === "BinaryOperation";
+}
+
+export function isMathNodeContentMultiplications(
+  value: MathNodeContent,
+): value is Extract<MathNodeContent, { kind: "Multiplications" }> {
+  return value.kind === "Multiplications";
+}
+
+export function isMathNodeContentAdditions(
+  value: MathNodeContent,
+): value is Extract<MathNodeContent, { kind: "Additions" }> {
+  return value.kind === "Additions";
+}
+
+export function isMathNodeContentDivision(
+  value: MathNodeContent,
+): value is Extract<MathNodeContent, { kind: "Division" }> {
+  return value.kind === "Division";
+}
+
+export function isMathNodeContentSumNotation(
+  value: MathNodeContent,
+): value is Extract<MathNodeContent, { kind: "SumNotation" }> {
+  return value.kind === "SumNotation";
+}
+
+export function isMathNodeContentProductNotation(
+  value: MathNodeContent,
+): value is Extract<MathNodeContent, { kind: "ProductNotation" }> {
+  return value.kind === "ProductNotation";
+}
+
+export function isMathNodeContentFraction(
+  value: MathNodeContent,
+): value is Extract<MathNodeContent, { kind: "Fraction" }> {
+  return value.kind === "Fraction";
+}
+
+export function isMathNodeContentPower(
+  value: MathNodeContent,
+): value is Extract<MathNodeContent, { kind: "Power" }> {
+  return value.kind === "Power";
+}
+
+export function isMathNodeContentRadical(
+  value: MathNodeContent,
+): value is Extract<MathNodeContent, { kind: "Radical" }> {
+  return value.kind === "Radical";
+}
+
+export function isMathNodeContentBinomial(
+  value: MathNodeContent,
+): value is Extract<MathNodeContent, { kind: "Binomial" }> {
+  return value.kind === "Binomial";
+}
+
+export function isMathNodeContentCases(
+  value: MathNodeContent,
+): value is Extract<MathNodeContent, { kind: "Cases" }> {
+  return value.kind === "Cases";
+}
+
+export function isMathNodeContentUnaryPostfixOperation(
+  value: MathNodeContent,
+): value is Extract<MathNodeContent, { kind: "UnaryPostfixOperation" }> {
+  return value.kind === "UnaryPostfixOperation";
+}
+
+export function isMathNodeContentUnaryPrefixOperation(
+  value: MathNodeContent,
+): value is Extract<MathNodeContent, { kind: "UnaryPrefixOperation" }> {
+  return value.kind === "UnaryPrefixOperation";
+}
+
+export function isMathNodeContentAbs(
+  value: MathNodeContent,
+): value is Extract<MathNodeContent, { kind: "Abs" }> {
+  return value.kind === "Abs";
+}
+
+export function isMathNodeContentNorm(
+  value: MathNodeContent,
+): value is Extract<MathNodeContent, { kind: "Norm" }> {
+  return value.kind === "Norm";
+}
+
+export function isMathNodeContentDecorated(
+  value: MathNodeContent,
+): value is Extract<MathNodeContent, { kind: "Decorated" }> {
+  return value.kind === "Decorated";
+}
+
+export function isMathNodeContentBrace(
+  value: MathNodeContent,
+): value is Extract<MathNodeContent, { kind: "Brace" }> {
+  return value.kind === "Brace";
+}
+
+export function isMathNodeContentStyled(
+  value: MathNodeContent,
+): value is Extract<MathNodeContent, { kind: "Styled" }> {
+  return value.kind === "Styled";
+}
+
+export function isMathNodeContentCancelled(
+  value: MathNodeContent,
+): value is Extract<MathNodeContent, { kind: "Cancelled" }> {
+  return value.kind === "Cancelled";
+}
+
+export function isMathNodeContentAngleMeasure(
+  value: MathNodeContent,
+): value is Extract<MathNodeContent, { kind: "AngleMeasure" }> {
+  return value.kind === "AngleMeasure";
+}
+
+export function isMathNodeContentAngleSymbol(
+  value: MathNodeContent,
+): value is Extract<MathNodeContent, { kind: "AngleSymbol" }> {
+  return value.kind === "AngleSymbol";
+}
+
+export function isMathNodeContentCoordinates(
+  value: MathNodeContent,
+): value is Extract<MathNodeContent, { kind: "Coordinates" }> {
+  return value.kind === "Coordinates";
+}
+
+export function isMathNodeContentBra(
+  value: MathNodeContent,
+): value is Extract<MathNodeContent, { kind: "Bra" }> {
+  return value.kind === "Bra";
+}
+
+export function isMathNodeContentKet(
+  value: MathNodeContent,
+): value is Extract<MathNodeContent, { kind: "Ket" }> {
+  return value.kind === "Ket";
+}
+
+export function isMathNodeContentBraKet(
+  value: MathNodeContent,
+): value is Extract<MathNodeContent, { kind: "BraKet" }> {
+  return value.kind === "BraKet";
+}
+
+export function isMathNodeContentExpectation(
+  value: MathNodeContent,
+): value is Extract<MathNodeContent, { kind: "Expectation" }> {
+  return value.kind === "Expectation";
+}
+
+export function isMathNodeContentFunctionCall(
+  value: MathNodeContent,
+): value is Extract<MathNodeContent, { kind: "FunctionCall" }> {
+  return value.kind === "FunctionCall";
+}
+
+export function isMathNodeContentQuantity(
+  value: MathNodeContent,
+): value is Extract<MathNodeContent, { kind: "Quantity" }> {
+  return value.kind === "Quantity";
+}
+
+export function isMathNodeContentScientificNotation(
+  value: MathNodeContent,
+): value is Extract<MathNodeContent, { kind: "ScientificNotation" }> {
+  return value.kind === "ScientificNotation";
+}
+
+export function isMathNodeContentIdentifier(
+  value: MathNodeContent,
+): value is Extract<MathNodeContent, { kind: "Identifier" }> {
+  return value.kind === "Identifier";
+}
+
+export function isMathNodeContentUnit(
+  value: MathNodeContent,
+): value is Extract<MathNodeContent, { kind: "Unit" }> {
+  return value.kind === "Unit";
+}
+
+export function isMathNodeContentChemicalExpression(
+  value: MathNodeContent,
+): value is Extract<MathNodeContent, { kind: "ChemicalExpression" }> {
+  return value.kind === "ChemicalExpression";
+}
+
+export function isMathNodeContentRelationship(
+  value: MathNodeContent,
+): value is Extract<MathNodeContent, { kind: "Relationship" }> {
+  return value.kind === "Relationship";
+}
+
+export function isMathNodeContentUnaryRelationship(
+  value: MathNodeContent,
+): value is Extract<MathNodeContent, { kind: "UnaryRelationship" }> {
+  return value.kind === "UnaryRelationship";
+}
+
+export function isMathNodeContentVariableDefinition(
+  value: MathNodeContent,
+): value is Extract<MathNodeContent, { kind: "VariableDefinition" }> {
+  return value.kind === "VariableDefinition";
+}
+
+export function isMathNodeContentFunctionDefinition(
+  value: MathNodeContent,
+): value is Extract<MathNodeContent, { kind: "FunctionDefinition" }> {
+  return value.kind === "FunctionDefinition";
+}
+
+export function isMathNodeContentLimit(
+  value: MathNodeContent,
+): value is Extract<MathNodeContent, { kind: "Limit" }> {
+  return value.kind === "Limit";
+}
+
+export function isMathNodeContentDifferential(
+  value: MathNodeContent,
+): value is Extract<MathNodeContent, { kind: "Differential" }> {
+  return value.kind === "Differential";
+}
+
+export function isMathNodeContentIntegration(
+  value: MathNodeContent,
+): value is Extract<MathNodeContent, { kind: "Integration" }> {
+  return value.kind === "Integration";
+}
+
+export function isMathNodeContentQuantifiedExpression(
+  value: MathNodeContent,
+): value is Extract<MathNodeContent, { kind: "QuantifiedExpression" }> {
+  return value.kind === "QuantifiedExpression";
+}
+
+export function isMathNodeContentSetExplicit(
+  value: MathNodeContent,
+): value is Extract<MathNodeContent, { kind: "SetExplicit" }> {
+  return value.kind === "SetExplicit";
+}
+
+export function isMathNodeContentSetBuilder(
+  value: MathNodeContent,
+): value is Extract<MathNodeContent, { kind: "SetBuilder" }> {
+  return value.kind === "SetBuilder";
+}
+
+export function isMathNodeContentRichTextContent(
+  value: MathNodeContent,
+): value is Extract<MathNodeContent, { kind: "RichTextContent" }> {
+  return value.kind === "RichTextContent";
+}
+
+export function isMathNodeContentAnd(
+  value: MathNodeContent,
+): value is Extract<MathNodeContent, { kind: "And" }> {
+  return value.kind === "And";
+}
+
+export function isMathNodeContentOr(
+  value: MathNodeContent,
+): value is Extract<MathNodeContent, { kind: "Or" }> {
+  return value.kind === "Or";
+}
+
+export function isMathNodeContentNot(
+  value: MathNodeContent,
+): value is Extract<MathNodeContent, { kind: "Not" }> {
+  return value.kind === "Not";
+}
+
+export function isMathNodeContentTrue(
+  value: MathNodeContent,
+): value is Extract<MathNodeContent, { kind: "True" }> {
+  return value.kind === "True";
+}
+
+export function isMathNodeContentFalse(
+  value: MathNodeContent,
+): value is Extract<MathNodeContent, { kind: "False" }> {
+  return value.kind === "False";
+}
+
+export function isMathNodeContentSequent(
+  value: MathNodeContent,
+): value is Extract<MathNodeContent, { kind: "Sequent" }> {
+  return value.kind === "Sequent";
+}
+
+export function isMathNodeContentFolded(
+  value: MathNodeContent,
+): value is Extract<MathNodeContent, { kind: "Folded" }> {
+  return value.kind === "Folded";
+}
+
+/**
+ * The variant of a `SectionContentNode`.
+ */
+export type SectionContentNodeTag =
+  | "SubSection"
+  | "RichText"
+  | "Math"
+  | "SecondOrderMath"
+  | "InteractiveDiagram"
+  | "Theorem"
+  | "List"
+  | "Table"
+  | "CodeBlock"
+  | "Image"
+  | "CollapsibleBlock"
+  | "Grid"
+  | "Columns"
+  | "ThematicBreak"
+  | "QuoteBlock"
+  | "AlertBox"
+  | "CustomComponent"
+  | "EmbeddedSectionRef"
+  | "SideBySideLayout"
+  | "PanelLayout"
+  | "AnnotationOverlay"
+  | "InteractiveControls"
+  | "EmbeddedDocument"
+  | "BranchingContainer"
+  | "Feedback"
+  | "GenerationError"
+  | "DerivationChain"
+  | "Equation";
+
+export function sectionContentNodeTag(value: SectionContentNode): SectionContentNodeTag {
+  return value.kind;
+}
+
+export function isSectionContentNodeSubSection(
+  value: SectionContentNode,
+): value is Extract<SectionContentNode, { kind: "SubSection" }> {
+  return value.kind === "SubSection";
+}
+
+export function isSectionContentNodeRichText(
+  value: SectionContentNode,
+): value is Extract<SectionContentNode, { kind: "RichText" }> {
+  return value.kind === "RichText";
+}
+
+export function isSectionContentNodeMath(
+  value: SectionContentNode,
+): value is Extract<SectionContentNode, { kind: "Math" }> {
+  return value.kind === "Math";
+}
+
+export function isSectionContentNodeSecondOrderMath(
+  value: SectionContentNode,
+): value is Extract<SectionContentNode, { kind: "SecondOrderMath" }> {
+  return value.kind === "SecondOrderMath";
+}
+
+export function isSectionContentNodeInteractiveDiagram(
+  value: SectionContentNode,
+): value is Extract<SectionContentNode, { kind: "InteractiveDiagram" }> {
+  return value.kind === "InteractiveDiagram";
+}
+
+export function isSectionContentNodeTheorem(
+  value: SectionContentNode,
+): value is Extract<SectionContentNode, { kind: "Theorem" }> {
+  return value.kind === "Theorem";
+}
+
+export function isSectionContentNodeList(
+  value: SectionContentNode,
+): value is Extract<SectionContentNode, { kind: "List" }> {
+  return value.kind === "List";
+}
+
+export function isSectionContentNodeTable(
+  value: SectionContentNode,
+): value is Extract<SectionContentNode, { kind: "Table" }> {
+  return value.kind === "Table";
+}
+
+export function isSectionContentNodeCodeBlock(
+  value: SectionContentNode,
+): value is Extract<SectionContentNode, { kind: "CodeBlock" }> {
+  return value.kind === "CodeBlock";
+}
+
+export function isSectionContentNodeImage(
+  value: SectionContentNode,
+): value is Extract<SectionContentNode, { kind: "Image" }> {
+  return value.kind === "Image";
+}
+
+export function isSectionContentNodeCollapsibleBlock(
+  value: SectionContentNode,
+): value is Extract<SectionContentNode, { kind: "CollapsibleBlock" }> {
+  return value.kind === "CollapsibleBlock";
+}
+
+export function isSectionContentNodeGrid(
+  value: SectionContentNode,
+): value is Extract<SectionContentNode, { kind: "Grid" }> {
+  return value.kind === "Grid";
+}
+
+export function isSectionContentNodeColumns(
+  value: SectionContentNode,
+): value is Extract<SectionContentNode, { kind: "Columns" }> {
+  return value.kind === "Columns";
+}
+
+export function isSectionContentNodeThematicBreak(
+  value: SectionContentNode,
+): value is Extract<SectionContentNode, { kind: "ThematicBreak" }> {
+  return value.kind === "ThematicBreak";
+}
+
+export function isSectionContentNodeQuoteBlock(
+  value: SectionContentNode,
+): value is Extract<SectionContentNode, { kind: "QuoteBlock" }> {
+  return value.kind === "QuoteBlock";
+}
+
+export function isSectionContentNodeAlertBox(
+  value: SectionContentNode,
+): value is Extract<SectionContentNode, { kind: "AlertBox" }> {
+  return value.kind === "AlertBox";
+}
+
+export function isSectionContentNodeCustomComponent(
+  value: SectionContentNode,
+): value is Extract<SectionContentNode, { kind: "CustomComponent" }> {
+  return value.kind === "CustomComponent";
+}
+
+export function isSectionContentNodeEmbeddedSectionRef(
+  value: SectionContentNode,
+): value is Extract<SectionContentNode, { kind: "EmbeddedSectionRef" }> {
+  return value.kind === "EmbeddedSectionRef";
+}
+
+export function isSectionContentNodeSideBySideLayout(
+  value: SectionContentNode,
+): value is Extract<SectionContentNode, { kind: "SideBySideLayout" }> {
+  return value.kind === "SideBySideLayout";
+}
+
+export function isSectionContentNodePanelLayout(
+  value: SectionContentNode,
+): value is Extract<SectionContentNode, { kind: "PanelLayout" }> {
+  return value.kind === "PanelLayout";
+}
+
+export function isSectionContentNodeAnnotationOverlay(
+  value: SectionContentNode,
+): value is Extract<SectionContentNode, { kind: "AnnotationOverlay" }> {
+  return value.kind === "AnnotationOverlay";
+}
+
+export function isSectionContentNodeInteractiveControls(
+  value: SectionContentNode,
+): value is Extract<SectionContentNode, { kind: "InteractiveControls" }> {
+  return value.kind === "InteractiveControls";
+}
+
+export function isSectionContentNodeEmbeddedDocument(
+  value: SectionContentNode,
+): value is Extract<SectionContentNode, { kind: "EmbeddedDocument" }> {
+  return value.kind === "EmbeddedDocument";
+}
+
+export function isSectionContentNodeBranchingContainer(
+  value: SectionContentNode,
+): value is Extract<SectionContentNode, { kind: "BranchingContainer" }> {
+  return value.kind === "BranchingContainer";
+}
+
+export function isSectionContentNodeFeedback(
+  value: SectionContentNode,
+): value is Extract<SectionContentNode, { kind: "Feedback" }> {
+  return value.kind === "Feedback";
+}
+
+export function isSectionContentNodeGenerationError(
+  value: SectionContentNode,
+): value is Extract<SectionContentNode, { kind: "GenerationError" }> {
+  return value.kind === "GenerationError";
+}
+
+export function isSectionContentNodeDerivationChain(
+  value: SectionContentNode,
+): value is Extract<SectionContentNode, { kind: "DerivationChain" }> {
+  return value.kind === "DerivationChain";
+}
+
+export function isSectionContentNodeEquation(
+  value: SectionContentNode,
+): value is Extract<SectionContentNode, { kind: "Equation" }> {
+  return value.kind === "Equation";
+}
@@ -0,0 +1,386 @@
+use super::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::path::{Component, Path};
+use ts_rs::TS;
+
+pub const BUNDLE_EXTENSION: &str = "turndoc";
+pub const BUNDLE_FORMAT_VERSION: u32 = 2; // 2: adjacently tagged enums
+
+const MANIFEST_ENTRY: &str = "manifest.json";
+const DOCUMENT_ENTRY: &str = "document.json";
+
+/// `manifest.json` of a `.turndoc` bundle: a ZIP archive holding the document, the images it
+/// shows and its tooltip documents. In `document.json` each tooltip document is left without
+/// its sections, which are in the tooltip's own entry; tooltips are listed in document order,
+/// each one before the tooltips in its link text and inside it. The entry of a tooltip that
+/// could not be loaded holds `null`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct BundleManifest {
+    pub format_version: u32,
+    pub document: String,
+    pub images: Vec<String>, // the entries, which image nodes in the bundle refer to
+    pub tooltips: Vec<String>,
+}
+
+/// A document with the images it shows, as stored in a bundle. Image nodes refer to the
+/// images by their key in `images`.
+#[derive(Debug, Clone)]
+pub struct DocumentBundle {
+    pub document: MathDocument,
+    pub images: BTreeMap<String, Vec<u8>>,
+}
+
+impl DocumentBundle {
+    /// Bundles the document with the local images it shows, read with `read_image` from their
+    /// `src`. Images given by a URL or a data URI stay where they are.
+    pub fn new(
+        document: &MathDocument,
+        read_image: impl FnMut(&str) -> Option<Vec<u8>>,
+    ) -> Result<DocumentBundle, BundleError> {
+        let mut document = document.clone();
+        let mut collector = ImageCollector {
+            read_image,
+            paths: HashMap::new(),
+            images: BTreeMap::new(),
+            missing: None,
+        };
+        collector.visit_document_mut(&mut document);
+        if let Some(src) = collector.missing {
+            return Err(BundleError::MissingImage { src });
+        }
+        Ok(DocumentBundle {
+            document,
+            images: collector.images,
+        })
+    }
+
+    pub fn to_bytes(&self) -> Result<Vec<u8>, BundleError> {
+        let mut document = self.document.clone();
+        let mut extractor = TooltipExtractor { tooltips: vec![] };
+        extractor.visit_document_mut(&mut document);
+        let tooltips = extractor.tooltips.into_iter();
+
+        let mut zip = ZipWriter::new();
+        let mut manifest = BundleManifest {
+            format_version: BUNDLE_FORMAT_VERSION,
+            document: DOCUMENT_ENTRY.to_string(),
+            images: vec![],
+            tooltips: vec![],
+        };
+        zip.add_file(DOCUMENT_ENTRY, &to_json(&document)?);
+        for (index, tooltip) in tooltips.enumerate() {
+            let entry = format!("tooltips/{}.json", index);
+            zip.add_file(&entry, &to_json(&tooltip)?);
+            manifest.tooltips.push(entry);
+        }
+        for (path, image) in &self.images {
+            zip.add_file(path, image);
+            manifest.images.push(path.clone());
+        }
+        zip.add_file(MANIFEST_ENTRY, &to_json(&manifest)?);
+        Ok(zip.finish())
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<DocumentBundle, BundleError> {
+        let mut entries: HashMap<String, Vec<u8>> = read_zip(bytes)?.into_iter().collect();
+        let mut take = |name: &str| {
+            entries
+                .remove(name)
+                .ok_or_else(|| BundleError::MissingEntry {
+                    name: name.to_string(),
+                })
+        };
+        let manifest: BundleManifest = from_json(MANIFEST_ENTRY, &take(MANIFEST_ENTRY)?)?;
+        if manifest.format_version != BUNDLE_FORMAT_VERSION {
+            return Err(BundleError::UnsupportedVersion {
+                version: manifest.format_version,
+            });
+        }
+        let mut document: MathDocument = from_json(&manifest.document, &take(&manifest.document)?)?;
+        let mut tooltips: Vec<Option<MathDocument>> = vec![];
+        for entry in &manifest.tooltips {
+            tooltips.push(from_json(entry, &take(entry)?)?);
+        }
+        let mut images = BTreeMap::new();
+        for path in &manifest.images {
+            images.insert(path.clone(), take(path)?);
+        }
+
+        let mut restorer = TooltipRestorer {
+            tooltips: tooltips.into_iter(),
+        };
+        restorer.visit_document_mut(&mut document);
+        if restorer.tooltips.next().is_some() {
+            return Err(BundleError::UnusedTooltips);
+        }
+        Ok(DocumentBundle { document, images })
+    }
+}
+
+impl MathDocument {
+    /// Writes the document with its images and tooltip documents as a single bundle file,
+    /// conventionally named `*.turndoc`. Images with a relative `src` are read from `asset_dir`;
+    /// a `src` that is absolute or climbs out with `..` is refused rather than read.
+    pub fn save_bundle(
+        &self,
+        path: impl AsRef<Path>,
+        asset_dir: impl AsRef<Path>,
+    ) -> Result<(), BundleError> {
+        let asset_dir = asset_dir.as_ref();
+        let mut outside = None;
+        let bundle = DocumentBundle::new(self, |src| {
+            if !is_inside_asset_dir(src) {
+                outside.get_or_insert_with(|| src.to_string());
+                return None;
+            }
+            std::fs::read(asset_dir.join(src)).ok()
+        });
+        if let Some(src) = outside {
+            return Err(BundleError::ImageOutsideAssetDir { src });
+        }
+        let bundle = bundle?;
+        std::fs::write(path, bundle.to_bytes()?).map_err(|error| BundleError::Io(error.to_string()))
+    }
+
+    pub fn load_bundle(path: impl AsRef<Path>) -> Result<DocumentBundle, BundleError> {
+        let bytes = std::fs::read(path).map_err(|error| BundleError::Io(error.to_string()))?;
+        DocumentBundle::from_bytes(&bytes)
+    }
+}
+
+/// Whether `src` refers to an image outside the bundle's reach: a URL or a data URI.
+fn is_remote(src: &str) -> bool {
+    src.contains("://") || src.starts_with("data:")
+}
+
+/// Whether `src`, joined to the asset directory, stays inside it: no root, drive prefix or `..`.
+fn is_inside_asset_dir(src: &str) -> bool {
+    Path::new(src)
+        .components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+}
+
+struct ImageCollector<F> {
+    read_image: F,
+    paths: HashMap<String, String>, // original src -> entry
+    images: BTreeMap<String, Vec<u8>>,
+    missing: Option<String>,
+}
+
+impl<F: FnMut(&str) -> Option<Vec<u8>>> DocumentVisitorMut for ImageCollector<F> {
+    fn visit_content_node_mut(&mut self, node: &mut SectionContentNode) {
+        if let SectionContentNode::Image(image) = node
+            && !is_remote(&image.src)
+            && !image.src.is_empty()
+        {
+            if let Some(path) = self.paths.get(&image.src) {
+                image.src = path.clone();
+            } else if let Some(bytes) = (self.read_image)(&image.src) {
+                let extension = Path::new(&image.src)
+                    .extension()
+                    .and_then(|extension| extension.to_str())
+                    .map(|extension| format!(".{}", extension))
+                    .unwrap_or_default();
+                let path = format!("images/{}{}", self.paths.len(), extension);
+                self.paths.insert(image.src.clone(), path.clone());
+                self.images.insert(path.clone(), bytes);
+                image.src = path;
+            } else if self.missing.is_none() {
+                self.missing = Some(image.src.clone());
+            }
+        }
+        walk_content_node_mut(self, node);
+    }
+}
+
+/// Takes the sections out of tooltip documents, leaving shells with the rest of their data.
+/// Tooltips are numbered in the order `walk_segment_mut` reaches them: a link's own tooltip,
+/// then those in the link's content, then those inside its tooltip document.
+struct TooltipExtractor {
+    tooltips: Vec<Option<MathDocument>>, // `None` for a tooltip that cannot be loaded
+}
+
+impl DocumentVisitorMut for TooltipExtractor {
+    fn visit_segment_mut(&mut self, segment: &mut RichTextSegment) {
+        if let RichTextSegment::Link {
+            content,
+            target: LinkTarget::TooltipDocument(document),
+            ..
+        } = segment
+        {
+            let index = self.tooltips.len();
+            self.tooltips.push(None);
+            walk_segments_mut(self, content);
+            if let Ok(mut tooltip) = document.get().cloned() {
+                self.visit_document_mut(&mut tooltip);
+                *document = DocumentHandle::new(tooltip.without_sections());
+                self.tooltips[index] = Some(tooltip);
+            }
+            return;
+        }
+        walk_segment_mut(self, segment);
+    }
+}
+
+/// Puts the tooltip documents back, in the order `TooltipExtractor` took them out. A `None`
+/// entry leaves its link's tooltip as it is.
+struct TooltipRestorer<I> {
+    tooltips: I,
+}
+
+impl<I: Iterator<Item = Option<MathDocument>>> DocumentVisitorMut for TooltipRestorer<I> {
+    fn visit_segment_mut(&mut self, segment: &mut RichTextSegment) {
+        if let RichTextSegment::Link {
+            target: LinkTarget::TooltipDocument(document),
+            ..
+        } = segment
+            && let Some(Some(tooltip)) = self.tooltips.next()
+        {
+            *document = DocumentHandle::new(tooltip);
+        }
+        walk_segment_mut(self, segment);
+    }
+}
+
+fn to_json(value: &impl Serialize) -> Result<Vec<u8>, BundleError> {
+    serde_json::to_vec_pretty(value).map_err(|error| BundleError::Json {
+        entry: String::new(),
+        message: error.to_string(),
+    })
+}
+
+fn from_json<T: serde::de::DeserializeOwned>(entry: &str, bytes: &[u8]) -> Result<T, BundleError> {
+    serde_json::from_slice(bytes).map_err(|error| BundleError::Json {
+        entry: entry.to_string(),
+        message: error.to_string(),
+    })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BundleError {
+    Io(String),
+    Zip(ZipError),
+    MissingEntry {
+        name: String,
+    },
+    Json {
+        entry: String,
+        message: String,
+    },
+    UnsupportedVersion {
+        version: u32,
+    },
+    /// An image to bundle could not be read.
+    MissingImage {
+        src: String,
+    },
+    /// An image's `src` is absolute or leads out of the asset directory with `..`.
+    ImageOutsideAssetDir {
+        src: String,
+    },
+    /// The manifest lists more tooltip documents than the document has.
+    UnusedTooltips,
+}
+
+impl From<ZipError> for BundleError {
+    fn from(error: ZipError) -> Self {
+        BundleError::Zip(error)
+    }
+}
+
+impl fmt::Display for BundleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BundleError::Io(message) => write!(f, "{}", message),
+            BundleError::Zip(error) => write!(f, "{}", error),
+            BundleError::MissingEntry { name } => write!(f, "bundle has no entry `{}`", name),
+            BundleError::Json { entry, message } => {
+                write!(f, "bundle entry `{}` is invalid: {}", entry, message)
+            }
+            BundleError::UnsupportedVersion { version } => write!(
+                f,
+                "bundle format version {} is not the supported {}",
+                version, BUNDLE_FORMAT_VERSION
+            ),
+            BundleError::MissingImage { src } => write!(f, "image `{}` cannot be read", src),
+            BundleError::ImageOutsideAssetDir { src } => {
+                write!(f, "image `{}` is outside the asset directory", src)
+            }
+            BundleError::UnusedTooltips => {
+                write!(
+                    f,
+                    "bundle has more tooltip documents than the document uses"
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for BundleError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{Value, json};
+
+    fn notes(id: &str, segments: Vec<RichTextSegment>) -> MathDocument {
+        let section = json!({
+            "id": format!("{}-text", id),
+            "content": {
+                "kind": "RichText",
+                "data": { "segments": serde_json::to_value(segments).unwrap() }
+            }
+        });
+        serde_json::from_value(json!({
+            "id": id,
+            "content_type": {
+                "kind": "PersonalNotes",
+                "data": {
+                    "title": id,
+                    "author_level": "Expert",
+                    "note_style": "Formal",
+                    "content_metadata": {},
+                    "structure": { "body": [section] },
+                    "relationships": {}
+                }
+            }
+        }))
+        .unwrap()
+    }
+
+    fn text(text: &str) -> RichTextSegment {
+        RichTextSegment::Text(text.to_string())
+    }
+
+    fn tooltip_link(content: Vec<RichTextSegment>, tooltip: MathDocument) -> RichTextSegment {
+        RichTextSegment::Link {
+            content,
+            target: LinkTarget::TooltipDocument(DocumentHandle::new(tooltip)),
+            tooltip: None,
+        }
+    }
+
+    #[test]
+    fn nested_tooltips_round_trip() {
+        let inner = notes("inner", vec![text("in the tooltip")]);
+        let outer = notes("outer", vec![tooltip_link(vec![text("more")], inner)]);
+        let in_text = notes("in-text", vec![text("in the link text")]);
+        let document = notes(
+            "root",
+            vec![tooltip_link(
+                vec![text("see "), tooltip_link(vec![text("this")], in_text)],
+                outer,
+            )],
+        );
+        let bundle = DocumentBundle {
+            document: document.clone(),
+            images: BTreeMap::new(),
+        };
+        let restored = DocumentBundle::from_bytes(&bundle.to_bytes().unwrap()).unwrap();
+        let json = |document: &MathDocument| -> Value { serde_json::to_value(document).unwrap() };
+        assert_eq!(json(&restored.document), json(&document));
+    }
+}
@@ -0,0 +1,116 @@
+use super::*;
+use std::sync::Arc;
+
+impl MathNode {
+    /// `inner` struck out with `cancel_style`.
+    pub fn cancelled(
+        id: impl Into<String>,
+        inner: MathNode,
+        cancel_style: CancelStyle,
+    ) -> MathNode {
+        MathNode {
+            id: id.into(),
+            content: Arc::new(MathNodeContent::Cancelled {
+                inner: Arc::new(inner),
+                cancel_style,
+                replacement: None,
+            }),
+        }
+    }
+
+    /// `inner` struck out diagonally towards what it cancels to, `\cancelto{replacement}{inner}`.
+    pub fn cancelled_to(id: impl Into<String>, inner: MathNode, replacement: MathNode) -> MathNode {
+        MathNode {
+            id: id.into(),
+            content: Arc::new(MathNodeContent::Cancelled {
+                inner: Arc::new(inner),
+                cancel_style: CancelStyle::Diagonal,
+                replacement: Some(Arc::new(replacement)),
+            }),
+        }
+    }
+
+    /// The next step of a simplification that shows cancellations: cancelled nodes with a
+    /// replacement become it, cancelled terms of a sum and factors of a product are dropped,
+    /// leaving `0` or `1` if none is left, and a cancelled numerator or denominator becomes `1`.
+    /// Any other cancelled node is shown plainly.
+    pub fn apply_cancellations(&self) -> MathNode {
+        let dropped = |node: &MathNode| {
+            matches!(
+                node.content.as_ref(),
+                MathNodeContent::Cancelled {
+                    replacement: None,
+                    ..
+                }
+            )
+        };
+        let one_if_dropped = |node: &Arc<MathNode>| {
+            if dropped(node) {
+                Arc::new(number(node.id.clone(), 1))
+            } else {
+                Arc::clone(node)
+            }
+        };
+        let applied = self.transform(&mut |node: &MathNode| {
+            let content = match node.content.as_ref() {
+                MathNodeContent::Cancelled {
+                    replacement: Some(replacement),
+                    ..
+                } => return Some(replacement.as_ref().clone()),
+                MathNodeContent::Additions { terms }
+                    if terms.iter().any(|(_, term)| dropped(term)) =>
+                {
+                    let terms: Vec<_> = terms
+                        .iter()
+                        .filter(|(_, term)| !dropped(term))
+                        .cloned()
+                        .collect();
+                    if terms.is_empty() {
+                        return Some(number(node.id.clone(), 0));
+                    }
+                    MathNodeContent::Additions { terms }
+                }
+                MathNodeContent::Multiplications { terms }
+                    if terms.iter().any(|(_, term)| dropped(term)) =>
+                {
+                    let terms: Vec<_> = terms
+                        .iter()
+                        .filter(|(_, term)| !dropped(term))
+                        .cloned()
+                        .collect();
+                    if terms.is_empty() {
+                        return Some(number(node.id.clone(), 1));
+                    }
+                    MathNodeContent::Multiplications { terms }
+                }
+                MathNodeContent::Fraction {
+                    numerator,
+                    denominator,
+                } if dropped(numerator) || dropped(denominator) => MathNodeContent::Fraction {
+                    numerator: one_if_dropped(numerator),
+                    denominator: one_if_dropped(denominator),
+                },
+                _ => return None,
+            };
+            Some(MathNode {
+                id: node.id.clone(),
+                content: Arc::new(content),
+            })
+        });
+        applied.transform(&mut |node: &MathNode| match node.content.as_ref() {
+            MathNodeContent::Cancelled { inner, .. } => Some(inner.as_ref().clone()),
+            _ => None,
+        })
+    }
+}
+
+fn number(id: String, value: i64) -> MathNode {
+    MathNode {
+        id,
+        content: Arc::new(MathNodeContent::Quantity {
+            number: value.to_string(),
+            scientific_notation: None,
+            unit: None,
+        }),
+    }
+}
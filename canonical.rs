@@ -0,0 +1,272 @@
+use super::*;
+use std::sync::Arc;
+
+/// Rewrites a tree into a light canonical form, so that trivially different spellings of the
+/// same expression compare equal:
+///
+/// - ids are cleared, and brackets and folded placeholders are dropped
+/// - matrices lose their labels, highlights and augmentation bar, and their delimiters unless
+///   these are the bars of a determinant or a norm
+/// - `Division` becomes `Fraction`, legacy `Additions`/`Multiplications` become `BinaryOperation`
+/// - `·` becomes `×` and `÷` becomes `/`
+/// - double negations `-(-x)` and `¬¬x` become `x`
+/// - nested associative operations are flattened, and operands of commutative operations
+///   (and both sides of symmetric relations) are put in a fixed order
+/// - the elements of a set literal are put in a fixed order, without repeats
+///
+/// The result is meant for comparison and hashing, not for display.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Canonicalizer;
+
+impl MathNodeTransformer for Canonicalizer {
+    fn transform_node(&mut self, node: &MathNode) -> Option<MathNode> {
+        Some(canonicalize_shallow(node))
+    }
+}
+
+impl MathNode {
+    pub fn canonicalize(&self) -> MathNode {
+        self.unfold_all().transform(&mut Canonicalizer)
+    }
+
+    /// Whether both nodes have the same canonical form.
+    pub fn canonically_eq(&self, other: &MathNode) -> bool {
+        self.canonicalize() == other.canonicalize()
+    }
+}
+
+/// The node a presentation-only wrapper (brackets, a brace, styling, a cancellation or a
+/// folded placeholder) stands for, or `None` if `content` is not such a wrapper.
+/// Canonicalization replaces these wrappers by their inner node.
+pub fn transparent_inner(content: &MathNodeContent) -> Option<&Arc<MathNode>> {
+    match content {
+        MathNodeContent::Bracketed { inner, .. }
+        | MathNodeContent::Brace { inner, .. }
+        | MathNodeContent::Styled { inner, .. }
+        | MathNodeContent::Cancelled { inner, .. }
+        | MathNodeContent::Folded { content: inner, .. } => Some(inner),
+        _ => None,
+    }
+}
+
+/// Whether canonicalization replaces `content` by its inner node, see `transparent_inner`.
+pub fn is_transparent_wrapper(content: &MathNodeContent) -> bool {
+    transparent_inner(content).is_some()
+}
+
+/// Canonicalizes `node` itself, assuming its children are already canonical.
+pub fn canonicalize_shallow(node: &MathNode) -> MathNode {
+    if let Some(inner) = transparent_inner(&node.content) {
+        return MathNode {
+            id: String::new(),
+            content: Arc::clone(&inner.content),
+        };
+    }
+    let content = match node.content.as_ref() {
+        MathNodeContent::Matrix {
+            rows, delimiters, ..
+        } => MathNodeContent::Matrix {
+            rows: rows.clone(),
+            delimiters: delimiters.filter(|delimiters| {
+                matches!(
+                    delimiters,
+                    MatrixDelimiters::Bars | MatrixDelimiters::DoubleBars
+                )
+            }),
+            augmented_after: None,
+            row_labels: vec![],
+            column_labels: vec![],
+            highlights: vec![],
+        },
+        MathNodeContent::Division {
+            numerator,
+            denominator,
+            ..
+        } => MathNodeContent::Fraction {
+            numerator: Arc::clone(numerator),
+            denominator: Arc::clone(denominator),
+        },
+        MathNodeContent::Additions { terms } => canonical_binary_operation(
+            &BinaryOperationType::Addition,
+            terms.iter().map(|(operator, term)| {
+                let operator = match operator {
+                    RefinedAddOrSubOperator::Subtraction => BinaryOperator::Minus,
+                    RefinedAddOrSubOperator::Addition | RefinedAddOrSubOperator::None => {
+                        BinaryOperator::Plus
+                    }
+                };
+                (operator, term.clone())
+            }),
+        ),
+        MathNodeContent::Multiplications { terms } => canonical_binary_operation(
+            &BinaryOperationType::Multiplication,
+            terms.iter().map(|(operator, term)| {
+                let operator = match operator {
+                    RefinedMulOrDivOperation::Division(_) => BinaryOperator::Slash,
+                    RefinedMulOrDivOperation::Multiplication(_)
+                    | RefinedMulOrDivOperation::None => BinaryOperator::Times,
+                };
+                (operator, term.clone())
+            }),
+        ),
+        MathNodeContent::BinaryOperation {
+            operation_type,
+            terms,
+        } => canonical_binary_operation(operation_type, terms.iter().cloned()),
+        MathNodeContent::And(operands) => {
+            MathNodeContent::And(flatten_and_sort(operands, |c| match c {
+                MathNodeContent::And(inner) => Some(inner),
+                _ => None,
+            }))
+        }
+        MathNodeContent::Or(operands) => {
+            MathNodeContent::Or(flatten_and_sort(operands, |c| match c {
+                MathNodeContent::Or(inner) => Some(inner),
+                _ => None,
+            }))
+        }
+        MathNodeContent::UnaryPrefixOperation {
+            parameter,
+            operator,
+        } => match parameter.content.as_ref() {
+            MathNodeContent::UnaryPrefixOperation {
+                parameter: inner,
+                operator: inner_operator,
+            } if negation(operator).is_some() && negation(operator) == negation(inner_operator) => {
+                return inner.as_ref().clone();
+            }
+            _ => node.content.as_ref().clone(),
+        },
+        MathNodeContent::SetExplicit { elements } => {
+            let mut elements = elements.clone();
+            elements.sort_by_cached_key(sort_key);
+            elements.dedup();
+            MathNodeContent::SetExplicit { elements }
+        }
+        MathNodeContent::Not(operand) => match operand.content.as_ref() {
+            MathNodeContent::Not(inner) => return inner.as_ref().clone(),
+            _ => node.content.as_ref().clone(),
+        },
+        MathNodeContent::Relationship { lhs, rhs, operator }
+            if is_symmetric(operator) && sort_key(rhs) < sort_key(lhs) =>
+        {
+            MathNodeContent::Relationship {
+                lhs: Arc::clone(rhs),
+                rhs: Arc::clone(lhs),
+                operator: operator.clone(),
+            }
+        }
+        content => content.clone(),
+    };
+    MathNode {
+        id: String::new(),
+        content: Arc::new(content),
+    }
+}
+
+fn canonical_binary_operation(
+    operation_type: &BinaryOperationType,
+    terms: impl Iterator<Item = (BinaryOperator, MathNode)>,
+) -> MathNodeContent {
+    let normalize = |operator: BinaryOperator| match operator {
+        BinaryOperator::Dot => BinaryOperator::Times,
+        BinaryOperator::Divide => BinaryOperator::Slash,
+        operator => operator,
+    };
+    let terms: Vec<(BinaryOperator, MathNode)> = terms
+        .map(|(operator, term)| (normalize(operator), term))
+        .collect();
+
+    let uniform =
+        |terms: &[(BinaryOperator, MathNode)]| terms.windows(2).all(|pair| pair[0].0 == pair[1].0);
+    if !is_commutative(operation_type) || !uniform(&terms) {
+        return MathNodeContent::BinaryOperation {
+            operation_type: operation_type.clone(),
+            terms,
+        };
+    }
+
+    // Every operand is combined with the same operator, so nested operations of the same kind
+    // can be flattened and the operands reordered.
+    let operator = terms[0].0.clone();
+    let mut flat: Vec<MathNode> = vec![];
+    for (_, term) in terms {
+        match term.content.as_ref() {
+            MathNodeContent::BinaryOperation {
+                operation_type: inner_type,
+                terms: inner_terms,
+            } if inner_type == operation_type
+                && inner_terms.iter().all(|(inner, _)| *inner == operator) =>
+            {
+                flat.extend(inner_terms.iter().map(|(_, inner)| inner.clone()))
+            }
+            _ => flat.push(term),
+        }
+    }
+    flat.sort_by_cached_key(sort_key);
+    MathNodeContent::BinaryOperation {
+        operation_type: operation_type.clone(),
+        terms: flat
+            .into_iter()
+            .map(|term| (operator.clone(), term))
+            .collect(),
+    }
+}
+
+fn flatten_and_sort(
+    operands: &[MathNode],
+    same_kind: impl Fn(&MathNodeContent) -> Option<&Vec<MathNode>>,
+) -> Vec<MathNode> {
+    let mut flat: Vec<MathNode> = vec![];
+    for operand in operands {
+        match same_kind(&operand.content) {
+            Some(inner) => flat.extend(inner.iter().cloned()),
+            None => flat.push(operand.clone()),
+        }
+    }
+    flat.sort_by_cached_key(sort_key);
+    flat
+}
+
+fn is_commutative(operation_type: &BinaryOperationType) -> bool {
+    matches!(
+        operation_type,
+        BinaryOperationType::Addition
+            | BinaryOperationType::Multiplication
+            | BinaryOperationType::RingDirectSum
+            | BinaryOperationType::SetUnion
+            | BinaryOperationType::SetIntersection
+            | BinaryOperationType::LogicalAnd
+            | BinaryOperationType::LogicalOr
+            | BinaryOperationType::LogicalXor
+    )
+}
+
+fn is_symmetric(operator: &RelationOperatorNode) -> bool {
+    matches!(
+        operator,
+        RelationOperatorNode::Equal
+            | RelationOperatorNode::NotEqual
+            | RelationOperatorNode::Equivalent
+            | RelationOperatorNode::Similar
+            | RelationOperatorNode::Congruent
+            | RelationOperatorNode::Disjoint
+    )
+}
+
+/// The negation a prefix operator stands for, arithmetic `-` or logical `¬`.
+fn negation(operator: &MathNode) -> Option<char> {
+    match operator.content.as_ref() {
+        MathNodeContent::Text(symbol) | MathNodeContent::String(symbol) => match symbol.as_str() {
+            "-" | "−" => Some('-'),
+            "¬" => Some('¬'),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+// Canonical nodes carry no ids, so their debug output is a stable ordering key.
+fn sort_key(node: &MathNode) -> String {
+    format!("{:?}", node.content)
+}
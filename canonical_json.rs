@@ -0,0 +1,68 @@
+use super::*;
+use serde::Serialize;
+use serde_json::{Number, Value};
+
+/// The value's JSON in a canonical form, so that equal values give equal bytes across runs,
+/// platforms and Rust versions: no whitespace, object keys sorted, and numbers normalized so
+/// that `1.0` and `1` or `-0.0` and `0` are written the same. Maps are serialized from
+/// `BTreeMap`s throughout the document types, so this is only needed where the bytes matter,
+/// e.g. for hashing, signing or diffing serialized documents.
+pub fn to_canonical_json(value: &impl Serialize) -> Result<String, serde_json::Error> {
+    Ok(canonical_json(&serde_json::to_value(value)?))
+}
+
+impl MathDocument {
+    /// See `to_canonical_json`.
+    pub fn to_canonical_json(&self) -> Result<String, serde_json::Error> {
+        to_canonical_json(self)
+    }
+}
+
+/// `value` written in the form of `to_canonical_json`.
+pub fn canonical_json(value: &Value) -> String {
+    let mut out = String::new();
+    write_canonical(value, &mut out);
+    out
+}
+
+fn write_canonical(value: &Value, out: &mut String) {
+    match value {
+        Value::Array(items) => {
+            out.push('[');
+            for (index, item) in items.iter().enumerate() {
+                if index > 0 {
+                    out.push(',');
+                }
+                write_canonical(item, out);
+            }
+            out.push(']');
+        }
+        Value::Object(fields) => {
+            let mut fields: Vec<(&String, &Value)> = fields.iter().collect();
+            fields.sort_by_key(|(key, _)| *key);
+            out.push('{');
+            for (index, (key, field)) in fields.into_iter().enumerate() {
+                if index > 0 {
+                    out.push(',');
+                }
+                out.push_str(&Value::String(key.clone()).to_string());
+                out.push(':');
+                write_canonical(field, out);
+            }
+            out.push('}');
+        }
+        Value::Number(number) => out.push_str(&normalize_number(number).to_string()),
+        value => out.push_str(&value.to_string()),
+    }
+}
+
+/// Integral floats as integers, as long as the integer is exact.
+fn normalize_number(number: &Number) -> Number {
+    const MAX_EXACT: f64 = (1u64 << 53) as f64;
+    match number.as_f64() {
+        Some(float) if number.is_f64() && float.fract() == 0.0 && float.abs() <= MAX_EXACT => {
+            Number::from(float as i64)
+        }
+        _ => number.clone(),
+    }
+}
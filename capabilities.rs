@@ -0,0 +1,284 @@
+use super::*;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// The interactive features a document relies on, computed from its content.
+/// Host applications compare it against what they support before embedding the document,
+/// and degrade the listed features instead of failing on unknown nodes.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct CapabilityManifest {
+    pub document_types: Vec<String>, // the document itself plus every embedded/tooltip document type
+    pub interaction_features: Vec<InteractionFeature>,
+    pub animation_types: Vec<String>, // AnimationType variant names, e.g. "FadeIn", "Morph"
+    pub visualization_types: Vec<VisualizationType>,
+    pub custom_components: Vec<String>, // CustomComponent::component_name values
+    pub diagram_types: Vec<String>,     // InteractiveDiagramNode::diagram_type_id values
+}
+
+impl CapabilityManifest {
+    pub fn for_document(document: &MathDocument) -> CapabilityManifest {
+        let mut manifest = CapabilityManifest::default();
+        manifest.visit_document(document);
+        manifest
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.document_types.is_empty()
+            && self.interaction_features.is_empty()
+            && self.animation_types.is_empty()
+            && self.visualization_types.is_empty()
+            && self.custom_components.is_empty()
+            && self.diagram_types.is_empty()
+    }
+
+    /// Everything this manifest requires that `host` does not declare.
+    /// An empty result means the document can be embedded without degradation.
+    pub fn unsupported_by(&self, host: &CapabilityManifest) -> CapabilityManifest {
+        fn missing<T: Clone + PartialEq>(required: &[T], supported: &[T]) -> Vec<T> {
+            required
+                .iter()
+                .filter(|item| !supported.contains(item))
+                .cloned()
+                .collect()
+        }
+
+        CapabilityManifest {
+            document_types: missing(&self.document_types, &host.document_types),
+            interaction_features: missing(&self.interaction_features, &host.interaction_features),
+            animation_types: missing(&self.animation_types, &host.animation_types),
+            visualization_types: missing(&self.visualization_types, &host.visualization_types),
+            custom_components: missing(&self.custom_components, &host.custom_components),
+            diagram_types: missing(&self.diagram_types, &host.diagram_types),
+        }
+    }
+
+    pub fn is_supported_by(&self, host: &CapabilityManifest) -> bool {
+        self.unsupported_by(host).is_empty()
+    }
+
+    /// Adds everything required by `other`.
+    pub fn merge(&mut self, other: &CapabilityManifest) {
+        for item in &other.document_types {
+            push_unique(&mut self.document_types, item.clone());
+        }
+        for item in &other.interaction_features {
+            push_unique(&mut self.interaction_features, item.clone());
+        }
+        for item in &other.animation_types {
+            push_unique(&mut self.animation_types, item.clone());
+        }
+        for item in &other.visualization_types {
+            push_unique(&mut self.visualization_types, item.clone());
+        }
+        for item in &other.custom_components {
+            push_unique(&mut self.custom_components, item.clone());
+        }
+        for item in &other.diagram_types {
+            push_unique(&mut self.diagram_types, item.clone());
+        }
+    }
+
+    fn feature(&mut self, feature: InteractionFeature) {
+        push_unique(&mut self.interaction_features, feature);
+    }
+
+    fn animation(&mut self, animation_type: &AnimationType) {
+        self.feature(InteractionFeature::Animations);
+        push_unique(
+            &mut self.animation_types,
+            animation_type_name(animation_type).to_string(),
+        );
+    }
+
+    fn visualization(&mut self, visualization_type: &VisualizationType) {
+        push_unique(&mut self.visualization_types, visualization_type.clone());
+    }
+
+    fn timeline(&mut self, timeline: &AnimationTimeline) {
+        for keyframe in &timeline.keyframes {
+            self.animation(&keyframe.animation_type);
+        }
+        if !timeline.interaction_points.is_empty() {
+            self.feature(InteractionFeature::InteractiveControls);
+        }
+    }
+}
+
+impl<'doc> DocumentVisitor<'doc> for CapabilityManifest {
+    fn visit_document(&mut self, document: &'doc MathDocument) {
+        let content_type = &document.content_type;
+        push_unique(
+            &mut self.document_types,
+            content_type.type_name().to_string(),
+        );
+
+        if let Some(config) = content_type.presentation_config() {
+            for feature in &config.interaction_features {
+                self.feature(feature.clone());
+            }
+            let animates = config
+                .animation_config
+                .as_ref()
+                .is_some_and(|animation_config| !animation_config.enabled_animations.is_empty());
+            if animates {
+                self.feature(InteractionFeature::Animations);
+            }
+        }
+
+        match content_type {
+            MathDocumentType::AnimatedPresentation(c) => {
+                self.feature(InteractionFeature::Animations);
+                self.timeline(&c.animation_timeline);
+            }
+            MathDocumentType::InteractivePlayground(c) => {
+                self.feature(InteractionFeature::InteractiveControls);
+                self.feature(InteractionFeature::ParameterAdjustment);
+                for visualization_type in &c.visualization_types {
+                    self.visualization(visualization_type);
+                }
+                if let Some(timeline) = &c.animation_timeline {
+                    self.timeline(timeline);
+                }
+            }
+            MathDocumentType::TypeMappingDisplay(c) => {
+                self.feature(InteractionFeature::TypeAnnotations);
+                for mapping in &c.mapping_visualizations {
+                    self.visualization(&mapping.visualization_type);
+                }
+                if let Some(timeline) = &c.animation_timeline {
+                    self.timeline(timeline);
+                }
+            }
+            MathDocumentType::ComparisonPage(c) if c.highlight_differences => {
+                self.feature(InteractionFeature::HighlightCorrespondence);
+            }
+            MathDocumentType::ConceptAlignment(c) => {
+                self.feature(InteractionFeature::HighlightCorrespondence);
+                for alignment in &c.alignment_visualizations {
+                    self.visualization(&alignment.visualization_type);
+                }
+            }
+            _ => {}
+        }
+
+        walk_document(self, document);
+    }
+
+    fn visit_content_node(&mut self, node: &'doc SectionContentNode) {
+        match node {
+            SectionContentNode::SecondOrderMath(SecondOrderMathNode::InteractiveProof(proof)) => {
+                self.feature(InteractionFeature::ExpandableProofs);
+                if proof.visual_config.animation_enabled {
+                    self.feature(InteractionFeature::Animations);
+                }
+                if proof.visual_config.show_interactive_elements {
+                    self.feature(InteractionFeature::HighlightCorrespondence);
+                }
+            }
+            SectionContentNode::InteractiveDiagram(diagram) => {
+                push_unique(&mut self.diagram_types, diagram.diagram_type_id.clone());
+            }
+            SectionContentNode::CollapsibleBlock(_) | SectionContentNode::BranchingContainer(_) => {
+                self.feature(InteractionFeature::ExpandableProofs);
+            }
+            SectionContentNode::CustomComponent { component_name, .. } => {
+                push_unique(&mut self.custom_components, component_name.clone());
+            }
+            SectionContentNode::SideBySideLayout(layout)
+                if layout.highlight_correspondence == Some(true) =>
+            {
+                self.feature(InteractionFeature::HighlightCorrespondence);
+            }
+            SectionContentNode::AnnotationOverlay(overlay) => {
+                match overlay.overlay_style {
+                    OverlayStyle::Tooltip | OverlayStyle::Popover => {
+                        self.feature(InteractionFeature::HoverTooltips)
+                    }
+                    OverlayStyle::Highlight => {
+                        self.feature(InteractionFeature::HighlightCorrespondence)
+                    }
+                    OverlayStyle::Inline | OverlayStyle::Sidebar => {}
+                }
+                for annotation in &overlay.annotations {
+                    match annotation.annotation_type {
+                        AnnotationType::TypeInfo => {
+                            self.feature(InteractionFeature::TypeAnnotations)
+                        }
+                        AnnotationType::Animation => self.feature(InteractionFeature::Animations),
+                        _ => {}
+                    }
+                }
+            }
+            SectionContentNode::InteractiveControls(controls) => {
+                self.feature(InteractionFeature::InteractiveControls);
+                let adjusts_parameters = controls.controls.iter().any(|control| {
+                    matches!(
+                        control.control_type,
+                        section_node::ControlType::Slider { .. }
+                            | section_node::ControlType::NumberInput { .. }
+                    )
+                });
+                if adjusts_parameters {
+                    self.feature(InteractionFeature::ParameterAdjustment);
+                }
+            }
+            SectionContentNode::Feedback(_) => self.feature(InteractionFeature::FeedbackCollection),
+            _ => {}
+        }
+        walk_content_node(self, node);
+    }
+
+    fn visit_segment(&mut self, segment: &'doc RichTextSegment) {
+        match segment {
+            RichTextSegment::Link {
+                target, tooltip, ..
+            } => {
+                self.feature(InteractionFeature::ClickableLinks);
+                if tooltip.is_some() {
+                    self.feature(InteractionFeature::HoverTooltips);
+                }
+                match target {
+                    LinkTarget::TooltipDocument(_) => {
+                        self.feature(InteractionFeature::HoverTooltips)
+                    }
+                    LinkTarget::AnimationTrigger { .. } => {
+                        self.feature(InteractionFeature::Animations)
+                    }
+                    _ => {}
+                }
+            }
+            RichTextSegment::InteractiveVariable { .. } => {
+                self.feature(InteractionFeature::HoverTooltips)
+            }
+            _ => {}
+        }
+        walk_segment(self, segment);
+    }
+}
+
+impl MathDocument {
+    pub fn capability_manifest(&self) -> CapabilityManifest {
+        CapabilityManifest::for_document(self)
+    }
+}
+
+/// Variant name of an `AnimationType`, as listed in `CapabilityManifest::animation_types`.
+pub fn animation_type_name(animation_type: &AnimationType) -> &'static str {
+    match animation_type {
+        AnimationType::FadeIn => "FadeIn",
+        AnimationType::FadeOut => "FadeOut",
+        AnimationType::SlideIn { .. } => "SlideIn",
+        AnimationType::Highlight { .. } => "Highlight",
+        AnimationType::Morph { .. } => "Morph",
+        AnimationType::TypewriteText => "TypewriteText",
+        AnimationType::CountUp { .. } => "CountUp",
+        AnimationType::Transform { .. } => "Transform",
+    }
+}
+
+fn push_unique<T: PartialEq>(items: &mut Vec<T>, item: T) {
+    if !items.contains(&item) {
+        items.push(item);
+    }
+}
@@ -0,0 +1,568 @@
+use super::*;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fmt, str::FromStr};
+use ts_rs::TS;
+
+/// A chemical formula or reaction as written with mhchem's `\ce{...}`: species with their
+/// coefficients, charges and states, joined by `+` and by reaction arrows with optional
+/// conditions. Parses from the same syntax, `"2H2 + O2 -> 2H2O"`, `"Fe^3+ + e- <=> Fe^2+"`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ChemicalExpression {
+    pub terms: Vec<ChemicalTerm>, // The first side of a reaction, or a lone formula
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub steps: Vec<ReactionStep>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ReactionStep {
+    pub arrow: ReactionArrow,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub above: Option<String>, // Conditions over the arrow, e.g. "Δ" or "Pt"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub below: Option<String>,
+    pub terms: Vec<ChemicalTerm>, // The side after the arrow
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub enum ReactionArrow {
+    Forward,     // ->
+    Backward,    // <-
+    Reversible,  // <->
+    Equilibrium, // <=>
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ChemicalTerm {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub coefficient: Option<String>, // "2" or "1/2"
+    pub species: ChemicalSpecies,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ChemicalSpecies {
+    pub parts: Vec<FormulaPart>,
+    #[serde(default)]
+    pub charge: i32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state: Option<PhysicalState>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub enum FormulaPart {
+    Element {
+        symbol: String, // "e" for an electron
+        count: u32,
+    },
+    Group {
+        parts: Vec<FormulaPart>,
+        bracket: BracketStyle, // Round for (OH)₂, Square for [Fe(CN)₆]
+        count: u32,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub enum PhysicalState {
+    Solid,   // (s)
+    Liquid,  // (l)
+    Gas,     // (g)
+    Aqueous, // (aq)
+}
+
+impl ChemicalExpression {
+    /// Every side of the reaction in order, the first side first; just the formula's for a lone
+    /// formula.
+    pub fn sides(&self) -> impl Iterator<Item = &[ChemicalTerm]> {
+        std::iter::once(self.terms.as_slice()).chain(self.steps.iter().map(|step| &*step.terms))
+    }
+
+    /// Whether every side has the same atoms and the same total charge, counting coefficients.
+    /// False when a coefficient is not a number, such as `n`.
+    pub fn is_balanced(&self) -> bool {
+        let totals: Option<Vec<_>> = self.sides().map(side_totals).collect();
+        let Some(totals) = totals else {
+            return false;
+        };
+        let close = |a: f64, b: f64| (a - b).abs() < 1e-9;
+        totals.windows(2).all(|pair| {
+            let ((atoms, charge), (other_atoms, other_charge)) = (&pair[0], &pair[1]);
+            let count =
+                |atoms: &BTreeMap<String, f64>, symbol| atoms.get(symbol).map_or(0.0, |c| *c);
+            close(*charge, *other_charge)
+                && atoms
+                    .keys()
+                    .chain(other_atoms.keys())
+                    .all(|symbol| close(count(atoms, symbol), count(other_atoms, symbol)))
+        })
+    }
+
+    /// The expression in mhchem syntax, wrapped in `\ce{...}`.
+    pub fn to_mhchem(&self) -> String {
+        let mut source = String::new();
+        write_side(&mut source, &self.terms, Notation::Mhchem);
+        for step in &self.steps {
+            source.push(' ');
+            source.push_str(match step.arrow {
+                ReactionArrow::Forward => "->",
+                ReactionArrow::Backward => "<-",
+                ReactionArrow::Reversible => "<->",
+                ReactionArrow::Equilibrium => "<=>",
+            });
+            write_conditions(&mut source, step);
+            source.push(' ');
+            write_side(&mut source, &step.terms, Notation::Mhchem);
+        }
+        format!("\\ce{{{}}}", source)
+    }
+}
+
+impl ChemicalSpecies {
+    /// The number of atoms of each element in one unit of the species; electrons are not
+    /// counted.
+    pub fn element_counts(&self) -> BTreeMap<String, u32> {
+        let mut counts = BTreeMap::new();
+        count_atoms(&self.parts, 1, &mut counts);
+        counts
+    }
+}
+
+fn count_atoms(parts: &[FormulaPart], multiplier: u32, counts: &mut BTreeMap<String, u32>) {
+    for part in parts {
+        match part {
+            FormulaPart::Element { symbol, .. } if symbol == "e" => {}
+            FormulaPart::Element { symbol, count } => {
+                *counts.entry(symbol.clone()).or_default() += count * multiplier;
+            }
+            FormulaPart::Group { parts, count, .. } => {
+                count_atoms(parts, count * multiplier, counts)
+            }
+        }
+    }
+}
+
+/// The atoms and total charge on one side of a reaction, `None` if a coefficient is not a
+/// number.
+fn side_totals(terms: &[ChemicalTerm]) -> Option<(BTreeMap<String, f64>, f64)> {
+    let mut atoms = BTreeMap::new();
+    let mut charge = 0.0;
+    for term in terms {
+        let coefficient = match &term.coefficient {
+            None => 1.0,
+            Some(coefficient) => match coefficient.split_once('/') {
+                Some((numerator, denominator)) => {
+                    numerator.trim().parse::<f64>().ok()?
+                        / denominator.trim().parse::<f64>().ok()?
+                }
+                None => coefficient.trim().parse().ok()?,
+            },
+        };
+        for (symbol, count) in term.species.element_counts() {
+            *atoms.entry(symbol).or_default() += coefficient * count as f64;
+        }
+        charge += coefficient * term.species.charge as f64;
+    }
+    Some((atoms, charge))
+}
+
+#[derive(Clone, Copy)]
+enum Notation {
+    Unicode,
+    Mhchem,
+}
+
+fn write_side(out: &mut String, terms: &[ChemicalTerm], notation: Notation) {
+    for (i, term) in terms.iter().enumerate() {
+        if i > 0 {
+            out.push_str(" + ");
+        }
+        if let Some(coefficient) = &term.coefficient {
+            out.push_str(coefficient);
+        }
+        write_parts(out, &term.species.parts, notation);
+        let charge = term.species.charge;
+        if charge != 0 {
+            let sign = if charge > 0 { '+' } else { '-' };
+            let magnitude = charge.unsigned_abs();
+            match notation {
+                Notation::Unicode => {
+                    if magnitude > 1 {
+                        out.extend(magnitude.to_string().chars().map(superscript));
+                    }
+                    out.push(superscript(sign));
+                }
+                Notation::Mhchem if magnitude > 1 => {
+                    out.push_str(&format!("^{{{}{}}}", magnitude, sign))
+                }
+                Notation::Mhchem => out.push_str(&format!("^{}", sign)),
+            }
+        }
+        if let Some(state) = term.species.state {
+            out.push_str(match state {
+                PhysicalState::Solid => "(s)",
+                PhysicalState::Liquid => "(l)",
+                PhysicalState::Gas => "(g)",
+                PhysicalState::Aqueous => "(aq)",
+            });
+        }
+    }
+}
+
+fn write_parts(out: &mut String, parts: &[FormulaPart], notation: Notation) {
+    let write_count = |out: &mut String, count: u32| {
+        if count == 1 {
+            return;
+        }
+        match notation {
+            Notation::Unicode => out.extend(count.to_string().chars().map(subscript)),
+            Notation::Mhchem => out.push_str(&count.to_string()),
+        }
+    };
+    for part in parts {
+        match part {
+            FormulaPart::Element { symbol, count } => {
+                out.push_str(symbol);
+                write_count(out, *count);
+            }
+            FormulaPart::Group {
+                parts,
+                bracket,
+                count,
+            } => {
+                let (open, close) = match bracket {
+                    BracketStyle::Square => ("[", "]"),
+                    _ => ("(", ")"),
+                };
+                out.push_str(open);
+                write_parts(out, parts, notation);
+                out.push_str(close);
+                write_count(out, *count);
+            }
+        }
+    }
+}
+
+fn write_conditions(out: &mut String, step: &ReactionStep) {
+    if step.above.is_none() && step.below.is_none() {
+        return;
+    }
+    out.push_str(&format!("[{}]", step.above.as_deref().unwrap_or("")));
+    if let Some(below) = &step.below {
+        out.push_str(&format!("[{}]", below));
+    }
+}
+
+fn subscript(digit: char) -> char {
+    match digit {
+        '0'..='9' => char::from_u32('₀' as u32 + (digit as u32 - '0' as u32)).unwrap_or(digit),
+        _ => digit,
+    }
+}
+
+fn superscript(c: char) -> char {
+    match c {
+        '1' => '¹',
+        '2' => '²',
+        '3' => '³',
+        '0' | '4'..='9' => char::from_u32('⁰' as u32 + (c as u32 - '0' as u32)).unwrap_or(c),
+        '+' => '⁺',
+        '-' => '⁻',
+        _ => c,
+    }
+}
+
+/// With Unicode subscripts, superscripts and arrows, `2H₂ + O₂ → 2H₂O`, conditions in brackets
+/// after the arrow as in mhchem.
+impl fmt::Display for ChemicalExpression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut text = String::new();
+        write_side(&mut text, &self.terms, Notation::Unicode);
+        for step in &self.steps {
+            text.push(' ');
+            text.push(match step.arrow {
+                ReactionArrow::Forward => '→',
+                ReactionArrow::Backward => '←',
+                ReactionArrow::Reversible => '↔',
+                ReactionArrow::Equilibrium => '⇌',
+            });
+            write_conditions(&mut text, step);
+            text.push(' ');
+            write_side(&mut text, &step.terms, Notation::Unicode);
+        }
+        f.write_str(&text)
+    }
+}
+
+impl FromStr for ChemicalExpression {
+    type Err = ChemicalParseError;
+
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        let mut parser = ChemicalParser {
+            chars: source.char_indices().peekable(),
+        };
+        parser.skip_whitespace();
+        if parser.chars.peek().is_none() {
+            return Err(ChemicalParseError::EmptyExpression);
+        }
+        let terms = parser.side()?;
+        let mut steps = vec![];
+        while let Some(arrow) = parser.arrow() {
+            let above = parser.condition()?;
+            let below = match above {
+                Some(_) => parser.condition()?,
+                None => None,
+            };
+            steps.push(ReactionStep {
+                arrow,
+                above: above.filter(|above| !above.is_empty()),
+                below,
+                terms: parser.side()?,
+            });
+        }
+        if parser.chars.peek().is_some() {
+            return Err(parser.unexpected("` + ` or a reaction arrow"));
+        }
+        Ok(ChemicalExpression { terms, steps })
+    }
+}
+
+struct ChemicalParser<'a> {
+    chars: std::iter::Peekable<std::str::CharIndices<'a>>,
+}
+
+impl ChemicalParser<'_> {
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+    }
+
+    fn unexpected(&mut self, expected: &'static str) -> ChemicalParseError {
+        match self.chars.peek() {
+            Some(&(position, found)) => ChemicalParseError::UnexpectedChar {
+                position,
+                found,
+                expected,
+            },
+            None => ChemicalParseError::UnexpectedEnd { expected },
+        }
+    }
+
+    fn next_is(&mut self, c: char) -> bool {
+        self.chars.next_if(|&(_, found)| found == c).is_some()
+    }
+
+    /// Whether the rest of the input starts with `prefix`, without consuming anything.
+    fn looking_at(&self, prefix: &str) -> bool {
+        let mut chars = self.chars.clone().map(|(_, c)| c);
+        prefix.chars().all(|c| chars.next() == Some(c))
+    }
+
+    fn number(&mut self) -> String {
+        let mut number = String::new();
+        while let Some((_, c)) = self.chars.next_if(|(_, c)| c.is_ascii_digit()) {
+            number.push(c);
+        }
+        number
+    }
+
+    /// Terms separated by ` + `, up to an arrow or the end.
+    fn side(&mut self) -> Result<Vec<ChemicalTerm>, ChemicalParseError> {
+        let mut terms = vec![];
+        loop {
+            self.skip_whitespace();
+            terms.push(self.term()?);
+            self.skip_whitespace();
+            if !self.next_is('+') {
+                return Ok(terms);
+            }
+        }
+    }
+
+    fn term(&mut self) -> Result<ChemicalTerm, ChemicalParseError> {
+        let mut coefficient = self.number();
+        if !coefficient.is_empty() && self.next_is('/') {
+            let denominator = self.number();
+            if denominator.is_empty() {
+                return Err(self.unexpected("a denominator"));
+            }
+            coefficient = format!("{}/{}", coefficient, denominator);
+        }
+        self.skip_whitespace();
+        let parts = self.parts()?;
+        if parts.is_empty() {
+            return Err(self.unexpected("an element symbol"));
+        }
+        let charge = self.charge()?;
+        let state = self.state();
+        Ok(ChemicalTerm {
+            coefficient: Some(coefficient).filter(|coefficient| !coefficient.is_empty()),
+            species: ChemicalSpecies {
+                parts,
+                charge,
+                state,
+            },
+        })
+    }
+
+    fn parts(&mut self) -> Result<Vec<FormulaPart>, ChemicalParseError> {
+        let mut parts = vec![];
+        loop {
+            let bracket = match self.chars.peek().map(|&(_, c)| c) {
+                Some('(') if self.state_ahead() => return Ok(parts),
+                Some('(') => Some((BracketStyle::Round, ')')),
+                Some('[') => Some((BracketStyle::Square, ']')),
+                Some(c) if c.is_ascii_uppercase() => None,
+                Some('e') if parts.is_empty() => None,
+                _ => return Ok(parts),
+            };
+            let part = match bracket {
+                Some((bracket, close)) => {
+                    self.chars.next();
+                    let inner = self.parts()?;
+                    if inner.is_empty() || !self.next_is(close) {
+                        return Err(self.unexpected("an element symbol or a closing bracket"));
+                    }
+                    FormulaPart::Group {
+                        parts: inner,
+                        bracket,
+                        count: self.count(),
+                    }
+                }
+                None => {
+                    let mut symbol = String::new();
+                    if let Some((_, c)) = self.chars.next() {
+                        symbol.push(c);
+                    }
+                    if symbol != "e" {
+                        while let Some((_, c)) = self.chars.next_if(|(_, c)| c.is_ascii_lowercase())
+                        {
+                            symbol.push(c);
+                        }
+                    }
+                    FormulaPart::Element {
+                        symbol,
+                        count: self.count(),
+                    }
+                }
+            };
+            parts.push(part);
+        }
+    }
+
+    fn count(&mut self) -> u32 {
+        self.number().parse().unwrap_or(1)
+    }
+
+    /// A charge written `^2-`, `^{2-}`, `^+`, or a bare sign right after the formula, as in
+    /// `Na+`.
+    fn charge(&mut self) -> Result<i32, ChemicalParseError> {
+        if !self.next_is('^') {
+            return Ok(match self.chars.next_if(|&(_, c)| c == '+' || c == '-') {
+                Some((_, '+')) => 1,
+                Some(_) => -1,
+                None => 0,
+            });
+        }
+        let braced = self.next_is('{');
+        let magnitude = self.number().parse().unwrap_or(1);
+        let sign = match self.chars.next_if(|&(_, c)| c == '+' || c == '-') {
+            Some((_, '+')) => 1,
+            Some(_) => -1,
+            None => return Err(self.unexpected("`+` or `-`")),
+        };
+        if braced && !self.next_is('}') {
+            return Err(self.unexpected("`}`"));
+        }
+        Ok(sign * magnitude)
+    }
+
+    fn state_ahead(&self) -> bool {
+        ["(s)", "(l)", "(g)", "(aq)"]
+            .iter()
+            .any(|state| self.looking_at(state))
+    }
+
+    fn state(&mut self) -> Option<PhysicalState> {
+        let state = [
+            ("(s)", PhysicalState::Solid),
+            ("(l)", PhysicalState::Liquid),
+            ("(g)", PhysicalState::Gas),
+            ("(aq)", PhysicalState::Aqueous),
+        ]
+        .into_iter()
+        .find(|(written, _)| self.looking_at(written))?;
+        for _ in state.0.chars() {
+            self.chars.next();
+        }
+        Some(state.1)
+    }
+
+    fn arrow(&mut self) -> Option<ReactionArrow> {
+        let arrow = [
+            ("<=>", ReactionArrow::Equilibrium),
+            ("<->", ReactionArrow::Reversible),
+            ("<-", ReactionArrow::Backward),
+            ("->", ReactionArrow::Forward),
+        ]
+        .into_iter()
+        .find(|(written, _)| self.looking_at(written))?;
+        for _ in arrow.0.chars() {
+            self.chars.next();
+        }
+        Some(arrow.1)
+    }
+
+    /// Bracketed conditions right after an arrow, `[Δ]`.
+    fn condition(&mut self) -> Result<Option<String>, ChemicalParseError> {
+        if !self.next_is('[') {
+            return Ok(None);
+        }
+        let mut condition = String::new();
+        loop {
+            match self.chars.next() {
+                Some((_, ']')) => return Ok(Some(condition.trim().to_string())),
+                Some((_, c)) => condition.push(c),
+                None => return Err(ChemicalParseError::UnexpectedEnd { expected: "`]`" }),
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChemicalParseError {
+    EmptyExpression,
+    UnexpectedEnd {
+        expected: &'static str,
+    },
+    UnexpectedChar {
+        position: usize,
+        found: char,
+        expected: &'static str,
+    },
+}
+
+impl fmt::Display for ChemicalParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChemicalParseError::EmptyExpression => write!(f, "chemical expression is empty"),
+            ChemicalParseError::UnexpectedEnd { expected } => {
+                write!(f, "chemical expression ended early, expected {}", expected)
+            }
+            ChemicalParseError::UnexpectedChar {
+                position,
+                found,
+                expected,
+            } => write!(
+                f,
+                "unexpected `{}` at offset {} in chemical expression, expected {}",
+                found, position, expected
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ChemicalParseError {}
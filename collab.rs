@@ -0,0 +1,645 @@
+use super::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use ts_rs::TS;
+
+/// Orders operations: a Lamport clock, with the replica breaking ties between concurrent
+/// operations the same way on every replica.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct Timestamp {
+    pub counter: u32,
+    pub replica: String,
+}
+
+impl Timestamp {
+    /// The timestamp of the state every replica starts from in `CollabDocument::new`.
+    pub fn origin() -> Self {
+        Timestamp {
+            counter: 0,
+            replica: String::new(),
+        }
+    }
+}
+
+/// A change to a `CollabDocument`, as sent between replicas. Operations commute and can be
+/// applied more than once, so replicas that have applied the same operations, in any order,
+/// hold the same document.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub enum CollabOperation {
+    /// The document's data outside its sections.
+    SetDocument {
+        stamp: Timestamp,
+        document: Box<MathDocument>,
+    },
+    /// Puts the section into `parent`, ordered among its siblings by `position`.
+    PlaceSection {
+        stamp: Timestamp,
+        section_id: String,
+        parent: SectionParent,
+        position: Vec<u32>,
+    },
+    /// The section's title, metadata and content, without the sections nested in it.
+    SetSection {
+        stamp: Timestamp,
+        section: Box<Section>,
+    },
+    /// Hides the section until it is placed again with a later timestamp.
+    RemoveSection {
+        stamp: Timestamp,
+        section_id: String,
+    },
+}
+
+impl CollabOperation {
+    pub fn stamp(&self) -> &Timestamp {
+        match self {
+            CollabOperation::SetDocument { stamp, .. }
+            | CollabOperation::PlaceSection { stamp, .. }
+            | CollabOperation::SetSection { stamp, .. }
+            | CollabOperation::RemoveSection { stamp, .. } => stamp,
+        }
+    }
+}
+
+/// A document several authors edit concurrently, as a CRDT: the document's data, and each
+/// section's place, own data and removal, are last-writer-wins registers, and sections in a
+/// list are ordered by dense position keys. Authors edit plain `MathDocument`s; `edit` turns
+/// the differences into operations to send to the other replicas, which `apply` them.
+///
+/// Concurrent edits to the same section keep the later one whole. Sections whose parent was
+/// removed or that ended up inside each other through concurrent moves are kept, at the end of
+/// the body (or base content).
+#[derive(Debug, Clone)]
+pub struct CollabDocument {
+    replica: String,
+    clock: u32,
+    document: Register<MathDocument>,
+    sections: BTreeMap<String, SectionRegisters>,
+}
+
+#[derive(Debug, Clone)]
+struct Register<T> {
+    stamp: Timestamp,
+    value: T,
+}
+
+#[derive(Debug, Clone, Default)]
+struct SectionRegisters {
+    placement: Option<Register<(SectionParent, Vec<u32>)>>,
+    content: Option<Register<Section>>,
+    removed: Option<Timestamp>,
+}
+
+impl SectionRegisters {
+    fn is_visible(&self) -> bool {
+        match (&self.placement, &self.content) {
+            (Some(placement), Some(_)) => self
+                .removed
+                .as_ref()
+                .is_none_or(|removed| *removed < placement.stamp),
+            _ => false,
+        }
+    }
+}
+
+/// Sets the register if `stamp` is later than its value's.
+fn set_register<T>(register: &mut Option<Register<T>>, stamp: &Timestamp, value: T) {
+    if register
+        .as_ref()
+        .is_none_or(|current| current.stamp < *stamp)
+    {
+        *register = Some(Register {
+            stamp: stamp.clone(),
+            value,
+        });
+    }
+}
+
+impl CollabDocument {
+    /// The replica `replica` of `document`. Replicas that are to converge start from the same
+    /// document, whose contents all carry `Timestamp::origin`.
+    pub fn new(replica: impl Into<String>, document: &MathDocument) -> Self {
+        let origin = Timestamp::origin();
+        let mut collab = CollabDocument {
+            replica: replica.into(),
+            clock: 0,
+            document: Register {
+                stamp: origin.clone(),
+                value: document.without_sections(),
+            },
+            sections: BTreeMap::new(),
+        };
+        let content_type = &document.content_type;
+        let mut lists = vec![];
+        if let Some(structure) = content_type.structure() {
+            let abstract_content: Vec<&Section> = structure.abstract_content.iter().collect();
+            lists.push((SectionParent::Abstract, abstract_content));
+            lists.push((SectionParent::Body, structure.body.iter().collect()));
+            lists.push((
+                SectionParent::Footnotes,
+                structure.footnotes.iter().collect(),
+            ));
+            lists.push((SectionParent::Glossary, structure.glossary.iter().collect()));
+        } else if let Some(base_content) = content_type.base_content() {
+            lists.push((SectionParent::BaseContent, base_content.iter().collect()));
+        }
+        for (parent, sections) in lists {
+            collab.add_origin_list(&origin, parent, sections);
+        }
+        collab
+    }
+
+    fn add_origin_list(&mut self, origin: &Timestamp, parent: SectionParent, list: Vec<&Section>) {
+        for (index, section) in list.into_iter().enumerate() {
+            let registers = self.sections.entry(section.id.clone()).or_default();
+            let position = vec![index as u32 + 1];
+            set_register(&mut registers.placement, origin, (parent.clone(), position));
+            set_register(
+                &mut registers.content,
+                origin,
+                section.without_subsections(),
+            );
+            for (list, sections) in section.subsection_lists().into_iter().enumerate() {
+                let parent = SectionParent::Section {
+                    section_id: section.id.clone(),
+                    list,
+                };
+                self.add_origin_list(origin, parent, sections.iter().collect());
+            }
+        }
+    }
+
+    pub fn replica(&self) -> &str {
+        &self.replica
+    }
+
+    /// Applies an operation from this or another replica. Older values than the ones held are
+    /// ignored, so operations can arrive in any order and more than once.
+    pub fn apply(&mut self, operation: &CollabOperation) {
+        let stamp = operation.stamp();
+        self.clock = self.clock.max(stamp.counter);
+        match operation {
+            CollabOperation::SetDocument { document, .. } => {
+                if self.document.stamp < *stamp {
+                    self.document = Register {
+                        stamp: stamp.clone(),
+                        value: (**document).clone(),
+                    };
+                }
+            }
+            CollabOperation::PlaceSection {
+                section_id,
+                parent,
+                position,
+                ..
+            } => {
+                let registers = self.sections.entry(section_id.clone()).or_default();
+                let placement = (parent.clone(), position.clone());
+                set_register(&mut registers.placement, stamp, placement);
+            }
+            CollabOperation::SetSection { section, .. } => {
+                let registers = self.sections.entry(section.id.clone()).or_default();
+                set_register(&mut registers.content, stamp, (**section).clone());
+            }
+            CollabOperation::RemoveSection { section_id, .. } => {
+                let registers = self.sections.entry(section_id.clone()).or_default();
+                if registers
+                    .removed
+                    .as_ref()
+                    .is_none_or(|removed| removed < stamp)
+                {
+                    registers.removed = Some(stamp.clone());
+                }
+            }
+        }
+    }
+
+    /// Merges another replica's state into this one, as if every operation it has seen had
+    /// been applied here.
+    pub fn merge(&mut self, other: &CollabDocument) {
+        self.clock = self.clock.max(other.clock);
+        if self.document.stamp < other.document.stamp {
+            self.document = other.document.clone();
+        }
+        for (section_id, theirs) in &other.sections {
+            let ours = self.sections.entry(section_id.clone()).or_default();
+            if let Some(placement) = &theirs.placement {
+                set_register(
+                    &mut ours.placement,
+                    &placement.stamp,
+                    placement.value.clone(),
+                );
+            }
+            if let Some(content) = &theirs.content {
+                set_register(&mut ours.content, &content.stamp, content.value.clone());
+            }
+            if let Some(removed) = &theirs.removed
+                && ours.removed.as_ref().is_none_or(|ours| ours < removed)
+            {
+                ours.removed = Some(removed.clone());
+            }
+        }
+    }
+
+    /// Records the local change from the current document to `document`, returning the
+    /// operations to send to the other replicas.
+    pub fn edit(&mut self, document: &MathDocument) -> Vec<CollabOperation> {
+        let current = self.to_document();
+        let new_ids: HashSet<&str> = section_tree(document)
+            .into_iter()
+            .map(|section| section.id.as_str())
+            .collect();
+        let mut operations = vec![];
+        for operation in current.diff(document).operations {
+            match operation {
+                PatchOperation::UpdateDocument { document } => {
+                    let stamp = self.tick();
+                    let operation = CollabOperation::SetDocument { stamp, document };
+                    self.apply(&operation);
+                    operations.push(operation);
+                }
+                PatchOperation::InsertSection {
+                    parent,
+                    after,
+                    section,
+                } => self.place_inserted(&mut operations, parent, after, &section),
+                PatchOperation::MoveSection {
+                    section_id,
+                    parent,
+                    after,
+                } => operations.push(self.place(section_id, parent, after.as_deref())),
+                PatchOperation::RemoveSection { section_id } => {
+                    let Some(section) = current.find_section(&section_id) else {
+                        continue;
+                    };
+                    for removed in section_tree_from(section) {
+                        if !new_ids.contains(removed.id.as_str()) {
+                            let operation = CollabOperation::RemoveSection {
+                                stamp: self.tick(),
+                                section_id: removed.id.clone(),
+                            };
+                            self.apply(&operation);
+                            operations.push(operation);
+                        }
+                    }
+                }
+                // Compared section by section below.
+                PatchOperation::UpdateSection { .. } | PatchOperation::ReplaceMath { .. } => {}
+            }
+        }
+        for section in section_tree(document) {
+            let shell = section.without_subsections();
+            let current = self.sections.get(&section.id);
+            let unchanged = current
+                .and_then(|registers| registers.content.as_ref())
+                .is_some_and(|content| to_json(&content.value) == to_json(&shell));
+            if !unchanged {
+                let operation = CollabOperation::SetSection {
+                    stamp: self.tick(),
+                    section: Box::new(shell),
+                };
+                self.apply(&operation);
+                operations.push(operation);
+            }
+        }
+        operations
+    }
+
+    /// Places an inserted section and the new sections inside it.
+    fn place_inserted(
+        &mut self,
+        operations: &mut Vec<CollabOperation>,
+        parent: SectionParent,
+        after: Option<String>,
+        section: &Section,
+    ) {
+        operations.push(self.place(section.id.clone(), parent, after.as_deref()));
+        for (list, nested) in section.subsection_lists().into_iter().enumerate() {
+            let mut after = None;
+            for child in nested {
+                let parent = SectionParent::Section {
+                    section_id: section.id.clone(),
+                    list,
+                };
+                self.place_inserted(operations, parent, after, child);
+                after = Some(child.id.clone());
+            }
+        }
+    }
+
+    /// Places the section after `after` in `parent`, or first, and applies the operation.
+    fn place(
+        &mut self,
+        section_id: String,
+        parent: SectionParent,
+        after: Option<&str>,
+    ) -> CollabOperation {
+        let mut siblings: Vec<(&Vec<u32>, &Timestamp, &str)> = self
+            .sections
+            .iter()
+            .filter(|(id, registers)| **id != section_id && registers.is_visible())
+            .filter_map(|(id, registers)| {
+                let placement = registers.placement.as_ref()?;
+                let (sibling_parent, position) = &placement.value;
+                (*sibling_parent == parent).then_some((position, &placement.stamp, id.as_str()))
+            })
+            .collect();
+        siblings.sort();
+        let index = match after {
+            Some(after) => siblings
+                .iter()
+                .position(|(_, _, id)| *id == after)
+                .map_or(siblings.len(), |index| index + 1),
+            None => 0,
+        };
+        let before = index
+            .checked_sub(1)
+            .map(|index| siblings[index].0.as_slice());
+        let next = siblings
+            .get(index)
+            .map(|(position, _, _)| position.as_slice());
+        let position = position_between(before.unwrap_or_default(), next, &self.replica);
+        let operation = CollabOperation::PlaceSection {
+            stamp: self.tick(),
+            section_id,
+            parent,
+            position,
+        };
+        self.apply(&operation);
+        operation
+    }
+
+    fn tick(&mut self) -> Timestamp {
+        self.clock += 1;
+        Timestamp {
+            counter: self.clock,
+            replica: self.replica.clone(),
+        }
+    }
+
+    /// The document as it stands on this replica.
+    pub fn to_document(&self) -> MathDocument {
+        let shell = &self.document.value;
+        let visible: BTreeMap<&str, (&SectionParent, &Vec<u32>, &Timestamp, &Section)> = self
+            .sections
+            .iter()
+            .filter(|(_, registers)| registers.is_visible())
+            .filter_map(|(id, registers)| {
+                let placement = registers.placement.as_ref()?;
+                let content = &registers.content.as_ref()?.value;
+                let (parent, position) = &placement.value;
+                Some((id.as_str(), (parent, position, &placement.stamp, content)))
+            })
+            .collect();
+        let fallback = if shell.content_type.structure().is_some() {
+            Some(SectionParent::Body)
+        } else if shell.content_type.base_content().is_some() {
+            Some(SectionParent::BaseContent)
+        } else {
+            None
+        };
+
+        // Where each section goes: its own parent if that is in the document.
+        let mut parents: HashMap<&str, Option<SectionParent>> = visible
+            .iter()
+            .map(|(&id, (parent, ..))| {
+                let parent = match parent {
+                    SectionParent::Section { section_id, list } => visible
+                        .get(section_id.as_str())
+                        .map(|(.., content)| content.subsection_lists().len())
+                        .filter(|&lists| lists > 0)
+                        .map(|lists| SectionParent::Section {
+                            section_id: section_id.clone(),
+                            list: (*list).min(lists - 1),
+                        }),
+                    SectionParent::BaseContent => shell
+                        .content_type
+                        .base_content()
+                        .map(|_| SectionParent::BaseContent),
+                    parent => shell.content_type.structure().map(|_| (*parent).clone()),
+                };
+                (id, parent.or_else(|| fallback.clone()))
+            })
+            .collect();
+        // Sections inside each other: the one placed last moves out.
+        while let Some(cycle) = find_cycle(&visible, &parents) {
+            let newest = cycle
+                .into_iter()
+                .max_by_key(|id| visible[id].2)
+                .expect("a cycle has at least one section");
+            parents.insert(newest, fallback.clone());
+        }
+
+        let mut lists: HashMap<SectionParent, Vec<&str>> = HashMap::new();
+        for (&id, parent) in &parents {
+            if let Some(parent) = parent {
+                lists.entry(parent.clone()).or_default().push(id);
+            }
+        }
+        for list in lists.values_mut() {
+            list.sort_by_key(|id| (visible[id].1, visible[id].2));
+        }
+        // An abstract placed after another goes to the end of the body.
+        if let Some(abstracts) = lists.get_mut(&SectionParent::Abstract)
+            && abstracts.len() > 1
+        {
+            let extra = abstracts.split_off(1);
+            lists.entry(SectionParent::Body).or_default().extend(extra);
+        }
+
+        let mut patch = DocumentPatch::default();
+        let top_level = [
+            SectionParent::Abstract,
+            SectionParent::Body,
+            SectionParent::Footnotes,
+            SectionParent::Glossary,
+            SectionParent::BaseContent,
+        ];
+        for parent in top_level {
+            add_inserts(&mut patch, &lists, &visible, parent);
+        }
+        let mut document = shell.clone();
+        document
+            .apply(&patch)
+            .expect("sections are inserted into parents inserted before them");
+        document
+    }
+}
+
+/// Inserts for the sections in `parent` and, after each, the sections in it.
+fn add_inserts(
+    patch: &mut DocumentPatch,
+    lists: &HashMap<SectionParent, Vec<&str>>,
+    visible: &BTreeMap<&str, (&SectionParent, &Vec<u32>, &Timestamp, &Section)>,
+    parent: SectionParent,
+) {
+    let mut after: Option<String> = None;
+    for &id in lists.get(&parent).into_iter().flatten() {
+        let section = visible[id].3;
+        patch.operations.push(PatchOperation::InsertSection {
+            parent: parent.clone(),
+            after: after.clone(),
+            section: section.clone(),
+        });
+        after = Some(id.to_string());
+        for list in 0..section.subsection_lists().len() {
+            let parent = SectionParent::Section {
+                section_id: id.to_string(),
+                list,
+            };
+            add_inserts(patch, lists, visible, parent);
+        }
+    }
+}
+
+/// Sections whose parents lead back to themselves, if any.
+fn find_cycle<'a>(
+    visible: &BTreeMap<&'a str, (&SectionParent, &Vec<u32>, &Timestamp, &Section)>,
+    parents: &HashMap<&'a str, Option<SectionParent>>,
+) -> Option<Vec<&'a str>> {
+    for &start in visible.keys() {
+        let mut chain = vec![start];
+        let mut id = start;
+        while let Some(Some(SectionParent::Section { section_id, .. })) = parents.get(id) {
+            let (&parent, _) = visible.get_key_value(section_id.as_str())?;
+            if let Some(index) = chain.iter().position(|&seen| seen == parent) {
+                return Some(chain.split_off(index));
+            }
+            chain.push(parent);
+            id = parent;
+        }
+    }
+    None
+}
+
+/// A position key ordered after `before` and before `next`, when given, ending in a digit
+/// derived from the replica: replicas that insert at the same place concurrently get different
+/// keys, so that later sections can still go between theirs. A `next` no greater than `before`,
+/// as when two replicas' digits collide, is ignored and the key goes just after `before`.
+fn position_between(before: &[u32], next: Option<&[u32]>, replica: &str) -> Vec<u32> {
+    let digit = |key: &[u32], index: usize| u64::from(key.get(index).copied().unwrap_or(0));
+    let next = next.filter(|next| {
+        let length = before.len().max(next.len());
+        (0..length)
+            .map(|index| digit(next, index))
+            .gt((0..length).map(|index| digit(before, index)))
+    });
+    let mut position = vec![];
+    let mut bounded = next.is_some();
+    for index in 0.. {
+        let low = digit(before, index);
+        let high = match next {
+            Some(next) if bounded => digit(next, index),
+            _ => 1 << 32,
+        };
+        if high > low + 1 {
+            position.push(((low + high) / 2) as u32);
+            break;
+        }
+        position.push(low as u32);
+        // Below `next` from here on, so the following digits are free.
+        bounded &= high == low;
+    }
+    position.push(replica_digit(replica));
+    position
+}
+
+/// 32-bit FNV-1a of the replica, the same on every platform and release.
+fn replica_digit(replica: &str) -> u32 {
+    replica.bytes().fold(0x811c_9dc5, |hash, byte| {
+        (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193)
+    })
+}
+
+/// The sections of a document in document order, nested ones included but not those of
+/// embedded documents.
+fn section_tree(document: &MathDocument) -> Vec<&Section> {
+    document
+        .content_type
+        .top_level_sections()
+        .into_iter()
+        .flat_map(section_tree_from)
+        .collect()
+}
+
+fn section_tree_from(section: &Section) -> Vec<&Section> {
+    let mut sections = vec![section];
+    for list in section.subsection_lists() {
+        sections.extend(list.iter().flat_map(section_tree_from));
+    }
+    sections
+}
+
+fn to_json(section: &Section) -> serde_json::Value {
+    serde_json::to_value(section).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{Value, json};
+
+    fn notes(section_ids: &[&str]) -> MathDocument {
+        let body: Vec<Value> = section_ids
+            .iter()
+            .map(|id| json!({ "id": id, "content": { "kind": "RichText", "data": {} } }))
+            .collect();
+        serde_json::from_value(json!({
+            "id": "notes",
+            "content_type": {
+                "kind": "PersonalNotes",
+                "data": {
+                    "title": "Scratch",
+                    "author_level": "Expert",
+                    "note_style": "Formal",
+                    "content_metadata": {},
+                    "structure": { "body": body },
+                    "relationships": {}
+                }
+            }
+        }))
+        .unwrap()
+    }
+
+    fn section_ids(document: &MathDocument) -> Vec<String> {
+        section_tree(document)
+            .into_iter()
+            .map(|section| section.id.clone())
+            .collect()
+    }
+
+    fn exchange(from: &[CollabOperation], to: &mut CollabDocument) {
+        from.iter().for_each(|operation| to.apply(operation));
+    }
+
+    #[test]
+    fn concurrent_inserts_at_the_same_place_converge_with_room_between() {
+        let start = notes(&["a"]);
+        let mut alice = CollabDocument::new("alice", &start);
+        let mut bob = CollabDocument::new("bob", &start);
+        let from_alice = alice.edit(&notes(&["a", "b"]));
+        let from_bob = bob.edit(&notes(&["a", "c"]));
+        exchange(&from_bob, &mut alice);
+        exchange(&from_alice, &mut bob);
+        let merged = section_ids(&alice.to_document());
+        assert_eq!(merged.len(), 3);
+        assert_eq!(merged, section_ids(&bob.to_document()));
+
+        let mut edited: Vec<&str> = merged.iter().map(String::as_str).collect();
+        edited.insert(2, "d");
+        let from_alice = alice.edit(&notes(&edited));
+        exchange(&from_alice, &mut bob);
+        assert_eq!(section_ids(&alice.to_document()), edited);
+        assert_eq!(section_ids(&bob.to_document()), edited);
+    }
+
+    #[test]
+    fn equal_neighbours_still_give_a_later_key() {
+        let position = position_between(&[5, 7], Some(&[5, 7]), "alice");
+        assert!(position.as_slice() > [5, 7].as_slice());
+        let position = position_between(&[], Some(&[0]), "alice");
+        assert!(!position.is_empty());
+    }
+}
@@ -0,0 +1,111 @@
+use super::*;
+use std::sync::Arc;
+
+impl MathNode {
+    /// `operand!`, with the operand in brackets unless it is an identifier, a number or already
+    /// bracketed.
+    pub fn factorial(id: impl Into<String>, operand: MathNode) -> MathNode {
+        let id = id.into();
+        let operand = match operand.content.as_ref() {
+            MathNodeContent::Identifier(_)
+            | MathNodeContent::Quantity { .. }
+            | MathNodeContent::Bracketed { .. } => operand,
+            _ => bracketed(format!("{}-operand", id), operand),
+        };
+        MathNode {
+            content: Arc::new(MathNodeContent::UnaryPostfixOperation {
+                parameter: Arc::new(operand),
+                operator: Arc::new(MathNode {
+                    id: format!("{}-operator", id),
+                    content: Arc::new(MathNodeContent::String("!".to_string())),
+                }),
+            }),
+            id,
+        }
+    }
+
+    pub fn binomial(
+        id: impl Into<String>,
+        upper: MathNode,
+        lower: MathNode,
+        style: BinomialStyle,
+    ) -> MathNode {
+        MathNode {
+            id: id.into(),
+            content: Arc::new(MathNodeContent::Binomial {
+                upper: Arc::new(upper),
+                lower: Arc::new(lower),
+                style,
+            }),
+        }
+    }
+
+    /// Whether this is a `!` postfix operation.
+    pub fn is_factorial(&self) -> bool {
+        matches!(
+            self.content.as_ref(),
+            MathNodeContent::UnaryPostfixOperation { operator, .. }
+                if matches!(
+                    operator.content.as_ref(),
+                    MathNodeContent::String(symbol) | MathNodeContent::Text(symbol)
+                        if symbol.trim() == "!"
+                )
+        )
+    }
+
+    /// A `Binomial` written out as factorials, `n!/(k!·(n - k)!)`, the first line of working one
+    /// out by hand. `None` for other nodes.
+    pub fn binomial_as_factorials(&self) -> Option<MathNode> {
+        let MathNodeContent::Binomial { upper, lower, .. } = self.content.as_ref() else {
+            return None;
+        };
+        let id = &self.id;
+        let difference = MathNode {
+            id: format!("{}-difference", id),
+            content: Arc::new(MathNodeContent::BinaryOperation {
+                operation_type: BinaryOperationType::Addition,
+                terms: vec![
+                    (BinaryOperator::Plus, upper.as_ref().clone()),
+                    (BinaryOperator::Minus, lower.as_ref().clone()),
+                ],
+            }),
+        };
+        let denominator = MathNode {
+            id: format!("{}-denominator", id),
+            content: Arc::new(MathNodeContent::BinaryOperation {
+                operation_type: BinaryOperationType::Multiplication,
+                terms: vec![
+                    (
+                        BinaryOperator::Dot,
+                        MathNode::factorial(format!("{}-lower", id), lower.as_ref().clone()),
+                    ),
+                    (
+                        BinaryOperator::Dot,
+                        MathNode::factorial(format!("{}-complement", id), difference),
+                    ),
+                ],
+            }),
+        };
+        Some(MathNode {
+            id: id.clone(),
+            content: Arc::new(MathNodeContent::Fraction {
+                numerator: Arc::new(MathNode::factorial(
+                    format!("{}-upper", id),
+                    upper.as_ref().clone(),
+                )),
+                denominator: Arc::new(denominator),
+            }),
+        })
+    }
+}
+
+fn bracketed(id: String, inner: MathNode) -> MathNode {
+    MathNode {
+        id,
+        content: Arc::new(MathNodeContent::Bracketed {
+            inner: Arc::new(inner),
+            style: BracketStyle::Round,
+            size: BracketSize::Normal,
+        }),
+    }
+}
@@ -0,0 +1,325 @@
+use super::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{collections::BTreeMap, fmt};
+use ts_rs::TS;
+
+/// Properties passed to a `CustomComponent`, optionally tied to a registered schema.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ComponentProps {
+    #[ts(type = "unknown")]
+    pub values: Value, // JSON object mapping prop names to values
+    pub schema_ref: Option<String>, // ComponentSchema::schema_id the values were written against
+}
+
+impl ComponentProps {
+    pub fn new(values: Value) -> Self {
+        ComponentProps {
+            values,
+            schema_ref: None,
+        }
+    }
+
+    pub fn with_schema(values: Value, schema_id: impl Into<String>) -> Self {
+        ComponentProps {
+            values,
+            schema_ref: Some(schema_id.into()),
+        }
+    }
+
+    pub fn get(&self, prop: &str) -> Option<&Value> {
+        self.values.get(prop)
+    }
+}
+
+/// The props a known component accepts.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ComponentSchema {
+    pub component_name: String, // matches CustomComponent::component_name
+    pub schema_id: String,      // referenced by ComponentProps::schema_ref, e.g. "function-plot@2"
+    pub props: Vec<PropSchema>,
+    pub allow_unknown_props: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct PropSchema {
+    pub name: String,
+    pub prop_type: PropType,
+    pub required: bool,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub enum PropType {
+    String,
+    Number,
+    Integer,
+    Boolean,
+    Array(Box<PropType>),
+    Object,
+    OneOf(Vec<String>), // string enumeration
+    Any,
+}
+
+impl PropType {
+    pub fn matches(&self, value: &Value) -> bool {
+        match self {
+            PropType::String => value.is_string(),
+            PropType::Number => value.is_number(),
+            PropType::Integer => value.is_i64() || value.is_u64(),
+            PropType::Boolean => value.is_boolean(),
+            PropType::Array(item_type) => value
+                .as_array()
+                .is_some_and(|items| items.iter().all(|item| item_type.matches(item))),
+            PropType::Object => value.is_object(),
+            PropType::OneOf(options) => value
+                .as_str()
+                .is_some_and(|value| options.iter().any(|option| option == value)),
+            PropType::Any => true,
+        }
+    }
+}
+
+impl ComponentSchema {
+    pub fn new(component_name: impl Into<String>, schema_id: impl Into<String>) -> Self {
+        ComponentSchema {
+            component_name: component_name.into(),
+            schema_id: schema_id.into(),
+            props: vec![],
+            allow_unknown_props: false,
+        }
+    }
+
+    pub fn required(mut self, name: impl Into<String>, prop_type: PropType) -> Self {
+        self.props.push(PropSchema {
+            name: name.into(),
+            prop_type,
+            required: true,
+            description: None,
+        });
+        self
+    }
+
+    pub fn optional(mut self, name: impl Into<String>, prop_type: PropType) -> Self {
+        self.props.push(PropSchema {
+            name: name.into(),
+            prop_type,
+            required: false,
+            description: None,
+        });
+        self
+    }
+
+    pub fn allow_unknown_props(mut self) -> Self {
+        self.allow_unknown_props = true;
+        self
+    }
+
+    /// Checks `props` against this schema; `None` is treated as an empty prop object.
+    pub fn validate(&self, props: Option<&ComponentProps>) -> Vec<PropValidationError> {
+        let component_name = &self.component_name;
+        let mut errors = vec![];
+
+        if let Some(schema_ref) = props.and_then(|props| props.schema_ref.as_ref())
+            && schema_ref != &self.schema_id
+        {
+            errors.push(PropValidationError::SchemaMismatch {
+                component_name: component_name.clone(),
+                expected: self.schema_id.clone(),
+                found: schema_ref.clone(),
+            });
+        }
+
+        let empty = serde_json::Map::new();
+        let values = match props.map(|props| &props.values) {
+            None | Some(Value::Null) => &empty,
+            Some(Value::Object(values)) => values,
+            Some(_) => {
+                errors.push(PropValidationError::NotAnObject {
+                    component_name: component_name.clone(),
+                });
+                return errors;
+            }
+        };
+
+        for prop in &self.props {
+            match values.get(&prop.name) {
+                None | Some(Value::Null) if prop.required => {
+                    errors.push(PropValidationError::MissingProp {
+                        component_name: component_name.clone(),
+                        prop: prop.name.clone(),
+                    })
+                }
+                None | Some(Value::Null) => {}
+                Some(value) if !prop.prop_type.matches(value) => {
+                    errors.push(PropValidationError::WrongType {
+                        component_name: component_name.clone(),
+                        prop: prop.name.clone(),
+                        expected: prop.prop_type.clone(),
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+
+        if !self.allow_unknown_props {
+            for name in values.keys() {
+                if !self.props.iter().any(|prop| &prop.name == name) {
+                    errors.push(PropValidationError::UnexpectedProp {
+                        component_name: component_name.clone(),
+                        prop: name.clone(),
+                    });
+                }
+            }
+        }
+
+        errors
+    }
+}
+
+/// The custom components a host knows how to render, keyed by component name.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct ComponentRegistry {
+    pub components: BTreeMap<String, ComponentSchema>,
+}
+
+impl ComponentRegistry {
+    pub fn new() -> Self {
+        ComponentRegistry::default()
+    }
+
+    pub fn register(&mut self, schema: ComponentSchema) {
+        self.components
+            .insert(schema.component_name.clone(), schema);
+    }
+
+    pub fn with(mut self, schema: ComponentSchema) -> Self {
+        self.register(schema);
+        self
+    }
+
+    pub fn get(&self, component_name: &str) -> Option<&ComponentSchema> {
+        self.components.get(component_name)
+    }
+
+    pub fn validate_props(
+        &self,
+        component_name: &str,
+        props: Option<&ComponentProps>,
+    ) -> Vec<PropValidationError> {
+        match self.get(component_name) {
+            Some(schema) => schema.validate(props),
+            None => vec![PropValidationError::UnknownComponent {
+                component_name: component_name.to_string(),
+            }],
+        }
+    }
+
+    /// Validates the props of every `CustomComponent` in `document`, including embedded documents.
+    pub fn validate_document(&self, document: &MathDocument) -> Vec<PropValidationError> {
+        let mut validator = PropsValidator {
+            registry: self,
+            errors: vec![],
+        };
+        validator.visit_document(document);
+        validator.errors
+    }
+}
+
+struct PropsValidator<'a> {
+    registry: &'a ComponentRegistry,
+    errors: Vec<PropValidationError>,
+}
+
+impl<'doc> DocumentVisitor<'doc> for PropsValidator<'_> {
+    fn visit_content_node(&mut self, node: &'doc SectionContentNode) {
+        if let SectionContentNode::CustomComponent {
+            component_name,
+            props,
+            ..
+        } = node
+        {
+            self.errors
+                .extend(self.registry.validate_props(component_name, props.as_ref()));
+        }
+        walk_content_node(self, node);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PropValidationError {
+    UnknownComponent {
+        component_name: String,
+    },
+    SchemaMismatch {
+        component_name: String,
+        expected: String,
+        found: String,
+    },
+    NotAnObject {
+        component_name: String,
+    },
+    MissingProp {
+        component_name: String,
+        prop: String,
+    },
+    UnexpectedProp {
+        component_name: String,
+        prop: String,
+    },
+    WrongType {
+        component_name: String,
+        prop: String,
+        expected: PropType,
+    },
+}
+
+impl fmt::Display for PropValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PropValidationError::UnknownComponent { component_name } => {
+                write!(f, "component `{}` is not registered", component_name)
+            }
+            PropValidationError::SchemaMismatch {
+                component_name,
+                expected,
+                found,
+            } => write!(
+                f,
+                "props for `{}` reference schema `{}`, but the registered schema is `{}`",
+                component_name, found, expected
+            ),
+            PropValidationError::NotAnObject { component_name } => {
+                write!(f, "props for `{}` must be a JSON object", component_name)
+            }
+            PropValidationError::MissingProp {
+                component_name,
+                prop,
+            } => write!(
+                f,
+                "`{}` is missing required prop `{}`",
+                component_name, prop
+            ),
+            PropValidationError::UnexpectedProp {
+                component_name,
+                prop,
+            } => write!(f, "`{}` does not accept prop `{}`", component_name, prop),
+            PropValidationError::WrongType {
+                component_name,
+                prop,
+                expected,
+            } => write!(
+                f,
+                "prop `{}` of `{}` should be {:?}",
+                prop, component_name, expected
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PropValidationError {}
@@ -1,8 +1,14 @@
-import React, { CSSProperties, useEffect, useRef, useState } from 'react';
+import React, { CSSProperties, createContext, useContext, useEffect, useRef, useState } from 'react';
 import classNames from 'classnames';
 import styles from './math_node.module.scss';
 import { MathNode } from '../../bindings/MathNode.ts';
 import { MathNodeContent } from '../../bindings/MathNodeContent.ts';
+import { QuantityFormat } from '../../bindings/QuantityFormat.ts';
+import {
+    isMathNodeContentEmpty,
+    isMathNodeContentFunctionCall,
+    isMathNodeContentQuantity,
+} from '../../bindings/index.ts';
 import { TurnTextLineNode } from '../../bindings/TurnTextLineNode.ts';
 import { MathJaxProvider, MathJaxNode } from '@yozora/react-mathjax';
 import { RefinedMulOrDivOperation } from '../../bindings/RefinedMulOrDivOperation.ts';
@@ -10,7 +16,12 @@ import { SpecialMiddleScriptContentTypeNode } from '../../bindings/SpecialMiddle
 import { UnaryRelationOperatorNode } from '../../bindings/UnaryRelationOperatorNode.ts';
 import { RelationOperatorNode } from '../../bindings/RelationOperatorNode.ts';
 import { ScriptNode } from '../../bindings/ScriptNode.ts';
+import { ChemicalTerm } from '../../bindings/ChemicalTerm.ts';
+import { FormulaPart } from '../../bindings/FormulaPart.ts';
+import { ReactionStep } from '../../bindings/ReactionStep.ts';
+import { MatrixAxis } from '../../bindings/MatrixAxis.ts';
 import { convertTextStylesToCSS } from '../rich_text/textStyleUtils';
+import { formatQuantity } from './quantityFormat';
 
 const hasMarginList = [
     // '÷',
@@ -1270,6 +1281,178 @@ const UnderOver = ({
     return null;
 };
 
+// The document's `quantity_format`, provided by DocumentRenderer; null writes quantities as stored
+export const QuantityFormatContext = createContext<QuantityFormat | null>(null);
+
+const FormattedQuantity = ({
+    id,
+    quantity,
+}: {
+    id: string;
+    quantity: Extract<MathNodeContent, { kind: 'Quantity' }>['data'];
+}) => {
+    const format = useContext(QuantityFormatContext);
+    // A formatted number has the scientific notation magnitude folded in
+    const formatted = format ? formatQuantity(quantity, format) : null;
+
+    return (
+        <Component
+            type="Mrow"
+            _props={{
+                id,
+                _classNames: styles.editable_span,
+            }}
+        >
+            {StringMapNode(formatted ?? quantity.number, id)}
+            {formatted === null && quantity.scientific_notation && renderMathNode(quantity.scientific_notation)}
+            {quantity.unit && renderMathNode(quantity.unit)}
+        </Component>
+    );
+};
+
+// Placeholder for a collapsed sub-expression; clicking it toggles the full expression
+const Folded = ({ id, summary, content }: { id: string; summary: string; content: MathNode }) => {
+    const [expanded, setExpanded] = useState(false);
+
+    return (
+        <span
+            className={classNames(styles.folded, { [styles.foldedExpanded]: expanded })}
+            data-id={id}
+            title={expanded ? 'Collapse' : 'Expand'}
+            onClick={(event) => {
+                event.stopPropagation();
+                setExpanded(!expanded);
+            }}
+        >
+            {expanded ? renderMathNode(content) : <Component type="Mtext">{summary}</Component>}
+        </span>
+    );
+};
+
+// A matrix as a grid, so that its delimiters stretch to its height and its labels and notes
+// line up with the entries: row labels on the left, column labels above, the notes of
+// highlighted rows on the right and of highlighted columns below
+const MatrixGrid = ({
+    id,
+    matrix,
+}: {
+    id: string;
+    matrix: Extract<MathNodeContent, { kind: 'Matrix' }>['data'];
+}) => {
+    const rows = matrix.rows;
+    const rowLabels = matrix.row_labels ?? [];
+    const columnLabels = matrix.column_labels ?? [];
+    const highlights = matrix.highlights ?? [];
+    const delimiters = matrix.delimiters ?? 'Brackets';
+    const columnCount = Math.max(0, ...rows.map((row) => row.length));
+    const highlightOf = (axis: MatrixAxis, index: number) =>
+        highlights.find((highlight) => highlight.axis === axis && highlight.index === index);
+
+    // Grid lines are 1-based: the row labels, if any, come before the opening delimiter
+    const firstRow = columnLabels.length > 0 ? 2 : 1;
+    const openingColumn = rowLabels.length > 0 ? 2 : 1;
+    const entryColumn = (column: number) => openingColumn + 1 + column;
+    const closingColumn = entryColumn(columnCount);
+    const delimiterRows = `${firstRow} / span ${Math.max(rows.length, 1)}`;
+
+    return (
+        <span className={classNames(styles.matrix)} data-id={id}>
+            {columnLabels.map((label, column) => (
+                <span
+                    key={`column-label-${column}`}
+                    className={classNames(styles.matrix_label)}
+                    style={{ gridRow: 1, gridColumn: entryColumn(column) }}
+                >
+                    {renderMathNode(label)}
+                </span>
+            ))}
+            {rowLabels.map((label, row) => (
+                <span
+                    key={`row-label-${row}`}
+                    className={classNames(styles.matrix_label)}
+                    style={{ gridRow: firstRow + row, gridColumn: 1 }}
+                >
+                    {renderMathNode(label)}
+                </span>
+            ))}
+            <span
+                className={classNames(styles.matrix_delimiter)}
+                data-delimiters={delimiters}
+                data-side="open"
+                style={{ gridRow: delimiterRows, gridColumn: openingColumn }}
+            >
+                {delimiters === 'Braces' && '{'}
+            </span>
+            {rows.map((row, rowIndex) => {
+                const rowHighlight = highlightOf('Row', rowIndex);
+                // Short rows are padded with empty entries, so that bars and highlights run on
+                return Array.from({ length: columnCount }, (_, column) => {
+                    const highlight = rowHighlight ?? highlightOf('Column', column);
+                    const entry = row[column];
+                    return (
+                        <span
+                            key={`${rowIndex}-${column}`}
+                            className={classNames(styles.matrix_entry, {
+                                [styles.matrix_highlighted]: highlight,
+                                [styles.matrix_augmented]:
+                                    column > 0 && column === matrix.augmented_after,
+                            })}
+                            style={{
+                                gridRow: firstRow + rowIndex,
+                                gridColumn: entryColumn(column),
+                                backgroundColor: highlight?.color ?? undefined,
+                            }}
+                        >
+                            {entry && renderMathNode(entry)}
+                        </span>
+                    );
+                });
+            })}
+            <span
+                className={classNames(styles.matrix_delimiter)}
+                data-delimiters={delimiters}
+                data-side="close"
+                style={{ gridRow: delimiterRows, gridColumn: closingColumn }}
+            >
+                {delimiters === 'Braces' && '}'}
+            </span>
+            {highlights.map(
+                (highlight, index) =>
+                    highlight.annotation && (
+                        <span
+                            key={`note-${index}`}
+                            className={classNames(styles.matrix_note)}
+                            data-axis={highlight.axis}
+                            style={
+                                highlight.axis === 'Row'
+                                    ? {
+                                          gridRow: firstRow + highlight.index,
+                                          gridColumn: closingColumn + 1,
+                                      }
+                                    : {
+                                          gridRow: firstRow + rows.length,
+                                          gridColumn: entryColumn(highlight.index),
+                                      }
+                            }
+                        >
+                            {renderMathNode(highlight.annotation)}
+                        </span>
+                    )
+            )}
+        </span>
+    );
+};
+
+// The symbol of a relation operator, as written between the sides of a Relationship
+export const relationOperatorSymbol = (operator: RelationOperatorNode): string => {
+    if (typeof operator === 'string') {
+        return _relationOperator[operator] || '';
+    } else if (operator && typeof operator === 'object' && 'Custom' in operator) {
+        return operator.Custom || '';
+    }
+    return '';
+};
+
 // Helper function to render binary operators based on operator type
 const renderBinaryOperator = (operator: any): React.ReactNode => {
     switch (operator) {
@@ -1314,13 +1497,161 @@ const renderBinaryOperator = (operator: any): React.ReactNode => {
     }
 };
 
+const _turnstile = {
+    Proves: '⊢',
+    Entails: '⊨',
+    NotProves: '⊬',
+    NotEntails: '⊭',
+};
+
+const _mathEmphasis: { [key: string]: CSSProperties } = {
+    Strong: { fontWeight: 'bold' },
+    Muted: { opacity: 0.5 },
+    Boxed: { border: '1px solid currentColor', padding: '0.1em 0.2em' },
+};
+
+// TeX's \! \, \: \; \quad and \qquad
+const _mathSpace = {
+    NegativeThin: '-0.167em',
+    Thin: '0.167em',
+    Medium: '0.222em',
+    Thick: '0.278em',
+    Quad: '1em',
+    Qquad: '2em',
+};
+
+// A TeX length as CSS, which shares pt, em, ex, cm and mm but not the math unit, 1/18 em
+const texLength = (length: string): string =>
+    length.trim().replace(/^(-?[\d.]+)\s*mu$/, (_, value) => `${parseFloat(value) / 18}em`);
+
+const _reactionArrow = {
+    Forward: '→',
+    Backward: '←',
+    Reversible: '↔',
+    Equilibrium: '⇌',
+};
+
+const _physicalState = {
+    Solid: '(s)',
+    Liquid: '(l)',
+    Gas: '(g)',
+    Aqueous: '(aq)',
+};
+
+// A formula as mhchem writes it: upright element symbols with their counts as subscripts
+const renderFormulaParts = (parts: FormulaPart[]): React.ReactNode[] =>
+    parts.map((part, index) => {
+        let base: React.ReactNode;
+        let count: number;
+        if ('Element' in part) {
+            base = <Component type="Mtext">{part.Element.symbol}</Component>;
+            count = part.Element.count;
+        } else {
+            const square = part.Group.bracket === 'Square';
+            base = (
+                <Component type="Mrow">
+                    <Component type="Mo">{square ? '[' : '('}</Component>
+                    {renderFormulaParts(part.Group.parts)}
+                    <Component type="Mo">{square ? ']' : ')'}</Component>
+                </Component>
+            );
+            count = part.Group.count;
+        }
+        if (count === 1) {
+            return <React.Fragment key={index}>{base}</React.Fragment>;
+        }
+        return (
+            <Component type="Msub" key={index}>
+                {base}
+                <Component type="Mn">{count}</Component>
+            </Component>
+        );
+    });
+
+// One side of a reaction: the terms joined by +, each with its coefficient, charge and state
+const renderChemicalTerms = (terms: ChemicalTerm[]): React.ReactNode[] =>
+    terms.map((term, index) => {
+        const { charge, state } = term.species;
+        const formula = renderFormulaParts(term.species.parts);
+        const magnitude = Math.abs(charge);
+        return (
+            <Component type="Mrow" key={index}>
+                {index > 0 && <Component type="Mo">+</Component>}
+                {term.coefficient && <Component type="Mn">{term.coefficient}</Component>}
+                {charge === 0 ? (
+                    formula
+                ) : (
+                    <Component type="Msup">
+                        <Component type="Mrow">{formula}</Component>
+                        <Component type="Mo">
+                            {`${magnitude > 1 ? magnitude : ''}${charge > 0 ? '+' : '−'}`}
+                        </Component>
+                    </Component>
+                )}
+                {state && <Component type="Mtext">{_physicalState[state]}</Component>}
+            </Component>
+        );
+    });
+
+// The reaction arrow with its conditions over and under it
+const renderReactionArrow = (step: ReactionStep): React.ReactNode => {
+    let arrow = <Component type="Mo">{_reactionArrow[step.arrow]}</Component>;
+    if (step.below) {
+        arrow = (
+            <Component type="Munder">
+                {arrow}
+                <Component type="Mtext">{step.below}</Component>
+            </Component>
+        );
+    }
+    if (step.above) {
+        arrow = (
+            <Component type="Mover">
+                {arrow}
+                <Component type="Mtext">{step.above}</Component>
+            </Component>
+        );
+    }
+    return arrow;
+};
+
+// The order of a derivative when it is a whole number, for primes and dots; null for a
+// symbolic order such as n
+const derivativeOrder = (order: MathNode): number | null => {
+    if (!isMathNodeContentQuantity(order.content)) return null;
+    const number = order.content.data.number.trim();
+    return /^\d+$/.test(number) ? parseInt(number, 10) : null;
+};
+
+// Dₓ or ∂ₓ, with the order above the variable for higher derivatives
+const renderDerivativeOperator = (
+    symbol: string,
+    variable: MathNode,
+    order: MathNode,
+    wholeOrder: number | null
+) => {
+    return (
+        <Component type="Msubsup">
+            <Component type="Mi">{symbol}</Component>
+            <Component type="Mrow" _props={{ dataType: 'sub' }}>
+                {renderMathNode(variable)}
+            </Component>
+            {wholeOrder !== 1 && (
+                <Component type="Mrow" _props={{ dataType: 'sup' }}>
+                    {renderMathNode(order)}
+                </Component>
+            )}
+        </Component>
+    );
+};
+
 export const renderMathNode = (node: MathNode): React.ReactNode => {
-    if (node.content === 'Empty') return null;
-    const key = Object.keys(node.content)[0];
+    const content = node.content;
+    if (isMathNodeContentEmpty(content)) return null;
 
-    switch (key) {
+    switch (content.kind) {
         case 'Text':
-            const { Text } = node.content as Extract<MathNodeContent, { Text: string }>;
+            const Text = content.data;
 
             return (
                 <Component
@@ -1334,14 +1665,10 @@ export const renderMathNode = (node: MathNode): React.ReactNode => {
                 </Component>
             );
         case 'String':
-            const { String } = node.content as Extract<MathNodeContent, { String: string }>;
+            const String = content.data;
             return <>{StringMapNode(String, node.id)}</>;
         case 'Integration':
-            const { Integration } = node.content as Extract<MathNodeContent, { Integration: { 
-                integrand: MathNode; 
-                differentials: Array<[MathNode, MathNode | null, MathNode | null]>;
-                domain: MathNode | null;
-            }}>;
+            const Integration = content.data;
             
             // Extract all differentials from the Integration object
             const differentials = Integration.differentials || [];
@@ -1419,7 +1746,7 @@ export const renderMathNode = (node: MathNode): React.ReactNode => {
             );
 
         case 'Limit':
-            const { Limit } = node.content as Extract<MathNodeContent, { Limit: any }>;
+            const Limit = content.data;
             return (
                 <Component
                     type="Mrow"
@@ -1455,37 +1782,122 @@ export const renderMathNode = (node: MathNode): React.ReactNode => {
                 </Component>
             );
         case 'Differential':
-            const { Differential } = node.content as Extract<MathNodeContent, { Differential: any }>;
-            return (
-                <Component
-                    type="Mrow"
-                    _props={{
-                        id: node.id,
-                        _classNames: styles.editable_span,
-                    }}
-                >
-                    <Component type="Mfrac">
-                        <Component type="Mrow">
-                            <Component type="Mi">d</Component>
-                            <Component type="Msup">
-                                <Component type="Mrow"></Component>
-                                <Component type="Mrow">{renderMathNode(Differential.order)}</Component>
+            const Differential = content.data;
+            const differentialVariable = Differential.variable;
+            const differentialOrder = derivativeOrder(Differential.order);
+            const differentialSymbol = Differential.diff_style === 'Partial' ? '∂' : 'd';
+            // d, or d² and dⁿ for higher orders, before the target
+            const differentialOperator =
+                differentialOrder === 1 ? (
+                    <Component type="Mi">{differentialSymbol}</Component>
+                ) : (
+                    <Component type="Msup">
+                        <Component type="Mi">{differentialSymbol}</Component>
+                        <Component type="Mrow">{renderMathNode(Differential.order)}</Component>
+                    </Component>
+                );
+            if (!differentialVariable) {
+                return (
+                    <Component
+                        type="Mrow"
+                        _props={{
+                            id: node.id,
+                            _classNames: styles.editable_span,
+                        }}
+                    >
+                        {differentialOperator}
+                        {renderMathNode(Differential.target)}
+                    </Component>
+                );
+            }
+            switch (Differential.notation) {
+                case 'Lagrange':
+                    return (
+                        <Component
+                            type="Msup"
+                            _props={{
+                                id: node.id,
+                                _classNames: styles.editable_span,
+                            }}
+                        >
+                            <Component type="Mrow">{renderMathNode(Differential.target)}</Component>
+                            {differentialOrder !== null && differentialOrder <= 3 ? (
+                                <Component type="Mo">{'′'.repeat(differentialOrder)}</Component>
+                            ) : (
+                                <Component type="Mrow">
+                                    <Component type="Mo">(</Component>
+                                    {renderMathNode(Differential.order)}
+                                    <Component type="Mo">)</Component>
+                                </Component>
+                            )}
+                        </Component>
+                    );
+                case 'Newton':
+                    if (differentialOrder !== null) {
+                        return (
+                            <Component
+                                type="Mover"
+                                _props={{
+                                    id: node.id,
+                                    _classNames: styles.editable_span,
+                                }}
+                            >
+                                <Component type="Mrow">{renderMathNode(Differential.target)}</Component>
+                                <Component type="Mo">
+                                    {_SpecialMiddleScriptContent['Dot'].repeat(differentialOrder)}
+                                </Component>
                             </Component>
+                        );
+                    }
+                    // A symbolic order has no number of dots: write it in operator form
+                case 'Operator':
+                    return (
+                        <Component
+                            type="Mrow"
+                            _props={{
+                                id: node.id,
+                                _classNames: styles.editable_span,
+                            }}
+                        >
+                            {renderDerivativeOperator(
+                                Differential.diff_style === 'Partial' ? '∂' : 'D',
+                                differentialVariable,
+                                Differential.order,
+                                differentialOrder
+                            )}
                             {renderMathNode(Differential.target)}
                         </Component>
-                        <Component type="Mrow">
-                            <Component type="Mi">d</Component>
-                            <Component type="Mi">x</Component>
-                            <Component type="Msup">
-                                <Component type="Mrow"></Component>
-                                <Component type="Mrow">{renderMathNode(Differential.order)}</Component>
+                    );
+                default:
+                    // Leibniz: d²y over dx², the order on the d above and on the variable below
+                    return (
+                        <Component
+                            type="Mfrac"
+                            _props={{
+                                id: node.id,
+                                _classNames: styles.editable_span,
+                            }}
+                        >
+                            <Component type="Mrow">
+                                {differentialOperator}
+                                {renderMathNode(Differential.target)}
+                            </Component>
+                            <Component type="Mrow">
+                                <Component type="Mi">{differentialSymbol}</Component>
+                                {differentialOrder === 1 ? (
+                                    renderMathNode(differentialVariable)
+                                ) : (
+                                    <Component type="Msup">
+                                        <Component type="Mrow">{renderMathNode(differentialVariable)}</Component>
+                                        <Component type="Mrow">{renderMathNode(Differential.order)}</Component>
+                                    </Component>
+                                )}
                             </Component>
                         </Component>
-                    </Component>
-                </Component>
-            );
+                    );
+            }
         case 'QuantifiedExpression':
-            const { QuantifiedExpression } = node.content as Extract<MathNodeContent, { QuantifiedExpression: any }>;
+            const QuantifiedExpression = content.data;
             return (
                 <Component
                     type="Mrow"
@@ -1550,7 +1962,7 @@ export const renderMathNode = (node: MathNode): React.ReactNode => {
                 </Component>
             );
         case 'ScientificNotation':
-            const { ScientificNotation } = node.content as Extract<MathNodeContent, { ScientificNotation: any }>;
+            const ScientificNotation = content.data;
             return (
                 <Component
                     type="Mrow"
@@ -1569,10 +1981,7 @@ export const renderMathNode = (node: MathNode): React.ReactNode => {
                 </Component>
             );
         case 'Multiplications':
-            const { Multiplications } = node.content as Extract<
-                MathNodeContent,
-                { Multiplications: any }
-            >;
+            const Multiplications = content.data;
 
             return (
                 <Component
@@ -1582,106 +1991,689 @@ export const renderMathNode = (node: MathNode): React.ReactNode => {
                         _classNames: styles.editable_span,
                     }}
                 >
-                    {Multiplications.terms.map(
-                        ([type, item]: [RefinedMulOrDivOperation, MathNode], ind) => {
-                            return (
-                                <Component type="Mrow" key={ind}>
-                                    {renderRefinedMulOrDivOperation(type)}
-                                    {renderMathNode(item)}
-                                </Component>
-                            );
-                        }
+                    {Multiplications.terms.map(
+                        ([type, item]: [RefinedMulOrDivOperation, MathNode], ind) => {
+                            return (
+                                <Component type="Mrow" key={ind}>
+                                    {renderRefinedMulOrDivOperation(type)}
+                                    {renderMathNode(item)}
+                                </Component>
+                            );
+                        }
+                    )}
+                </Component>
+            );
+        case 'Additions':
+            const Additions = content.data;
+            return (
+                <>
+                    <Component
+                        type="Mrow"
+                        _props={{
+                            id: node.id,
+                            _classNames: styles.editable_span,
+                        }}
+                    >
+                        {Additions.terms.map(([type, node], ind) => {
+                            return (
+                                <Component type="Morw" key={ind}>
+                                    {type === 'None' ? null : (
+                                        <Component
+                                            type="Mo"
+                                            _props={{
+                                                isUnit: ind == 0,
+                                            }}
+                                        >
+                                            {_AddOrSubOperator[type]}
+                                        </Component>
+                                    )}
+                                    {renderMathNode(node)}
+                                </Component>
+                            );
+                        })}
+                    </Component>
+                </>
+            );
+        case 'Division':
+            const Division = content.data;
+            let result;
+            switch (Division.style) {
+                case 'Division':
+                    result = (
+                        <Component
+                            type="Mrow"
+                            _props={{
+                                id: node.id,
+                                _classNames: styles.editable_span,
+                            }}
+                        >
+                            {renderMathNode(Division.numerator)}
+                            <Component type="Mo">÷</Component>
+                            {renderMathNode(Division.denominator)}
+                        </Component>
+                    );
+                    break;
+                case 'Fraction':
+                    result = (
+                        <Component
+                            type="Mfrac"
+                            _props={{
+                                id: node.id,
+                                _classNames: styles.editable_span,
+                            }}
+                        >
+                            <Component type="Mrow">{renderMathNode(Division.numerator)}</Component>
+                            <Component type="Mrow">
+                                {renderMathNode(Division.denominator)}
+                            </Component>
+                        </Component>
+                    );
+                    break;
+                case 'Inline':
+                    result = (
+                        <Component
+                            type="Mrow"
+                            _props={{
+                                id: node.id,
+                                _classNames: styles.editable_span,
+                            }}
+                        >
+                            {renderMathNode(Division.numerator)}
+                            <Component type="Mo">/</Component>
+                            {renderMathNode(Division.denominator)}
+                        </Component>
+                    );
+                    break;
+                default:
+                    result = '';
+            }
+
+            return result;
+        case 'SumNotation':
+            const SumNotation = content.data;
+            return (
+                <Component
+                    type="Mrow"
+                    _props={{
+                        id: node.id,
+                        _classNames: styles.editable_span,
+                    }}
+                >
+                    <Component type="Munderover">
+                        <Component type="Mo">∑</Component>
+                        {SumNotation.lower_limit && (
+                            <Component type="Mrow">
+                                <Component type="Mrow">
+                                    {renderMathNode(SumNotation.lower_limit)}
+                                </Component>
+                            </Component>
+                        )}
+                        {SumNotation.upper_limit && (
+                            <Component type="Mrow">
+                                <Component type="Mrow">
+                                    {renderMathNode(SumNotation.upper_limit)}
+                                </Component>
+                            </Component>
+                        )}
+                    </Component>
+                    <Component
+                        type="Mspace"
+                        _props={{
+                            width: '0.167',
+                        }}
+                    ></Component>
+                    <Component type="Mrow">
+                        <Component type="Msub">
+                            {renderMathNode(SumNotation.summand)}
+                            {SumNotation.variable && (
+                                <Component type="Mrow">
+                                    {renderMathNode(SumNotation.variable)}
+                                </Component>
+                            )}
+                        </Component>
+                    </Component>
+                </Component>
+            );
+
+        case 'ProductNotation':
+            const ProductNotation = content.data;
+            return (
+                <Component
+                    type="Mrow"
+                    _props={{
+                        id: node.id,
+                        _classNames: styles.editable_span,
+                    }}
+                >
+                    <Component type="Munderover">
+                        <Component type="Mo">∏</Component>
+                        {ProductNotation.lower_limit && (
+                            <Component type="Mrow">
+                                <Component type="Mrow">
+                                    {renderMathNode(ProductNotation.lower_limit)}
+                                </Component>
+                            </Component>
+                        )}
+                        {ProductNotation.upper_limit && (
+                            <Component type="Mrow">
+                                <Component type="Mrow">
+                                    {renderMathNode(ProductNotation.upper_limit)}
+                                </Component>
+                            </Component>
+                        )}
+                    </Component>
+                    <Component
+                        type="Mspace"
+                        _props={{
+                            width: '0.167',
+                        }}
+                    ></Component>
+                    <Component type="Mrow">
+                        <Component type="Msub">
+                            {renderMathNode(ProductNotation.multiplicand)}
+                            {ProductNotation.variable && (
+                                <Component type="Mrow">
+                                    {renderMathNode(ProductNotation.variable)}
+                                </Component>
+                            )}
+                        </Component>
+                    </Component>
+                </Component>
+            );
+
+        case 'Fraction':
+            const Fraction = content.data;
+            return (
+                <Component
+                    type="Mfrac"
+                    _props={{
+                        id: node.id,
+                        _classNames: styles.editable_span,
+                    }}
+                >
+                    <Component type="Mrow">{renderMathNode(Fraction.numerator)}</Component>
+                    <Component type="Mrow">{renderMathNode(Fraction.denominator)}</Component>
+                </Component>
+            );
+        case 'Radical':
+            const Radical = content.data;
+            if (Radical.index === null) {
+                return (
+                    <Component
+                        type="Msqrt"
+                        _props={{
+                            id: node.id,
+                            _classNames: styles.editable_span,
+                        }}
+                    >
+                        {renderMathNode(Radical.radicand)}
+                    </Component>
+                );
+            }
+            return (
+                <Component
+                    type="Mroot"
+                    _props={{
+                        id: node.id,
+                        _classNames: styles.editable_span,
+                    }}
+                >
+                    <Component type="Msqrt">{renderMathNode(Radical.radicand)}</Component>
+                    <Component type="Mrow">{renderMathNode(Radical.index)}</Component>
+                </Component>
+            );
+        case 'Binomial':
+            const Binomial = content.data;
+            switch (Binomial.style) {
+                case 'Function':
+                    return (
+                        <Component
+                            type="Mrow"
+                            _props={{
+                                id: node.id,
+                                _classNames: styles.editable_span,
+                            }}
+                        >
+                            <Component type="Mi">C</Component>
+                            <Component type="Mo">(</Component>
+                            {renderMathNode(Binomial.upper)}
+                            <Component type="Mo">,</Component>
+                            {renderMathNode(Binomial.lower)}
+                            <Component type="Mo">)</Component>
+                        </Component>
+                    );
+                case 'Prefix':
+                    return (
+                        <Component
+                            type="Mrow"
+                            _props={{
+                                id: node.id,
+                                _classNames: styles.editable_span,
+                            }}
+                        >
+                            <Component type="Msup">
+                                <Component type="Mrow"></Component>
+                                <Component type="Mrow">{renderMathNode(Binomial.upper)}</Component>
+                            </Component>
+                            <Component type="Msub">
+                                <Component type="Mi">C</Component>
+                                <Component type="Mrow">{renderMathNode(Binomial.lower)}</Component>
+                            </Component>
+                        </Component>
+                    );
+                default:
+                    // The upper and lower entries stacked without a bar, in parentheses
+                    return (
+                        <Component
+                            type="Mrow"
+                            _props={{
+                                id: node.id,
+                                _classNames: styles.editable_span,
+                                style: {
+                                    verticalAlign: 'middle',
+                                },
+                            }}
+                        >
+                            <Component type="Mo">(</Component>
+                            <Component
+                                type="Mrow"
+                                _props={{
+                                    style: {
+                                        boxSizing: 'border-box',
+                                        display: 'inline-block',
+                                    },
+                                }}
+                            >
+                                <Component type="Mtable">
+                                    <Component type="Mtablerow">
+                                        <Component type="Mtablecell">
+                                            {renderMathNode(Binomial.upper)}
+                                        </Component>
+                                    </Component>
+                                    <Component type="Mtablerow">
+                                        <Component type="Mtablecell">
+                                            {renderMathNode(Binomial.lower)}
+                                        </Component>
+                                    </Component>
+                                </Component>
+                            </Component>
+                            <Component type="Mo">)</Component>
+                        </Component>
+                    );
+            }
+        case 'Cases':
+            const Cases = content.data;
+            return (
+                <Component
+                    type="Mrow"
+                    _props={{
+                        id: node.id,
+                        _classNames: styles.editable_span,
+                        style: {
+                            verticalAlign: Cases.branches.length > 1 ? 'middle' : 'baseline',
+                        },
+                    }}
+                >
+                    <Component type="Mo">{'{'}</Component>
+                    <Component
+                        type="Mrow"
+                        _props={{
+                            style: {
+                                boxSizing: 'border-box',
+                                display: 'inline-block',
+                            },
+                        }}
+                    >
+                        <Component type="Mtable" _props={{ style: { textAlign: 'left' } }}>
+                            {Cases.branches.map(([expression, condition], rowIndex) => (
+                                <Component type="Mtablerow" key={rowIndex}>
+                                    <Component type="Mtablecell">
+                                        {renderMathNode(expression)}
+                                        <span className={classNames(styles.tstrut)}></span>
+                                    </Component>
+                                    <Component type="Mtablecell">
+                                        <Component type="Mspace" _props={{ width: '1' }}></Component>
+                                        {renderMathNode(condition)}
+                                    </Component>
+                                </Component>
+                            ))}
+                        </Component>
+                    </Component>
+                </Component>
+            );
+        case 'Bra':
+            const Bra = content.data;
+            return (
+                <Component
+                    type="Mrow"
+                    _props={{
+                        id: node.id,
+                        _classNames: styles.editable_span,
+                    }}
+                >
+                    <Component type="Mo">⟨</Component>
+                    {renderMathNode(Bra.state)}
+                    <Component type="Mo">|</Component>
+                </Component>
+            );
+        case 'Ket':
+            const Ket = content.data;
+            return (
+                <Component
+                    type="Mrow"
+                    _props={{
+                        id: node.id,
+                        _classNames: styles.editable_span,
+                    }}
+                >
+                    <Component type="Mo">|</Component>
+                    {renderMathNode(Ket.state)}
+                    <Component type="Mo">⟩</Component>
+                </Component>
+            );
+        case 'BraKet':
+            const BraKet = content.data;
+            return (
+                <Component
+                    type="Mrow"
+                    _props={{
+                        id: node.id,
+                        _classNames: styles.editable_span,
+                    }}
+                >
+                    <Component type="Mo">⟨</Component>
+                    {renderMathNode(BraKet.bra)}
+                    <Component type="Mo">|</Component>
+                    {BraKet.operator && (
+                        <>
+                            {renderMathNode(BraKet.operator)}
+                            <Component type="Mo">|</Component>
+                        </>
+                    )}
+                    {renderMathNode(BraKet.ket)}
+                    <Component type="Mo">⟩</Component>
+                </Component>
+            );
+        case 'Expectation':
+            const Expectation = content.data;
+            return (
+                <Component
+                    type="Mrow"
+                    _props={{
+                        id: node.id,
+                        _classNames: styles.editable_span,
+                    }}
+                >
+                    <Component type="Mo">⟨</Component>
+                    {Expectation.state && (
+                        <>
+                            {renderMathNode(Expectation.state)}
+                            <Component type="Mo">|</Component>
+                        </>
+                    )}
+                    {renderMathNode(Expectation.operator)}
+                    {Expectation.state && (
+                        <>
+                            <Component type="Mo">|</Component>
+                            {renderMathNode(Expectation.state)}
+                        </>
+                    )}
+                    <Component type="Mo">⟩</Component>
+                </Component>
+            );
+        case 'SetExplicit':
+            const SetExplicit = content.data;
+            return (
+                <Component
+                    type="Mrow"
+                    _props={{
+                        id: node.id,
+                        _classNames: styles.editable_span,
+                    }}
+                >
+                    <Component type="Mo">{'{'}</Component>
+                    {SetExplicit.elements.map((item, ind) => {
+                        return (
+                            <Component type="Mrow" key={ind}>
+                                {renderMathNode(item)}
+                                {ind < SetExplicit.elements.length - 1 && (
+                                    <Component type="Mo">,</Component>
+                                )}
+                            </Component>
+                        );
+                    })}
+                    <Component type="Mo">{'}'}</Component>
+                </Component>
+            );
+        case 'SetBuilder':
+            const SetBuilder = content.data;
+            return (
+                <Component
+                    type="Mrow"
+                    _props={{
+                        id: node.id,
+                        _classNames: styles.editable_span,
+                    }}
+                >
+                    <Component type="Mo">{'{'}</Component>
+                    {renderMathNode(SetBuilder.variable)}
+                    {SetBuilder.domain && (
+                        <>
+                            <Component type="Mo">∈</Component>
+                            {renderMathNode(SetBuilder.domain)}
+                        </>
+                    )}
+                    <Component type="Mo">|</Component>
+                    {renderMathNode(SetBuilder.predicate)}
+                    <Component type="Mo">{'}'}</Component>
+                </Component>
+            );
+        case 'Norm':
+            const Norm = content.data;
+            return (
+                <Component
+                    type="Mrow"
+                    _props={{
+                        id: node.id,
+                        _classNames: styles.editable_span,
+                    }}
+                >
+                    <Component type="Mo">‖</Component>
+                    {renderMathNode(Norm.inner)}
+                    {Norm.subscript ? (
+                        <Component type="Msub">
+                            <Component type="Mo">‖</Component>
+                            <Component type="Mrow">{renderMathNode(Norm.subscript)}</Component>
+                        </Component>
+                    ) : (
+                        <Component type="Mo">‖</Component>
+                    )}
+                </Component>
+            );
+        case 'Decorated':
+            const Decorated = content.data;
+            if (Decorated.decoration === 'Bold') {
+                return (
+                    <Component
+                        type="Mrow"
+                        _props={{
+                            id: node.id,
+                            _classNames: styles.editable_span,
+                            style: {
+                                fontWeight: 'bold',
+                            },
+                        }}
+                    >
+                        {renderMathNode(Decorated.inner)}
+                    </Component>
+                );
+            }
+            return (
+                <Component
+                    type="Mover"
+                    _props={{
+                        id: node.id,
+                        _classNames: styles.editable_span,
+                    }}
+                >
+                    <Component type="Mrow">{renderMathNode(Decorated.inner)}</Component>
+                    <Component type="Mo">
+                        {Decorated.decoration === 'Hat' ? _SpecialMiddleScriptContent['Hat'] : '→'}
+                    </Component>
+                </Component>
+            );
+        case 'ChemicalExpression':
+            const ChemicalExpression = content.data;
+            return (
+                <Component
+                    type="Mrow"
+                    _props={{
+                        id: node.id,
+                        _classNames: styles.editable_span,
+                    }}
+                >
+                    {renderChemicalTerms(ChemicalExpression.terms)}
+                    {(ChemicalExpression.steps ?? []).map((step, index) => (
+                        <Component type="Mrow" key={index}>
+                            <Component type="Mspace" _props={{ width: '0.278' }}></Component>
+                            {renderReactionArrow(step)}
+                            <Component type="Mspace" _props={{ width: '0.278' }}></Component>
+                            {renderChemicalTerms(step.terms)}
+                        </Component>
+                    ))}
+                </Component>
+            );
+        case 'Brace':
+            const Brace = content.data;
+            // The brace next to the expression, the label beyond it
+            if (Brace.position === 'Over') {
+                return (
+                    <Component
+                        type="Mover"
+                        _props={{
+                            id: node.id,
+                            _classNames: styles.editable_span,
+                        }}
+                    >
+                        <Component type="Mover">
+                            <Component type="Mrow">{renderMathNode(Brace.inner)}</Component>
+                            <Component type="Mo">⏞</Component>
+                        </Component>
+                        <Component type="Mrow">{renderMathNode(Brace.label)}</Component>
+                    </Component>
+                );
+            }
+            return (
+                <Component
+                    type="Munder"
+                    _props={{
+                        id: node.id,
+                        _classNames: styles.editable_span,
+                    }}
+                >
+                    <Component type="Munder">
+                        <Component type="Mrow">{renderMathNode(Brace.inner)}</Component>
+                        <Component type="Mo">⏟</Component>
+                    </Component>
+                    <Component type="Mrow">{renderMathNode(Brace.label)}</Component>
+                </Component>
+            );
+        case 'Phantom':
+            const Phantom = content.data;
+            return (
+                <Component
+                    type="Mrow"
+                    _props={{
+                        id: node.id,
+                        _classNames: styles.editable_span,
+                        style: {
+                            visibility: 'hidden',
+                            ...(Phantom.extent === 'Horizontal' && { height: 0, overflow: 'hidden' }),
+                            ...(Phantom.extent === 'Vertical' && { width: 0, overflow: 'hidden' }),
+                        },
+                    }}
+                >
+                    {renderMathNode(Phantom.inner)}
+                </Component>
+            );
+        case 'Space':
+            const Space = content.data;
+            const spaceWidth = typeof Space === 'string' ? _mathSpace[Space] : texLength(Space.Custom);
+            // A negative space pulls what follows back over it
+            return (
+                <span
+                    data-id={node.id}
+                    className={classNames(styles.gap)}
+                    style={spaceWidth.startsWith('-') ? { marginLeft: spaceWidth } : { width: spaceWidth }}
+                ></span>
+            );
+        case 'Styled':
+            const Styled = content.data;
+            return (
+                <Component
+                    type="Mrow"
+                    _props={{
+                        id: node.id,
+                        _classNames: styles.editable_span,
+                        style: {
+                            color: Styled.color ?? undefined,
+                            background: Styled.background ?? undefined,
+                            ...(Styled.emphasis && _mathEmphasis[Styled.emphasis]),
+                        },
+                    }}
+                >
+                    {renderMathNode(Styled.inner)}
+                </Component>
+            );
+        case 'Cancelled':
+            const Cancelled = content.data;
+            const struck = (
+                <span className={classNames(styles.cancel)} data-cancel={Cancelled.cancel_style}>
+                    {renderMathNode(Cancelled.inner)}
+                </span>
+            );
+            return (
+                <Component
+                    type="Mrow"
+                    _props={{
+                        id: node.id,
+                        _classNames: styles.editable_span,
+                    }}
+                >
+                    {Cancelled.replacement ? (
+                        // What the term cancels to, at the top end of the stroke
+                        <Component type="Msup">
+                            {struck}
+                            <Component type="Mrow">{renderMathNode(Cancelled.replacement)}</Component>
+                        </Component>
+                    ) : (
+                        struck
+                    )}
+                </Component>
+            );
+        case 'AngleMeasure':
+            const AngleMeasure = content.data;
+            return (
+                <Component
+                    type="Mrow"
+                    _props={{
+                        id: node.id,
+                        _classNames: styles.editable_span,
+                    }}
+                >
+                    {renderMathNode(AngleMeasure.value)}
+                    {AngleMeasure.unit === 'Degrees' ? (
+                        <Component type="Mo">°</Component>
+                    ) : (
+                        <>
+                            <Component type="Mspace" _props={{ width: '0.167' }}></Component>
+                            <Component type="Mtext">rad</Component>
+                        </>
                     )}
                 </Component>
             );
-        case 'Additions':
-            const { Additions } = node.content as Extract<MathNodeContent, { Additions: any }>;
-            return (
-                <>
-                    <Component
-                        type="Mrow"
-                        _props={{
-                            id: node.id,
-                            _classNames: styles.editable_span,
-                        }}
-                    >
-                        {Additions.terms.map(([type, node], ind) => {
-                            return (
-                                <Component type="Morw" key={ind}>
-                                    {type === 'None' ? null : (
-                                        <Component
-                                            type="Mo"
-                                            _props={{
-                                                isUnit: ind == 0,
-                                            }}
-                                        >
-                                            {_AddOrSubOperator[type]}
-                                        </Component>
-                                    )}
-                                    {renderMathNode(node)}
-                                </Component>
-                            );
-                        })}
-                    </Component>
-                </>
-            );
-        case 'Division':
-            const { Division } = node.content as Extract<MathNodeContent, { Division: any }>;
-            let result;
-            switch (Division.style) {
-                case 'Division':
-                    result = (
-                        <Component
-                            type="Mrow"
-                            _props={{
-                                id: node.id,
-                                _classNames: styles.editable_span,
-                            }}
-                        >
-                            {renderMathNode(Division.numerator)}
-                            <Component type="Mo">÷</Component>
-                            {renderMathNode(Division.denominator)}
-                        </Component>
-                    );
-                    break;
-                case 'Fraction':
-                    result = (
-                        <Component
-                            type="Mfrac"
-                            _props={{
-                                id: node.id,
-                                _classNames: styles.editable_span,
-                            }}
-                        >
-                            <Component type="Mrow">{renderMathNode(Division.numerator)}</Component>
-                            <Component type="Mrow">
-                                {renderMathNode(Division.denominator)}
-                            </Component>
-                        </Component>
-                    );
-                    break;
-                case 'Inline':
-                    result = (
-                        <Component
-                            type="Mrow"
-                            _props={{
-                                id: node.id,
-                                _classNames: styles.editable_span,
-                            }}
-                        >
-                            {renderMathNode(Division.numerator)}
-                            <Component type="Mo">/</Component>
-                            {renderMathNode(Division.denominator)}
-                        </Component>
-                    );
-                    break;
-                default:
-                    result = '';
-            }
-
-            return result;
-        case 'SumNotation':
-            const { SumNotation } = node.content as Extract<MathNodeContent, { SumNotation: any }>;
+        case 'AngleSymbol':
+            const AngleSymbol = content.data;
             return (
                 <Component
                     type="Mrow"
@@ -1690,47 +2682,16 @@ export const renderMathNode = (node: MathNode): React.ReactNode => {
                         _classNames: styles.editable_span,
                     }}
                 >
-                    <Component type="Munderover">
-                        <Component type="Mo">∑</Component>
-                        {SumNotation.lower_limit && (
-                            <Component type="Mrow">
-                                <Component type="Mrow">
-                                    {renderMathNode(SumNotation.lower_limit)}
-                                </Component>
-                            </Component>
-                        )}
-                        {SumNotation.upper_limit && (
-                            <Component type="Mrow">
-                                <Component type="Mrow">
-                                    {renderMathNode(SumNotation.upper_limit)}
-                                </Component>
-                            </Component>
-                        )}
-                    </Component>
-                    <Component
-                        type="Mspace"
-                        _props={{
-                            width: '0.167',
-                        }}
-                    ></Component>
-                    <Component type="Mrow">
-                        <Component type="Msub">
-                            {renderMathNode(SumNotation.summand)}
-                            {SumNotation.variable && (
-                                <Component type="Mrow">
-                                    {renderMathNode(SumNotation.variable)}
-                                </Component>
-                            )}
+                    <Component type="Mo">∠</Component>
+                    {AngleSymbol.vertices.map((vertex, ind) => (
+                        <Component type="Mrow" key={ind}>
+                            {renderMathNode(vertex)}
                         </Component>
-                    </Component>
+                    ))}
                 </Component>
             );
-
-        case 'ProductNotation':
-            const { ProductNotation } = node.content as Extract<
-                MathNodeContent,
-                { ProductNotation: any }
-            >;
+        case 'Coordinates':
+            const Coordinates = content.data;
             return (
                 <Component
                     type="Mrow"
@@ -1739,58 +2700,49 @@ export const renderMathNode = (node: MathNode): React.ReactNode => {
                         _classNames: styles.editable_span,
                     }}
                 >
-                    <Component type="Munderover">
-                        <Component type="Mo">∏</Component>
-                        {ProductNotation.lower_limit && (
-                            <Component type="Mrow">
-                                <Component type="Mrow">
-                                    {renderMathNode(ProductNotation.lower_limit)}
-                                </Component>
-                            </Component>
-                        )}
-                        {ProductNotation.upper_limit && (
-                            <Component type="Mrow">
-                                <Component type="Mrow">
-                                    {renderMathNode(ProductNotation.upper_limit)}
-                                </Component>
+                    {Coordinates.point && renderMathNode(Coordinates.point)}
+                    <Component type="Mo">(</Component>
+                    {Coordinates.components.map((item, ind) => {
+                        return (
+                            <Component type="Mrow" key={ind}>
+                                {renderMathNode(item)}
+                                {ind < Coordinates.components.length - 1 && (
+                                    <Component type="Mo">,</Component>
+                                )}
                             </Component>
-                        )}
-                    </Component>
-                    <Component
-                        type="Mspace"
-                        _props={{
-                            width: '0.167',
-                        }}
-                    ></Component>
-                    <Component type="Mrow">
-                        <Component type="Msub">
-                            {renderMathNode(ProductNotation.multiplicand)}
-                            {ProductNotation.variable && (
-                                <Component type="Mrow">
-                                    {renderMathNode(ProductNotation.variable)}
-                                </Component>
-                            )}
-                        </Component>
-                    </Component>
+                        );
+                    })}
+                    <Component type="Mo">)</Component>
                 </Component>
             );
-
-        case 'Fraction':
-            const { Fraction } = node.content as Extract<MathNodeContent, { Fraction: any }>;
+        case 'Sequent':
+            const Sequent = content.data;
             return (
                 <Component
-                    type="Mfrac"
+                    type="Mrow"
                     _props={{
                         id: node.id,
                         _classNames: styles.editable_span,
                     }}
                 >
-                    <Component type="Mrow">{renderMathNode(Fraction.numerator)}</Component>
-                    <Component type="Mrow">{renderMathNode(Fraction.denominator)}</Component>
+                    {Sequent.context.map((item, ind) => {
+                        return (
+                            <Component type="Mrow" key={ind}>
+                                {renderMathNode(item)}
+                                {ind < Sequent.context.length - 1 && (
+                                    <Component type="Mo">,</Component>
+                                )}
+                            </Component>
+                        );
+                    })}
+                    <Component type="Mspace" _props={{ width: '0.278' }}></Component>
+                    <Component type="Mo">{_turnstile[Sequent.turnstile]}</Component>
+                    <Component type="Mspace" _props={{ width: '0.278' }}></Component>
+                    {renderMathNode(Sequent.conclusion)}
                 </Component>
             );
         case 'Bracketed':
-            const { Bracketed } = node.content as Extract<MathNodeContent, { Bracketed: any }>;
+            const Bracketed = content.data;
             return (
                 <Component
                     type="Mrow"
@@ -1805,65 +2757,11 @@ export const renderMathNode = (node: MathNode): React.ReactNode => {
                 </Component>
             );
         case 'Matrix':
-            const { Matrix } = node.content as Extract<MathNodeContent, { Matrix: any }>;
-            const maxLength = Math.max(...Matrix.rows.map((row) => row.length));
-            type MathNodeOrNull = MathNode | null;
-            const filledRows: MathNodeOrNull[][] = [...Matrix.rows].map((row: MathNodeOrNull[]) => {
-                while (row.length < maxLength) {
-                    row.push(null);
-                }
-                return row;
-            });
-
-            return (
-                <Component
-                    type="Mrow"
-                    _props={{
-                        id: node.id,
-                        _classNames: styles.editable_span,
-                        style: {
-                            verticalAlign: filledRows.length > 1 ? 'middle' : 'baseline',
-                        },
-                    }}
-                >
-                    <Component type="Mo">[</Component>
-                    <Component
-                        type="Mrow"
-                        _props={{
-                            style: {
-                                boxSizing: 'border-box',
-                                display: 'inline-block',
-                            },
-                        }}
-                    >
-                        <Component type="Mtable">
-                            {filledRows.map((item, rowIndex) => {
-                                return (
-                                    <Component type="Mtablerow" key={rowIndex}>
-                                        {item.map((cell, cellIndex) => {
-                                            return (
-                                                <Component type="Mtablecell" key={cellIndex}>
-                                                    {cell && renderMathNode(cell)}
-                                                    <span
-                                                        className={classNames(styles.tstrut)}
-                                                    ></span>
-                                                </Component>
-                                            );
-                                        })}
-                                    </Component>
-                                );
-                            })}
-                        </Component>
-                    </Component>
-                    <Component type="Mo">]</Component>
-                </Component>
-            );
+            const Matrix = content.data;
+            return <MatrixGrid id={node.id} matrix={Matrix} />;
 
         case 'UnaryPostfixOperation':
-            const { UnaryPostfixOperation } = node.content as Extract<
-                MathNodeContent,
-                { UnaryPostfixOperation: any }
-            >;
+            const UnaryPostfixOperation = content.data;
             return (
                 <Component
                     type="Mrow"
@@ -1877,7 +2775,7 @@ export const renderMathNode = (node: MathNode): React.ReactNode => {
                 </Component>
             );
         case 'UnaryPrefixOperation':
-            const { UnaryPrefixOperation } = node.content as Extract<MathNodeContent, { UnaryPrefixOperation: any }>;
+            const UnaryPrefixOperation = content.data;
             return (
                 <Component
                     type="Mrow"
@@ -1891,7 +2789,7 @@ export const renderMathNode = (node: MathNode): React.ReactNode => {
                 </Component>
             );
         case 'Abs':
-            const { Abs } = node.content as Extract<MathNodeContent, { Abs: any }>;
+            const Abs = content.data;
             return (
                 <Component
                     type="Mrow"
@@ -1906,7 +2804,7 @@ export const renderMathNode = (node: MathNode): React.ReactNode => {
                 </Component>
             );
         case 'Power':
-            const { Power } = node.content as Extract<MathNodeContent, { Power: any }>;
+            const Power = content.data;
             return (
                 <Component
                     type="Msup"
@@ -1920,10 +2818,7 @@ export const renderMathNode = (node: MathNode): React.ReactNode => {
                 </Component>
             );
         case 'FunctionCall':
-            const { FunctionCall } = node.content as Extract<
-                MathNodeContent,
-                { FunctionCall: any }
-            >;
+            const FunctionCall = content.data;
 
             return (
                 <Component
@@ -1952,22 +2847,10 @@ export const renderMathNode = (node: MathNode): React.ReactNode => {
                 </Component>
             );
         case 'Quantity':
-            const { Quantity } = node.content as Extract<MathNodeContent, { Quantity: any }>;
-            return (
-                <Component
-                    type="Mrow"
-                    _props={{
-                        id: node.id,
-                        _classNames: styles.editable_span,
-                    }}
-                >
-                    {StringMapNode(Quantity.number, node.id)}
-                    {Quantity.scientific_notation && renderMathNode(Quantity.scientific_notation)}
-                    {Quantity.unit && renderMathNode(Quantity.unit)}
-                </Component>
-            );
+            const Quantity = content.data;
+            return <FormattedQuantity id={node.id} quantity={Quantity} />;
         case 'Identifier':
-            const { Identifier } = node.content as Extract<MathNodeContent, { Identifier: any }>;
+            const Identifier = content.data;
             return (
                 <Component
                     type="Mrow"
@@ -2025,7 +2908,7 @@ export const renderMathNode = (node: MathNode): React.ReactNode => {
             );
 
         case 'Unit':
-            const { Unit } = node.content as Extract<MathNodeContent, { Unit: any }>;
+            const Unit = content.data;
             return (
                 <Component
                     type="Mrow"
@@ -2040,20 +2923,7 @@ export const renderMathNode = (node: MathNode): React.ReactNode => {
                 </Component>
             );
         case 'Relationship':
-            const { Relationship } = node.content as Extract<
-                MathNodeContent,
-                { Relationship: any }
-            >;
-            
-            // Get the operator symbol based on the operator type
-            const getRelationOperatorSymbol = (operator: RelationOperatorNode): string => {
-                if (typeof operator === 'string') {
-                    return _relationOperator[operator] || '';
-                } else if (operator && typeof operator === 'object' && 'Custom' in operator) {
-                    return operator.Custom || '';
-                }
-                return '';
-            };
+            const Relationship = content.data;
             
             return (
                 <Component
@@ -2065,16 +2935,13 @@ export const renderMathNode = (node: MathNode): React.ReactNode => {
                 >
                     {renderMathNode(Relationship.lhs)}
                     <Component type="Mo">
-                        {getRelationOperatorSymbol(Relationship.operator)}
+                        {relationOperatorSymbol(Relationship.operator)}
                     </Component>
                     {renderMathNode(Relationship.rhs)}
                 </Component>
             );
         case 'UnaryRelationship':
-            const { UnaryRelationship } = node.content as Extract<
-                MathNodeContent,
-                { UnaryRelationship: { subject: MathNode; predicate: UnaryRelationOperatorNode } }
-            >;
+            const UnaryRelationship = content.data;
 
              // Get the operator symbol based on the operator type
              const getUnaryRelationOperatorSymbol = (operator: UnaryRelationOperatorNode): string => {
@@ -2107,10 +2974,7 @@ export const renderMathNode = (node: MathNode): React.ReactNode => {
                 </Component>
             );
         case 'VariableDefinition':
-            const { VariableDefinition } = node.content as Extract<
-                MathNodeContent,
-                { VariableDefinition: any }
-            >;
+            const VariableDefinition = content.data;
             return (
                 <Component
                     type="Mrow"
@@ -2126,10 +2990,64 @@ export const renderMathNode = (node: MathNode): React.ReactNode => {
             );
 
         case 'FunctionDefinition':
-            const { FunctionDefinition } = node.content as Extract<
-                MathNodeContent,
-                { FunctionDefinition: any }
-            >;
+            const FunctionDefinition = content.data;
+            const clauses = FunctionDefinition.clauses ?? [];
+            if (clauses.length > 0) {
+                // One line per clause, f(0) = 1 over f(n) = n·f(n − 1), n > 0, the equals signs
+                // and conditions in columns
+                const definedFunction = FunctionDefinition.custom_function;
+                const functionName = isMathNodeContentFunctionCall(definedFunction.content)
+                    ? definedFunction.content.data.name
+                    : definedFunction;
+                return (
+                    <Component
+                        type="Mrow"
+                        _props={{
+                            id: node.id,
+                            _classNames: styles.editable_span,
+                            style: {
+                                verticalAlign: clauses.length > 1 ? 'middle' : 'baseline',
+                            },
+                        }}
+                    >
+                        <Component type="Mtable" _props={{ style: { textAlign: 'left' } }}>
+                            {clauses.map((clause, rowIndex) => (
+                                <Component type="Mtablerow" key={rowIndex}>
+                                    <Component type="Mtablecell">
+                                        {renderMathNode(functionName)}
+                                        <Component type="Mo">(</Component>
+                                        {clause.arguments.map((item, ind) => (
+                                            <Component type="Mrow" key={ind}>
+                                                {renderMathNode(item)}
+                                                {ind < clause.arguments.length - 1 && (
+                                                    <Component type="Mo">,</Component>
+                                                )}
+                                            </Component>
+                                        ))}
+                                        <Component type="Mo">)</Component>
+                                    </Component>
+                                    <Component type="Mtablecell">
+                                        <Component type="Mo">=</Component>
+                                        {renderMathNode(clause.definition)}
+                                    </Component>
+                                    <Component type="Mtablecell">
+                                        {clause.condition && (
+                                            <>
+                                                <Component type="Mo">,</Component>
+                                                <Component
+                                                    type="Mspace"
+                                                    _props={{ width: '1' }}
+                                                ></Component>
+                                                {renderMathNode(clause.condition)}
+                                            </>
+                                        )}
+                                    </Component>
+                                </Component>
+                            ))}
+                        </Component>
+                    </Component>
+                );
+            }
             return (
                 <Component
                     type="Mrow"
@@ -2151,10 +3069,7 @@ export const renderMathNode = (node: MathNode): React.ReactNode => {
         // - GroupSemidirectProduct: BinaryOperation { operation_type: GroupSemidirectProduct, terms: [(SemidirectProduct, left_group), (None, right_group)] }
 
         case 'BinaryOperation':
-            const { BinaryOperation } = node.content as Extract<
-                MathNodeContent,
-                { BinaryOperation: { operation_type: any; terms: Array<[any, MathNode]> } }
-            >;
+            const BinaryOperation = content.data;
             return (
                 <Component
                     type="Mrow"
@@ -2185,8 +3100,7 @@ export const renderMathNode = (node: MathNode): React.ReactNode => {
             );
 
         case 'RichTextContent':
-            // Cast to access the segments directly
-            const segments = (node.content as any).RichTextContent;
+            const segments = content.data;
             
             // Add safety check for segments
             if (!segments || !Array.isArray(segments)) {
@@ -2348,7 +3262,7 @@ export const renderMathNode = (node: MathNode): React.ReactNode => {
             );
 
         case 'And':
-            const { And } = node.content as Extract<MathNodeContent, { And: MathNode[] }>;
+            const And = content.data;
             return (
                 <Component
                     type="Mrow"
@@ -2379,7 +3293,7 @@ export const renderMathNode = (node: MathNode): React.ReactNode => {
             );
 
         case 'Or':
-            const { Or } = node.content as Extract<MathNodeContent, { Or: MathNode[] }>;
+            const Or = content.data;
             return (
                 <Component
                     type="Mrow"
@@ -2410,7 +3324,7 @@ export const renderMathNode = (node: MathNode): React.ReactNode => {
             );
 
         case 'Not':
-            const { Not } = node.content as Extract<MathNodeContent, { Not: MathNode }>;
+            const Not = content.data;
             return (
                 <Component
                     type="Mrow"
@@ -2449,6 +3363,12 @@ export const renderMathNode = (node: MathNode): React.ReactNode => {
                     <Component type="Mo">⊥</Component>
                 </Component>
             );
+
+        case 'Folded':
+            const FoldedContent = content.data;
+            return (
+                <Folded id={node.id} summary={FoldedContent.summary} content={FoldedContent.content} />
+            );
     }
     return null;
 };
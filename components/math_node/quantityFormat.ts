@@ -0,0 +1,204 @@
+import type { MathNode } from '../../bindings/MathNode.ts';
+import type { QuantityFormat } from '../../bindings/QuantityFormat.ts';
+import type { ScientificNotationStyle } from '../../bindings/ScientificNotationStyle.ts';
+import { isMathNodeContentQuantity, isMathNodeContentScientificNotation } from '../../bindings/index.ts';
+
+// A port of quantity_format.rs, the reference implementation, so that quantities read the
+// same here as in the other backends. Keep the two in step.
+
+// Numbers with a larger exponent are left as stored rather than written out in full
+const MAX_EXPONENT = 1000;
+
+// An exact decimal: digits × 10^exponent, with digits free of leading zeros
+interface Decimal {
+    negative: boolean;
+    digits: number[];
+    exponent: number;
+}
+
+// Parses plain and exponent forms such as -1234.50, .5 and 6.02e23
+const parseDecimal = (number: string): Decimal | null => {
+    const trimmed = number.trim();
+    let negative = false;
+    let unsigned = trimmed;
+    if (trimmed.startsWith('-') || trimmed.startsWith('−')) {
+        negative = true;
+        unsigned = trimmed.slice(1);
+    } else if (trimmed.startsWith('+')) {
+        unsigned = trimmed.slice(1);
+    }
+
+    let mantissa = unsigned;
+    let exponent = 0;
+    const e = unsigned.search(/[eE]/);
+    if (e >= 0) {
+        mantissa = unsigned.slice(0, e);
+        const power = unsigned.slice(e + 1);
+        if (!/^[+-]?\d+$/.test(power)) return null;
+        exponent = parseInt(power, 10);
+        if (Math.abs(exponent) > MAX_EXPONENT) return null;
+    }
+
+    const point = mantissa.indexOf('.');
+    const integer = point >= 0 ? mantissa.slice(0, point) : mantissa;
+    const fraction = point >= 0 ? mantissa.slice(point + 1) : '';
+    if (!integer && !fraction) return null;
+    if (!/^\d*$/.test(integer + fraction)) return null;
+
+    const digits = [...(integer + fraction)].map(Number);
+    let leadingZeros = 0;
+    while (leadingZeros < digits.length - 1 && digits[leadingZeros] === 0) leadingZeros++;
+    digits.splice(0, leadingZeros);
+    exponent -= fraction.length;
+    // Trailing zeros of a whole number are not significant: 12300 has three figures
+    if (point < 0) {
+        while (digits.length > 1 && digits[digits.length - 1] === 0) {
+            digits.pop();
+            exponent += 1;
+        }
+    }
+    return {
+        negative: negative && digits.some((digit) => digit !== 0),
+        digits,
+        exponent,
+    };
+};
+
+const isZero = (decimal: Decimal) => decimal.digits.every((digit) => digit === 0);
+
+// Rounds half away from zero, or pads with zeros, to the given significant digits
+const roundTo = (decimal: Decimal, figures: number) => {
+    figures = Math.max(figures, 1);
+    if (isZero(decimal)) {
+        decimal.exponent -= figures - decimal.digits.length;
+        decimal.digits = new Array(figures).fill(0);
+        return;
+    }
+    if (decimal.digits.length <= figures) {
+        const padding = figures - decimal.digits.length;
+        decimal.digits.push(...new Array(padding).fill(0));
+        decimal.exponent -= padding;
+        return;
+    }
+    const roundUp = decimal.digits[figures] >= 5;
+    decimal.exponent += decimal.digits.length - figures;
+    decimal.digits.length = figures;
+    if (roundUp) {
+        let position = figures;
+        for (;;) {
+            if (position === 0) {
+                // 999 → 1000: one more digit, so drop the last to keep the figures
+                decimal.digits.unshift(1);
+                decimal.digits.pop();
+                decimal.exponent += 1;
+                break;
+            }
+            position -= 1;
+            if (decimal.digits[position] === 9) {
+                decimal.digits[position] = 0;
+            } else {
+                decimal.digits[position] += 1;
+                break;
+            }
+        }
+    }
+};
+
+// The power of ten of the leading digit, 2 for 123
+const magnitudeOf = (decimal: Decimal) => decimal.digits.length - 1 + decimal.exponent;
+
+// digits × 10^exponent written out with the locale's separators
+const positional = (digits: number[], exponent: number, format: QuantityFormat): string => {
+    let integer: string;
+    let fraction = '';
+    if (exponent >= 0) {
+        integer = digits.join('') + '0'.repeat(exponent);
+    } else {
+        const point = digits.length + exponent;
+        if (point <= 0) {
+            integer = '0';
+            fraction = '0'.repeat(-point) + digits.join('');
+        } else {
+            integer = digits.slice(0, point).join('');
+            fraction = digits.slice(point).join('');
+        }
+    }
+
+    let text = '';
+    const separator = format.group_digits ? format.locale.group_separator : null;
+    if (separator) {
+        [...integer].forEach((c, index) => {
+            if (index > 0 && (integer.length - index) % 3 === 0) text += separator;
+            text += c;
+        });
+    } else {
+        text += integer;
+    }
+    if (fraction) {
+        text += format.locale.decimal_separator + fraction;
+    }
+    return text;
+};
+
+const SUPERSCRIPT_DIGITS = '⁰¹²³⁴⁵⁶⁷⁸⁹';
+
+const exponentText = (power: number, style: ScientificNotationStyle): string => {
+    switch (style) {
+        case 'LowerCaseE':
+            return `e${power}`;
+        case 'UpperCaseE':
+            return `E${power}`;
+        case 'TimesTenPower':
+            return (
+                '×10' +
+                [...String(power)].map((c) => (c === '-' ? '⁻' : SUPERSCRIPT_DIGITS[Number(c)])).join('')
+            );
+    }
+};
+
+// Writes a Quantity number in the format, or returns null if it is not a decimal number,
+// which is then shown as stored
+export const formatNumber = (number: string, format: QuantityFormat): string | null => {
+    const decimal = parseDecimal(number);
+    if (!decimal) return null;
+    if (format.significant_figures != null) {
+        roundTo(decimal, format.significant_figures);
+    }
+
+    let power = 0;
+    if (format.notation !== 'Plain' && !isZero(decimal)) {
+        const magnitude = magnitudeOf(decimal);
+        power = format.notation === 'Scientific' ? magnitude : magnitude - (((magnitude % 3) + 3) % 3);
+    }
+
+    let text = decimal.negative ? '-' : '';
+    text += positional(decimal.digits, decimal.exponent - power, format);
+    if (format.notation !== 'Plain') {
+        text += exponentText(power, format.exponent_style);
+    }
+    return text;
+};
+
+// The number of a Quantity in the format, with an integer ScientificNotation magnitude folded
+// in, so that 1.5 × 10³ in plain notation becomes 1500; null when it cannot be formatted
+export const formatQuantity = (
+    quantity: { number: string; scientific_notation: MathNode | null },
+    format: QuantityFormat
+): string | null => {
+    let magnitude = 0;
+    if (quantity.scientific_notation) {
+        const notation = quantity.scientific_notation.content;
+        if (!isMathNodeContentScientificNotation(notation)) return null;
+        const inner = notation.data.magnitude.content;
+        if (!isMathNodeContentQuantity(inner) || inner.data.scientific_notation) return null;
+        const power = inner.data.number.trim();
+        if (!/^[+-]?\d+$/.test(power)) return null;
+        magnitude = parseInt(power, 10);
+    }
+
+    const decimal = parseDecimal(quantity.number);
+    if (!decimal) return null;
+    decimal.exponent += Math.max(-MAX_EXPONENT, Math.min(MAX_EXPONENT, magnitude));
+    const digits = (decimal.negative ? '-' : '') + decimal.digits.join('');
+    return formatNumber(`${digits}e${decimal.exponent}`, format);
+};
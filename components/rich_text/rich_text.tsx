@@ -6,11 +6,11 @@ import { renderMathNode } from '../math_node/math_node';
 import styles from './rich_text.module.scss';
 
 interface RichTextProps {
-  segments: RichTextSegment[];
+  segments?: RichTextSegment[];
   alignment?: string | null;
 }
 
-export const RichText: React.FC<RichTextProps> = ({ segments, alignment }) => {
+export const RichText: React.FC<RichTextProps> = ({ segments = [], alignment }) => {
   const renderSegment = (segment: RichTextSegment, index: number) => {
     if ('Text' in segment) {
       return <span key={index}>{segment.Text}</span>;
@@ -87,9 +87,9 @@ export const RichText: React.FC<RichTextProps> = ({ segments, alignment }) => {
 
 // RichTextRenderer - handles RichText segments with className
 export const RichTextRenderer: React.FC<{ 
-  segments: RichTextSegment[]; 
+  segments?: RichTextSegment[]; 
   className?: string;
-}> = ({ segments, className }) => (
+}> = ({ segments = [], className }) => (
   <span className={className}>
     {segments.map((segment, index) => {
       if ('Text' in segment) {
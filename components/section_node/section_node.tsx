@@ -1,11 +1,12 @@
-import React from 'react';
-import { renderMathNode } from '../math_node/math_node.tsx';
+import React, { useState } from 'react';
+import { renderMathNode, relationOperatorSymbol } from '../math_node/math_node.tsx';
 import { RichTextRenderer, ParagraphRenderer } from '../rich_text/rich_text.tsx';
 import { SecondOrderMathNodeRenderer, CollapsibleBlockRenderer } from '../structured_math_node/structured_math_node.tsx';
 import { HighlightableComponent } from '../structured_math_node/math_highlighting_wrapper.tsx';
 
 // Import proper binding types instead of duplicating interfaces
 import type { SectionContentNode } from '../../bindings/SectionContentNode';
+import { sectionContentNodeTag } from '../../bindings/index.ts';
 import type { Section } from '../../bindings/Section.ts';
 import type { RichText } from '../../bindings/RichText.ts';
 import type { MathNode } from '../../bindings/MathNode.ts';
@@ -15,6 +16,12 @@ import type { SecondOrderMathNode } from '../../bindings/SecondOrderMathNode.ts'
 import type { CodeBlockNode } from '../../bindings/CodeBlockNode.ts';
 import type { ImageNode } from '../../bindings/ImageNode.ts';
 import type { BranchingContainer } from '../../bindings/BranchingContainer.ts';
+import type { FeedbackNode } from '../../bindings/FeedbackNode.ts';
+import type { FeedbackResponse } from '../../bindings/FeedbackResponse.ts';
+import type { FeedbackValue } from '../../bindings/FeedbackValue.ts';
+import type { GenerationErrorNode } from '../../bindings/GenerationErrorNode.ts';
+import type { DerivationChain } from '../../bindings/DerivationChain.ts';
+import type { Equation } from '../../bindings/Equation.ts';
 
 
 import styles from './section_node.module.scss';
@@ -55,7 +62,7 @@ const SectionRenderer: React.FC<{ section: Section }> = ({ section }) => {
           <h2 className={styles.sectionTitle}>
             <RichTextRenderer segments={section.title.segments} />
           </h2>
-          {section.metadata.length > 0 && (
+          {(section.metadata ?? []).length > 0 && (
             <div className={styles.sectionMeta}>
               <span className={styles.level}>Level {sectionLevel}</span>
               {sectionType !== 'general' && (
@@ -75,17 +82,14 @@ const SectionRenderer: React.FC<{ section: Section }> = ({ section }) => {
 
 // ContentNodeRenderer - handles all SectionContentNode variants
 const ContentNodeRenderer: React.FC<{ node: SectionContentNode; context?: any }> = ({ node, context }) => {
-  // Get the variant key from the union type
-  const variantKey = Object.keys(node)[0] as keyof SectionContentNode;
-  
-  switch (variantKey) {
+  switch (node.kind) {
     case 'RichText': {
-      const { RichText } = node as Extract<SectionContentNode, { RichText: RichText }>;
+      const RichText = node.data;
       return <ParagraphRenderer paragraph={RichText} />;
     }
     
     case 'Math': {
-      const { Math } = node as Extract<SectionContentNode, { Math: MathNode }>;
+      const Math = node.data;
       return (
         <div className={styles.mathBlock}>
           <div className={styles.mathContent}>
@@ -102,110 +106,130 @@ const ContentNodeRenderer: React.FC<{ node: SectionContentNode; context?: any }>
     }
     
     case 'SecondOrderMath': {
-      const { SecondOrderMath } = node as Extract<SectionContentNode, { SecondOrderMath: SecondOrderMathNode }>;
+      const SecondOrderMath = node.data;
       return <SecondOrderMathNodeRenderer secondOrderMath={SecondOrderMath} />;
     }
     
     case 'BranchingContainer': {
-      const { BranchingContainer } = node as Extract<SectionContentNode, { BranchingContainer: BranchingContainer }>;
+      const BranchingContainer = node.data;
       return <BranchingContainerRenderer container={BranchingContainer} />;
     }
     
     case 'List': {
-      const { List } = node as Extract<SectionContentNode, { List: ListNode }>;
+      const List = node.data;
       return <ListRenderer list={List} />;
     }
     
     case 'Table': {
-      const { Table } = node as Extract<SectionContentNode, { Table: TableNode }>;
+      const Table = node.data;
       return <TableRenderer table={Table} />;
     }
     
     case 'CodeBlock': {
-      const { CodeBlock } = node as Extract<SectionContentNode, { CodeBlock: CodeBlockNode }>;
+      const CodeBlock = node.data;
       return <CodeBlockRenderer codeBlock={CodeBlock} />;
     }
     
     case 'Image': {
-      const { Image } = node as Extract<SectionContentNode, { Image: ImageNode }>;
+      const Image = node.data;
       return <ImageRenderer image={Image} />;
     }
     
     case 'InteractiveDiagram': {
-      const { InteractiveDiagram } = node as Extract<SectionContentNode, { InteractiveDiagram: any }>;
+      const InteractiveDiagram = node.data;
       return <InteractiveDiagramRenderer diagram={InteractiveDiagram} />;
     }
     
     case 'CollapsibleBlock': {
-      const { CollapsibleBlock } = node as Extract<SectionContentNode, { CollapsibleBlock: any }>;
+      const CollapsibleBlock = node.data;
       return <CollapsibleBlockRenderer block={CollapsibleBlock} />;
     }
     
     case 'Grid': {
-      const { Grid } = node as Extract<SectionContentNode, { Grid: any }>;
+      const Grid = node.data;
       return <GridRenderer grid={Grid} />;
     }
     
     case 'Columns': {
-      const { Columns } = node as Extract<SectionContentNode, { Columns: any }>;
+      const Columns = node.data;
       return <ColumnsRenderer columns={Columns} />;
     }
     
     case 'ThematicBreak': {
-      const { ThematicBreak } = node as Extract<SectionContentNode, { ThematicBreak: any }>;
+      const ThematicBreak = node.data;
       return <ThematicBreakRenderer break={ThematicBreak} />;
     }
     
     case 'QuoteBlock': {
-      const { QuoteBlock } = node as Extract<SectionContentNode, { QuoteBlock: { content: RichText[]; attribution: RichText | null } }>;
+      const QuoteBlock = node.data;
       return <QuoteBlockRenderer quote={QuoteBlock} />;
     }
     
     case 'AlertBox': {
-      const { AlertBox } = node as Extract<SectionContentNode, { AlertBox: any }>;
+      const AlertBox = node.data;
       return <AlertBoxRenderer alert={AlertBox} />;
     }
     
     case 'CustomComponent': {
-      const { CustomComponent } = node as Extract<SectionContentNode, { CustomComponent: any }>;
+      const CustomComponent = node.data;
       return <CustomComponentRenderer component={CustomComponent} />;
     }
     
     case 'EmbeddedSectionRef': {
-      const { EmbeddedSectionRef } = node as Extract<SectionContentNode, { EmbeddedSectionRef: string }>;
+      const EmbeddedSectionRef = node.data;
       return <EmbeddedSectionRefRenderer ref={EmbeddedSectionRef} />;
     }
     
     case 'SubSection': {
-      const { SubSection } = node as Extract<SectionContentNode, { SubSection: Array<Section> }>;
+      const SubSection = node.data;
       return <SubSectionRenderer subSections={SubSection} />;
     }
     
     case 'SideBySideLayout': {
-      const { SideBySideLayout } = node as Extract<SectionContentNode, { SideBySideLayout: any }>;
+      const SideBySideLayout = node.data;
       return <SideBySideLayoutRenderer layout={SideBySideLayout} />;
     }
     
     case 'PanelLayout': {
-      const { PanelLayout } = node as Extract<SectionContentNode, { PanelLayout: any }>;
+      const PanelLayout = node.data;
       return <PanelLayoutRenderer layout={PanelLayout} />;
     }
     
     case 'AnnotationOverlay': {
-      const { AnnotationOverlay } = node as Extract<SectionContentNode, { AnnotationOverlay: any }>;
+      const AnnotationOverlay = node.data;
       return <AnnotationOverlayRenderer overlay={AnnotationOverlay} />;
     }
     
     case 'InteractiveControls': {
-      const { InteractiveControls } = node as Extract<SectionContentNode, { InteractiveControls: any }>;
+      const InteractiveControls = node.data;
       return <InteractiveControlsRenderer controls={InteractiveControls} />;
     }
     
     case 'EmbeddedDocument': {
-      const { EmbeddedDocument } = node as Extract<SectionContentNode, { EmbeddedDocument: any }>;
+      const EmbeddedDocument = node.data;
       return <EmbeddedDocumentRenderer document={EmbeddedDocument} />;
     }
     
+    case 'Feedback': {
+      const Feedback = node.data;
+      return <FeedbackRenderer feedback={Feedback} />;
+    }
+    
+    case 'GenerationError': {
+      const GenerationError = node.data;
+      return <GenerationErrorRenderer error={GenerationError} />;
+    }
+    
+    case 'DerivationChain': {
+      const DerivationChain = node.data;
+      return <DerivationChainRenderer chain={DerivationChain} />;
+    }
+    
+    case 'Equation': {
+      const Equation = node.data;
+      return <EquationRenderer equation={Equation} context={context} />;
+    }
+    
     default:
       return <UnknownContentRenderer node={node} />;
   }
@@ -224,9 +248,9 @@ const ListRenderer: React.FC<{ list: ListNode }> = ({ list }) => {
     <div className={styles.list}>
       {isOrdered ? (
         <ol className={styles.listItems} start={list.start_index || 1}>
-          {list.items.map((item, index) => (
+          {(list.items ?? []).map((item, index) => (
             <li key={index} className={styles.listItem}>
-              {item.content.map((itemNode, itemIndex) => (
+              {(item.content ?? []).map((itemNode, itemIndex) => (
                 <ContentNodeRenderer key={itemIndex} node={itemNode} />
               ))}
             </li>
@@ -234,9 +258,9 @@ const ListRenderer: React.FC<{ list: ListNode }> = ({ list }) => {
         </ol>
       ) : (
         <ul className={styles.listItems}>
-          {list.items.map((item, index) => (
+          {(list.items ?? []).map((item, index) => (
             <li key={index} className={styles.listItem}>
-              {item.content.map((itemNode, itemIndex) => (
+              {(item.content ?? []).map((itemNode, itemIndex) => (
                 <ContentNodeRenderer key={itemIndex} node={itemNode} />
               ))}
             </li>
@@ -255,9 +279,9 @@ const TableRenderer: React.FC<{ table: TableNode }> = ({ table }) => (
       </div>
     )}
     <table className={styles.tableElement}>
-      {table.header_rows.length > 0 && (
+      {(table.header_rows ?? []).length > 0 && (
         <thead>
-          {table.header_rows.map((row, rowIndex) => (
+          {(table.header_rows ?? []).map((row, rowIndex) => (
             <tr key={rowIndex}>
               {row.cells.map((cell, cellIndex) => (
                 <th 
@@ -266,7 +290,7 @@ const TableRenderer: React.FC<{ table: TableNode }> = ({ table }) => (
                   colSpan={cell.col_span || 1}
                   rowSpan={cell.row_span || 1}
                 >
-                  {cell.content.map((cellNode, cellNodeIndex) => (
+                  {(cell.content ?? []).map((cellNode, cellNodeIndex) => (
                     <ContentNodeRenderer key={cellNodeIndex} node={cellNode} />
                   ))}
                 </th>
@@ -276,7 +300,7 @@ const TableRenderer: React.FC<{ table: TableNode }> = ({ table }) => (
         </thead>
       )}
       <tbody>
-        {table.body_rows.map((row, rowIndex) => (
+        {(table.body_rows ?? []).map((row, rowIndex) => (
           <tr key={rowIndex}>
             {row.cells.map((cell, cellIndex) => (
               <td 
@@ -285,7 +309,7 @@ const TableRenderer: React.FC<{ table: TableNode }> = ({ table }) => (
                 colSpan={cell.col_span || 1}
                 rowSpan={cell.row_span || 1}
               >
-                {cell.content.map((cellNode, cellNodeIndex) => (
+                {(cell.content ?? []).map((cellNode, cellNodeIndex) => (
                   <ContentNodeRenderer key={cellNodeIndex} node={cellNode} />
                 ))}
               </td>
@@ -293,9 +317,9 @@ const TableRenderer: React.FC<{ table: TableNode }> = ({ table }) => (
           </tr>
         ))}
       </tbody>
-      {table.footer_rows.length > 0 && (
+      {(table.footer_rows ?? []).length > 0 && (
         <tfoot>
-          {table.footer_rows.map((row, rowIndex) => (
+          {(table.footer_rows ?? []).map((row, rowIndex) => (
             <tr key={rowIndex}>
               {row.cells.map((cell, cellIndex) => (
                 <td 
@@ -304,7 +328,7 @@ const TableRenderer: React.FC<{ table: TableNode }> = ({ table }) => (
                   colSpan={cell.col_span || 1}
                   rowSpan={cell.row_span || 1}
                 >
-                  {cell.content.map((cellNode, cellNodeIndex) => (
+                  {(cell.content ?? []).map((cellNode, cellNodeIndex) => (
                     <ContentNodeRenderer key={cellNodeIndex} node={cellNode} />
                   ))}
                 </td>
@@ -435,10 +459,160 @@ const EmbeddedDocumentRenderer: React.FC<{ document: any }> = () => (
   <div className={styles.placeholder}>[Embedded Document - Not yet implemented]</div>
 );
 
+// Answers are dispatched as a bubbling `turn-render:feedback` event whose detail is a
+// FeedbackResponse, so the host page only has to listen and forward them.
+const FeedbackRenderer: React.FC<{ feedback: FeedbackNode }> = ({ feedback }) => {
+  const [value, setValue] = useState<FeedbackValue | null>(null);
+  const [comment, setComment] = useState('');
+  const [submitted, setSubmitted] = useState(false);
+  const kind = feedback.response_kind;
+
+  const submit = (event: React.MouseEvent<HTMLElement>) => {
+    if (value === null) return;
+    const response: FeedbackResponse = {
+      feedback_id: feedback.id,
+      value,
+      comment: feedback.allow_comment && comment.trim() ? comment.trim() : null,
+    };
+    event.currentTarget.dispatchEvent(
+      new CustomEvent('turn-render:feedback', { detail: response, bubbles: true })
+    );
+    setSubmitted(true);
+  };
+
+  const isSelected = (candidate: FeedbackValue) => JSON.stringify(candidate) === JSON.stringify(value);
+  const option = (label: string, candidate: FeedbackValue, key: number | string) => (
+    <button
+      key={key}
+      type="button"
+      className={`${styles.feedbackOption} ${isSelected(candidate) ? styles.feedbackSelected : ''}`}
+      onClick={() => setValue(candidate)}
+    >
+      {label}
+    </button>
+  );
+
+  if (submitted) {
+    return <div className={styles.feedback}>Thanks for your feedback.</div>;
+  }
+
+  return (
+    <div className={styles.feedback} data-feedback-id={feedback.id} data-target-id={feedback.target_id ?? undefined}>
+      <div className={styles.feedbackPrompt}>
+        <RichTextRenderer segments={feedback.prompt.segments} />
+      </div>
+      <div className={styles.feedbackOptions}>
+        {kind === 'YesNo' && [option('Yes', { YesNo: true }, 'yes'), option('No', { YesNo: false }, 'no')]}
+        {typeof kind === 'object' && 'EmojiScale' in kind &&
+          kind.EmojiScale.options.map((emoji, index) => option(emoji, { Scale: index }, index))}
+        {typeof kind === 'object' && 'Rating' in kind &&
+          Array.from({ length: kind.Rating.max - kind.Rating.min + 1 }, (_, i) => kind.Rating.min + i)
+            .map((rating) => option(String(rating), { Rating: rating }, rating))}
+        {typeof kind === 'object' && 'Choice' in kind &&
+          kind.Choice.options.map((label, index) => {
+            const chosen = value !== null && typeof value === 'object' && 'Choices' in value ? value.Choices : [];
+            const selected = chosen.includes(index);
+            const next = kind.Choice.multiple
+              ? (selected ? chosen.filter((i) => i !== index) : [...chosen, index])
+              : [index];
+            return (
+              <button
+                key={index}
+                type="button"
+                className={`${styles.feedbackOption} ${selected ? styles.feedbackSelected : ''}`}
+                onClick={() => setValue(next.length > 0 ? { Choices: next } : null)}
+              >
+                {label}
+              </button>
+            );
+          })}
+        {typeof kind === 'object' && 'FreeText' in kind && (
+          <textarea
+            className={styles.feedbackText}
+            placeholder={kind.FreeText.placeholder ?? undefined}
+            maxLength={kind.FreeText.max_length ?? undefined}
+            onChange={(event) => setValue(event.target.value.trim() ? { Text: event.target.value } : null)}
+          />
+        )}
+      </div>
+      {feedback.allow_comment && (
+        <textarea
+          className={styles.feedbackText}
+          placeholder="Anything else? (optional)"
+          value={comment}
+          onChange={(event) => setComment(event.target.value)}
+        />
+      )}
+      <button type="button" className={styles.feedbackSubmit} disabled={value === null} onClick={submit}>
+        Send
+      </button>
+    </div>
+  );
+};
+
+const GenerationErrorRenderer: React.FC<{ error: GenerationErrorNode }> = ({ error }) => (
+  <div className={styles.generationError} role="alert" data-error-code={error.code} data-generator={error.generator}>
+    <div className={styles.generationErrorMessage}>
+      This content could not be generated: {error.message}
+    </div>
+    {error.fallback_content.map((contentNode, index) => (
+      <ContentNodeRenderer key={index} node={contentNode} />
+    ))}
+  </div>
+);
+
+// One line per step, aligned on the relations as in an align environment, with the reason for
+// each step on the right
+const DerivationChainRenderer: React.FC<{ chain: DerivationChain }> = ({ chain }) => (
+  <table className={styles.derivationChain} id={chain.id}>
+    <tbody>
+      <tr>
+        <td className={styles.derivationRelation} />
+        <td className={styles.derivationExpression}>{renderMathNode(chain.start)}</td>
+        <td className={styles.derivationJustification} />
+      </tr>
+      {(chain.steps ?? []).map((step, index) => (
+        <tr key={index}>
+          <td className={styles.derivationRelation}>{relationOperatorSymbol(step.relation)}</td>
+          <td className={styles.derivationExpression}>{renderMathNode(step.expression)}</td>
+          <td className={styles.derivationJustification}>
+            {step.justification && <RichTextRenderer segments={step.justification.segments} />}
+          </td>
+        </tr>
+      ))}
+    </tbody>
+  </table>
+);
+
+// What the margin shows, `(3.2)` or `(∗)`, as `Equation::label` does; nothing for an `Auto`
+// equation the document has not numbered
+const equationLabel = (equation: Equation): string | null => {
+  const text = equation.numbering === 'Auto' ? equation.number : equation.numbering.Tag;
+  return text == null ? null : `(${text})`;
+};
+
+const EquationRenderer: React.FC<{ equation: Equation; context?: any }> = ({ equation, context }) => {
+  const label = equationLabel(equation);
+  return (
+    <div className={`${styles.mathBlock} ${styles.equation}`} id={equation.id}>
+      <div className={styles.mathContent}>
+        <HighlightableComponent 
+          id={equation.math.id} 
+          context={context}
+          className={styles.mathHighlightable}
+        >
+          {renderMathNode(equation.math)}
+        </HighlightableComponent>
+      </div>
+      {label && <span className={styles.equationLabel}>{label}</span>}
+    </div>
+  );
+};
+
 const UnknownContentRenderer: React.FC<{ node: SectionContentNode }> = ({ node }) => (
   <div className={styles.unknownContent}>
     <span className={styles.unknownType}>
-      [Unknown content type: {Object.keys(node)[0]}]
+      [Unknown content type: {sectionContentNodeTag(node)}]
     </span>
     <pre className={styles.debugInfo}>
       {JSON.stringify(node, null, 2)}
@@ -462,14 +636,14 @@ const BranchingContainerRenderer: React.FC<{ container: BranchingContainer }> =
           </div>
     
     <div className={styles.containerNodes}>
-      {container.nodes.map((node, index) => (
+      {(container.nodes ?? []).map((node, index) => (
         <BranchingNodeRenderer key={index} node={node} stepNumber={index + 1} />
             ))}
           </div>
     
-    {container.container_metadata.length > 0 && (
+    {(container.container_metadata ?? []).length > 0 && (
       <div className={styles.containerMetadata}>
-        {container.container_metadata.map(([key, value], index) => (
+        {(container.container_metadata ?? []).map(([key, value], index) => (
           <span key={index} className={styles.metadataItem}>
             {key}: {value}
           </span>
@@ -511,12 +685,15 @@ const BranchingNodeRenderer: React.FC<{ node: any; stepNumber?: number }> = ({ n
   const isProofStep = node.node_type === 'ProofStep';
   const isCompleted = node.node_state === 'Completed';
   
+  const nodeMetadata: [string, string][] = node.node_metadata ?? [];
+  const children: string[] = node.children ?? [];
+
   // Extract tactic name from metadata
-  const tacticName = node.node_metadata
+  const tacticName = nodeMetadata
     .find(([key]: [string, string]) => key === 'tactic')?.[1] || '';
   
   // Extract transformation data from metadata
-  const transformationDataStr = node.node_metadata
+  const transformationDataStr = nodeMetadata
     .find(([key]: [string, string]) => key === 'transformation_flow')?.[1] || '';
   
   let transformationData = null;
@@ -530,7 +707,7 @@ const BranchingNodeRenderer: React.FC<{ node: any; stepNumber?: number }> = ({ n
   }
   
   // Extract context size from metadata
-  const contextSize = node.node_metadata
+  const contextSize = nodeMetadata
     .find(([key]: [string, string]) => key === 'context_size')?.[1] || '0';
   
   // Handle highlighting of source and target elements
@@ -690,7 +867,7 @@ const BranchingNodeRenderer: React.FC<{ node: any; stepNumber?: number }> = ({ n
       
       {/* Main Content */}
       <div className={styles.nodeContent}>
-        {node.content.map((contentNode: any, index: number) => (
+        {(node.content ?? []).map((contentNode: any, index: number) => (
           <ContentNodeRenderer 
             key={index} 
             node={contentNode} 
@@ -700,9 +877,9 @@ const BranchingNodeRenderer: React.FC<{ node: any; stepNumber?: number }> = ({ n
                 </div>
       
       {/* Children (for nested proof structure) */}
-      {node.children.length > 0 && (
+      {children.length > 0 && (
         <div className={styles.nodeChildren}>
-          <span className={styles.childrenLabel}>→ {node.children.length} subgoals</span>
+          <span className={styles.childrenLabel}>→ {children.length} subgoals</span>
             </div>
       )}
           </div>
@@ -261,7 +261,7 @@ export const CollapsibleBlockRenderer: React.FC<CollapsibleBlockRendererProps> =
         <RichTextRenderer segments={block.summary} />
       </summary>
       <div className={styles.content}>
-        {block.details.map((_contentNode: SectionContentNode, index: number) => (
+        {(block.details ?? []).map((_contentNode: SectionContentNode, index: number) => (
           <div key={index} className={styles.contentNode}>
             {/* Render content nodes */}
             <span>Content Node {index + 1}</span>
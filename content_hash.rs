@@ -0,0 +1,161 @@
+use super::*;
+use serde::Serialize;
+use std::sync::Arc;
+
+/// A stable hash of the value's content: its canonical JSON (see `to_canonical_json`), so it
+/// does not depend on field order or the Rust version. The hash is 128-bit FNV-1a as 32 hex
+/// digits; it is meant for cache keys, deduplication and change detection, not for security.
+/// Fails when the value does not serialize, e.g. a document embedding a `DocumentHandle` that
+/// cannot be loaded.
+///
+/// The `content_hash` methods of `MathNode`, `Section` and `MathDocument` also leave out the
+/// ids of the nodes, sections and documents they contain.
+pub fn content_hash(value: &impl Serialize) -> Result<String, serde_json::Error> {
+    let canonical = canonical_json(&serde_json::to_value(value)?);
+    Ok(format!("{:032x}", fnv1a_128(canonical.as_bytes())))
+}
+
+impl MathNode {
+    /// See `content_hash`. Ids are ignored, but not spelling: `a + b` and `b + a` hash
+    /// differently; hash `canonicalize()` to identify those.
+    pub fn content_hash(&self) -> Result<String, serde_json::Error> {
+        content_hash(&without_node_ids(self))
+    }
+}
+
+impl Section {
+    /// See `content_hash`. Nested and embedded sections are part of the content.
+    pub fn content_hash(&self) -> Result<String, serde_json::Error> {
+        let mut section = self.clone();
+        IdClearer.visit_section_mut(&mut section);
+        content_hash(&section)
+    }
+}
+
+impl MathDocument {
+    /// See `content_hash`. The stored hash, the creation and modification times and the
+    /// revision history are not part of the content.
+    pub fn content_hash(&self) -> Result<String, serde_json::Error> {
+        let mut document = self.clone();
+        document.revision_history = None;
+        if let Some(metadata) = document.content_type.content_metadata_mut() {
+            metadata.content_hash = None;
+            metadata.created_at = None;
+            metadata.last_modified = None;
+        }
+        IdClearer.visit_document_mut(&mut document);
+        content_hash(&document)
+    }
+
+    /// Stores `content_hash` in `ContentMetadata.content_hash` on the primary knowledge
+    /// document types. The stored hash is left alone when the document does not serialize.
+    pub fn refresh_content_hash(&mut self) -> Result<(), serde_json::Error> {
+        let hash = self.content_hash()?;
+        if let Some(metadata) = self.content_type.content_metadata_mut() {
+            metadata.content_hash = Some(hash);
+        }
+        Ok(())
+    }
+
+    /// Whether the stored hash matches the content, i.e. the document did not change since
+    /// `refresh_content_hash`. Documents without a stored hash are never current.
+    pub fn content_hash_is_current(&self) -> Result<bool, serde_json::Error> {
+        let Some(stored) = self
+            .content_type
+            .content_metadata()
+            .and_then(|metadata| metadata.content_hash.as_deref())
+        else {
+            return Ok(false);
+        };
+        Ok(stored == self.content_hash()?)
+    }
+}
+
+/// Clears the ids of documents, sections and math nodes, which name content rather than
+/// being part of it. Other fields called `id`, such as those in the free-form
+/// `ComponentProps.values`, are content and stay.
+struct IdClearer;
+
+impl DocumentVisitorMut for IdClearer {
+    fn visit_document_mut(&mut self, document: &mut MathDocument) {
+        document.id.clear();
+        walk_document_mut(self, document);
+    }
+
+    fn visit_section_mut(&mut self, section: &mut Section) {
+        section.id.clear();
+        walk_section_mut(self, section);
+    }
+
+    fn visit_math_node_mut(&mut self, node: &mut MathNode) {
+        *node = without_node_ids(node);
+    }
+}
+
+fn without_node_ids(node: &MathNode) -> MathNode {
+    node.transform(&mut |node: &MathNode| {
+        (!node.id.is_empty()).then(|| MathNode {
+            id: String::new(),
+            content: Arc::clone(&node.content),
+        })
+    })
+}
+
+fn fnv1a_128(bytes: &[u8]) -> u128 {
+    const OFFSET_BASIS: u128 = 0x6c62272e07bb014262b821756295c58d;
+    const PRIME: u128 = 0x0000000001000000000000000000013b;
+    bytes.iter().fold(OFFSET_BASIS, |hash, &byte| {
+        (hash ^ byte as u128).wrapping_mul(PRIME)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn notes(document_id: &str, section_id: &str, prop_id: &str) -> MathDocument {
+        let section = json!({
+            "id": section_id,
+            "content": {
+                "kind": "CustomComponent",
+                "data": {
+                    "component_name": "Slider",
+                    "props": { "values": { "id": prop_id }, "schema_ref": null },
+                    "fallback_content": []
+                }
+            }
+        });
+        serde_json::from_value(json!({
+            "id": document_id,
+            "content_type": {
+                "kind": "PersonalNotes",
+                "data": {
+                    "title": "Scratch",
+                    "author_level": "Expert",
+                    "note_style": "Formal",
+                    "content_metadata": {},
+                    "structure": { "body": [section] },
+                    "relationships": {}
+                }
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn document_and_section_ids_are_ignored() {
+        assert_eq!(
+            notes("a", "s", "p").content_hash().unwrap(),
+            notes("b", "t", "p").content_hash().unwrap()
+        );
+    }
+
+    #[test]
+    fn component_props_named_id_are_content() {
+        assert_ne!(
+            notes("a", "s", "p").content_hash().unwrap(),
+            notes("a", "s", "q").content_hash().unwrap()
+        );
+    }
+}
@@ -0,0 +1,261 @@
+use super::*;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashSet, fmt};
+use ts_rs::TS;
+
+/// Nodes on the left and right of a comparison that correspond to each other.
+/// Hovering any member highlights every member of the group on both sides.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct CorrespondenceGroup {
+    pub id: String,
+    pub label: Option<RichText>,
+    pub color: Option<String>, // CSS color, e.g. "#e07a5f"; frontends pick a palette color if None
+    pub left_ids: Vec<String>, // ids of sections, panels, annotations or math nodes on the left side
+    pub right_ids: Vec<String>,
+}
+
+impl CorrespondenceGroup {
+    pub fn new(
+        id: impl Into<String>,
+        left_ids: impl IntoIterator<Item = impl Into<String>>,
+        right_ids: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        CorrespondenceGroup {
+            id: id.into(),
+            label: None,
+            color: None,
+            left_ids: left_ids.into_iter().map(Into::into).collect(),
+            right_ids: right_ids.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    pub fn with_label(mut self, label: RichText) -> Self {
+        self.label = Some(label);
+        self
+    }
+
+    pub fn with_color(mut self, color: impl Into<String>) -> Self {
+        self.color = Some(color.into());
+        self
+    }
+
+    /// Whether `node_id` belongs to this group on either side.
+    pub fn contains(&self, node_id: &str) -> bool {
+        self.left_ids
+            .iter()
+            .chain(&self.right_ids)
+            .any(|id| id == node_id)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CorrespondenceSide {
+    Left,
+    Right,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CorrespondenceError {
+    DuplicateGroupId {
+        group_id: String,
+    },
+    EmptySide {
+        group_id: String,
+        side: CorrespondenceSide,
+    },
+    UnknownId {
+        group_id: String,
+        side: CorrespondenceSide,
+        node_id: String,
+    },
+}
+
+impl fmt::Display for CorrespondenceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CorrespondenceError::DuplicateGroupId { group_id } => {
+                write!(f, "correspondence group `{}` is defined twice", group_id)
+            }
+            CorrespondenceError::EmptySide { group_id, side } => write!(
+                f,
+                "correspondence group `{}` has no {:?} members",
+                group_id, side
+            ),
+            CorrespondenceError::UnknownId {
+                group_id,
+                side,
+                node_id,
+            } => write!(
+                f,
+                "correspondence group `{}` references `{}`, which is not on the {:?} side",
+                group_id, node_id, side
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CorrespondenceError {}
+
+/// Checks that every group has members on both sides and only references ids present on its side.
+pub fn validate_correspondence_groups(
+    groups: &[CorrespondenceGroup],
+    left_ids: &HashSet<String>,
+    right_ids: &HashSet<String>,
+) -> Vec<CorrespondenceError> {
+    let mut errors = vec![];
+    let mut seen = HashSet::new();
+    for group in groups {
+        if !seen.insert(group.id.as_str()) {
+            errors.push(CorrespondenceError::DuplicateGroupId {
+                group_id: group.id.clone(),
+            });
+        }
+        let sides = [
+            (CorrespondenceSide::Left, &group.left_ids, left_ids),
+            (CorrespondenceSide::Right, &group.right_ids, right_ids),
+        ];
+        for (side, members, known_ids) in sides {
+            if members.is_empty() {
+                errors.push(CorrespondenceError::EmptySide {
+                    group_id: group.id.clone(),
+                    side,
+                });
+            }
+            for node_id in members {
+                if !known_ids.contains(node_id) {
+                    errors.push(CorrespondenceError::UnknownId {
+                        group_id: group.id.clone(),
+                        side,
+                        node_id: node_id.clone(),
+                    });
+                }
+            }
+        }
+    }
+    errors
+}
+
+impl SideBySideLayout {
+    pub fn validate_correspondence(&self) -> Vec<CorrespondenceError> {
+        validate_correspondence_groups(
+            &self.correspondence_groups,
+            &panel_ids(&self.left_panel),
+            &panel_ids(&self.right_panel),
+        )
+    }
+
+    /// The groups `node_id` belongs to, for highlighting its counterparts.
+    pub fn groups_containing(&self, node_id: &str) -> Vec<&CorrespondenceGroup> {
+        self.correspondence_groups
+            .iter()
+            .filter(|group| group.contains(node_id))
+            .collect()
+    }
+}
+
+impl ComparisonSection {
+    pub fn validate_correspondence(&self) -> Vec<CorrespondenceError> {
+        validate_correspondence_groups(
+            &self.correspondence_groups,
+            &content_ids(&self.left_content),
+            &content_ids(&self.right_content),
+        )
+    }
+}
+
+impl MathDocument {
+    /// Validates the correspondence groups of every comparison section and side-by-side layout,
+    /// including those in embedded documents.
+    pub fn validate_correspondence(&self) -> Vec<CorrespondenceError> {
+        let mut validator = CorrespondenceValidator::default();
+        validator.visit_document(self);
+        validator.errors
+    }
+}
+
+#[derive(Default)]
+struct CorrespondenceValidator {
+    errors: Vec<CorrespondenceError>,
+}
+
+impl<'doc> DocumentVisitor<'doc> for CorrespondenceValidator {
+    fn visit_document(&mut self, document: &'doc MathDocument) {
+        if let MathDocumentType::ComparisonPage(c) = &document.content_type {
+            for section in &c.comparison_structure.sections {
+                self.errors.extend(section.validate_correspondence());
+            }
+        }
+        walk_document(self, document);
+    }
+
+    fn visit_content_node(&mut self, node: &'doc SectionContentNode) {
+        if let SectionContentNode::SideBySideLayout(layout) = node {
+            self.errors.extend(layout.validate_correspondence());
+        }
+        walk_content_node(self, node);
+    }
+}
+
+/// Every addressable id inside `nodes`: section, panel, annotation and branching node ids,
+/// plus the ids of all math nodes. Embedded documents are not included.
+pub fn content_ids(nodes: &[SectionContentNode]) -> HashSet<String> {
+    let mut collector = IdCollector::default();
+    walk_content_nodes(&mut collector, nodes);
+    collector.ids
+}
+
+fn panel_ids(panel: &Panel) -> HashSet<String> {
+    let mut collector = IdCollector::default();
+    collector.visit_panel(panel);
+    collector.ids
+}
+
+#[derive(Default)]
+struct IdCollector {
+    ids: HashSet<String>,
+}
+
+impl MathNodeVisitor for IdCollector {
+    fn visit_node(&mut self, node: &MathNode) {
+        self.ids.insert(node.id.clone());
+        walk_node(self, node);
+    }
+}
+
+impl<'doc> DocumentVisitor<'doc> for IdCollector {
+    // Ids inside an embedded document are not addressable from the embedding layout.
+    fn visit_document(&mut self, _document: &'doc MathDocument) {}
+
+    fn visit_section(&mut self, section: &'doc Section) {
+        self.ids.insert(section.id.clone());
+        walk_section(self, section);
+    }
+
+    fn visit_panel(&mut self, panel: &'doc Panel) {
+        self.ids.insert(panel.id.clone());
+        walk_panel(self, panel);
+    }
+
+    fn visit_content_node(&mut self, node: &'doc SectionContentNode) {
+        match node {
+            SectionContentNode::AnnotationOverlay(overlay) => {
+                for annotation in &overlay.annotations {
+                    self.ids.insert(annotation.id.clone());
+                }
+            }
+            SectionContentNode::BranchingContainer(container) => {
+                self.ids.insert(container.container_id.clone());
+                for node in &container.nodes {
+                    self.ids.insert(node.node_id.clone());
+                }
+            }
+            _ => {}
+        }
+        walk_content_node(self, node);
+    }
+
+    fn visit_math_node(&mut self, node: &'doc MathNode) {
+        node.accept(self);
+    }
+}
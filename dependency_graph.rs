@@ -0,0 +1,247 @@
+use super::*;
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
+
+impl DependencyType {
+    /// Whether an edge of this type makes its target a prerequisite of its source, so the target
+    /// has to come first. References and contradictions do not order content.
+    pub fn is_prerequisite(&self) -> bool {
+        matches!(
+            self,
+            DependencyType::Requires | DependencyType::Builds | DependencyType::Extends
+        )
+    }
+}
+
+/// An edge `from_node -> to_node` reads "`from_node` requires (builds on, extends, ...)
+/// `to_node`": only prerequisite edges (`DependencyType::is_prerequisite`) take part in the
+/// ordering and reachability queries below.
+impl DependencyGraph {
+    pub fn node(&self, node_id: &str) -> Option<&DependencyNode> {
+        self.nodes.iter().find(|node| node.node_id == node_id)
+    }
+
+    /// The direct prerequisites of `node_id`, in edge order.
+    pub fn prerequisites(&self, node_id: &str) -> Vec<&str> {
+        self.prerequisite_edges()
+            .filter(|edge| edge.from_node == node_id)
+            .map(|edge| edge.to_node.as_str())
+            .collect()
+    }
+
+    /// Every node `node_id` depends on, directly or through other prerequisites, nearest first.
+    pub fn all_prerequisites(&self, node_id: &str) -> Vec<&str> {
+        let successors = self.successors(|edge| (&edge.from_node, &edge.to_node));
+        reachable(&successors, node_id)
+    }
+
+    /// Every node that depends on `node_id`, directly or indirectly, nearest first.
+    pub fn dependents(&self, node_id: &str) -> Vec<&str> {
+        let successors = self.successors(|edge| (&edge.to_node, &edge.from_node));
+        reachable(&successors, node_id)
+    }
+
+    /// Whether `node_id` depends on `prerequisite`, directly or indirectly.
+    pub fn depends_on(&self, node_id: &str, prerequisite: &str) -> bool {
+        self.all_prerequisites(node_id).contains(&prerequisite)
+    }
+
+    /// The circular dependencies: groups of nodes that all depend on each other, each in node
+    /// order, and nodes that require themselves.
+    pub fn find_cycles(&self) -> Vec<Vec<String>> {
+        let successors = self.successors(|edge| (&edge.from_node, &edge.to_node));
+        let mut cycles: Vec<Vec<String>> = StronglyConnected::components(self, &successors)
+            .into_iter()
+            .filter(|component| {
+                component.len() > 1
+                    || successors
+                        .get(component[0])
+                        .is_some_and(|next| next.contains(&component[0]))
+            })
+            .map(|component| {
+                let mut ids: Vec<&str> = component;
+                ids.sort_by_key(|id| self.nodes.iter().position(|node| node.node_id == *id));
+                ids.into_iter().map(str::to_string).collect()
+            })
+            .collect();
+        cycles.sort_by_key(|cycle| self.nodes.iter().position(|node| node.node_id == cycle[0]));
+        cycles
+    }
+
+    /// The nodes ordered so that every node comes after its prerequisites, otherwise keeping
+    /// the order of `nodes`. Fails if an edge names an unknown node or the dependencies are
+    /// circular.
+    pub fn topological_order(&self) -> Result<Vec<&DependencyNode>, DependencyError> {
+        for edge in &self.edges {
+            for node_id in [&edge.from_node, &edge.to_node] {
+                if self.node(node_id).is_none() {
+                    return Err(DependencyError::UnknownNode {
+                        node_id: node_id.clone(),
+                    });
+                }
+            }
+        }
+        if let Some(cycle) = self.find_cycles().into_iter().next() {
+            return Err(DependencyError::Cycle { node_ids: cycle });
+        }
+
+        let mut order: Vec<&DependencyNode> = vec![];
+        let mut placed = HashSet::new();
+        while order.len() < self.nodes.len() {
+            // The first node whose prerequisites are all placed; one exists as there is no cycle.
+            let next = self
+                .nodes
+                .iter()
+                .find(|node| {
+                    !placed.contains(node.node_id.as_str())
+                        && self
+                            .prerequisites(&node.node_id)
+                            .iter()
+                            .all(|id| placed.contains(id))
+                })
+                .expect("an acyclic graph always has a node ready to place");
+            placed.insert(next.node_id.as_str());
+            order.push(next);
+        }
+        Ok(order)
+    }
+
+    fn prerequisite_edges(&self) -> impl Iterator<Item = &DependencyEdge> {
+        self.edges
+            .iter()
+            .filter(|edge| edge.dependency_type.is_prerequisite())
+    }
+
+    /// Adjacency lists over the prerequisite edges, oriented by `ends`.
+    fn successors<'a>(
+        &'a self,
+        ends: impl Fn(&'a DependencyEdge) -> (&'a String, &'a String),
+    ) -> HashMap<&'a str, Vec<&'a str>> {
+        let mut successors: HashMap<&str, Vec<&str>> = HashMap::new();
+        for edge in self.prerequisite_edges() {
+            let (from, to) = ends(edge);
+            successors.entry(from).or_default().push(to);
+        }
+        successors
+    }
+}
+
+impl TextbookContent {
+    /// The content ids of the dependency graph's nodes in an order that introduces every
+    /// prerequisite before the content that needs it. Empty without a dependency graph.
+    pub fn prerequisite_order(&self) -> Result<Vec<String>, DependencyError> {
+        let Some(graph) = &self.relationships.dependency_graph else {
+            return Ok(vec![]);
+        };
+        Ok(graph
+            .topological_order()?
+            .into_iter()
+            .map(|node| node.content_id.clone())
+            .collect())
+    }
+}
+
+/// The nodes reachable from `start`, breadth first, excluding `start` unless it is on a cycle.
+fn reachable<'a>(successors: &HashMap<&'a str, Vec<&'a str>>, start: &str) -> Vec<&'a str> {
+    let mut found: Vec<&str> = vec![];
+    let mut next = 0;
+    let mut frontier: Vec<&str> = successors.get(start).cloned().unwrap_or_default();
+    loop {
+        for id in frontier {
+            if !found.contains(&id) {
+                found.push(id);
+            }
+        }
+        let Some(id) = found.get(next) else {
+            return found;
+        };
+        next += 1;
+        frontier = successors.get(id).cloned().unwrap_or_default();
+    }
+}
+
+/// Tarjan's strongly connected components algorithm.
+struct StronglyConnected<'a, 'g> {
+    successors: &'g HashMap<&'a str, Vec<&'a str>>,
+    index: HashMap<&'a str, usize>,
+    low_link: HashMap<&'a str, usize>,
+    stack: Vec<&'a str>,
+    on_stack: HashSet<&'a str>,
+    components: Vec<Vec<&'a str>>,
+}
+
+impl<'a, 'g> StronglyConnected<'a, 'g> {
+    fn components(
+        graph: &'a DependencyGraph,
+        successors: &'g HashMap<&'a str, Vec<&'a str>>,
+    ) -> Vec<Vec<&'a str>> {
+        let mut search = StronglyConnected {
+            successors,
+            index: HashMap::new(),
+            low_link: HashMap::new(),
+            stack: vec![],
+            on_stack: HashSet::new(),
+            components: vec![],
+        };
+        for node in &graph.nodes {
+            if !search.index.contains_key(node.node_id.as_str()) {
+                search.connect(&node.node_id);
+            }
+        }
+        search.components
+    }
+
+    fn connect(&mut self, node: &'a str) {
+        let index = self.index.len();
+        self.index.insert(node, index);
+        self.low_link.insert(node, index);
+        self.stack.push(node);
+        self.on_stack.insert(node);
+
+        for &next in self.successors.get(node).into_iter().flatten() {
+            if !self.index.contains_key(next) {
+                self.connect(next);
+                let low = self.low_link[node].min(self.low_link[next]);
+                self.low_link.insert(node, low);
+            } else if self.on_stack.contains(next) {
+                let low = self.low_link[node].min(self.index[next]);
+                self.low_link.insert(node, low);
+            }
+        }
+
+        if self.low_link[node] == index {
+            let mut component = vec![];
+            while let Some(member) = self.stack.pop() {
+                self.on_stack.remove(member);
+                component.push(member);
+                if member == node {
+                    break;
+                }
+            }
+            self.components.push(component);
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DependencyError {
+    UnknownNode { node_id: String },
+    Cycle { node_ids: Vec<String> },
+}
+
+impl fmt::Display for DependencyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DependencyError::UnknownNode { node_id } => {
+                write!(f, "dependency edge refers to unknown node `{}`", node_id)
+            }
+            DependencyError::Cycle { node_ids } => {
+                write!(f, "circular dependency between `{}`", node_ids.join("`, `"))
+            }
+        }
+    }
+}
+
+impl std::error::Error for DependencyError {}
@@ -0,0 +1,99 @@
+use super::*;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use ts_rs::TS;
+
+/// A worked solution or derivation as a chain of expressions, `a = b = c ≤ d`, written one step
+/// per line with the relation to the previous line on the left and the reason for the step on
+/// the right, as in an `align` environment.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct DerivationChain {
+    pub id: String,
+    pub start: MathNode, // The first line, without a relation
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub steps: Vec<DerivationStep>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct DerivationStep {
+    pub relation: RelationOperatorNode, // Between the previous line and this one
+    pub expression: MathNode,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub justification: Option<RichText>, // e.g. "by the chain rule"
+}
+
+impl DerivationChain {
+    pub fn new(id: impl Into<String>, start: MathNode) -> Self {
+        DerivationChain {
+            id: id.into(),
+            start,
+            steps: vec![],
+        }
+    }
+
+    pub fn step(mut self, relation: RelationOperatorNode, expression: MathNode) -> Self {
+        self.steps.push(DerivationStep {
+            relation,
+            expression,
+            justification: None,
+        });
+        self
+    }
+
+    pub fn justified_step(
+        mut self,
+        relation: RelationOperatorNode,
+        expression: MathNode,
+        justification: RichText,
+    ) -> Self {
+        self.steps.push(DerivationStep {
+            relation,
+            expression,
+            justification: Some(justification),
+        });
+        self
+    }
+
+    /// Every line's expression, the start first.
+    pub fn expressions(&self) -> impl Iterator<Item = &MathNode> {
+        std::iter::once(&self.start).chain(self.steps.iter().map(|step| &step.expression))
+    }
+
+    /// The last line's expression, what the chain arrives at.
+    pub fn conclusion(&self) -> &MathNode {
+        self.steps
+            .last()
+            .map_or(&self.start, |step| &step.expression)
+    }
+
+    /// Each step as the relationship it claims between its line and the previous one, e.g.
+    /// `b = c` for the second step of `a = b = c`, with the id `{id}-step-{index}`.
+    pub fn relationships(&self) -> Vec<MathNode> {
+        self.expressions()
+            .zip(&self.steps)
+            .enumerate()
+            .map(|(index, (previous, step))| MathNode {
+                id: format!("{}-step-{}", self.id, index),
+                content: Arc::new(MathNodeContent::Relationship {
+                    lhs: Arc::new(previous.clone()),
+                    rhs: Arc::new(step.expression.clone()),
+                    operator: step.relation.clone(),
+                }),
+            })
+            .collect()
+    }
+}
+
+impl MathDocument {
+    /// Every derivation chain in the document, embedded documents included, in document order.
+    pub fn derivation_chains(&self) -> Vec<&DerivationChain> {
+        self.fold(vec![], |mut chains, node| {
+            if let FoldNode::ContentNode(SectionContentNode::DerivationChain(chain)) = node {
+                chains.push(chain);
+            }
+            chains
+        })
+    }
+}
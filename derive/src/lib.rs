@@ -0,0 +1,362 @@
+//! `#[derive(ToTurnMath)]` and `#[derive(ToSectionNode)]` for the simple wrapper types that most
+//! hand-written impls are for; anything else keeps a manual impl. Both are re-exported from
+//! `turn_render` next to the traits they implement.
+//!
+//! The notation is chosen with `#[turn_math(...)]` on the type:
+//!
+//! ```ignore
+//! // A newtype written as the value it wraps, the default for a single field.
+//! #[derive(ToTurnMath)]
+//! struct Degree(Natural);
+//!
+//! // A constant symbol, or one with its fields as subscripts: `C_n`.
+//! #[derive(ToTurnMath)]
+//! #[turn_math(symbol = "C")]
+//! struct CyclicGroup {
+//!     order: Natural,
+//! }
+//!
+//! // The fields as the arguments of a function: `Hom_C(source, target)`.
+//! #[derive(ToTurnMath, ToSectionNode)]
+//! #[turn_math(function = "Hom", title = "Morphisms")]
+//! struct Hom {
+//!     #[turn_math(subscript)]
+//!     category: Category,
+//!     source: Object,
+//!     target: Object,
+//! }
+//!
+//! // Two fields joined by an operator: `f ∘ g`.
+//! #[derive(ToTurnMath)]
+//! #[turn_math(infix = "∘")]
+//! struct Composition(Morphism, Morphism);
+//! ```
+//!
+//! A field goes where its `#[turn_math(argument)]`, `#[turn_math(subscript)]` or
+//! `#[turn_math(superscript)]` says, by default among the subscripts of a symbol and the
+//! arguments of a function; `#[turn_math(skip)]` leaves it out. The converted fields must
+//! implement `ToTurnMath`; each is converted with the id `{master_id}-{field}`.
+//!
+//! `ToSectionNode` needs a `ToTurnMath` impl: it gives a section titled `title`, the type's name
+//! by default, showing the type's math, with the id `{id_prefix}-section`.
+//!
+//! The generated code refers to `crate::turn_render`; a crate using `turn_render` as a
+//! dependency names it with `#[turn_math(crate = "path::to::turn_render")]`.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{Attribute, Data, DeriveInput, Error, LitStr, Member, Path, Result, parse_macro_input};
+
+#[proc_macro_derive(ToTurnMath, attributes(turn_math))]
+pub fn derive_to_turn_math(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    to_turn_math(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+#[proc_macro_derive(ToSectionNode, attributes(turn_math))]
+pub fn derive_to_section_node(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    to_section_node(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// How the type is written, from `#[turn_math(...)]` on the type.
+enum Notation {
+    Delegate,
+    Symbol(LitStr),
+    Function(LitStr),
+    Infix(LitStr),
+}
+
+#[derive(Default)]
+struct TypeOptions {
+    notation: Option<Notation>,
+    title: Option<LitStr>,
+    krate: Option<Path>,
+}
+
+impl TypeOptions {
+    fn parse(attrs: &[Attribute]) -> Result<TypeOptions> {
+        let mut options = TypeOptions::default();
+        for attr in attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("turn_math"))
+        {
+            attr.parse_nested_meta(|meta| {
+                let notation = if meta.path.is_ident("symbol") {
+                    Notation::Symbol(meta.value()?.parse()?)
+                } else if meta.path.is_ident("function") {
+                    Notation::Function(meta.value()?.parse()?)
+                } else if meta.path.is_ident("infix") {
+                    Notation::Infix(meta.value()?.parse()?)
+                } else if meta.path.is_ident("delegate") {
+                    Notation::Delegate
+                } else if meta.path.is_ident("title") {
+                    options.title = Some(meta.value()?.parse()?);
+                    return Ok(());
+                } else if meta.path.is_ident("crate") {
+                    let path: LitStr = meta.value()?.parse()?;
+                    options.krate = Some(path.parse()?);
+                    return Ok(());
+                } else {
+                    return Err(meta.error(
+                        "expected `symbol`, `function`, `infix`, `delegate`, `title` or `crate`",
+                    ));
+                };
+                if options.notation.replace(notation).is_some() {
+                    return Err(meta.error("the notation is already given"));
+                }
+                Ok(())
+            })?;
+        }
+        Ok(options)
+    }
+
+    fn krate(&self) -> TokenStream2 {
+        match &self.krate {
+            Some(path) => quote!(#path),
+            None => quote!(crate::turn_render),
+        }
+    }
+}
+
+/// Where a field goes, from `#[turn_math(...)]` on the field.
+#[derive(Clone, Copy, PartialEq)]
+enum Place {
+    Default,
+    Argument,
+    Subscript,
+    Superscript,
+    Skip,
+}
+
+struct Field {
+    member: Member,
+    name: String, // the id suffix, the field's name or index
+    place: Place,
+}
+
+fn fields(input: &DeriveInput) -> Result<Vec<Field>> {
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new_spanned(
+            &input.ident,
+            "`turn_math` derives only support structs",
+        ));
+    };
+    let mut fields = vec![];
+    for (index, field) in data.fields.iter().enumerate() {
+        let member = match &field.ident {
+            Some(ident) => Member::Named(ident.clone()),
+            None => Member::Unnamed(index.into()),
+        };
+        let name = match &field.ident {
+            Some(ident) => ident.to_string(),
+            None => index.to_string(),
+        };
+        let mut place = Place::Default;
+        for attr in field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("turn_math"))
+        {
+            attr.parse_nested_meta(|meta| {
+                place = if meta.path.is_ident("argument") {
+                    Place::Argument
+                } else if meta.path.is_ident("subscript") {
+                    Place::Subscript
+                } else if meta.path.is_ident("superscript") {
+                    Place::Superscript
+                } else if meta.path.is_ident("skip") {
+                    Place::Skip
+                } else {
+                    return Err(
+                        meta.error("expected `argument`, `subscript`, `superscript` or `skip`")
+                    );
+                };
+                Ok(())
+            })?;
+        }
+        if place != Place::Skip {
+            fields.push(Field {
+                member,
+                name,
+                place,
+            });
+        }
+    }
+    Ok(fields)
+}
+
+fn to_turn_math(input: &DeriveInput) -> Result<TokenStream2> {
+    let options = TypeOptions::parse(&input.attrs)?;
+    let krate = options.krate();
+    let fields = fields(input)?;
+    let notation = match options.notation {
+        Some(notation) => notation,
+        None if fields.len() == 1 => Notation::Delegate,
+        None => {
+            return Err(Error::new_spanned(
+                &input.ident,
+                "a type with other than one field needs `#[turn_math(symbol = \"..\")]`, \
+                 `#[turn_math(function = \"..\")]` or `#[turn_math(infix = \"..\")]`",
+            ));
+        }
+    };
+
+    let convert = |field: &Field| {
+        let member = &field.member;
+        let suffix = format!("{{}}-{}", field.name);
+        quote! {
+            #krate::ToTurnMath::to_turn_math(&self.#member, format!(#suffix, master_id))
+        }
+    };
+    let body = match notation {
+        Notation::Delegate => {
+            let [field] = fields.as_slice() else {
+                return Err(Error::new_spanned(
+                    &input.ident,
+                    "`delegate` needs exactly one field",
+                ));
+            };
+            let member = &field.member;
+            quote! {
+                #krate::ToTurnMath::to_turn_math(&self.#member, master_id)
+            }
+        }
+        Notation::Infix(operator) => {
+            let [left, right] = fields.as_slice() else {
+                return Err(Error::new_spanned(
+                    &input.ident,
+                    "`infix` needs exactly two fields",
+                ));
+            };
+            let (left, right) = (convert(left), convert(right));
+            quote! {
+                let left = #left;
+                let right = #right;
+                #krate::MathNode {
+                    id: master_id,
+                    content: ::std::sync::Arc::new(#krate::MathNodeContent::BinaryOperation {
+                        operation_type: #krate::BinaryOperationType::Custom(#operator.to_string()),
+                        terms: vec![
+                            (#krate::BinaryOperator::Custom(#operator.to_string()), left),
+                            (#krate::BinaryOperator::Custom(#operator.to_string()), right),
+                        ],
+                    }),
+                }
+            }
+        }
+        Notation::Symbol(body) => identifier(&krate, &body, &fields, Place::Subscript, convert),
+        Notation::Function(body) => identifier(&krate, &body, &fields, Place::Argument, convert),
+    };
+
+    let name = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics #krate::ToTurnMath for #name #type_generics #where_clause {
+            fn to_turn_math(&self, master_id: String) -> #krate::MathNode {
+                #body
+            }
+        }
+    })
+}
+
+/// An identifier with the fields as scripts, applied to the fields that are arguments if there
+/// are any.
+fn identifier(
+    krate: &TokenStream2,
+    body: &LitStr,
+    fields: &[Field],
+    default: Place,
+    convert: impl Fn(&Field) -> TokenStream2,
+) -> TokenStream2 {
+    let placed = |place: Place| -> Vec<TokenStream2> {
+        fields
+            .iter()
+            .filter(|field| {
+                field.place == place || (field.place == Place::Default && default == place)
+            })
+            .map(&convert)
+            .collect()
+    };
+    let (arguments, subscripts, superscripts) = (
+        placed(Place::Argument),
+        placed(Place::Subscript),
+        placed(Place::Superscript),
+    );
+    let post_script = if subscripts.is_empty() && superscripts.is_empty() {
+        quote!(None)
+    } else {
+        quote! {
+            Some(#krate::ScriptNode {
+                subscripts: vec![#(#subscripts),*],
+                superscripts: vec![#(#superscripts),*],
+            })
+        }
+    };
+    let is_function = !arguments.is_empty();
+    let identifier = quote! {
+        let identifier = #krate::Identifier {
+            post_script: #post_script,
+            is_function: #is_function,
+            ..#krate::Identifier::new_simple(#body.to_string())
+        };
+    };
+    if !is_function {
+        return quote! {
+            #identifier
+            #krate::MathNode {
+                id: master_id,
+                content: ::std::sync::Arc::new(#krate::MathNodeContent::Identifier(identifier)),
+            }
+        };
+    }
+    quote! {
+        #identifier
+        let parameters = vec![#(#arguments),*];
+        let name = #krate::MathNode {
+            id: format!("{}-name", master_id),
+            content: ::std::sync::Arc::new(#krate::MathNodeContent::Identifier(identifier)),
+        };
+        #krate::MathNode {
+            id: master_id,
+            content: ::std::sync::Arc::new(#krate::MathNodeContent::FunctionCall {
+                name: ::std::sync::Arc::new(name),
+                parameters,
+            }),
+        }
+    }
+}
+
+fn to_section_node(input: &DeriveInput) -> Result<TokenStream2> {
+    let options = TypeOptions::parse(&input.attrs)?;
+    let krate = options.krate();
+    let name = &input.ident;
+    let title = match options.title {
+        Some(title) => title,
+        None => LitStr::new(&name.to_string(), name.span()),
+    };
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics #krate::ToSectionNode for #name #type_generics #where_clause {
+            fn to_section_node(&self, id_prefix: &str) -> #krate::Section {
+                #krate::Section {
+                    id: format!("{}-section", id_prefix),
+                    title: Some(#krate::RichText::text(#title.to_string())),
+                    content: #krate::SectionContentNode::Math(#krate::ToTurnMath::to_turn_math(
+                        self,
+                        format!("{}-math", id_prefix),
+                    )),
+                    metadata: vec![],
+                    display_options: None,
+                    difficulty: None,
+                    variant_seed: None,
+                }
+            }
+        }
+    })
+}
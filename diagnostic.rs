@@ -0,0 +1,439 @@
+use super::*;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use ts_rs::TS;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub enum Severity {
+    Info,    // worth knowing, e.g. an unused bibliography entry
+    Warning, // renders, but badly or not for everyone
+    Error,   // breaks rendering, linking or generation
+}
+
+impl Severity {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        }
+    }
+}
+
+/// A problem reported by one of the validators, in a shape editors and other tools can show
+/// without knowing which validator produced it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub code: String,                // stable, machine-readable, e.g. "duplicate-id"
+    pub message: String,             // human-readable, the `Display` of the validator's error
+    pub location: Vec<LocationStep>, // enclosing documents, sections and panels, outermost first
+    pub node_path: Option<NodePath>, // within the expression `offending_id` names, for math errors
+    pub offending_id: Option<String>, // the id, key or name at fault
+}
+
+impl Diagnostic {
+    pub fn new(severity: Severity, code: &str, message: impl fmt::Display) -> Self {
+        Diagnostic {
+            severity,
+            code: code.to_string(),
+            message: message.to_string(),
+            location: vec![],
+            node_path: None,
+            offending_id: None,
+        }
+    }
+
+    pub fn at(mut self, location: &[LocationStep]) -> Self {
+        self.location = location.to_vec();
+        self
+    }
+
+    pub fn with_node_path(mut self, node_path: &NodePath) -> Self {
+        self.node_path = Some(node_path.clone());
+        self
+    }
+
+    pub fn with_id(mut self, id: &str) -> Self {
+        self.offending_id = Some(id.to_string());
+        self
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}[{}]: {}",
+            self.severity.name(),
+            self.code,
+            self.message
+        )
+    }
+}
+
+/// Implemented by every validator's error type.
+pub trait ToDiagnostic {
+    fn to_diagnostic(&self) -> Diagnostic;
+}
+
+impl MathDocument {
+    /// Everything the document-level validators find, as diagnostics: `validate`, `check_toc`,
+    /// `check_bibliography`, `accessibility_lint`, `validate_variables`,
+    /// `validate_panel_layouts`, `MathNode::lint` on every expression, `check_dimensions` with
+    /// the built-in units and circular dependencies in the dependency graph. Validators that
+    /// need a registry (theories, other units) are run separately; see `diagnostics_with` for
+    /// custom component props.
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+        self.diagnostics_from(self.validate())
+    }
+
+    /// `diagnostics`, with `validate_with` checking custom component props against `registry`.
+    pub fn diagnostics_with(&self, registry: &ComponentRegistry) -> Vec<Diagnostic> {
+        self.diagnostics_from(self.validate_with(registry))
+    }
+
+    fn diagnostics_from(&self, errors: Vec<ValidationError>) -> Vec<Diagnostic> {
+        let mut diagnostics: Vec<Diagnostic> = errors.iter().map(|e| e.to_diagnostic()).collect();
+        let location = [LocationStep::Document(self.id.clone())];
+        for issue in self.check_toc() {
+            diagnostics.push(issue.to_diagnostic().at(&location));
+        }
+        for issue in self.check_bibliography() {
+            diagnostics.push(issue.to_diagnostic().at(&location));
+        }
+        diagnostics.extend(self.accessibility_lint().iter().map(|w| w.to_diagnostic()));
+        for error in self.validate_variables() {
+            let mut diagnostic = error.to_diagnostic();
+            diagnostic.location.splice(0..0, location.iter().cloned());
+            diagnostics.push(diagnostic);
+        }
+        // `validate` already reports a repeated panel id as a duplicate id.
+        for error in self.validate_panel_layouts() {
+            if !matches!(error, PanelLayoutError::DuplicatePanelId { .. }) {
+                diagnostics.push(error.to_diagnostic().at(&location));
+            }
+        }
+        for expression in self.expressions() {
+            for lint in expression.lint() {
+                let diagnostic = lint.to_diagnostic().at(&location);
+                diagnostics.push(diagnostic.with_id(&expression.id));
+            }
+        }
+        for (id, issue) in self.check_dimensions(UnitRegistry::built_in()) {
+            diagnostics.push(issue.to_diagnostic().at(&location).with_id(&id));
+        }
+        if let Some(graph) = self
+            .content_type
+            .relationships()
+            .and_then(|relationships| relationships.dependency_graph.as_ref())
+            && let Err(error) = graph.topological_order()
+        {
+            diagnostics.push(error.to_diagnostic().at(&location));
+        }
+        diagnostics
+    }
+}
+
+impl ToDiagnostic for ValidationError {
+    fn to_diagnostic(&self) -> Diagnostic {
+        match self {
+            ValidationError::DuplicateId { id, duplicate, .. } => {
+                Diagnostic::new(Severity::Error, "duplicate-id", self)
+                    .at(&duplicate.path)
+                    .with_id(id)
+            }
+            ValidationError::DuplicateLabel {
+                duplicate_section_id,
+                ..
+            } => Diagnostic::new(Severity::Error, "duplicate-label", self)
+                .with_id(duplicate_section_id),
+            ValidationError::DanglingReference { target, path, .. } => {
+                Diagnostic::new(Severity::Error, "dangling-reference", self)
+                    .at(path)
+                    .with_id(target)
+            }
+            ValidationError::MalformedMath {
+                root_id,
+                path,
+                error,
+            } => Diagnostic {
+                message: self.to_string(),
+                ..error.to_diagnostic().at(path).with_id(root_id)
+            },
+            ValidationError::InvalidInteraction { document_id, error } => Diagnostic {
+                message: self.to_string(),
+                ..error
+                    .to_diagnostic()
+                    .at(&[LocationStep::Document(document_id.clone())])
+            },
+            ValidationError::InvalidProps { path, error } => Diagnostic {
+                message: self.to_string(),
+                ..error.to_diagnostic().at(path)
+            },
+        }
+    }
+}
+
+impl ToDiagnostic for MathNodeError {
+    fn to_diagnostic(&self) -> Diagnostic {
+        let code = match self {
+            MathNodeError::EmptyOperation { .. } => "empty-operation",
+            MathNodeError::EmptyMatrix { .. } => "empty-matrix",
+            MathNodeError::RaggedMatrix { .. } => "ragged-matrix",
+            MathNodeError::MatrixBarOutOfRange { .. } => "matrix-bar-out-of-range",
+            MathNodeError::MatrixHighlightOutOfRange { .. } => "matrix-highlight-out-of-range",
+            MathNodeError::InvalidNumber { .. } => "invalid-number",
+            MathNodeError::EmptyIdentifier { .. } => "empty-identifier",
+            MathNodeError::VariableNameNotIdentifier { .. } => "variable-name-not-identifier",
+            MathNodeError::FunctionDefinitionNotCall { .. } => "function-definition-not-call",
+            MathNodeError::FunctionClauseArity { .. } => "function-clause-arity",
+            MathNodeError::EmptyLimitVariable { .. } => "empty-limit-variable",
+            MathNodeError::MissingDifferentials { .. } => "missing-differentials",
+        };
+        Diagnostic::new(Severity::Error, code, self).with_node_path(self.path())
+    }
+}
+
+impl ToDiagnostic for MathLint {
+    fn to_diagnostic(&self) -> Diagnostic {
+        let code = match self {
+            MathLint::IdenticalSides { .. } => "identical-sides",
+            MathLint::ZeroDenominator { .. } => "zero-denominator",
+            MathLint::NoQuantifiedVariables { .. } => "no-quantified-variables",
+            MathLint::RedundantBrackets { .. } => "redundant-brackets",
+        };
+        Diagnostic::new(Severity::Warning, code, self).with_node_path(self.path())
+    }
+}
+
+impl ToDiagnostic for DimensionIssue {
+    fn to_diagnostic(&self) -> Diagnostic {
+        let code = match self {
+            DimensionIssue::MismatchedSides { .. } => "mismatched-dimensions",
+            DimensionIssue::MismatchedTerms { .. } => "mismatched-term-dimensions",
+            DimensionIssue::NotDimensionless { .. } => "not-dimensionless",
+        };
+        Diagnostic::new(Severity::Warning, code, self).with_node_path(self.path())
+    }
+}
+
+impl ToDiagnostic for TocIssue {
+    fn to_diagnostic(&self) -> Diagnostic {
+        let (code, id) = match self {
+            TocIssue::MissingTarget { target_id } => ("toc-missing-target", target_id),
+            TocIssue::TitleMismatch { target_id, .. } => ("toc-title-mismatch", target_id),
+            TocIssue::OutOfOrder { target_id } => ("toc-out-of-order", target_id),
+            TocIssue::MissingEntry { section_id } => ("toc-missing-entry", section_id),
+        };
+        Diagnostic::new(Severity::Warning, code, self).with_id(id)
+    }
+}
+
+impl ToDiagnostic for AccessibilityWarning {
+    fn to_diagnostic(&self) -> Diagnostic {
+        let diagnostic = |code| Diagnostic::new(Severity::Warning, code, self).at(&self.path);
+        match &self.issue {
+            AccessibilityIssue::ImageWithoutAltText { src } => {
+                diagnostic("image-without-alt-text").with_id(src)
+            }
+            AccessibilityIssue::TableWithoutHeader => diagnostic("table-without-header"),
+            AccessibilityIssue::MathOnlyLink => diagnostic("math-only-link"),
+            AccessibilityIssue::ColorOnlyAnnotation { annotation_id } => {
+                diagnostic("color-only-annotation").with_id(annotation_id)
+            }
+        }
+    }
+}
+
+impl ToDiagnostic for BibliographyIssue {
+    fn to_diagnostic(&self) -> Diagnostic {
+        let (severity, code, key) = match self {
+            BibliographyIssue::MissingField { key, .. } => {
+                (Severity::Warning, "bibliography-missing-field", key)
+            }
+            BibliographyIssue::DuplicateKey { key } => {
+                (Severity::Error, "bibliography-duplicate-key", key)
+            }
+            BibliographyIssue::UnusedEntry { key } => {
+                (Severity::Info, "bibliography-unused-entry", key)
+            }
+        };
+        Diagnostic::new(severity, code, self).with_id(key)
+    }
+}
+
+impl ToDiagnostic for DependencyError {
+    fn to_diagnostic(&self) -> Diagnostic {
+        match self {
+            DependencyError::UnknownNode { node_id } => {
+                Diagnostic::new(Severity::Error, "unknown-dependency-node", self).with_id(node_id)
+            }
+            DependencyError::Cycle { node_ids } => {
+                Diagnostic::new(Severity::Error, "dependency-cycle", self).with_id(&node_ids[0])
+            }
+        }
+    }
+}
+
+impl ToDiagnostic for ParameterSpaceError {
+    fn to_diagnostic(&self) -> Diagnostic {
+        let (code, id) = match self {
+            ParameterSpaceError::InvalidFormula { formula, .. } => ("invalid-formula", formula),
+            ParameterSpaceError::UnknownParameter { name, .. } => ("unknown-parameter", name),
+            ParameterSpaceError::DefaultOutOfRange { name, .. } => ("default-out-of-range", name),
+            ParameterSpaceError::DefaultsViolate { formula } => ("defaults-violate", formula),
+        };
+        Diagnostic::new(Severity::Error, code, self).with_id(id)
+    }
+}
+
+impl ToDiagnostic for TheoryError {
+    fn to_diagnostic(&self) -> Diagnostic {
+        match self {
+            TheoryError::UnknownTheory {
+                document_id,
+                theory,
+                ..
+            } => Diagnostic::new(Severity::Error, "unknown-theory", self)
+                .at(&[LocationStep::Document(document_id.clone())])
+                .with_id(&theory.0),
+            TheoryError::DuplicateName { name, .. } => {
+                Diagnostic::new(Severity::Error, "duplicate-theory-name", self).with_id(name)
+            }
+        }
+    }
+}
+
+impl ToDiagnostic for PropValidationError {
+    fn to_diagnostic(&self) -> Diagnostic {
+        let (code, component_name) = match self {
+            PropValidationError::UnknownComponent { component_name } => {
+                ("unknown-component", component_name)
+            }
+            PropValidationError::SchemaMismatch { component_name, .. } => {
+                ("props-schema-mismatch", component_name)
+            }
+            PropValidationError::NotAnObject { component_name } => {
+                ("props-not-an-object", component_name)
+            }
+            PropValidationError::MissingProp { component_name, .. } => {
+                ("missing-prop", component_name)
+            }
+            PropValidationError::UnexpectedProp { component_name, .. } => {
+                ("unexpected-prop", component_name)
+            }
+            PropValidationError::WrongType { component_name, .. } => {
+                ("wrong-prop-type", component_name)
+            }
+        };
+        Diagnostic::new(Severity::Error, code, self).with_id(component_name)
+    }
+}
+
+impl ToDiagnostic for CorrespondenceError {
+    fn to_diagnostic(&self) -> Diagnostic {
+        let (code, id) = match self {
+            CorrespondenceError::DuplicateGroupId { group_id } => {
+                ("duplicate-correspondence-group", group_id)
+            }
+            CorrespondenceError::EmptySide { group_id, .. } => {
+                ("empty-correspondence-side", group_id)
+            }
+            CorrespondenceError::UnknownId { node_id, .. } => {
+                ("unknown-correspondence-id", node_id)
+            }
+        };
+        Diagnostic::new(Severity::Error, code, self).with_id(id)
+    }
+}
+
+impl ToDiagnostic for InteractionError {
+    fn to_diagnostic(&self) -> Diagnostic {
+        match self {
+            InteractionError::UnknownControl { control_id } => {
+                Diagnostic::new(Severity::Error, "unknown-control", self).with_id(control_id)
+            }
+            InteractionError::UnknownStateVariable { variable, .. } => {
+                Diagnostic::new(Severity::Error, "unknown-state-variable", self).with_id(variable)
+            }
+            InteractionError::UnknownTarget { selector } => {
+                Diagnostic::new(Severity::Error, "unknown-event-target", self).with_id(selector)
+            }
+            InteractionError::UnknownElement { element_id } => {
+                Diagnostic::new(Severity::Error, "unknown-highlight-element", self)
+                    .with_id(element_id)
+            }
+            InteractionError::SlideOutOfRange { .. } => {
+                Diagnostic::new(Severity::Error, "slide-out-of-range", self)
+            }
+        }
+    }
+}
+
+impl ToDiagnostic for VariableResolutionError {
+    fn to_diagnostic(&self) -> Diagnostic {
+        let (code, variable_id, section_id) = match self {
+            VariableResolutionError::Undeclared {
+                variable_id,
+                section_id,
+            } => ("undeclared-variable", variable_id, section_id.as_ref()),
+            VariableResolutionError::OutOfScope {
+                variable_id,
+                section_id,
+                ..
+            } => ("variable-out-of-scope", variable_id, section_id.as_ref()),
+            VariableResolutionError::UnknownBinding { variable_id } => {
+                ("unknown-variable-binding", variable_id, None)
+            }
+        };
+        let location: Vec<LocationStep> = section_id
+            .map(|id| LocationStep::Section(id.clone()))
+            .into_iter()
+            .collect();
+        Diagnostic::new(Severity::Error, code, self)
+            .at(&location)
+            .with_id(variable_id)
+    }
+}
+
+impl ToDiagnostic for PanelLayoutError {
+    fn to_diagnostic(&self) -> Diagnostic {
+        let (code, panel_id) = match self {
+            PanelLayoutError::DuplicatePanelId { panel_id } => ("duplicate-panel-id", panel_id),
+            PanelLayoutError::UnknownMainPanel { main_panel_id } => {
+                ("unknown-main-panel", main_panel_id)
+            }
+        };
+        Diagnostic::new(Severity::Error, code, self).with_id(panel_id)
+    }
+}
+
+impl ToDiagnostic for FeedbackError {
+    fn to_diagnostic(&self) -> Diagnostic {
+        let (code, feedback_id) = match self {
+            FeedbackError::WrongFeedbackId { found, .. } => ("wrong-feedback-id", found),
+            FeedbackError::KindMismatch { feedback_id } => ("feedback-kind-mismatch", feedback_id),
+            FeedbackError::OutOfRange { feedback_id } => ("feedback-out-of-range", feedback_id),
+            FeedbackError::CommentNotAllowed { feedback_id } => {
+                ("feedback-comment-not-allowed", feedback_id)
+            }
+        };
+        Diagnostic::new(Severity::Error, code, self).with_id(feedback_id)
+    }
+}
+
+impl ToDiagnostic for SectionRefError {
+    fn to_diagnostic(&self) -> Diagnostic {
+        match self {
+            SectionRefError::Unresolved { section_id } => {
+                Diagnostic::new(Severity::Error, "unresolved-section-ref", self).with_id(section_id)
+            }
+            SectionRefError::Cycle { cycle } => {
+                Diagnostic::new(Severity::Error, "section-ref-cycle", self).with_id(&cycle[0])
+            }
+        }
+    }
+}
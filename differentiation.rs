@@ -0,0 +1,706 @@
+use super::*;
+use std::{fmt, sync::Arc};
+
+impl MathNode {
+    /// The unevaluated derivative of this expression with respect to `variable`, written with
+    /// differentials as `d(expr)/dx`: the first line of a worked differentiation.
+    pub fn derivative_notation(&self, variable: &Identifier) -> MathNode {
+        let id = format!("{}-derivative", self.id);
+        let differential = |id: String, target: MathNode| MathNode {
+            content: Arc::new(MathNodeContent::Differential {
+                target: Arc::new(target),
+                order: Arc::new(number(format!("{}-order", id), 1)),
+                diff_style: DifferentialStyle::Total,
+                notation: DerivativeNotation::Leibniz,
+                variable: None,
+            }),
+            id,
+        };
+        let target = match self.content.as_ref() {
+            MathNodeContent::Identifier(_)
+            | MathNodeContent::FunctionCall { .. }
+            | MathNodeContent::Quantity { .. }
+            | MathNodeContent::Bracketed { .. } => self.clone(),
+            _ => bracketed(format!("{}-target", id), self.clone()),
+        };
+        let variable = MathNode {
+            id: format!("{}-variable", id),
+            content: Arc::new(MathNodeContent::Identifier(variable.clone())),
+        };
+        MathNode {
+            content: Arc::new(MathNodeContent::Fraction {
+                numerator: Arc::new(differential(format!("{}-numerator", id), target)),
+                denominator: Arc::new(differential(format!("{}-denominator", id), variable)),
+            }),
+            id,
+        }
+    }
+
+    /// The derivative with respect to `variable`, by the sum, product, quotient, power and chain
+    /// rules and the derivatives of `exp`, `ln`, `log`, `sqrt` and the trigonometric and
+    /// hyperbolic functions and their inverses. Subexpressions without a free `variable` are
+    /// constants, piecewise expressions are differentiated branch by branch, and an unknown
+    /// function of one argument differentiates to `f'(u)·u'`.
+    /// What has no rule, such as an integral or a function of several arguments depending on
+    /// `variable`, is left as `derivative_notation`.
+    ///
+    /// Only zeros and ones are simplified away. Subexpressions copied from this expression keep
+    /// their ids, so that a step can be linked back to the terms it came from; new nodes get ids
+    /// derived from the node whose derivative they are part of.
+    pub fn differentiate(&self, variable: &Identifier) -> Result<MathNode, DifferentiationError> {
+        derivative(self, variable)
+    }
+}
+
+fn derivative(node: &MathNode, variable: &Identifier) -> Result<MathNode, DifferentiationError> {
+    let id = format!("{}-d", node.id);
+    let d = |node: &MathNode| derivative(node, variable);
+    match node.content.as_ref() {
+        MathNodeContent::Relationship { .. }
+        | MathNodeContent::UnaryRelationship { .. }
+        | MathNodeContent::VariableDefinition { .. }
+        | MathNodeContent::FunctionDefinition { .. }
+        | MathNodeContent::QuantifiedExpression { .. }
+        | MathNodeContent::SetExplicit { .. }
+        | MathNodeContent::SetBuilder { .. }
+        | MathNodeContent::RichTextContent(_)
+        | MathNodeContent::ChemicalExpression(_)
+        | MathNodeContent::Phantom { .. }
+        | MathNodeContent::Space(_)
+        | MathNodeContent::AngleSymbol { .. }
+        | MathNodeContent::And(_)
+        | MathNodeContent::Or(_)
+        | MathNodeContent::Not(_)
+        | MathNodeContent::Sequent { .. }
+        | MathNodeContent::True
+        | MathNodeContent::False => {
+            return Err(DifferentiationError::Unsupported {
+                kind: node.content.kind_name(),
+            });
+        }
+        _ => {}
+    }
+    let depends = |node: &MathNode| {
+        node.collect_free_variables()
+            .iter()
+            .any(|free| same_variable(free, variable))
+    };
+    if !depends(node) {
+        return Ok(number(id, 0));
+    }
+    let unevaluated = || Ok(node.derivative_notation(variable));
+    match node.content.as_ref() {
+        MathNodeContent::Identifier(identifier) if same_variable(identifier, variable) => {
+            Ok(number(id, 1))
+        }
+        // A label or a cancellation describes `inner`, not its derivative
+        MathNodeContent::Bracketed { inner, .. }
+        | MathNodeContent::Brace { inner, .. }
+        | MathNodeContent::Cancelled { inner, .. }
+        | MathNodeContent::Folded { content: inner, .. } => d(inner),
+        // The derivative of a determinant is not the determinant of the derivatives
+        MathNodeContent::Matrix {
+            delimiters: Some(MatrixDelimiters::Bars),
+            ..
+        } => Err(DifferentiationError::Unsupported {
+            kind: node.content.kind_name(),
+        }),
+        // Entry by entry; notes on the rows describe the matrix, not its derivative
+        MathNodeContent::Matrix {
+            rows,
+            delimiters,
+            augmented_after,
+            row_labels,
+            column_labels,
+            ..
+        } => {
+            let rows = rows
+                .iter()
+                .map(|row| row.iter().map(d).collect::<Result<Vec<_>, _>>())
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(MathNode {
+                id,
+                content: Arc::new(MathNodeContent::Matrix {
+                    rows,
+                    delimiters: *delimiters,
+                    augmented_after: *augmented_after,
+                    row_labels: row_labels.clone(),
+                    column_labels: column_labels.clone(),
+                    highlights: vec![],
+                }),
+            })
+        }
+        // Component by component; the point's name belongs to the point, not its derivative
+        MathNodeContent::Coordinates { components, .. } => Ok(MathNode {
+            id,
+            content: Arc::new(MathNodeContent::Coordinates {
+                point: None,
+                components: components.iter().map(d).collect::<Result<_, _>>()?,
+            }),
+        }),
+        MathNodeContent::Decorated { inner, decoration } => Ok(MathNode {
+            id,
+            content: Arc::new(MathNodeContent::Decorated {
+                inner: Arc::new(d(inner)?),
+                decoration: *decoration,
+            }),
+        }),
+        // The derivative of a highlighted term stays highlighted, so a step can be followed
+        MathNodeContent::Styled {
+            inner,
+            color,
+            background,
+            emphasis,
+        } => Ok(MathNode {
+            id,
+            content: Arc::new(MathNodeContent::Styled {
+                inner: Arc::new(d(inner)?),
+                color: color.clone(),
+                background: background.clone(),
+                emphasis: *emphasis,
+            }),
+        }),
+        MathNodeContent::Additions { terms } => {
+            let terms = terms
+                .iter()
+                .map(|(operator, term)| {
+                    let negative = matches!(operator, RefinedAddOrSubOperator::Subtraction);
+                    Ok((negative, d(term)?))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(sum(id, terms))
+        }
+        MathNodeContent::Multiplications { terms } => {
+            let (numerator, denominator) = terms.iter().partition::<Vec<_>, _>(|(operator, _)| {
+                !matches!(operator, RefinedMulOrDivOperation::Division(_))
+            });
+            quotient_derivative(id, operands(numerator), operands(denominator), variable)
+        }
+        MathNodeContent::BinaryOperation { terms, .. } => {
+            let rest = terms.iter().skip(1).map(|(operator, _)| operator);
+            if rest
+                .clone()
+                .all(|operator| matches!(operator, BinaryOperator::Plus | BinaryOperator::Minus))
+            {
+                let terms = terms
+                    .iter()
+                    .map(|(operator, term)| Ok((*operator == BinaryOperator::Minus, d(term)?)))
+                    .collect::<Result<Vec<_>, _>>()?;
+                return Ok(sum(id, terms));
+            }
+            let is_product = matches!(
+                terms.first(),
+                Some((BinaryOperator::Times | BinaryOperator::Dot, _))
+            ) && rest.clone().all(|operator| {
+                matches!(
+                    operator,
+                    BinaryOperator::Times
+                        | BinaryOperator::Dot
+                        | BinaryOperator::Slash
+                        | BinaryOperator::Divide
+                )
+            });
+            if !is_product {
+                return unevaluated();
+            }
+            let (numerator, denominator) = terms.iter().partition::<Vec<_>, _>(|(operator, _)| {
+                matches!(operator, BinaryOperator::Times | BinaryOperator::Dot)
+            });
+            quotient_derivative(id, operands(numerator), operands(denominator), variable)
+        }
+        MathNodeContent::Fraction {
+            numerator,
+            denominator,
+        }
+        | MathNodeContent::Division {
+            numerator,
+            denominator,
+            ..
+        } => quotient_derivative(id, vec![numerator], vec![denominator], variable),
+        MathNodeContent::Power { base, exponent } => {
+            let outer = match (depends(base), depends(exponent)) {
+                // n·u^(n-1)
+                (true, false) => {
+                    let reduced = match integer(exponent) {
+                        Some(n) => number(format!("{}-exponent", id), n - 1),
+                        None => sum(
+                            format!("{}-exponent", id),
+                            vec![
+                                (false, exponent.as_ref().clone()),
+                                (true, number(format!("{}-one", id), 1)),
+                            ],
+                        ),
+                    };
+                    product(
+                        format!("{}-outer", id),
+                        vec![
+                            exponent.as_ref().clone(),
+                            power(format!("{}-power", id), base.as_ref().clone(), reduced),
+                        ],
+                    )
+                }
+                // a^u·ln(a), or e^u
+                (false, true) => {
+                    if is_euler(base) {
+                        node.clone()
+                    } else {
+                        let log = call(format!("{}-ln", id), "ln", base.as_ref().clone());
+                        product(format!("{}-outer", id), vec![node.clone(), log])
+                    }
+                }
+                // u^v·(v'·ln(u) + v·u'/u)
+                _ => {
+                    let log = call(format!("{}-ln", id), "ln", base.as_ref().clone());
+                    let exponent_term =
+                        product(format!("{}-exponent", id), vec![d(exponent)?, log]);
+                    let base_term = product(
+                        format!("{}-base", id),
+                        vec![
+                            exponent.as_ref().clone(),
+                            fraction(format!("{}-ratio", id), d(base)?, base.as_ref().clone()),
+                        ],
+                    );
+                    let inner = sum(
+                        format!("{}-sum", id),
+                        vec![(false, exponent_term), (false, base_term)],
+                    );
+                    return Ok(product(id, vec![node.clone(), inner]));
+                }
+            };
+            let inner = if depends(base) {
+                d(base)?
+            } else {
+                d(exponent)?
+            };
+            Ok(product(id, vec![outer, inner]))
+        }
+        // (ⁿ√u)' = u'/(n·ⁿ√u^(n-1))
+        MathNodeContent::Radical { radicand, index } => {
+            let index = match index {
+                Some(index) if depends(index) => return unevaluated(),
+                Some(index) => index.as_ref().clone(),
+                None => number(format!("{}-index", id), 2),
+            };
+            let reduced = match integer(&index) {
+                Some(n) => number(format!("{}-exponent", id), n - 1),
+                None => sum(
+                    format!("{}-exponent", id),
+                    vec![
+                        (false, index.clone()),
+                        (true, number(format!("{}-one", id), 1)),
+                    ],
+                ),
+            };
+            let denominator = product(
+                format!("{}-denominator", id),
+                vec![index, power(format!("{}-power", id), node.clone(), reduced)],
+            );
+            Ok(fraction(id, d(radicand)?, denominator))
+        }
+        MathNodeContent::UnaryPrefixOperation {
+            parameter,
+            operator,
+        } => match symbol(operator) {
+            Some("-" | "−") => Ok(negate(id, d(parameter)?)),
+            Some("+") => d(parameter),
+            _ => unevaluated(),
+        },
+        MathNodeContent::Cases { branches } => {
+            let branches = branches
+                .iter()
+                .map(|(expression, condition)| Ok((d(expression)?, condition.clone())))
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(MathNode {
+                id,
+                content: Arc::new(MathNodeContent::Cases { branches }),
+            })
+        }
+        // |u|' = u/|u|·u'
+        MathNodeContent::Abs { parameter } => {
+            let sign = fraction(
+                format!("{}-sign", id),
+                parameter.as_ref().clone(),
+                node.clone(),
+            );
+            Ok(product(id, vec![sign, d(parameter)?]))
+        }
+        MathNodeContent::FunctionCall { name, parameters } => {
+            let ([argument], MathNodeContent::Identifier(function)) =
+                (parameters.as_slice(), name.content.as_ref())
+            else {
+                return unevaluated();
+            };
+            let outer = match outer_derivative(&id, function, argument) {
+                Some(outer) => outer,
+                // f'(u)
+                None => MathNode {
+                    id: format!("{}-outer", id),
+                    content: Arc::new(MathNodeContent::FunctionCall {
+                        name: Arc::new(MathNode {
+                            id: format!("{}-name", id),
+                            content: Arc::new(MathNodeContent::Identifier(Identifier {
+                                primes: function.primes + 1,
+                                ..function.clone()
+                            })),
+                        }),
+                        parameters: parameters.clone(),
+                    }),
+                },
+            };
+            Ok(product(id, vec![outer, d(argument)?]))
+        }
+        _ => unevaluated(),
+    }
+}
+
+/// The derivative of the product of `numerator` divided by the product of `denominator`.
+fn quotient_derivative(
+    id: String,
+    numerator: Vec<&MathNode>,
+    denominator: Vec<&MathNode>,
+    variable: &Identifier,
+) -> Result<MathNode, DifferentiationError> {
+    // Σ_i f_1⋯f_i'⋯f_n
+    let product_derivative = |id: String, factors: &[&MathNode]| {
+        let mut terms = vec![];
+        for (index, factor) in factors.iter().enumerate() {
+            let mut term: Vec<MathNode> = factors.iter().map(|f| (*f).clone()).collect();
+            term[index] = derivative(factor, variable)?;
+            terms.push((false, product(format!("{}-{}", id, index), term)));
+        }
+        Ok(sum(id, terms))
+    };
+    let cloned = |factors: &[&MathNode]| factors.iter().map(|f| (*f).clone()).collect();
+    let numerator_derivative = product_derivative(format!("{}-numerator", id), &numerator)?;
+    if denominator.is_empty() {
+        return Ok(numerator_derivative);
+    }
+    let below = product(format!("{}-denominator", id), cloned(&denominator));
+    let denominator_derivative = product_derivative(format!("{}-denominator-d", id), &denominator)?;
+    if is_number(&denominator_derivative, 0) {
+        return Ok(fraction(id, numerator_derivative, below));
+    }
+    // (N'·D - N·D')/D²
+    let above = sum(
+        format!("{}-above", id),
+        vec![
+            (
+                false,
+                product(
+                    format!("{}-left", id),
+                    vec![numerator_derivative, below.clone()],
+                ),
+            ),
+            (
+                true,
+                product(format!("{}-right", id), {
+                    let mut factors: Vec<MathNode> = cloned(&numerator);
+                    factors.push(denominator_derivative);
+                    factors
+                }),
+            ),
+        ],
+    );
+    let squared = power(
+        format!("{}-squared", id),
+        below,
+        number(format!("{}-two", id), 2),
+    );
+    Ok(fraction(id, above, squared))
+}
+
+fn operands<O>(terms: Vec<&(O, MathNode)>) -> Vec<&MathNode> {
+    terms.into_iter().map(|(_, term)| term).collect()
+}
+
+/// `f'(u)` for the known functions `f`.
+fn outer_derivative(id: &str, function: &Identifier, argument: &MathNode) -> Option<MathNode> {
+    if function.pre_script.is_some()
+        || function.mid_script.is_some()
+        || function.post_script.is_some()
+        || function.primes > 0
+    {
+        return None;
+    }
+    let id = format!("{}-outer", id);
+    let part = {
+        let id = id.clone();
+        move |name: &str| format!("{}-{}", id, name)
+    };
+    let u = || argument.clone();
+    let one = || number(part("one"), 1);
+    let apply = |name: &str| call(part(name), name, u());
+    // 1/f(u)²
+    let reciprocal_square = |name: &str| {
+        let squared = power(part("squared"), apply(name), number(part("two"), 2));
+        fraction(id.clone(), one(), squared)
+    };
+    // √(1 - u²)
+    let root = || {
+        let squared = power(part("squared"), u(), number(part("two"), 2));
+        let difference = sum(part("difference"), vec![(false, one()), (true, squared)]);
+        call(part("sqrt"), "sqrt", difference)
+    };
+    Some(match function.body.as_str() {
+        "sin" => apply("cos"),
+        "cos" => negate(id.clone(), apply("sin")),
+        "tan" => reciprocal_square("cos"),
+        "sinh" => apply("cosh"),
+        "cosh" => apply("sinh"),
+        "tanh" => reciprocal_square("cosh"),
+        "exp" => apply("exp"),
+        "ln" => fraction(id, one(), u()),
+        "log" => {
+            let ten = call(part("ln"), "ln", number(part("ten"), 10));
+            fraction(id.clone(), one(), product(part("product"), vec![u(), ten]))
+        }
+        "sqrt" => {
+            let twice = product(part("product"), vec![number(part("two"), 2), apply("sqrt")]);
+            fraction(id, one(), twice)
+        }
+        "arcsin" => fraction(id, one(), root()),
+        "arccos" => negate(id.clone(), fraction(part("fraction"), one(), root())),
+        "arctan" => {
+            let squared = power(part("squared"), u(), number(part("two"), 2));
+            let total = sum(part("sum"), vec![(false, one()), (false, squared)]);
+            fraction(id, one(), total)
+        }
+        _ => return None,
+    })
+}
+
+/// Whether `a` and `b` name the same variable; `is_function` is presentation only.
+fn same_variable(a: &Identifier, b: &Identifier) -> bool {
+    a.body == b.body
+        && a.pre_script == b.pre_script
+        && a.mid_script == b.mid_script
+        && a.post_script == b.post_script
+        && a.primes == b.primes
+}
+
+fn symbol(node: &MathNode) -> Option<&str> {
+    match node.content.as_ref() {
+        MathNodeContent::Text(text) | MathNodeContent::String(text) => Some(text.trim()),
+        _ => None,
+    }
+}
+
+fn integer(node: &MathNode) -> Option<i64> {
+    match node.content.as_ref() {
+        MathNodeContent::Quantity {
+            number,
+            scientific_notation: None,
+            unit: None,
+        } => number.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+fn is_number(node: &MathNode, value: i64) -> bool {
+    integer(node) == Some(value)
+}
+
+fn is_euler(node: &MathNode) -> bool {
+    matches!(
+        node.content.as_ref(),
+        MathNodeContent::Identifier(Identifier {
+            body,
+            pre_script: None,
+            mid_script: None,
+            post_script: None,
+            primes: 0,
+            ..
+        }) if body == "e"
+    )
+}
+
+fn number(id: String, value: i64) -> MathNode {
+    MathNode {
+        id,
+        content: Arc::new(MathNodeContent::Quantity {
+            number: value.to_string(),
+            scientific_notation: None,
+            unit: None,
+        }),
+    }
+}
+
+fn bracketed(id: String, inner: MathNode) -> MathNode {
+    MathNode {
+        id,
+        content: Arc::new(MathNodeContent::Bracketed {
+            inner: Arc::new(inner),
+            style: BracketStyle::Round,
+            size: BracketSize::Normal,
+        }),
+    }
+}
+
+/// `node`, in brackets if it is a sum or a negation, for use as a factor.
+fn factor(node: MathNode) -> MathNode {
+    match node.content.as_ref() {
+        MathNodeContent::Additions { .. }
+        | MathNodeContent::UnaryPrefixOperation { .. }
+        | MathNodeContent::BinaryOperation {
+            operation_type: BinaryOperationType::Addition,
+            ..
+        } => bracketed(format!("{}-bracketed", node.id), node),
+        _ => node,
+    }
+}
+
+fn call(id: String, function: &str, argument: MathNode) -> MathNode {
+    MathNode {
+        content: Arc::new(MathNodeContent::FunctionCall {
+            name: Arc::new(MathNode {
+                id: format!("{}-name", id),
+                content: Arc::new(MathNodeContent::Identifier(Identifier {
+                    is_function: true,
+                    ..Identifier::new_simple(function.to_string())
+                })),
+            }),
+            parameters: vec![argument],
+        }),
+        id,
+    }
+}
+
+/// The sum of `terms`, each subtracted when its flag is set, leaving out zeros.
+fn sum(id: String, terms: Vec<(bool, MathNode)>) -> MathNode {
+    let mut terms: Vec<(bool, MathNode)> = terms
+        .into_iter()
+        .filter(|(_, term)| !is_number(term, 0))
+        .collect();
+    match terms.len() {
+        0 => number(id, 0),
+        1 => {
+            let (negative, term) = terms.remove(0);
+            if negative { negate(id, term) } else { term }
+        }
+        _ => MathNode {
+            id,
+            content: Arc::new(MathNodeContent::BinaryOperation {
+                operation_type: BinaryOperationType::Addition,
+                terms: terms
+                    .into_iter()
+                    .map(|(negative, term)| {
+                        let operator = if negative {
+                            BinaryOperator::Minus
+                        } else {
+                            BinaryOperator::Plus
+                        };
+                        (operator, factor(term))
+                    })
+                    .collect(),
+            }),
+        },
+    }
+}
+
+/// The product of `factors`, leaving out ones; zero if one of them is zero.
+fn product(id: String, factors: Vec<MathNode>) -> MathNode {
+    if factors.iter().any(|factor| is_number(factor, 0)) {
+        return number(id, 0);
+    }
+    let mut factors: Vec<MathNode> = factors
+        .into_iter()
+        .filter(|factor| !is_number(factor, 1))
+        .collect();
+    match factors.len() {
+        0 => number(id, 1),
+        1 => factors.remove(0),
+        _ => MathNode {
+            id,
+            content: Arc::new(MathNodeContent::BinaryOperation {
+                operation_type: BinaryOperationType::Multiplication,
+                terms: factors
+                    .into_iter()
+                    .map(|term| (BinaryOperator::Dot, factor(term)))
+                    .collect(),
+            }),
+        },
+    }
+}
+
+fn fraction(id: String, numerator: MathNode, denominator: MathNode) -> MathNode {
+    if is_number(&numerator, 0) || is_number(&denominator, 1) {
+        return numerator;
+    }
+    MathNode {
+        id,
+        content: Arc::new(MathNodeContent::Fraction {
+            numerator: Arc::new(numerator),
+            denominator: Arc::new(denominator),
+        }),
+    }
+}
+
+fn power(id: String, base: MathNode, exponent: MathNode) -> MathNode {
+    if is_number(&exponent, 0) {
+        return number(id, 1);
+    }
+    if is_number(&exponent, 1) {
+        return base;
+    }
+    let base = match base.content.as_ref() {
+        MathNodeContent::Identifier(_)
+        | MathNodeContent::Quantity { .. }
+        | MathNodeContent::FunctionCall { .. }
+        | MathNodeContent::Bracketed { .. }
+        | MathNodeContent::Abs { .. }
+        | MathNodeContent::Radical { .. } => base,
+        _ => bracketed(format!("{}-base", id), base),
+    };
+    MathNode {
+        id,
+        content: Arc::new(MathNodeContent::Power {
+            base: Arc::new(base),
+            exponent: Arc::new(exponent),
+        }),
+    }
+}
+
+/// `-node`, undoing a negation rather than doubling it.
+fn negate(id: String, node: MathNode) -> MathNode {
+    if is_number(&node, 0) {
+        return node;
+    }
+    if let MathNodeContent::UnaryPrefixOperation {
+        parameter,
+        operator,
+    } = node.content.as_ref()
+        && matches!(symbol(operator), Some("-" | "−"))
+    {
+        return parameter.as_ref().clone();
+    }
+    MathNode {
+        content: Arc::new(MathNodeContent::UnaryPrefixOperation {
+            operator: Arc::new(MathNode {
+                id: format!("{}-operator", id),
+                content: Arc::new(MathNodeContent::String("-".to_string())),
+            }),
+            parameter: Arc::new(factor(node)),
+        }),
+        id,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DifferentiationError {
+    /// A node that is not a term, such as a relation, a definition or a logical connective.
+    Unsupported {
+        kind: &'static str, // `MathNodeContent::kind_name` of the node
+    },
+}
+
+impl fmt::Display for DifferentiationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DifferentiationError::Unsupported { kind } => {
+                write!(f, "{} cannot be differentiated", kind)
+            }
+        }
+    }
+}
+
+impl std::error::Error for DifferentiationError {}
@@ -0,0 +1,165 @@
+use super::*;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// How hard a section, example or exercise is, for adaptive-learning consumers that sequence
+/// content by difficulty.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct DifficultyAnnotation {
+    pub level: DifficultyLevel,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cognitive_load: Option<CognitiveLoad>,
+    pub role: ContentRole,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimated_minutes: Option<u32>, // Expected time for a learner at the target level
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub enum DifficultyLevel {
+    Introductory,
+    Basic,
+    Intermediate,
+    Advanced,
+    Research,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub enum CognitiveLoad {
+    Low,
+    Moderate,
+    High, // Many interacting ideas held at once
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub enum ContentRole {
+    Exposition,
+    Example,
+    Exercise,
+}
+
+/// Difficulty rollup of one chapter (a top-level body section) and everything nested in it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct DifficultyProfile {
+    pub section_id: String,
+    pub annotated_count: usize,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub level_counts: Vec<(DifficultyLevel, usize)>, // Only levels that occur, easiest first
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mean_level: Option<f64>, // 0.0 = Introductory ... 4.0 = Research
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub peak_level: Option<DifficultyLevel>,
+    pub high_load_count: usize,
+    pub example_count: usize,
+    pub exercise_count: usize,
+    pub estimated_minutes: u32,
+}
+
+impl DifficultyAnnotation {
+    pub fn new(level: DifficultyLevel, role: ContentRole) -> Self {
+        DifficultyAnnotation {
+            level,
+            cognitive_load: None,
+            role,
+            estimated_minutes: None,
+        }
+    }
+
+    pub fn with_cognitive_load(mut self, cognitive_load: CognitiveLoad) -> Self {
+        self.cognitive_load = Some(cognitive_load);
+        self
+    }
+
+    pub fn with_estimated_minutes(mut self, estimated_minutes: u32) -> Self {
+        self.estimated_minutes = Some(estimated_minutes);
+        self
+    }
+}
+
+impl DifficultyLevel {
+    /// Position on a 0 (introductory) to 4 (research) scale.
+    pub fn score(self) -> u8 {
+        self as u8
+    }
+}
+
+impl DifficultyProfile {
+    /// Rolls up the annotations on `chapter` and every section nested in it.
+    pub fn for_section(chapter: &Section) -> Self {
+        let mut collector = AnnotationCollector::default();
+        collector.visit_section(chapter);
+
+        let annotations = collector.annotations;
+        let mut level_counts: Vec<(DifficultyLevel, usize)> = vec![];
+        for annotation in &annotations {
+            match level_counts
+                .iter_mut()
+                .find(|(level, _)| *level == annotation.level)
+            {
+                Some((_, count)) => *count += 1,
+                None => level_counts.push((annotation.level, 1)),
+            }
+        }
+        level_counts.sort();
+
+        let total_score: u32 = annotations.iter().map(|a| a.level.score() as u32).sum();
+        let count_role = |role: ContentRole| annotations.iter().filter(|a| a.role == role).count();
+        DifficultyProfile {
+            section_id: chapter.id.clone(),
+            annotated_count: annotations.len(),
+            level_counts,
+            mean_level: (!annotations.is_empty())
+                .then(|| total_score as f64 / annotations.len() as f64),
+            peak_level: annotations.iter().map(|a| a.level).max(),
+            high_load_count: annotations
+                .iter()
+                .filter(|a| a.cognitive_load == Some(CognitiveLoad::High))
+                .count(),
+            example_count: count_role(ContentRole::Example),
+            exercise_count: count_role(ContentRole::Exercise),
+            estimated_minutes: annotations.iter().filter_map(|a| a.estimated_minutes).sum(),
+        }
+    }
+}
+
+impl MathDocument {
+    /// One profile per body section, in order.
+    pub fn difficulty_profiles(&self) -> Vec<DifficultyProfile> {
+        self.content_type
+            .structure()
+            .map(|structure| {
+                structure
+                    .body
+                    .iter()
+                    .map(DifficultyProfile::for_section)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Recomputes the stored `difficulty_profiles` of textbooks and study notes.
+    pub fn refresh_difficulty_profiles(&mut self) {
+        let profiles = self.difficulty_profiles();
+        match &mut self.content_type {
+            MathDocumentType::Textbook(c) => c.difficulty_profiles = profiles,
+            MathDocumentType::StudyNotes(c) => c.difficulty_profiles = profiles,
+            _ => {}
+        }
+    }
+}
+
+#[derive(Default)]
+struct AnnotationCollector<'doc> {
+    annotations: Vec<&'doc DifficultyAnnotation>,
+}
+
+impl<'doc> DocumentVisitor<'doc> for AnnotationCollector<'doc> {
+    fn visit_section(&mut self, section: &'doc Section) {
+        self.annotations.extend(&section.difficulty);
+        walk_section(self, section);
+    }
+}
@@ -0,0 +1,414 @@
+use super::*;
+use std::{collections::BTreeMap, fmt};
+
+/// An expression whose quantities do not fit together dimensionally, typically a slip in
+/// physics content such as an equation with metres on one side and seconds on the other.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DimensionIssue {
+    /// The sides of an equation or inequality differ in dimension, e.g. `5 m = 3 s`.
+    MismatchedSides {
+        path: NodePath, // the relationship
+        lhs: Dimension,
+        rhs: Dimension,
+    },
+    /// Terms of a sum, branches of a piecewise expression, or arguments of `min` or `max`, that
+    /// differ in dimension, e.g. `5 m + 3 s`.
+    MismatchedTerms {
+        path: NodePath, // the sum, cases or call
+        first: Dimension,
+        other: Dimension,
+    },
+    /// A quantity with a dimension where only a pure number makes sense, e.g. `sin(3 m)`.
+    NotDimensionless {
+        path: NodePath, // the function argument or exponent
+        dimension: Dimension,
+    },
+}
+
+impl DimensionIssue {
+    pub fn path(&self) -> &NodePath {
+        match self {
+            DimensionIssue::MismatchedSides { path, .. }
+            | DimensionIssue::MismatchedTerms { path, .. }
+            | DimensionIssue::NotDimensionless { path, .. } => path,
+        }
+    }
+}
+
+impl fmt::Display for DimensionIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DimensionIssue::MismatchedSides { lhs, rhs, .. } => write!(
+                f,
+                "left-hand side has dimension {} but right-hand side has dimension {}",
+                lhs, rhs
+            ),
+            DimensionIssue::MismatchedTerms { first, other, .. } => {
+                write!(f, "terms of dimension {} and {} are combined", first, other)
+            }
+            DimensionIssue::NotDimensionless { dimension, .. } => {
+                write!(
+                    f,
+                    "expected a dimensionless value, found dimension {}",
+                    dimension
+                )
+            }
+        }
+    }
+}
+
+impl UnitRegistry {
+    /// The dimensional inconsistencies in `node`, with quantity units looked up in this
+    /// registry. Only what can be told from the quantities is checked: an expression with a
+    /// variable in a product, or a unit the registry does not know, has no dimension to compare,
+    /// and the literal `0` fits any dimension.
+    ///
+    /// Sums, `min` and `max` need terms of one dimension; equations and inequalities need sides
+    /// of one dimension; exponents and the arguments of `exp`, `ln`, the trigonometric and the
+    /// hyperbolic functions need to be dimensionless.
+    pub fn check_dimensions(&self, node: &MathNode) -> Vec<DimensionIssue> {
+        let mut checker = DimensionChecker {
+            registry: self,
+            issues: vec![],
+        };
+        checker.dimension(node, &NodePath::root());
+        checker.issues
+    }
+}
+
+impl MathNode {
+    /// The dimensional inconsistencies in this expression, for the built-in units, see
+    /// `UnitRegistry::check_dimensions`.
+    pub fn check_dimensions(&self) -> Vec<DimensionIssue> {
+        UnitRegistry::built_in().check_dimensions(self)
+    }
+}
+
+impl MathDocument {
+    /// The dimensional inconsistencies in every expression of the document, and in the steps
+    /// of its derivation chains, each with the id of the expression or chain it is in. Paths
+    /// of chain steps are within `DerivationChain::relationships`.
+    pub fn check_dimensions(&self, registry: &UnitRegistry) -> Vec<(String, DimensionIssue)> {
+        let mut issues = vec![];
+        for expression in self.expressions() {
+            for issue in registry.check_dimensions(expression) {
+                issues.push((expression.id.clone(), issue));
+            }
+        }
+        for chain in self.derivation_chains() {
+            for step in chain.relationships() {
+                // The expressions themselves were checked above.
+                issues.extend(
+                    registry
+                        .check_dimensions(&step)
+                        .into_iter()
+                        .filter(|issue| issue.path().is_root())
+                        .map(|issue| (chain.id.clone(), issue)),
+                );
+            }
+        }
+        issues
+    }
+}
+
+struct DimensionChecker<'a> {
+    registry: &'a UnitRegistry,
+    issues: Vec<DimensionIssue>,
+}
+
+impl DimensionChecker<'_> {
+    /// The dimension of `node`, `None` where it cannot be told. Checks everything within `node`
+    /// on the way.
+    fn dimension(&mut self, node: &MathNode, path: &NodePath) -> Option<Dimension> {
+        let field = |name: &str| path.clone().field(name);
+        match node.content.as_ref() {
+            MathNodeContent::Quantity { number, unit, .. } => {
+                if unit.is_none() && number.trim().parse::<f64>() == Ok(0.0) {
+                    return None;
+                }
+                self.registry.si_unit(node).ok().map(|unit| unit.dimension)
+            }
+            MathNodeContent::Unit { .. } => {
+                self.registry.si_unit(node).ok().map(|unit| unit.dimension)
+            }
+            MathNodeContent::Bracketed { inner, .. } => self.dimension(inner, &field("inner")),
+            MathNodeContent::Folded { content, .. } => self.dimension(content, &field("content")),
+            MathNodeContent::Abs { parameter } => self.dimension(parameter, &field("parameter")),
+            MathNodeContent::Norm { inner, .. } => self.dimension(inner, &field("inner")),
+            MathNodeContent::Decorated { inner, .. } => self.dimension(inner, &field("inner")),
+            MathNodeContent::Brace { inner, .. } => self.dimension(inner, &field("inner")),
+            MathNodeContent::Styled { inner, .. } => self.dimension(inner, &field("inner")),
+            MathNodeContent::Cancelled { inner, .. } => self.dimension(inner, &field("inner")),
+            MathNodeContent::AngleMeasure { value, .. } => {
+                self.dimensionless(value, &field("value"));
+                Some(Dimension::DIMENSIONLESS)
+            }
+            MathNodeContent::UnaryPrefixOperation {
+                parameter,
+                operator,
+            } if matches!(symbol(operator), Some("-" | "−" | "+" | "±")) => {
+                self.dimension(parameter, &field("parameter"))
+            }
+            MathNodeContent::BinaryOperation {
+                operation_type: BinaryOperationType::Addition,
+                terms,
+            } => self.same(path, "terms", terms.iter().map(|(_, term)| term)),
+            MathNodeContent::Additions { terms } => {
+                self.same(path, "terms", terms.iter().map(|(_, term)| term))
+            }
+            MathNodeContent::BinaryOperation {
+                operation_type: BinaryOperationType::Multiplication | BinaryOperationType::Division,
+                terms,
+            } => self.product(
+                path,
+                terms.iter().map(|(operator, term)| match operator {
+                    BinaryOperator::Slash | BinaryOperator::Divide => (-1, term),
+                    _ => (1, term),
+                }),
+            ),
+            MathNodeContent::Multiplications { terms } => self.product(
+                path,
+                terms.iter().map(|(operator, term)| match operator {
+                    RefinedMulOrDivOperation::Division(_) => (-1, term),
+                    _ => (1, term),
+                }),
+            ),
+            MathNodeContent::Fraction {
+                numerator,
+                denominator,
+            }
+            | MathNodeContent::Division {
+                numerator,
+                denominator,
+                ..
+            } => {
+                let numerator = self.dimension(numerator, &field("numerator"));
+                let denominator = self.dimension(denominator, &field("denominator"));
+                Some(combine(numerator?, denominator?, -1))
+            }
+            MathNodeContent::Power { base, exponent } => {
+                let base_dimension = self.dimension(base, &field("base"));
+                self.dimensionless(exponent, &field("exponent"));
+                let base_dimension = base_dimension?;
+                if base_dimension.is_dimensionless() {
+                    return Some(Dimension::DIMENSIONLESS);
+                }
+                let exponent = integer(exponent)?;
+                Some(combine(Dimension::DIMENSIONLESS, base_dimension, exponent))
+            }
+            MathNodeContent::Radical { radicand, index } => {
+                let dimension = self.dimension(radicand, &field("radicand"));
+                if let Some(index) = index {
+                    self.dimensionless(index, &field("index"));
+                }
+                let dimension = dimension?;
+                if dimension.is_dimensionless() {
+                    return Some(Dimension::DIMENSIONLESS);
+                }
+                match index {
+                    Some(index) => root(dimension, integer(index)?),
+                    None => root(dimension, 2),
+                }
+            }
+            MathNodeContent::Cases { branches } => {
+                let mut dimensions = vec![];
+                for (index, (expression, condition)) in branches.iter().enumerate() {
+                    let branch = field("branches").index(index);
+                    dimensions.extend(self.dimension(expression, &branch.clone().index(0)));
+                    self.dimension(condition, &branch.index(1));
+                }
+                self.common(path, dimensions)
+            }
+            MathNodeContent::Binomial { upper, lower, .. } => {
+                self.dimensionless(upper, &field("upper"));
+                self.dimensionless(lower, &field("lower"));
+                Some(Dimension::DIMENSIONLESS)
+            }
+            MathNodeContent::FunctionCall { name, parameters } => {
+                let field = field("parameters");
+                match function_name(name) {
+                    Some(
+                        "sin" | "cos" | "tan" | "cot" | "sec" | "csc" | "arcsin" | "arccos"
+                        | "arctan" | "sinh" | "cosh" | "tanh" | "exp" | "ln" | "log",
+                    ) => {
+                        for (index, parameter) in parameters.iter().enumerate() {
+                            self.dimensionless(parameter, &field.clone().index(index));
+                        }
+                        Some(Dimension::DIMENSIONLESS)
+                    }
+                    Some("min" | "max") => self.same(path, "parameters", parameters.iter()),
+                    Some("abs") if parameters.len() == 1 => {
+                        self.dimension(&parameters[0], &field.index(0))
+                    }
+                    Some("sqrt") if parameters.len() == 1 => {
+                        let dimension = self.dimension(&parameters[0], &field.index(0))?;
+                        root(dimension, 2)
+                    }
+                    _ => {
+                        self.check_children(node, path);
+                        None
+                    }
+                }
+            }
+            MathNodeContent::Relationship { lhs, rhs, operator } => {
+                let lhs_dimension = self.dimension(lhs, &field("lhs"));
+                let rhs_dimension = self.dimension(rhs, &field("rhs"));
+                if let (Some(lhs), Some(rhs)) = (lhs_dimension, rhs_dimension)
+                    && lhs != rhs
+                    && is_comparison(operator)
+                {
+                    self.issues.push(DimensionIssue::MismatchedSides {
+                        path: path.clone(),
+                        lhs,
+                        rhs,
+                    });
+                }
+                None
+            }
+            _ => {
+                self.check_children(node, path);
+                None
+            }
+        }
+    }
+
+    fn check_children(&mut self, node: &MathNode, path: &NodePath) {
+        for (child_path, child) in node.content.children_with_paths() {
+            self.dimension(child, &path.join(&child_path));
+        }
+    }
+
+    /// Reports `node` if it has a dimension.
+    fn dimensionless(&mut self, node: &MathNode, path: &NodePath) {
+        if let Some(dimension) = self.dimension(node, path)
+            && !dimension.is_dimensionless()
+        {
+            self.issues.push(DimensionIssue::NotDimensionless {
+                path: path.clone(),
+                dimension,
+            });
+        }
+    }
+
+    /// The common dimension of `terms`, the list `field` of the node at `path`, as far as it is
+    /// known. Reports the node if two terms differ.
+    fn same<'n>(
+        &mut self,
+        path: &NodePath,
+        field: &str,
+        terms: impl Iterator<Item = &'n MathNode>,
+    ) -> Option<Dimension> {
+        let dimensions: Vec<Dimension> = terms
+            .enumerate()
+            .filter_map(|(index, term)| {
+                self.dimension(term, &path.clone().field(field).index(index))
+            })
+            .collect();
+        self.common(path, dimensions)
+    }
+
+    /// The dimension all of `dimensions` share, reporting the node at `path` if two differ.
+    fn common(&mut self, path: &NodePath, dimensions: Vec<Dimension>) -> Option<Dimension> {
+        let first = *dimensions.first()?;
+        if let Some(other) = dimensions.iter().find(|dimension| **dimension != first) {
+            self.issues.push(DimensionIssue::MismatchedTerms {
+                path: path.clone(),
+                first,
+                other: *other,
+            });
+            return None;
+        }
+        Some(first)
+    }
+
+    /// The dimension of a product of `terms`, each with the exponent 1 or -1 it is taken to.
+    fn product<'n>(
+        &mut self,
+        path: &NodePath,
+        terms: impl Iterator<Item = (i32, &'n MathNode)>,
+    ) -> Option<Dimension> {
+        let mut product = Some(Dimension::DIMENSIONLESS);
+        for (index, (exponent, term)) in terms.enumerate() {
+            let dimension = self.dimension(term, &path.clone().field("terms").index(index));
+            product = product
+                .zip(dimension)
+                .map(|(product, dimension)| combine(product, dimension, exponent));
+        }
+        product
+    }
+}
+
+fn is_comparison(operator: &RelationOperatorNode) -> bool {
+    match operator {
+        RelationOperatorNode::IsEqual
+        | RelationOperatorNode::Equal
+        | RelationOperatorNode::NotEqual
+        | RelationOperatorNode::Greater
+        | RelationOperatorNode::Less
+        | RelationOperatorNode::GreaterEqual
+        | RelationOperatorNode::LessEqual => true,
+        RelationOperatorNode::Custom(symbol) => {
+            matches!(
+                symbol.trim(),
+                "≈" | "≃" | "≪" | "≫" | "≲" | "≳" | "≥" | "≤" | "≠"
+            )
+        }
+        _ => false,
+    }
+}
+
+fn combine(dimension: Dimension, other: Dimension, times: i32) -> Dimension {
+    Dimension {
+        length: dimension.length + other.length * times,
+        mass: dimension.mass + other.mass * times,
+        time: dimension.time + other.time * times,
+        current: dimension.current + other.current * times,
+        temperature: dimension.temperature + other.temperature * times,
+        amount: dimension.amount + other.amount * times,
+        luminous_intensity: dimension.luminous_intensity + other.luminous_intensity * times,
+    }
+}
+
+/// Each exponent divided by `n`, `None` unless they are all multiples of it.
+fn root(dimension: Dimension, n: i32) -> Option<Dimension> {
+    let divided = |exponent: i32| (n != 0 && exponent % n == 0).then(|| exponent / n);
+    Some(Dimension {
+        length: divided(dimension.length)?,
+        mass: divided(dimension.mass)?,
+        time: divided(dimension.time)?,
+        current: divided(dimension.current)?,
+        temperature: divided(dimension.temperature)?,
+        amount: divided(dimension.amount)?,
+        luminous_intensity: divided(dimension.luminous_intensity)?,
+    })
+}
+
+fn integer(node: &MathNode) -> Option<i32> {
+    match node.evaluate(&BTreeMap::new()) {
+        Ok(NumericValue::Rational {
+            numerator,
+            denominator: 1,
+        }) => i32::try_from(numerator).ok(),
+        _ => None,
+    }
+}
+
+fn function_name(name: &MathNode) -> Option<&str> {
+    match name.content.as_ref() {
+        MathNodeContent::Identifier(identifier)
+            if identifier.pre_script.is_none()
+                && identifier.mid_script.is_none()
+                && identifier.post_script.is_none() =>
+        {
+            Some(&identifier.body)
+        }
+        _ => symbol(name),
+    }
+}
+
+fn symbol(node: &MathNode) -> Option<&str> {
+    match node.content.as_ref() {
+        MathNodeContent::Text(text) | MathNodeContent::String(text) => Some(text.trim()),
+        _ => None,
+    }
+}
@@ -0,0 +1,365 @@
+use super::*;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+
+impl Section {
+    /// The `SubSection` lists in the section's content, in the order `SectionParent::Section`
+    /// counts them.
+    pub fn subsection_lists(&self) -> Vec<&Vec<Section>> {
+        let mut finder = SubsectionLists { lists: vec![] };
+        finder.visit_content_node(&self.content);
+        finder.lists
+    }
+
+    /// The section without the sections nested in it: its `SubSection` lists are emptied.
+    pub fn without_subsections(&self) -> Section {
+        let mut shell = self.clone();
+        SectionFilter {
+            keep: |_: &Section| false,
+        }
+        .visit_content_node_mut(&mut shell.content);
+        shell
+    }
+}
+
+impl MathDocument {
+    /// The document without its sections, for comparing or syncing everything else. The
+    /// revision history is left out too: it is not part of any revision.
+    pub fn without_sections(&self) -> MathDocument {
+        let mut shell = self.clone();
+        shell.revision_history = None;
+        if let Some(structure) = shell.content_type.structure_mut() {
+            structure.abstract_content = None;
+            structure.body.clear();
+            structure.footnotes.clear();
+            structure.glossary.clear();
+        } else if let Some(base_content) = shell.content_type.base_content_mut() {
+            base_content.clear();
+        }
+        shell
+    }
+}
+
+impl MathDocument {
+    /// The patch turning this document into `other`. Sections are matched by id, so a section
+    /// whose id changed is reported as removed and inserted; ids are assumed unique, as
+    /// `validate` checks.
+    pub fn diff(&self, other: &MathDocument) -> DocumentPatch {
+        let old = SectionTree::new(self);
+        let new = SectionTree::new(other);
+        let kept: HashSet<&str> = new
+            .entries
+            .iter()
+            .map(|entry| entry.section.id.as_str())
+            .filter(|id| old.get(id).is_some())
+            .collect();
+        let in_place = sections_in_place(&old, &new);
+
+        let mut operations = vec![];
+        let (old_shell, new_shell) = (self.without_sections(), other.without_sections());
+        if to_json(&old_shell) != to_json(&new_shell) {
+            operations.push(PatchOperation::UpdateDocument {
+                document: Box::new(new_shell),
+            });
+        }
+        for entry in &new.entries {
+            let id = entry.section.id.as_str();
+            let Some(old_entry) = old.get(id) else {
+                if parent_exists(&entry.parent, &kept) {
+                    operations.push(PatchOperation::InsertSection {
+                        parent: entry.parent.clone(),
+                        after: entry.after.map(str::to_string),
+                        section: without_kept(entry.section, &kept),
+                    });
+                }
+                continue;
+            };
+            if !in_place.contains(id) {
+                operations.push(PatchOperation::MoveSection {
+                    section_id: id.to_string(),
+                    parent: entry.parent.clone(),
+                    after: entry.after.map(str::to_string),
+                });
+            }
+            operations.extend(section_changes(old_entry.section, entry.section));
+        }
+        for entry in &old.entries {
+            let id = entry.section.id.as_str();
+            if !kept.contains(id) && parent_exists(&entry.parent, &kept) {
+                operations.push(PatchOperation::RemoveSection {
+                    section_id: id.to_string(),
+                });
+            }
+        }
+        DocumentPatch { operations }
+    }
+}
+
+/// Every section of a document with where it sits, in document order.
+struct SectionTree<'doc> {
+    entries: Vec<SectionEntry<'doc>>,
+    lists: Vec<(SectionParent, Vec<&'doc str>)>,
+}
+
+struct SectionEntry<'doc> {
+    section: &'doc Section,
+    parent: SectionParent,
+    after: Option<&'doc str>, // the previous section in the same list
+}
+
+impl<'doc> SectionTree<'doc> {
+    fn new(document: &'doc MathDocument) -> Self {
+        let mut tree = SectionTree {
+            entries: vec![],
+            lists: vec![],
+        };
+        for (parent, sections) in top_level_lists(document) {
+            tree.add_list(parent, sections);
+        }
+        tree
+    }
+
+    fn add_list(&mut self, parent: SectionParent, sections: Vec<&'doc Section>) {
+        let ids = sections.iter().map(|section| section.id.as_str()).collect();
+        self.lists.push((parent.clone(), ids));
+        let mut after = None;
+        for section in sections {
+            self.entries.push(SectionEntry {
+                section,
+                parent: parent.clone(),
+                after,
+            });
+            after = Some(section.id.as_str());
+            for (list, subsections) in section.subsection_lists().into_iter().enumerate() {
+                let parent = SectionParent::Section {
+                    section_id: section.id.clone(),
+                    list,
+                };
+                self.add_list(parent, subsections.iter().collect());
+            }
+        }
+    }
+
+    fn get(&self, section_id: &str) -> Option<&SectionEntry<'doc>> {
+        self.entries
+            .iter()
+            .find(|entry| entry.section.id == section_id)
+    }
+}
+
+/// The document's top-level section lists, with the abstract as a list of at most one.
+fn top_level_lists(document: &MathDocument) -> Vec<(SectionParent, Vec<&Section>)> {
+    let content_type = &document.content_type;
+    if let Some(structure) = content_type.structure() {
+        vec![
+            (
+                SectionParent::Abstract,
+                structure.abstract_content.iter().collect(),
+            ),
+            (SectionParent::Body, structure.body.iter().collect()),
+            (
+                SectionParent::Footnotes,
+                structure.footnotes.iter().collect(),
+            ),
+            (SectionParent::Glossary, structure.glossary.iter().collect()),
+        ]
+    } else if let Some(base_content) = content_type.base_content() {
+        vec![(SectionParent::BaseContent, base_content.iter().collect())]
+    } else {
+        vec![]
+    }
+}
+
+struct SubsectionLists<'doc> {
+    lists: Vec<&'doc Vec<Section>>,
+}
+
+impl<'doc> DocumentVisitor<'doc> for SubsectionLists<'doc> {
+    fn visit_document(&mut self, _document: &'doc MathDocument) {}
+
+    fn visit_content_node(&mut self, node: &'doc SectionContentNode) {
+        match node {
+            SectionContentNode::SubSection(sections) => self.lists.push(sections),
+            _ => walk_content_node(self, node),
+        }
+    }
+}
+
+/// The kept sections that stay where they are: those in the same list in both versions,
+/// in the same order relative to each other as far as possible. The others are moved.
+fn sections_in_place<'doc>(old: &SectionTree<'doc>, new: &SectionTree<'doc>) -> HashSet<&'doc str> {
+    let old_parents: HashMap<&str, &SectionParent> = old
+        .entries
+        .iter()
+        .map(|entry| (entry.section.id.as_str(), &entry.parent))
+        .collect();
+    let new_parents: HashMap<&str, &SectionParent> = new
+        .entries
+        .iter()
+        .map(|entry| (entry.section.id.as_str(), &entry.parent))
+        .collect();
+    let mut in_place = HashSet::new();
+    for (parent, new_ids) in &new.lists {
+        let Some((_, old_ids)) = old.lists.iter().find(|(p, _)| p == parent) else {
+            continue;
+        };
+        let olds: Vec<&str> = old_ids
+            .iter()
+            .copied()
+            .filter(|id| new_parents.get(id) == Some(&parent))
+            .collect();
+        let news: Vec<&str> = new_ids
+            .iter()
+            .copied()
+            .filter(|id| old_parents.get(id) == Some(&parent))
+            .collect();
+        in_place.extend(longest_common_subsequence(&olds, &news));
+    }
+    in_place
+}
+
+fn longest_common_subsequence<'a>(a: &[&'a str], b: &[&'a str]) -> Vec<&'a str> {
+    // lengths[i][j]: longest common subsequence of a[i..] and b[j..]
+    let mut lengths = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lengths[i][j] = if a[i] == b[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+    let mut common = vec![];
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() && j < b.len() {
+        if a[i] == b[j] {
+            common.push(a[i]);
+            i += 1;
+            j += 1;
+        } else if lengths[i + 1][j] >= lengths[i][j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    common
+}
+
+/// Whether the list exists in both versions: top-level lists always do, nested ones when their
+/// section is kept. Sections in other lists come and go with their parent.
+fn parent_exists(parent: &SectionParent, kept: &HashSet<&str>) -> bool {
+    match parent {
+        SectionParent::Section { section_id, .. } => kept.contains(section_id.as_str()),
+        _ => true,
+    }
+}
+
+/// The operations updating a kept section's own data: `ReplaceMath` for each changed expression
+/// if nothing else changed, `UpdateSection` otherwise.
+fn section_changes(old: &Section, new: &Section) -> Vec<PatchOperation> {
+    let (old_shell, new_shell) = (old.without_subsections(), new.without_subsections());
+    if to_json(&old_shell) == to_json(&new_shell) {
+        return vec![];
+    }
+    let (mut old_stub, mut new_stub) = (old_shell, new_shell.clone());
+    let old_math = stub_math(&mut old_stub);
+    let new_math = stub_math(&mut new_stub);
+    if to_json(&old_stub) != to_json(&new_stub) {
+        return vec![PatchOperation::UpdateSection {
+            section_id: new.id.clone(),
+            section: new_shell,
+        }];
+    }
+    old_math
+        .into_iter()
+        .zip(new_math)
+        .filter(|(old, new)| old != new)
+        .flat_map(|(old, new)| math_changes(&old, new))
+        .collect()
+}
+
+/// `ReplaceMath` for each replaced subtree when that is all `MathNode::diff` finds, or for the
+/// whole expression.
+fn math_changes(old: &MathNode, new: MathNode) -> Vec<PatchOperation> {
+    let edits = old.diff(&new);
+    let replacements: Option<Vec<PatchOperation>> = edits
+        .into_iter()
+        .map(|edit| match edit {
+            MathEdit::Replace {
+                old_path,
+                new_path,
+                new,
+                ..
+            } if old_path == new_path => Some(PatchOperation::ReplaceMath {
+                root_id: old.id.clone(),
+                path: old_path,
+                node: new,
+            }),
+            _ => None,
+        })
+        .collect();
+    replacements.unwrap_or_else(|| {
+        vec![PatchOperation::ReplaceMath {
+            root_id: old.id.clone(),
+            path: NodePath::root(),
+            node: new,
+        }]
+    })
+}
+
+/// The section without the nested sections that are kept, which are moved in on their own.
+fn without_kept(section: &Section, kept: &HashSet<&str>) -> Section {
+    let mut section = section.clone();
+    let mut filter = SectionFilter {
+        keep: |nested: &Section| !kept.contains(nested.id.as_str()),
+    };
+    filter.visit_content_node_mut(&mut section.content);
+    section
+}
+
+/// Removes the nested sections `keep` rejects, looking into the ones it keeps.
+struct SectionFilter<F> {
+    keep: F,
+}
+
+impl<F: FnMut(&Section) -> bool> DocumentVisitorMut for SectionFilter<F> {
+    fn visit_document_mut(&mut self, _document: &mut MathDocument) {}
+
+    fn visit_content_node_mut(&mut self, node: &mut SectionContentNode) {
+        if let SectionContentNode::SubSection(sections) = node {
+            sections.retain(|section| (self.keep)(section));
+        }
+        walk_content_node_mut(self, node);
+    }
+}
+
+/// Replaces the section's expressions by empty nodes with the same id, returning the
+/// expressions in document order.
+fn stub_math(section: &mut Section) -> Vec<MathNode> {
+    let mut stubber = MathStubber {
+        expressions: vec![],
+    };
+    stubber.visit_section_mut(section);
+    stubber.expressions
+}
+
+struct MathStubber {
+    expressions: Vec<MathNode>,
+}
+
+impl DocumentVisitorMut for MathStubber {
+    fn visit_math_node_mut(&mut self, node: &mut MathNode) {
+        let stub = MathNode {
+            id: node.id.clone(),
+            ..MathNode::empty()
+        };
+        self.expressions.push(std::mem::replace(node, stub));
+    }
+}
+
+/// For comparing types without `PartialEq`.
+fn to_json(value: &impl Serialize) -> Value {
+    serde_json::to_value(value).unwrap_or_default()
+}
@@ -0,0 +1,476 @@
+use super::*;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use ts_rs::TS;
+
+/// A list sections are kept in: one of the document's top-level lists, or the `list`th
+/// `SubSection` list in the content of a section, counted in document order without looking
+/// into nested sections or embedded documents.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub enum SectionParent {
+    Abstract, // holds at most one section: inserting into it replaces the current abstract
+    Body,
+    Footnotes,
+    Glossary,
+    BaseContent,
+    Section { section_id: String, list: usize },
+}
+
+/// Changes to a document, addressed by section id and by expression id and `NodePath`, as
+/// `MathDocument::diff` produces them and `MathDocument::apply` applies them. Operations
+/// apply in order: `diff` inserts and moves sections in the new document's order, after the
+/// sibling they follow there, and removes sections last, once the sections that survive have
+/// been moved out of them.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct DocumentPatch {
+    pub operations: Vec<PatchOperation>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub enum PatchOperation {
+    /// New data for the document outside its sections: id, title, metadata, relationships and
+    /// so on. `document` has no sections; the current ones stay in their lists.
+    UpdateDocument { document: Box<MathDocument> },
+    /// A new section, placed after the `after` section in `parent`, or first. Nested sections
+    /// that already exist elsewhere are left out of `section` and moved in separately.
+    InsertSection {
+        parent: SectionParent,
+        after: Option<String>,
+        section: Section,
+    },
+    MoveSection {
+        section_id: String,
+        parent: SectionParent,
+        after: Option<String>,
+    },
+    /// New title, metadata and content for a section. `section` has empty `SubSection` lists;
+    /// the current nested sections stay in the lists they are in, counted as `SectionParent`
+    /// does, and must be moved or removed if their list is gone.
+    UpdateSection {
+        section_id: String,
+        section: Section,
+    },
+    /// Replaces the node at `path` below the expression with id `root_id`, as
+    /// `MathDocument::replace_at_path` does.
+    ReplaceMath {
+        root_id: String,
+        path: NodePath,
+        node: MathNode,
+    },
+    /// Removes the section along with the sections nested in it.
+    RemoveSection { section_id: String },
+}
+
+impl DocumentPatch {
+    pub fn is_empty(&self) -> bool {
+        self.operations.is_empty()
+    }
+}
+
+impl MathDocument {
+    /// Applies the patch's operations in order and returns the patch that undoes them, for
+    /// undo and redo stacks. If an operation fails the document is left as it was.
+    pub fn apply(&mut self, patch: &DocumentPatch) -> Result<DocumentPatch, PatchError> {
+        let mut patcher = Patcher {
+            document: self.clone(),
+            detached: vec![],
+        };
+        for operation in &patch.operations {
+            patcher.apply(operation)?;
+        }
+        let undo = patcher.document.diff(self);
+        *self = patcher.document;
+        Ok(undo)
+    }
+}
+
+struct Patcher {
+    document: MathDocument,
+    // Sections out of the document until an operation moves them back in: abstracts replaced
+    // by a new one and sections whose list an update removed. Whatever is left is dropped.
+    detached: Vec<Section>,
+}
+
+impl Patcher {
+    fn apply(&mut self, operation: &PatchOperation) -> Result<(), PatchError> {
+        match operation {
+            PatchOperation::UpdateDocument { document } => {
+                let lists = self.take_top_level_lists();
+                let revision_history = self.document.revision_history.take();
+                self.document = (**document).clone();
+                self.document.revision_history = revision_history;
+                for (parent, sections) in lists {
+                    for section in sections.into_iter().rev() {
+                        if let Err(PatchError::UnknownParent { .. }) =
+                            self.insert(&parent, None, section.clone())
+                        {
+                            self.detached.push(section);
+                        }
+                    }
+                }
+                Ok(())
+            }
+            PatchOperation::InsertSection {
+                parent,
+                after,
+                section,
+            } => {
+                if self.with_section(&section.id, |_| ()).is_some() {
+                    return Err(PatchError::DuplicateSection {
+                        section_id: section.id.clone(),
+                    });
+                }
+                self.insert(parent, after.as_deref(), section.clone())
+            }
+            PatchOperation::MoveSection {
+                section_id,
+                parent,
+                after,
+            } => {
+                let section = self.take_section(section_id)?;
+                self.insert(parent, after.as_deref(), section)
+            }
+            PatchOperation::UpdateSection {
+                section_id,
+                section,
+            } => {
+                let left_over = self
+                    .with_section(section_id, |current| {
+                        let lists = take_subsection_lists(current);
+                        *current = section.clone();
+                        fill_subsection_lists(current, lists)
+                    })
+                    .ok_or_else(|| PatchError::UnknownSection {
+                        section_id: section_id.clone(),
+                    })?;
+                self.detached.extend(left_over);
+                Ok(())
+            }
+            PatchOperation::ReplaceMath {
+                root_id,
+                path,
+                node,
+            } => self
+                .document
+                .replace_at_path(root_id, path, node.clone())
+                .map(|_| ())
+                .map_err(|error| PatchError::InvalidMath { error }),
+            PatchOperation::RemoveSection { section_id } => {
+                self.take_section(section_id).map(|_| ())
+            }
+        }
+    }
+
+    /// Puts `section` into `parent`, after the section with id `after` or first.
+    fn insert(
+        &mut self,
+        parent: &SectionParent,
+        after: Option<&str>,
+        section: Section,
+    ) -> Result<(), PatchError> {
+        let unknown_parent = || PatchError::UnknownParent {
+            parent: parent.clone(),
+        };
+        if *parent == SectionParent::Abstract {
+            let structure = self.document.content_type.structure_mut();
+            let abstract_content = &mut structure.ok_or_else(unknown_parent)?.abstract_content;
+            self.detached.extend(abstract_content.replace(section));
+            return Ok(());
+        }
+        let inserted = self.with_list(parent, |list| {
+            let index = match after {
+                Some(after) => list.iter().position(|sibling| sibling.id == after)? + 1,
+                None => 0,
+            };
+            list.insert(index, section);
+            Some(())
+        });
+        match inserted {
+            Some(Some(())) => Ok(()),
+            Some(None) => Err(PatchError::UnknownSection {
+                section_id: after.unwrap_or_default().to_string(),
+            }),
+            None => Err(unknown_parent()),
+        }
+    }
+
+    /// Runs `action` on the list `parent` names, other than the abstract.
+    fn with_list<R>(
+        &mut self,
+        parent: &SectionParent,
+        action: impl FnOnce(&mut Vec<Section>) -> R,
+    ) -> Option<R> {
+        let content_type = &mut self.document.content_type;
+        match parent {
+            SectionParent::Abstract => None,
+            SectionParent::Body => Some(action(&mut content_type.structure_mut()?.body)),
+            SectionParent::Footnotes => Some(action(&mut content_type.structure_mut()?.footnotes)),
+            SectionParent::Glossary => Some(action(&mut content_type.structure_mut()?.glossary)),
+            SectionParent::BaseContent => Some(action(content_type.base_content_mut()?)),
+            SectionParent::Section { section_id, list } => self
+                .with_section(section_id, |section| {
+                    let mut finder = ListFinder {
+                        index: *list,
+                        action: Some(action),
+                        result: None,
+                    };
+                    finder.visit_content_node_mut(&mut section.content);
+                    finder.result
+                })
+                .flatten(),
+        }
+    }
+
+    /// Runs `action` on the section with id `section_id`, in the document or detached.
+    fn with_section<R>(
+        &mut self,
+        section_id: &str,
+        action: impl FnOnce(&mut Section) -> R,
+    ) -> Option<R> {
+        let mut finder = SectionFinder {
+            section_id,
+            action: Some(action),
+            result: None,
+        };
+        let sections = self.document.content_type.top_level_sections_mut();
+        for section in sections.into_iter().chain(&mut self.detached) {
+            finder.visit_section_mut(section);
+        }
+        finder.result
+    }
+
+    /// Takes the section with id `section_id` out of the document or the detached sections.
+    fn take_section(&mut self, section_id: &str) -> Result<Section, PatchError> {
+        if let Some(structure) = self.document.content_type.structure_mut()
+            && structure
+                .abstract_content
+                .as_ref()
+                .is_some_and(|section| section.id == section_id)
+        {
+            return Ok(structure.abstract_content.take().unwrap());
+        }
+        let take = |list: &mut Vec<Section>| {
+            let index = list.iter().position(|section| section.id == section_id)?;
+            Some(list.remove(index))
+        };
+        let top_level = [
+            SectionParent::Body,
+            SectionParent::Footnotes,
+            SectionParent::Glossary,
+            SectionParent::BaseContent,
+        ];
+        for parent in &top_level {
+            if let Some(Some(section)) = self.with_list(parent, take) {
+                return Ok(section);
+            }
+        }
+        if let Some(section) = take(&mut self.detached) {
+            return Ok(section);
+        }
+        let mut taker = SectionTaker {
+            section_id,
+            taken: None,
+        };
+        let sections = self.document.content_type.top_level_sections_mut();
+        for section in sections.into_iter().chain(&mut self.detached) {
+            taker.visit_section_mut(section);
+        }
+        taker.taken.ok_or_else(|| PatchError::UnknownSection {
+            section_id: section_id.to_string(),
+        })
+    }
+
+    fn take_top_level_lists(&mut self) -> Vec<(SectionParent, Vec<Section>)> {
+        let content_type = &mut self.document.content_type;
+        if let Some(structure) = content_type.structure_mut() {
+            let abstract_content = structure.abstract_content.take();
+            vec![
+                (
+                    SectionParent::Abstract,
+                    abstract_content.into_iter().collect(),
+                ),
+                (SectionParent::Body, std::mem::take(&mut structure.body)),
+                (
+                    SectionParent::Footnotes,
+                    std::mem::take(&mut structure.footnotes),
+                ),
+                (
+                    SectionParent::Glossary,
+                    std::mem::take(&mut structure.glossary),
+                ),
+            ]
+        } else if let Some(base_content) = content_type.base_content_mut() {
+            vec![(SectionParent::BaseContent, std::mem::take(base_content))]
+        } else {
+            vec![]
+        }
+    }
+}
+
+struct SectionFinder<'a, F, R> {
+    section_id: &'a str,
+    action: Option<F>,
+    result: Option<R>,
+}
+
+impl<F: FnOnce(&mut Section) -> R, R> DocumentVisitorMut for SectionFinder<'_, F, R> {
+    fn visit_document_mut(&mut self, _document: &mut MathDocument) {}
+
+    fn visit_section_mut(&mut self, section: &mut Section) {
+        if section.id == self.section_id
+            && let Some(action) = self.action.take()
+        {
+            self.result = Some(action(section));
+        } else if self.action.is_some() {
+            walk_section_mut(self, section);
+        }
+    }
+}
+
+/// Removes the section with id `section_id` from the `SubSection` lists it walks.
+struct SectionTaker<'a> {
+    section_id: &'a str,
+    taken: Option<Section>,
+}
+
+impl DocumentVisitorMut for SectionTaker<'_> {
+    fn visit_document_mut(&mut self, _document: &mut MathDocument) {}
+
+    fn visit_content_node_mut(&mut self, node: &mut SectionContentNode) {
+        if self.taken.is_some() {
+            return;
+        }
+        if let SectionContentNode::SubSection(sections) = node
+            && let Some(index) = sections.iter().position(|s| s.id == self.section_id)
+        {
+            self.taken = Some(sections.remove(index));
+            return;
+        }
+        walk_content_node_mut(self, node);
+    }
+}
+
+/// Runs `action` on the `index`th `SubSection` list of the content it walks, counting as
+/// `SectionParent::Section` does.
+struct ListFinder<F, R> {
+    index: usize,
+    action: Option<F>,
+    result: Option<R>,
+}
+
+impl<F: FnOnce(&mut Vec<Section>) -> R, R> DocumentVisitorMut for ListFinder<F, R> {
+    fn visit_document_mut(&mut self, _document: &mut MathDocument) {}
+
+    fn visit_content_node_mut(&mut self, node: &mut SectionContentNode) {
+        match node {
+            SectionContentNode::SubSection(sections) => {
+                if self.index == 0
+                    && let Some(action) = self.action.take()
+                {
+                    self.result = Some(action(sections));
+                }
+                self.index = self.index.saturating_sub(1);
+            }
+            _ => walk_content_node_mut(self, node),
+        }
+    }
+}
+
+/// Takes the sections out of each `SubSection` list of the section's content, in the order
+/// `SectionParent::Section` counts them.
+fn take_subsection_lists(section: &mut Section) -> Vec<Vec<Section>> {
+    let mut taker = ListTaker { lists: vec![] };
+    taker.visit_content_node_mut(&mut section.content);
+    taker.lists
+}
+
+/// Appends `lists` to the section's `SubSection` lists in order, returning the sections of
+/// the lists it has no counterpart for.
+fn fill_subsection_lists(section: &mut Section, lists: Vec<Vec<Section>>) -> Vec<Section> {
+    let mut filler = ListFiller {
+        lists: lists.into_iter(),
+    };
+    filler.visit_content_node_mut(&mut section.content);
+    filler.lists.flatten().collect()
+}
+
+struct ListTaker {
+    lists: Vec<Vec<Section>>,
+}
+
+impl DocumentVisitorMut for ListTaker {
+    fn visit_document_mut(&mut self, _document: &mut MathDocument) {}
+
+    fn visit_content_node_mut(&mut self, node: &mut SectionContentNode) {
+        match node {
+            SectionContentNode::SubSection(sections) => self.lists.push(std::mem::take(sections)),
+            _ => walk_content_node_mut(self, node),
+        }
+    }
+}
+
+struct ListFiller {
+    lists: std::vec::IntoIter<Vec<Section>>,
+}
+
+impl DocumentVisitorMut for ListFiller {
+    fn visit_document_mut(&mut self, _document: &mut MathDocument) {}
+
+    fn visit_content_node_mut(&mut self, node: &mut SectionContentNode) {
+        match node {
+            SectionContentNode::SubSection(sections) => {
+                sections.extend(self.lists.next().into_iter().flatten())
+            }
+            _ => walk_content_node_mut(self, node),
+        }
+    }
+}
+
+impl fmt::Display for SectionParent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SectionParent::Abstract => write!(f, "the abstract"),
+            SectionParent::Body => write!(f, "the body"),
+            SectionParent::Footnotes => write!(f, "the footnotes"),
+            SectionParent::Glossary => write!(f, "the glossary"),
+            SectionParent::BaseContent => write!(f, "the base content"),
+            SectionParent::Section { section_id, list } => {
+                write!(f, "subsection list {} of section `{}`", list, section_id)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PatchError {
+    UnknownSection { section_id: String },
+    UnknownParent { parent: SectionParent },
+    DuplicateSection { section_id: String },
+    InvalidMath { error: NodePathError },
+}
+
+impl fmt::Display for PatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PatchError::UnknownSection { section_id } => {
+                write!(f, "patch refers to unknown section `{}`", section_id)
+            }
+            PatchError::UnknownParent { parent } => {
+                write!(f, "patch inserts into {}, which does not exist", parent)
+            }
+            PatchError::DuplicateSection { section_id } => {
+                write!(
+                    f,
+                    "patch inserts section `{}`, which already exists",
+                    section_id
+                )
+            }
+            PatchError::InvalidMath { error } => write!(f, "patch replaces math: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for PatchError {}
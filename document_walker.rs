@@ -0,0 +1,411 @@
+use super::*;
+
+/// Read-only traversal over a `MathDocument` and everything nested in it.
+///
+/// The default methods reach every `Section`, `SectionContentNode`, `Panel`, `RichTextSegment`
+/// and embedded `MathNode`, including content inside panels, branching containers, proof displays,
+/// embedded documents and tooltip documents. A pass overrides the hooks it needs and calls the
+/// matching `walk_*` function to keep descending. `visit_math_node` is called once per embedded
+/// expression; use a `MathNodeVisitor` from there to look inside it. Lazy embedded and tooltip
+/// documents are loaded on the way; those that cannot be loaded are skipped.
+///
+/// The `'doc` lifetime lets visitors keep references into the document they walk.
+pub trait DocumentVisitor<'doc> {
+    fn visit_document(&mut self, document: &'doc MathDocument) {
+        walk_document(self, document);
+    }
+
+    fn visit_section(&mut self, section: &'doc Section) {
+        walk_section(self, section);
+    }
+
+    fn visit_content_node(&mut self, node: &'doc SectionContentNode) {
+        walk_content_node(self, node);
+    }
+
+    fn visit_panel(&mut self, panel: &'doc Panel) {
+        walk_panel(self, panel);
+    }
+
+    fn visit_second_order_math(&mut self, node: &'doc SecondOrderMathNode) {
+        walk_second_order_math(self, node);
+    }
+
+    fn visit_segment(&mut self, segment: &'doc RichTextSegment) {
+        walk_segment(self, segment);
+    }
+
+    fn visit_math_node(&mut self, _node: &'doc MathNode) {}
+}
+
+pub fn walk_document<'doc, V: DocumentVisitor<'doc> + ?Sized>(
+    visitor: &mut V,
+    document: &'doc MathDocument,
+) {
+    let content_type = &document.content_type;
+    if let Some(content) = content_type.simplified_content() {
+        for key_point in &content.key_points {
+            walk_segments(visitor, &key_point.content);
+        }
+        for definition in &content.essential_definitions {
+            walk_segments(visitor, &definition.simplified_definition);
+            if let Some(formal_definition) = &definition.formal_definition {
+                visitor.visit_math_node(formal_definition);
+            }
+            if let Some(explanation) = &definition.intuitive_explanation {
+                walk_segments(visitor, explanation);
+            }
+        }
+        for example in &content.core_examples {
+            walk_content_nodes(visitor, &example.content);
+        }
+    }
+    match content_type {
+        MathDocumentType::ComparisonPage(c) => {
+            for section in &c.comparison_structure.sections {
+                walk_content_nodes(visitor, &section.left_content);
+                walk_content_nodes(visitor, &section.right_content);
+                if let Some(notes) = &section.comparison_notes {
+                    walk_segments(visitor, notes);
+                }
+            }
+        }
+        MathDocumentType::TransformationMapping(c) => {
+            for step in &c.transformation_steps {
+                walk_segments(visitor, &step.description);
+            }
+        }
+        _ => {}
+    }
+    for section in content_type.top_level_sections() {
+        visitor.visit_section(section);
+    }
+}
+
+pub fn walk_section<'doc, V: DocumentVisitor<'doc> + ?Sized>(
+    visitor: &mut V,
+    section: &'doc Section,
+) {
+    walk_rich_text(visitor, &section.title);
+    visitor.visit_content_node(&section.content);
+}
+
+pub fn walk_content_node<'doc, V: DocumentVisitor<'doc> + ?Sized>(
+    visitor: &mut V,
+    node: &'doc SectionContentNode,
+) {
+    match node {
+        SectionContentNode::SubSection(sections) => {
+            for section in sections {
+                visitor.visit_section(section);
+            }
+        }
+        SectionContentNode::RichText(rich_text) => walk_segments(visitor, &rich_text.segments),
+        SectionContentNode::Math(math) => visitor.visit_math_node(math),
+        SectionContentNode::SecondOrderMath(second_order) => {
+            visitor.visit_second_order_math(second_order)
+        }
+        SectionContentNode::InteractiveDiagram(diagram) => {
+            walk_rich_text(visitor, &diagram.caption)
+        }
+        SectionContentNode::Feedback(feedback) => walk_segments(visitor, &feedback.prompt.segments),
+        SectionContentNode::Equation(equation) => visitor.visit_math_node(&equation.math),
+        SectionContentNode::DerivationChain(chain) => {
+            visitor.visit_math_node(&chain.start);
+            for step in &chain.steps {
+                visitor.visit_math_node(&step.expression);
+                walk_rich_text(visitor, &step.justification);
+            }
+        }
+        SectionContentNode::Theorem
+        | SectionContentNode::ThematicBreak(_)
+        | SectionContentNode::EmbeddedSectionRef(_)
+        | SectionContentNode::InteractiveControls(_) => {}
+        SectionContentNode::List(list) => {
+            for item in &list.items {
+                walk_content_nodes(visitor, &item.content);
+            }
+        }
+        SectionContentNode::Table(table) => {
+            walk_rich_text(visitor, &table.caption);
+            let rows = table
+                .header_rows
+                .iter()
+                .chain(&table.body_rows)
+                .chain(&table.footer_rows);
+            for row in rows {
+                for cell in &row.cells {
+                    walk_content_nodes(visitor, &cell.content);
+                }
+            }
+        }
+        SectionContentNode::CodeBlock(code) => walk_rich_text(visitor, &code.caption),
+        SectionContentNode::Image(image) => walk_rich_text(visitor, &image.caption),
+        SectionContentNode::CollapsibleBlock(block) => {
+            walk_segments(visitor, &block.summary);
+            walk_content_nodes(visitor, &block.details);
+        }
+        SectionContentNode::Grid(grid) => {
+            for item in &grid.items {
+                visitor.visit_content_node(&item.content);
+            }
+        }
+        SectionContentNode::Columns(columns) => {
+            for column in &columns.columns_content {
+                walk_content_nodes(visitor, column);
+            }
+        }
+        SectionContentNode::QuoteBlock {
+            content,
+            attribution,
+        } => {
+            for rich_text in content {
+                walk_segments(visitor, &rich_text.segments);
+            }
+            walk_rich_text(visitor, attribution);
+        }
+        SectionContentNode::AlertBox { content, .. } => walk_content_nodes(visitor, content),
+        SectionContentNode::CustomComponent {
+            fallback_content, ..
+        } => walk_content_nodes(visitor, fallback_content),
+        SectionContentNode::GenerationError(error) => {
+            walk_content_nodes(visitor, &error.fallback_content)
+        }
+        SectionContentNode::SideBySideLayout(layout) => {
+            visitor.visit_panel(&layout.left_panel);
+            visitor.visit_panel(&layout.right_panel);
+        }
+        SectionContentNode::PanelLayout(layout) => {
+            for panel in &layout.panels {
+                visitor.visit_panel(panel);
+            }
+        }
+        SectionContentNode::AnnotationOverlay(overlay) => {
+            for annotation in &overlay.annotations {
+                walk_segments(visitor, &annotation.annotation_content);
+            }
+            walk_content_nodes(visitor, &overlay.base_content);
+        }
+        SectionContentNode::EmbeddedDocument(document) => {
+            if let Ok(document) = document.get() {
+                visitor.visit_document(document)
+            }
+        }
+        SectionContentNode::BranchingContainer(container) => {
+            for node in &container.nodes {
+                walk_content_nodes(visitor, &node.content);
+            }
+        }
+    }
+}
+
+pub fn walk_content_nodes<'doc, V: DocumentVisitor<'doc> + ?Sized>(
+    visitor: &mut V,
+    nodes: &'doc [SectionContentNode],
+) {
+    for node in nodes {
+        visitor.visit_content_node(node);
+    }
+}
+
+pub fn walk_panel<'doc, V: DocumentVisitor<'doc> + ?Sized>(visitor: &mut V, panel: &'doc Panel) {
+    walk_rich_text(visitor, &panel.title);
+    walk_content_nodes(visitor, &panel.content);
+}
+
+pub fn walk_second_order_math<'doc, V: DocumentVisitor<'doc> + ?Sized>(
+    visitor: &mut V,
+    node: &'doc SecondOrderMathNode,
+) {
+    match node {
+        SecondOrderMathNode::Logic(logic) => walk_logical_node(visitor, logic),
+        SecondOrderMathNode::Judgement(judgement) => {
+            for declaration in &judgement.non_quantifiers {
+                walk_variable_declaration(visitor, declaration);
+            }
+            for group in &judgement.quantifiers {
+                walk_quantified_group(visitor, group);
+            }
+            walk_logical_node(visitor, &judgement.statement);
+        }
+        SecondOrderMathNode::SystemOf(equations) => {
+            for equation in equations {
+                visitor.visit_math_node(equation);
+            }
+        }
+        SecondOrderMathNode::Solution(solution) => {
+            for section in &solution.solution_space {
+                visitor.visit_section(section);
+            }
+        }
+        SecondOrderMathNode::VariableDeclaration(declaration) => {
+            walk_variable_declaration(visitor, declaration)
+        }
+        SecondOrderMathNode::QuantifiedVariableDeclarationGroup(group) => {
+            walk_quantified_group(visitor, group)
+        }
+        SecondOrderMathNode::InteractiveProof(proof) => {
+            for root in &proof.proof_forest.root_nodes {
+                walk_proof_node(visitor, root);
+            }
+            for data in &proof.transformation_data {
+                walk_expressions(visitor, &data.source_expressions);
+                walk_expressions(visitor, &data.target_expressions);
+                for instantiation in &data.instantiations {
+                    visitor.visit_math_node(&instantiation.instantiated_value);
+                }
+                for element in &data.interactive_elements {
+                    if let Some(expression) = &element.expression {
+                        visitor.visit_math_node(expression);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn walk_logical_node<'doc, V: DocumentVisitor<'doc> + ?Sized>(
+    visitor: &mut V,
+    node: &'doc LogicalNode,
+) {
+    match node {
+        LogicalNode::And(operands) | LogicalNode::Or(operands) => {
+            for operand in operands {
+                walk_logical_node(visitor, operand);
+            }
+        }
+        LogicalNode::Atomic(math) => visitor.visit_math_node(math),
+        LogicalNode::True | LogicalNode::False => {}
+    }
+}
+
+fn walk_variable_declaration<'doc, V: DocumentVisitor<'doc> + ?Sized>(
+    visitor: &mut V,
+    declaration: &'doc VariableDeclaration,
+) {
+    visitor.visit_math_node(&declaration.name);
+    walk_segments(visitor, &declaration.type_info.segments);
+}
+
+fn walk_quantified_group<'doc, V: DocumentVisitor<'doc> + ?Sized>(
+    visitor: &mut V,
+    group: &'doc QuantifiedVariableDeclarationGroup,
+) {
+    match group {
+        QuantifiedVariableDeclarationGroup::Exists(declaration)
+        | QuantifiedVariableDeclarationGroup::UniqueExists(declaration) => {
+            walk_variable_declaration(visitor, declaration)
+        }
+        QuantifiedVariableDeclarationGroup::ForAll(declarations) => {
+            for declaration in declarations {
+                walk_variable_declaration(visitor, declaration);
+            }
+        }
+    }
+}
+
+fn walk_proof_node<'doc, V: DocumentVisitor<'doc> + ?Sized>(
+    visitor: &mut V,
+    node: &'doc ProofNodeDisplay,
+) {
+    for variable in &node.goal_display.context_variables {
+        visitor.visit_math_node(&variable.variable_name);
+        walk_segments(visitor, &variable.variable_type.segments);
+    }
+    visitor.visit_math_node(&node.goal_display.goal_statement);
+    if let Some(transformation) = &node.transformation_display {
+        walk_expressions(visitor, &transformation.source_expressions);
+        walk_expressions(visitor, &transformation.target_expressions);
+        for instantiation in &transformation.instantiations {
+            visitor.visit_math_node(&instantiation.instantiated_value);
+        }
+        for element in &transformation.interactive_elements {
+            if let Some(expression) = &element.expression {
+                visitor.visit_math_node(expression);
+            }
+        }
+    }
+    for child in &node.children {
+        walk_proof_node(visitor, child);
+    }
+}
+
+fn walk_expressions<'doc, V: DocumentVisitor<'doc> + ?Sized>(
+    visitor: &mut V,
+    expressions: &'doc [InteractiveExpression],
+) {
+    for expression in expressions {
+        visitor.visit_math_node(&expression.expression);
+    }
+}
+
+pub fn walk_segment<'doc, V: DocumentVisitor<'doc> + ?Sized>(
+    visitor: &mut V,
+    segment: &'doc RichTextSegment,
+) {
+    match segment {
+        RichTextSegment::Math(math) => visitor.visit_math_node(math),
+        RichTextSegment::Link {
+            content, target, ..
+        } => {
+            walk_segments(visitor, content);
+            if let LinkTarget::TooltipDocument(document) = target
+                && let Ok(document) = document.get()
+            {
+                visitor.visit_document(document);
+            }
+        }
+        RichTextSegment::InteractiveVariable {
+            tooltip_content, ..
+        } => walk_rich_text(visitor, tooltip_content),
+        RichTextSegment::Text(_)
+        | RichTextSegment::StyledText { .. }
+        | RichTextSegment::FootnoteReference(_)
+        | RichTextSegment::CodeInline(_) => {}
+    }
+}
+
+pub fn walk_segments<'doc, V: DocumentVisitor<'doc> + ?Sized>(
+    visitor: &mut V,
+    segments: &'doc [RichTextSegment],
+) {
+    for segment in segments {
+        visitor.visit_segment(segment);
+    }
+}
+
+pub fn walk_rich_text<'doc, V: DocumentVisitor<'doc> + ?Sized>(
+    visitor: &mut V,
+    rich_text: &'doc Option<RichText>,
+) {
+    if let Some(rich_text) = rich_text {
+        walk_segments(visitor, &rich_text.segments);
+    }
+}
+
+impl MathDocument {
+    /// Runs `visitor` over this document and everything nested in it.
+    pub fn walk<'doc, V: DocumentVisitor<'doc> + ?Sized>(&'doc self, visitor: &mut V) {
+        visitor.visit_document(self);
+    }
+
+    /// Every expression embedded in the document, in document order: math content nodes,
+    /// math in rich text (including annotations and tooltips), proof displays and nested documents.
+    /// Only the root of each expression is yielded; use a `MathNodeVisitor` to reach its subterms.
+    pub fn iter_math_nodes(&self) -> impl Iterator<Item = &MathNode> {
+        let mut collector = MathNodeCollector::default();
+        collector.visit_document(self);
+        collector.nodes.into_iter()
+    }
+}
+
+#[derive(Default)]
+struct MathNodeCollector<'doc> {
+    nodes: Vec<&'doc MathNode>,
+}
+
+impl<'doc> DocumentVisitor<'doc> for MathNodeCollector<'doc> {
+    fn visit_math_node(&mut self, node: &'doc MathNode) {
+        self.nodes.push(node);
+    }
+}
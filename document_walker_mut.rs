@@ -0,0 +1,384 @@
+use super::*;
+
+/// Mutable counterpart of `DocumentVisitor`, reaching the same nodes in the same order.
+///
+/// Embedded and tooltip documents are shared through `Arc`; walking into one loads it if it is
+/// lazy (see `DocumentHandle`) and makes it unique with `Arc::make_mut` first, so passes that
+/// only touch some of the document still copy every nested document they descend into.
+pub trait DocumentVisitorMut {
+    fn visit_document_mut(&mut self, document: &mut MathDocument) {
+        walk_document_mut(self, document);
+    }
+
+    fn visit_section_mut(&mut self, section: &mut Section) {
+        walk_section_mut(self, section);
+    }
+
+    fn visit_content_node_mut(&mut self, node: &mut SectionContentNode) {
+        walk_content_node_mut(self, node);
+    }
+
+    fn visit_panel_mut(&mut self, panel: &mut Panel) {
+        walk_panel_mut(self, panel);
+    }
+
+    fn visit_second_order_math_mut(&mut self, node: &mut SecondOrderMathNode) {
+        walk_second_order_math_mut(self, node);
+    }
+
+    fn visit_segment_mut(&mut self, segment: &mut RichTextSegment) {
+        walk_segment_mut(self, segment);
+    }
+
+    fn visit_math_node_mut(&mut self, _node: &mut MathNode) {}
+}
+
+pub fn walk_document_mut<V: DocumentVisitorMut + ?Sized>(
+    visitor: &mut V,
+    document: &mut MathDocument,
+) {
+    let content_type = &mut document.content_type;
+    if let Some(content) = content_type.simplified_content_mut() {
+        for key_point in &mut content.key_points {
+            walk_segments_mut(visitor, &mut key_point.content);
+        }
+        for definition in &mut content.essential_definitions {
+            walk_segments_mut(visitor, &mut definition.simplified_definition);
+            if let Some(formal_definition) = &mut definition.formal_definition {
+                visitor.visit_math_node_mut(formal_definition);
+            }
+            if let Some(explanation) = &mut definition.intuitive_explanation {
+                walk_segments_mut(visitor, explanation);
+            }
+        }
+        for example in &mut content.core_examples {
+            walk_content_nodes_mut(visitor, &mut example.content);
+        }
+    }
+    match content_type {
+        MathDocumentType::ComparisonPage(c) => {
+            for section in &mut c.comparison_structure.sections {
+                walk_content_nodes_mut(visitor, &mut section.left_content);
+                walk_content_nodes_mut(visitor, &mut section.right_content);
+                if let Some(notes) = &mut section.comparison_notes {
+                    walk_segments_mut(visitor, notes);
+                }
+            }
+        }
+        MathDocumentType::TransformationMapping(c) => {
+            for step in &mut c.transformation_steps {
+                walk_segments_mut(visitor, &mut step.description);
+            }
+        }
+        _ => {}
+    }
+    for section in content_type.top_level_sections_mut() {
+        visitor.visit_section_mut(section);
+    }
+}
+
+pub fn walk_section_mut<V: DocumentVisitorMut + ?Sized>(visitor: &mut V, section: &mut Section) {
+    walk_rich_text_mut(visitor, &mut section.title);
+    visitor.visit_content_node_mut(&mut section.content);
+}
+
+pub fn walk_content_node_mut<V: DocumentVisitorMut + ?Sized>(
+    visitor: &mut V,
+    node: &mut SectionContentNode,
+) {
+    match node {
+        SectionContentNode::SubSection(sections) => {
+            for section in sections {
+                visitor.visit_section_mut(section);
+            }
+        }
+        SectionContentNode::RichText(rich_text) => {
+            walk_segments_mut(visitor, &mut rich_text.segments)
+        }
+        SectionContentNode::Math(math) => visitor.visit_math_node_mut(math),
+        SectionContentNode::SecondOrderMath(second_order) => {
+            visitor.visit_second_order_math_mut(second_order)
+        }
+        SectionContentNode::InteractiveDiagram(diagram) => {
+            walk_rich_text_mut(visitor, &mut diagram.caption)
+        }
+        SectionContentNode::Feedback(feedback) => {
+            walk_segments_mut(visitor, &mut feedback.prompt.segments)
+        }
+        SectionContentNode::Equation(equation) => visitor.visit_math_node_mut(&mut equation.math),
+        SectionContentNode::DerivationChain(chain) => {
+            visitor.visit_math_node_mut(&mut chain.start);
+            for step in &mut chain.steps {
+                visitor.visit_math_node_mut(&mut step.expression);
+                walk_rich_text_mut(visitor, &mut step.justification);
+            }
+        }
+        SectionContentNode::Theorem
+        | SectionContentNode::ThematicBreak(_)
+        | SectionContentNode::EmbeddedSectionRef(_)
+        | SectionContentNode::InteractiveControls(_) => {}
+        SectionContentNode::List(list) => {
+            for item in &mut list.items {
+                walk_content_nodes_mut(visitor, &mut item.content);
+            }
+        }
+        SectionContentNode::Table(table) => {
+            walk_rich_text_mut(visitor, &mut table.caption);
+            let rows = table
+                .header_rows
+                .iter_mut()
+                .chain(&mut table.body_rows)
+                .chain(&mut table.footer_rows);
+            for row in rows {
+                for cell in &mut row.cells {
+                    walk_content_nodes_mut(visitor, &mut cell.content);
+                }
+            }
+        }
+        SectionContentNode::CodeBlock(code) => walk_rich_text_mut(visitor, &mut code.caption),
+        SectionContentNode::Image(image) => walk_rich_text_mut(visitor, &mut image.caption),
+        SectionContentNode::CollapsibleBlock(block) => {
+            walk_segments_mut(visitor, &mut block.summary);
+            walk_content_nodes_mut(visitor, &mut block.details);
+        }
+        SectionContentNode::Grid(grid) => {
+            for item in &mut grid.items {
+                visitor.visit_content_node_mut(&mut item.content);
+            }
+        }
+        SectionContentNode::Columns(columns) => {
+            for column in &mut columns.columns_content {
+                walk_content_nodes_mut(visitor, column);
+            }
+        }
+        SectionContentNode::QuoteBlock {
+            content,
+            attribution,
+        } => {
+            for rich_text in content {
+                walk_segments_mut(visitor, &mut rich_text.segments);
+            }
+            walk_rich_text_mut(visitor, attribution);
+        }
+        SectionContentNode::AlertBox { content, .. } => walk_content_nodes_mut(visitor, content),
+        SectionContentNode::CustomComponent {
+            fallback_content, ..
+        } => walk_content_nodes_mut(visitor, fallback_content),
+        SectionContentNode::GenerationError(error) => {
+            walk_content_nodes_mut(visitor, &mut error.fallback_content)
+        }
+        SectionContentNode::SideBySideLayout(layout) => {
+            visitor.visit_panel_mut(&mut layout.left_panel);
+            visitor.visit_panel_mut(&mut layout.right_panel);
+        }
+        SectionContentNode::PanelLayout(layout) => {
+            for panel in &mut layout.panels {
+                visitor.visit_panel_mut(panel);
+            }
+        }
+        SectionContentNode::AnnotationOverlay(overlay) => {
+            for annotation in &mut overlay.annotations {
+                walk_segments_mut(visitor, &mut annotation.annotation_content);
+            }
+            walk_content_nodes_mut(visitor, &mut overlay.base_content);
+        }
+        SectionContentNode::EmbeddedDocument(document) => {
+            if let Ok(document) = document.get_mut() {
+                visitor.visit_document_mut(document)
+            }
+        }
+        SectionContentNode::BranchingContainer(container) => {
+            for node in &mut container.nodes {
+                walk_content_nodes_mut(visitor, &mut node.content);
+            }
+        }
+    }
+}
+
+pub fn walk_content_nodes_mut<V: DocumentVisitorMut + ?Sized>(
+    visitor: &mut V,
+    nodes: &mut [SectionContentNode],
+) {
+    for node in nodes {
+        visitor.visit_content_node_mut(node);
+    }
+}
+
+pub fn walk_panel_mut<V: DocumentVisitorMut + ?Sized>(visitor: &mut V, panel: &mut Panel) {
+    walk_rich_text_mut(visitor, &mut panel.title);
+    walk_content_nodes_mut(visitor, &mut panel.content);
+}
+
+pub fn walk_second_order_math_mut<V: DocumentVisitorMut + ?Sized>(
+    visitor: &mut V,
+    node: &mut SecondOrderMathNode,
+) {
+    match node {
+        SecondOrderMathNode::Logic(logic) => walk_logical_node_mut(visitor, logic),
+        SecondOrderMathNode::Judgement(judgement) => {
+            for declaration in &mut judgement.non_quantifiers {
+                walk_variable_declaration_mut(visitor, declaration);
+            }
+            for group in &mut judgement.quantifiers {
+                walk_quantified_group_mut(visitor, group);
+            }
+            walk_logical_node_mut(visitor, &mut judgement.statement);
+        }
+        SecondOrderMathNode::SystemOf(equations) => {
+            for equation in equations {
+                visitor.visit_math_node_mut(equation);
+            }
+        }
+        SecondOrderMathNode::Solution(solution) => {
+            for section in &mut solution.solution_space {
+                visitor.visit_section_mut(section);
+            }
+        }
+        SecondOrderMathNode::VariableDeclaration(declaration) => {
+            walk_variable_declaration_mut(visitor, declaration)
+        }
+        SecondOrderMathNode::QuantifiedVariableDeclarationGroup(group) => {
+            walk_quantified_group_mut(visitor, group)
+        }
+        SecondOrderMathNode::InteractiveProof(proof) => {
+            for root in &mut proof.proof_forest.root_nodes {
+                walk_proof_node_mut(visitor, root);
+            }
+            for data in &mut proof.transformation_data {
+                walk_expressions_mut(visitor, &mut data.source_expressions);
+                walk_expressions_mut(visitor, &mut data.target_expressions);
+                for instantiation in &mut data.instantiations {
+                    visitor.visit_math_node_mut(&mut instantiation.instantiated_value);
+                }
+                for element in &mut data.interactive_elements {
+                    if let Some(expression) = &mut element.expression {
+                        visitor.visit_math_node_mut(expression);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn walk_logical_node_mut<V: DocumentVisitorMut + ?Sized>(visitor: &mut V, node: &mut LogicalNode) {
+    match node {
+        LogicalNode::And(operands) | LogicalNode::Or(operands) => {
+            for operand in operands {
+                walk_logical_node_mut(visitor, operand);
+            }
+        }
+        LogicalNode::Atomic(math) => visitor.visit_math_node_mut(math),
+        LogicalNode::True | LogicalNode::False => {}
+    }
+}
+
+fn walk_variable_declaration_mut<V: DocumentVisitorMut + ?Sized>(
+    visitor: &mut V,
+    declaration: &mut VariableDeclaration,
+) {
+    visitor.visit_math_node_mut(&mut declaration.name);
+    walk_segments_mut(visitor, &mut declaration.type_info.segments);
+}
+
+fn walk_quantified_group_mut<V: DocumentVisitorMut + ?Sized>(
+    visitor: &mut V,
+    group: &mut QuantifiedVariableDeclarationGroup,
+) {
+    match group {
+        QuantifiedVariableDeclarationGroup::Exists(declaration)
+        | QuantifiedVariableDeclarationGroup::UniqueExists(declaration) => {
+            walk_variable_declaration_mut(visitor, declaration)
+        }
+        QuantifiedVariableDeclarationGroup::ForAll(declarations) => {
+            for declaration in declarations {
+                walk_variable_declaration_mut(visitor, declaration);
+            }
+        }
+    }
+}
+
+fn walk_proof_node_mut<V: DocumentVisitorMut + ?Sized>(
+    visitor: &mut V,
+    node: &mut ProofNodeDisplay,
+) {
+    for variable in &mut node.goal_display.context_variables {
+        visitor.visit_math_node_mut(&mut variable.variable_name);
+        walk_segments_mut(visitor, &mut variable.variable_type.segments);
+    }
+    visitor.visit_math_node_mut(&mut node.goal_display.goal_statement);
+    if let Some(transformation) = &mut node.transformation_display {
+        walk_expressions_mut(visitor, &mut transformation.source_expressions);
+        walk_expressions_mut(visitor, &mut transformation.target_expressions);
+        for instantiation in &mut transformation.instantiations {
+            visitor.visit_math_node_mut(&mut instantiation.instantiated_value);
+        }
+        for element in &mut transformation.interactive_elements {
+            if let Some(expression) = &mut element.expression {
+                visitor.visit_math_node_mut(expression);
+            }
+        }
+    }
+    for child in &mut node.children {
+        walk_proof_node_mut(visitor, child);
+    }
+}
+
+fn walk_expressions_mut<V: DocumentVisitorMut + ?Sized>(
+    visitor: &mut V,
+    expressions: &mut [InteractiveExpression],
+) {
+    for expression in expressions {
+        visitor.visit_math_node_mut(&mut expression.expression);
+    }
+}
+
+pub fn walk_segment_mut<V: DocumentVisitorMut + ?Sized>(
+    visitor: &mut V,
+    segment: &mut RichTextSegment,
+) {
+    match segment {
+        RichTextSegment::Math(math) => visitor.visit_math_node_mut(math),
+        RichTextSegment::Link {
+            content, target, ..
+        } => {
+            walk_segments_mut(visitor, content);
+            if let LinkTarget::TooltipDocument(document) = target
+                && let Ok(document) = document.get_mut()
+            {
+                visitor.visit_document_mut(document);
+            }
+        }
+        RichTextSegment::InteractiveVariable {
+            tooltip_content, ..
+        } => walk_rich_text_mut(visitor, tooltip_content),
+        RichTextSegment::Text(_)
+        | RichTextSegment::StyledText { .. }
+        | RichTextSegment::FootnoteReference(_)
+        | RichTextSegment::CodeInline(_) => {}
+    }
+}
+
+pub fn walk_segments_mut<V: DocumentVisitorMut + ?Sized>(
+    visitor: &mut V,
+    segments: &mut [RichTextSegment],
+) {
+    for segment in segments {
+        visitor.visit_segment_mut(segment);
+    }
+}
+
+pub fn walk_rich_text_mut<V: DocumentVisitorMut + ?Sized>(
+    visitor: &mut V,
+    rich_text: &mut Option<RichText>,
+) {
+    if let Some(rich_text) = rich_text {
+        walk_segments_mut(visitor, &mut rich_text.segments);
+    }
+}
+
+impl MathDocument {
+    /// Runs `visitor` over this document and everything nested in it, allowing edits in place.
+    pub fn walk_mut<V: DocumentVisitorMut + ?Sized>(&mut self, visitor: &mut V) {
+        visitor.visit_document_mut(self);
+    }
+}
@@ -0,0 +1,153 @@
+use super::*;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// Display math with an equation number or tag at the right margin, which prose refers to with
+/// `LinkTarget::Equation`. Display math without one is plain `SectionContentNode::Math`.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct Equation {
+    pub id: String,
+    pub math: MathNode,
+    #[serde(default)]
+    pub numbering: EquationNumbering,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub number: Option<String>, // Assigned to `Auto` equations by `MathDocument::number_equations`
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub enum EquationNumbering {
+    #[default]
+    Auto, // Numbered in document order
+    Tag(String), // A fixed tag such as "∗" or "3.2a", shown instead of a number and not counted
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub enum EquationNumberStyle {
+    Sequential, // (1), (2), ... through the whole document
+    BySection,  // (3.1), (3.2), ... restarting in each body section
+}
+
+impl Equation {
+    pub fn new(id: impl Into<String>, math: MathNode) -> Self {
+        Equation {
+            id: id.into(),
+            math,
+            numbering: EquationNumbering::Auto,
+            number: None,
+        }
+    }
+
+    pub fn tagged(id: impl Into<String>, math: MathNode, tag: impl Into<String>) -> Self {
+        Equation {
+            numbering: EquationNumbering::Tag(tag.into()),
+            ..Equation::new(id, math)
+        }
+    }
+
+    /// What the margin and references show, `(3.2)` or `(∗)`. `None` for an `Auto` equation
+    /// that has not been numbered.
+    pub fn label(&self) -> Option<String> {
+        let text = match &self.numbering {
+            EquationNumbering::Auto => self.number.as_ref()?,
+            EquationNumbering::Tag(tag) => tag,
+        };
+        Some(format!("({})", text))
+    }
+}
+
+impl MathDocument {
+    /// Numbers the `Auto` equations in document order, overwriting earlier numbers, so that
+    /// renderers and `equation_label` show them. `BySection` numbers the equations of the n-th
+    /// body section of a structured document `n.1`, `n.2`, ..., leaving those in the abstract,
+    /// footnotes and glossary unnumbered; for other documents it goes by the top-level sections.
+    /// Embedded and tooltip documents are numbered on their own.
+    pub fn number_equations(&mut self, style: EquationNumberStyle) {
+        let mut numberer = EquationNumberer {
+            style,
+            prefix: Some(String::new()),
+            count: 0,
+        };
+        if style == EquationNumberStyle::BySection
+            && let Some(structure) = self.content_type.structure_mut()
+        {
+            numberer.prefix = None;
+            let unnumbered = structure
+                .abstract_content
+                .iter_mut()
+                .chain(structure.footnotes.iter_mut())
+                .chain(structure.glossary.iter_mut());
+            for section in unnumbered {
+                numberer.visit_section_mut(section);
+            }
+            for (i, section) in structure.body.iter_mut().enumerate() {
+                numberer.restart(i);
+                numberer.visit_section_mut(section);
+            }
+            return;
+        }
+        for (i, section) in self
+            .content_type
+            .top_level_sections_mut()
+            .into_iter()
+            .enumerate()
+        {
+            if style == EquationNumberStyle::BySection {
+                numberer.restart(i);
+            }
+            numberer.visit_section_mut(section);
+        }
+    }
+
+    /// Every equation in the document, embedded documents included, in document order.
+    pub fn equations(&self) -> Vec<&Equation> {
+        self.fold(vec![], |mut equations, node| {
+            if let FoldNode::ContentNode(SectionContentNode::Equation(equation)) = node {
+                equations.push(equation);
+            }
+            equations
+        })
+    }
+
+    /// The label of the equation `id`, what a `LinkTarget::Equation` link to it shows.
+    pub fn equation_label(&self, id: &str) -> Option<String> {
+        self.equations()
+            .into_iter()
+            .find(|equation| equation.id == id)?
+            .label()
+    }
+}
+
+struct EquationNumberer {
+    style: EquationNumberStyle,
+    prefix: Option<String>, // `None` where equations go unnumbered
+    count: usize,
+}
+
+impl EquationNumberer {
+    /// Starts the equations of the section at `index` among those numbered.
+    fn restart(&mut self, index: usize) {
+        self.prefix = Some(format!("{}.", index + 1));
+        self.count = 0;
+    }
+}
+
+impl DocumentVisitorMut for EquationNumberer {
+    fn visit_document_mut(&mut self, document: &mut MathDocument) {
+        document.number_equations(self.style);
+    }
+
+    fn visit_content_node_mut(&mut self, node: &mut SectionContentNode) {
+        if let SectionContentNode::Equation(equation) = node
+            && equation.numbering == EquationNumbering::Auto
+        {
+            equation.number = self.prefix.as_ref().map(|prefix| {
+                self.count += 1;
+                format!("{}{}", prefix, self.count)
+            });
+        }
+        walk_content_node_mut(self, node);
+    }
+}
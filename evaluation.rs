@@ -0,0 +1,1089 @@
+use super::*;
+use std::{collections::BTreeMap, fmt};
+
+/// The value of an evaluated expression: an exact fraction as long as everything it was
+/// computed from is rational, a float once a function, a non-integer power or a float from the
+/// environment comes in or the fraction overflows.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NumericValue {
+    Rational { numerator: i64, denominator: i64 }, // in lowest terms, `denominator` positive
+    Real(f64),
+}
+
+impl NumericValue {
+    pub fn integer(value: i64) -> NumericValue {
+        NumericValue::Rational {
+            numerator: value,
+            denominator: 1,
+        }
+    }
+
+    /// `numerator / denominator` in lowest terms, or `None` when `denominator` is zero.
+    pub fn rational(numerator: i64, denominator: i64) -> Option<NumericValue> {
+        (denominator != 0).then(|| reduce(numerator as i128, denominator as i128))
+    }
+
+    /// Integral floats are taken as integers, so that they stay exact in fractions.
+    pub fn from_f64(value: f64) -> NumericValue {
+        const MAX_EXACT: f64 = (1u64 << 53) as f64;
+        if value.fract() == 0.0 && value.abs() <= MAX_EXACT {
+            NumericValue::integer(value as i64)
+        } else {
+            NumericValue::Real(value)
+        }
+    }
+
+    pub fn to_f64(self) -> f64 {
+        match self {
+            NumericValue::Rational {
+                numerator,
+                denominator,
+            } => numerator as f64 / denominator as f64,
+            NumericValue::Real(value) => value,
+        }
+    }
+
+    pub fn is_exact(self) -> bool {
+        matches!(self, NumericValue::Rational { .. })
+    }
+
+    fn is_zero(self) -> bool {
+        self.to_f64() == 0.0
+    }
+
+    fn add(self, other: NumericValue) -> NumericValue {
+        match (self.parts(), other.parts()) {
+            (Some((a, b)), Some((c, d))) => reduce(a * d + c * b, b * d),
+            _ => NumericValue::Real(self.to_f64() + other.to_f64()),
+        }
+    }
+
+    fn negate(self) -> NumericValue {
+        match self.parts() {
+            Some((numerator, denominator)) => rational_or_real(-numerator, denominator),
+            None => NumericValue::Real(-self.to_f64()),
+        }
+    }
+
+    fn multiply(self, other: NumericValue) -> NumericValue {
+        match (self.parts(), other.parts()) {
+            (Some((a, b)), Some((c, d))) => reduce(a * c, b * d),
+            _ => NumericValue::Real(self.to_f64() * other.to_f64()),
+        }
+    }
+
+    /// `None` when dividing by zero.
+    fn divide(self, other: NumericValue) -> Option<NumericValue> {
+        if other.is_zero() {
+            return None;
+        }
+        Some(match (self.parts(), other.parts()) {
+            (Some((a, b)), Some((c, d))) => reduce(a * d, b * c),
+            _ => NumericValue::Real(self.to_f64() / other.to_f64()),
+        })
+    }
+
+    /// `None` for zero to a negative power.
+    fn power(self, exponent: NumericValue) -> Option<NumericValue> {
+        if let (Some((numerator, denominator)), Some((n, 1))) = (self.parts(), exponent.parts()) {
+            if numerator == 0 && n < 0 {
+                return None;
+            }
+            let (numerator, denominator) = if n < 0 {
+                (denominator, numerator)
+            } else {
+                (numerator, denominator)
+            };
+            if let Ok(power) = u32::try_from(n.unsigned_abs())
+                && let (Some(numerator), Some(denominator)) =
+                    (numerator.checked_pow(power), denominator.checked_pow(power))
+            {
+                return Some(reduce(numerator, denominator));
+            }
+        }
+        Some(NumericValue::Real(self.to_f64().powf(exponent.to_f64())))
+    }
+
+    fn parts(self) -> Option<(i128, i128)> {
+        match self {
+            NumericValue::Rational {
+                numerator,
+                denominator,
+            } => Some((numerator as i128, denominator as i128)),
+            NumericValue::Real(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for NumericValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NumericValue::Rational {
+                numerator,
+                denominator: 1,
+            } => write!(f, "{}", numerator),
+            NumericValue::Rational {
+                numerator,
+                denominator,
+            } => write!(f, "{}/{}", numerator, denominator),
+            NumericValue::Real(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+fn reduce(numerator: i128, denominator: i128) -> NumericValue {
+    let divisor = gcd(numerator.unsigned_abs(), denominator.unsigned_abs()) as i128;
+    let sign = denominator.signum();
+    rational_or_real(numerator / divisor * sign, denominator / divisor * sign)
+}
+
+fn gcd(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a.max(1)
+}
+
+/// The fraction, already in lowest terms, as a float if it does not fit in `i64`s.
+fn rational_or_real(numerator: i128, denominator: i128) -> NumericValue {
+    match (i64::try_from(numerator), i64::try_from(denominator)) {
+        (Ok(numerator), Ok(denominator)) => NumericValue::Rational {
+            numerator,
+            denominator,
+        },
+        _ => NumericValue::Real(numerator as f64 / denominator as f64),
+    }
+}
+
+impl MathNode {
+    /// Computes the value of an arithmetic expression for a playground preview, with the
+    /// identifiers set to `environment`.
+    ///
+    /// Expressions are built from quantities (their units are ignored), identifiers, sums,
+    /// products, fractions and divisions, powers and roots, binomials, piecewise definitions,
+    /// `-`/`+` prefixes, the `!` and `%`
+    /// postfixes, absolute values, norms of numbers and of vectors written as matrices (the
+    /// `p`-norm for a subscript `p`, `∞` for the largest magnitude, 2 without one), determinants
+    /// of square matrices between bars, brackets, and
+    /// calls of `abs`, `sqrt`, `exp`, `ln`, `log`,
+    /// `sin`, `cos`, `tan`, `arcsin`, `arccos`, `arctan`, `sinh`, `cosh`, `tanh`, `min` and
+    /// `max`. Identifiers
+    /// are looked up by their body, followed by `_` and a plain subscript if they have one, as in
+    /// `x_1`, and by a `'` per prime; `π`, `pi` and `e` are constants unless `environment` sets
+    /// them. Results that are not real numbers, such as `sqrt(-1)`, are NaN.
+    pub fn evaluate(
+        &self,
+        environment: &BTreeMap<String, f64>,
+    ) -> Result<NumericValue, EvaluationError> {
+        evaluate_node(self, environment)
+    }
+
+    /// `evaluate`, as a float.
+    pub fn evaluate_f64(
+        &self,
+        environment: &BTreeMap<String, f64>,
+    ) -> Result<f64, EvaluationError> {
+        self.evaluate(environment).map(NumericValue::to_f64)
+    }
+
+    /// Bounds on the value of the expression while each identifier ranges over its interval in
+    /// `environment`, e.g. from `ParameterSpace::intervals`, for playground plots and sanity
+    /// checks on constraints. Takes the same expressions as `evaluate`; see `Interval` for how
+    /// far the bounds can be trusted. A function applied entirely outside its domain, such as
+    /// `sqrt` over `[-2, -1]`, fails with `InvalidArgument`; otherwise the part of the interval
+    /// inside the domain is used.
+    pub fn evaluate_interval(
+        &self,
+        environment: &BTreeMap<String, Interval>,
+    ) -> Result<Interval, EvaluationError> {
+        evaluate_interval_node(self, environment)
+    }
+}
+
+fn evaluate_node(
+    node: &MathNode,
+    environment: &BTreeMap<String, f64>,
+) -> Result<NumericValue, EvaluationError> {
+    let evaluate = |node: &MathNode| evaluate_node(node, environment);
+    let unsupported = || EvaluationError::Unsupported {
+        kind: node.content.kind_name(),
+    };
+    match node.content.as_ref() {
+        MathNodeContent::Quantity {
+            number,
+            scientific_notation,
+            ..
+        } => {
+            let value = parse_decimal(number).ok_or_else(|| EvaluationError::InvalidNumber {
+                number: number.clone(),
+            })?;
+            match scientific_notation {
+                Some(notation) => Ok(value.multiply(evaluate(notation)?)),
+                None => Ok(value),
+            }
+        }
+        MathNodeContent::ScientificNotation { magnitude, .. } => NumericValue::integer(10)
+            .power(evaluate(magnitude)?)
+            .ok_or(EvaluationError::DivisionByZero),
+        MathNodeContent::Identifier(identifier) => {
+            let name = identifier_name(identifier).ok_or_else(unsupported)?;
+            match environment.get(&name) {
+                Some(value) => Ok(NumericValue::from_f64(*value)),
+                None => match name.as_str() {
+                    "π" | "pi" => Ok(NumericValue::Real(std::f64::consts::PI)),
+                    "e" => Ok(NumericValue::Real(std::f64::consts::E)),
+                    _ => Err(EvaluationError::UnboundIdentifier { name }),
+                },
+            }
+        }
+        MathNodeContent::Bracketed { inner, .. }
+        | MathNodeContent::Brace { inner, .. }
+        | MathNodeContent::Styled { inner, .. }
+        | MathNodeContent::Cancelled { inner, .. }
+        | MathNodeContent::Folded { content: inner, .. } => evaluate(inner),
+        MathNodeContent::BinaryOperation { terms, .. } => {
+            let mut value: Option<NumericValue> = None;
+            for (operator, term) in terms {
+                let term = evaluate(term)?;
+                value = Some(match (value, operator) {
+                    (None, BinaryOperator::Minus) => term.negate(),
+                    (None, BinaryOperator::Plus | BinaryOperator::Times | BinaryOperator::Dot) => {
+                        term
+                    }
+                    (Some(value), BinaryOperator::Plus) => value.add(term),
+                    (Some(value), BinaryOperator::Minus) => value.add(term.negate()),
+                    (Some(value), BinaryOperator::Times | BinaryOperator::Dot) => {
+                        value.multiply(term)
+                    }
+                    (Some(value), BinaryOperator::Slash | BinaryOperator::Divide) => {
+                        value.divide(term).ok_or(EvaluationError::DivisionByZero)?
+                    }
+                    _ => return Err(unsupported()),
+                });
+            }
+            value.ok_or_else(unsupported)
+        }
+        MathNodeContent::Additions { terms } => {
+            let mut value = NumericValue::integer(0);
+            for (operator, term) in terms {
+                let term = evaluate(term)?;
+                value = match operator {
+                    RefinedAddOrSubOperator::Subtraction => value.add(term.negate()),
+                    RefinedAddOrSubOperator::Addition | RefinedAddOrSubOperator::None => {
+                        value.add(term)
+                    }
+                };
+            }
+            Ok(value)
+        }
+        MathNodeContent::Multiplications { terms } => {
+            let mut value = NumericValue::integer(1);
+            for (operator, term) in terms {
+                let term = evaluate(term)?;
+                value = match operator {
+                    RefinedMulOrDivOperation::Division(_) => {
+                        value.divide(term).ok_or(EvaluationError::DivisionByZero)?
+                    }
+                    RefinedMulOrDivOperation::Multiplication(_)
+                    | RefinedMulOrDivOperation::None => value.multiply(term),
+                };
+            }
+            Ok(value)
+        }
+        MathNodeContent::Fraction {
+            numerator,
+            denominator,
+        }
+        | MathNodeContent::Division {
+            numerator,
+            denominator,
+            ..
+        } => evaluate(numerator)?
+            .divide(evaluate(denominator)?)
+            .ok_or(EvaluationError::DivisionByZero),
+        MathNodeContent::Power { base, exponent } => evaluate(base)?
+            .power(evaluate(exponent)?)
+            .ok_or(EvaluationError::DivisionByZero),
+        MathNodeContent::Radical { radicand, index } => root(
+            evaluate(radicand)?,
+            index.as_deref().map(evaluate).transpose()?,
+        ),
+        MathNodeContent::Binomial { upper, lower, .. } => {
+            binomial(evaluate(upper)?, evaluate(lower)?)
+        }
+        MathNodeContent::Cases { branches } => {
+            for (expression, condition) in branches {
+                if holds(condition, environment)? {
+                    return evaluate(expression);
+                }
+            }
+            Err(EvaluationError::NoMatchingCase)
+        }
+        MathNodeContent::UnaryPrefixOperation {
+            parameter,
+            operator,
+        } => match symbol(operator) {
+            Some("-" | "−") => Ok(evaluate(parameter)?.negate()),
+            Some("+") => evaluate(parameter),
+            _ => Err(unsupported()),
+        },
+        MathNodeContent::UnaryPostfixOperation {
+            parameter,
+            operator,
+        } => match symbol(operator) {
+            Some("!") => factorial(evaluate(parameter)?),
+            Some("%") => Ok(evaluate(parameter)?.multiply(NumericValue::Rational {
+                numerator: 1,
+                denominator: 100,
+            })),
+            _ => Err(unsupported()),
+        },
+        MathNodeContent::Abs { parameter } => Ok(magnitude(evaluate(parameter)?)),
+        MathNodeContent::AngleMeasure { value, unit } => {
+            let value = evaluate(value)?;
+            match unit {
+                AngleUnit::Degrees => Ok(NumericValue::Real(value.to_f64().to_radians())),
+                AngleUnit::Radians => Ok(value),
+            }
+        }
+        MathNodeContent::Norm { inner, subscript } => {
+            let magnitudes = components(inner)
+                .into_iter()
+                .map(|component| evaluate(component).map(magnitude))
+                .collect::<Result<Vec<_>, _>>()?;
+            match subscript.as_deref() {
+                Some(subscript) if is_infinity(subscript) => magnitudes
+                    .into_iter()
+                    .reduce(|a, b| if b.to_f64() > a.to_f64() { b } else { a })
+                    .ok_or_else(unsupported),
+                subscript => {
+                    let order = match subscript {
+                        Some(subscript) => evaluate(subscript)?,
+                        None => NumericValue::integer(2),
+                    };
+                    let mut sum = NumericValue::integer(0);
+                    for magnitude in magnitudes {
+                        sum = sum.add(
+                            magnitude
+                                .power(order)
+                                .ok_or(EvaluationError::DivisionByZero)?,
+                        );
+                    }
+                    root(sum, Some(order))
+                }
+            }
+        }
+        MathNodeContent::Matrix {
+            rows,
+            delimiters: Some(MatrixDelimiters::Bars),
+            ..
+        } if is_square(rows) => {
+            let values = rows
+                .iter()
+                .map(|row| row.iter().map(evaluate).collect::<Result<Vec<_>, _>>())
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(determinant(
+                &values,
+                NumericValue::integer(0),
+                NumericValue::add,
+                NumericValue::multiply,
+                NumericValue::negate,
+            ))
+        }
+        MathNodeContent::FunctionCall { name, parameters } => {
+            let name = function_name(name).ok_or_else(unsupported)?;
+            let arguments = parameters
+                .iter()
+                .map(evaluate)
+                .collect::<Result<Vec<_>, _>>()?;
+            call(name, &arguments)
+        }
+        _ => Err(unsupported()),
+    }
+}
+
+fn evaluate_interval_node(
+    node: &MathNode,
+    environment: &BTreeMap<String, Interval>,
+) -> Result<Interval, EvaluationError> {
+    let evaluate = |node: &MathNode| evaluate_interval_node(node, environment);
+    let unsupported = || EvaluationError::Unsupported {
+        kind: node.content.kind_name(),
+    };
+    let divide = |a: Interval, b: Interval| a.checked_div(b).ok_or(EvaluationError::DivisionByZero);
+    match node.content.as_ref() {
+        MathNodeContent::Quantity {
+            number,
+            scientific_notation,
+            ..
+        } => {
+            let value = parse_decimal(number).ok_or_else(|| EvaluationError::InvalidNumber {
+                number: number.clone(),
+            })?;
+            let value = exact_interval(value);
+            match scientific_notation {
+                Some(notation) => Ok(value * evaluate(notation)?),
+                None => Ok(value),
+            }
+        }
+        MathNodeContent::ScientificNotation { magnitude, .. } => {
+            power_interval(Interval::point(10.0), evaluate(magnitude)?)
+        }
+        MathNodeContent::Identifier(identifier) => {
+            let name = identifier_name(identifier).ok_or_else(unsupported)?;
+            match environment.get(&name) {
+                Some(interval) => Ok(*interval),
+                None => match name.as_str() {
+                    "π" | "pi" => Ok(Interval::point(std::f64::consts::PI).widened()),
+                    "e" => Ok(Interval::point(std::f64::consts::E).widened()),
+                    _ => Err(EvaluationError::UnboundIdentifier { name }),
+                },
+            }
+        }
+        MathNodeContent::Bracketed { inner, .. }
+        | MathNodeContent::Brace { inner, .. }
+        | MathNodeContent::Styled { inner, .. }
+        | MathNodeContent::Cancelled { inner, .. }
+        | MathNodeContent::Folded { content: inner, .. } => evaluate(inner),
+        MathNodeContent::BinaryOperation { terms, .. } => {
+            let mut value: Option<Interval> = None;
+            for (operator, term) in terms {
+                let term = evaluate(term)?;
+                value = Some(match (value, operator) {
+                    (None, BinaryOperator::Minus) => -term,
+                    (None, BinaryOperator::Plus | BinaryOperator::Times | BinaryOperator::Dot) => {
+                        term
+                    }
+                    (Some(value), BinaryOperator::Plus) => value + term,
+                    (Some(value), BinaryOperator::Minus) => value - term,
+                    (Some(value), BinaryOperator::Times | BinaryOperator::Dot) => value * term,
+                    (Some(value), BinaryOperator::Slash | BinaryOperator::Divide) => {
+                        divide(value, term)?
+                    }
+                    _ => return Err(unsupported()),
+                });
+            }
+            value.ok_or_else(unsupported)
+        }
+        MathNodeContent::Additions { terms } => {
+            let mut value = Interval::point(0.0);
+            for (operator, term) in terms {
+                let term = evaluate(term)?;
+                value = match operator {
+                    RefinedAddOrSubOperator::Subtraction => value - term,
+                    RefinedAddOrSubOperator::Addition | RefinedAddOrSubOperator::None => {
+                        value + term
+                    }
+                };
+            }
+            Ok(value)
+        }
+        MathNodeContent::Multiplications { terms } => {
+            let mut value = Interval::point(1.0);
+            for (operator, term) in terms {
+                let term = evaluate(term)?;
+                value = match operator {
+                    RefinedMulOrDivOperation::Division(_) => divide(value, term)?,
+                    RefinedMulOrDivOperation::Multiplication(_)
+                    | RefinedMulOrDivOperation::None => value * term,
+                };
+            }
+            Ok(value)
+        }
+        MathNodeContent::Fraction {
+            numerator,
+            denominator,
+        }
+        | MathNodeContent::Division {
+            numerator,
+            denominator,
+            ..
+        } => divide(evaluate(numerator)?, evaluate(denominator)?),
+        MathNodeContent::Power { base, exponent } => {
+            power_interval(evaluate(base)?, evaluate(exponent)?)
+        }
+        MathNodeContent::Radical { radicand, index } => root_interval(
+            evaluate(radicand)?,
+            index.as_deref().map(evaluate).transpose()?,
+        ),
+        MathNodeContent::Binomial { upper, lower, .. } => {
+            let (upper, lower) = (evaluate(upper)?, evaluate(lower)?);
+            if upper.width() != 0.0 || lower.width() != 0.0 {
+                return Err(unsupported());
+            }
+            let value = binomial(
+                NumericValue::from_f64(upper.lower),
+                NumericValue::from_f64(lower.lower),
+            )?;
+            Ok(exact_interval(value))
+        }
+        // Every branch that can be evaluated, whatever the conditions
+        MathNodeContent::Cases { branches } => {
+            let mut hull: Option<Interval> = None;
+            for (expression, _) in branches {
+                match evaluate(expression) {
+                    Ok(value) => hull = Some(hull.map_or(value, |hull| hull.hull(value))),
+                    Err(EvaluationError::InvalidArgument { .. }) => {}
+                    Err(error) => return Err(error),
+                }
+            }
+            hull.ok_or(EvaluationError::NoMatchingCase)
+        }
+        MathNodeContent::UnaryPrefixOperation {
+            parameter,
+            operator,
+        } => match symbol(operator) {
+            Some("-" | "−") => Ok(-evaluate(parameter)?),
+            Some("+") => evaluate(parameter),
+            _ => Err(unsupported()),
+        },
+        MathNodeContent::UnaryPostfixOperation {
+            parameter,
+            operator,
+        } => match symbol(operator) {
+            Some("!") => factorial_interval(evaluate(parameter)?),
+            Some("%") => Ok(evaluate(parameter)? * Interval::point(0.01).widened()),
+            _ => Err(unsupported()),
+        },
+        MathNodeContent::Abs { parameter } => Ok(evaluate(parameter)?.abs()),
+        MathNodeContent::AngleMeasure { value, unit } => {
+            let value = evaluate(value)?;
+            match unit {
+                AngleUnit::Degrees => {
+                    Ok(value * Interval::point(std::f64::consts::PI / 180.0).widened())
+                }
+                AngleUnit::Radians => Ok(value),
+            }
+        }
+        MathNodeContent::Norm { inner, subscript } => {
+            let magnitudes = components(inner)
+                .into_iter()
+                .map(|component| evaluate(component).map(Interval::abs))
+                .collect::<Result<Vec<_>, _>>()?;
+            match subscript.as_deref() {
+                Some(subscript) if is_infinity(subscript) => magnitudes
+                    .into_iter()
+                    .reduce(Interval::max)
+                    .ok_or_else(unsupported),
+                subscript => {
+                    let order = match subscript {
+                        Some(subscript) => evaluate(subscript)?,
+                        None => Interval::point(2.0),
+                    };
+                    let mut sum = Interval::point(0.0);
+                    for magnitude in magnitudes {
+                        sum = sum + power_interval(magnitude, order)?;
+                    }
+                    root_interval(sum, Some(order))
+                }
+            }
+        }
+        MathNodeContent::Matrix {
+            rows,
+            delimiters: Some(MatrixDelimiters::Bars),
+            ..
+        } if is_square(rows) => {
+            let values = rows
+                .iter()
+                .map(|row| row.iter().map(evaluate).collect::<Result<Vec<_>, _>>())
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(determinant(
+                &values,
+                Interval::point(0.0),
+                |a, b| a + b,
+                |a, b| a * b,
+                |a| -a,
+            ))
+        }
+        MathNodeContent::FunctionCall { name, parameters } => {
+            let name = function_name(name).ok_or_else(unsupported)?;
+            let arguments = parameters
+                .iter()
+                .map(evaluate)
+                .collect::<Result<Vec<_>, _>>()?;
+            call_interval(name, &arguments)
+        }
+        _ => Err(unsupported()),
+    }
+}
+
+/// The interval of just `value`, widened when the float is not exactly it.
+fn exact_interval(value: NumericValue) -> Interval {
+    const MAX_EXACT: i64 = 1 << 53;
+    match value {
+        NumericValue::Rational {
+            numerator,
+            denominator: 1,
+        } if numerator.abs() <= MAX_EXACT => Interval::point(numerator as f64),
+        value => Interval::point(value.to_f64()).widened(),
+    }
+}
+
+/// Integer powers for an exponent of a single integer, `exp(exponent·ln(base))` otherwise.
+fn power_interval(base: Interval, exponent: Interval) -> Result<Interval, EvaluationError> {
+    if exponent.lower == exponent.upper
+        && exponent.lower.fract() == 0.0
+        && let Ok(exponent) = i32::try_from(exponent.lower as i64)
+    {
+        return base.powi(exponent).ok_or(EvaluationError::DivisionByZero);
+    }
+    base.powf(exponent)
+        .ok_or_else(|| EvaluationError::InvalidArgument {
+            function: "^".to_string(),
+        })
+}
+
+/// Real roots for an index of a single natural number, `radicand^(1/index)` otherwise; a square
+/// root without an index.
+fn root_interval(radicand: Interval, index: Option<Interval>) -> Result<Interval, EvaluationError> {
+    let invalid = || EvaluationError::InvalidArgument {
+        function: "√".to_string(),
+    };
+    let index = index.unwrap_or(Interval::point(2.0));
+    if index.lower == index.upper
+        && index.lower.fract() == 0.0
+        && let Ok(index) = u32::try_from(index.lower as i64)
+    {
+        return radicand.root(index).ok_or_else(invalid);
+    }
+    let exponent = Interval::point(1.0)
+        .checked_div(index)
+        .ok_or(EvaluationError::DivisionByZero)?;
+    radicand.powf(exponent).ok_or_else(invalid)
+}
+
+/// The factorials of the natural numbers in the interval, which increase with them.
+fn factorial_interval(value: Interval) -> Result<Interval, EvaluationError> {
+    let invalid = || EvaluationError::InvalidArgument {
+        function: "!".to_string(),
+    };
+    let lower = value.lower.max(0.0).ceil();
+    let upper = value.upper.floor();
+    if lower > upper {
+        return Err(invalid());
+    }
+    let at = |n: f64| -> Result<f64, EvaluationError> {
+        if n.is_finite() {
+            Ok(factorial(NumericValue::from_f64(n))?.to_f64())
+        } else {
+            Ok(f64::INFINITY)
+        }
+    };
+    Ok(Interval {
+        lower: at(lower)?,
+        upper: at(upper)?,
+    }
+    .widened())
+}
+
+fn call_interval(name: &str, arguments: &[Interval]) -> Result<Interval, EvaluationError> {
+    let unary: Option<fn(Interval) -> Option<Interval>> = match name {
+        "sqrt" => Some(Interval::sqrt),
+        "exp" => Some(|x| Some(x.exp())),
+        "ln" => Some(Interval::ln),
+        "log" => Some(Interval::log10),
+        "sin" => Some(|x| Some(x.sin())),
+        "cos" => Some(|x| Some(x.cos())),
+        "tan" => Some(|x| Some(x.tan())),
+        "arcsin" => Some(Interval::asin),
+        "arccos" => Some(Interval::acos),
+        "arctan" => Some(|x| Some(x.atan())),
+        "sinh" => Some(|x| Some(x.sinh())),
+        "cosh" => Some(|x| Some(x.cosh())),
+        "tanh" => Some(|x| Some(x.tanh())),
+        "abs" => Some(|x| Some(x.abs())),
+        _ => None,
+    };
+    match (unary, name, arguments) {
+        (Some(function), _, [x]) => function(*x).ok_or_else(|| EvaluationError::InvalidArgument {
+            function: name.to_string(),
+        }),
+        (None, "min", [first, rest @ ..]) => Ok(rest.iter().fold(*first, |a, b| a.min(*b))),
+        (None, "max", [first, rest @ ..]) => Ok(rest.iter().fold(*first, |a, b| a.max(*b))),
+        (None, "min" | "max", _) | (Some(_), _, _) => Err(EvaluationError::WrongArgumentCount {
+            function: name.to_string(),
+            count: arguments.len(),
+        }),
+        (None, _, _) => Err(EvaluationError::UnknownFunction {
+            name: name.to_string(),
+        }),
+    }
+}
+
+/// An exact fraction for a plain decimal, `2.5` as 5/2; a float with an exponent.
+fn parse_decimal(number: &str) -> Option<NumericValue> {
+    let number = number.trim();
+    let value = number
+        .parse::<f64>()
+        .ok()
+        .filter(|value| value.is_finite())?;
+    let digits = number.trim_start_matches(['-', '+']);
+    let (whole, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+    if !(whole.chars().chain(fraction.chars())).all(|c| c.is_ascii_digit()) {
+        return Some(NumericValue::Real(value));
+    }
+    let exact = (|| {
+        let numerator: i64 = format!("{}{}", whole, fraction).parse().ok()?;
+        let denominator = 10i64.checked_pow(u32::try_from(fraction.len()).ok()?)?;
+        let sign = if number.starts_with('-') { -1 } else { 1 };
+        NumericValue::rational(sign * numerator, denominator)
+    })();
+    Some(exact.unwrap_or(NumericValue::Real(value)))
+}
+
+/// The name an identifier is looked up by, `None` for scripts other than a plain subscript.
+fn identifier_name(identifier: &Identifier) -> Option<String> {
+    if identifier.pre_script.is_some() || identifier.mid_script.is_some() {
+        return None;
+    }
+    let mut name = identifier.body.clone();
+    if let Some(script) = &identifier.post_script {
+        let [subscript] = script.subscripts.as_slice() else {
+            return None;
+        };
+        if !script.superscripts.is_empty() {
+            return None;
+        }
+        name.push('_');
+        name.push_str(&match subscript.content.as_ref() {
+            MathNodeContent::Quantity {
+                number,
+                scientific_notation: None,
+                unit: None,
+            } => number.clone(),
+            MathNodeContent::Identifier(identifier) => identifier_name(identifier)?,
+            MathNodeContent::Text(text) | MathNodeContent::String(text) => text.clone(),
+            _ => return None,
+        });
+    }
+    name.extend(std::iter::repeat_n('\'', identifier.primes));
+    Some(name)
+}
+
+fn function_name(name: &MathNode) -> Option<&str> {
+    match name.content.as_ref() {
+        MathNodeContent::Identifier(identifier)
+            if identifier.pre_script.is_none()
+                && identifier.mid_script.is_none()
+                && identifier.post_script.is_none() =>
+        {
+            Some(&identifier.body)
+        }
+        _ => symbol(name),
+    }
+}
+
+fn symbol(node: &MathNode) -> Option<&str> {
+    match node.content.as_ref() {
+        MathNodeContent::Text(text) | MathNodeContent::String(text) => Some(text.trim()),
+        _ => None,
+    }
+}
+
+fn magnitude(value: NumericValue) -> NumericValue {
+    if value.to_f64() < 0.0 {
+        value.negate()
+    } else {
+        value
+    }
+}
+
+/// The components of the vector inside a norm: the entries of a matrix, row by row, or the
+/// coordinates of a point, through brackets and decorations, or just `node` for a number.
+fn components(node: &MathNode) -> Vec<&MathNode> {
+    match node.content.as_ref() {
+        MathNodeContent::Matrix { rows, .. } => rows.iter().flatten().collect(),
+        MathNodeContent::Coordinates { components, .. } => components.iter().collect(),
+        MathNodeContent::Bracketed { inner, .. }
+        | MathNodeContent::Decorated { inner, .. }
+        | MathNodeContent::Brace { inner, .. }
+        | MathNodeContent::Styled { inner, .. } => components(inner),
+        _ => vec![node],
+    }
+}
+
+fn is_square(rows: &[Vec<MathNode>]) -> bool {
+    !rows.is_empty() && rows.iter().all(|row| row.len() == rows.len())
+}
+
+/// The determinant of a square matrix by cofactor expansion along the first row, which keeps
+/// rational entries exact; the matrices of worked examples are small.
+fn determinant<T: Copy>(
+    rows: &[Vec<T>],
+    zero: T,
+    add: fn(T, T) -> T,
+    multiply: fn(T, T) -> T,
+    negate: fn(T) -> T,
+) -> T {
+    let (first, rest) = rows.split_first().expect("a square matrix has a row");
+    if rest.is_empty() {
+        return first[0];
+    }
+    first.iter().enumerate().fold(zero, |sum, (j, &entry)| {
+        let minor: Vec<Vec<T>> = rest
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .enumerate()
+                    .filter(|&(k, _)| k != j)
+                    .map(|(_, &value)| value)
+                    .collect()
+            })
+            .collect();
+        let term = multiply(entry, determinant(&minor, zero, add, multiply, negate));
+        add(sum, if j % 2 == 0 { term } else { negate(term) })
+    })
+}
+
+/// Whether a norm's subscript is `∞`, for the largest magnitude.
+fn is_infinity(node: &MathNode) -> bool {
+    match node.content.as_ref() {
+        MathNodeContent::Identifier(identifier) => identifier.body == "∞",
+        _ => symbol(node) == Some("∞"),
+    }
+}
+
+fn factorial(value: NumericValue) -> Result<NumericValue, EvaluationError> {
+    match value {
+        NumericValue::Rational {
+            numerator,
+            denominator: 1,
+        } if numerator >= 0 => {
+            let mut product = NumericValue::integer(1);
+            for factor in 2..=numerator {
+                product = product.multiply(NumericValue::integer(factor));
+                if !product.to_f64().is_finite() {
+                    break;
+                }
+            }
+            Ok(product)
+        }
+        _ => Err(EvaluationError::InvalidArgument {
+            function: "!".to_string(),
+        }),
+    }
+}
+
+/// Whether the condition of a `Cases` branch holds: a comparison, possibly chained as in
+/// `0 < x ≤ 1`, `True`, `False`, "otherwise", or `And`, `Or` and `Not` of those.
+fn holds(
+    condition: &MathNode,
+    environment: &BTreeMap<String, f64>,
+) -> Result<bool, EvaluationError> {
+    let value = |node: &MathNode| evaluate_node(node, environment).map(NumericValue::to_f64);
+    match condition.content.as_ref() {
+        MathNodeContent::True => Ok(true),
+        MathNodeContent::False => Ok(false),
+        MathNodeContent::Text(text) | MathNodeContent::String(text)
+            if text.trim().eq_ignore_ascii_case("otherwise") =>
+        {
+            Ok(true)
+        }
+        MathNodeContent::And(terms) => {
+            for term in terms {
+                if !holds(term, environment)? {
+                    return Ok(false);
+                }
+            }
+            Ok(true)
+        }
+        MathNodeContent::Or(terms) => {
+            for term in terms {
+                if holds(term, environment)? {
+                    return Ok(true);
+                }
+            }
+            Ok(false)
+        }
+        MathNodeContent::Not(term) => Ok(!holds(term, environment)?),
+        MathNodeContent::Relationship { lhs, rhs, operator } => {
+            // In a chain the left side is the comparison before, whose right side is compared
+            let lhs = match lhs.content.as_ref() {
+                MathNodeContent::Relationship { rhs: previous, .. } => {
+                    if !holds(lhs, environment)? {
+                        return Ok(false);
+                    }
+                    previous
+                }
+                _ => lhs,
+            };
+            let (a, b) = (value(lhs)?, value(rhs)?);
+            match operator {
+                RelationOperatorNode::Equal | RelationOperatorNode::IsEqual => Ok(a == b),
+                RelationOperatorNode::NotEqual => Ok(a != b),
+                RelationOperatorNode::Less => Ok(a < b),
+                RelationOperatorNode::LessEqual => Ok(a <= b),
+                RelationOperatorNode::Greater => Ok(a > b),
+                RelationOperatorNode::GreaterEqual => Ok(a >= b),
+                _ => Err(EvaluationError::Unsupported {
+                    kind: condition.content.kind_name(),
+                }),
+            }
+        }
+        _ => Err(EvaluationError::Unsupported {
+            kind: condition.content.kind_name(),
+        }),
+    }
+}
+
+/// `n` choose `k` for natural numbers, zero when `k > n`; a float once it overflows.
+fn binomial(n: NumericValue, k: NumericValue) -> Result<NumericValue, EvaluationError> {
+    let natural = |value: NumericValue| match value {
+        NumericValue::Rational {
+            numerator,
+            denominator: 1,
+        } if numerator >= 0 => Some(numerator as i128),
+        _ => None,
+    };
+    let (Some(n), Some(k)) = (natural(n), natural(k)) else {
+        return Err(EvaluationError::InvalidArgument {
+            function: "binomial".to_string(),
+        });
+    };
+    if k > n {
+        return Ok(NumericValue::integer(0));
+    }
+    // n(n-1)…(n-k+1)/k!, where every partial product divides exactly
+    let mut exact = Some(1);
+    let mut approximate = 1.0;
+    for i in 0..k.min(n - k) {
+        exact = exact
+            .and_then(|value: i128| value.checked_mul(n - i))
+            .map(|value| value / (i + 1));
+        approximate *= (n - i) as f64 / (i + 1) as f64;
+        if !approximate.is_finite() {
+            break;
+        }
+    }
+    Ok(match exact {
+        Some(value) => rational_or_real(value, 1),
+        None => NumericValue::Real(approximate),
+    })
+}
+
+/// `radicand^(1/index)`, a square root without an index; negative for an odd root of a negative
+/// number rather than NaN.
+fn root(
+    radicand: NumericValue,
+    index: Option<NumericValue>,
+) -> Result<NumericValue, EvaluationError> {
+    let index = index.unwrap_or(NumericValue::integer(2));
+    let exponent = NumericValue::integer(1)
+        .divide(index)
+        .ok_or(EvaluationError::DivisionByZero)?;
+    let odd = matches!(
+        index,
+        NumericValue::Rational {
+            numerator,
+            denominator: 1,
+        } if numerator % 2 != 0
+    );
+    if odd && radicand.to_f64() < 0.0 {
+        return radicand
+            .negate()
+            .power(exponent)
+            .map(NumericValue::negate)
+            .ok_or(EvaluationError::DivisionByZero);
+    }
+    radicand
+        .power(exponent)
+        .ok_or(EvaluationError::DivisionByZero)
+}
+
+fn call(name: &str, arguments: &[NumericValue]) -> Result<NumericValue, EvaluationError> {
+    let unary: Option<fn(f64) -> f64> = match name {
+        "sqrt" => Some(f64::sqrt),
+        "exp" => Some(f64::exp),
+        "ln" => Some(f64::ln),
+        "log" => Some(f64::log10),
+        "sin" => Some(f64::sin),
+        "cos" => Some(f64::cos),
+        "tan" => Some(f64::tan),
+        "arcsin" => Some(f64::asin),
+        "arccos" => Some(f64::acos),
+        "arctan" => Some(f64::atan),
+        "sinh" => Some(f64::sinh),
+        "cosh" => Some(f64::cosh),
+        "tanh" => Some(f64::tanh),
+        _ => None,
+    };
+    let compare = |pick: fn(f64, f64) -> bool, first: NumericValue, rest: &[NumericValue]| {
+        rest.iter().fold(
+            first,
+            |a, b| if pick(b.to_f64(), a.to_f64()) { *b } else { a },
+        )
+    };
+    match (unary, name, arguments) {
+        (Some(function), _, [x]) => Ok(NumericValue::Real(function(x.to_f64()))),
+        (None, "abs", [x]) if x.to_f64() < 0.0 => Ok(x.negate()),
+        (None, "abs", [x]) => Ok(*x),
+        (None, "min", [first, rest @ ..]) => Ok(compare(|a, b| a < b, *first, rest)),
+        (None, "max", [first, rest @ ..]) => Ok(compare(|a, b| a > b, *first, rest)),
+        (None, "abs" | "min" | "max", _) | (Some(_), _, _) => {
+            Err(EvaluationError::WrongArgumentCount {
+                function: name.to_string(),
+                count: arguments.len(),
+            })
+        }
+        (None, _, _) => Err(EvaluationError::UnknownFunction {
+            name: name.to_string(),
+        }),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum EvaluationError {
+    /// An identifier neither in the environment nor a known constant.
+    UnboundIdentifier {
+        name: String,
+    },
+    UnknownFunction {
+        name: String,
+    },
+    WrongArgumentCount {
+        function: String,
+        count: usize,
+    },
+    /// A factorial of something other than a natural number, or a function applied to an
+    /// interval entirely outside its domain.
+    InvalidArgument {
+        function: String,
+    },
+    InvalidNumber {
+        number: String,
+    },
+    DivisionByZero,
+    /// A piecewise expression none of whose conditions holds.
+    NoMatchingCase,
+    /// A node that has no numeric value, such as a relation, a sum notation or a matrix.
+    Unsupported {
+        kind: &'static str, // `MathNodeContent::kind_name` of the node
+    },
+}
+
+impl fmt::Display for EvaluationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EvaluationError::UnboundIdentifier { name } => {
+                write!(f, "identifier `{}` has no value", name)
+            }
+            EvaluationError::UnknownFunction { name } => write!(f, "unknown function `{}`", name),
+            EvaluationError::WrongArgumentCount { function, count } => write!(
+                f,
+                "`{}` cannot be called with {} arguments",
+                function, count
+            ),
+            EvaluationError::InvalidArgument { function } => {
+                write!(f, "invalid argument for `{}`", function)
+            }
+            EvaluationError::InvalidNumber { number } => {
+                write!(f, "`{}` is not a number", number)
+            }
+            EvaluationError::DivisionByZero => write!(f, "division by zero"),
+            EvaluationError::NoMatchingCase => write!(f, "no case applies"),
+            EvaluationError::Unsupported { kind } => write!(f, "{} cannot be evaluated", kind),
+        }
+    }
+}
+
+impl std::error::Error for EvaluationError {}
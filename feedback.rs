@@ -0,0 +1,184 @@
+use super::*;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use ts_rs::TS;
+
+/// Inline request for reader feedback, e.g. "Was this proof clear?".
+/// The renderer reports answers as `FeedbackResponse`s keyed by `id`.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct FeedbackNode {
+    pub id: String,
+    pub prompt: RichText,
+    pub response_kind: FeedbackResponseKind,
+    pub allow_comment: bool, // Offer a free-text box alongside the main answer
+    pub target_id: Option<String>, // Section or content the feedback is about
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub enum FeedbackResponseKind {
+    YesNo,
+    EmojiScale {
+        options: Vec<String>, // Lowest first, e.g. ["😞", "😐", "🙂", "😀"]
+    },
+    Rating {
+        min: u8,
+        max: u8,
+    },
+    FreeText {
+        placeholder: Option<String>,
+        max_length: Option<usize>,
+    },
+    Choice {
+        options: Vec<String>,
+        multiple: bool,
+    },
+}
+
+/// A reader's answer to a `FeedbackNode`, as sent back by the renderer.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct FeedbackResponse {
+    pub feedback_id: String,
+    pub value: FeedbackValue,
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub enum FeedbackValue {
+    YesNo(bool),
+    Scale(usize), // Index into `EmojiScale::options`
+    Rating(u8),
+    Text(String),
+    Choices(Vec<usize>), // Indices into `Choice::options`
+}
+
+impl FeedbackNode {
+    pub fn new(
+        id: impl Into<String>,
+        prompt: RichText,
+        response_kind: FeedbackResponseKind,
+    ) -> Self {
+        FeedbackNode {
+            id: id.into(),
+            prompt,
+            response_kind,
+            allow_comment: false,
+            target_id: None,
+        }
+    }
+
+    pub fn with_comment(mut self) -> Self {
+        self.allow_comment = true;
+        self
+    }
+
+    pub fn about(mut self, target_id: impl Into<String>) -> Self {
+        self.target_id = Some(target_id.into());
+        self
+    }
+
+    /// Checks that `response` answers this node with a value of the expected shape.
+    pub fn validate_response(&self, response: &FeedbackResponse) -> Result<(), FeedbackError> {
+        if response.feedback_id != self.id {
+            return Err(FeedbackError::WrongFeedbackId {
+                expected: self.id.clone(),
+                found: response.feedback_id.clone(),
+            });
+        }
+        if response.comment.is_some() && !self.allow_comment {
+            return Err(FeedbackError::CommentNotAllowed {
+                feedback_id: self.id.clone(),
+            });
+        }
+        let out_of_range = || FeedbackError::OutOfRange {
+            feedback_id: self.id.clone(),
+        };
+        match (&self.response_kind, &response.value) {
+            (FeedbackResponseKind::YesNo, FeedbackValue::YesNo(_)) => Ok(()),
+            (FeedbackResponseKind::EmojiScale { options }, FeedbackValue::Scale(index)) => {
+                if *index < options.len() {
+                    Ok(())
+                } else {
+                    Err(out_of_range())
+                }
+            }
+            (FeedbackResponseKind::Rating { min, max }, FeedbackValue::Rating(rating)) => {
+                if (min..=max).contains(&rating) {
+                    Ok(())
+                } else {
+                    Err(out_of_range())
+                }
+            }
+            (FeedbackResponseKind::FreeText { max_length, .. }, FeedbackValue::Text(text)) => {
+                match max_length {
+                    Some(max_length) if text.chars().count() > *max_length => Err(out_of_range()),
+                    _ => Ok(()),
+                }
+            }
+            (
+                FeedbackResponseKind::Choice { options, multiple },
+                FeedbackValue::Choices(chosen),
+            ) => {
+                let valid_count = !chosen.is_empty() && (*multiple || chosen.len() == 1);
+                if valid_count && chosen.iter().all(|index| *index < options.len()) {
+                    Ok(())
+                } else {
+                    Err(out_of_range())
+                }
+            }
+            _ => Err(FeedbackError::KindMismatch {
+                feedback_id: self.id.clone(),
+            }),
+        }
+    }
+}
+
+impl MathDocument {
+    /// Every feedback node in the document, in document order.
+    pub fn feedback_nodes(&self) -> Vec<&FeedbackNode> {
+        self.fold(vec![], |mut nodes, node| {
+            if let FoldNode::ContentNode(SectionContentNode::Feedback(feedback)) = node {
+                nodes.push(feedback);
+            }
+            nodes
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FeedbackError {
+    WrongFeedbackId { expected: String, found: String },
+    KindMismatch { feedback_id: String },
+    OutOfRange { feedback_id: String },
+    CommentNotAllowed { feedback_id: String },
+}
+
+impl fmt::Display for FeedbackError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FeedbackError::WrongFeedbackId { expected, found } => write!(
+                f,
+                "response is for feedback `{}`, expected `{}`",
+                found, expected
+            ),
+            FeedbackError::KindMismatch { feedback_id } => write!(
+                f,
+                "response to feedback `{}` does not match its response kind",
+                feedback_id
+            ),
+            FeedbackError::OutOfRange { feedback_id } => write!(
+                f,
+                "response to feedback `{}` is outside the allowed values",
+                feedback_id
+            ),
+            FeedbackError::CommentNotAllowed { feedback_id } => {
+                write!(f, "feedback `{}` does not accept comments", feedback_id)
+            }
+        }
+    }
+}
+
+impl std::error::Error for FeedbackError {}
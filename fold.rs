@@ -0,0 +1,122 @@
+use super::*;
+
+/// A node reached by a document fold, see `MathDocument::fold`.
+#[derive(Debug, Clone, Copy)]
+pub enum FoldNode<'doc> {
+    Section(&'doc Section),
+    Panel(&'doc Panel),
+    ContentNode(&'doc SectionContentNode),
+    MathNode(&'doc MathNode),
+}
+
+impl MathDocument {
+    /// Threads `init` through every section, panel, content node and math node of the document in
+    /// pre-order, the same order as `DocumentVisitor`. Math nodes include all their descendants.
+    pub fn fold<'doc, T>(&'doc self, init: T, f: impl FnMut(T, FoldNode<'doc>) -> T) -> T {
+        let mut folder = Folder { acc: Some(init), f };
+        folder.visit_document(self);
+        folder.into_acc()
+    }
+}
+
+impl Section {
+    /// Like `MathDocument::fold`, starting at this section.
+    pub fn fold<'doc, T>(&'doc self, init: T, f: impl FnMut(T, FoldNode<'doc>) -> T) -> T {
+        let mut folder = Folder { acc: Some(init), f };
+        folder.visit_section(self);
+        folder.into_acc()
+    }
+}
+
+impl SectionContentNode {
+    /// Like `MathDocument::fold`, starting at this content node.
+    pub fn fold<'doc, T>(&'doc self, init: T, f: impl FnMut(T, FoldNode<'doc>) -> T) -> T {
+        let mut folder = Folder { acc: Some(init), f };
+        folder.visit_content_node(self);
+        folder.into_acc()
+    }
+}
+
+impl MathNode {
+    /// Threads `init` through this node and its descendants in pre-order, children in
+    /// `children_with_paths` order.
+    pub fn fold<'a, T>(&'a self, init: T, mut f: impl FnMut(T, &'a MathNode) -> T) -> T {
+        fold_math_node(self, init, &mut f)
+    }
+
+    /// Bottom-up fold: `f` receives each node together with the results already computed for its
+    /// children, in `children_with_paths` order.
+    pub fn fold_up<T>(&self, mut f: impl FnMut(&MathNode, Vec<T>) -> T) -> T {
+        fn go<T>(node: &MathNode, f: &mut impl FnMut(&MathNode, Vec<T>) -> T) -> T {
+            let children = node
+                .content
+                .children_with_paths()
+                .into_iter()
+                .map(|(_, child)| go(child, f))
+                .collect();
+            f(node, children)
+        }
+        go(self, &mut f)
+    }
+}
+
+fn fold_math_node<'a, T>(
+    node: &'a MathNode,
+    acc: T,
+    f: &mut impl FnMut(T, &'a MathNode) -> T,
+) -> T {
+    let acc = f(acc, node);
+    node.content
+        .children_with_paths()
+        .into_iter()
+        .fold(acc, |acc, (_, child)| fold_math_node(child, acc, f))
+}
+
+struct Folder<T, F> {
+    acc: Option<T>, // only `None` while `f` runs
+    f: F,
+}
+
+impl<T, F> Folder<T, F> {
+    fn into_acc(self) -> T {
+        self.acc.expect("fold accumulator is always restored")
+    }
+}
+
+impl<'doc, T, F: FnMut(T, FoldNode<'doc>) -> T> Folder<T, F> {
+    fn apply(&mut self, node: FoldNode<'doc>) {
+        let acc = self
+            .acc
+            .take()
+            .expect("fold accumulator is always restored");
+        self.acc = Some((self.f)(acc, node));
+    }
+}
+
+impl<'doc, T, F: FnMut(T, FoldNode<'doc>) -> T> DocumentVisitor<'doc> for Folder<T, F> {
+    fn visit_section(&mut self, section: &'doc Section) {
+        self.apply(FoldNode::Section(section));
+        walk_section(self, section);
+    }
+
+    fn visit_content_node(&mut self, node: &'doc SectionContentNode) {
+        self.apply(FoldNode::ContentNode(node));
+        walk_content_node(self, node);
+    }
+
+    fn visit_panel(&mut self, panel: &'doc Panel) {
+        self.apply(FoldNode::Panel(panel));
+        walk_panel(self, panel);
+    }
+
+    fn visit_math_node(&mut self, node: &'doc MathNode) {
+        let acc = self
+            .acc
+            .take()
+            .expect("fold accumulator is always restored");
+        let f = &mut self.f;
+        self.acc = Some(fold_math_node(node, acc, &mut |acc, node| {
+            f(acc, FoldNode::MathNode(node))
+        }));
+    }
+}
@@ -0,0 +1,1024 @@
+use super::*;
+use std::{
+    borrow::Borrow,
+    collections::{BTreeSet, HashSet},
+    sync::Arc,
+};
+
+impl MathNode {
+    /// Every identifier occurring in this expression, including function names and identifiers
+    /// inside scripts, bound or not.
+    pub fn collect_identifiers(&self) -> HashSet<Identifier> {
+        self.fold(HashSet::new(), |mut identifiers, node| {
+            if let MathNodeContent::Identifier(identifier) = node.content.as_ref() {
+                identifiers.insert(identifier.clone());
+            }
+            identifiers
+        })
+    }
+
+    /// The identifiers this expression depends on from outside: every identifier that is not a
+    /// function name and is not bound by an enclosing construct.
+    ///
+    /// Binding constructs are
+    /// - `QuantifiedExpression`: its variables, in the predicate
+    /// - `SetBuilder`: the variable, in the predicate
+    /// - `SumNotation`/`ProductNotation`: the index variable, in the summand and in a lower limit
+    ///   of the form `i = ...` (whose right-hand side stays free)
+    /// - `Limit`: the limit variable, in the function
+    /// - `Integration`: the variables of the differentials, in the integrand
+    /// - `FunctionDefinition`: the parameters, in the definition, and the identifiers in the
+    ///   arguments of each clause, in the clause's condition and definition
+    ///
+    /// Names introduced by `VariableDefinition` and `FunctionDefinition` are not free either.
+    pub fn collect_free_variables(&self) -> HashSet<Identifier> {
+        self.variable_analysis().free.into_iter().collect()
+    }
+
+    /// Whether the expression has no free variables.
+    pub fn is_closed(&self) -> bool {
+        self.collect_free_variables().is_empty()
+    }
+}
+
+/// Which variables of an expression or a judgement are free and which are bound, for validators
+/// and for exporting to Lean or Coq, where every variable has to be bound or declared.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VariableAnalysis {
+    /// Variables used outside the scope of any binding of them.
+    pub free: BTreeSet<Identifier>,
+    /// Variables bound by one of the constructs listed at `collect_free_variables`, or declared by
+    /// a judgement.
+    pub bound: BTreeSet<Identifier>,
+    pub shadowing: Vec<ShadowingWarning>,
+}
+
+/// A variable bound again within the scope of a binding of it, like the inner `x` of
+/// `∀ x : ∃ x : x > 0`, which hides the outer one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShadowingWarning {
+    pub variable: Identifier,
+    pub node_id: String, // the node binding it again
+}
+
+impl VariableAnalysis {
+    /// Records `variable` being bound by the node `node_id` where `bound` are already bound.
+    fn bind(&mut self, variable: &Identifier, bound: &[Identifier], node_id: &str) {
+        if bound.iter().any(|b| same_variable(b, variable)) {
+            self.shadowing.push(ShadowingWarning {
+                variable: variable.clone(),
+                node_id: node_id.to_string(),
+            });
+        }
+        self.bound.insert(variable.clone());
+    }
+}
+
+impl MathNode {
+    /// The free and bound variables of the expression, with the bindings that shadow an
+    /// enclosing one.
+    pub fn variable_analysis(&self) -> VariableAnalysis {
+        let mut analysis = VariableAnalysis::default();
+        collect_free(self, &mut vec![], &mut analysis);
+        analysis
+    }
+}
+
+impl Judgement {
+    /// The free and bound variables of the judgement. Each declaration, quantified or not, binds
+    /// its names in the declarations after it and in the statement; math in a declaration's type
+    /// may use the names declared before it.
+    pub fn variable_analysis(&self) -> VariableAnalysis {
+        let mut analysis = VariableAnalysis::default();
+        let mut bound = vec![];
+        let quantified = self.quantifiers.iter().flat_map(|group| match group {
+            QuantifiedVariableDeclarationGroup::Exists(declaration)
+            | QuantifiedVariableDeclarationGroup::UniqueExists(declaration) => {
+                std::slice::from_ref(declaration)
+            }
+            QuantifiedVariableDeclarationGroup::ForAll(declarations) => declarations.as_slice(),
+        });
+        for declaration in self.non_quantifiers.iter().chain(quantified) {
+            for segment in &declaration.type_info.segments {
+                if let RichTextSegment::Math(math) = segment {
+                    collect_free(math, &mut bound, &mut analysis);
+                }
+            }
+            for variable in binders(&declaration.name) {
+                analysis.bind(&variable, &bound, &declaration.name.id);
+                bound.push(variable);
+            }
+        }
+        collect_free_logical(&self.statement, &mut bound, &mut analysis);
+        analysis
+    }
+}
+
+fn collect_free_logical(
+    node: &LogicalNode,
+    bound: &mut Vec<Identifier>,
+    analysis: &mut VariableAnalysis,
+) {
+    match node {
+        LogicalNode::And(nodes) | LogicalNode::Or(nodes) => {
+            for node in nodes {
+                collect_free_logical(node, bound, analysis);
+            }
+        }
+        LogicalNode::Atomic(math) => collect_free(math, bound, analysis),
+        LogicalNode::True | LogicalNode::False => {}
+    }
+}
+
+/// Whether `a` and `b` name the same variable; `is_function` is presentation only.
+fn same_variable(a: &Identifier, b: &Identifier) -> bool {
+    a.body == b.body
+        && a.pre_script == b.pre_script
+        && a.mid_script == b.mid_script
+        && a.post_script == b.post_script
+        && a.primes == b.primes
+}
+
+/// The identifiers introduced by a binding occurrence such as a sum index, `dx` or `f(x, y)`.
+fn binders(node: &MathNode) -> Vec<Identifier> {
+    match node.content.as_ref() {
+        MathNodeContent::Identifier(identifier) => vec![identifier.clone()],
+        MathNodeContent::Bracketed { inner, .. } => binders(inner),
+        MathNodeContent::Differential { target, .. } => binders(target),
+        MathNodeContent::FunctionCall { parameters, .. } => {
+            parameters.iter().flat_map(binders).collect()
+        }
+        _ => vec![],
+    }
+}
+
+/// Records the variables of `node` in `analysis`, with `bound` holding the variables bound by
+/// enclosing constructs.
+fn collect_free(node: &MathNode, bound: &mut Vec<Identifier>, analysis: &mut VariableAnalysis) {
+    // Visits `body` with `binding`, introduced by `node`, added to the bound variables.
+    let within = |body: &MathNode,
+                  binding: Vec<Identifier>,
+                  bound: &mut Vec<Identifier>,
+                  analysis: &mut VariableAnalysis| {
+        let depth = bound.len();
+        for variable in binding {
+            analysis.bind(&variable, bound, &node.id);
+            bound.push(variable);
+        }
+        collect_free(body, bound, analysis);
+        bound.truncate(depth);
+    };
+
+    match node.content.as_ref() {
+        MathNodeContent::Identifier(identifier) => {
+            if !identifier.is_function && !bound.iter().any(|b| same_variable(b, identifier)) {
+                analysis.free.insert(identifier.clone());
+            }
+            // Scripts are ordinary subexpressions, e.g. the `i` in `x_i`.
+            for (_, child) in node.content.children_with_paths() {
+                collect_free(child, bound, analysis);
+            }
+        }
+        MathNodeContent::FunctionCall { name, parameters } => {
+            match name.content.as_ref() {
+                // A function name is not a variable, but its scripts may use some.
+                MathNodeContent::Identifier(_) => {
+                    for (_, child) in name.content.children_with_paths() {
+                        collect_free(child, bound, analysis);
+                    }
+                }
+                _ => collect_free(name, bound, analysis),
+            }
+            for parameter in parameters {
+                collect_free(parameter, bound, analysis);
+            }
+        }
+        MathNodeContent::SumNotation {
+            summand: body,
+            variable,
+            lower_limit,
+            upper_limit,
+        }
+        | MathNodeContent::ProductNotation {
+            multiplicand: body,
+            variable,
+            lower_limit,
+            upper_limit,
+        } => {
+            let mut binding = variable.as_ref().map(binders).unwrap_or_default();
+            if let Some(lower_limit) = lower_limit {
+                match lower_limit.content.as_ref() {
+                    // In `i = 1` the `i` is the binding occurrence itself.
+                    MathNodeContent::Relationship { lhs, rhs, .. } if !binders(lhs).is_empty() => {
+                        if binding.is_empty() {
+                            binding = binders(lhs);
+                        }
+                        collect_free(rhs, bound, analysis);
+                    }
+                    _ => collect_free(lower_limit, bound, analysis),
+                }
+            }
+            if let Some(upper_limit) = upper_limit {
+                collect_free(upper_limit, bound, analysis);
+            }
+            within(body, binding, bound, analysis);
+        }
+        MathNodeContent::Limit {
+            function,
+            variable,
+            approaching_value,
+        } => {
+            collect_free(approaching_value, bound, analysis);
+            let binding = vec![Identifier::new_simple(variable.clone())];
+            within(function, binding, bound, analysis);
+        }
+        MathNodeContent::Integration {
+            integrand,
+            differentials,
+            domain,
+        } => {
+            let mut binding = vec![];
+            for (differential, lower_bound, upper_bound) in differentials {
+                binding.extend(binders(differential));
+                for limit in [lower_bound, upper_bound].into_iter().flatten() {
+                    collect_free(limit, bound, analysis);
+                }
+            }
+            if let Some(domain) = domain {
+                collect_free(domain, bound, analysis);
+            }
+            within(integrand, binding, bound, analysis);
+        }
+        MathNodeContent::QuantifiedExpression {
+            variables,
+            domain,
+            predicate,
+            ..
+        } => {
+            if let Some(domain) = domain {
+                collect_free(domain, bound, analysis);
+            }
+            if let Some(predicate) = predicate {
+                let binding = variables.iter().flat_map(binders).collect();
+                within(predicate, binding, bound, analysis);
+            }
+        }
+        MathNodeContent::SetBuilder {
+            variable,
+            domain,
+            predicate,
+        } => {
+            if let Some(domain) = domain {
+                collect_free(domain, bound, analysis);
+            }
+            within(predicate, binders(variable), bound, analysis);
+        }
+        MathNodeContent::VariableDefinition { definition, .. } => {
+            if let Some(definition) = definition {
+                collect_free(definition, bound, analysis);
+            }
+        }
+        MathNodeContent::FunctionDefinition {
+            custom_function,
+            definition,
+            clauses,
+        } => {
+            if let Some(definition) = definition {
+                within(definition, binders(custom_function), bound, analysis);
+            }
+            for clause in clauses {
+                let binding: Vec<Identifier> = clause.arguments.iter().flat_map(binders).collect();
+                if let Some(condition) = &clause.condition {
+                    within(condition, binding.clone(), bound, analysis);
+                }
+                within(&clause.definition, binding, bound, analysis);
+            }
+        }
+        _ => {
+            for (_, child) in node.content.children_with_paths() {
+                collect_free(child, bound, analysis);
+            }
+        }
+    }
+}
+
+impl MathNode {
+    /// This expression with the free occurrences of `variable` replaced by `replacement`, for
+    /// showing the steps of a proof. Occurrences bound by one of the constructs listed at
+    /// `collect_free_variables` are left alone, and a bound variable that would capture a free
+    /// variable of `replacement` is renamed with primes first: substituting `y` for `x` in
+    /// `∀ y : x < y` gives `∀ y' : y < y'`.
+    pub fn substitute(&self, variable: &Identifier, replacement: &MathNode) -> MathNode {
+        let substitution = Substitution {
+            variable,
+            replacement,
+            replacement_free: replacement.collect_free_variables(),
+            renaming: false,
+        };
+        substitution.apply(self).unwrap_or_else(|| self.clone())
+    }
+}
+
+struct Substitution<'a> {
+    variable: &'a Identifier,
+    replacement: &'a MathNode,
+    replacement_free: HashSet<Identifier>,
+    renaming: bool, // renaming a bound variable, keeping the ids of its occurrences
+}
+
+impl Substitution<'_> {
+    /// `node` with the substitution applied, `None` when nothing changed.
+    fn apply(&self, node: &MathNode) -> Option<MathNode> {
+        let content = match node.content.as_ref() {
+            MathNodeContent::Identifier(identifier)
+                if !identifier.is_function && same_variable(identifier, self.variable) =>
+            {
+                if self.renaming {
+                    return Some(MathNode {
+                        id: node.id.clone(),
+                        content: Arc::clone(&self.replacement.content),
+                    });
+                }
+                return Some(self.replacement.clone());
+            }
+            MathNodeContent::FunctionCall { name, parameters } => {
+                let new_name = match name.content.as_ref() {
+                    // A function name is not a variable, but its scripts may use one.
+                    MathNodeContent::Identifier(_) => name
+                        .content
+                        .map_children(|child| self.apply(child))
+                        .map(|content| MathNode {
+                            id: name.id.clone(),
+                            content: Arc::new(content),
+                        }),
+                    _ => self.apply(name),
+                };
+                let new_parameters = self.apply_all(parameters);
+                if new_name.is_none() && new_parameters.is_none() {
+                    return None;
+                }
+                MathNodeContent::FunctionCall {
+                    name: keep_arc(new_name, name),
+                    parameters: new_parameters.unwrap_or_else(|| parameters.clone()),
+                }
+            }
+            MathNodeContent::SumNotation {
+                summand: body,
+                variable,
+                lower_limit,
+                upper_limit,
+            }
+            | MathNodeContent::ProductNotation {
+                multiplicand: body,
+                variable,
+                lower_limit,
+                upper_limit,
+            } => {
+                // As in `collect_free`: the index is `variable`, or the `i` of `i = 1`.
+                let lower_binding =
+                    lower_limit
+                        .as_ref()
+                        .and_then(|limit| match limit.content.as_ref() {
+                            MathNodeContent::Relationship { lhs, rhs, operator }
+                                if !binders(lhs).is_empty() =>
+                            {
+                                Some((lhs, rhs, operator))
+                            }
+                            _ => None,
+                        });
+                let binding = match (variable, lower_binding) {
+                    (Some(variable), _) if !binders(variable).is_empty() => binders(variable),
+                    (_, Some((lhs, ..))) => binders(lhs),
+                    _ => vec![],
+                };
+                let (new_body, renames) = self.scoped(body, &binding, primed);
+                let new_lower = match (lower_limit, lower_binding) {
+                    (Some(limit), Some((lhs, rhs, operator))) => {
+                        let new_lhs = rename(lhs, &renames);
+                        let new_rhs = self.apply(rhs);
+                        (new_lhs.is_some() || new_rhs.is_some()).then(|| MathNode {
+                            id: limit.id.clone(),
+                            content: Arc::new(MathNodeContent::Relationship {
+                                lhs: keep_arc(new_lhs, lhs),
+                                rhs: keep_arc(new_rhs, rhs),
+                                operator: operator.clone(),
+                            }),
+                        })
+                    }
+                    (Some(limit), None) => self.apply(limit),
+                    (None, _) => None,
+                };
+                let new_upper = upper_limit.as_ref().and_then(|limit| self.apply(limit));
+                if new_body.is_none() && new_lower.is_none() && new_upper.is_none() {
+                    return None;
+                }
+                let variable = variable
+                    .as_ref()
+                    .map(|variable| rename(variable, &renames).unwrap_or_else(|| variable.clone()));
+                let body = keep_arc(new_body, body);
+                let lower_limit = lower_limit.as_ref().map(|limit| keep_arc(new_lower, limit));
+                let upper_limit = upper_limit.as_ref().map(|limit| keep_arc(new_upper, limit));
+                match node.content.as_ref() {
+                    MathNodeContent::SumNotation { .. } => MathNodeContent::SumNotation {
+                        summand: body,
+                        variable,
+                        lower_limit,
+                        upper_limit,
+                    },
+                    _ => MathNodeContent::ProductNotation {
+                        multiplicand: body,
+                        variable,
+                        lower_limit,
+                        upper_limit,
+                    },
+                }
+            }
+            MathNodeContent::Limit {
+                function,
+                variable,
+                approaching_value,
+            } => {
+                let binding = [Identifier::new_simple(variable.clone())];
+                // The variable is a plain name here, so it is renamed by priming its body.
+                let (new_function, renames) =
+                    self.scoped(function, &binding, |identifier, primes| {
+                        Identifier::new_simple(format!("{}{}", identifier.body, "'".repeat(primes)))
+                    });
+                let new_approaching = self.apply(approaching_value);
+                if new_function.is_none() && new_approaching.is_none() {
+                    return None;
+                }
+                MathNodeContent::Limit {
+                    function: keep_arc(new_function, function),
+                    variable: renames
+                        .first()
+                        .map_or_else(|| variable.clone(), |(_, to)| to.body.clone()),
+                    approaching_value: keep_arc(new_approaching, approaching_value),
+                }
+            }
+            MathNodeContent::Integration {
+                integrand,
+                differentials,
+                domain,
+            } => {
+                let binding: Vec<Identifier> = differentials
+                    .iter()
+                    .flat_map(|(differential, ..)| binders(differential))
+                    .collect();
+                let (new_integrand, renames) = self.scoped(integrand, &binding, primed);
+                let mut changed = new_integrand.is_some();
+                let differentials = differentials
+                    .iter()
+                    .map(|(differential, lower_bound, upper_bound)| {
+                        let mut bound = |limit: &Option<Arc<MathNode>>| {
+                            limit.as_ref().map(|limit| {
+                                let new_limit = self.apply(limit);
+                                changed |= new_limit.is_some();
+                                keep_arc(new_limit, limit)
+                            })
+                        };
+                        (
+                            keep_arc(rename(differential, &renames), differential),
+                            bound(lower_bound),
+                            bound(upper_bound),
+                        )
+                    })
+                    .collect();
+                let domain = domain.as_ref().map(|domain| {
+                    let new_domain = self.apply(domain);
+                    changed |= new_domain.is_some();
+                    keep_arc(new_domain, domain)
+                });
+                if !changed {
+                    return None;
+                }
+                MathNodeContent::Integration {
+                    integrand: keep_arc(new_integrand, integrand),
+                    differentials,
+                    domain,
+                }
+            }
+            MathNodeContent::QuantifiedExpression {
+                quantifier,
+                variables,
+                domain,
+                predicate,
+            } => {
+                let binding: Vec<Identifier> = variables.iter().flat_map(binders).collect();
+                let (new_predicate, renames) = match predicate {
+                    Some(predicate) => self.scoped(predicate, &binding, primed),
+                    None => (None, vec![]),
+                };
+                let new_domain = domain.as_ref().and_then(|domain| self.apply(domain));
+                if new_predicate.is_none() && new_domain.is_none() {
+                    return None;
+                }
+                MathNodeContent::QuantifiedExpression {
+                    quantifier: quantifier.clone(),
+                    variables: variables
+                        .iter()
+                        .map(|variable| {
+                            rename(variable, &renames).unwrap_or_else(|| variable.clone())
+                        })
+                        .collect(),
+                    domain: domain.as_ref().map(|domain| keep_arc(new_domain, domain)),
+                    predicate: predicate
+                        .as_ref()
+                        .map(|predicate| keep_arc(new_predicate, predicate)),
+                }
+            }
+            MathNodeContent::SetBuilder {
+                variable,
+                domain,
+                predicate,
+            } => {
+                let (new_predicate, renames) = self.scoped(predicate, &binders(variable), primed);
+                let new_domain = domain.as_ref().and_then(|domain| self.apply(domain));
+                if new_predicate.is_none() && new_domain.is_none() {
+                    return None;
+                }
+                MathNodeContent::SetBuilder {
+                    variable: keep_arc(rename(variable, &renames), variable),
+                    domain: domain.as_ref().map(|domain| keep_arc(new_domain, domain)),
+                    predicate: keep_arc(new_predicate, predicate),
+                }
+            }
+            MathNodeContent::VariableDefinition { name, definition } => {
+                // The defined name is not an occurrence of a variable.
+                let new_definition = definition
+                    .as_ref()
+                    .and_then(|definition| self.apply(definition))?;
+                MathNodeContent::VariableDefinition {
+                    name: name.clone(),
+                    definition: Some(new_definition),
+                }
+            }
+            MathNodeContent::FunctionDefinition {
+                custom_function,
+                definition,
+                clauses,
+            } => {
+                let (new_definition, renames) = match definition {
+                    Some(definition) => self.scoped(definition, &binders(custom_function), primed),
+                    None => (None, vec![]),
+                };
+                let new_clauses: Vec<Option<FunctionClause>> = clauses
+                    .iter()
+                    .map(|clause| self.apply_clause(clause))
+                    .collect();
+                if new_definition.is_none() && new_clauses.iter().all(Option::is_none) {
+                    return None;
+                }
+                MathNodeContent::FunctionDefinition {
+                    custom_function: keep_arc(rename(custom_function, &renames), custom_function),
+                    definition: new_definition.or_else(|| definition.clone()),
+                    clauses: new_clauses
+                        .into_iter()
+                        .zip(clauses)
+                        .map(|(new_clause, clause)| new_clause.unwrap_or_else(|| clause.clone()))
+                        .collect(),
+                }
+            }
+            _ => node.content.map_children(|child| self.apply(child))?,
+        };
+        Some(MathNode {
+            id: node.id.clone(),
+            content: Arc::new(content),
+        })
+    }
+
+    fn apply_all(&self, nodes: &[MathNode]) -> Option<Vec<MathNode>> {
+        let new_nodes: Vec<Option<MathNode>> = nodes.iter().map(|node| self.apply(node)).collect();
+        if new_nodes.iter().all(Option::is_none) {
+            return None;
+        }
+        Some(
+            new_nodes
+                .into_iter()
+                .zip(nodes)
+                .map(|(new_node, node)| new_node.unwrap_or_else(|| node.clone()))
+                .collect(),
+        )
+    }
+
+    /// A clause of a `FunctionDefinition` with the substitution applied, `None` when nothing
+    /// changed. The identifiers of the arguments are bound in the condition and the definition.
+    fn apply_clause(&self, clause: &FunctionClause) -> Option<FunctionClause> {
+        let binding: Vec<Identifier> = clause.arguments.iter().flat_map(binders).collect();
+        // The condition and the definition share their bound variables, so they are handled
+        // together, as the one branch of a `Cases`.
+        let body = match &clause.condition {
+            Some(condition) => MathNode {
+                id: clause.definition.id.clone(),
+                content: Arc::new(MathNodeContent::Cases {
+                    branches: vec![(clause.definition.clone(), condition.clone())],
+                }),
+            },
+            None => clause.definition.clone(),
+        };
+        let (new_body, renames) = self.scoped(&body, &binding, primed);
+        let new_body = new_body?;
+        let (definition, condition) = match (new_body.content.as_ref(), &clause.condition) {
+            (MathNodeContent::Cases { branches }, Some(_)) => {
+                let (definition, condition) = branches[0].clone();
+                (definition, Some(condition))
+            }
+            _ => (new_body, None),
+        };
+        Some(FunctionClause {
+            arguments: clause
+                .arguments
+                .iter()
+                .map(|argument| rename(argument, &renames).unwrap_or_else(|| argument.clone()))
+                .collect(),
+            condition,
+            definition,
+        })
+    }
+
+    /// Applies the substitution to `body`, in which `binding` is bound. Returns the new body and
+    /// the bound variables that had to be renamed, each with its new name, which the binding
+    /// occurrences have to be renamed to as well. `fresh(variable, n)` gives the `n`th
+    /// candidate for a new name of `variable`.
+    fn scoped(
+        &self,
+        body: &MathNode,
+        binding: &[Identifier],
+        fresh: impl Fn(&Identifier, usize) -> Identifier,
+    ) -> (Option<MathNode>, Vec<(Identifier, Identifier)>) {
+        let shadowed = binding
+            .iter()
+            .any(|bound| same_variable(bound, self.variable));
+        let occurs = body
+            .collect_free_variables()
+            .iter()
+            .any(|free| same_variable(free, self.variable));
+        if shadowed || !occurs {
+            return (None, vec![]);
+        }
+
+        let mut taken: Vec<Identifier> = body.collect_identifiers().into_iter().collect();
+        taken.extend(self.replacement_free.iter().cloned());
+        taken.extend(binding.iter().cloned());
+        taken.push(self.variable.clone());
+        let mut renames = vec![];
+        let mut body = body.clone();
+        for bound in binding {
+            if !self
+                .replacement_free
+                .iter()
+                .any(|free| same_variable(free, bound))
+            {
+                continue;
+            }
+            let renamed = (1..)
+                .map(|n| fresh(bound, n))
+                .find(|candidate| !taken.iter().any(|taken| same_variable(taken, candidate)))
+                .unwrap_or_else(|| bound.clone());
+            let rename_bound = Substitution {
+                variable: bound,
+                replacement: &MathNode::identifier(renamed.clone()),
+                replacement_free: HashSet::new(),
+                renaming: true,
+            };
+            body = rename_bound.apply(&body).unwrap_or(body);
+            taken.push(renamed.clone());
+            renames.push((bound.clone(), renamed));
+        }
+        (Some(self.apply(&body).unwrap_or(body)), renames)
+    }
+}
+
+/// `identifier` with `primes` more primes.
+fn primed(identifier: &Identifier, primes: usize) -> Identifier {
+    Identifier {
+        primes: identifier.primes + primes,
+        ..identifier.clone()
+    }
+}
+
+/// Renames the identifiers of a binding occurrence, such as the `x` of `dx`, by `renames`.
+fn rename(node: &MathNode, renames: &[(Identifier, Identifier)]) -> Option<MathNode> {
+    if renames.is_empty() {
+        return None;
+    }
+    if let MathNodeContent::Identifier(identifier) = node.content.as_ref()
+        && let Some((_, to)) = renames
+            .iter()
+            .find(|(from, _)| same_variable(from, identifier))
+    {
+        return Some(MathNode {
+            id: node.id.clone(),
+            content: Arc::new(MathNodeContent::Identifier(Identifier {
+                is_function: identifier.is_function,
+                ..to.clone()
+            })),
+        });
+    }
+    node.content
+        .map_children(|child| rename(child, renames))
+        .map(|content| MathNode {
+            id: node.id.clone(),
+            content: Arc::new(content),
+        })
+}
+
+fn keep_arc(new_node: Option<MathNode>, node: &Arc<MathNode>) -> Arc<MathNode> {
+    new_node.map_or_else(|| Arc::clone(node), Arc::new)
+}
+
+impl MathNode {
+    /// Whether both expressions are the same up to the names of bound variables, e.g.
+    /// `∀ x : x > 0` and `∀ y : y > 0`, or `Σ_{i=1}^n i` and `Σ_{k=1}^n k`. Variables count as
+    /// bound in the constructs listed at `collect_free_variables`; free variables have to match
+    /// by name. Node ids are ignored.
+    pub fn alpha_eq(&self, other: &MathNode) -> bool {
+        AlphaEq { bound: vec![] }.eq(self, other)
+    }
+}
+
+/// Compares two expressions, with `bound` pairing up the variables bound by the enclosing
+/// constructs on either side, innermost last.
+struct AlphaEq {
+    bound: Vec<(Identifier, Identifier)>,
+}
+
+impl AlphaEq {
+    fn eq(&mut self, a: &MathNode, b: &MathNode) -> bool {
+        let (a, b) = (a.content.as_ref(), b.content.as_ref());
+        if let (MathNodeContent::Identifier(x), MathNodeContent::Identifier(y)) = (a, b) {
+            let x_index = self
+                .bound
+                .iter()
+                .rposition(|(bound, _)| same_variable(bound, x));
+            let y_index = self
+                .bound
+                .iter()
+                .rposition(|(_, bound)| same_variable(bound, y));
+            match (x_index, y_index) {
+                (Some(x_index), Some(y_index)) => return x_index == y_index,
+                (None, None) => {}
+                _ => return false,
+            }
+        }
+        if shell(a) != shell(b) {
+            return false;
+        }
+        match (a, b) {
+            (
+                MathNodeContent::SumNotation {
+                    summand: a_body,
+                    variable: a_variable,
+                    lower_limit: a_lower,
+                    upper_limit: a_upper,
+                }
+                | MathNodeContent::ProductNotation {
+                    multiplicand: a_body,
+                    variable: a_variable,
+                    lower_limit: a_lower,
+                    upper_limit: a_upper,
+                },
+                MathNodeContent::SumNotation {
+                    summand: b_body,
+                    variable: b_variable,
+                    lower_limit: b_lower,
+                    upper_limit: b_upper,
+                }
+                | MathNodeContent::ProductNotation {
+                    multiplicand: b_body,
+                    variable: b_variable,
+                    lower_limit: b_lower,
+                    upper_limit: b_upper,
+                },
+            ) => {
+                let (a_binding, a_index) = index_binding(a_variable, a_lower);
+                let (b_binding, b_index) = index_binding(b_variable, b_lower);
+                if !self.eq_options(a_upper, b_upper) {
+                    return false;
+                }
+                // The `i = 1` of a lower limit: `1` is outside the scope of `i`.
+                let lower_eq = match (a_index, b_index) {
+                    (Some((a_limit, a_lhs, a_rhs)), Some((b_limit, b_lhs, b_rhs))) => {
+                        shell(&a_limit.content) == shell(&b_limit.content)
+                            && self.eq(a_rhs, b_rhs)
+                            && self.within(&a_binding, &b_binding, |eq| eq.eq(a_lhs, b_lhs))
+                    }
+                    (None, None) => self.eq_options(a_lower, b_lower),
+                    _ => false,
+                };
+                lower_eq
+                    && self.within(&a_binding, &b_binding, |eq| {
+                        eq.eq(a_body, b_body) && eq.eq_options(a_variable, b_variable)
+                    })
+            }
+            (
+                MathNodeContent::Limit {
+                    function: a_function,
+                    variable: a_variable,
+                    approaching_value: a_value,
+                },
+                MathNodeContent::Limit {
+                    function: b_function,
+                    variable: b_variable,
+                    approaching_value: b_value,
+                },
+            ) => {
+                let a_binding = [Identifier::new_simple(a_variable.clone())];
+                let b_binding = [Identifier::new_simple(b_variable.clone())];
+                self.eq(a_value, b_value)
+                    && self.within(&a_binding, &b_binding, |eq| eq.eq(a_function, b_function))
+            }
+            (
+                MathNodeContent::Integration {
+                    integrand: a_integrand,
+                    differentials: a_differentials,
+                    domain: a_domain,
+                },
+                MathNodeContent::Integration {
+                    integrand: b_integrand,
+                    differentials: b_differentials,
+                    domain: b_domain,
+                },
+            ) => {
+                let binding = |differentials: &[(Arc<MathNode>, _, _)]| -> Vec<Identifier> {
+                    differentials
+                        .iter()
+                        .flat_map(|(differential, ..)| binders(differential))
+                        .collect()
+                };
+                let (a_binding, b_binding) = (binding(a_differentials), binding(b_differentials));
+                let bounds_eq = a_differentials.iter().zip(b_differentials).all(
+                    |((_, a_lower, a_upper), (_, b_lower, b_upper))| {
+                        self.eq_options(a_lower, b_lower) && self.eq_options(a_upper, b_upper)
+                    },
+                );
+                bounds_eq
+                    && self.eq_options(a_domain, b_domain)
+                    && self.within(&a_binding, &b_binding, |eq| {
+                        eq.eq(a_integrand, b_integrand)
+                            && a_differentials
+                                .iter()
+                                .zip(b_differentials)
+                                .all(|((a, ..), (b, ..))| eq.eq(a, b))
+                    })
+            }
+            (
+                MathNodeContent::QuantifiedExpression {
+                    variables: a_variables,
+                    domain: a_domain,
+                    predicate: a_predicate,
+                    ..
+                },
+                MathNodeContent::QuantifiedExpression {
+                    variables: b_variables,
+                    domain: b_domain,
+                    predicate: b_predicate,
+                    ..
+                },
+            ) => {
+                let a_binding: Vec<Identifier> = a_variables.iter().flat_map(binders).collect();
+                let b_binding: Vec<Identifier> = b_variables.iter().flat_map(binders).collect();
+                self.eq_options(a_domain, b_domain)
+                    && self.within(&a_binding, &b_binding, |eq| {
+                        eq.eq_options(a_predicate, b_predicate)
+                            && a_variables
+                                .iter()
+                                .zip(b_variables)
+                                .all(|(a, b)| eq.eq(a, b))
+                    })
+            }
+            (
+                MathNodeContent::SetBuilder {
+                    variable: a_variable,
+                    domain: a_domain,
+                    predicate: a_predicate,
+                },
+                MathNodeContent::SetBuilder {
+                    variable: b_variable,
+                    domain: b_domain,
+                    predicate: b_predicate,
+                },
+            ) => {
+                let (a_binding, b_binding) = (binders(a_variable), binders(b_variable));
+                self.eq_options(a_domain, b_domain)
+                    && self.within(&a_binding, &b_binding, |eq| {
+                        eq.eq(a_predicate, b_predicate) && eq.eq(a_variable, b_variable)
+                    })
+            }
+            (
+                MathNodeContent::FunctionDefinition {
+                    custom_function: a_function,
+                    definition: a_definition,
+                    clauses: a_clauses,
+                },
+                MathNodeContent::FunctionDefinition {
+                    custom_function: b_function,
+                    definition: b_definition,
+                    clauses: b_clauses,
+                },
+            ) => {
+                let (a_binding, b_binding) = (binders(a_function), binders(b_function));
+                self.within(&a_binding, &b_binding, |eq| {
+                    eq.eq(a_function, b_function) && eq.eq_options(a_definition, b_definition)
+                }) && a_clauses.len() == b_clauses.len()
+                    && a_clauses
+                        .iter()
+                        .zip(b_clauses)
+                        .all(|(a_clause, b_clause)| self.eq_clauses(a_clause, b_clause))
+            }
+            _ => {
+                let a_children = a.children_with_paths();
+                let b_children = b.children_with_paths();
+                a_children.len() == b_children.len()
+                    && a_children
+                        .into_iter()
+                        .zip(b_children)
+                        .all(|((_, a), (_, b))| self.eq(a, b))
+            }
+        }
+    }
+
+    fn eq_options<N: Borrow<MathNode>>(&mut self, a: &Option<N>, b: &Option<N>) -> bool {
+        match (a, b) {
+            (Some(a), Some(b)) => self.eq(a.borrow(), b.borrow()),
+            (None, None) => true,
+            _ => false,
+        }
+    }
+
+    fn eq_clauses(&mut self, a: &FunctionClause, b: &FunctionClause) -> bool {
+        let a_binding: Vec<Identifier> = a.arguments.iter().flat_map(binders).collect();
+        let b_binding: Vec<Identifier> = b.arguments.iter().flat_map(binders).collect();
+        a.arguments.len() == b.arguments.len()
+            && self.within(&a_binding, &b_binding, |eq| {
+                a.arguments
+                    .iter()
+                    .zip(&b.arguments)
+                    .all(|(a_argument, b_argument)| eq.eq(a_argument, b_argument))
+                    && eq.eq_options(&a.condition, &b.condition)
+                    && eq.eq(&a.definition, &b.definition)
+            })
+    }
+
+    /// `f` with `a_binding` and `b_binding` bound to each other, which needs them to have the
+    /// same number of variables.
+    fn within(
+        &mut self,
+        a_binding: &[Identifier],
+        b_binding: &[Identifier],
+        f: impl FnOnce(&mut AlphaEq) -> bool,
+    ) -> bool {
+        if a_binding.len() != b_binding.len() {
+            return false;
+        }
+        let depth = self.bound.len();
+        self.bound
+            .extend(a_binding.iter().cloned().zip(b_binding.iter().cloned()));
+        let eq = f(self);
+        self.bound.truncate(depth);
+        eq
+    }
+}
+
+/// The variables a sum or product binds, as in `collect_free`, with the lower limit split into
+/// its parts when it has the form `i = 1`.
+fn index_binding<'a>(
+    variable: &'a Option<MathNode>,
+    lower_limit: &'a Option<Arc<MathNode>>,
+) -> (
+    Vec<Identifier>,
+    Option<(&'a MathNode, &'a MathNode, &'a MathNode)>,
+) {
+    let index = lower_limit
+        .as_ref()
+        .and_then(|limit| match limit.content.as_ref() {
+            MathNodeContent::Relationship { lhs, rhs, .. } if !binders(lhs).is_empty() => {
+                Some((limit.as_ref(), lhs.as_ref(), rhs.as_ref()))
+            }
+            _ => None,
+        });
+    let binding = match (variable, index) {
+        (Some(variable), _) if !binders(variable).is_empty() => binders(variable),
+        (_, Some((_, lhs, _))) => binders(lhs),
+        _ => vec![],
+    };
+    (binding, index)
+}
+
+/// The content with every child replaced by an empty node, and the name of a limit's variable
+/// dropped, for comparing a node's own data.
+fn shell(content: &MathNodeContent) -> MathNodeContent {
+    match content.map_children(|_| Some(MathNode::empty())) {
+        Some(MathNodeContent::Limit {
+            function,
+            approaching_value,
+            ..
+        }) => MathNodeContent::Limit {
+            function,
+            variable: String::new(),
+            approaching_value,
+        },
+        Some(shell) => shell,
+        None => content.clone(),
+    }
+}
@@ -0,0 +1,73 @@
+use super::*;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// Content a generation pipeline failed to produce, rendered as an inline error in its place so
+/// that the rest of the document can still be shown.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct GenerationErrorNode {
+    pub code: String,      // Machine-readable, e.g. "timeout" or "invalid_output"
+    pub message: String,   // Shown to the reader
+    pub generator: String, // The generator that failed, e.g. "proof-sketch"
+    pub fallback_content: Vec<SectionContentNode>, // Shown below the error, e.g. a partial result
+}
+
+impl GenerationErrorNode {
+    pub fn new(
+        code: impl Into<String>,
+        message: impl Into<String>,
+        generator: impl Into<String>,
+    ) -> Self {
+        GenerationErrorNode {
+            code: code.into(),
+            message: message.into(),
+            generator: generator.into(),
+            fallback_content: vec![],
+        }
+    }
+
+    /// An error for `generator` failing with `error`, using the error's message.
+    pub fn from_error(
+        code: impl Into<String>,
+        generator: impl Into<String>,
+        error: &dyn std::error::Error,
+    ) -> Self {
+        GenerationErrorNode::new(code, error.to_string(), generator)
+    }
+
+    pub fn with_fallback(mut self, fallback_content: Vec<SectionContentNode>) -> Self {
+        self.fallback_content = fallback_content;
+        self
+    }
+
+    /// A section `id` holding only this error, to stand in for a section that failed to generate.
+    pub fn into_section(self, id: impl Into<String>) -> Section {
+        Section {
+            id: id.into(),
+            title: None,
+            content: SectionContentNode::GenerationError(self),
+            metadata: vec![],
+            display_options: None,
+            difficulty: None,
+            variant_seed: None,
+        }
+    }
+}
+
+impl MathDocument {
+    /// Every generation error left in the document, embedded documents included, in document
+    /// order. Empty once the document is fully generated.
+    pub fn generation_errors(&self) -> Vec<&GenerationErrorNode> {
+        self.fold(vec![], |mut errors, node| {
+            if let FoldNode::ContentNode(SectionContentNode::GenerationError(error)) = node {
+                errors.push(error);
+            }
+            errors
+        })
+    }
+
+    pub fn is_partially_generated(&self) -> bool {
+        !self.generation_errors().is_empty()
+    }
+}
@@ -0,0 +1,50 @@
+use super::*;
+use std::sync::Arc;
+
+impl MathNode {
+    /// `value°`.
+    pub fn degrees(id: impl Into<String>, value: MathNode) -> MathNode {
+        MathNode {
+            id: id.into(),
+            content: Arc::new(MathNodeContent::AngleMeasure {
+                value: Arc::new(value),
+                unit: AngleUnit::Degrees,
+            }),
+        }
+    }
+
+    /// `∠ABC` from the identifiers of its vertices, the middle one being the angle's.
+    pub fn angle_at(id: impl Into<String>, vertices: &[&str]) -> MathNode {
+        let id = id.into();
+        MathNode {
+            content: Arc::new(MathNodeContent::AngleSymbol {
+                vertices: vertices
+                    .iter()
+                    .enumerate()
+                    .map(|(i, vertex)| MathNode {
+                        id: format!("{}-vertex-{}", id, i),
+                        content: Arc::new(MathNodeContent::Identifier(Identifier::new_simple(
+                            vertex.to_string(),
+                        ))),
+                    })
+                    .collect(),
+            }),
+            id,
+        }
+    }
+
+    /// `(components)`, or `point(components)` when the point is named.
+    pub fn coordinates(
+        id: impl Into<String>,
+        point: Option<MathNode>,
+        components: Vec<MathNode>,
+    ) -> MathNode {
+        MathNode {
+            id: id.into(),
+            content: Arc::new(MathNodeContent::Coordinates {
+                point: point.map(Arc::new),
+                components,
+            }),
+        }
+    }
+}
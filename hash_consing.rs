@@ -0,0 +1,308 @@
+use super::*;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+
+/// Key of the objects that stand for a shared subtree in `HashConsed`.
+pub const SUBTREE_REF_KEY: &str = "$subtree";
+
+/// Subtrees smaller than this many bytes of JSON are repeated rather than shared, as a
+/// reference would not be much shorter.
+pub const DEFAULT_MIN_SUBTREE_SIZE: usize = 64;
+
+/// A value's JSON with every subtree that occurs more than once stored once in `subtrees`, by
+/// hash, and replaced by `{ "$subtree": hash }` wherever it occurs, for payloads with highly
+/// repetitive generated math. Subtrees are only shared when they are equal including their
+/// ids, so `expand` gives back exactly the original value. Serialize it as JSON or MessagePack
+/// like any other payload.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HashConsed {
+    pub subtrees: BTreeMap<String, Value>,
+    pub root: Value,
+}
+
+impl HashConsed {
+    pub fn new(value: &impl Serialize) -> Result<HashConsed, serde_json::Error> {
+        HashConsed::with_min_subtree_size(value, DEFAULT_MIN_SUBTREE_SIZE)
+    }
+
+    pub fn with_min_subtree_size(
+        value: &impl Serialize,
+        min_size: usize,
+    ) -> Result<HashConsed, serde_json::Error> {
+        let value = serde_json::to_value(value)?;
+        let mut hashes = vec![];
+        hash_subtrees(&value, &mut hashes);
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for subtree in &hashes {
+            *counts.entry(subtree.hash.as_str()).or_default() += 1;
+        }
+
+        let mut consing = Consing {
+            hashes: &hashes,
+            counts: &counts,
+            min_size,
+            next: 0,
+            subtrees: BTreeMap::new(),
+        };
+        let root = consing.replace(&value);
+        let mut consed = HashConsed {
+            subtrees: consing.subtrees,
+            root,
+        };
+        consed.inline_single_references();
+        Ok(consed)
+    }
+
+    /// The original value, with every reference replaced by its subtree.
+    pub fn expand<T: DeserializeOwned>(&self) -> Result<T, HashConsError> {
+        let mut expanded = HashMap::new();
+        let value = self.expand_value(&self.root, &mut expanded, &mut HashSet::new())?;
+        serde_json::from_value(value).map_err(|error| HashConsError::Serde(error.to_string()))
+    }
+
+    fn expand_value(
+        &self,
+        value: &Value,
+        expanded: &mut HashMap<String, Value>,
+        expanding: &mut HashSet<String>,
+    ) -> Result<Value, HashConsError> {
+        if let Some(hash) = subtree_ref(value) {
+            if let Some(subtree) = expanded.get(hash) {
+                return Ok(subtree.clone());
+            }
+            let subtree = self
+                .subtrees
+                .get(hash)
+                .ok_or_else(|| HashConsError::UnknownSubtree {
+                    hash: hash.to_string(),
+                })?;
+            if !expanding.insert(hash.to_string()) {
+                return Err(HashConsError::Cycle {
+                    hash: hash.to_string(),
+                });
+            }
+            let subtree = self.expand_value(subtree, expanded, expanding)?;
+            expanding.remove(hash);
+            expanded.insert(hash.to_string(), subtree.clone());
+            return Ok(subtree);
+        }
+        Ok(match value {
+            Value::Array(items) => Value::Array(
+                items
+                    .iter()
+                    .map(|item| self.expand_value(item, expanded, expanding))
+                    .collect::<Result<_, _>>()?,
+            ),
+            Value::Object(fields) => Value::Object(
+                fields
+                    .iter()
+                    .map(|(key, field)| {
+                        Ok((key.clone(), self.expand_value(field, expanded, expanding)?))
+                    })
+                    .collect::<Result<_, HashConsError>>()?,
+            ),
+            value => value.clone(),
+        })
+    }
+
+    /// Subtrees that only occur inside one shared subtree are shared as well; this puts the
+    /// ones referenced only once back in place.
+    fn inline_single_references(&mut self) {
+        let mut references: HashMap<String, usize> = HashMap::new();
+        for value in self.subtrees.values().chain([&self.root]) {
+            count_references(value, &mut references);
+        }
+        let mut single: BTreeMap<String, Value> = BTreeMap::new();
+        self.subtrees.retain(|hash, subtree| {
+            let keep = references.get(hash).copied().unwrap_or_default() > 1;
+            if !keep {
+                single.insert(hash.clone(), std::mem::take(subtree));
+            }
+            keep
+        });
+        inline_references(&mut self.root, &mut single);
+        for subtree in self.subtrees.values_mut() {
+            inline_references(subtree, &mut single);
+        }
+    }
+}
+
+impl MathDocument {
+    /// See `HashConsed`.
+    pub fn to_hash_consed(&self) -> Result<HashConsed, serde_json::Error> {
+        HashConsed::new(self)
+    }
+
+    pub fn from_hash_consed(consed: &HashConsed) -> Result<MathDocument, HashConsError> {
+        consed.expand()
+    }
+}
+
+/// Hash and JSON size of a subtree.
+struct SubtreeHash {
+    hash: String,
+    size: usize,
+}
+
+/// Hashes every array and object in `value`, in pre-order. A container's hash covers its
+/// scalars and the hashes of its children, so each subtree is only serialized once.
+fn hash_subtrees(value: &Value, hashes: &mut Vec<SubtreeHash>) -> Option<usize> {
+    let index = hashes.len();
+    let (mut text, children): (String, Vec<(Option<&String>, &Value)>) = match value {
+        Value::Array(items) => ("[".to_string(), items.iter().map(|i| (None, i)).collect()),
+        Value::Object(fields) => {
+            let mut fields: Vec<_> = fields.iter().map(|(k, v)| (Some(k), v)).collect();
+            fields.sort_by_key(|(key, _)| *key);
+            ("{".to_string(), fields)
+        }
+        _ => return None,
+    };
+    hashes.push(SubtreeHash {
+        hash: String::new(),
+        size: 0,
+    });
+    let mut size = 2;
+    for (key, child) in children {
+        if let Some(key) = key {
+            text.push_str(&Value::String(key.clone()).to_string());
+            text.push(':');
+            size += key.len() + 3;
+        }
+        match hash_subtrees(child, hashes) {
+            Some(child_index) => {
+                // `#` starts no JSON value, so a hash cannot be mistaken for a scalar.
+                text.push('#');
+                text.push_str(&hashes[child_index].hash);
+                size += hashes[child_index].size;
+            }
+            None => {
+                let scalar = child.to_string();
+                size += scalar.len();
+                text.push_str(&scalar);
+            }
+        }
+        text.push(',');
+        size += 1;
+    }
+    hashes[index] = SubtreeHash {
+        hash: content_hash(&text).expect("a string always serializes"),
+        size,
+    };
+    Some(index)
+}
+
+struct Consing<'a> {
+    hashes: &'a [SubtreeHash],
+    counts: &'a HashMap<&'a str, usize>,
+    min_size: usize,
+    next: usize, // index in `hashes` of the next container, in pre-order
+    subtrees: BTreeMap<String, Value>,
+}
+
+impl Consing<'_> {
+    fn replace(&mut self, value: &Value) -> Value {
+        let index = self.next;
+        let replaced = match value {
+            Value::Array(items) => {
+                self.next += 1;
+                Value::Array(items.iter().map(|item| self.replace(item)).collect())
+            }
+            Value::Object(fields) => {
+                self.next += 1;
+                // Children were hashed in key order, so they are visited in it too.
+                let mut fields: Vec<_> = fields.iter().collect();
+                fields.sort_by_key(|(key, _)| *key);
+                Value::Object(
+                    fields
+                        .into_iter()
+                        .map(|(key, field)| (key.clone(), self.replace(field)))
+                        .collect(),
+                )
+            }
+            scalar => return scalar.clone(),
+        };
+        let subtree = &self.hashes[index];
+        if self.counts[subtree.hash.as_str()] < 2 || subtree.size < self.min_size {
+            return replaced;
+        }
+        self.subtrees
+            .entry(subtree.hash.clone())
+            .or_insert(replaced);
+        subtree_value(subtree.hash.clone())
+    }
+}
+
+fn subtree_value(hash: String) -> Value {
+    let mut reference = Map::new();
+    reference.insert(SUBTREE_REF_KEY.to_string(), Value::String(hash));
+    Value::Object(reference)
+}
+
+fn subtree_ref(value: &Value) -> Option<&str> {
+    match value {
+        Value::Object(fields) if fields.len() == 1 => fields.get(SUBTREE_REF_KEY)?.as_str(),
+        _ => None,
+    }
+}
+
+fn count_references(value: &Value, references: &mut HashMap<String, usize>) {
+    if let Some(hash) = subtree_ref(value) {
+        *references.entry(hash.to_string()).or_default() += 1;
+        return;
+    }
+    match value {
+        Value::Array(items) => items
+            .iter()
+            .for_each(|item| count_references(item, references)),
+        Value::Object(fields) => fields
+            .values()
+            .for_each(|field| count_references(field, references)),
+        _ => {}
+    }
+}
+
+/// Replaces the references to `subtrees`, each of which is referenced once, by the subtree.
+fn inline_references(value: &mut Value, subtrees: &mut BTreeMap<String, Value>) {
+    if let Some(subtree) = subtree_ref(value).and_then(|hash| subtrees.remove(hash)) {
+        *value = subtree;
+    }
+    match value {
+        Value::Array(items) => items
+            .iter_mut()
+            .for_each(|item| inline_references(item, subtrees)),
+        Value::Object(fields) => fields
+            .values_mut()
+            .for_each(|field| inline_references(field, subtrees)),
+        _ => {}
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum HashConsError {
+    UnknownSubtree {
+        hash: String,
+    },
+    /// A subtree contains a reference to itself.
+    Cycle {
+        hash: String,
+    },
+    /// The expanded value does not fit the type.
+    Serde(String),
+}
+
+impl fmt::Display for HashConsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HashConsError::UnknownSubtree { hash } => {
+                write!(f, "reference to unknown subtree `{}`", hash)
+            }
+            HashConsError::Cycle { hash } => write!(f, "subtree `{}` contains itself", hash),
+            HashConsError::Serde(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for HashConsError {}
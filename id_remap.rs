@@ -0,0 +1,308 @@
+use super::*;
+use std::{collections::BTreeMap, sync::Arc};
+
+impl MathDocument {
+    /// Renames ids through `mapping`, wherever they are defined (documents, sections, panels,
+    /// math nodes, annotations, controls, ...) and wherever they are referenced (links, footnote
+    /// references, embedded section refs, cross references, ToC entries, animation and
+    /// interaction targets, correspondence groups, ...). Ids missing from `mapping` are kept.
+    ///
+    /// Targets given as selectors are renamed when the selector is exactly an id or `#id`; other
+    /// CSS selectors are left alone. Returns the number of occurrences renamed.
+    pub fn remap_ids(&mut self, mapping: &BTreeMap<String, String>) -> usize {
+        let mut remapper = IdRemapper { mapping, count: 0 };
+        remapper.visit_document_mut(self);
+        remapper.count
+    }
+
+    /// Prefixes every id defined in the document with `prefix`, updating the references to them,
+    /// so that the content can be transplanted into another document without id clashes.
+    /// References to ids defined elsewhere are kept. Returns the mapping that was applied.
+    pub fn prefix_ids(&mut self, prefix: &str) -> BTreeMap<String, String> {
+        let mapping: BTreeMap<String, String> = self
+            .defined_ids()
+            .into_iter()
+            .map(|id| (id.to_string(), format!("{}{}", prefix, id)))
+            .collect();
+        self.remap_ids(&mapping);
+        mapping
+    }
+
+    /// The non-empty ids defined in the document, nested documents included, in document order
+    /// and without duplicates.
+    pub fn defined_ids(&self) -> Vec<&str> {
+        let mut ids = vec![];
+        document_defined_ids(self, &mut ids);
+        self.fold(ids, |mut ids, node| {
+            match node {
+                FoldNode::Section(section) => ids.push(&section.id),
+                FoldNode::Panel(panel) => ids.push(&panel.id),
+                FoldNode::MathNode(node) => ids.push(&node.id),
+                FoldNode::ContentNode(node) => content_defined_ids(node, &mut ids),
+            }
+            ids
+        })
+        .into_iter()
+        .filter(|id| !id.is_empty())
+        .fold(vec![], |mut unique, id| {
+            if !unique.contains(&id) {
+                unique.push(id);
+            }
+            unique
+        })
+    }
+}
+
+fn document_defined_ids<'doc>(document: &'doc MathDocument, ids: &mut Vec<&'doc str>) {
+    ids.push(&document.id);
+    let interaction_system = match &document.content_type {
+        MathDocumentType::AnimatedPresentation(c) => Some(&c.interaction_system),
+        MathDocumentType::InteractivePlayground(c) => Some(&c.interaction_system),
+        _ => None,
+    };
+    if let Some(system) = interaction_system {
+        ids.extend(system.controls.iter().map(|control| control.id.as_str()));
+    }
+}
+
+fn content_defined_ids<'doc>(node: &'doc SectionContentNode, ids: &mut Vec<&'doc str>) {
+    match node {
+        SectionContentNode::SideBySideLayout(layout) => {
+            ids.extend(layout.correspondence_groups.iter().map(|g| g.id.as_str()))
+        }
+        SectionContentNode::AnnotationOverlay(overlay) => {
+            ids.extend(overlay.annotations.iter().map(|a| a.id.as_str()))
+        }
+        SectionContentNode::InteractiveControls(controls) => {
+            ids.extend(controls.controls.iter().map(|c| c.id.as_str()))
+        }
+        SectionContentNode::Feedback(feedback) => ids.push(&feedback.id),
+        SectionContentNode::DerivationChain(chain) => ids.push(&chain.id),
+        SectionContentNode::Equation(equation) => ids.push(&equation.id),
+        SectionContentNode::BranchingContainer(container) => {
+            ids.push(&container.container_id);
+            ids.extend(container.nodes.iter().map(|n| n.node_id.as_str()));
+        }
+        SectionContentNode::EmbeddedDocument(document) => {
+            if let Ok(document) = document.get() {
+                document_defined_ids(document, ids)
+            }
+        }
+        _ => {}
+    }
+}
+
+struct IdRemapper<'a> {
+    mapping: &'a BTreeMap<String, String>,
+    count: usize,
+}
+
+impl IdRemapper<'_> {
+    fn id(&mut self, id: &mut String) {
+        if let Some(renamed) = self.mapping.get(id.as_str()) {
+            *id = renamed.clone();
+            self.count += 1;
+        }
+    }
+
+    fn ids(&mut self, ids: &mut [String]) {
+        for id in ids {
+            self.id(id);
+        }
+    }
+
+    /// Renames a target given as an element id or an `#id` selector.
+    fn selector(&mut self, selector: &mut String) {
+        match selector.strip_prefix('#') {
+            Some(id) => {
+                if let Some(renamed) = self.mapping.get(id) {
+                    *selector = format!("#{}", renamed);
+                    self.count += 1;
+                }
+            }
+            None => self.id(selector),
+        }
+    }
+
+    fn selectors(&mut self, selectors: &mut [String]) {
+        for selector in selectors {
+            self.selector(selector);
+        }
+    }
+
+    fn toc(&mut self, toc: &mut TocNode) {
+        self.id(&mut toc.target_id);
+        for child in &mut toc.children {
+            self.toc(child);
+        }
+    }
+
+    fn timeline(&mut self, timeline: &mut AnimationTimeline) {
+        for keyframe in &mut timeline.keyframes {
+            self.selectors(&mut keyframe.target_elements);
+        }
+        for point in &mut timeline.interaction_points {
+            self.selector(&mut point.target_element);
+        }
+        for group in &mut timeline.synchronization_groups {
+            self.selectors(&mut group.elements);
+        }
+    }
+
+    fn interaction_system(&mut self, system: &mut InteractionSystem) {
+        for control in &mut system.controls {
+            self.id(&mut control.id);
+        }
+        for handler in &mut system.event_handlers {
+            self.selector(&mut handler.target_selector);
+            if let InteractionAction::HighlightElement { element_id } = &mut handler.action {
+                self.id(element_id);
+            }
+        }
+    }
+}
+
+impl DocumentVisitorMut for IdRemapper<'_> {
+    fn visit_document_mut(&mut self, document: &mut MathDocument) {
+        self.id(&mut document.id);
+        let content_type = &mut document.content_type;
+        if let Some(relationships) = content_type.relationships_mut() {
+            for reference in &mut relationships.cross_references {
+                self.id(&mut reference.target_id);
+            }
+            if let Some(graph) = &mut relationships.dependency_graph {
+                for node in &mut graph.nodes {
+                    self.id(&mut node.content_id);
+                }
+            }
+        }
+        if let Some(toc) = content_type
+            .structure_mut()
+            .and_then(|structure| structure.table_of_contents.as_mut())
+        {
+            self.toc(toc);
+        }
+        match content_type {
+            MathDocumentType::AnimatedPresentation(c) => {
+                self.timeline(&mut c.animation_timeline);
+                for point in &mut c.interaction_points {
+                    self.selector(&mut point.target_element);
+                }
+                self.interaction_system(&mut c.interaction_system);
+                for binding in &mut c.control_bindings {
+                    self.id(&mut binding.control_id);
+                }
+            }
+            MathDocumentType::InteractivePlayground(c) => {
+                if let Some(timeline) = &mut c.animation_timeline {
+                    self.timeline(timeline);
+                }
+                self.interaction_system(&mut c.interaction_system);
+                for binding in &mut c.control_bindings {
+                    self.id(&mut binding.control_id);
+                }
+            }
+            _ => {}
+        }
+        walk_document_mut(self, document);
+    }
+
+    fn visit_section_mut(&mut self, section: &mut Section) {
+        self.id(&mut section.id);
+        walk_section_mut(self, section);
+    }
+
+    fn visit_panel_mut(&mut self, panel: &mut Panel) {
+        self.id(&mut panel.id);
+        walk_panel_mut(self, panel);
+    }
+
+    fn visit_content_node_mut(&mut self, node: &mut SectionContentNode) {
+        match node {
+            SectionContentNode::EmbeddedSectionRef(id) => self.id(id),
+            SectionContentNode::SideBySideLayout(layout) => {
+                for group in &mut layout.correspondence_groups {
+                    self.id(&mut group.id);
+                    self.ids(&mut group.left_ids);
+                    self.ids(&mut group.right_ids);
+                }
+            }
+            SectionContentNode::PanelLayout(layout) => {
+                if let PanelLayoutType::Sidebar { main_panel_id } = &mut layout.layout_type {
+                    self.id(main_panel_id);
+                }
+            }
+            SectionContentNode::AnnotationOverlay(overlay) => {
+                for annotation in &mut overlay.annotations {
+                    self.id(&mut annotation.id);
+                    self.selector(&mut annotation.target_selector);
+                }
+            }
+            SectionContentNode::InteractiveControls(controls) => {
+                for control in &mut controls.controls {
+                    self.id(&mut control.id);
+                }
+                self.ids(&mut controls.target_content_ids);
+            }
+            SectionContentNode::Feedback(feedback) => {
+                self.id(&mut feedback.id);
+                if let Some(target_id) = &mut feedback.target_id {
+                    self.id(target_id);
+                }
+            }
+            SectionContentNode::DerivationChain(chain) => self.id(&mut chain.id),
+            SectionContentNode::Equation(equation) => self.id(&mut equation.id),
+            SectionContentNode::BranchingContainer(container) => {
+                self.id(&mut container.container_id);
+                for node in &mut container.nodes {
+                    self.id(&mut node.node_id);
+                    if let Some(parent_id) = &mut node.parent_id {
+                        self.id(parent_id);
+                    }
+                    self.ids(&mut node.children);
+                }
+            }
+            _ => {}
+        }
+        walk_content_node_mut(self, node);
+    }
+
+    fn visit_segment_mut(&mut self, segment: &mut RichTextSegment) {
+        match segment {
+            RichTextSegment::FootnoteReference(id) => self.id(id),
+            RichTextSegment::Link { target, .. } => match target {
+                LinkTarget::InternalPageId(id)
+                | LinkTarget::TheoremId(id)
+                | LinkTarget::InteractiveElementId(id)
+                | LinkTarget::Equation(id) => self.id(id),
+                LinkTarget::DefinitionId { term_id, .. }
+                | LinkTarget::DefinitionAspect { term_id, .. } => self.id(term_id),
+                LinkTarget::ObjectConstructorTemplate { template_id, .. } => self.id(template_id),
+                LinkTarget::AnimationTrigger { animation_id, .. } => self.id(animation_id),
+                LinkTarget::Url(_)
+                | LinkTarget::GlossaryTerm(_)
+                | LinkTarget::BibliographyKey(_)
+                | LinkTarget::TooltipDocument(_) => {}
+            },
+            _ => {}
+        }
+        walk_segment_mut(self, segment);
+    }
+
+    fn visit_math_node_mut(&mut self, node: &mut MathNode) {
+        let mapping = self.mapping;
+        let mut count = 0;
+        let renamed = node.transform(&mut |node: &MathNode| {
+            let id = mapping.get(&node.id)?;
+            count += 1;
+            Some(MathNode {
+                id: id.clone(),
+                content: Arc::clone(&node.content),
+            })
+        });
+        if count > 0 {
+            *node = renamed;
+            self.count += count;
+        }
+    }
+}
@@ -0,0 +1,132 @@
+// `#[derive(ToTurnMath)]` and `#[derive(ToSectionNode)]` from the `turn-render-derive` crate,
+// which documents the `#[turn_math(...)]` notations. They share their names with the traits, so
+// importing one brings in both, as with serde's derives.
+pub use turn_render_derive::{ToSectionNode, ToTurnMath};
+
+#[cfg(test)]
+mod tests {
+    use super::super::*;
+    use std::sync::Arc;
+
+    /// A leaf written as the identifier `name`.
+    struct Var(&'static str);
+
+    impl ToTurnMath for Var {
+        fn to_turn_math(&self, master_id: String) -> MathNode {
+            identifier(&master_id, Identifier::new_simple(self.0.to_string()))
+        }
+    }
+
+    fn identifier(id: &str, identifier: Identifier) -> MathNode {
+        MathNode {
+            id: id.to_string(),
+            content: Arc::new(MathNodeContent::Identifier(identifier)),
+        }
+    }
+
+    fn var(id: &str, name: &'static str) -> MathNode {
+        Var(name).to_turn_math(id.to_string())
+    }
+
+    #[derive(ToTurnMath)]
+    struct Degree(Var);
+
+    #[derive(ToTurnMath)]
+    #[turn_math(symbol = "1")]
+    struct TrivialGroup;
+
+    #[derive(ToTurnMath)]
+    #[turn_math(symbol = "C")]
+    struct CyclicGroup {
+        order: Var,
+        #[turn_math(skip)]
+        _generator: Option<String>,
+    }
+
+    #[derive(ToTurnMath, ToSectionNode)]
+    #[turn_math(function = "Hom", title = "Morphisms")]
+    struct Hom {
+        #[turn_math(subscript)]
+        category: Var,
+        source: Var,
+        target: Var,
+    }
+
+    #[derive(ToTurnMath)]
+    #[turn_math(infix = "∘")]
+    struct Composition(Var, Var);
+
+    #[test]
+    fn single_fields_delegate() {
+        assert_eq!(Degree(Var("d")).to_turn_math("n".into()), var("n", "d"));
+    }
+
+    #[test]
+    fn symbols_take_their_fields_as_subscripts() {
+        assert_eq!(
+            TrivialGroup.to_turn_math("g".into()),
+            identifier("g", Identifier::new_simple("1".to_string()))
+        );
+        let group = CyclicGroup {
+            order: Var("n"),
+            _generator: None,
+        };
+        let expected = Identifier {
+            post_script: Some(ScriptNode {
+                subscripts: vec![var("g-order", "n")],
+                superscripts: vec![],
+            }),
+            ..Identifier::new_simple("C".to_string())
+        };
+        assert_eq!(group.to_turn_math("g".into()), identifier("g", expected));
+    }
+
+    #[test]
+    fn functions_take_their_fields_as_arguments() {
+        let hom = Hom {
+            category: Var("C"),
+            source: Var("a"),
+            target: Var("b"),
+        };
+        let name = Identifier {
+            post_script: Some(ScriptNode {
+                subscripts: vec![var("h-category", "C")],
+                superscripts: vec![],
+            }),
+            is_function: true,
+            ..Identifier::new_simple("Hom".to_string())
+        };
+        let expected = MathNode {
+            id: "h".to_string(),
+            content: Arc::new(MathNodeContent::FunctionCall {
+                name: Arc::new(identifier("h-name", name)),
+                parameters: vec![var("h-source", "a"), var("h-target", "b")],
+            }),
+        };
+        assert_eq!(hom.to_turn_math("h".into()), expected);
+
+        let section = hom.to_section_node("p");
+        assert_eq!(section.id, "p-section");
+        assert_eq!(
+            serde_json::to_value(&section.title).unwrap(),
+            serde_json::to_value(RichText::text("Morphisms".to_string())).unwrap()
+        );
+        assert!(matches!(&section.content, SectionContentNode::Math(math) if math.id == "p-math"));
+    }
+
+    #[test]
+    fn infix_joins_two_fields() {
+        let operator = || BinaryOperator::Custom("∘".to_string());
+        let expected = MathNode {
+            id: "c".to_string(),
+            content: Arc::new(MathNodeContent::BinaryOperation {
+                operation_type: BinaryOperationType::Custom("∘".to_string()),
+                terms: vec![(operator(), var("c-0", "f")), (operator(), var("c-1", "g"))],
+            }),
+        };
+        assert_eq!(
+            Composition(Var("f"), Var("g")).to_turn_math("c".into()),
+            expected
+        );
+    }
+}
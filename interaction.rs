@@ -0,0 +1,192 @@
+use super::*;
+use std::fmt;
+
+impl MathDocumentType {
+    /// The interaction system of the interactive document types.
+    pub fn interaction_system(&self) -> Option<&InteractionSystem> {
+        match self {
+            MathDocumentType::AnimatedPresentation(c) => Some(&c.interaction_system),
+            MathDocumentType::InteractivePlayground(c) => Some(&c.interaction_system),
+            MathDocumentType::TypeMappingDisplay(c) => Some(&c.interaction_system),
+            MathDocumentType::ResourcePanel(c) => Some(&c.interaction_system),
+            _ => None,
+        }
+    }
+
+    pub fn control_bindings(&self) -> &[ControlBinding] {
+        match self {
+            MathDocumentType::AnimatedPresentation(c) => &c.control_bindings,
+            MathDocumentType::InteractivePlayground(c) => &c.control_bindings,
+            MathDocumentType::TypeMappingDisplay(c) => &c.control_bindings,
+            MathDocumentType::ResourcePanel(c) => &c.control_bindings,
+            _ => &[],
+        }
+    }
+}
+
+impl InteractionSystem {
+    /// Checks the system against the page it is part of:
+    /// - every binding's control is one of `controls` or an element of the page, and its target
+    ///   variable is one of `state_variables`
+    /// - event handler targets given as an element id or `#id` selector, and highlighted
+    ///   elements, are elements of the page; other CSS selectors are not checked
+    /// - slide navigation stays below `slide_count`, which is 0 outside presentations
+    pub fn validate(
+        &self,
+        bindings: &[ControlBinding],
+        page_ids: &[&str],
+        slide_count: usize,
+    ) -> Vec<InteractionError> {
+        let mut errors = vec![];
+        for binding in bindings {
+            let is_control = self.controls.iter().any(|c| c.id == binding.control_id);
+            if !is_control && !page_ids.contains(&binding.control_id.as_str()) {
+                errors.push(InteractionError::UnknownControl {
+                    control_id: binding.control_id.clone(),
+                });
+            }
+            let variables = &self.state_variables;
+            if !variables.iter().any(|v| v.name == binding.target_variable) {
+                errors.push(InteractionError::UnknownStateVariable {
+                    control_id: binding.control_id.clone(),
+                    variable: binding.target_variable.clone(),
+                });
+            }
+        }
+        for handler in &self.event_handlers {
+            if let Some(id) = selector_id(&handler.target_selector)
+                && !page_ids.contains(&id)
+            {
+                errors.push(InteractionError::UnknownTarget {
+                    selector: handler.target_selector.clone(),
+                });
+            }
+            match &handler.action {
+                InteractionAction::HighlightElement { element_id }
+                    if !page_ids.contains(&element_id.as_str()) =>
+                {
+                    errors.push(InteractionError::UnknownElement {
+                        element_id: element_id.clone(),
+                    })
+                }
+                InteractionAction::NavigateToSlide { slide_index }
+                    if *slide_index >= slide_count =>
+                {
+                    errors.push(InteractionError::SlideOutOfRange {
+                        slide_index: *slide_index,
+                        slide_count,
+                    })
+                }
+                _ => {}
+            }
+        }
+        errors
+    }
+}
+
+impl MathDocument {
+    /// `InteractionSystem::validate` for the document and the interactive documents embedded in
+    /// it, against the ids defined anywhere on the page.
+    pub fn validate_interactions(&self) -> Vec<ValidationError> {
+        let mut validator = InteractionValidator {
+            page_ids: self.defined_ids(),
+            errors: vec![],
+        };
+        validator.visit_document(self);
+        validator.errors
+    }
+}
+
+struct InteractionValidator<'doc> {
+    page_ids: Vec<&'doc str>,
+    errors: Vec<ValidationError>,
+}
+
+impl<'doc> DocumentVisitor<'doc> for InteractionValidator<'doc> {
+    fn visit_document(&mut self, document: &'doc MathDocument) {
+        let content_type = &document.content_type;
+        if let Some(system) = content_type.interaction_system() {
+            let slide_count = match content_type {
+                MathDocumentType::AnimatedPresentation(c) => c.slide_count,
+                _ => 0,
+            };
+            let bindings = content_type.control_bindings();
+            for error in system.validate(bindings, &self.page_ids, slide_count) {
+                self.errors.push(ValidationError::InvalidInteraction {
+                    document_id: document.id.clone(),
+                    error,
+                });
+            }
+        }
+        walk_document(self, document);
+    }
+}
+
+/// The element id a selector names, for `#id` and bare id selectors.
+fn selector_id(selector: &str) -> Option<&str> {
+    let id = selector.strip_prefix('#').unwrap_or(selector);
+    let is_id = !id.is_empty()
+        && id
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_');
+    is_id.then_some(id)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum InteractionError {
+    UnknownControl {
+        control_id: String,
+    },
+    UnknownStateVariable {
+        control_id: String,
+        variable: String,
+    },
+    UnknownTarget {
+        selector: String,
+    },
+    UnknownElement {
+        element_id: String,
+    },
+    SlideOutOfRange {
+        slide_index: usize,
+        slide_count: usize,
+    },
+}
+
+impl fmt::Display for InteractionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InteractionError::UnknownControl { control_id } => {
+                write!(f, "binding refers to unknown control `{}`", control_id)
+            }
+            InteractionError::UnknownStateVariable {
+                control_id,
+                variable,
+            } => write!(
+                f,
+                "control `{}` is bound to unknown state variable `{}`",
+                control_id, variable
+            ),
+            InteractionError::UnknownTarget { selector } => {
+                write!(f, "event handler targets unknown element `{}`", selector)
+            }
+            InteractionError::UnknownElement { element_id } => {
+                write!(
+                    f,
+                    "event handler highlights unknown element `{}`",
+                    element_id
+                )
+            }
+            InteractionError::SlideOutOfRange {
+                slide_index,
+                slide_count,
+            } => write!(
+                f,
+                "event handler navigates to slide {}, but there are only {} slides",
+                slide_index, slide_count
+            ),
+        }
+    }
+}
+
+impl std::error::Error for InteractionError {}
@@ -0,0 +1,88 @@
+use super::*;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// Pool of node contents shared behind `Arc`s, so that a symbol written thousands of times
+/// is stored once. Contents are compared whole, so besides leaves like identifiers, repeated
+/// subtrees are shared too as long as their node ids agree; node ids themselves are never
+/// merged. Keep one pool for everything built from the same source, e.g. a whole document.
+#[derive(Debug, Clone, Default)]
+pub struct MathInterner {
+    contents: HashSet<Arc<MathNodeContent>>,
+}
+
+impl MathInterner {
+    pub fn new() -> Self {
+        MathInterner::default()
+    }
+
+    /// The pooled copy of `content`, adding it if it is new. Children are taken as they are;
+    /// use `intern` for whole trees.
+    pub fn content(&mut self, content: MathNodeContent) -> Arc<MathNodeContent> {
+        if let Some(shared) = self.contents.get(&content) {
+            return Arc::clone(shared);
+        }
+        let shared = Arc::new(content);
+        self.contents.insert(Arc::clone(&shared));
+        shared
+    }
+
+    /// A node with pooled content, for building trees.
+    pub fn node(&mut self, id: impl Into<String>, content: MathNodeContent) -> MathNode {
+        MathNode {
+            id: id.into(),
+            content: self.content(content),
+        }
+    }
+
+    pub fn identifier(&mut self, id: impl Into<String>, identifier: Identifier) -> MathNode {
+        self.node(id, MathNodeContent::Identifier(identifier))
+    }
+
+    /// The tree with its contents pooled from the leaves up, sharing every one that is
+    /// already in the pool.
+    pub fn intern(&mut self, node: &MathNode) -> MathNode {
+        node.transform(self)
+    }
+
+    /// Pools the contents of every expression in the document.
+    pub fn intern_document(&mut self, document: &mut MathDocument) {
+        document.walk_mut(&mut DocumentInterner { interner: self });
+    }
+
+    /// The number of distinct contents in the pool.
+    pub fn len(&self) -> usize {
+        self.contents.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.contents.is_empty()
+    }
+}
+
+impl MathNodeTransformer for MathInterner {
+    fn transform_node(&mut self, node: &MathNode) -> Option<MathNode> {
+        let shared = match self.contents.get(&node.content) {
+            Some(shared) if Arc::ptr_eq(shared, &node.content) => return None,
+            Some(shared) => Arc::clone(shared),
+            None => {
+                self.contents.insert(Arc::clone(&node.content));
+                return None;
+            }
+        };
+        Some(MathNode {
+            id: node.id.clone(),
+            content: shared,
+        })
+    }
+}
+
+struct DocumentInterner<'a> {
+    interner: &'a mut MathInterner,
+}
+
+impl DocumentVisitorMut for DocumentInterner<'_> {
+    fn visit_math_node_mut(&mut self, node: &mut MathNode) {
+        *node = self.interner.intern(node);
+    }
+}
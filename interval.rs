@@ -0,0 +1,401 @@
+use super::*;
+use std::{
+    collections::BTreeMap,
+    f64::consts::{FRAC_PI_2, PI},
+    fmt,
+    ops::{Add, Mul, Neg, Sub},
+};
+
+/// A closed interval of reals `[lower, upper]`, possibly unbounded, for bounding the values of
+/// an expression over ranges of its parameters.
+///
+/// Operations round outwards by a unit in the last place wherever floating-point rounding could
+/// make the result too narrow, so the bounds are guaranteed: every value the expression can take
+/// lies within them. They need not be tight, e.g. `x - x` over `[0, 1]` gives `[-1, 1]`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Interval {
+    pub lower: f64,
+    pub upper: f64,
+}
+
+impl Interval {
+    pub const ENTIRE: Interval = Interval {
+        lower: f64::NEG_INFINITY,
+        upper: f64::INFINITY,
+    };
+
+    /// `None` when `lower > upper` or either bound is NaN.
+    pub fn new(lower: f64, upper: f64) -> Option<Interval> {
+        (lower <= upper).then_some(Interval { lower, upper })
+    }
+
+    pub fn point(value: f64) -> Interval {
+        Interval {
+            lower: value,
+            upper: value,
+        }
+    }
+
+    pub fn contains(self, value: f64) -> bool {
+        self.lower <= value && value <= self.upper
+    }
+
+    pub fn width(self) -> f64 {
+        self.upper - self.lower
+    }
+
+    /// The smallest interval containing both.
+    pub fn hull(self, other: Interval) -> Interval {
+        Interval {
+            lower: self.lower.min(other.lower),
+            upper: self.upper.max(other.upper),
+        }
+    }
+
+    /// The common part of both, `None` if they are disjoint.
+    pub fn intersection(self, other: Interval) -> Option<Interval> {
+        Interval::new(self.lower.max(other.lower), self.upper.min(other.upper))
+    }
+
+    /// Widened by a unit in the last place on both sides, to cover a rounding error.
+    pub fn widened(self) -> Interval {
+        Interval {
+            lower: self.lower.next_down(),
+            upper: self.upper.next_up(),
+        }
+    }
+
+    /// `None` when dividing by `[0, 0]`; everything when `other` contains zero otherwise.
+    pub fn checked_div(self, other: Interval) -> Option<Interval> {
+        if other == Interval::point(0.0) {
+            return None;
+        }
+        if other.contains(0.0) {
+            return Some(Interval::ENTIRE);
+        }
+        let reciprocal = Interval {
+            lower: 1.0 / other.upper,
+            upper: 1.0 / other.lower,
+        };
+        Some(self * reciprocal.widened())
+    }
+
+    /// `self` to the power `exponent`, `None` for zero to a negative power.
+    pub fn powi(self, exponent: i32) -> Option<Interval> {
+        if exponent < 0 {
+            return match exponent.checked_neg() {
+                Some(exponent) => Interval::point(1.0).checked_div(self.powi(exponent)?),
+                // `i32::MIN` has no positive counterpart, but it is even.
+                None => self.powi(exponent / 2)?.powi(2),
+            };
+        }
+        let (lower, upper) = if exponent % 2 == 1 || self.lower >= 0.0 {
+            (self.lower, self.upper)
+        } else if self.upper <= 0.0 {
+            (-self.upper, -self.lower)
+        } else {
+            (0.0, self.upper.max(-self.lower))
+        };
+        let power = Interval {
+            lower: lower.powi(exponent),
+            upper: upper.powi(exponent),
+        };
+        Some(if exponent <= 1 {
+            power
+        } else {
+            power.widened()
+        })
+    }
+
+    /// `self` to the power `exponent` for a positive base, as `exp(exponent·ln(self))`.
+    pub fn powf(self, exponent: Interval) -> Option<Interval> {
+        Some((exponent * self.ln()?).exp())
+    }
+
+    pub fn abs(self) -> Interval {
+        if self.lower >= 0.0 {
+            self
+        } else if self.upper <= 0.0 {
+            -self
+        } else {
+            Interval {
+                lower: 0.0,
+                upper: self.upper.max(-self.lower),
+            }
+        }
+    }
+
+    pub fn min(self, other: Interval) -> Interval {
+        Interval {
+            lower: self.lower.min(other.lower),
+            upper: self.upper.min(other.upper),
+        }
+    }
+
+    pub fn max(self, other: Interval) -> Interval {
+        Interval {
+            lower: self.lower.max(other.lower),
+            upper: self.upper.max(other.upper),
+        }
+    }
+
+    /// `None` when the interval is entirely negative.
+    pub fn sqrt(self) -> Option<Interval> {
+        self.monotone(f64::sqrt, 0.0, f64::INFINITY)
+    }
+
+    /// The real `n`th root, negative for an odd root of a negative number, as
+    /// `exp(ln(self)/n)`. `None` for a zeroth root and for an even root of an entirely negative
+    /// interval.
+    pub fn root(self, n: u32) -> Option<Interval> {
+        if n == 2 {
+            return self.sqrt();
+        }
+        let exponent = Interval::point(1.0).checked_div(Interval::point(n as f64))?;
+        let nonnegative = Interval {
+            lower: 0.0,
+            upper: f64::INFINITY,
+        };
+        let positive = self
+            .intersection(nonnegative)
+            .and_then(|part| part.powf(exponent));
+        if n.is_multiple_of(2) {
+            return positive;
+        }
+        let negative = (-self)
+            .intersection(nonnegative)
+            .and_then(|part| part.powf(exponent))
+            .map(Neg::neg);
+        match (positive, negative) {
+            (Some(positive), Some(negative)) => Some(positive.hull(negative)),
+            (positive, negative) => positive.or(negative),
+        }
+    }
+
+    pub fn exp(self) -> Interval {
+        increasing(self, f64::exp)
+    }
+
+    /// `None` when the interval has no positive part.
+    pub fn ln(self) -> Option<Interval> {
+        self.monotone(f64::ln, 0.0, f64::INFINITY)
+    }
+
+    pub fn log10(self) -> Option<Interval> {
+        self.monotone(f64::log10, 0.0, f64::INFINITY)
+    }
+
+    pub fn sin(self) -> Interval {
+        // sin has its maxima at π/2 + 2kπ and its minima at -π/2 + 2kπ
+        let bounds = increasing_hull(self, f64::sin);
+        Interval {
+            lower: if self.reaches(-FRAC_PI_2, 2.0 * PI) {
+                -1.0
+            } else {
+                bounds.lower
+            },
+            upper: if self.reaches(FRAC_PI_2, 2.0 * PI) {
+                1.0
+            } else {
+                bounds.upper
+            },
+        }
+    }
+
+    pub fn cos(self) -> Interval {
+        let bounds = increasing_hull(self, f64::cos);
+        Interval {
+            lower: if self.reaches(PI, 2.0 * PI) {
+                -1.0
+            } else {
+                bounds.lower
+            },
+            upper: if self.reaches(0.0, 2.0 * PI) {
+                1.0
+            } else {
+                bounds.upper
+            },
+        }
+    }
+
+    /// Everything when the interval contains a pole.
+    pub fn tan(self) -> Interval {
+        if self.reaches(FRAC_PI_2, PI) {
+            return Interval::ENTIRE;
+        }
+        increasing(self, f64::tan)
+    }
+
+    /// `None` when the interval misses `[-1, 1]`.
+    pub fn asin(self) -> Option<Interval> {
+        self.monotone(f64::asin, -1.0, 1.0)
+    }
+
+    pub fn acos(self) -> Option<Interval> {
+        let domain = self.intersection(Interval {
+            lower: -1.0,
+            upper: 1.0,
+        })?;
+        Some(decreasing(domain, f64::acos))
+    }
+
+    pub fn atan(self) -> Interval {
+        increasing(self, f64::atan)
+    }
+
+    pub fn sinh(self) -> Interval {
+        increasing(self, f64::sinh)
+    }
+
+    pub fn cosh(self) -> Interval {
+        // even, increasing in |x|
+        increasing(self.abs(), f64::cosh)
+    }
+
+    pub fn tanh(self) -> Interval {
+        increasing(self, f64::tanh)
+    }
+
+    /// `function`, increasing on `[lower, upper]`, applied to the part of the interval within
+    /// it.
+    fn monotone(self, function: fn(f64) -> f64, lower: f64, upper: f64) -> Option<Interval> {
+        let domain = self.intersection(Interval { lower, upper })?;
+        Some(increasing(domain, function))
+    }
+
+    /// Whether the interval contains a point `offset + k·period` for some integer `k`.
+    fn reaches(self, offset: f64, period: f64) -> bool {
+        // Far from zero the points cannot be located to a unit in the last place.
+        const MAX_LOCATED: f64 = 1e9;
+        if self.width() >= period || self.lower.abs().max(self.upper.abs()) > MAX_LOCATED {
+            return true;
+        }
+        // Widened so that rounding in the division cannot miss a point at the edge.
+        let k = ((self.lower - offset) / period).floor();
+        [k, k + 1.0]
+            .into_iter()
+            .any(|k| self.widened().contains(offset + k * period))
+    }
+}
+
+/// An increasing `function` applied to both bounds, rounded outwards.
+fn increasing(interval: Interval, function: fn(f64) -> f64) -> Interval {
+    Interval {
+        lower: function(interval.lower),
+        upper: function(interval.upper),
+    }
+    .widened()
+}
+
+fn decreasing(interval: Interval, function: fn(f64) -> f64) -> Interval {
+    Interval {
+        lower: function(interval.upper),
+        upper: function(interval.lower),
+    }
+    .widened()
+}
+
+/// The values of `function` at both bounds, in order and rounded outwards.
+fn increasing_hull(interval: Interval, function: fn(f64) -> f64) -> Interval {
+    let (a, b) = (function(interval.lower), function(interval.upper));
+    Interval {
+        lower: a.min(b),
+        upper: a.max(b),
+    }
+    .widened()
+}
+
+impl Add for Interval {
+    type Output = Interval;
+
+    fn add(self, other: Interval) -> Interval {
+        Interval {
+            lower: self.lower + other.lower,
+            upper: self.upper + other.upper,
+        }
+        .widened()
+    }
+}
+
+impl Sub for Interval {
+    type Output = Interval;
+
+    fn sub(self, other: Interval) -> Interval {
+        self + -other
+    }
+}
+
+impl Neg for Interval {
+    type Output = Interval;
+
+    fn neg(self) -> Interval {
+        Interval {
+            lower: -self.upper,
+            upper: -self.lower,
+        }
+    }
+}
+
+impl Mul for Interval {
+    type Output = Interval;
+
+    fn mul(self, other: Interval) -> Interval {
+        // 0·∞ is 0 here: a zero factor is exactly zero, whatever the other one
+        let product = |a: f64, b: f64| if a == 0.0 || b == 0.0 { 0.0 } else { a * b };
+        let products = [
+            product(self.lower, other.lower),
+            product(self.lower, other.upper),
+            product(self.upper, other.lower),
+            product(self.upper, other.upper),
+        ];
+        Interval {
+            lower: products.into_iter().fold(f64::INFINITY, f64::min),
+            upper: products.into_iter().fold(f64::NEG_INFINITY, f64::max),
+        }
+        .widened()
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}, {}]", self.lower, self.upper)
+    }
+}
+
+impl ParameterRange {
+    /// The range as an interval, unbounded on a side without a bound.
+    pub fn interval(&self) -> Interval {
+        Interval {
+            lower: self.min.unwrap_or(f64::NEG_INFINITY),
+            upper: self.max.unwrap_or(f64::INFINITY),
+        }
+    }
+}
+
+impl Parameter {
+    /// The interval of the values `contains` allows: the range for numbers, `[0, 1]` for
+    /// booleans and the option indices for categorical parameters.
+    pub fn interval(&self) -> Interval {
+        match &self.parameter_type {
+            ParameterType::Boolean => Interval {
+                lower: 0.0,
+                upper: 1.0,
+            },
+            ParameterType::Categorical { options } => Interval {
+                lower: 0.0,
+                upper: options.len().saturating_sub(1) as f64,
+            },
+            _ => self.range.interval(),
+        }
+    }
+}
+
+impl ParameterSpace {
+    /// Every parameter's interval by name, the environment for `MathNode::evaluate_interval`.
+    pub fn intervals(&self) -> BTreeMap<String, Interval> {
+        self.parameters
+            .iter()
+            .map(|parameter| (parameter.name.clone(), parameter.interval()))
+            .collect()
+    }
+}
@@ -0,0 +1,149 @@
+use super::*;
+use std::fmt;
+
+/// Metadata key holding a section's label, e.g. `("label", "eq:pythagoras")` on a section
+/// presenting an equation or theorem.
+pub const LABEL_KEY: &str = "label";
+
+/// Separates the document id from the label in a qualified reference, `document-id::label`.
+pub const LABEL_SEPARATOR: &str = "::";
+
+impl Section {
+    pub fn label(&self) -> Option<&str> {
+        self.metadata
+            .iter()
+            .find(|(key, _)| key == LABEL_KEY)
+            .map(|(_, label)| label.as_str())
+    }
+}
+
+/// A label together with the document it is defined in. Labels are scoped to the innermost
+/// document defining them, so an embedded or tooltip document may reuse its host's labels.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct QualifiedLabel {
+    pub document_id: String,
+    pub label: String,
+}
+
+impl QualifiedLabel {
+    pub fn new(document_id: impl Into<String>, label: impl Into<String>) -> Self {
+        QualifiedLabel {
+            document_id: document_id.into(),
+            label: label.into(),
+        }
+    }
+
+    /// Reads a reference made from within document `document_id`: `other-doc::eq:1` names label
+    /// `eq:1` of document `other-doc`, a bare `eq:1` names the label of `document_id` itself.
+    pub fn parse(reference: &str, document_id: &str) -> Self {
+        match reference.split_once(LABEL_SEPARATOR) {
+            Some((document, label)) if !document.is_empty() => QualifiedLabel::new(document, label),
+            Some((_, label)) => QualifiedLabel::new(document_id, label),
+            None => QualifiedLabel::new(document_id, reference),
+        }
+    }
+
+    /// An element id for the label's anchor that stays unique when documents are embedded in one
+    /// another, unlike the label itself.
+    pub fn anchor(&self) -> String {
+        let escape = |text: &str| -> String {
+            text.chars()
+                .map(|c| {
+                    if c.is_alphanumeric() || c == '-' {
+                        c
+                    } else {
+                        '_'
+                    }
+                })
+                .collect()
+        };
+        format!(
+            "label--{}--{}",
+            escape(&self.document_id),
+            escape(&self.label)
+        )
+    }
+}
+
+impl fmt::Display for QualifiedLabel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}{}", self.document_id, LABEL_SEPARATOR, self.label)
+    }
+}
+
+/// The labelled sections of a document and the documents embedded in it.
+#[derive(Debug, Clone, Default)]
+pub struct LabelIndex {
+    labels: Vec<(QualifiedLabel, String)>, // (label, section id), in document order
+}
+
+impl LabelIndex {
+    pub fn new(document: &MathDocument) -> Self {
+        let mut collector = LabelCollector::default();
+        collector.visit_document(document);
+        LabelIndex {
+            labels: collector.labels,
+        }
+    }
+
+    /// Every label with the id of the section carrying it, in document order. A label defined
+    /// more than once in the same document appears once per definition.
+    pub fn labels(&self) -> impl Iterator<Item = (&QualifiedLabel, &str)> {
+        self.labels
+            .iter()
+            .map(|(label, section_id)| (label, section_id.as_str()))
+    }
+
+    /// The id of the section a reference made from document `document_id` points to, see
+    /// `QualifiedLabel::parse`. The first definition wins when a label is duplicated.
+    pub fn resolve(&self, reference: &str, document_id: &str) -> Option<&str> {
+        let target = QualifiedLabel::parse(reference, document_id);
+        self.labels
+            .iter()
+            .find(|(label, _)| *label == target)
+            .map(|(_, section_id)| section_id.as_str())
+    }
+}
+
+impl MathDocument {
+    pub fn label_index(&self) -> LabelIndex {
+        LabelIndex::new(self)
+    }
+
+    /// The labels defined more than once in the same document scope once the embedded section
+    /// references have been inlined through `resolver`, which is how the frontend renders them.
+    /// A labelled section transcluded twice, or next to a section with the same label, is
+    /// reported. References `resolver` cannot resolve are skipped.
+    pub fn validate_labels(&self, resolver: &SectionResolver) -> Vec<ValidationError> {
+        let mut resolved = self.clone();
+        resolver.inline_refs(&mut resolved);
+        resolved
+            .validate()
+            .into_iter()
+            .filter(|error| matches!(error, ValidationError::DuplicateLabel { .. }))
+            .collect()
+    }
+}
+
+#[derive(Default)]
+struct LabelCollector {
+    documents: Vec<String>,
+    labels: Vec<(QualifiedLabel, String)>,
+}
+
+impl<'doc> DocumentVisitor<'doc> for LabelCollector {
+    fn visit_document(&mut self, document: &'doc MathDocument) {
+        self.documents.push(document.id.clone());
+        walk_document(self, document);
+        self.documents.pop();
+    }
+
+    fn visit_section(&mut self, section: &'doc Section) {
+        if let Some(label) = section.label() {
+            let document_id = self.documents.last().cloned().unwrap_or_default();
+            self.labels
+                .push((QualifiedLabel::new(document_id, label), section.id.clone()));
+        }
+        walk_section(self, section);
+    }
+}
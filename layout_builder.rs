@@ -0,0 +1,298 @@
+use super::{ColumnsNode, GridItemNode, GridNode, SectionContentNode};
+use std::collections::HashSet;
+
+/// How many grid tracks an item occupies, in columns and rows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GridSpan {
+    pub columns: usize,
+    pub rows: usize,
+}
+
+impl GridSpan {
+    pub fn single() -> Self {
+        GridSpan {
+            columns: 1,
+            rows: 1,
+        }
+    }
+    pub fn new(columns: usize, rows: usize) -> Self {
+        GridSpan {
+            columns: columns.max(1),
+            rows: rows.max(1),
+        }
+    }
+    pub fn columns(columns: usize) -> Self {
+        GridSpan::new(columns, 1)
+    }
+    pub fn rows(rows: usize) -> Self {
+        GridSpan::new(1, rows)
+    }
+    /// Spans every column of a grid with `column_count` columns.
+    pub fn full_width(column_count: usize) -> Self {
+        GridSpan::new(column_count, 1)
+    }
+}
+
+impl GridItemNode {
+    /// Number of columns covered, derived from the 1-based, end-exclusive grid lines.
+    pub fn column_span(&self) -> usize {
+        match (self.col_start, self.col_end) {
+            (Some(start), Some(end)) if end > start => end - start,
+            _ => 1,
+        }
+    }
+    pub fn row_span(&self) -> usize {
+        match (self.row_start, self.row_end) {
+            (Some(start), Some(end)) if end > start => end - start,
+            _ => 1,
+        }
+    }
+}
+
+impl GridNode {
+    /// Starts a builder that places items automatically, row by row.
+    /// `column_template` is either a column count ("3") or a CSS grid-template-columns string ("1fr 2fr").
+    pub fn builder(column_template: impl Into<String>) -> GridNodeBuilder {
+        GridNodeBuilder::new(column_template)
+    }
+
+    /// Number of columns described by `column_template`.
+    pub fn column_count(&self) -> usize {
+        column_count_from_template(&self.column_template)
+    }
+}
+
+/// Counts the tracks of a grid-template-columns string, expanding `repeat(n, ...)`.
+pub fn column_count_from_template(template: &str) -> usize {
+    let template = template.trim();
+    if let Ok(count) = template.parse::<usize>() {
+        return count.max(1);
+    }
+
+    let mut count = 0;
+    let mut depth = 0;
+    let mut token = String::new();
+    let mut tokens = Vec::new();
+    for c in template.chars() {
+        match c {
+            '(' => {
+                depth += 1;
+                token.push(c);
+            }
+            ')' => {
+                depth -= 1;
+                token.push(c);
+            }
+            c if c.is_whitespace() && depth == 0 => {
+                if !token.is_empty() {
+                    tokens.push(std::mem::take(&mut token));
+                }
+            }
+            _ => token.push(c),
+        }
+    }
+    if !token.is_empty() {
+        tokens.push(token);
+    }
+
+    for token in tokens {
+        if let Some(inner) = token
+            .strip_prefix("repeat(")
+            .and_then(|rest| rest.strip_suffix(')'))
+        {
+            let mut parts = inner.splitn(2, ',');
+            let times = parts
+                .next()
+                .and_then(|n| n.trim().parse::<usize>().ok())
+                .unwrap_or(1);
+            let tracks = parts.next().map(column_count_from_template).unwrap_or(1);
+            count += times * tracks;
+        } else {
+            count += 1;
+        }
+    }
+    count.max(1)
+}
+
+/// Builds a `GridNode`, computing `col_start`/`col_end`/`row_start`/`row_end` for every item.
+/// Items flow left to right and wrap to the next row; spanning items take the first free slot large enough.
+#[derive(Debug, Clone)]
+pub struct GridNodeBuilder {
+    column_template: String,
+    column_count: usize,
+    row_gap: Option<String>,
+    column_gap: Option<String>,
+    items: Vec<GridItemNode>,
+    occupied: HashSet<(usize, usize)>, // (row, column), 1-based
+    cursor: (usize, usize),
+}
+
+impl GridNodeBuilder {
+    pub fn new(column_template: impl Into<String>) -> Self {
+        let column_template = column_template.into();
+        let column_count = column_count_from_template(&column_template);
+        GridNodeBuilder {
+            column_template,
+            column_count,
+            row_gap: None,
+            column_gap: None,
+            items: vec![],
+            occupied: HashSet::new(),
+            cursor: (1, 1),
+        }
+    }
+
+    pub fn row_gap(mut self, gap: impl Into<String>) -> Self {
+        self.row_gap = Some(gap.into());
+        self
+    }
+
+    pub fn column_gap(mut self, gap: impl Into<String>) -> Self {
+        self.column_gap = Some(gap.into());
+        self
+    }
+
+    pub fn gap(self, gap: impl Into<String>) -> Self {
+        let gap = gap.into();
+        self.row_gap(gap.clone()).column_gap(gap)
+    }
+
+    /// Places a single-cell item in the next free slot.
+    pub fn item(self, content: SectionContentNode) -> Self {
+        self.spanning_item(content, GridSpan::single())
+    }
+
+    /// Places an item covering `span`, clamped to the number of columns.
+    pub fn spanning_item(mut self, content: SectionContentNode, span: GridSpan) -> Self {
+        let span = GridSpan::new(span.columns.min(self.column_count), span.rows);
+        let (row, column) = self.find_slot(span);
+        for r in row..row + span.rows {
+            for c in column..column + span.columns {
+                self.occupied.insert((r, c));
+            }
+        }
+        self.cursor = (row, column + span.columns);
+        self.items.push(GridItemNode {
+            content,
+            col_start: Some(column),
+            col_end: Some(column + span.columns),
+            row_start: Some(row),
+            row_end: Some(row + span.rows),
+        });
+        self
+    }
+
+    /// Places every item as a single cell.
+    pub fn items(self, contents: impl IntoIterator<Item = SectionContentNode>) -> Self {
+        contents
+            .into_iter()
+            .fold(self, |builder, content| builder.item(content))
+    }
+
+    /// Places every item with its own span.
+    pub fn spanning_items(
+        self,
+        contents: impl IntoIterator<Item = (SectionContentNode, GridSpan)>,
+    ) -> Self {
+        contents.into_iter().fold(self, |builder, (content, span)| {
+            builder.spanning_item(content, span)
+        })
+    }
+
+    /// Moves the cursor to the start of the next row that has no items yet.
+    pub fn next_row(mut self) -> Self {
+        let last_row = self.occupied.iter().map(|(r, _)| *r).max().unwrap_or(0);
+        self.cursor = (last_row + 1, 1);
+        self
+    }
+
+    pub fn build(self) -> GridNode {
+        GridNode {
+            items: self.items,
+            column_template: self.column_template,
+            row_gap: self.row_gap,
+            column_gap: self.column_gap,
+        }
+    }
+
+    fn find_slot(&self, span: GridSpan) -> (usize, usize) {
+        let (mut row, mut column) = self.cursor;
+        loop {
+            if column + span.columns - 1 > self.column_count {
+                row += 1;
+                column = 1;
+                continue;
+            }
+            let free = (row..row + span.rows)
+                .all(|r| (column..column + span.columns).all(|c| !self.occupied.contains(&(r, c))));
+            if free {
+                return (row, column);
+            }
+            column += 1;
+        }
+    }
+}
+
+impl ColumnsNode {
+    pub fn builder() -> ColumnsNodeBuilder {
+        ColumnsNodeBuilder::default()
+    }
+
+    /// Lays out `columns` with widths taken from a template such as "1fr 2fr" or "30% 70%".
+    /// Missing widths default to "1fr"; surplus widths are dropped.
+    pub fn from_template<I, C>(column_template: &str, columns: I) -> ColumnsNode
+    where
+        I: IntoIterator<Item = C>,
+        C: IntoIterator<Item = SectionContentNode>,
+    {
+        let mut widths = column_template.split_whitespace();
+        columns
+            .into_iter()
+            .fold(ColumnsNode::builder(), |builder, column| {
+                let width = widths.next().unwrap_or("1fr").to_string();
+                builder.column(width, column)
+            })
+            .build()
+    }
+
+    /// Lays out `columns` with equal "1fr" widths.
+    pub fn equal<I, C>(columns: I) -> ColumnsNode
+    where
+        I: IntoIterator<Item = C>,
+        C: IntoIterator<Item = SectionContentNode>,
+    {
+        ColumnsNode::from_template("", columns)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ColumnsNodeBuilder {
+    columns_content: Vec<Vec<SectionContentNode>>,
+    column_widths: Vec<String>,
+    gap: Option<String>,
+}
+
+impl ColumnsNodeBuilder {
+    pub fn column(
+        mut self,
+        width: impl Into<String>,
+        content: impl IntoIterator<Item = SectionContentNode>,
+    ) -> Self {
+        self.column_widths.push(width.into());
+        self.columns_content.push(content.into_iter().collect());
+        self
+    }
+
+    pub fn gap(mut self, gap: impl Into<String>) -> Self {
+        self.gap = Some(gap.into());
+        self
+    }
+
+    pub fn build(self) -> ColumnsNode {
+        ColumnsNode {
+            columns_content: self.columns_content,
+            column_widths: self.column_widths,
+            gap: self.gap,
+        }
+    }
+}
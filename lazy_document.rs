@@ -0,0 +1,191 @@
+use super::*;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::value::RawValue;
+use std::fmt;
+use std::sync::{Arc, OnceLock};
+
+/// A nested document, as held by `SectionContentNode::EmbeddedDocument` and
+/// `LinkTarget::TooltipDocument`, that is only turned into a `MathDocument` when first used.
+/// Deserializing a page keeps nested documents as their JSON text until they are shown or
+/// walked; a handle can also be given just an id and a loader that fetches the document on
+/// demand. Serialized, a handle is the document itself, so the format is the same as for an
+/// eager one.
+#[derive(Clone)]
+pub struct DocumentHandle {
+    document: OnceLock<Result<Arc<MathDocument>, DocumentLoadError>>,
+    source: DocumentSource,
+}
+
+type DocumentLoader = Arc<dyn Fn(&str) -> Option<MathDocument> + Send + Sync>;
+
+#[derive(Clone)]
+enum DocumentSource {
+    Loaded,
+    Json {
+        id: Option<String>, // read up front so that `id()` does not load the document
+        json: Arc<RawValue>,
+    },
+    Loader {
+        id: String,
+        load: DocumentLoader,
+    },
+}
+
+impl DocumentHandle {
+    pub fn new(document: MathDocument) -> Self {
+        DocumentHandle::from(Arc::new(document))
+    }
+
+    /// A handle for the document with id `id`, fetched with `load` when first used.
+    pub fn lazy(
+        id: impl Into<String>,
+        load: impl Fn(&str) -> Option<MathDocument> + Send + Sync + 'static,
+    ) -> Self {
+        DocumentHandle {
+            document: OnceLock::new(),
+            source: DocumentSource::Loader {
+                id: id.into(),
+                load: Arc::new(load),
+            },
+        }
+    }
+
+    /// The document's id, without loading it.
+    pub fn id(&self) -> Option<&str> {
+        if let Some(Ok(document)) = self.document.get() {
+            return Some(&document.id);
+        }
+        match &self.source {
+            DocumentSource::Loaded => None,
+            DocumentSource::Json { id, .. } => id.as_deref(),
+            DocumentSource::Loader { id, .. } => Some(id),
+        }
+    }
+
+    pub fn is_loaded(&self) -> bool {
+        self.document.get().is_some()
+    }
+
+    /// The document, loading it if needed. A failed load is kept, and returned again by later
+    /// calls.
+    pub fn get(&self) -> Result<&MathDocument, DocumentLoadError> {
+        let document = self
+            .document
+            .get_or_init(|| self.source.load().map(Arc::new));
+        document.as_deref().map_err(Clone::clone)
+    }
+
+    /// The document for editing, loading it if needed; shared copies are left unchanged.
+    pub fn get_mut(&mut self) -> Result<&mut MathDocument, DocumentLoadError> {
+        self.get()?;
+        match self.document.get_mut() {
+            Some(Ok(document)) => Ok(Arc::make_mut(document)),
+            Some(Err(error)) => Err(error.clone()),
+            None => unreachable!("`get` initializes the document"),
+        }
+    }
+}
+
+impl DocumentSource {
+    fn load(&self) -> Result<MathDocument, DocumentLoadError> {
+        match self {
+            DocumentSource::Loaded => unreachable!("a loaded handle starts with its document"),
+            DocumentSource::Json { id, json } => {
+                serde_json::from_str(json.get()).map_err(|error| DocumentLoadError::Invalid {
+                    id: id.clone(),
+                    message: error.to_string(),
+                })
+            }
+            DocumentSource::Loader { id, load } => {
+                load(id).ok_or_else(|| DocumentLoadError::NotFound { id: id.clone() })
+            }
+        }
+    }
+}
+
+/// Why a `DocumentHandle` has no document.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DocumentLoadError {
+    /// The nested JSON is not a valid `MathDocument`.
+    Invalid { id: Option<String>, message: String },
+    /// The loader found no document with this id.
+    NotFound { id: String },
+}
+
+impl fmt::Display for DocumentLoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DocumentLoadError::Invalid { id, message } => write!(
+                f,
+                "embedded document `{}` is invalid: {}",
+                id.as_deref().unwrap_or_default(),
+                message
+            ),
+            DocumentLoadError::NotFound { id } => {
+                write!(f, "embedded document `{}` cannot be loaded", id)
+            }
+        }
+    }
+}
+
+impl std::error::Error for DocumentLoadError {}
+
+impl From<Arc<MathDocument>> for DocumentHandle {
+    fn from(document: Arc<MathDocument>) -> Self {
+        DocumentHandle {
+            document: OnceLock::from(Ok(document)),
+            source: DocumentSource::Loaded,
+        }
+    }
+}
+
+impl From<MathDocument> for DocumentHandle {
+    fn from(document: MathDocument) -> Self {
+        DocumentHandle::new(document)
+    }
+}
+
+impl Serialize for DocumentHandle {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        // Through the document even when it is still JSON, so that the output is the same
+        // whether or not it was loaded.
+        match self.get() {
+            Ok(document) => document.serialize(serializer),
+            Err(error) => Err(serde::ser::Error::custom(error)),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for DocumentHandle {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct Id {
+            id: Option<String>,
+        }
+
+        let json: Box<RawValue> = Box::deserialize(deserializer)?;
+        let id = serde_json::from_str::<Id>(json.get())
+            .ok()
+            .and_then(|document| document.id);
+        Ok(DocumentHandle {
+            document: OnceLock::new(),
+            source: DocumentSource::Json {
+                id,
+                json: Arc::from(json),
+            },
+        })
+    }
+}
+
+impl fmt::Debug for DocumentHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.document.get() {
+            Some(Ok(document)) => document.fmt(f),
+            _ => f
+                .debug_struct("DocumentHandle")
+                .field("id", &self.id())
+                .field("loaded", &self.is_loaded())
+                .finish(),
+        }
+    }
+}
@@ -0,0 +1,132 @@
+use super::*;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// A link found by `MathDocument::collect_links`.
+#[derive(Debug, Clone)]
+pub struct LinkOccurrence<'doc> {
+    pub target: &'doc LinkTarget,
+    pub content: &'doc [RichTextSegment], // the visible link content
+    pub path: Vec<LocationStep>, // enclosing documents, sections and panels, outermost first
+}
+
+/// One level of the location of a link.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub enum LocationStep {
+    Document(String),
+    Section(String),
+    Panel(String),
+}
+
+impl LinkOccurrence<'_> {
+    /// The top-level document the link was found in.
+    pub fn document_id(&self) -> Option<&str> {
+        self.path.iter().find_map(|step| match step {
+            LocationStep::Document(id) => Some(id.as_str()),
+            _ => None,
+        })
+    }
+
+    /// The innermost section holding the link.
+    pub fn section_id(&self) -> Option<&str> {
+        self.path.iter().rev().find_map(|step| match step {
+            LocationStep::Section(id) => Some(id.as_str()),
+            _ => None,
+        })
+    }
+
+    /// The location as a `/`-separated path of ids, e.g. `doc/intro/panel-1`.
+    pub fn path_string(&self) -> String {
+        LocationStep::path_string(&self.path)
+    }
+}
+
+impl LocationStep {
+    pub fn id(&self) -> &str {
+        match self {
+            LocationStep::Document(id) | LocationStep::Section(id) | LocationStep::Panel(id) => id,
+        }
+    }
+
+    /// `path` as a `/`-separated path of ids, e.g. `doc/intro/panel-1`.
+    pub fn path_string(path: &[LocationStep]) -> String {
+        path.iter()
+            .map(LocationStep::id)
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+}
+
+impl LinkTarget {
+    /// The URL of a link leaving the corpus.
+    pub fn external_url(&self) -> Option<&str> {
+        match self {
+            LinkTarget::Url(url) => Some(url),
+            _ => None,
+        }
+    }
+}
+
+impl MathDocument {
+    /// Every link in the document in document order, including links in embedded and tooltip
+    /// documents, with where each was found.
+    pub fn collect_links(&self) -> Vec<LinkOccurrence<'_>> {
+        let mut collector = LinkCollector::default();
+        collector.visit_document(self);
+        collector.links
+    }
+
+    /// The distinct external URLs linked from the document, in order of first appearance.
+    pub fn external_urls(&self) -> Vec<&str> {
+        let mut urls: Vec<&str> = vec![];
+        for link in self.collect_links() {
+            if let Some(url) = link.target.external_url()
+                && !urls.contains(&url)
+            {
+                urls.push(url);
+            }
+        }
+        urls
+    }
+}
+
+#[derive(Default)]
+struct LinkCollector<'doc> {
+    path: Vec<LocationStep>,
+    links: Vec<LinkOccurrence<'doc>>,
+}
+
+impl<'doc> DocumentVisitor<'doc> for LinkCollector<'doc> {
+    fn visit_document(&mut self, document: &'doc MathDocument) {
+        self.path.push(LocationStep::Document(document.id.clone()));
+        walk_document(self, document);
+        self.path.pop();
+    }
+
+    fn visit_section(&mut self, section: &'doc Section) {
+        self.path.push(LocationStep::Section(section.id.clone()));
+        walk_section(self, section);
+        self.path.pop();
+    }
+
+    fn visit_panel(&mut self, panel: &'doc Panel) {
+        self.path.push(LocationStep::Panel(panel.id.clone()));
+        walk_panel(self, panel);
+        self.path.pop();
+    }
+
+    fn visit_segment(&mut self, segment: &'doc RichTextSegment) {
+        if let RichTextSegment::Link {
+            content, target, ..
+        } = segment
+        {
+            self.links.push(LinkOccurrence {
+                target,
+                content,
+                path: self.path.clone(),
+            });
+        }
+        walk_segment(self, segment);
+    }
+}
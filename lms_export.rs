@@ -0,0 +1,209 @@
+use super::*;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::fmt::Write as _;
+use ts_rs::TS;
+
+/// A course or assessment made of documents, described for learning management systems: as a
+/// SCORM 2004 content package or as LTI 1.3 deep-linking content items.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct LmsPackage {
+    pub identifier: String,
+    pub title: String,
+    pub items: Vec<LmsItem>,
+    pub player_files: Vec<String>, // Files of the renderer bundle, relative to the package root
+    pub launch_page: String,       // Player page that renders `?document=<path>`
+}
+
+/// One launchable document of a package.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct LmsItem {
+    pub document_id: String,
+    pub title: String,
+    pub document_path: String, // Document JSON, relative to the package root
+    pub score_maximum: Option<f64>, // Graded items report a score out of this to the LMS
+    pub mastery_score: Option<f64>, // Normalized 0..1 score needed to pass
+}
+
+impl LmsPackage {
+    /// A package with one item per document, in order. Documents with exercise sections
+    /// (see `DifficultyAnnotation::role`) are graded out of their number of exercises.
+    pub fn new<'a>(
+        identifier: &str,
+        title: &str,
+        documents: impl IntoIterator<Item = &'a MathDocument>,
+    ) -> Self {
+        let items = documents
+            .into_iter()
+            .map(|document| {
+                let exercises = document.fold(0, |count, node| match node {
+                    FoldNode::Section(Section {
+                        difficulty: Some(difficulty),
+                        ..
+                    }) if difficulty.role == ContentRole::Exercise => count + 1,
+                    _ => count,
+                });
+                LmsItem {
+                    document_id: document.id.clone(),
+                    title: document
+                        .content_type
+                        .title()
+                        .unwrap_or(&document.id)
+                        .to_string(),
+                    document_path: format!("documents/{}.json", document.id),
+                    score_maximum: (exercises > 0).then_some(exercises as f64),
+                    mastery_score: None,
+                }
+            })
+            .collect();
+        LmsPackage {
+            identifier: identifier.to_string(),
+            title: title.to_string(),
+            items,
+            player_files: vec![],
+            launch_page: "index.html".to_string(),
+        }
+    }
+
+    pub fn with_player(mut self, launch_page: &str, player_files: Vec<String>) -> Self {
+        self.launch_page = launch_page.to_string();
+        self.player_files = player_files;
+        self
+    }
+
+    /// The `imsmanifest.xml` of a SCORM 2004 (4th edition) package: one SCO per item, sharing
+    /// the player files as an asset resource.
+    pub fn scorm_manifest(&self) -> String {
+        let mut xml = String::from(r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+        let _ = write!(
+            xml,
+            r#"<manifest identifier="{}" version="1" xmlns="http://www.imsglobal.org/xsd/imscp_v1p1" xmlns:adlcp="http://www.adlnet.org/xsd/adlcp_v1p3" xmlns:imsss="http://www.imsglobal.org/xsd/imsss"><metadata><schema>ADL SCORM</schema><schemaversion>2004 4th Edition</schemaversion></metadata>"#,
+            xml_id("MANIFEST", &self.identifier)
+        );
+        let _ = write!(
+            xml,
+            r#"<organizations default="ORG-1"><organization identifier="ORG-1"><title>{}</title>"#,
+            escape_xml(&self.title)
+        );
+        for (i, item) in self.items.iter().enumerate() {
+            let _ = write!(
+                xml,
+                r#"<item identifier="ITEM-{}" identifierref="RES-{}"><title>{}</title>"#,
+                i + 1,
+                i + 1,
+                escape_xml(&item.title)
+            );
+            if let Some(mastery_score) = item.mastery_score {
+                let _ = write!(
+                    xml,
+                    r#"<imsss:sequencing><imsss:objectives><imsss:primaryObjective objectiveID="{}" satisfiedByMeasure="true"><imsss:minNormalizedMeasure>{}</imsss:minNormalizedMeasure></imsss:primaryObjective></imsss:objectives></imsss:sequencing>"#,
+                    xml_id("OBJ", &item.document_id),
+                    mastery_score
+                );
+            }
+            xml.push_str("</item>");
+        }
+        xml.push_str("</organization></organizations><resources>");
+        for (i, item) in self.items.iter().enumerate() {
+            let _ = write!(
+                xml,
+                r#"<resource identifier="RES-{}" type="webcontent" adlcp:scormType="sco" href="{}"><file href="{}"/>"#,
+                i + 1,
+                escape_xml(&self.launch_url(item)),
+                escape_xml(&item.document_path)
+            );
+            if !self.player_files.is_empty() {
+                xml.push_str(r#"<dependency identifierref="PLAYER"/>"#);
+            }
+            xml.push_str("</resource>");
+        }
+        if !self.player_files.is_empty() {
+            xml.push_str(
+                r#"<resource identifier="PLAYER" type="webcontent" adlcp:scormType="asset">"#,
+            );
+            for file in &self.player_files {
+                let _ = write!(xml, r#"<file href="{}"/>"#, escape_xml(file));
+            }
+            xml.push_str("</resource>");
+        }
+        xml.push_str("</resources></manifest>");
+        xml
+    }
+
+    /// The SCORM package as a zip: the manifest, each document as JSON at its `document_path`,
+    /// and `player_files` as given, which should match `self.player_files`. Documents without an
+    /// item are skipped.
+    pub fn to_scorm_zip<'a>(
+        &self,
+        documents: impl IntoIterator<Item = &'a MathDocument>,
+        player_files: &[(String, Vec<u8>)],
+    ) -> Result<Vec<u8>, serde_json::Error> {
+        let mut zip = ZipWriter::new();
+        zip.add_file("imsmanifest.xml", self.scorm_manifest().as_bytes());
+        for document in documents {
+            if let Some(item) = self
+                .items
+                .iter()
+                .find(|item| item.document_id == document.id)
+            {
+                zip.add_file(&item.document_path, &serde_json::to_vec(document)?);
+            }
+        }
+        for (path, contents) in player_files {
+            zip.add_file(path, contents);
+        }
+        Ok(zip.finish())
+    }
+
+    /// LTI 1.3 deep-linking `ltiResourceLink` content items for the items, launched from
+    /// `base_url` (the tool's launch endpoint). Send them as the
+    /// `https://purl.imsglobal.org/spec/lti-dl/claim/content_items` claim.
+    pub fn lti_content_items(&self, base_url: &str) -> Value {
+        let base_url = base_url.trim_end_matches('/');
+        Value::Array(
+            self.items
+                .iter()
+                .map(|item| {
+                    let mut content_item = json!({
+                        "type": "ltiResourceLink",
+                        "title": item.title,
+                        "url": format!("{}/{}", base_url, self.launch_url(item)),
+                        "custom": {
+                            "package_id": self.identifier,
+                            "document_id": item.document_id,
+                        },
+                    });
+                    if let Some(score_maximum) = item.score_maximum {
+                        content_item["lineItem"] = json!({
+                            "scoreMaximum": score_maximum,
+                            "label": item.title,
+                            "resourceId": item.document_id,
+                        });
+                    }
+                    content_item
+                })
+                .collect(),
+        )
+    }
+
+    fn launch_url(&self, item: &LmsItem) -> String {
+        format!("{}?document={}", self.launch_page, item.document_path)
+    }
+}
+
+/// `value` turned into an XML id (a letter first, then letters, digits, `-`, `_` and `.`).
+fn xml_id(prefix: &str, value: &str) -> String {
+    let cleaned: String = value
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("{}-{}", prefix, cleaned)
+}
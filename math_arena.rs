@@ -0,0 +1,146 @@
+use super::*;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Position of a node in a `MathArena`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NodeIndex(u32);
+
+impl NodeIndex {
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+/// Flat storage for `MathNode` trees: nodes live in one `Vec` and refer to their children by
+/// index, for analyses that walk large trees many times. Children are stored before their
+/// parents, so a pass over `iter()` is a bottom-up traversal. A subtree shared between several
+/// parents (the same `Arc`) is stored once, and converting back shares its content again.
+#[derive(Debug, Clone, Default)]
+pub struct MathArena {
+    nodes: Vec<ArenaNode>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ArenaNode {
+    pub id: String,
+    /// The node's content with every child replaced by `MathNode::empty()`; leaves keep their
+    /// original content.
+    pub content: Arc<MathNodeContent>,
+    /// The children in `MathNodeContent::map_children` order.
+    pub children: Vec<NodeIndex>,
+}
+
+impl MathArena {
+    pub fn new() -> Self {
+        MathArena::default()
+    }
+
+    pub fn from_node(node: &MathNode) -> (MathArena, NodeIndex) {
+        let mut arena = MathArena::new();
+        let root = arena.add(node);
+        (arena, root)
+    }
+
+    /// Adds the tree, returning the index of its root.
+    pub fn add(&mut self, node: &MathNode) -> NodeIndex {
+        self.add_node(node, &mut HashMap::new())
+    }
+
+    fn add_node(
+        &mut self,
+        node: &MathNode,
+        added: &mut HashMap<*const MathNode, NodeIndex>,
+    ) -> NodeIndex {
+        if let Some(&index) = added.get(&(node as *const MathNode)) {
+            return index;
+        }
+        let children: Vec<NodeIndex> = node
+            .content
+            .children_with_paths()
+            .into_iter()
+            .map(|(_, child)| self.add_node(child, added))
+            .collect();
+        let content = match node.content.map_children(|_| Some(MathNode::empty())) {
+            Some(shell) => Arc::new(shell),
+            None => Arc::clone(&node.content),
+        };
+        let index = NodeIndex(self.nodes.len() as u32);
+        self.nodes.push(ArenaNode {
+            id: node.id.clone(),
+            content,
+            children,
+        });
+        added.insert(node as *const MathNode, index);
+        index
+    }
+
+    pub fn get(&self, index: NodeIndex) -> &ArenaNode {
+        &self.nodes[index.index()]
+    }
+
+    pub fn children(&self, index: NodeIndex) -> &[NodeIndex] {
+        &self.get(index).children
+    }
+
+    /// Every node with its index, children before parents.
+    pub fn iter(&self) -> impl Iterator<Item = (NodeIndex, &ArenaNode)> {
+        self.nodes
+            .iter()
+            .enumerate()
+            .map(|(index, node)| (NodeIndex(index as u32), node))
+    }
+
+    /// The subtree at `index`, in pre-order.
+    pub fn descendants(&self, index: NodeIndex) -> Vec<NodeIndex> {
+        let mut descendants = vec![];
+        let mut stack = vec![index];
+        while let Some(index) = stack.pop() {
+            descendants.push(index);
+            stack.extend(self.children(index).iter().rev());
+        }
+        descendants
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// The tree at `index` as a `MathNode`, equal to the one that was added.
+    pub fn to_node(&self, index: NodeIndex) -> MathNode {
+        self.build(index, &mut HashMap::new())
+    }
+
+    fn build(&self, index: NodeIndex, built: &mut HashMap<NodeIndex, MathNode>) -> MathNode {
+        if let Some(node) = built.get(&index) {
+            return node.clone();
+        }
+        let arena_node = self.get(index);
+        let mut children = arena_node
+            .children
+            .iter()
+            .map(|&child| self.build(child, built))
+            .collect::<Vec<_>>()
+            .into_iter();
+        let content = match arena_node.content.map_children(|_| children.next()) {
+            Some(content) => Arc::new(content),
+            None => Arc::clone(&arena_node.content),
+        };
+        let node = MathNode {
+            id: arena_node.id.clone(),
+            content,
+        };
+        built.insert(index, node.clone());
+        node
+    }
+}
+
+impl MathNode {
+    pub fn to_arena(&self) -> (MathArena, NodeIndex) {
+        MathArena::from_node(self)
+    }
+}
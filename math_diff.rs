@@ -0,0 +1,296 @@
+use super::*;
+use serde_json::Value;
+use std::fmt;
+
+/// One edit turning an old expression into a new one. Paths are relative to the roots of the
+/// old and new trees; ids are ignored throughout, so a regenerated tree with fresh ids diffs
+/// as unchanged.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MathEdit {
+    /// The subtree at `old_path` was swapped for an unrelated one at `new_path`.
+    Replace {
+        old_path: NodePath,
+        new_path: NodePath,
+        old: MathNode,
+        new: MathNode,
+    },
+    /// `node` was inserted into a list, or as an optional child, at `path` in the new tree.
+    Insert { path: NodePath, node: MathNode },
+    /// `node` was removed from a list, or as an optional child, at `path` in the old tree.
+    Delete { path: NodePath, node: MathNode },
+    /// The node kept its kind and its children but not its own data: an operator, a bracket
+    /// style, an operation type. Changes to its children are separate edits.
+    Relabel {
+        old_path: NodePath,
+        new_path: NodePath,
+        old: MathNode,
+        new: MathNode,
+    },
+}
+
+impl MathNode {
+    /// The edits turning this expression into `other`, in pre-order of the old tree. Matching
+    /// children are kept in place, list items are aligned by their longest common
+    /// subsequence, and a node is only replaced as a whole when its kind changes or it has no
+    /// children to descend into.
+    pub fn diff(&self, other: &MathNode) -> Vec<MathEdit> {
+        let mut edits = vec![];
+        diff_nodes(self, other, NodePath::root(), NodePath::root(), &mut edits);
+        edits
+    }
+
+    /// Whether both trees are the same apart from node ids.
+    pub fn structurally_eq(&self, other: &MathNode) -> bool {
+        let children = self.content.children_with_paths();
+        let other_children = other.content.children_with_paths();
+        children.len() == other_children.len()
+            && shell(&self.content) == shell(&other.content)
+            && children
+                .iter()
+                .zip(&other_children)
+                .all(|((_, a), (_, b))| a.structurally_eq(b))
+    }
+}
+
+fn diff_nodes(
+    old: &MathNode,
+    new: &MathNode,
+    old_path: NodePath,
+    new_path: NodePath,
+    edits: &mut Vec<MathEdit>,
+) {
+    if old.structurally_eq(new) {
+        return;
+    }
+    let old_children = old.content.children_with_paths();
+    let new_children = new.content.children_with_paths();
+    if old.content.kind_name() != new.content.kind_name()
+        || old_children.is_empty()
+        || new_children.is_empty()
+    {
+        edits.push(MathEdit::Replace {
+            old_path,
+            new_path,
+            old: old.clone(),
+            new: new.clone(),
+        });
+        return;
+    }
+
+    // Children are compared within lists (`terms[0]`, `terms[1]`, ...) or single fields.
+    let mut lists: Vec<NodePath> = vec![];
+    for (path, _) in old_children.iter().chain(&new_children) {
+        let list = list_of(path);
+        if !lists.contains(&list) {
+            lists.push(list);
+        }
+    }
+    let mut pairs = vec![];
+    let mut child_edits = vec![];
+    for list in &lists {
+        let olds: Vec<usize> = (0..old_children.len())
+            .filter(|&i| list_of(&old_children[i].0) == *list)
+            .collect();
+        let news: Vec<usize> = (0..new_children.len())
+            .filter(|&j| list_of(&new_children[j].0) == *list)
+            .collect();
+        for (i, j) in align(&olds, &news, &old_children, &new_children) {
+            if let (Some(i), Some(j)) = (i, j) {
+                pairs.push((i, j));
+            }
+            child_edits.push((i, j));
+        }
+    }
+
+    if !same_data(old, new, &pairs, old_children.len(), new_children.len()) {
+        edits.push(MathEdit::Relabel {
+            old_path: old_path.clone(),
+            new_path: new_path.clone(),
+            old: old.clone(),
+            new: new.clone(),
+        });
+    }
+    for (i, j) in child_edits {
+        match (i.map(|i| &old_children[i]), j.map(|j| &new_children[j])) {
+            (Some((old_relative, old_child)), Some((new_relative, new_child))) => diff_nodes(
+                old_child,
+                new_child,
+                old_path.join(old_relative),
+                new_path.join(new_relative),
+                edits,
+            ),
+            (Some((relative, child)), None) => edits.push(MathEdit::Delete {
+                path: old_path.join(relative),
+                node: (*child).clone(),
+            }),
+            (None, Some((relative, child))) => edits.push(MathEdit::Insert {
+                path: new_path.join(relative),
+                node: (*child).clone(),
+            }),
+            (None, None) => {}
+        }
+    }
+}
+
+/// The list a child belongs to: its path without a trailing index. Single fields are lists
+/// of their own.
+fn list_of(path: &NodePath) -> NodePath {
+    match path.segments.last() {
+        Some(PathSegment::Index(_)) => path.parent().unwrap_or_default(),
+        _ => path.clone(),
+    }
+}
+
+/// Aligns the old and new items of one list: structurally equal items by their longest
+/// common subsequence, then the leftovers between two matches pairwise in order, so a changed
+/// item is diffed rather than deleted and inserted. Returns the pairs in list order, with
+/// `None` on the side an item is missing from.
+fn align(
+    olds: &[usize],
+    news: &[usize],
+    old_children: &[(NodePath, &MathNode)],
+    new_children: &[(NodePath, &MathNode)],
+) -> Vec<(Option<usize>, Option<usize>)> {
+    let equal = |a: usize, b: usize| {
+        old_children[olds[a]]
+            .1
+            .structurally_eq(new_children[news[b]].1)
+    };
+    // lengths[a][b]: longest common subsequence of olds[a..] and news[b..]
+    let mut lengths = vec![vec![0usize; news.len() + 1]; olds.len() + 1];
+    for a in (0..olds.len()).rev() {
+        for b in (0..news.len()).rev() {
+            lengths[a][b] = if equal(a, b) {
+                lengths[a + 1][b + 1] + 1
+            } else {
+                lengths[a + 1][b].max(lengths[a][b + 1])
+            };
+        }
+    }
+
+    let mut aligned = vec![];
+    let mut gap_olds = vec![];
+    let mut gap_news = vec![];
+    let flush = |aligned: &mut Vec<_>, gap_olds: &mut Vec<usize>, gap_news: &mut Vec<usize>| {
+        for k in 0..gap_olds.len().max(gap_news.len()) {
+            aligned.push((gap_olds.get(k).copied(), gap_news.get(k).copied()));
+        }
+        gap_olds.clear();
+        gap_news.clear();
+    };
+    let (mut a, mut b) = (0, 0);
+    while a < olds.len() || b < news.len() {
+        if a < olds.len() && b < news.len() && equal(a, b) {
+            flush(&mut aligned, &mut gap_olds, &mut gap_news);
+            aligned.push((Some(olds[a]), Some(news[b])));
+            a += 1;
+            b += 1;
+        } else if b == news.len() || (a < olds.len() && lengths[a + 1][b] >= lengths[a][b + 1]) {
+            gap_olds.push(olds[a]);
+            a += 1;
+        } else {
+            gap_news.push(news[b]);
+            b += 1;
+        }
+    }
+    flush(&mut aligned, &mut gap_olds, &mut gap_news);
+    aligned
+}
+
+/// Whether `old` and `new` hold the same data besides their children, given which children
+/// were paired up; unpaired children are left out of the comparison along with the list
+/// entries holding them, such as the operator of an inserted term.
+fn same_data(
+    old: &MathNode,
+    new: &MathNode,
+    pairs: &[(usize, usize)],
+    old_count: usize,
+    new_count: usize,
+) -> bool {
+    let old_marks: Vec<Option<usize>> = (0..old_count)
+        .map(|i| pairs.iter().position(|&(o, _)| o == i))
+        .collect();
+    let new_marks: Vec<Option<usize>> = (0..new_count)
+        .map(|j| pairs.iter().position(|&(_, n)| n == j))
+        .collect();
+    marked_data(&old.content, &old_marks) == marked_data(&new.content, &new_marks)
+}
+
+const UNPAIRED: &str = "\u{0}unpaired";
+
+/// The content as JSON with each child replaced by a marker: the number of its pair, or
+/// `UNPAIRED`, whose list entries are then dropped.
+fn marked_data(content: &MathNodeContent, marks: &[Option<usize>]) -> Value {
+    let mut index = 0;
+    let marked = content.map_children(|_| {
+        let id = match marks[index] {
+            Some(pair) => pair.to_string(),
+            None => UNPAIRED.to_string(),
+        };
+        index += 1;
+        Some(MathNode {
+            id,
+            ..MathNode::empty()
+        })
+    });
+    let mut value = serde_json::to_value(marked.as_ref().unwrap_or(content)).unwrap_or_default();
+    drop_unpaired(&mut value);
+    value
+}
+
+fn drop_unpaired(value: &mut Value) {
+    let is_unpaired = |value: &Value| value.get("id").and_then(Value::as_str) == Some(UNPAIRED);
+    match value {
+        Value::Array(items) => {
+            items.retain(|item| match item {
+                Value::Array(tuple) => !tuple.iter().any(is_unpaired),
+                item => !is_unpaired(item),
+            });
+            items.iter_mut().for_each(drop_unpaired);
+        }
+        Value::Object(fields) => {
+            fields.retain(|_, field| !is_unpaired(field));
+            fields.values_mut().for_each(drop_unpaired);
+        }
+        _ => {}
+    }
+}
+
+/// The content with every child replaced by an empty node, for comparing a node's own data.
+fn shell(content: &MathNodeContent) -> MathNodeContent {
+    content
+        .map_children(|_| Some(MathNode::empty()))
+        .unwrap_or_else(|| content.clone())
+}
+
+impl fmt::Display for MathEdit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let at = |path: &NodePath| {
+            if path.is_root() {
+                "the root".to_string()
+            } else {
+                format!("`{}`", path)
+            }
+        };
+        match self {
+            MathEdit::Replace {
+                old_path, new, old, ..
+            } => write!(
+                f,
+                "replace {} at {} with {}",
+                old.content.kind_name(),
+                at(old_path),
+                new.content.kind_name()
+            ),
+            MathEdit::Insert { path, node } => {
+                write!(f, "insert {} at {}", node.content.kind_name(), at(path))
+            }
+            MathEdit::Delete { path, node } => {
+                write!(f, "delete {} at {}", node.content.kind_name(), at(path))
+            }
+            MathEdit::Relabel { old_path, old, .. } => {
+                write!(f, "relabel {} at {}", old.content.kind_name(), at(old_path))
+            }
+        }
+    }
+}
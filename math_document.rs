@@ -1,6 +1,10 @@
 // --- MAIN: Mathematical Content System ---
 
-use super::{MathNode, RichTextSegment, Section, SectionContentNode};
+use super::{
+    CorrespondenceGroup, DifficultyAnnotation, DifficultyProfile, MathNode, QuantityFormat,
+    RevisionHistory, RichTextSegment, Section, SectionContentNode, TheoryId, UnitInterval,
+    VariableContext,
+};
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 
@@ -14,10 +18,17 @@ pub trait ToMathDocument {
 pub struct MathDocument {
     pub id: String,
     pub content_type: MathDocumentType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variable_context: Option<VariableContext>, // Declarations backing InteractiveVariable ids
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revision_history: Option<RevisionHistory>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quantity_format: Option<QuantityFormat>, // How every backend writes `Quantity` numbers
 }
 
 /// Each variant represents a distinct document type with its own specialized structure and behavior
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(tag = "kind", content = "data")]
 #[ts(export)]
 pub enum MathDocumentType {
     // --- Primary Knowledge Documents ---
@@ -52,14 +63,230 @@ pub enum MathDocumentType {
     IFrameEmbed(IFrameEmbedContent),
 }
 
+impl MathDocumentType {
+    /// Variant name, matching the serialized tag.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            MathDocumentType::WikiPage(_) => "WikiPage",
+            MathDocumentType::Textbook(_) => "Textbook",
+            MathDocumentType::ScientificPaper(_) => "ScientificPaper",
+            MathDocumentType::PersonalNotes(_) => "PersonalNotes",
+            MathDocumentType::MathematicianNotes(_) => "MathematicianNotes",
+            MathDocumentType::StudyNotes(_) => "StudyNotes",
+            MathDocumentType::TooltipSummary(_) => "TooltipSummary",
+            MathDocumentType::BlogPost(_) => "BlogPost",
+            MathDocumentType::AbstractSummary(_) => "AbstractSummary",
+            MathDocumentType::ConceptMap(_) => "ConceptMap",
+            MathDocumentType::AnimatedPresentation(_) => "AnimatedPresentation",
+            MathDocumentType::InteractivePlayground(_) => "InteractivePlayground",
+            MathDocumentType::TypeMappingDisplay(_) => "TypeMappingDisplay",
+            MathDocumentType::ResourcePanel(_) => "ResourcePanel",
+            MathDocumentType::ComparisonPage(_) => "ComparisonPage",
+            MathDocumentType::TransformationMapping(_) => "TransformationMapping",
+            MathDocumentType::ConceptAlignment(_) => "ConceptAlignment",
+            MathDocumentType::StaticPreview(_) => "StaticPreview",
+            MathDocumentType::LiveEmbed(_) => "LiveEmbed",
+            MathDocumentType::ConceptExtract(_) => "ConceptExtract",
+            MathDocumentType::IFrameEmbed(_) => "IFrameEmbed",
+        }
+    }
+
+    /// Full document structure, present on the primary knowledge document types.
+    pub fn structure(&self) -> Option<&DocumentStructure> {
+        match self {
+            MathDocumentType::WikiPage(c) => Some(&c.structure),
+            MathDocumentType::Textbook(c) => Some(&c.structure),
+            MathDocumentType::ScientificPaper(c) => Some(&c.structure),
+            MathDocumentType::PersonalNotes(c) => Some(&c.structure),
+            MathDocumentType::MathematicianNotes(c) => Some(&c.structure),
+            MathDocumentType::StudyNotes(c) => Some(&c.structure),
+            _ => None,
+        }
+    }
+
+    /// Simplified content, present on derived documents and static previews.
+    pub fn simplified_content(&self) -> Option<&SimplifiedContentStructure> {
+        match self {
+            MathDocumentType::TooltipSummary(c) => Some(&c.content),
+            MathDocumentType::BlogPost(c) => Some(&c.content),
+            MathDocumentType::AbstractSummary(c) => Some(&c.content),
+            MathDocumentType::ConceptMap(c) => Some(&c.content),
+            MathDocumentType::StaticPreview(c) => Some(&c.content_snapshot),
+            _ => None,
+        }
+    }
+
+    /// Sections the interactive documents are built on top of.
+    pub fn base_content(&self) -> Option<&Vec<Section>> {
+        match self {
+            MathDocumentType::AnimatedPresentation(c) => Some(&c.base_content),
+            MathDocumentType::InteractivePlayground(c) => Some(&c.base_content),
+            MathDocumentType::TypeMappingDisplay(c) => Some(&c.base_content),
+            MathDocumentType::ResourcePanel(c) => Some(&c.base_content),
+            _ => None,
+        }
+    }
+
+    pub fn presentation_config(&self) -> Option<&PresentationConfig> {
+        match self {
+            MathDocumentType::TooltipSummary(c) => Some(&c.presentation_config),
+            MathDocumentType::BlogPost(c) => Some(&c.presentation_config),
+            MathDocumentType::AbstractSummary(c) => Some(&c.presentation_config),
+            MathDocumentType::ConceptMap(c) => Some(&c.presentation_config),
+            _ => None,
+        }
+    }
+
+    pub fn title(&self) -> Option<&str> {
+        match self {
+            MathDocumentType::WikiPage(c) => Some(&c.title),
+            MathDocumentType::Textbook(c) => Some(&c.title),
+            MathDocumentType::ScientificPaper(c) => Some(&c.title),
+            MathDocumentType::PersonalNotes(c) => Some(&c.title),
+            MathDocumentType::MathematicianNotes(c) => Some(&c.title),
+            MathDocumentType::StudyNotes(c) => Some(&c.title),
+            MathDocumentType::BlogPost(c) => Some(&c.title),
+            MathDocumentType::AnimatedPresentation(c) => Some(&c.title),
+            MathDocumentType::InteractivePlayground(c) => Some(&c.title),
+            MathDocumentType::TypeMappingDisplay(c) => Some(&c.title),
+            MathDocumentType::ResourcePanel(c) => Some(&c.title),
+            MathDocumentType::ComparisonPage(c) => Some(&c.title),
+            MathDocumentType::TransformationMapping(c) => Some(&c.title),
+            MathDocumentType::ConceptAlignment(c) => Some(&c.title),
+            _ => None,
+        }
+    }
+
+    /// Declared relationships, present on the primary knowledge document types.
+    pub fn relationships(&self) -> Option<&DocumentRelationships> {
+        match self {
+            MathDocumentType::WikiPage(c) => Some(&c.relationships),
+            MathDocumentType::Textbook(c) => Some(&c.relationships),
+            MathDocumentType::ScientificPaper(c) => Some(&c.relationships),
+            MathDocumentType::PersonalNotes(c) => Some(&c.relationships),
+            MathDocumentType::MathematicianNotes(c) => Some(&c.relationships),
+            MathDocumentType::StudyNotes(c) => Some(&c.relationships),
+            _ => None,
+        }
+    }
+
+    /// Language, version and hash metadata, present on the primary knowledge document types.
+    pub fn content_metadata(&self) -> Option<&ContentMetadata> {
+        match self {
+            MathDocumentType::WikiPage(c) => Some(&c.content_metadata),
+            MathDocumentType::Textbook(c) => Some(&c.content_metadata),
+            MathDocumentType::ScientificPaper(c) => Some(&c.content_metadata),
+            MathDocumentType::PersonalNotes(c) => Some(&c.content_metadata),
+            MathDocumentType::MathematicianNotes(c) => Some(&c.content_metadata),
+            MathDocumentType::StudyNotes(c) => Some(&c.content_metadata),
+            _ => None,
+        }
+    }
+
+    /// Every top-level section of the document, in display order.
+    /// Abstract, body, footnotes and glossary for structured documents; base content for interactive ones.
+    pub fn top_level_sections(&self) -> Vec<&Section> {
+        if let Some(structure) = self.structure() {
+            structure
+                .abstract_content
+                .iter()
+                .chain(structure.body.iter())
+                .chain(structure.footnotes.iter())
+                .chain(structure.glossary.iter())
+                .collect()
+        } else if let Some(base_content) = self.base_content() {
+            base_content.iter().collect()
+        } else {
+            vec![]
+        }
+    }
+
+    pub fn structure_mut(&mut self) -> Option<&mut DocumentStructure> {
+        match self {
+            MathDocumentType::WikiPage(c) => Some(&mut c.structure),
+            MathDocumentType::Textbook(c) => Some(&mut c.structure),
+            MathDocumentType::ScientificPaper(c) => Some(&mut c.structure),
+            MathDocumentType::PersonalNotes(c) => Some(&mut c.structure),
+            MathDocumentType::MathematicianNotes(c) => Some(&mut c.structure),
+            MathDocumentType::StudyNotes(c) => Some(&mut c.structure),
+            _ => None,
+        }
+    }
+
+    pub fn relationships_mut(&mut self) -> Option<&mut DocumentRelationships> {
+        match self {
+            MathDocumentType::WikiPage(c) => Some(&mut c.relationships),
+            MathDocumentType::Textbook(c) => Some(&mut c.relationships),
+            MathDocumentType::ScientificPaper(c) => Some(&mut c.relationships),
+            MathDocumentType::PersonalNotes(c) => Some(&mut c.relationships),
+            MathDocumentType::MathematicianNotes(c) => Some(&mut c.relationships),
+            MathDocumentType::StudyNotes(c) => Some(&mut c.relationships),
+            _ => None,
+        }
+    }
+
+    pub fn content_metadata_mut(&mut self) -> Option<&mut ContentMetadata> {
+        match self {
+            MathDocumentType::WikiPage(c) => Some(&mut c.content_metadata),
+            MathDocumentType::Textbook(c) => Some(&mut c.content_metadata),
+            MathDocumentType::ScientificPaper(c) => Some(&mut c.content_metadata),
+            MathDocumentType::PersonalNotes(c) => Some(&mut c.content_metadata),
+            MathDocumentType::MathematicianNotes(c) => Some(&mut c.content_metadata),
+            MathDocumentType::StudyNotes(c) => Some(&mut c.content_metadata),
+            _ => None,
+        }
+    }
+
+    pub fn simplified_content_mut(&mut self) -> Option<&mut SimplifiedContentStructure> {
+        match self {
+            MathDocumentType::TooltipSummary(c) => Some(&mut c.content),
+            MathDocumentType::BlogPost(c) => Some(&mut c.content),
+            MathDocumentType::AbstractSummary(c) => Some(&mut c.content),
+            MathDocumentType::ConceptMap(c) => Some(&mut c.content),
+            MathDocumentType::StaticPreview(c) => Some(&mut c.content_snapshot),
+            _ => None,
+        }
+    }
+
+    pub fn base_content_mut(&mut self) -> Option<&mut Vec<Section>> {
+        match self {
+            MathDocumentType::AnimatedPresentation(c) => Some(&mut c.base_content),
+            MathDocumentType::InteractivePlayground(c) => Some(&mut c.base_content),
+            MathDocumentType::TypeMappingDisplay(c) => Some(&mut c.base_content),
+            MathDocumentType::ResourcePanel(c) => Some(&mut c.base_content),
+            _ => None,
+        }
+    }
+
+    /// Mutable counterpart of `top_level_sections`, in the same order.
+    pub fn top_level_sections_mut(&mut self) -> Vec<&mut Section> {
+        // Checked through `structure()` first: borrowck rejects the `if let ... else if let` form.
+        if self.structure().is_some() {
+            let structure = self.structure_mut().unwrap();
+            structure
+                .abstract_content
+                .iter_mut()
+                .chain(structure.body.iter_mut())
+                .chain(structure.footnotes.iter_mut())
+                .chain(structure.glossary.iter_mut())
+                .collect()
+        } else if let Some(base_content) = self.base_content_mut() {
+            base_content.iter_mut().collect()
+        } else {
+            vec![]
+        }
+    }
+}
+
 // --- Primary Knowledge Document Structs ---
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[ts(export)]
 pub struct WikiPageContent {
     pub title: String,
-    pub theory_domain: String,
+    pub theory_domain: TheoryId,
     pub completeness_level: CompletenessLevel,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub maintainer: Option<String>,
     pub content_metadata: ContentMetadata,
     pub structure: DocumentStructure,
@@ -71,12 +298,16 @@ pub struct WikiPageContent {
 pub struct TextbookContent {
     pub title: String,
     pub course_level: CourseLevel,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub chapter_info: Option<ChapterInfo>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub prerequisites: Vec<String>,
     pub content_metadata: ContentMetadata,
     pub academic_metadata: AcademicMetadata,
     pub structure: DocumentStructure,
     pub relationships: DocumentRelationships,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub difficulty_profiles: Vec<DifficultyProfile>, // Per-chapter rollup, see `refresh_difficulty_profiles`
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -84,6 +315,7 @@ pub struct TextbookContent {
 pub struct ScientificPaperContent {
     pub title: String,
     pub paper_type: PaperType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub venue: Option<String>,
     pub peer_reviewed: bool,
     pub content_metadata: ContentMetadata,
@@ -124,6 +356,8 @@ pub struct StudyNotesContent {
     pub content_metadata: ContentMetadata,
     pub structure: DocumentStructure,
     pub relationships: DocumentRelationships,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub difficulty_profiles: Vec<DifficultyProfile>, // Per-chapter rollup, see `refresh_difficulty_profiles`
 }
 
 // --- Derived/Simplified Content Structs ---
@@ -132,8 +366,11 @@ pub struct StudyNotesContent {
 #[ts(export)]
 pub struct TooltipSummaryContent {
     pub summarization_level: SummarizationLevel,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub max_length: Option<usize>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub focus_concepts: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub source_references: Vec<SourceReference>,
     pub derivation_metadata: DerivationMetadata,
     pub content: SimplifiedContentStructure,
@@ -147,10 +384,12 @@ pub struct BlogPostContent {
     pub writing_style: WritingStyle,
     pub target_audience: AudienceLevel,
     pub examples_included: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub source_references: Vec<SourceReference>,
     pub derivation_metadata: DerivationMetadata,
     pub content: SimplifiedContentStructure,
     pub presentation_config: PresentationConfig,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub academic_metadata: Option<AcademicMetadata>,
 }
 
@@ -158,7 +397,9 @@ pub struct BlogPostContent {
 #[ts(export)]
 pub struct AbstractSummaryContent {
     pub abstraction_level: u8, // L1-L4
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub key_properties: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub source_references: Vec<SourceReference>,
     pub derivation_metadata: DerivationMetadata,
     pub content: SimplifiedContentStructure,
@@ -169,7 +410,9 @@ pub struct AbstractSummaryContent {
 #[ts(export)]
 pub struct ConceptMapContent {
     pub central_concept: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub relationship_types: Vec<RelationshipType>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub source_references: Vec<SourceReference>,
     pub derivation_metadata: DerivationMetadata,
     pub content: SimplifiedContentStructure,
@@ -184,10 +427,13 @@ pub struct AnimatedPresentationContent {
     pub title: String,
     pub slide_count: usize,
     pub auto_advance: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub base_content: Vec<Section>,
     pub interaction_system: InteractionSystem,
     pub animation_timeline: AnimationTimeline,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub control_bindings: Vec<ControlBinding>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub interaction_points: Vec<InteractionPoint>,
 }
 
@@ -196,11 +442,15 @@ pub struct AnimatedPresentationContent {
 pub struct InteractivePlaygroundContent {
     pub title: String,
     pub parameter_space: ParameterSpace,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub visualization_types: Vec<VisualizationType>,
     pub real_time_feedback: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub base_content: Vec<Section>,
     pub interaction_system: InteractionSystem,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub control_bindings: Vec<ControlBinding>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub animation_timeline: Option<AnimationTimeline>,
 }
 
@@ -208,12 +458,16 @@ pub struct InteractivePlaygroundContent {
 #[ts(export)]
 pub struct TypeMappingDisplayContent {
     pub title: String,
-    pub source_theory: String,
-    pub target_theory: String,
+    pub source_theory: TheoryId,
+    pub target_theory: TheoryId,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub mapping_visualizations: Vec<MappingVisualization>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub base_content: Vec<Section>,
     pub interaction_system: InteractionSystem,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub animation_timeline: Option<AnimationTimeline>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub control_bindings: Vec<ControlBinding>,
 }
 
@@ -221,11 +475,15 @@ pub struct TypeMappingDisplayContent {
 #[ts(export)]
 pub struct ResourcePanelContent {
     pub title: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub resource_categories: Vec<ResourceCategory>,
     pub search_capabilities: SearchCapabilities,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub filtering_options: Vec<FilterOption>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub base_content: Vec<Section>,
     pub interaction_system: InteractionSystem,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub control_bindings: Vec<ControlBinding>,
 }
 
@@ -235,9 +493,11 @@ pub struct ResourcePanelContent {
 #[ts(export)]
 pub struct ComparisonPageContent {
     pub title: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub comparison_criteria: Vec<ComparisonCriterion>,
     pub highlight_differences: bool,
     pub synchronized_navigation: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub theories_involved: Vec<TheoryReference>,
     pub relationship_metadata: RelationshipMetadata,
     pub comparison_structure: ComparisonStructure,
@@ -250,9 +510,11 @@ pub struct TransformationMappingContent {
     pub transformation_type: TransformationType,
     pub step_by_step: bool,
     pub bidirectional: bool,
-    pub source_theory: String,
-    pub target_theory: String,
+    pub source_theory: TheoryId,
+    pub target_theory: TheoryId,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub transformation_steps: Vec<TransformationStep>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub theories_involved: Vec<TheoryReference>,
     pub relationship_metadata: RelationshipMetadata,
 }
@@ -262,9 +524,12 @@ pub struct TransformationMappingContent {
 pub struct ConceptAlignmentContent {
     pub title: String,
     pub alignment_type: AlignmentType,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub correspondence_mappings: Vec<ConceptCorrespondence>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub theories_involved: Vec<TheoryReference>,
     pub relationship_metadata: RelationshipMetadata,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub alignment_visualizations: Vec<AlignmentVisualization>,
 }
 
@@ -287,6 +552,7 @@ pub struct StaticPreviewContent {
 pub struct LiveEmbedContent {
     pub source_document_id: String,
     pub sync_with_source: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub allowed_interactions: Vec<AllowedInteraction>,
     pub extraction_metadata: ExtractionMetadata,
     pub viewport_config: ViewportConfig,
@@ -297,6 +563,7 @@ pub struct LiveEmbedContent {
 #[ts(export)]
 pub struct ConceptExtractContent {
     pub source_document_id: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub extracted_concepts: Vec<String>,
     pub context_preservation: ContextPreservationLevel,
     pub extraction_metadata: ExtractionMetadata,
@@ -308,6 +575,7 @@ pub struct ConceptExtractContent {
 #[ts(export)]
 pub struct IFrameEmbedContent {
     pub source_document_id: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub sandbox_permissions: Vec<SandboxPermission>,
     pub responsive_scaling: bool,
     pub extraction_metadata: ExtractionMetadata,
@@ -328,31 +596,47 @@ pub enum CompletenessLevel {
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export)]
 pub struct ContentMetadata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub language: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub created_at: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub last_modified: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub content_hash: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[ts(export)]
 pub struct DocumentStructure {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub abstract_content: Option<Section>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub table_of_contents: Option<TocNode>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub body: Vec<Section>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub footnotes: Vec<Section>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub glossary: Vec<Section>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub bibliography: Vec<BibEntry>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export)]
 pub struct DocumentRelationships {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub parent_documents: Vec<String>, // Documents this is derived from
-    pub child_documents: Vec<String>,  // Documents derived from this
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub child_documents: Vec<String>, // Documents derived from this
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub related_concepts: Vec<ConceptReference>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub cross_references: Vec<CrossReference>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub dependency_graph: Option<DependencyGraph>,
 }
 
@@ -362,10 +646,11 @@ pub struct DocumentRelationships {
 #[ts(export)]
 pub struct SourceReference {
     pub source_id: String,
-    pub source_type: String,            // e.g., "WikiPage", "Textbook"
+    pub source_type: String, // e.g., "WikiPage", "Textbook"
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub specific_sections: Vec<String>, // Section IDs referenced
     pub derivation_method: DerivationMethod,
-    pub confidence_level: f64, // 0.0 - 1.0
+    pub confidence_level: UnitInterval,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
@@ -382,8 +667,10 @@ pub enum DerivationMethod {
 #[ts(export)]
 pub struct DerivationMetadata {
     pub derived_at: String, // timestamp
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub derivation_rules: Vec<String>,
     pub human_reviewed: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub accuracy_metrics: Option<AccuracyMetrics>,
 }
 
@@ -409,18 +696,22 @@ pub enum SummarizationLevel {
 #[ts(export)]
 pub struct AnimationTimeline {
     pub total_duration: f64, // seconds
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub keyframes: Vec<AnimationKeyframe>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub interaction_points: Vec<InteractionPoint>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub synchronization_groups: Vec<SyncGroup>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export)]
 pub struct AnimationKeyframe {
-    pub time: f64,                    // seconds from start
+    pub time: f64, // seconds from start
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub target_elements: Vec<String>, // CSS selectors or element IDs
     pub animation_type: AnimationType,
-    pub properties: std::collections::HashMap<String, String>,
+    pub properties: std::collections::BTreeMap<String, String>,
     pub easing: EasingFunction,
 }
 
@@ -444,6 +735,7 @@ pub struct InteractionPoint {
     pub interaction_type: InteractionType,
     pub target_element: String,
     pub required_action: UserAction,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub timeout: Option<f64>,
 }
 
@@ -473,7 +765,7 @@ pub enum UserAction {
 pub struct ConceptReference {
     pub concept_id: String,
     pub relationship_type: ConceptRelationType,
-    pub strength: f64, // 0.0 - 1.0
+    pub strength: UnitInterval,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
@@ -493,9 +785,13 @@ pub enum ConceptRelationType {
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[ts(export)]
 pub struct SimplifiedContentStructure {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub key_points: Vec<KeyPoint>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub essential_definitions: Vec<EssentialDefinition>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub core_examples: Vec<CoreExample>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub concept_relationships: Vec<ConceptRelationship>,
 }
 
@@ -503,8 +799,10 @@ pub struct SimplifiedContentStructure {
 #[ts(export)]
 pub struct KeyPoint {
     pub id: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub content: Vec<RichTextSegment>,
     pub importance_level: ImportanceLevel,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub source_section_id: Option<String>,
 }
 
@@ -521,8 +819,11 @@ pub enum ImportanceLevel {
 #[ts(export)]
 pub struct EssentialDefinition {
     pub term: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub simplified_definition: Vec<RichTextSegment>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub formal_definition: Option<MathNode>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub intuitive_explanation: Option<Vec<RichTextSegment>>,
 }
 
@@ -572,20 +873,29 @@ pub enum InteractionLevel {
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export)]
 pub struct AcademicMetadata {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub authors: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub date_published: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub date_modified: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub venue: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub doi: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub keywords: Vec<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export)]
 pub struct ChapterInfo {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub chapter_number: Option<u32>,
     pub chapter_title: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub prerequisites: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub learning_objectives: Vec<String>,
 }
 
@@ -613,8 +923,11 @@ pub enum NoteStyle {
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export)]
 pub struct InteractionSystem {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub controls: Vec<Control>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub event_handlers: Vec<EventHandler>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub state_variables: Vec<StateVariable>,
 }
 
@@ -659,6 +972,7 @@ pub enum StateVariableType {
 pub struct ControlBinding {
     pub control_id: String,
     pub target_variable: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub transformation: Option<ValueTransformation>,
 }
 
@@ -674,9 +988,13 @@ pub enum ValueTransformation {
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export)]
 pub struct ParameterSpace {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub parameters: Vec<Parameter>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub constraints: Vec<Constraint>,
-    pub default_values: std::collections::HashMap<String, f64>,
+    pub default_values: std::collections::BTreeMap<String, f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variant_seed: Option<u32>, // Draw defaults from the ranges, see `seeded_defaults`
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
@@ -685,6 +1003,7 @@ pub struct Parameter {
     pub name: String,
     pub parameter_type: ParameterType,
     pub range: ParameterRange,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
 }
 
@@ -701,8 +1020,11 @@ pub enum ParameterType {
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export)]
 pub struct ParameterRange {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub min: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub max: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub step: Option<f64>,
 }
 
@@ -710,6 +1032,7 @@ pub struct ParameterRange {
 #[ts(export)]
 pub struct Constraint {
     pub constraint_type: ConstraintType,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub parameters_involved: Vec<String>,
     pub formula: String,
 }
@@ -738,8 +1061,11 @@ pub enum VisualizationType {
 #[ts(export)]
 pub struct MappingVisualization {
     pub visualization_type: VisualizationType,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub source_elements: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub target_elements: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub mapping_arrows: Vec<MappingArrow>,
 }
 
@@ -749,6 +1075,7 @@ pub struct MappingArrow {
     pub from: String,
     pub to: String,
     pub arrow_style: ArrowStyle,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub label: Option<String>,
 }
 
@@ -765,8 +1092,11 @@ pub enum ArrowStyle {
 #[ts(export)]
 pub struct ResourceCategory {
     pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub icon: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub color: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub items: Vec<ResourceItem>,
 }
 
@@ -775,8 +1105,10 @@ pub struct ResourceCategory {
 pub struct ResourceItem {
     pub id: String,
     pub title: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
     pub resource_type: ResourceType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub link: Option<String>,
 }
 
@@ -797,6 +1129,7 @@ pub struct SearchCapabilities {
     pub full_text_search: bool,
     pub semantic_search: bool,
     pub filter_by_type: bool,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub sort_options: Vec<SortOption>,
 }
 
@@ -815,6 +1148,7 @@ pub enum SortOption {
 pub struct FilterOption {
     pub name: String,
     pub filter_type: FilterType,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub options: Vec<String>,
 }
 
@@ -830,8 +1164,9 @@ pub enum FilterType {
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export)]
 pub struct TheoryReference {
-    pub theory_id: String,
+    pub theory_id: TheoryId,
     pub theory_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub version: Option<String>,
 }
 
@@ -839,9 +1174,11 @@ pub struct TheoryReference {
 #[ts(export)]
 pub struct RelationshipMetadata {
     pub relationship_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub strength: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub bidirectional: Option<bool>,
-    pub properties: std::collections::HashMap<String, String>,
+    pub properties: std::collections::BTreeMap<String, String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -849,6 +1186,7 @@ pub struct RelationshipMetadata {
 pub struct ComparisonStructure {
     pub comparison_type: String,
     pub layout: ComparisonLayout,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub sections: Vec<ComparisonSection>,
 }
 
@@ -865,9 +1203,14 @@ pub enum ComparisonLayout {
 #[ts(export)]
 pub struct ComparisonSection {
     pub section_id: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub left_content: Vec<SectionContentNode>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub right_content: Vec<SectionContentNode>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub comparison_notes: Option<Vec<RichTextSegment>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub correspondence_groups: Vec<CorrespondenceGroup>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
@@ -875,7 +1218,9 @@ pub struct ComparisonSection {
 pub struct ComparisonCriterion {
     pub criterion_id: String,
     pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub weight: Option<f64>,
 }
 
@@ -884,6 +1229,7 @@ pub struct ComparisonCriterion {
 pub struct TransformationType {
     pub transformation_id: String,
     pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
     pub reversible: bool,
 }
@@ -893,6 +1239,7 @@ pub struct TransformationType {
 pub struct AlignmentType {
     pub alignment_id: String,
     pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
     pub precision_level: AlignmentPrecision,
 }
@@ -912,6 +1259,7 @@ pub struct ConceptCorrespondence {
     pub source_concept: String,
     pub target_concept: String,
     pub correspondence_type: CorrespondenceType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub confidence: Option<f64>,
 }
 
@@ -930,18 +1278,26 @@ pub enum CorrespondenceType {
 pub struct ExtractionMetadata {
     pub extracted_at: String,
     pub extraction_method: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub source_version: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub extraction_rules: Vec<String>,
-    pub quality_metrics: Option<std::collections::HashMap<String, f64>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quality_metrics: Option<std::collections::BTreeMap<String, f64>>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export)]
 pub struct ViewportConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub width: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub height: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub responsive: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub scroll_behavior: Option<ScrollBehavior>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub zoom_level: Option<f64>,
 }
 
@@ -958,7 +1314,9 @@ pub enum ScrollBehavior {
 #[ts(export)]
 pub struct AllowedInteraction {
     pub interaction_type: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub permissions: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub restrictions: Option<Vec<String>>,
 }
 
@@ -977,6 +1335,7 @@ pub struct ContextPreservationLevel {
 pub struct SandboxPermission {
     pub permission_type: String,
     pub allowed: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub restrictions: Option<Vec<String>>,
 }
 
@@ -985,8 +1344,11 @@ pub struct SandboxPermission {
 pub struct CoreExample {
     pub example_id: String,
     pub title: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub content: Vec<SectionContentNode>,
-    pub difficulty_level: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub difficulty: Option<DifficultyAnnotation>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub concepts_illustrated: Vec<String>,
 }
 
@@ -996,6 +1358,7 @@ pub struct ConceptRelationship {
     pub source_concept: String,
     pub target_concept: String,
     pub relationship_type: ConceptRelationType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
 }
 
@@ -1003,6 +1366,7 @@ pub struct ConceptRelationship {
 #[ts(export)]
 pub struct SyncGroup {
     pub group_id: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub elements: Vec<String>,
     pub sync_type: SyncType,
 }
@@ -1020,6 +1384,7 @@ pub enum SyncType {
 #[ts(export)]
 pub struct EasingFunction {
     pub function_type: EasingType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub parameters: Option<Vec<f64>>,
 }
 
@@ -1039,6 +1404,7 @@ pub enum EasingType {
 #[ts(export)]
 pub struct Direction {
     pub direction_type: DirectionType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub angle: Option<f64>,
 }
 
@@ -1058,6 +1424,7 @@ pub struct CrossReference {
     pub reference_id: String,
     pub target_id: String,
     pub reference_type: CrossReferenceType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub display_text: Option<String>,
 }
 
@@ -1075,9 +1442,12 @@ pub enum CrossReferenceType {
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export)]
 pub struct DependencyGraph {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub nodes: Vec<DependencyNode>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub edges: Vec<DependencyEdge>,
-    pub graph_metadata: Option<std::collections::HashMap<String, String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub graph_metadata: Option<std::collections::BTreeMap<String, String>>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
@@ -1104,7 +1474,8 @@ pub struct DependencyEdge {
     pub from_node: String,
     pub to_node: String,
     pub dependency_type: DependencyType,
-    pub strength: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub strength: Option<UnitInterval>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
@@ -1121,11 +1492,14 @@ pub enum DependencyType {
 #[ts(export)]
 pub struct TransformationStep {
     pub step_number: usize,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub description: Vec<RichTextSegment>,
     pub source_concept: String,
     pub target_concept: String,
     pub transformation_rule: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub visual_representation: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub interactive_demo: Option<String>,
 }
 
@@ -1133,8 +1507,11 @@ pub struct TransformationStep {
 #[ts(export)]
 pub struct AlignmentVisualization {
     pub visualization_type: VisualizationType,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub source_elements: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub target_elements: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub alignment_arrows: Vec<AlignmentArrow>,
 }
 
@@ -1143,7 +1520,7 @@ pub struct AlignmentVisualization {
 pub struct AlignmentArrow {
     pub from_concept: String,
     pub to_concept: String,
-    pub alignment_strength: f64, // 0.0 - 1.0
+    pub alignment_strength: UnitInterval,
     pub alignment_type: String,
     pub visual_style: ArrowStyle,
 }
@@ -1153,15 +1530,17 @@ pub struct AlignmentArrow {
 pub struct TocNode {
     pub title: String,     // e.g., "1. Introduction"
     pub target_id: String, // ID of the Section
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub children: Vec<TocNode>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export)]
 pub struct BibEntry {
+    pub key: String,        // Citation key, the target of `LinkTarget::BibliographyKey`
     pub entry_type: String, // e.g., "article", "book", "inproceedings"
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub fields: Vec<(String, String)>, // BibTeX-like fields (author, title, year, journal, etc.)
-                            // The key for this entry would be the key in the bibliography HashMap.
                             // pub formatted_citation_html: Option<String>, // Could be pre-rendered by a citation processor
 }
 
@@ -1185,6 +1564,7 @@ pub struct Control {
     pub control_type: ControlType,
     pub parameter_name: String,
     pub default_value: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
 }
 
@@ -1206,9 +1586,11 @@ pub enum ControlType {
 #[ts(export)]
 pub struct PresentationConfig {
     pub layout_style: LayoutStyle,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub interaction_features: Vec<InteractionFeature>,
     pub target_audience: AudienceLevel,
     pub formality_level: FormalityLevel,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub animation_config: Option<AnimationConfig>,
 }
 
@@ -1235,6 +1617,7 @@ pub enum InteractionFeature {
     TypeAnnotations,
     HighlightCorrespondence,
     ParameterAdjustment,
+    FeedbackCollection,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
@@ -1250,8 +1633,125 @@ pub enum FormalityLevel {
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export)]
 pub struct AnimationConfig {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub enabled_animations: Vec<String>,
     pub animation_speed: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub auto_play: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub show_controls: Option<bool>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::super::{BranchingNode, ListNode, RichText, SectionDisplayOptions, TableNode};
+    use super::*;
+    use serde::de::DeserializeOwned;
+    use serde_json::{Value, json};
+
+    /// The fields the TypeScript declaration of `T` requires, i.e. those without a `?`.
+    fn required_fields<T: TS>() -> Vec<String> {
+        let declaration = T::decl();
+        let body = &declaration[declaration.find('{').expect("an object type") + 1..];
+        let mut fields = vec![];
+        let mut depth = 0;
+        let mut at_field = true;
+        let mut name = String::new();
+        for c in body.chars() {
+            match c {
+                '{' | '<' | '(' | '[' => depth += 1,
+                '}' | '>' | ')' | ']' if depth == 0 => break,
+                '}' | '>' | ')' | ']' => depth -= 1,
+                ',' | ';' if depth == 0 => at_field = true,
+                ':' | '?' if depth == 0 && at_field => {
+                    if c == ':' {
+                        fields.push(name.clone());
+                    }
+                    name.clear();
+                    at_field = false;
+                }
+                _ if depth == 0 && at_field && !c.is_whitespace() => name.push(c),
+                _ => {}
+            }
+        }
+        fields
+    }
+
+    /// `compact` has only the fields TypeScript requires of `T`, and reads and writes back as
+    /// it is.
+    fn assert_compact<T: TS + Serialize + DeserializeOwned>(compact: Value) {
+        let object = compact.as_object().expect("an object");
+        let mut present: Vec<String> = object.keys().cloned().collect();
+        let mut required = required_fields::<T>();
+        present.sort();
+        required.sort();
+        assert_eq!(present, required, "{}", T::ident());
+
+        let value: T = serde_json::from_value(compact.clone()).unwrap();
+        assert_eq!(
+            serde_json::to_value(&value).unwrap(),
+            compact,
+            "{}",
+            T::ident()
+        );
+    }
+
+    fn identifier() -> Value {
+        json!({
+            "id": "x",
+            "content": {
+                "kind": "Identifier",
+                "data": {
+                    "body": "x",
+                    "pre_script": null,
+                    "mid_script": null,
+                    "post_script": null,
+                    "primes": 0,
+                    "is_function": false
+                }
+            }
+        })
+    }
+
+    #[test]
+    fn empty_structs_serialize_as_empty_objects() {
+        assert_compact::<ContentMetadata>(json!({}));
+        assert_compact::<DocumentStructure>(json!({}));
+        assert_compact::<DocumentRelationships>(json!({}));
+        assert_compact::<ViewportConfig>(json!({}));
+        assert_compact::<SectionDisplayOptions>(json!({}));
+        assert_compact::<RichText>(json!({}));
+    }
+
+    #[test]
+    fn compact_sections_and_nodes_match_their_types() {
+        assert_compact::<Section>(json!({
+            "id": "s",
+            "content": { "kind": "Math", "data": identifier() }
+        }));
+        assert_compact::<ListNode>(json!({ "style": { "Unordered": "Disc" } }));
+        assert_compact::<TableNode>(json!({}));
+        assert_compact::<BranchingNode>(json!({
+            "node_id": "n",
+            "node_type": "ProofStep",
+            "node_state": "Completed"
+        }));
+    }
+
+    #[test]
+    fn compact_documents_match_their_types() {
+        let wiki = json!({
+            "title": "Groups",
+            "theory_domain": "GroupTheory",
+            "completeness_level": "Stub",
+            "content_metadata": {},
+            "structure": {},
+            "relationships": {}
+        });
+        assert_compact::<WikiPageContent>(wiki.clone());
+        assert_compact::<MathDocument>(json!({
+            "id": "groups",
+            "content_type": { "kind": "WikiPage", "data": wiki }
+        }));
+    }
+}
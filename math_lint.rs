@@ -0,0 +1,123 @@
+use super::*;
+use std::fmt;
+
+/// A math structure that renders fine but is probably not what the author meant, typically a
+/// slip in generated content.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MathLint {
+    IdenticalSides {
+        path: NodePath, // the relationship, whose sides are canonically equal
+    },
+    ZeroDenominator {
+        path: NodePath, // the division or fraction
+    },
+    NoQuantifiedVariables {
+        path: NodePath,
+    },
+    RedundantBrackets {
+        path: NodePath, // the inner bracket, of the same style as the one around it
+        style: BracketStyle,
+    },
+}
+
+impl MathLint {
+    pub fn path(&self) -> &NodePath {
+        match self {
+            MathLint::IdenticalSides { path }
+            | MathLint::ZeroDenominator { path }
+            | MathLint::NoQuantifiedVariables { path }
+            | MathLint::RedundantBrackets { path, .. } => path,
+        }
+    }
+}
+
+impl MathNode {
+    /// Suspicious structures in this expression, in pre-order:
+    /// - relationships whose two sides are the same expression, e.g. `x = x`
+    /// - divisions and fractions whose denominator is the literal `0`
+    /// - quantified expressions without variables
+    /// - brackets directly inside brackets of the same style, e.g. `((x))`
+    ///
+    /// Unlike `validate`, these are not errors: `x = x` can be intended, in an example of
+    /// reflexivity say.
+    pub fn lint(&self) -> Vec<MathLint> {
+        let mut lints = vec![];
+        for (path, node) in self.descendants_with_paths() {
+            lints.extend(lint_node(&node.content, path));
+        }
+        lints
+    }
+}
+
+fn lint_node(content: &MathNodeContent, path: NodePath) -> Option<MathLint> {
+    match content {
+        MathNodeContent::Relationship { lhs, rhs, .. } if lhs.canonically_eq(rhs) => {
+            Some(MathLint::IdenticalSides { path })
+        }
+        MathNodeContent::Division { denominator, .. }
+        | MathNodeContent::Fraction { denominator, .. }
+            if is_literal_zero(denominator) =>
+        {
+            Some(MathLint::ZeroDenominator { path })
+        }
+        MathNodeContent::QuantifiedExpression { variables, .. } if variables.is_empty() => {
+            Some(MathLint::NoQuantifiedVariables { path })
+        }
+        MathNodeContent::Bracketed { inner, style, .. } => match inner.content.as_ref() {
+            MathNodeContent::Bracketed {
+                style: inner_style, ..
+            } if inner_style == style && *style != BracketStyle::None => {
+                Some(MathLint::RedundantBrackets {
+                    path: path.field("inner"),
+                    style: style.clone(),
+                })
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Whether `node` is a unitless quantity equal to zero, possibly in brackets.
+fn is_literal_zero(node: &MathNode) -> bool {
+    match node.content.as_ref() {
+        MathNodeContent::Quantity {
+            number,
+            scientific_notation: None,
+            unit: None,
+        } => number.trim().parse::<f64>() == Ok(0.0),
+        MathNodeContent::Bracketed { inner, .. } => is_literal_zero(inner),
+        _ => false,
+    }
+}
+
+impl fmt::Display for MathLint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let at = |path: &NodePath| {
+            if path.is_root() {
+                "the root".to_string()
+            } else {
+                format!("`{}`", path)
+            }
+        };
+        match self {
+            MathLint::IdenticalSides { path } => {
+                write!(f, "relationship at {} has identical sides", at(path))
+            }
+            MathLint::ZeroDenominator { path } => {
+                write!(f, "division at {} has a zero denominator", at(path))
+            }
+            MathLint::NoQuantifiedVariables { path } => write!(
+                f,
+                "quantified expression at {} quantifies no variables",
+                at(path)
+            ),
+            MathLint::RedundantBrackets { path, style } => write!(
+                f,
+                "brackets at {} are directly inside brackets of the same style ({:?})",
+                at(path),
+                style
+            ),
+        }
+    }
+}
@@ -0,0 +1,480 @@
+use super::*;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use ts_rs::TS;
+
+/// Size and shape of a `MathNode` tree, for layout decisions and difficulty estimation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct MathMetrics {
+    pub node_count: usize,
+    pub depth: usize, // 1 for a single leaf
+    pub leaf_count: usize,
+    pub identifier_count: usize,
+    pub operator_histogram: Vec<(String, usize)>, // Most frequent first, then by name
+    pub visual_width: f64, // Estimated rendered width in em, see `MathNode::visual_width`
+}
+
+/// How math-heavy a section is, counting everything nested in it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct SectionMetrics {
+    pub section_id: String,
+    pub word_count: usize,
+    pub expression_count: usize, // Embedded expressions, inline or display
+    pub math_node_count: usize,
+    pub max_depth: usize,
+    pub widest_expression: f64,  // In em
+    pub math_per_100_words: f64, // Expressions per 100 words of prose
+}
+
+impl MathNode {
+    pub fn metrics(&self) -> MathMetrics {
+        let mut histogram = OperatorHistogram::default();
+        self.accept(&mut histogram);
+        let mut operator_histogram = histogram.counts;
+        operator_histogram
+            .sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then_with(|| a.cmp(b)));
+
+        let (node_count, depth, leaf_count) =
+            self.fold_up(|_, children: Vec<(usize, usize, usize)>| {
+                if children.is_empty() {
+                    return (1, 1, 1);
+                }
+                children.into_iter().fold(
+                    (1, 1, 0),
+                    |(count, depth, leaves), (child_count, child_depth, child_leaves)| {
+                        (
+                            count + child_count,
+                            depth.max(child_depth + 1),
+                            leaves + child_leaves,
+                        )
+                    },
+                )
+            });
+        MathMetrics {
+            node_count,
+            depth,
+            leaf_count,
+            identifier_count: self.fold(0, |count, node| {
+                count + usize::from(matches!(*node.content, MathNodeContent::Identifier(_)))
+            }),
+            operator_histogram,
+            visual_width: self.visual_width(),
+        }
+    }
+
+    /// Rough rendered width in em at the base font size. Glyph widths are averaged and
+    /// scripts are assumed to be set at 70%, so this is meant for comparing expressions and
+    /// choosing between inline and display layouts, not for exact placement.
+    pub fn visual_width(&self) -> f64 {
+        const GLYPH: f64 = 0.55;
+        const OPERATOR: f64 = 1.0; // an operator with its surrounding spaces
+        const BRACKETS: f64 = 0.8;
+        const SCRIPT: f64 = 0.7;
+        const BAR: f64 = 0.3; // a vertical bar, of Dirac notation, a norm or a determinant
+
+        let width = |node: &MathNode| node.visual_width();
+        let text = |text: &str| text.chars().count() as f64 * GLYPH;
+        let scripts = |script: &Option<ScriptNode>| {
+            script.as_ref().map_or(0.0, |script| {
+                let run = |nodes: &[MathNode]| nodes.iter().map(width).sum::<f64>();
+                run(&script.subscripts).max(run(&script.superscripts)) * SCRIPT
+            })
+        };
+        let sequence = |nodes: &mut dyn Iterator<Item = &MathNode>| {
+            let widths: Vec<f64> = nodes.map(width).collect();
+            widths.iter().sum::<f64>() + widths.len().saturating_sub(1) as f64 * OPERATOR
+        };
+
+        match self.content.as_ref() {
+            MathNodeContent::Empty => 0.0,
+            MathNodeContent::True | MathNodeContent::False => text("True"),
+            MathNodeContent::Text(string) | MathNodeContent::String(string) => text(string),
+            MathNodeContent::Phantom { inner, extent } => match extent {
+                PhantomExtent::Full | PhantomExtent::Horizontal => width(inner),
+                PhantomExtent::Vertical => 0.0,
+            },
+            MathNodeContent::Space(space) => space.width_em().unwrap_or(0.0),
+            MathNodeContent::Quantity { number, unit, .. } => {
+                text(number) + unit.as_ref().map_or(0.0, |unit| 0.2 + width(unit))
+            }
+            MathNodeContent::Identifier(identifier) => {
+                text(&identifier.body)
+                    + identifier.primes as f64 * 0.3
+                    + scripts(&identifier.pre_script)
+                    + scripts(&identifier.post_script)
+            }
+            MathNodeContent::Bracketed { inner, .. } => width(inner) + BRACKETS,
+            MathNodeContent::Abs { parameter } => width(parameter) + BRACKETS,
+            MathNodeContent::Norm { inner, subscript } => {
+                width(inner)
+                    + 4.0 * BAR
+                    + subscript
+                        .as_ref()
+                        .map_or(0.0, |subscript| width(subscript) * SCRIPT)
+            }
+            MathNodeContent::Decorated { inner, .. } => width(inner),
+            MathNodeContent::Styled {
+                inner, emphasis, ..
+            } => match emphasis {
+                Some(MathEmphasis::Boxed) => width(inner) + BRACKETS,
+                _ => width(inner),
+            },
+            MathNodeContent::AngleMeasure { value, unit } => match unit {
+                AngleUnit::Degrees => width(value) + 0.4,
+                AngleUnit::Radians => width(value) + text(" rad"),
+            },
+            MathNodeContent::AngleSymbol { vertices } => {
+                text("∠") + vertices.iter().map(width).sum::<f64>()
+            }
+            MathNodeContent::Coordinates { point, components } => {
+                let separators = components.len().saturating_sub(1) as f64 * 0.5;
+                point.as_ref().map_or(0.0, |point| width(point))
+                    + BRACKETS
+                    + components.iter().map(width).sum::<f64>()
+                    + separators
+            }
+            MathNodeContent::Cancelled {
+                inner, replacement, ..
+            } => {
+                width(inner)
+                    + replacement
+                        .as_ref()
+                        .map_or(0.0, |replacement| width(replacement) * SCRIPT)
+            }
+            MathNodeContent::Brace { inner, label, .. } => width(inner).max(width(label) * SCRIPT),
+            MathNodeContent::SetExplicit { elements } => sequence(&mut elements.iter()) + BRACKETS,
+            MathNodeContent::SetBuilder {
+                variable,
+                domain,
+                predicate,
+            } => {
+                let domain = domain
+                    .as_ref()
+                    .map_or(0.0, |domain| OPERATOR + width(domain));
+                width(variable) + domain + OPERATOR + width(predicate) + BRACKETS
+            }
+            MathNodeContent::Bra { state } | MathNodeContent::Ket { state } => {
+                width(state) + BRACKETS
+            }
+            MathNodeContent::BraKet { bra, operator, ket } => {
+                let operator = operator
+                    .as_ref()
+                    .map_or(0.0, |operator| width(operator) + BAR);
+                width(bra) + operator + BAR + width(ket) + BRACKETS
+            }
+            MathNodeContent::Expectation { operator, state } => {
+                let state = state
+                    .as_ref()
+                    .map_or(0.0, |state| 2.0 * (width(state) + BAR));
+                width(operator) + state + BRACKETS
+            }
+            MathNodeContent::BinaryOperation { terms, .. } => {
+                sequence(&mut terms.iter().map(|(_, term)| term))
+            }
+            MathNodeContent::Multiplications { terms } => {
+                sequence(&mut terms.iter().map(|(_, term)| term))
+            }
+            MathNodeContent::Additions { terms } => {
+                sequence(&mut terms.iter().map(|(_, term)| term))
+            }
+            MathNodeContent::And(terms) | MathNodeContent::Or(terms) => sequence(&mut terms.iter()),
+            MathNodeContent::Not(inner) => GLYPH + width(inner),
+            MathNodeContent::Sequent {
+                context,
+                conclusion,
+                ..
+            } => {
+                let separators = context.len().saturating_sub(1) as f64 * 0.5;
+                context.iter().map(width).sum::<f64>() + separators + OPERATOR + width(conclusion)
+            }
+            MathNodeContent::Fraction {
+                numerator,
+                denominator,
+            } => width(numerator).max(width(denominator)) + 0.4,
+            MathNodeContent::Division {
+                numerator,
+                denominator,
+                ..
+            } => width(numerator) + OPERATOR + width(denominator),
+            MathNodeContent::Power { base, exponent } => width(base) + width(exponent) * SCRIPT,
+            MathNodeContent::Radical { radicand, index } => {
+                GLYPH + width(radicand) + index.as_ref().map_or(0.0, |index| width(index) * SCRIPT)
+            }
+            MathNodeContent::Binomial {
+                upper,
+                lower,
+                style,
+            } => match style {
+                BinomialStyle::Parenthesized => width(upper).max(width(lower)) + BRACKETS,
+                BinomialStyle::Function => 2.0 * GLYPH + width(upper) + width(lower) + BRACKETS,
+                BinomialStyle::Prefix => GLYPH + (width(upper) + width(lower)) * SCRIPT,
+            },
+            MathNodeContent::Cases { branches } => {
+                let column = |widths: &mut dyn Iterator<Item = f64>| widths.fold(0.0, f64::max);
+                BRACKETS / 2.0
+                    + column(&mut branches.iter().map(|(expression, _)| width(expression)))
+                    + 2.0 * OPERATOR
+                    + column(&mut branches.iter().map(|(_, condition)| width(condition)))
+            }
+            MathNodeContent::SumNotation {
+                summand: body,
+                lower_limit,
+                upper_limit,
+                ..
+            }
+            | MathNodeContent::ProductNotation {
+                multiplicand: body,
+                lower_limit,
+                upper_limit,
+                ..
+            } => {
+                let limit = |limit: &Option<Arc<MathNode>>| {
+                    limit.as_deref().map_or(0.0, |limit| width(limit) * SCRIPT)
+                };
+                1.2_f64.max(limit(lower_limit)).max(limit(upper_limit)) + 0.2 + width(body)
+            }
+            MathNodeContent::Integration {
+                integrand,
+                differentials,
+                ..
+            } => {
+                let signs = differentials.len().max(1) as f64 * 0.9;
+                let bounds: f64 = differentials
+                    .iter()
+                    .map(|(_, lower, upper)| {
+                        let bound = |b: &Option<Arc<MathNode>>| b.as_deref().map_or(0.0, width);
+                        bound(lower).max(bound(upper)) * SCRIPT
+                    })
+                    .sum();
+                let differential_widths: f64 = differentials
+                    .iter()
+                    .map(|(differential, _, _)| 0.2 + width(differential))
+                    .sum();
+                signs + bounds + width(integrand) + differential_widths
+            }
+            MathNodeContent::Differential {
+                target,
+                order,
+                notation,
+                variable,
+                ..
+            } => {
+                let order = width(order) * SCRIPT;
+                match (variable, notation) {
+                    (None, _) => GLYPH + order + width(target),
+                    (Some(variable), DerivativeNotation::Leibniz) => {
+                        (GLYPH + order + width(target)).max(GLYPH + width(variable) + order) + 0.4
+                    }
+                    (Some(_), DerivativeNotation::Lagrange) => width(target) + order,
+                    (Some(_), DerivativeNotation::Newton) => width(target),
+                    (Some(variable), DerivativeNotation::Operator) => {
+                        GLYPH + (width(variable) * SCRIPT).max(order) + width(target)
+                    }
+                }
+            }
+            MathNodeContent::Limit {
+                function,
+                variable,
+                approaching_value,
+            } => {
+                let subscript = (text(variable) + OPERATOR + width(approaching_value)) * SCRIPT;
+                text("lim").max(subscript) + 0.2 + width(function)
+            }
+            MathNodeContent::FunctionCall { name, parameters } => {
+                let separators = parameters.len().saturating_sub(1) as f64 * 0.5;
+                width(name) + BRACKETS + parameters.iter().map(width).sum::<f64>() + separators
+            }
+            MathNodeContent::Matrix {
+                rows,
+                delimiters,
+                augmented_after,
+                row_labels,
+                highlights,
+                ..
+            } => {
+                let columns = rows.iter().map(Vec::len).max().unwrap_or(0);
+                let column_widths: f64 = (0..columns)
+                    .map(|j| {
+                        rows.iter()
+                            .filter_map(|row| row.get(j))
+                            .map(width)
+                            .fold(0.0, f64::max)
+                    })
+                    .sum();
+                let delimiters = match delimiters {
+                    Some(MatrixDelimiters::None) => 0.0,
+                    Some(MatrixDelimiters::Bars) => 2.0 * BAR,
+                    Some(MatrixDelimiters::DoubleBars) => 4.0 * BAR,
+                    _ => 2.0 * BRACKETS,
+                };
+                // Row labels are set in a column to the left, notes on rows to the right
+                let labels = row_labels.iter().map(width).fold(0.0, f64::max);
+                let notes = highlights
+                    .iter()
+                    .filter(|highlight| highlight.axis == MatrixAxis::Row)
+                    .filter_map(|highlight| highlight.annotation.as_ref())
+                    .map(width)
+                    .fold(0.0, f64::max);
+                let margin = |side: f64| if side > 0.0 { side + OPERATOR } else { 0.0 };
+                column_widths
+                    + columns.saturating_sub(1) as f64 * OPERATOR
+                    + augmented_after.map_or(0.0, |_| BAR)
+                    + delimiters
+                    + margin(labels)
+                    + margin(notes)
+            }
+            MathNodeContent::Relationship { lhs, rhs, .. } => {
+                width(lhs) + 1.5 * OPERATOR + width(rhs)
+            }
+            MathNodeContent::Unit { original_form, .. } => width(original_form),
+            MathNodeContent::ChemicalExpression(expression) => text(&expression.to_string()),
+            MathNodeContent::Folded { summary, .. } => text(summary),
+            _ => sequence(
+                &mut self
+                    .content
+                    .children_with_paths()
+                    .into_iter()
+                    .map(|(_, child)| child),
+            ),
+        }
+    }
+}
+
+impl Section {
+    pub fn metrics(&self) -> SectionMetrics {
+        let mut collector = SectionMetricsCollector::default();
+        collector.visit_content_node(&self.content);
+        if let Some(title) = &self.title {
+            collector.words += title.segments.iter().map(segment_words).sum::<usize>();
+        }
+        SectionMetrics {
+            section_id: self.id.clone(),
+            word_count: collector.words,
+            expression_count: collector.expressions,
+            math_node_count: collector.math_nodes,
+            max_depth: collector.max_depth,
+            widest_expression: collector.widest,
+            math_per_100_words: match collector.words {
+                0 => 0.0,
+                words => collector.expressions as f64 * 100.0 / words as f64,
+            },
+        }
+    }
+}
+
+impl MathDocument {
+    /// Metrics for every section, nested and embedded ones included, in document order.
+    pub fn section_metrics(&self) -> Vec<SectionMetrics> {
+        self.sections().into_iter().map(Section::metrics).collect()
+    }
+}
+
+fn segment_words(segment: &RichTextSegment) -> usize {
+    match segment {
+        RichTextSegment::Text(text) | RichTextSegment::StyledText { text, .. } => {
+            text.split_whitespace().count()
+        }
+        _ => 0,
+    }
+}
+
+#[derive(Default)]
+struct SectionMetricsCollector {
+    words: usize,
+    expressions: usize,
+    math_nodes: usize,
+    max_depth: usize,
+    widest: f64,
+}
+
+impl<'doc> DocumentVisitor<'doc> for SectionMetricsCollector {
+    fn visit_segment(&mut self, segment: &'doc RichTextSegment) {
+        self.words += segment_words(segment);
+        walk_segment(self, segment);
+    }
+
+    fn visit_math_node(&mut self, node: &'doc MathNode) {
+        let metrics = node.metrics();
+        self.expressions += 1;
+        self.math_nodes += metrics.node_count;
+        self.max_depth = self.max_depth.max(metrics.depth);
+        self.widest = self.widest.max(metrics.visual_width);
+    }
+}
+
+#[derive(Default)]
+struct OperatorHistogram {
+    counts: Vec<(String, usize)>,
+}
+
+impl OperatorHistogram {
+    fn add(&mut self, name: String) {
+        match self
+            .counts
+            .iter_mut()
+            .find(|(existing, _)| *existing == name)
+        {
+            Some((_, count)) => *count += 1,
+            None => self.counts.push((name, 1)),
+        }
+    }
+}
+
+impl MathNodeVisitor for OperatorHistogram {
+    fn visit_node(&mut self, node: &MathNode) {
+        match node.content.as_ref() {
+            // The first term of an operation carries no operator of its own.
+            MathNodeContent::BinaryOperation { terms, .. } => {
+                for (operator, _) in terms.iter().skip(1) {
+                    self.add(match operator {
+                        BinaryOperator::Custom(symbol) => symbol.clone(),
+                        operator => format!("{:?}", operator),
+                    });
+                }
+            }
+            MathNodeContent::Multiplications { terms } => {
+                for _ in terms.iter().skip(1) {
+                    self.add("Times".to_string());
+                }
+            }
+            MathNodeContent::Additions { terms } => {
+                for (operator, _) in terms.iter().skip(1) {
+                    self.add(match operator {
+                        RefinedAddOrSubOperator::Subtraction => "Minus".to_string(),
+                        _ => "Plus".to_string(),
+                    });
+                }
+            }
+            MathNodeContent::Relationship { operator, .. } => {
+                self.add(format!("{:?}", operator));
+            }
+            MathNodeContent::Sequent { turnstile, .. } => {
+                self.add(format!("{:?}", turnstile));
+            }
+            MathNodeContent::Fraction { .. }
+            | MathNodeContent::Division { .. }
+            | MathNodeContent::Power { .. }
+            | MathNodeContent::Radical { .. }
+            | MathNodeContent::Binomial { .. }
+            | MathNodeContent::Cases { .. }
+            | MathNodeContent::SumNotation { .. }
+            | MathNodeContent::ProductNotation { .. }
+            | MathNodeContent::Integration { .. }
+            | MathNodeContent::Limit { .. }
+            | MathNodeContent::Differential { .. }
+            | MathNodeContent::Abs { .. }
+            | MathNodeContent::Norm { .. }
+            | MathNodeContent::BraKet { .. }
+            | MathNodeContent::Expectation { .. }
+            | MathNodeContent::FunctionCall { .. }
+            | MathNodeContent::QuantifiedExpression { .. }
+            | MathNodeContent::And(_)
+            | MathNodeContent::Or(_)
+            | MathNodeContent::Not(_) => self.add(node.content.kind_name().to_string()),
+            _ => {}
+        }
+        walk_node(self, node);
+    }
+}
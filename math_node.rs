@@ -2,7 +2,7 @@ use std::sync::Arc;
 
 use crate::subjects::math::formalism::location::Located;
 use crate::subjects::math::formalism::relations::MathRelation;
-use crate::turn_render::{RichText, TextStyle};
+use crate::turn_render::{ChemicalExpression, RichText, SetType, TextStyle};
 
 /// Simple text segments for mathematical expressions
 #[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize, TS)]
@@ -51,6 +51,24 @@ impl MathNode {
         )
     }
 
+    /// The kind of set this node writes: a set literal or set-builder notation, one of the
+    /// standard number sets ℕ, ℤ, ℚ, ℝ and ℂ, or ∅.
+    pub fn set_type(&self) -> Option<SetType> {
+        match self.content.as_ref() {
+            MathNodeContent::SetExplicit { elements } if elements.is_empty() => {
+                Some(SetType::Empty)
+            }
+            MathNodeContent::SetExplicit { .. } => Some(SetType::Explicit),
+            MathNodeContent::SetBuilder { .. } => Some(SetType::Implicit),
+            MathNodeContent::Identifier(identifier) => match identifier.body.as_str() {
+                "ℕ" | "ℤ" | "ℚ" | "ℝ" | "ℂ" => Some(SetType::Standard),
+                "∅" => Some(SetType::Empty),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
     pub fn identifier(input: Identifier) -> MathNode {
         MathNode {
             id: input.body.clone(),
@@ -74,6 +92,7 @@ impl MathNode {
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize, TS)]
+#[serde(tag = "kind", content = "data")]
 #[ts(export)]
 pub enum MathNodeContent {
     Empty, // dummy placeholder
@@ -82,6 +101,16 @@ pub enum MathNodeContent {
 
     String(String), // this is in latex math font
 
+    // Takes up the room `inner` would, across, up and down or both, without drawing it, to line
+    // up expressions across steps: \phantom, \hphantom, \vphantom
+    Phantom {
+        inner: Arc<MathNode>,
+        extent: PhantomExtent,
+    },
+
+    // explicit horizontal space, \, \: \; \quad, or the negative \!
+    Space(MathSpace),
+
     // bracketed scopes
     Bracketed {
         inner: Arc<MathNode>,
@@ -89,9 +118,21 @@ pub enum MathNodeContent {
         size: BracketSize,
     },
 
-    // matrix and tensor
+    // matrix and tensor; the decorations are for row-reduction walkthroughs: a bar after the
+    // first `augmented_after` columns as in [A | b], labels beside the rows (R₁, R₂) and above
+    // the columns, and highlighted rows or columns with a note such as R₂ → R₂ − 2R₁
     Matrix {
         rows: Vec<Vec<MathNode>>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        delimiters: Option<MatrixDelimiters>, // square brackets when absent
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        augmented_after: Option<usize>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        row_labels: Vec<MathNode>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        column_labels: Vec<MathNode>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        highlights: Vec<MatrixHighlight>,
     },
 
     // Generalized binary operations (associative)
@@ -136,6 +177,25 @@ pub enum MathNodeContent {
         exponent: Arc<MathNode>,
     },
 
+    // √x, or ∛x and other roots with an index, drawn with a radical sign rather than as a power
+    Radical {
+        radicand: Arc<MathNode>,
+        index: Option<Arc<MathNode>>, // None for a square root
+    },
+
+    // The number of ways to choose `lower` things from `upper`: (n k), C(n, k) or ⁿCₖ
+    Binomial {
+        upper: Arc<MathNode>,
+        lower: Arc<MathNode>,
+        style: BinomialStyle,
+    },
+
+    // A piecewise definition: a left brace before the branches, one per line, with the
+    // expressions and their conditions aligned in two columns
+    Cases {
+        branches: Vec<(MathNode, MathNode)>, // (expression, condition), "otherwise" as Text
+    },
+
     UnaryPostfixOperation {
         parameter: Arc<MathNode>,
         operator: Arc<MathNode>, // "!", "T", "%"
@@ -152,6 +212,84 @@ pub enum MathNodeContent {
         parameter: Arc<MathNode>,
     },
 
+    // ‖x‖, or ‖x‖₂ and ‖x‖_∞ with the norm named by a subscript after the closing bars
+    Norm {
+        inner: Arc<MathNode>,
+        subscript: Option<Arc<MathNode>>,
+    },
+
+    // A vector mark over or on the whole of `inner`, →(AB) as well as →v, where an identifier's
+    // middle script only reaches a single symbol
+    Decorated {
+        inner: Arc<MathNode>,
+        decoration: VectorDecoration,
+    },
+
+    // A brace over or under `inner` with `label` set beyond it, as in a derivation that names
+    // a subexpression: x² + 2x + 1 with "(x + 1)²" under the brace
+    Brace {
+        inner: Arc<MathNode>,
+        label: Arc<MathNode>,
+        position: BracePosition,
+    },
+
+    // A subterm picked out by color or emphasis, so that an explanation can refer to it as
+    // "the term in red"; colors are CSS colors
+    Styled {
+        inner: Arc<MathNode>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        color: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        background: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        emphasis: Option<MathEmphasis>,
+    },
+
+    // A term struck out as it cancels in a step-by-step simplification, with what it cancels
+    // to at the end of the stroke if anything, as in \cancelto{0}{x - x}
+    Cancelled {
+        inner: Arc<MathNode>,
+        cancel_style: CancelStyle,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        replacement: Option<Arc<MathNode>>,
+    },
+
+    // An angle with its unit, 30° or π/2 rad
+    AngleMeasure {
+        value: Arc<MathNode>,
+        unit: AngleUnit,
+    },
+
+    // The angle at the middle vertex, ∠ABC, or ∠A with a single vertex
+    AngleSymbol {
+        vertices: Vec<MathNode>,
+    },
+
+    // A point or vector by its coordinates, (1, 2), or P(1, 2, 3) with the point named
+    Coordinates {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        point: Option<Arc<MathNode>>,
+        components: Vec<MathNode>,
+    },
+
+    // Dirac notation for quantum states: the bra ⟨ψ|, the ket |ψ⟩, the inner product ⟨φ|ψ⟩ or
+    // the matrix element ⟨φ|A|ψ⟩, and the expectation value ⟨ψ|A|ψ⟩ or ⟨A⟩
+    Bra {
+        state: Arc<MathNode>,
+    },
+    Ket {
+        state: Arc<MathNode>,
+    },
+    BraKet {
+        bra: Arc<MathNode>,
+        operator: Option<Arc<MathNode>>,
+        ket: Arc<MathNode>,
+    },
+    Expectation {
+        operator: Arc<MathNode>,
+        state: Option<Arc<MathNode>>, // None for ⟨A⟩, the state left implicit
+    },
+
     // general function names
     FunctionCall {
         name: Arc<MathNode>,
@@ -176,6 +314,9 @@ pub enum MathNodeContent {
         flattened_form: Arc<MathNode>, // multiplication
     },
 
+    // a chemical formula or reaction, \ce{...} in mhchem
+    ChemicalExpression(ChemicalExpression),
+
     // universal relations for all theories
     Relationship {
         lhs: Arc<MathNode>,
@@ -197,6 +338,8 @@ pub enum MathNodeContent {
     FunctionDefinition {
         custom_function: Arc<MathNode>, // this ia MathNodeContent::FunctionCall
         definition: Option<MathNode>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        clauses: Vec<FunctionClause>, // piecewise definition, e.g. f(0) = 1; f(n) = n·f(n−1)
     },
 
     // Calculus
@@ -205,10 +348,16 @@ pub enum MathNodeContent {
         variable: String,
         approaching_value: Arc<MathNode>,
     },
+    // The differential `d target` on its own, as in `∫ f dx`, or with a variable the derivative
+    // of `target` with respect to it, written in `notation`
     Differential {
         target: Arc<MathNode>,
         order: Arc<MathNode>,
         diff_style: DifferentialStyle,
+        #[serde(default)]
+        notation: DerivativeNotation, // Only used with a variable
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        variable: Option<Arc<MathNode>>,
     },
     Integration {
         integrand: Arc<MathNode>,
@@ -225,6 +374,17 @@ pub enum MathNodeContent {
         predicate: Option<Arc<MathNode>>, // Optional predicate (the ": P(x)" part)
     },
 
+    // Sets written out, see `SetType`: the elements of {1, 2, 3} (none for ∅ written as {}),
+    // and {x ∈ S | P(x)}, which binds the variable in the predicate like a quantifier
+    SetExplicit {
+        elements: Vec<MathNode>,
+    },
+    SetBuilder {
+        variable: Arc<MathNode>,
+        domain: Option<Arc<MathNode>>, // The "∈ S" part
+        predicate: Arc<MathNode>,
+    },
+
     // Group Theory Operations now use BinaryOperation variant with appropriate BinaryOperationType
     // Examples:
     // - GroupQuotient: BinaryOperation { operation_type: GroupQuotient, terms: [(Slash, group), (None, normal_subgroup)] }
@@ -240,6 +400,20 @@ pub enum MathNodeContent {
     Not(Arc<MathNode>),
     True,
     False,
+
+    // A judgement Γ ⊢ φ, or semantic entailment Γ ⊨ φ, with the hypotheses Γ written as a
+    // comma-separated list that may be empty, as in ⊢ φ
+    Sequent {
+        context: Vec<MathNode>,
+        turnstile: Turnstile,
+        conclusion: Arc<MathNode>,
+    },
+
+    // collapsed sub-expression shown as `summary` until expanded, see `MathNode::auto_fold`
+    Folded {
+        summary: String,
+        content: Arc<MathNode>,
+    },
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize, TS)]
@@ -331,6 +505,18 @@ pub struct ScriptNode {
     pub superscripts: Vec<MathNode>,
 }
 
+/// One case of a `FunctionDefinition` given by clauses: `f(arguments) = definition`, when
+/// `condition` holds. The arguments are patterns such as `0` or `n`, whose identifiers are bound
+/// in the condition and the definition.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct FunctionClause {
+    pub arguments: Vec<MathNode>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub condition: Option<MathNode>,
+    pub definition: MathNode,
+}
+
 #[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize, TS)]
 #[ts(export)]
 pub enum ScientificNotationStyle {
@@ -346,6 +532,16 @@ pub enum DifferentialStyle {
     Total,
 }
 
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub enum DerivativeNotation {
+    #[default]
+    Leibniz, // dy/dx, d²y/dx², ∂f/∂x
+    Lagrange, // y', y'', y⁽ⁿ⁾ from the fourth derivative on
+    Newton,   // ẏ, ÿ, for derivatives with respect to time
+    Operator, // Dₓy, Dₓ²y, or ∂ₓf
+}
+
 #[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize, TS)]
 #[ts(export)]
 pub enum IntegralType {
@@ -445,6 +641,113 @@ pub enum DivisionStyle {
     Division, // a÷b
 }
 
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub enum BinomialStyle {
+    Parenthesized, // \binom{n}{k}
+    Function,      // C(n, k)
+    Prefix,        // ⁿCₖ, as on calculators
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub enum VectorDecoration {
+    Arrow, // \vec{v}, or \overrightarrow over longer expressions
+    Bold,  // \mathbf{v}
+    Hat,   // \hat{v}, for unit vectors
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub enum MathEmphasis {
+    Strong, // \boldsymbol
+    Muted,  // greyed out, as for terms about to cancel
+    Boxed,  // \boxed, as for a final result
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub enum CancelStyle {
+    Diagonal,     // \cancel
+    BackDiagonal, // \bcancel
+    Cross,        // \xcancel
+    Horizontal,   // \sout
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub enum AngleUnit {
+    Degrees, // 30°
+    Radians, // π/2 rad; bare radians are plain numbers
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub enum Turnstile {
+    Proves,     // ⊢
+    Entails,    // ⊨
+    NotProves,  // ⊬
+    NotEntails, // ⊭
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub enum PhantomExtent {
+    Full,       // \phantom, width and height
+    Horizontal, // \hphantom, width only
+    Vertical,   // \vphantom, height only
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub enum MathSpace {
+    NegativeThin,   // \!
+    Thin,           // \,
+    Medium,         // \:
+    Thick,          // \;
+    Quad,           // \quad
+    Qquad,          // \qquad
+    Custom(String), // \hspace{0.5em}, a TeX length
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub enum BracePosition {
+    Over,  // \overbrace{inner}^{label}
+    Under, // \underbrace{inner}_{label}
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub enum MatrixDelimiters {
+    Brackets,    // bmatrix
+    Parentheses, // pmatrix
+    Bars,        // vmatrix, the determinant
+    DoubleBars,  // Vmatrix, the norm
+    Braces,      // Bmatrix
+    None,        // matrix, as in a block of a larger matrix
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub enum MatrixAxis {
+    Row,
+    Column,
+}
+
+/// A row or column of a `Matrix` picked out, with a note beside a row (after the matrix) or
+/// below a column, such as the row operation about to be applied to it.
+#[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct MatrixHighlight {
+    pub axis: MatrixAxis,
+    pub index: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub annotation: Option<MathNode>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>, // a CSS color, or the renderer's highlight color
+}
+
 #[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize, TS)]
 #[ts(export)]
 pub enum MulSymbol {
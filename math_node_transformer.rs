@@ -0,0 +1,476 @@
+use super::*;
+use std::sync::Arc;
+
+/// Bottom-up rewrite pass over a `MathNode` tree.
+///
+/// Children are transformed before their parent, and `transform_node` sees the parent with its
+/// children already rewritten. Subtrees that come back unchanged keep their original `Arc`s, so a
+/// pass that touches a few nodes only allocates along the paths leading to them.
+pub trait MathNodeTransformer {
+    /// Returns the replacement for `node`, or `None` to keep it as is.
+    fn transform_node(&mut self, node: &MathNode) -> Option<MathNode>;
+
+    /// Returns `false` to leave the subtree under `node` untouched; `transform_node` still runs on `node`.
+    fn enter_node(&mut self, _node: &MathNode) -> bool {
+        true
+    }
+}
+
+impl<F: FnMut(&MathNode) -> Option<MathNode>> MathNodeTransformer for F {
+    fn transform_node(&mut self, node: &MathNode) -> Option<MathNode> {
+        self(node)
+    }
+}
+
+/// Transforms `node` and its descendants, returning `None` if nothing changed.
+pub fn transform_node<T: MathNodeTransformer + ?Sized>(
+    transformer: &mut T,
+    node: &MathNode,
+) -> Option<MathNode> {
+    let rebuilt = if transformer.enter_node(node) {
+        node.content
+            .map_children(|child| transform_node(transformer, child))
+            .map(|content| MathNode {
+                id: node.id.clone(),
+                content: Arc::new(content),
+            })
+    } else {
+        None
+    };
+    let current = rebuilt.as_ref().unwrap_or(node);
+    transformer.transform_node(current).or(rebuilt)
+}
+
+impl MathNode {
+    /// Runs `transformer` over this tree; returns a cheap clone of `self` if nothing changed.
+    pub fn transform<T: MathNodeTransformer + ?Sized>(&self, transformer: &mut T) -> MathNode {
+        transform_node(transformer, self).unwrap_or_else(|| self.clone())
+    }
+}
+
+impl MathNodeContent {
+    /// Rebuilds this content with each direct child replaced by `f(child)` where it returns `Some`.
+    /// Returns `None` when `f` changed no child. Children are visited in the same order as
+    /// `MathNodeVisitor`'s `walk_node`.
+    pub fn map_children(
+        &self,
+        f: impl FnMut(&MathNode) -> Option<MathNode>,
+    ) -> Option<MathNodeContent> {
+        let mut map = ChildMap { f, changed: false };
+        let content = match self {
+            MathNodeContent::Empty
+            | MathNodeContent::Text(_)
+            | MathNodeContent::String(_)
+            | MathNodeContent::Space(_)
+            | MathNodeContent::ChemicalExpression(_)
+            | MathNodeContent::True
+            | MathNodeContent::False => return None,
+            MathNodeContent::Phantom { inner, extent } => MathNodeContent::Phantom {
+                inner: map.arc(inner),
+                extent: *extent,
+            },
+            MathNodeContent::Bracketed { inner, style, size } => MathNodeContent::Bracketed {
+                inner: map.arc(inner),
+                style: style.clone(),
+                size: size.clone(),
+            },
+            MathNodeContent::Matrix {
+                rows,
+                delimiters,
+                augmented_after,
+                row_labels,
+                column_labels,
+                highlights,
+            } => MathNodeContent::Matrix {
+                rows: rows.iter().map(|row| map.nodes(row)).collect(),
+                delimiters: *delimiters,
+                augmented_after: *augmented_after,
+                row_labels: map.nodes(row_labels),
+                column_labels: map.nodes(column_labels),
+                highlights: highlights
+                    .iter()
+                    .map(|highlight| MatrixHighlight {
+                        axis: highlight.axis,
+                        index: highlight.index,
+                        annotation: map.optional_node(&highlight.annotation),
+                        color: highlight.color.clone(),
+                    })
+                    .collect(),
+            },
+            MathNodeContent::BinaryOperation {
+                operation_type,
+                terms,
+            } => MathNodeContent::BinaryOperation {
+                operation_type: operation_type.clone(),
+                terms: terms
+                    .iter()
+                    .map(|(operator, term)| (operator.clone(), map.node(term)))
+                    .collect(),
+            },
+            MathNodeContent::Multiplications { terms } => MathNodeContent::Multiplications {
+                terms: terms
+                    .iter()
+                    .map(|(operator, term)| (operator.clone(), map.node(term)))
+                    .collect(),
+            },
+            MathNodeContent::Additions { terms } => MathNodeContent::Additions {
+                terms: terms
+                    .iter()
+                    .map(|(operator, term)| (operator.clone(), map.node(term)))
+                    .collect(),
+            },
+            MathNodeContent::Division {
+                numerator,
+                denominator,
+                style,
+            } => MathNodeContent::Division {
+                numerator: map.arc(numerator),
+                denominator: map.arc(denominator),
+                style: style.clone(),
+            },
+            MathNodeContent::Cases { branches } => MathNodeContent::Cases {
+                branches: branches
+                    .iter()
+                    .map(|(expression, condition)| (map.node(expression), map.node(condition)))
+                    .collect(),
+            },
+            MathNodeContent::SumNotation {
+                summand,
+                variable,
+                lower_limit,
+                upper_limit,
+            } => MathNodeContent::SumNotation {
+                summand: map.arc(summand),
+                variable: map.optional_node(variable),
+                lower_limit: map.optional_arc(lower_limit),
+                upper_limit: map.optional_arc(upper_limit),
+            },
+            MathNodeContent::ProductNotation {
+                multiplicand,
+                variable,
+                lower_limit,
+                upper_limit,
+            } => MathNodeContent::ProductNotation {
+                multiplicand: map.arc(multiplicand),
+                variable: map.optional_node(variable),
+                lower_limit: map.optional_arc(lower_limit),
+                upper_limit: map.optional_arc(upper_limit),
+            },
+            MathNodeContent::Fraction {
+                numerator,
+                denominator,
+            } => MathNodeContent::Fraction {
+                numerator: map.arc(numerator),
+                denominator: map.arc(denominator),
+            },
+            MathNodeContent::Power { base, exponent } => MathNodeContent::Power {
+                base: map.arc(base),
+                exponent: map.arc(exponent),
+            },
+            MathNodeContent::Radical { radicand, index } => MathNodeContent::Radical {
+                radicand: map.arc(radicand),
+                index: map.optional_arc(index),
+            },
+            MathNodeContent::Binomial {
+                upper,
+                lower,
+                style,
+            } => MathNodeContent::Binomial {
+                upper: map.arc(upper),
+                lower: map.arc(lower),
+                style: style.clone(),
+            },
+            MathNodeContent::UnaryPostfixOperation {
+                parameter,
+                operator,
+            } => MathNodeContent::UnaryPostfixOperation {
+                parameter: map.arc(parameter),
+                operator: map.arc(operator),
+            },
+            MathNodeContent::UnaryPrefixOperation {
+                parameter,
+                operator,
+            } => MathNodeContent::UnaryPrefixOperation {
+                parameter: map.arc(parameter),
+                operator: map.arc(operator),
+            },
+            MathNodeContent::Abs { parameter } => MathNodeContent::Abs {
+                parameter: map.arc(parameter),
+            },
+            MathNodeContent::Norm { inner, subscript } => MathNodeContent::Norm {
+                inner: map.arc(inner),
+                subscript: map.optional_arc(subscript),
+            },
+            MathNodeContent::Decorated { inner, decoration } => MathNodeContent::Decorated {
+                inner: map.arc(inner),
+                decoration: *decoration,
+            },
+            MathNodeContent::Brace {
+                inner,
+                label,
+                position,
+            } => MathNodeContent::Brace {
+                inner: map.arc(inner),
+                label: map.arc(label),
+                position: *position,
+            },
+            MathNodeContent::Styled {
+                inner,
+                color,
+                background,
+                emphasis,
+            } => MathNodeContent::Styled {
+                inner: map.arc(inner),
+                color: color.clone(),
+                background: background.clone(),
+                emphasis: *emphasis,
+            },
+            MathNodeContent::Cancelled {
+                inner,
+                cancel_style,
+                replacement,
+            } => MathNodeContent::Cancelled {
+                inner: map.arc(inner),
+                cancel_style: *cancel_style,
+                replacement: map.optional_arc(replacement),
+            },
+            MathNodeContent::AngleMeasure { value, unit } => MathNodeContent::AngleMeasure {
+                value: map.arc(value),
+                unit: *unit,
+            },
+            MathNodeContent::AngleSymbol { vertices } => MathNodeContent::AngleSymbol {
+                vertices: map.nodes(vertices),
+            },
+            MathNodeContent::Coordinates { point, components } => MathNodeContent::Coordinates {
+                point: map.optional_arc(point),
+                components: map.nodes(components),
+            },
+            MathNodeContent::Bra { state } => MathNodeContent::Bra {
+                state: map.arc(state),
+            },
+            MathNodeContent::Ket { state } => MathNodeContent::Ket {
+                state: map.arc(state),
+            },
+            MathNodeContent::BraKet { bra, operator, ket } => MathNodeContent::BraKet {
+                bra: map.arc(bra),
+                operator: map.optional_arc(operator),
+                ket: map.arc(ket),
+            },
+            MathNodeContent::Expectation { operator, state } => MathNodeContent::Expectation {
+                operator: map.arc(operator),
+                state: map.optional_arc(state),
+            },
+            MathNodeContent::FunctionCall { name, parameters } => MathNodeContent::FunctionCall {
+                name: map.arc(name),
+                parameters: map.nodes(parameters),
+            },
+            MathNodeContent::Quantity {
+                number,
+                scientific_notation,
+                unit,
+            } => MathNodeContent::Quantity {
+                number: number.clone(),
+                scientific_notation: map.optional_node(scientific_notation),
+                unit: map.optional_node(unit),
+            },
+            MathNodeContent::ScientificNotation { magnitude, style } => {
+                MathNodeContent::ScientificNotation {
+                    magnitude: map.arc(magnitude),
+                    style: style.clone(),
+                }
+            }
+            MathNodeContent::Identifier(identifier) => {
+                MathNodeContent::Identifier(map.identifier(identifier))
+            }
+            MathNodeContent::Unit {
+                original_form,
+                flattened_form,
+            } => MathNodeContent::Unit {
+                original_form: map.arc(original_form),
+                flattened_form: map.arc(flattened_form),
+            },
+            MathNodeContent::Relationship { lhs, rhs, operator } => MathNodeContent::Relationship {
+                lhs: map.arc(lhs),
+                rhs: map.arc(rhs),
+                operator: operator.clone(),
+            },
+            MathNodeContent::UnaryRelationship { subject, predicate } => {
+                MathNodeContent::UnaryRelationship {
+                    subject: map.arc(subject),
+                    predicate: predicate.clone(),
+                }
+            }
+            MathNodeContent::VariableDefinition { name, definition } => {
+                MathNodeContent::VariableDefinition {
+                    name: map.arc(name),
+                    definition: map.optional_node(definition),
+                }
+            }
+            MathNodeContent::FunctionDefinition {
+                custom_function,
+                definition,
+                clauses,
+            } => MathNodeContent::FunctionDefinition {
+                custom_function: map.arc(custom_function),
+                definition: map.optional_node(definition),
+                clauses: clauses
+                    .iter()
+                    .map(|clause| FunctionClause {
+                        arguments: map.nodes(&clause.arguments),
+                        condition: map.optional_node(&clause.condition),
+                        definition: map.node(&clause.definition),
+                    })
+                    .collect(),
+            },
+            MathNodeContent::Limit {
+                function,
+                variable,
+                approaching_value,
+            } => MathNodeContent::Limit {
+                function: map.arc(function),
+                variable: variable.clone(),
+                approaching_value: map.arc(approaching_value),
+            },
+            MathNodeContent::Differential {
+                target,
+                order,
+                diff_style,
+                notation,
+                variable,
+            } => MathNodeContent::Differential {
+                target: map.arc(target),
+                order: map.arc(order),
+                diff_style: diff_style.clone(),
+                notation: *notation,
+                variable: map.optional_arc(variable),
+            },
+            MathNodeContent::Integration {
+                integrand,
+                differentials,
+                domain,
+            } => MathNodeContent::Integration {
+                integrand: map.arc(integrand),
+                differentials: differentials
+                    .iter()
+                    .map(|(differential, lower_bound, upper_bound)| {
+                        (
+                            map.arc(differential),
+                            map.optional_arc(lower_bound),
+                            map.optional_arc(upper_bound),
+                        )
+                    })
+                    .collect(),
+                domain: map.optional_arc(domain),
+            },
+            MathNodeContent::SetExplicit { elements } => MathNodeContent::SetExplicit {
+                elements: map.nodes(elements),
+            },
+            MathNodeContent::SetBuilder {
+                variable,
+                domain,
+                predicate,
+            } => MathNodeContent::SetBuilder {
+                variable: map.arc(variable),
+                domain: map.optional_arc(domain),
+                predicate: map.arc(predicate),
+            },
+            MathNodeContent::QuantifiedExpression {
+                quantifier,
+                variables,
+                domain,
+                predicate,
+            } => MathNodeContent::QuantifiedExpression {
+                quantifier: quantifier.clone(),
+                variables: map.nodes(variables),
+                domain: map.optional_arc(domain),
+                predicate: map.optional_arc(predicate),
+            },
+            MathNodeContent::RichTextContent(segments) => MathNodeContent::RichTextContent(
+                segments
+                    .iter()
+                    .map(|segment| match segment {
+                        MathTextSegment::Math(node) => MathTextSegment::Math(map.node(node)),
+                        _ => segment.clone(),
+                    })
+                    .collect(),
+            ),
+            MathNodeContent::And(operands) => MathNodeContent::And(map.nodes(operands)),
+            MathNodeContent::Or(operands) => MathNodeContent::Or(map.nodes(operands)),
+            MathNodeContent::Not(operand) => MathNodeContent::Not(map.arc(operand)),
+            MathNodeContent::Sequent {
+                context,
+                turnstile,
+                conclusion,
+            } => MathNodeContent::Sequent {
+                context: map.nodes(context),
+                turnstile: *turnstile,
+                conclusion: map.arc(conclusion),
+            },
+            MathNodeContent::Folded { summary, content } => MathNodeContent::Folded {
+                summary: summary.clone(),
+                content: map.arc(content),
+            },
+        };
+        map.changed.then_some(content)
+    }
+}
+
+struct ChildMap<F> {
+    f: F,
+    changed: bool,
+}
+
+impl<F: FnMut(&MathNode) -> Option<MathNode>> ChildMap<F> {
+    fn node(&mut self, node: &MathNode) -> MathNode {
+        match (self.f)(node) {
+            Some(replacement) => {
+                self.changed = true;
+                replacement
+            }
+            None => node.clone(),
+        }
+    }
+
+    fn arc(&mut self, node: &Arc<MathNode>) -> Arc<MathNode> {
+        match (self.f)(node) {
+            Some(replacement) => {
+                self.changed = true;
+                Arc::new(replacement)
+            }
+            None => Arc::clone(node),
+        }
+    }
+
+    fn optional_node(&mut self, node: &Option<MathNode>) -> Option<MathNode> {
+        node.as_ref().map(|node| self.node(node))
+    }
+
+    fn optional_arc(&mut self, node: &Option<Arc<MathNode>>) -> Option<Arc<MathNode>> {
+        node.as_ref().map(|node| self.arc(node))
+    }
+
+    fn nodes(&mut self, nodes: &[MathNode]) -> Vec<MathNode> {
+        nodes.iter().map(|node| self.node(node)).collect()
+    }
+
+    fn script(&mut self, script: &ScriptNode) -> ScriptNode {
+        ScriptNode {
+            subscripts: self.nodes(&script.subscripts),
+            superscripts: self.nodes(&script.superscripts),
+        }
+    }
+
+    fn identifier(&mut self, identifier: &Identifier) -> Identifier {
+        Identifier {
+            pre_script: identifier
+                .pre_script
+                .as_ref()
+                .map(|script| self.script(script)),
+            post_script: identifier
+                .post_script
+                .as_ref()
+                .map(|script| self.script(script)),
+            ..identifier.clone()
+        }
+    }
+}
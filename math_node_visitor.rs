@@ -0,0 +1,397 @@
+use super::*;
+
+/// Read-only traversal over a `MathNode` tree.
+///
+/// Every method has a default that keeps walking, so an analysis only overrides the hooks it
+/// cares about. An override that still wants the children visited calls the matching `walk_*`
+/// function, e.g. `walk_node(self, node)`.
+pub trait MathNodeVisitor {
+    fn visit_node(&mut self, node: &MathNode) {
+        walk_node(self, node);
+    }
+
+    fn visit_identifier(&mut self, identifier: &Identifier) {
+        walk_identifier(self, identifier);
+    }
+
+    fn visit_script(&mut self, script: &ScriptNode) {
+        walk_script(self, script);
+    }
+
+    fn visit_text_segment(&mut self, segment: &MathTextSegment) {
+        walk_text_segment(self, segment);
+    }
+
+    fn visit_text(&mut self, _text: &str) {}
+
+    fn visit_string(&mut self, _string: &str) {}
+
+    fn visit_quantity(&mut self, _number: &str) {}
+
+    fn visit_binary_operator(&mut self, _operator: &BinaryOperator) {}
+
+    fn visit_relation_operator(&mut self, _operator: &RelationOperatorNode) {}
+
+    fn visit_unary_relation_operator(&mut self, _operator: &UnaryRelationOperatorNode) {}
+
+    fn visit_quantifier(&mut self, _quantifier: &QuantificationNode) {}
+}
+
+/// Visits the children of `node` in field order.
+pub fn walk_node<V: MathNodeVisitor + ?Sized>(visitor: &mut V, node: &MathNode) {
+    match node.content.as_ref() {
+        MathNodeContent::Empty
+        | MathNodeContent::Space(_)
+        | MathNodeContent::ChemicalExpression(_)
+        | MathNodeContent::True
+        | MathNodeContent::False => {}
+        MathNodeContent::Text(text) => visitor.visit_text(text),
+        MathNodeContent::String(string) => visitor.visit_string(string),
+        MathNodeContent::Phantom { inner, .. } => visitor.visit_node(inner),
+        MathNodeContent::Bracketed { inner, .. } => visitor.visit_node(inner),
+        MathNodeContent::Matrix {
+            rows,
+            row_labels,
+            column_labels,
+            highlights,
+            ..
+        } => {
+            for entry in rows.iter().flatten() {
+                visitor.visit_node(entry);
+            }
+            for label in row_labels.iter().chain(column_labels) {
+                visitor.visit_node(label);
+            }
+            for highlight in highlights {
+                if let Some(annotation) = &highlight.annotation {
+                    visitor.visit_node(annotation);
+                }
+            }
+        }
+        MathNodeContent::BinaryOperation { terms, .. } => {
+            for (operator, term) in terms {
+                visitor.visit_binary_operator(operator);
+                visitor.visit_node(term);
+            }
+        }
+        MathNodeContent::Multiplications { terms } => {
+            for (_, term) in terms {
+                visitor.visit_node(term);
+            }
+        }
+        MathNodeContent::Additions { terms } => {
+            for (_, term) in terms {
+                visitor.visit_node(term);
+            }
+        }
+        MathNodeContent::Division {
+            numerator,
+            denominator,
+            ..
+        }
+        | MathNodeContent::Fraction {
+            numerator,
+            denominator,
+        } => {
+            visitor.visit_node(numerator);
+            visitor.visit_node(denominator);
+        }
+        MathNodeContent::SumNotation {
+            summand: body,
+            variable,
+            lower_limit,
+            upper_limit,
+        }
+        | MathNodeContent::ProductNotation {
+            multiplicand: body,
+            variable,
+            lower_limit,
+            upper_limit,
+        } => {
+            visitor.visit_node(body);
+            if let Some(variable) = variable {
+                visitor.visit_node(variable);
+            }
+            if let Some(lower_limit) = lower_limit {
+                visitor.visit_node(lower_limit);
+            }
+            if let Some(upper_limit) = upper_limit {
+                visitor.visit_node(upper_limit);
+            }
+        }
+        MathNodeContent::Power { base, exponent } => {
+            visitor.visit_node(base);
+            visitor.visit_node(exponent);
+        }
+        MathNodeContent::Radical { radicand, index } => {
+            visitor.visit_node(radicand);
+            if let Some(index) = index {
+                visitor.visit_node(index);
+            }
+        }
+        MathNodeContent::Binomial { upper, lower, .. } => {
+            visitor.visit_node(upper);
+            visitor.visit_node(lower);
+        }
+        MathNodeContent::Cases { branches } => {
+            for (expression, condition) in branches {
+                visitor.visit_node(expression);
+                visitor.visit_node(condition);
+            }
+        }
+        MathNodeContent::UnaryPostfixOperation {
+            parameter,
+            operator,
+        }
+        | MathNodeContent::UnaryPrefixOperation {
+            parameter,
+            operator,
+        } => {
+            visitor.visit_node(parameter);
+            visitor.visit_node(operator);
+        }
+        MathNodeContent::Abs { parameter } => visitor.visit_node(parameter),
+        MathNodeContent::Norm { inner, subscript } => {
+            visitor.visit_node(inner);
+            if let Some(subscript) = subscript {
+                visitor.visit_node(subscript);
+            }
+        }
+        MathNodeContent::Decorated { inner, .. } | MathNodeContent::Styled { inner, .. } => {
+            visitor.visit_node(inner)
+        }
+        MathNodeContent::Cancelled {
+            inner, replacement, ..
+        } => {
+            visitor.visit_node(inner);
+            if let Some(replacement) = replacement {
+                visitor.visit_node(replacement);
+            }
+        }
+        MathNodeContent::AngleMeasure { value, .. } => visitor.visit_node(value),
+        MathNodeContent::AngleSymbol { vertices } => {
+            for vertex in vertices {
+                visitor.visit_node(vertex);
+            }
+        }
+        MathNodeContent::Coordinates { point, components } => {
+            if let Some(point) = point {
+                visitor.visit_node(point);
+            }
+            for component in components {
+                visitor.visit_node(component);
+            }
+        }
+        MathNodeContent::Brace { inner, label, .. } => {
+            visitor.visit_node(inner);
+            visitor.visit_node(label);
+        }
+        MathNodeContent::Bra { state } | MathNodeContent::Ket { state } => {
+            visitor.visit_node(state)
+        }
+        MathNodeContent::BraKet { bra, operator, ket } => {
+            visitor.visit_node(bra);
+            if let Some(operator) = operator {
+                visitor.visit_node(operator);
+            }
+            visitor.visit_node(ket);
+        }
+        MathNodeContent::Expectation { operator, state } => {
+            visitor.visit_node(operator);
+            if let Some(state) = state {
+                visitor.visit_node(state);
+            }
+        }
+        MathNodeContent::FunctionCall { name, parameters } => {
+            visitor.visit_node(name);
+            for parameter in parameters {
+                visitor.visit_node(parameter);
+            }
+        }
+        MathNodeContent::Quantity {
+            number,
+            scientific_notation,
+            unit,
+        } => {
+            visitor.visit_quantity(number);
+            if let Some(scientific_notation) = scientific_notation {
+                visitor.visit_node(scientific_notation);
+            }
+            if let Some(unit) = unit {
+                visitor.visit_node(unit);
+            }
+        }
+        MathNodeContent::ScientificNotation { magnitude, .. } => visitor.visit_node(magnitude),
+        MathNodeContent::Identifier(identifier) => visitor.visit_identifier(identifier),
+        MathNodeContent::Unit {
+            original_form,
+            flattened_form,
+        } => {
+            visitor.visit_node(original_form);
+            visitor.visit_node(flattened_form);
+        }
+        MathNodeContent::Relationship { lhs, rhs, operator } => {
+            visitor.visit_node(lhs);
+            visitor.visit_relation_operator(operator);
+            visitor.visit_node(rhs);
+        }
+        MathNodeContent::UnaryRelationship { subject, predicate } => {
+            visitor.visit_node(subject);
+            visitor.visit_unary_relation_operator(predicate);
+        }
+        MathNodeContent::VariableDefinition { name, definition } => {
+            visitor.visit_node(name);
+            if let Some(definition) = definition {
+                visitor.visit_node(definition);
+            }
+        }
+        MathNodeContent::FunctionDefinition {
+            custom_function,
+            definition,
+            clauses,
+        } => {
+            visitor.visit_node(custom_function);
+            if let Some(definition) = definition {
+                visitor.visit_node(definition);
+            }
+            for clause in clauses {
+                for argument in &clause.arguments {
+                    visitor.visit_node(argument);
+                }
+                if let Some(condition) = &clause.condition {
+                    visitor.visit_node(condition);
+                }
+                visitor.visit_node(&clause.definition);
+            }
+        }
+        MathNodeContent::Limit {
+            function,
+            approaching_value,
+            ..
+        } => {
+            visitor.visit_node(function);
+            visitor.visit_node(approaching_value);
+        }
+        MathNodeContent::Differential {
+            target,
+            order,
+            variable,
+            ..
+        } => {
+            visitor.visit_node(target);
+            visitor.visit_node(order);
+            if let Some(variable) = variable {
+                visitor.visit_node(variable);
+            }
+        }
+        MathNodeContent::Integration {
+            integrand,
+            differentials,
+            domain,
+        } => {
+            visitor.visit_node(integrand);
+            for (differential, lower_bound, upper_bound) in differentials {
+                visitor.visit_node(differential);
+                if let Some(lower_bound) = lower_bound {
+                    visitor.visit_node(lower_bound);
+                }
+                if let Some(upper_bound) = upper_bound {
+                    visitor.visit_node(upper_bound);
+                }
+            }
+            if let Some(domain) = domain {
+                visitor.visit_node(domain);
+            }
+        }
+        MathNodeContent::SetExplicit { elements } => {
+            for element in elements {
+                visitor.visit_node(element);
+            }
+        }
+        MathNodeContent::SetBuilder {
+            variable,
+            domain,
+            predicate,
+        } => {
+            visitor.visit_node(variable);
+            if let Some(domain) = domain {
+                visitor.visit_node(domain);
+            }
+            visitor.visit_node(predicate);
+        }
+        MathNodeContent::QuantifiedExpression {
+            quantifier,
+            variables,
+            domain,
+            predicate,
+        } => {
+            visitor.visit_quantifier(quantifier);
+            for variable in variables {
+                visitor.visit_node(variable);
+            }
+            if let Some(domain) = domain {
+                visitor.visit_node(domain);
+            }
+            if let Some(predicate) = predicate {
+                visitor.visit_node(predicate);
+            }
+        }
+        MathNodeContent::RichTextContent(segments) => {
+            for segment in segments {
+                visitor.visit_text_segment(segment);
+            }
+        }
+        MathNodeContent::And(operands) | MathNodeContent::Or(operands) => {
+            for operand in operands {
+                visitor.visit_node(operand);
+            }
+        }
+        MathNodeContent::Not(operand) => visitor.visit_node(operand),
+        MathNodeContent::Sequent {
+            context,
+            conclusion,
+            ..
+        } => {
+            for hypothesis in context {
+                visitor.visit_node(hypothesis);
+            }
+            visitor.visit_node(conclusion);
+        }
+        MathNodeContent::Folded { content, .. } => visitor.visit_node(content),
+    }
+}
+
+/// Visits the pre- and post-scripts of `identifier`.
+pub fn walk_identifier<V: MathNodeVisitor + ?Sized>(visitor: &mut V, identifier: &Identifier) {
+    if let Some(pre_script) = &identifier.pre_script {
+        visitor.visit_script(pre_script);
+    }
+    if let Some(post_script) = &identifier.post_script {
+        visitor.visit_script(post_script);
+    }
+}
+
+pub fn walk_script<V: MathNodeVisitor + ?Sized>(visitor: &mut V, script: &ScriptNode) {
+    for subscript in &script.subscripts {
+        visitor.visit_node(subscript);
+    }
+    for superscript in &script.superscripts {
+        visitor.visit_node(superscript);
+    }
+}
+
+pub fn walk_text_segment<V: MathNodeVisitor + ?Sized>(visitor: &mut V, segment: &MathTextSegment) {
+    match segment {
+        MathTextSegment::Text(text) | MathTextSegment::StyledText { text, .. } => {
+            visitor.visit_text(text)
+        }
+        MathTextSegment::Math(node) => visitor.visit_node(node),
+    }
+}
+
+impl MathNode {
+    /// Runs `visitor` over this node and all of its descendants.
+    pub fn accept<V: MathNodeVisitor + ?Sized>(&self, visitor: &mut V) {
+        visitor.visit_node(self);
+    }
+}
@@ -0,0 +1,162 @@
+use super::*;
+use std::sync::Arc;
+
+/// An occurrence of a searched expression inside a document.
+#[derive(Debug, Clone)]
+pub struct SubexpressionMatch<'doc> {
+    pub section_id: Option<&'doc str>, // innermost section containing the expression
+    pub root: &'doc MathNode,          // the embedded expression the match was found in
+    pub path: NodePath,                // from `root` to `node`
+    pub node: &'doc MathNode,
+}
+
+impl MathNode {
+    /// Every subtree of this node whose canonical form equals that of `pattern`, in pre-order.
+    /// Brackets, ids and the order of commutative operands are ignored; see `Canonicalizer`.
+    pub fn find_subexpression(&self, pattern: &MathNode) -> Vec<(NodePath, &MathNode)> {
+        let pattern = pattern.canonicalize();
+        let mut matches = vec![];
+        search(self, NodePath::root(), &pattern, &mut matches);
+        matches
+    }
+}
+
+impl MathDocument {
+    /// Every occurrence of `pattern` in the document's expressions, see `MathNode::find_subexpression`.
+    pub fn find_subexpression(&self, pattern: &MathNode) -> Vec<SubexpressionMatch<'_>> {
+        let mut finder = SubexpressionFinder {
+            pattern: pattern.canonicalize(),
+            sections: vec![],
+            matches: vec![],
+        };
+        finder.visit_document(self);
+        finder.matches
+    }
+}
+
+struct SubexpressionFinder<'doc> {
+    pattern: MathNode, // canonical
+    sections: Vec<&'doc str>,
+    matches: Vec<SubexpressionMatch<'doc>>,
+}
+
+impl<'doc> DocumentVisitor<'doc> for SubexpressionFinder<'doc> {
+    fn visit_section(&mut self, section: &'doc Section) {
+        self.sections.push(&section.id);
+        walk_section(self, section);
+        self.sections.pop();
+    }
+
+    fn visit_math_node(&mut self, root: &'doc MathNode) {
+        let mut found = vec![];
+        search(root, NodePath::root(), &self.pattern, &mut found);
+        let section_id = self.sections.last().copied();
+        self.matches
+            .extend(found.into_iter().map(|(path, node)| SubexpressionMatch {
+                section_id,
+                root,
+                path,
+                node,
+            }));
+    }
+}
+
+/// Canonicalizes `node` bottom-up, recording every subtree equal to `pattern`.
+/// Each subtree is canonicalized once, reusing the canonical forms of its children.
+fn search<'a>(
+    node: &'a MathNode,
+    path: NodePath,
+    pattern: &MathNode,
+    matches: &mut Vec<(NodePath, &'a MathNode)>,
+) -> MathNode {
+    let first_child_match = matches.len();
+    let mut canonical_children: Vec<MathNode> = node
+        .content
+        .children_with_paths()
+        .into_iter()
+        .map(|(relative, child)| search(child, path.join(&relative), pattern, matches))
+        .collect();
+    canonical_children.reverse();
+    let rebuilt = match node.content.map_children(|_| canonical_children.pop()) {
+        Some(content) => MathNode {
+            id: node.id.clone(),
+            content: Arc::new(content),
+        },
+        None => node.clone(),
+    };
+    let canonical = canonicalize_shallow(&rebuilt);
+
+    // A wrapper such as brackets or styling matches exactly when its contents do;
+    // report only the contents.
+    if !is_transparent_wrapper(&node.content) && canonical == *pattern {
+        matches.insert(first_child_match, (path, node));
+    }
+    canonical
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{Value, json};
+
+    fn identifier(name: &str) -> Value {
+        json!({
+            "id": name,
+            "content": {
+                "kind": "Identifier",
+                "data": {
+                    "body": name,
+                    "pre_script": null,
+                    "mid_script": null,
+                    "post_script": null,
+                    "primes": 0,
+                    "is_function": false
+                }
+            }
+        })
+    }
+
+    fn sum() -> Value {
+        json!({
+            "id": "sum",
+            "content": {
+                "kind": "BinaryOperation",
+                "data": {
+                    "operation_type": "Addition",
+                    "terms": [["Plus", identifier("x")], ["Plus", identifier("y")]]
+                }
+            }
+        })
+    }
+
+    fn node(value: Value) -> MathNode {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn wrappers_are_reported_once_at_their_contents() {
+        let wrappers = [
+            (
+                "Bracketed",
+                json!({ "inner": sum(), "style": "Round", "size": "Normal" }),
+            ),
+            ("Styled", json!({ "inner": sum(), "color": "red" })),
+            (
+                "Brace",
+                json!({ "inner": sum(), "label": identifier("n"), "position": "Under" }),
+            ),
+            (
+                "Cancelled",
+                json!({ "inner": sum(), "cancel_style": "Diagonal" }),
+            ),
+            ("Folded", json!({ "summary": "…", "content": sum() })),
+        ];
+        for (kind, data) in wrappers {
+            let wrapper = node(json!({ "id": "w", "content": { "kind": kind, "data": data } }));
+            assert!(is_transparent_wrapper(&wrapper.content), "{kind}");
+            let matches = wrapper.find_subexpression(&node(sum()));
+            assert_eq!(matches.len(), 1, "{kind}");
+            assert_eq!(matches[0].1.id, "sum", "{kind}");
+        }
+    }
+}
@@ -0,0 +1,38 @@
+use super::*;
+use std::sync::Arc;
+
+impl MathNode {
+    /// `inner` set in `color`, a CSS color.
+    pub fn colored(id: impl Into<String>, inner: MathNode, color: impl Into<String>) -> MathNode {
+        MathNode {
+            id: id.into(),
+            content: Arc::new(MathNodeContent::Styled {
+                inner: Arc::new(inner),
+                color: Some(color.into()),
+                background: None,
+                emphasis: None,
+            }),
+        }
+    }
+
+    pub fn emphasized(id: impl Into<String>, inner: MathNode, emphasis: MathEmphasis) -> MathNode {
+        MathNode {
+            id: id.into(),
+            content: Arc::new(MathNodeContent::Styled {
+                inner: Arc::new(inner),
+                color: None,
+                background: None,
+                emphasis: Some(emphasis),
+            }),
+        }
+    }
+
+    /// This tree with every `Styled` node replaced by the node it styles, for showing an
+    /// expression without the explanation that refers to its colors.
+    pub fn without_styling(&self) -> MathNode {
+        self.transform(&mut |node: &MathNode| match node.content.as_ref() {
+            MathNodeContent::Styled { inner, .. } => Some(inner.as_ref().clone()),
+            _ => None,
+        })
+    }
+}
@@ -0,0 +1,195 @@
+use super::*;
+use std::sync::Arc;
+
+/// A cursor over a `MathNode` tree for interactive editing.
+///
+/// The zipper holds the focused subtree plus, for each ancestor, the ancestor itself and the
+/// position of the focus among its children. Moving down or up costs time proportional to the
+/// number of children at that level, never to the size or depth of the tree. Edits replace the
+/// focus; ancestors are only rebuilt (reusing untouched siblings) when the cursor moves back up
+/// past them, and untouched ancestors are handed back as they were.
+///
+/// Children are numbered in `children_with_paths` order.
+#[derive(Debug, Clone)]
+pub struct MathZipper {
+    focus: MathNode,
+    changed: bool, // whether `focus` differs from the child stored in the parent crumb
+    crumbs: Vec<Crumb>,
+}
+
+#[derive(Debug, Clone)]
+struct Crumb {
+    parent: MathNode,
+    index: usize,
+    relative: NodePath, // from `parent` to the focus
+    changed: bool,      // the parent's own `changed` flag
+}
+
+impl MathZipper {
+    pub fn new(root: MathNode) -> Self {
+        MathZipper {
+            focus: root,
+            changed: false,
+            crumbs: vec![],
+        }
+    }
+
+    pub fn focus(&self) -> &MathNode {
+        &self.focus
+    }
+
+    /// Number of ancestors above the focus.
+    pub fn depth(&self) -> usize {
+        self.crumbs.len()
+    }
+
+    pub fn is_root(&self) -> bool {
+        self.crumbs.is_empty()
+    }
+
+    /// Path from the root to the focus.
+    pub fn path(&self) -> NodePath {
+        self.crumbs
+            .iter()
+            .fold(NodePath::root(), |path, crumb| path.join(&crumb.relative))
+    }
+
+    /// Position of the focus among its siblings, or `None` at the root.
+    pub fn index(&self) -> Option<usize> {
+        self.crumbs.last().map(|crumb| crumb.index)
+    }
+
+    pub fn child_count(&self) -> usize {
+        self.focus.content.children_with_paths().len()
+    }
+
+    /// Moves to the first child.
+    pub fn down(&mut self) -> bool {
+        self.down_to(0)
+    }
+
+    /// Moves to the child at `index`.
+    pub fn down_to(&mut self, index: usize) -> bool {
+        let Some((relative, child)) = self
+            .focus
+            .content
+            .children_with_paths()
+            .into_iter()
+            .nth(index)
+        else {
+            return false;
+        };
+        let child = child.clone();
+        let parent = std::mem::replace(&mut self.focus, child);
+        self.crumbs.push(Crumb {
+            parent,
+            index,
+            relative,
+            changed: self.changed,
+        });
+        self.changed = false;
+        true
+    }
+
+    /// Moves to the parent, writing any edits below it back into it.
+    pub fn up(&mut self) -> bool {
+        let Some(crumb) = self.crumbs.pop() else {
+            return false;
+        };
+        if self.changed {
+            let mut edited = Some(self.focus.clone());
+            let mut position = 0;
+            let content = crumb.parent.content.map_children(|_| {
+                let mapped = if position == crumb.index {
+                    edited.take()
+                } else {
+                    None
+                };
+                position += 1;
+                mapped
+            });
+            self.focus = MathNode {
+                id: crumb.parent.id,
+                content: content.map(Arc::new).unwrap_or(crumb.parent.content),
+            };
+        } else {
+            self.focus = crumb.parent;
+        }
+        self.changed |= crumb.changed;
+        true
+    }
+
+    /// Moves to the previous sibling.
+    pub fn left(&mut self) -> bool {
+        match self.index() {
+            Some(index) if index > 0 => self.up() && self.down_to(index - 1),
+            _ => false,
+        }
+    }
+
+    /// Moves to the next sibling.
+    pub fn right(&mut self) -> bool {
+        let Some(index) = self.index() else {
+            return false;
+        };
+        let sibling_count = self.crumbs[self.crumbs.len() - 1]
+            .parent
+            .content
+            .children_with_paths()
+            .len();
+        index + 1 < sibling_count && self.up() && self.down_to(index + 1)
+    }
+
+    /// Moves to the node at `path` below the focus. On failure the focus is left where it was.
+    pub fn descend(&mut self, path: &NodePath) -> bool {
+        let depth = self.depth();
+        let mut rest = path.segments.as_slice();
+        while !rest.is_empty() {
+            let step = self
+                .focus
+                .content
+                .children_with_paths()
+                .into_iter()
+                .position(|(relative, _)| rest.starts_with(&relative.segments));
+            let moved = step.is_some_and(|index| self.down_to(index));
+            if !moved {
+                while self.depth() > depth {
+                    self.up();
+                }
+                return false;
+            }
+            rest = &rest[self.crumbs[self.crumbs.len() - 1].relative.len()..];
+        }
+        true
+    }
+
+    /// Moves all the way up to the root.
+    pub fn top(&mut self) {
+        while self.up() {}
+    }
+
+    /// Replaces the focus, returning the previous subtree.
+    pub fn replace(&mut self, node: MathNode) -> MathNode {
+        self.changed = true;
+        std::mem::replace(&mut self.focus, node)
+    }
+
+    /// Replaces the focus with `edit` applied to it.
+    pub fn modify(&mut self, edit: impl FnOnce(&MathNode) -> MathNode) {
+        let edited = edit(&self.focus);
+        self.replace(edited);
+    }
+
+    /// The whole tree, with every edit applied.
+    pub fn into_root(mut self) -> MathNode {
+        self.top();
+        self.focus
+    }
+}
+
+impl MathNode {
+    /// A zipper focused on this node.
+    pub fn zipper(self) -> MathZipper {
+        MathZipper::new(self)
+    }
+}
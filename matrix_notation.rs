@@ -0,0 +1,81 @@
+use super::*;
+use std::sync::Arc;
+
+impl MathNode {
+    /// `|rows|`, the determinant of the matrix.
+    pub fn determinant(id: impl Into<String>, rows: Vec<Vec<MathNode>>) -> MathNode {
+        MathNode {
+            id: id.into(),
+            content: Arc::new(MathNodeContent::Matrix {
+                rows,
+                delimiters: Some(MatrixDelimiters::Bars),
+                augmented_after: None,
+                row_labels: vec![],
+                column_labels: vec![],
+                highlights: vec![],
+            }),
+        }
+    }
+
+    /// `[A | b]`, the coefficients of a linear system with its right-hand side, one entry of
+    /// `constants` per row.
+    pub fn augmented_matrix(
+        id: impl Into<String>,
+        coefficients: Vec<Vec<MathNode>>,
+        constants: Vec<MathNode>,
+    ) -> MathNode {
+        let columns = coefficients.first().map_or(0, Vec::len);
+        MathNode {
+            id: id.into(),
+            content: Arc::new(MathNodeContent::Matrix {
+                rows: coefficients
+                    .into_iter()
+                    .zip(constants)
+                    .map(|(mut row, constant)| {
+                        row.push(constant);
+                        row
+                    })
+                    .collect(),
+                delimiters: None,
+                augmented_after: Some(columns),
+                row_labels: vec![],
+                column_labels: vec![],
+                highlights: vec![],
+            }),
+        }
+    }
+
+    /// This matrix with row `row` highlighted and `operation`, such as `R₂ → R₂ − 2R₁`, noted
+    /// beside it, for a step of a row reduction. Other nodes are returned as they are.
+    pub fn with_row_operation(&self, row: usize, operation: MathNode) -> MathNode {
+        let MathNodeContent::Matrix {
+            rows,
+            delimiters,
+            augmented_after,
+            row_labels,
+            column_labels,
+            highlights,
+        } = self.content.as_ref()
+        else {
+            return self.clone();
+        };
+        let mut highlights = highlights.clone();
+        highlights.push(MatrixHighlight {
+            axis: MatrixAxis::Row,
+            index: row,
+            annotation: Some(operation),
+            color: None,
+        });
+        MathNode {
+            id: self.id.clone(),
+            content: Arc::new(MathNodeContent::Matrix {
+                rows: rows.clone(),
+                delimiters: *delimiters,
+                augmented_after: *augmented_after,
+                row_labels: row_labels.clone(),
+                column_labels: column_labels.clone(),
+                highlights,
+            }),
+        }
+    }
+}
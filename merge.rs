@@ -0,0 +1,237 @@
+use super::*;
+use serde_json::Value;
+use std::fmt;
+
+/// The outcome of `MathDocument::merge`: the merged document and the changes of theirs that
+/// were left out of it.
+#[derive(Debug, Clone)]
+pub struct MergeResult {
+    pub document: MathDocument,
+    pub conflicts: Vec<MergeConflict>,
+}
+
+impl MergeResult {
+    pub fn is_clean(&self) -> bool {
+        self.conflicts.is_empty()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum MergeConflict {
+    /// Both sides changed the section or expression `id` differently; ours was kept.
+    BothChanged {
+        id: String,
+        ours: Box<PatchOperation>,
+        theirs: PatchOperation,
+    },
+    /// Their change does not apply to our version, e.g. it inserts after a section we removed.
+    NotApplicable {
+        theirs: PatchOperation,
+        error: PatchError,
+    },
+}
+
+impl MathDocument {
+    /// Merges the changes `ours` and `theirs` made to `base`: their patch (`diff`) is applied
+    /// to ours one operation at a time, skipping the ones we made too. An operation of theirs
+    /// conflicts when one of ours touches the same thing differently, the thing being the
+    /// position or own data of a section, the document's data outside sections, or an
+    /// expression subtree, where removing a section touches everything in it. Conflicts keep
+    /// our side and are reported with the operations involved.
+    pub fn merge(base: &MathDocument, ours: &MathDocument, theirs: &MathDocument) -> MergeResult {
+        let our_operations: Vec<(PatchOperation, Vec<Touch>)> = base
+            .diff(ours)
+            .operations
+            .into_iter()
+            .map(|operation| {
+                let touches = touches(base, &operation);
+                (operation, touches)
+            })
+            .collect();
+
+        let mut document = ours.clone();
+        let mut conflicts = vec![];
+        'operations: for operation in base.diff(theirs).operations {
+            let operation_json = to_json(&operation);
+            if our_operations
+                .iter()
+                .any(|(ours, _)| to_json(ours) == operation_json)
+            {
+                continue;
+            }
+            let their_touches = touches(base, &operation);
+            for (ours, our_touches) in &our_operations {
+                let overlap = their_touches
+                    .iter()
+                    .find(|touch| our_touches.iter().any(|other| touch.overlaps(other)));
+                if let Some(touch) = overlap {
+                    conflicts.push(MergeConflict::BothChanged {
+                        id: touch.id().to_string(),
+                        ours: Box::new(ours.clone()),
+                        theirs: operation,
+                    });
+                    continue 'operations;
+                }
+            }
+            let patch = DocumentPatch {
+                operations: vec![operation.clone()],
+            };
+            if let Err(error) = document.apply(&patch) {
+                conflicts.push(MergeConflict::NotApplicable {
+                    theirs: operation,
+                    error,
+                });
+            }
+        }
+        MergeResult {
+            document,
+            conflicts,
+        }
+    }
+}
+
+/// Something an operation changes.
+#[derive(Debug, Clone, PartialEq)]
+enum Touch {
+    Document(String),
+    Position(String), // where the section is
+    Content(String),  // the section's own data
+    Math { root_id: String, path: NodePath },
+    Removed(String), // the section is gone
+    Within(String),  // a section is put into one of the section's lists
+}
+
+impl Touch {
+    fn id(&self) -> &str {
+        match self {
+            Touch::Document(id)
+            | Touch::Position(id)
+            | Touch::Content(id)
+            | Touch::Removed(id)
+            | Touch::Within(id) => id,
+            Touch::Math { root_id, .. } => root_id,
+        }
+    }
+
+    fn overlaps(&self, other: &Touch) -> bool {
+        match (self, other) {
+            (
+                Touch::Math { root_id, path },
+                Touch::Math {
+                    root_id: other_root_id,
+                    path: other_path,
+                },
+            ) => {
+                root_id == other_root_id
+                    && (path.starts_with(other_path) || other_path.starts_with(path))
+            }
+            (Touch::Removed(id), Touch::Within(other_id))
+            | (Touch::Within(id), Touch::Removed(other_id)) => id == other_id,
+            (Touch::Within(_), Touch::Within(_)) => false,
+            _ => self == other,
+        }
+    }
+}
+
+/// What `operation` changes, as seen from `base`.
+fn touches(base: &MathDocument, operation: &PatchOperation) -> Vec<Touch> {
+    match operation {
+        PatchOperation::UpdateDocument { .. } => vec![Touch::Document(base.id.clone())],
+        PatchOperation::InsertSection {
+            section, parent, ..
+        } => {
+            let mut touches = vec![
+                Touch::Position(section.id.clone()),
+                Touch::Content(section.id.clone()),
+            ];
+            touches.extend(within(parent));
+            touches
+        }
+        PatchOperation::MoveSection {
+            section_id, parent, ..
+        } => {
+            let mut touches = vec![Touch::Position(section_id.clone())];
+            touches.extend(within(parent));
+            touches
+        }
+        PatchOperation::UpdateSection { section_id, .. } => {
+            let mut touches = vec![Touch::Content(section_id.clone())];
+            if let Some(section) = base.find_section(section_id) {
+                touches.extend(contents(section, false));
+            }
+            touches
+        }
+        PatchOperation::ReplaceMath { root_id, path, .. } => vec![Touch::Math {
+            root_id: root_id.clone(),
+            path: path.clone(),
+        }],
+        PatchOperation::RemoveSection { section_id } => {
+            let mut touches = vec![
+                Touch::Position(section_id.clone()),
+                Touch::Removed(section_id.clone()),
+            ];
+            match base.find_section(section_id) {
+                Some(section) => touches.extend(contents(section, true)),
+                None => touches.push(Touch::Content(section_id.clone())),
+            }
+            touches
+        }
+    }
+}
+
+fn within(parent: &SectionParent) -> Option<Touch> {
+    match parent {
+        SectionParent::Section { section_id, .. } => Some(Touch::Within(section_id.clone())),
+        _ => None,
+    }
+}
+
+/// The section's own data and expressions, and with `nested` those of the sections in it.
+fn contents(section: &Section, nested: bool) -> Vec<Touch> {
+    let mut collector = ContentCollector {
+        nested,
+        touches: vec![Touch::Content(section.id.clone())],
+    };
+    walk_section(&mut collector, section);
+    collector.touches
+}
+
+struct ContentCollector {
+    nested: bool,
+    touches: Vec<Touch>,
+}
+
+impl<'doc> DocumentVisitor<'doc> for ContentCollector {
+    fn visit_section(&mut self, section: &'doc Section) {
+        if self.nested {
+            self.touches.push(Touch::Position(section.id.clone()));
+            self.touches.push(Touch::Content(section.id.clone()));
+            self.touches.push(Touch::Removed(section.id.clone()));
+            walk_section(self, section);
+        }
+    }
+
+    fn visit_math_node(&mut self, node: &'doc MathNode) {
+        self.touches.push(Touch::Math {
+            root_id: node.id.clone(),
+            path: NodePath::root(),
+        });
+    }
+}
+
+fn to_json(operation: &PatchOperation) -> Value {
+    serde_json::to_value(operation).unwrap_or_default()
+}
+
+impl fmt::Display for MergeConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MergeConflict::BothChanged { id, .. } => {
+                write!(f, "both sides changed `{}`; kept ours", id)
+            }
+            MergeConflict::NotApplicable { error, .. } => {
+                write!(f, "their change does not apply to ours: {}", error)
+            }
+        }
+    }
+}
@@ -0,0 +1,665 @@
+use super::*;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Number, Value};
+use std::fmt;
+
+/// MessagePack for the WebSocket transport: the same data model as the JSON the bindings
+/// describe, so the frontend decodes it with any MessagePack library into the same objects,
+/// but smaller and faster to parse. Integers use the smallest encoding that holds them; other
+/// numbers are 64-bit floats.
+pub fn to_message_pack(value: &impl Serialize) -> Result<Vec<u8>, MessagePackError> {
+    let value =
+        serde_json::to_value(value).map_err(|error| MessagePackError::Serde(error.to_string()))?;
+    let mut bytes = vec![];
+    write_value(&value, &mut bytes);
+    Ok(bytes)
+}
+
+/// Reads a value written by `to_message_pack`, or by a MessagePack encoder on the other end.
+/// Binary data becomes an array of bytes; map keys must be strings and extension types are not
+/// supported. Arrays and maps may be nested `MAX_DEPTH` deep, so that a hostile message cannot
+/// exhaust the stack.
+pub fn from_message_pack<T: DeserializeOwned>(bytes: &[u8]) -> Result<T, MessagePackError> {
+    let mut reader = Reader {
+        bytes,
+        offset: 0,
+        depth: 0,
+    };
+    let value = reader.read_value()?;
+    if reader.offset < bytes.len() {
+        return Err(MessagePackError::TrailingBytes {
+            offset: reader.offset,
+        });
+    }
+    serde_json::from_value(value).map_err(|error| MessagePackError::Serde(error.to_string()))
+}
+
+/// How deeply `from_message_pack` lets arrays and maps nest, as serde_json does for JSON.
+pub const MAX_DEPTH: usize = 128;
+
+impl MathDocument {
+    pub fn to_message_pack(&self) -> Result<Vec<u8>, MessagePackError> {
+        to_message_pack(self)
+    }
+
+    pub fn from_message_pack(bytes: &[u8]) -> Result<MathDocument, MessagePackError> {
+        from_message_pack(bytes)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MessagePackError {
+    UnexpectedEnd,
+    UnsupportedMarker {
+        marker: u8,
+        offset: usize,
+    },
+    InvalidUtf8 {
+        offset: usize,
+    },
+    NonStringKey {
+        offset: usize,
+    },
+    TrailingBytes {
+        offset: usize,
+    },
+    TooDeep {
+        offset: usize, // the array or map past `MAX_DEPTH`
+    },
+    /// The data does not fit the type, or the type cannot be serialized.
+    Serde(String),
+}
+
+fn write_value(value: &Value, out: &mut Vec<u8>) {
+    match value {
+        Value::Null => out.push(0xc0),
+        Value::Bool(false) => out.push(0xc2),
+        Value::Bool(true) => out.push(0xc3),
+        Value::Number(number) => write_number(number, out),
+        Value::String(text) => {
+            let len = text.len();
+            if len < 32 {
+                out.push(0xa0 | len as u8);
+            } else if len <= u8::MAX as usize {
+                out.extend([0xd9, len as u8]);
+            } else if len <= u16::MAX as usize {
+                out.push(0xda);
+                out.extend((len as u16).to_be_bytes());
+            } else {
+                out.push(0xdb);
+                out.extend((len as u32).to_be_bytes());
+            }
+            out.extend(text.as_bytes());
+        }
+        Value::Array(items) => {
+            write_length(items.len(), [0x90, 0xdc, 0xdd], out);
+            for item in items {
+                write_value(item, out);
+            }
+        }
+        Value::Object(fields) => {
+            write_length(fields.len(), [0x80, 0xde, 0xdf], out);
+            for (key, field) in fields {
+                write_value(&Value::String(key.clone()), out);
+                write_value(field, out);
+            }
+        }
+    }
+}
+
+/// The header of an array or map: the fix form for up to 15 entries, then 16 and 32 bits.
+fn write_length(len: usize, [fix, short, long]: [u8; 3], out: &mut Vec<u8>) {
+    if len < 16 {
+        out.push(fix | len as u8);
+    } else if len <= u16::MAX as usize {
+        out.push(short);
+        out.extend((len as u16).to_be_bytes());
+    } else {
+        out.push(long);
+        out.extend((len as u32).to_be_bytes());
+    }
+}
+
+fn write_number(number: &Number, out: &mut Vec<u8>) {
+    if let Some(n) = number.as_u64() {
+        if n < 0x80 {
+            out.push(n as u8);
+        } else if n <= u8::MAX as u64 {
+            out.extend([0xcc, n as u8]);
+        } else if n <= u16::MAX as u64 {
+            out.push(0xcd);
+            out.extend((n as u16).to_be_bytes());
+        } else if n <= u32::MAX as u64 {
+            out.push(0xce);
+            out.extend((n as u32).to_be_bytes());
+        } else {
+            out.push(0xcf);
+            out.extend(n.to_be_bytes());
+        }
+    } else if let Some(n) = number.as_i64() {
+        // Negative, as non-negative integers fit `u64`.
+        if n >= -32 {
+            out.push(n as i8 as u8);
+        } else if n >= i8::MIN as i64 {
+            out.extend([0xd0, n as i8 as u8]);
+        } else if n >= i16::MIN as i64 {
+            out.push(0xd1);
+            out.extend((n as i16).to_be_bytes());
+        } else if n >= i32::MIN as i64 {
+            out.push(0xd2);
+            out.extend((n as i32).to_be_bytes());
+        } else {
+            out.push(0xd3);
+            out.extend(n.to_be_bytes());
+        }
+    } else {
+        out.push(0xcb);
+        out.extend(number.as_f64().unwrap_or_default().to_be_bytes());
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+    depth: usize, // arrays and maps being read
+}
+
+impl Reader<'_> {
+    fn take(&mut self, count: usize) -> Result<&[u8], MessagePackError> {
+        let end = self
+            .offset
+            .checked_add(count)
+            .filter(|&end| end <= self.bytes.len())
+            .ok_or(MessagePackError::UnexpectedEnd)?;
+        let taken = &self.bytes[self.offset..end];
+        self.offset = end;
+        Ok(taken)
+    }
+
+    fn take_array<const N: usize>(&mut self) -> Result<[u8; N], MessagePackError> {
+        Ok(self.take(N)?.try_into().unwrap_or([0; N]))
+    }
+
+    fn read_length(&mut self, size: usize) -> Result<usize, MessagePackError> {
+        Ok(match size {
+            1 => self.take_array::<1>()?[0] as usize,
+            2 => u16::from_be_bytes(self.take_array()?) as usize,
+            _ => u32::from_be_bytes(self.take_array()?) as usize,
+        })
+    }
+
+    fn read_value(&mut self) -> Result<Value, MessagePackError> {
+        let offset = self.offset;
+        let marker = self.take_array::<1>()?[0];
+        let value = match marker {
+            0x00..=0x7f => Value::from(marker),
+            0x80..=0x8f => {
+                self.nested(offset, |reader| reader.read_map((marker & 0x0f) as usize))?
+            }
+            0x90..=0x9f => {
+                self.nested(offset, |reader| reader.read_array((marker & 0x0f) as usize))?
+            }
+            0xa0..=0xbf => self.read_string((marker & 0x1f) as usize)?,
+            0xc0 => Value::Null,
+            0xc2 => Value::Bool(false),
+            0xc3 => Value::Bool(true),
+            0xc4..=0xc6 => {
+                let len = self.read_length(1 << (marker - 0xc4))?;
+                Value::from(self.take(len)?.to_vec())
+            }
+            0xca => Value::from(f32::from_be_bytes(self.take_array()?) as f64),
+            0xcb => Value::from(f64::from_be_bytes(self.take_array()?)),
+            0xcc => Value::from(self.take_array::<1>()?[0]),
+            0xcd => Value::from(u16::from_be_bytes(self.take_array()?)),
+            0xce => Value::from(u32::from_be_bytes(self.take_array()?)),
+            0xcf => Value::from(u64::from_be_bytes(self.take_array()?)),
+            0xd0 => Value::from(i8::from_be_bytes(self.take_array()?)),
+            0xd1 => Value::from(i16::from_be_bytes(self.take_array()?)),
+            0xd2 => Value::from(i32::from_be_bytes(self.take_array()?)),
+            0xd3 => Value::from(i64::from_be_bytes(self.take_array()?)),
+            0xd9..=0xdb => {
+                let len = self.read_length(1 << (marker - 0xd9))?;
+                self.read_string(len)?
+            }
+            0xdc | 0xdd => {
+                let len = self.read_length(2 << (marker - 0xdc))?;
+                self.nested(offset, |reader| reader.read_array(len))?
+            }
+            0xde | 0xdf => {
+                let len = self.read_length(2 << (marker - 0xde))?;
+                self.nested(offset, |reader| reader.read_map(len))?
+            }
+            0xe0..=0xff => Value::from(marker as i8),
+            _ => return Err(MessagePackError::UnsupportedMarker { marker, offset }),
+        };
+        Ok(value)
+    }
+
+    /// `read` one level deeper, for the array or map at `offset`.
+    fn nested(
+        &mut self,
+        offset: usize,
+        read: impl FnOnce(&mut Self) -> Result<Value, MessagePackError>,
+    ) -> Result<Value, MessagePackError> {
+        if self.depth == MAX_DEPTH {
+            return Err(MessagePackError::TooDeep { offset });
+        }
+        self.depth += 1;
+        let value = read(self);
+        self.depth -= 1;
+        value
+    }
+
+    fn read_string(&mut self, len: usize) -> Result<Value, MessagePackError> {
+        let offset = self.offset;
+        let text = std::str::from_utf8(self.take(len)?)
+            .map_err(|_| MessagePackError::InvalidUtf8 { offset })?;
+        Ok(Value::String(text.to_string()))
+    }
+
+    fn read_array(&mut self, len: usize) -> Result<Value, MessagePackError> {
+        // Every item takes at least a byte, which bounds what a bogus length can allocate.
+        let mut items = Vec::with_capacity(len.min(self.bytes.len() - self.offset));
+        for _ in 0..len {
+            items.push(self.read_value()?);
+        }
+        Ok(Value::Array(items))
+    }
+
+    fn read_map(&mut self, len: usize) -> Result<Value, MessagePackError> {
+        let mut fields = Map::new();
+        for _ in 0..len {
+            let offset = self.offset;
+            let Value::String(key) = self.read_value()? else {
+                return Err(MessagePackError::NonStringKey { offset });
+            };
+            fields.insert(key, self.read_value()?);
+        }
+        Ok(Value::Object(fields))
+    }
+}
+
+impl fmt::Display for MessagePackError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MessagePackError::UnexpectedEnd => write!(f, "MessagePack data ends early"),
+            MessagePackError::UnsupportedMarker { marker, offset } => write!(
+                f,
+                "unsupported MessagePack marker {:#04x} at byte {}",
+                marker, offset
+            ),
+            MessagePackError::InvalidUtf8 { offset } => {
+                write!(f, "MessagePack string at byte {} is not UTF-8", offset)
+            }
+            MessagePackError::NonStringKey { offset } => {
+                write!(f, "MessagePack map key at byte {} is not a string", offset)
+            }
+            MessagePackError::TrailingBytes { offset } => {
+                write!(
+                    f,
+                    "unexpected data after the MessagePack value at byte {}",
+                    offset
+                )
+            }
+            MessagePackError::TooDeep { offset } => write!(
+                f,
+                "MessagePack data nests deeper than {} levels at byte {}",
+                MAX_DEPTH, offset
+            ),
+            MessagePackError::Serde(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for MessagePackError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn identifier(id: &str, body: &str) -> Value {
+        json!({
+            "id": id,
+            "content": {
+                "kind": "Identifier",
+                "data": {
+                    "body": body,
+                    "pre_script": null,
+                    "mid_script": null,
+                    "post_script": null,
+                    "primes": 0,
+                    "is_function": false
+                }
+            }
+        })
+    }
+
+    /// A document structure with one section holding `x/y`, so that nested sections and math
+    /// nodes go over the wire as well.
+    fn structure() -> Value {
+        json!({
+            "body": [{
+                "id": "s1",
+                "content": {
+                    "kind": "Math",
+                    "data": {
+                        "id": "m1",
+                        "content": {
+                            "kind": "Fraction",
+                            "data": {
+                                "numerator": identifier("m2", "x"),
+                                "denominator": identifier("m3", "y")
+                            }
+                        }
+                    }
+                },
+                "metadata": [["level", "L1"]]
+            }]
+        })
+    }
+
+    fn derivation_metadata() -> Value {
+        json!({ "derived_at": "2024-01-01", "human_reviewed": true })
+    }
+
+    fn presentation_config() -> Value {
+        json!({
+            "layout_style": "SingleColumn",
+            "target_audience": "Expert",
+            "formality_level": "FullyFormal"
+        })
+    }
+
+    fn relationship_metadata() -> Value {
+        json!({ "relationship_type": "generalizes", "properties": { "strict": "true" } })
+    }
+
+    fn extraction_metadata() -> Value {
+        json!({ "extracted_at": "2024-01-01", "extraction_method": "manual" })
+    }
+
+    /// One document of every `MathDocumentType` variant.
+    fn documents() -> Vec<(&'static str, Value)> {
+        vec![
+            (
+                "WikiPage",
+                json!({
+                    "title": "Groups",
+                    "theory_domain": "GroupTheory",
+                    "completeness_level": "Basic",
+                    "content_metadata": { "language": "en" },
+                    "structure": structure(),
+                    "relationships": { "parent_documents": ["algebra"] }
+                }),
+            ),
+            (
+                "Textbook",
+                json!({
+                    "title": "Linear Algebra",
+                    "course_level": "HighSchool",
+                    "content_metadata": {},
+                    "academic_metadata": {},
+                    "structure": structure(),
+                    "relationships": {}
+                }),
+            ),
+            (
+                "ScientificPaper",
+                json!({
+                    "title": "On Rings",
+                    "paper_type": "Research",
+                    "venue": "Annals",
+                    "peer_reviewed": true,
+                    "content_metadata": {},
+                    "academic_metadata": {},
+                    "structure": structure(),
+                    "relationships": {}
+                }),
+            ),
+            (
+                "PersonalNotes",
+                json!({
+                    "title": "Scratch",
+                    "author_level": "Expert",
+                    "note_style": "Formal",
+                    "content_metadata": {},
+                    "structure": structure(),
+                    "relationships": {}
+                }),
+            ),
+            (
+                "MathematicianNotes",
+                json!({
+                    "title": "Conjectures",
+                    "research_area": "Number theory",
+                    "formality_level": "FullyFormal",
+                    "content_metadata": {},
+                    "structure": structure(),
+                    "relationships": {}
+                }),
+            ),
+            (
+                "StudyNotes",
+                json!({
+                    "title": "Exam prep",
+                    "subject": "Calculus",
+                    "study_level": "HighSchool",
+                    "exam_prep": true,
+                    "content_metadata": {},
+                    "structure": structure(),
+                    "relationships": {}
+                }),
+            ),
+            (
+                "TooltipSummary",
+                json!({
+                    "summarization_level": "KeyDefinitionsOnly",
+                    "max_length": 280,
+                    "derivation_metadata": derivation_metadata(),
+                    "content": {},
+                    "presentation_config": presentation_config()
+                }),
+            ),
+            (
+                "BlogPost",
+                json!({
+                    "title": "Why groups?",
+                    "writing_style": "Formal",
+                    "target_audience": "Expert",
+                    "examples_included": true,
+                    "derivation_metadata": derivation_metadata(),
+                    "content": {},
+                    "presentation_config": presentation_config()
+                }),
+            ),
+            (
+                "AbstractSummary",
+                json!({
+                    "abstraction_level": 2,
+                    "key_properties": ["associativity"],
+                    "derivation_metadata": derivation_metadata(),
+                    "content": {},
+                    "presentation_config": presentation_config()
+                }),
+            ),
+            (
+                "ConceptMap",
+                json!({
+                    "central_concept": "group",
+                    "derivation_metadata": derivation_metadata(),
+                    "content": {},
+                    "presentation_config": presentation_config()
+                }),
+            ),
+            (
+                "AnimatedPresentation",
+                json!({
+                    "title": "Rotations",
+                    "slide_count": 12,
+                    "auto_advance": false,
+                    "interaction_system": {},
+                    "animation_timeline": { "total_duration": 30.5 }
+                }),
+            ),
+            (
+                "InteractivePlayground",
+                json!({
+                    "title": "Parabola",
+                    "parameter_space": { "default_values": { "a": 1.5, "b": -2 } },
+                    "real_time_feedback": true,
+                    "interaction_system": {}
+                }),
+            ),
+            (
+                "TypeMappingDisplay",
+                json!({
+                    "title": "Groups as categories",
+                    "source_theory": "GroupTheory",
+                    "target_theory": "CategoryTheory",
+                    "interaction_system": {}
+                }),
+            ),
+            (
+                "ResourcePanel",
+                json!({
+                    "title": "Further reading",
+                    "search_capabilities": {
+                        "full_text_search": true,
+                        "semantic_search": false,
+                        "filter_by_type": true
+                    },
+                    "interaction_system": {}
+                }),
+            ),
+            (
+                "ComparisonPage",
+                json!({
+                    "title": "Groups and monoids",
+                    "highlight_differences": true,
+                    "synchronized_navigation": false,
+                    "relationship_metadata": relationship_metadata(),
+                    "comparison_structure": {
+                        "comparison_type": "definitions",
+                        "layout": "SideBySide"
+                    }
+                }),
+            ),
+            (
+                "TransformationMapping",
+                json!({
+                    "title": "Fourier transform",
+                    "transformation_type": {
+                        "transformation_id": "fourier",
+                        "name": "Fourier",
+                        "reversible": true
+                    },
+                    "step_by_step": true,
+                    "bidirectional": true,
+                    "source_theory": "Analysis",
+                    "target_theory": "HarmonicAnalysis",
+                    "relationship_metadata": relationship_metadata()
+                }),
+            ),
+            (
+                "ConceptAlignment",
+                json!({
+                    "title": "Vector spaces and modules",
+                    "alignment_type": {
+                        "alignment_id": "generalization",
+                        "name": "Generalization",
+                        "precision_level": "Exact"
+                    },
+                    "relationship_metadata": relationship_metadata()
+                }),
+            ),
+            (
+                "StaticPreview",
+                json!({
+                    "source_document_id": "groups",
+                    "content_snapshot": {},
+                    "last_updated": "2024-01-01",
+                    "auto_refresh": false,
+                    "extraction_metadata": extraction_metadata(),
+                    "viewport_config": { "width": "400px", "zoom_level": 1.25 },
+                    "interaction_level": "ReadOnly"
+                }),
+            ),
+            (
+                "LiveEmbed",
+                json!({
+                    "source_document_id": "groups",
+                    "sync_with_source": true,
+                    "extraction_metadata": extraction_metadata(),
+                    "viewport_config": {},
+                    "interaction_level": "ReadOnly"
+                }),
+            ),
+            (
+                "ConceptExtract",
+                json!({
+                    "source_document_id": "groups",
+                    "extracted_concepts": ["identity", "inverse"],
+                    "context_preservation": {
+                        "level_name": "full",
+                        "preserve_structure": true,
+                        "preserve_formatting": true,
+                        "preserve_links": false,
+                        "preserve_metadata": false
+                    },
+                    "extraction_metadata": extraction_metadata(),
+                    "viewport_config": {},
+                    "interaction_level": "ReadOnly"
+                }),
+            ),
+            (
+                "IFrameEmbed",
+                json!({
+                    "source_document_id": "groups",
+                    "responsive_scaling": true,
+                    "extraction_metadata": extraction_metadata(),
+                    "viewport_config": {},
+                    "interaction_level": "ReadOnly"
+                }),
+            ),
+        ]
+    }
+
+    #[test]
+    fn every_document_type_round_trips() {
+        let documents = documents();
+        assert_eq!(
+            documents.len(),
+            21,
+            "one document per `MathDocumentType` variant"
+        );
+        for (kind, data) in documents {
+            let json = json!({ "id": kind, "content_type": { "kind": kind, "data": data } });
+            let document: MathDocument = serde_json::from_value(json).unwrap();
+            assert_eq!(document.content_type.type_name(), kind);
+
+            let bytes = document.to_message_pack().unwrap();
+            let decoded = MathDocument::from_message_pack(&bytes).unwrap();
+            assert_eq!(
+                serde_json::to_value(&decoded).unwrap(),
+                serde_json::to_value(&document).unwrap(),
+                "{} changed on the way through MessagePack",
+                kind
+            );
+        }
+    }
+
+    #[test]
+    fn deep_nesting_is_an_error() {
+        let bytes = vec![0x91; 200_000];
+        assert_eq!(
+            from_message_pack::<Value>(&bytes),
+            Err(MessagePackError::TooDeep { offset: MAX_DEPTH })
+        );
+
+        let mut bytes = vec![0x91; MAX_DEPTH - 1];
+        bytes.push(0x90);
+        assert!(from_message_pack::<Value>(&bytes).is_ok());
+    }
+}
@@ -0,0 +1,115 @@
+use super::*;
+use serde::Deserialize;
+use serde::de::{self, Deserializer, IgnoredAny, MapAccess, Visitor};
+use std::borrow::Cow;
+use std::fmt;
+
+/// The metadata of a serialized `MathDocument`, read without building the document: strings
+/// borrow from the input where it has no escapes, and sections, relationships and everything
+/// else are skipped over unparsed. For endpoints that scan many stored documents to list,
+/// filter or check freshness, and only load the few they return.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct DocumentMetadataView<'a> {
+    #[serde(borrow)]
+    pub id: Cow<'a, str>,
+    #[serde(borrow, rename = "content_type")]
+    pub content: ContentTypeView<'a>,
+}
+
+/// The `content_type` of a serialized document, see `DocumentMetadataView`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContentTypeView<'a> {
+    pub type_name: Cow<'a, str>, // as in `MathDocumentType::type_name`
+    pub title: Option<Cow<'a, str>>,
+    pub content_metadata: Option<ContentMetadataView<'a>>, // only on the primary knowledge types
+}
+
+/// `ContentMetadata` with borrowed strings.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub struct ContentMetadataView<'a> {
+    #[serde(borrow, default, deserialize_with = "borrow_optional")]
+    pub language: Option<Cow<'a, str>>,
+    #[serde(borrow, default, deserialize_with = "borrow_optional")]
+    pub version: Option<Cow<'a, str>>,
+    #[serde(borrow, default, deserialize_with = "borrow_optional")]
+    pub created_at: Option<Cow<'a, str>>,
+    #[serde(borrow, default, deserialize_with = "borrow_optional")]
+    pub last_modified: Option<Cow<'a, str>>,
+    #[serde(borrow, default, deserialize_with = "borrow_optional")]
+    pub content_hash: Option<Cow<'a, str>>,
+}
+
+impl<'a> DocumentMetadataView<'a> {
+    pub fn from_json(json: &'a str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    pub fn from_json_slice(json: &'a [u8]) -> Result<Self, serde_json::Error> {
+        serde_json::from_slice(json)
+    }
+}
+
+impl MathDocument {
+    /// See `DocumentMetadataView`.
+    pub fn read_metadata(json: &str) -> Result<DocumentMetadataView<'_>, serde_json::Error> {
+        DocumentMetadataView::from_json(json)
+    }
+}
+
+/// A string borrowed from the input when possible; serde only borrows a `Cow` as a field of
+/// its own, not inside an `Option` or as a map key.
+#[derive(Deserialize)]
+struct BorrowedStr<'a>(#[serde(borrow)] Cow<'a, str>);
+
+fn borrow_optional<'de: 'a, 'a, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Cow<'a, str>>, D::Error> {
+    Ok(Option::<BorrowedStr>::deserialize(deserializer)?.map(|BorrowedStr(text)| text))
+}
+
+/// The fields the view reads from inside a content type.
+#[derive(Default, Deserialize)]
+struct ContentFields<'a> {
+    #[serde(borrow, default, deserialize_with = "borrow_optional")]
+    title: Option<Cow<'a, str>>,
+    #[serde(borrow, default)]
+    content_metadata: Option<ContentMetadataView<'a>>,
+}
+
+impl<'de: 'a, 'a> Deserialize<'de> for ContentTypeView<'a> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_map(ContentTypeVisitor)
+    }
+}
+
+/// Reads the adjacently tagged `{ "kind": "WikiPage", "data": { ... } }` form, with the keys in
+/// either order.
+struct ContentTypeVisitor;
+
+impl<'de> Visitor<'de> for ContentTypeVisitor {
+    type Value = ContentTypeView<'de>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "a document content type")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut type_name = None;
+        let mut fields = ContentFields::default();
+        while let Some(BorrowedStr(key)) = map.next_key()? {
+            match key.as_ref() {
+                ENUM_TAG_KEY => type_name = Some(map.next_value::<BorrowedStr>()?.0),
+                ENUM_CONTENT_KEY => fields = map.next_value()?,
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+        let type_name = type_name.ok_or_else(|| de::Error::missing_field(ENUM_TAG_KEY))?;
+        Ok(ContentTypeView {
+            type_name,
+            title: fields.title,
+            content_metadata: fields.content_metadata,
+        })
+    }
+}
@@ -1,13 +1,176 @@
+pub mod accessibility;
+pub mod archive;
+pub mod auto_fold;
+pub mod backlinks;
+pub mod bibliography;
+pub mod bundle;
+pub mod cancellation;
+pub mod canonical;
+pub mod canonical_json;
+pub mod capabilities;
+pub mod chemistry;
+pub mod collab;
+pub mod combinatorics;
+pub mod component_registry;
+pub mod content_hash;
+pub mod correspondence;
+pub mod dependency_graph;
+pub mod derivation_chain;
+pub mod diagnostic;
+pub mod differentiation;
+pub mod difficulty;
+pub mod dimension_check;
+pub mod document_diff;
+pub mod document_patch;
+pub mod document_walker;
+pub mod document_walker_mut;
+pub mod equation;
+pub mod evaluation;
+pub mod feedback;
+pub mod fold;
+pub mod free_identifiers;
+pub mod generation_error;
+pub mod geometry;
+pub mod hash_consing;
+pub mod id_remap;
+pub mod impl_macros;
+pub mod interaction;
+pub mod interning;
+pub mod interval;
+pub mod labels;
+pub mod layout_builder;
+pub mod lazy_document;
+pub mod links;
+pub mod lms_export;
+pub mod math_arena;
+pub mod math_diff;
 pub mod math_document;
+pub mod math_lint;
+pub mod math_metrics;
 pub mod math_node;
+pub mod math_node_transformer;
+pub mod math_node_visitor;
+pub mod math_search;
+pub mod math_styling;
+pub mod math_zipper;
+pub mod matrix_notation;
+pub mod merge;
+pub mod message_pack;
+pub mod metadata_view;
+pub mod node_path;
+pub mod panel_layout;
+pub mod parallel;
+pub mod parameter_space;
+pub mod pattern;
+pub mod protobuf;
+pub mod quantity_format;
+pub mod query;
+pub mod references;
+pub mod review;
+pub mod revision_history;
 pub mod rich_text;
+pub mod schema_manifest;
 pub mod second_order_math_node;
 pub mod section_node;
+pub mod section_refs;
+pub mod simplification;
+pub mod spacing;
+pub mod spreadsheet_export;
+pub mod static_export;
+pub mod streaming;
 pub mod textline_node;
+pub mod theory;
+pub mod thread_safety;
+pub mod toc;
+pub mod ts_index;
+pub mod unit_interval;
+pub mod units;
+pub mod validation;
+pub mod variable_context;
+pub mod variant_seed;
+pub mod vector_notation;
+pub mod web_annotation;
+pub mod well_formed;
 
+pub use accessibility::*;
+pub use archive::*;
+pub use auto_fold::*;
+pub use backlinks::*;
+pub use bibliography::*;
+pub use bundle::*;
+pub use canonical::*;
+pub use canonical_json::*;
+pub use capabilities::*;
+pub use chemistry::*;
+pub use collab::*;
+pub use component_registry::*;
+pub use content_hash::*;
+pub use correspondence::*;
+pub use dependency_graph::*;
+pub use derivation_chain::*;
+pub use diagnostic::*;
+pub use differentiation::*;
+pub use difficulty::*;
+pub use dimension_check::*;
+pub use document_patch::*;
+pub use document_walker::*;
+pub use document_walker_mut::*;
+pub use equation::*;
+pub use evaluation::*;
+pub use feedback::*;
+pub use fold::*;
+pub use free_identifiers::*;
+pub use generation_error::*;
+pub use hash_consing::*;
+pub use impl_macros::*;
+pub use interaction::*;
+pub use interning::*;
+pub use interval::*;
+pub use labels::*;
+pub use layout_builder::*;
+pub use lazy_document::*;
+pub use links::*;
+pub use lms_export::*;
+pub use math_arena::*;
+pub use math_diff::*;
 pub use math_document::*;
+pub use math_lint::*;
+pub use math_metrics::*;
 pub use math_node::*;
+pub use math_node_transformer::*;
+pub use math_node_visitor::*;
+pub use math_search::*;
+pub use math_zipper::*;
+pub use merge::*;
+pub use message_pack::*;
+pub use metadata_view::*;
+pub use node_path::*;
+pub use panel_layout::*;
+pub use parallel::*;
+pub use parameter_space::*;
+pub use pattern::*;
+pub use protobuf::*;
+pub use quantity_format::*;
+pub use query::*;
+pub use references::*;
+pub use review::*;
+pub use revision_history::*;
 pub use rich_text::*;
+pub use schema_manifest::*;
 pub use second_order_math_node::*;
 pub use section_node::*;
+pub use section_refs::*;
+pub use simplification::*;
+pub use spreadsheet_export::*;
+pub use streaming::*;
 pub use textline_node::*;
+pub use theory::*;
+pub use toc::*;
+pub use ts_index::*;
+pub use unit_interval::*;
+pub use units::*;
+pub use validation::*;
+pub use variable_context::*;
+pub use variant_seed::*;
+pub use web_annotation::*;
+pub use well_formed::*;
@@ -0,0 +1,577 @@
+use super::*;
+use serde::{Deserialize, Serialize};
+use std::{fmt, sync::Arc};
+use ts_rs::TS;
+
+/// Location of a node below some root `MathNode`, as the field names and indices to follow.
+/// Displayed as e.g. `numerator.terms[1]`; the empty path is the root itself.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct NodePath {
+    pub segments: Vec<PathSegment>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub enum PathSegment {
+    Field(String), // named field of a MathNodeContent variant, e.g. "numerator"
+    Index(usize),  // position in a list, or in a tuple variant such as `Not`
+}
+
+impl NodePath {
+    pub fn root() -> Self {
+        NodePath::default()
+    }
+
+    pub fn is_root(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.segments.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    pub fn field(mut self, name: impl Into<String>) -> Self {
+        self.segments.push(PathSegment::Field(name.into()));
+        self
+    }
+
+    pub fn index(mut self, index: usize) -> Self {
+        self.segments.push(PathSegment::Index(index));
+        self
+    }
+
+    /// This path followed by `relative`.
+    pub fn join(&self, relative: &NodePath) -> NodePath {
+        NodePath {
+            segments: self
+                .segments
+                .iter()
+                .chain(&relative.segments)
+                .cloned()
+                .collect(),
+        }
+    }
+
+    pub fn parent(&self) -> Option<NodePath> {
+        let (_, parent) = self.segments.split_last()?;
+        Some(NodePath {
+            segments: parent.to_vec(),
+        })
+    }
+
+    pub fn starts_with(&self, prefix: &NodePath) -> bool {
+        self.segments.starts_with(&prefix.segments)
+    }
+}
+
+impl fmt::Display for NodePath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (position, segment) in self.segments.iter().enumerate() {
+            match segment {
+                PathSegment::Field(name) if position == 0 => write!(f, "{}", name)?,
+                PathSegment::Field(name) => write!(f, ".{}", name)?,
+                PathSegment::Index(index) => write!(f, "[{}]", index)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl MathNodeContent {
+    /// Direct children paired with their path relative to this node, in the same order as
+    /// `map_children` and `walk_node`.
+    pub fn children_with_paths(&self) -> Vec<(NodePath, &MathNode)> {
+        let mut children = ChildPaths::default();
+        match self {
+            MathNodeContent::Empty
+            | MathNodeContent::Text(_)
+            | MathNodeContent::String(_)
+            | MathNodeContent::Space(_)
+            | MathNodeContent::ChemicalExpression(_)
+            | MathNodeContent::True
+            | MathNodeContent::False => {}
+            MathNodeContent::Phantom { inner, .. } => children.field("inner", inner),
+            MathNodeContent::Bracketed { inner, .. } => children.field("inner", inner),
+            MathNodeContent::Matrix {
+                rows,
+                row_labels,
+                column_labels,
+                highlights,
+                ..
+            } => {
+                for (i, row) in rows.iter().enumerate() {
+                    for (j, entry) in row.iter().enumerate() {
+                        children.push(NodePath::root().field("rows").index(i).index(j), entry);
+                    }
+                }
+                children.list("row_labels", row_labels);
+                children.list("column_labels", column_labels);
+                for (i, highlight) in highlights.iter().enumerate() {
+                    if let Some(annotation) = &highlight.annotation {
+                        let path = NodePath::root().field("highlights").index(i);
+                        children.push(path.field("annotation"), annotation);
+                    }
+                }
+            }
+            MathNodeContent::BinaryOperation { terms, .. } => {
+                children.list("terms", terms.iter().map(|(_, term)| term))
+            }
+            MathNodeContent::Multiplications { terms } => {
+                children.list("terms", terms.iter().map(|(_, term)| term))
+            }
+            MathNodeContent::Additions { terms } => {
+                children.list("terms", terms.iter().map(|(_, term)| term))
+            }
+            MathNodeContent::Division {
+                numerator,
+                denominator,
+                ..
+            }
+            | MathNodeContent::Fraction {
+                numerator,
+                denominator,
+            } => {
+                children.field("numerator", numerator);
+                children.field("denominator", denominator);
+            }
+            MathNodeContent::SumNotation {
+                summand,
+                variable,
+                lower_limit,
+                upper_limit,
+            } => {
+                children.field("summand", summand);
+                children.optional("variable", variable.as_ref());
+                children.optional("lower_limit", lower_limit.as_deref());
+                children.optional("upper_limit", upper_limit.as_deref());
+            }
+            MathNodeContent::ProductNotation {
+                multiplicand,
+                variable,
+                lower_limit,
+                upper_limit,
+            } => {
+                children.field("multiplicand", multiplicand);
+                children.optional("variable", variable.as_ref());
+                children.optional("lower_limit", lower_limit.as_deref());
+                children.optional("upper_limit", upper_limit.as_deref());
+            }
+            MathNodeContent::Power { base, exponent } => {
+                children.field("base", base);
+                children.field("exponent", exponent);
+            }
+            MathNodeContent::Radical { radicand, index } => {
+                children.field("radicand", radicand);
+                children.optional("index", index.as_deref());
+            }
+            MathNodeContent::Binomial { upper, lower, .. } => {
+                children.field("upper", upper);
+                children.field("lower", lower);
+            }
+            MathNodeContent::Cases { branches } => {
+                for (i, (expression, condition)) in branches.iter().enumerate() {
+                    let base = NodePath::root().field("branches").index(i);
+                    children.push(base.clone().index(0), expression);
+                    children.push(base.index(1), condition);
+                }
+            }
+            MathNodeContent::UnaryPostfixOperation {
+                parameter,
+                operator,
+            }
+            | MathNodeContent::UnaryPrefixOperation {
+                parameter,
+                operator,
+            } => {
+                children.field("parameter", parameter);
+                children.field("operator", operator);
+            }
+            MathNodeContent::Abs { parameter } => children.field("parameter", parameter),
+            MathNodeContent::Norm { inner, subscript } => {
+                children.field("inner", inner);
+                children.optional("subscript", subscript.as_deref());
+            }
+            MathNodeContent::Decorated { inner, .. } | MathNodeContent::Styled { inner, .. } => {
+                children.field("inner", inner)
+            }
+            MathNodeContent::Cancelled {
+                inner, replacement, ..
+            } => {
+                children.field("inner", inner);
+                children.optional("replacement", replacement.as_deref());
+            }
+            MathNodeContent::AngleMeasure { value, .. } => children.field("value", value),
+            MathNodeContent::AngleSymbol { vertices } => children.list("vertices", vertices),
+            MathNodeContent::Coordinates { point, components } => {
+                children.optional("point", point.as_deref());
+                children.list("components", components);
+            }
+            MathNodeContent::Brace { inner, label, .. } => {
+                children.field("inner", inner);
+                children.field("label", label);
+            }
+            MathNodeContent::Bra { state } | MathNodeContent::Ket { state } => {
+                children.field("state", state)
+            }
+            MathNodeContent::BraKet { bra, operator, ket } => {
+                children.field("bra", bra);
+                children.optional("operator", operator.as_deref());
+                children.field("ket", ket);
+            }
+            MathNodeContent::Expectation { operator, state } => {
+                children.field("operator", operator);
+                children.optional("state", state.as_deref());
+            }
+            MathNodeContent::FunctionCall { name, parameters } => {
+                children.field("name", name);
+                children.list("parameters", parameters);
+            }
+            MathNodeContent::Quantity {
+                scientific_notation,
+                unit,
+                ..
+            } => {
+                children.optional("scientific_notation", scientific_notation.as_ref());
+                children.optional("unit", unit.as_ref());
+            }
+            MathNodeContent::ScientificNotation { magnitude, .. } => {
+                children.field("magnitude", magnitude)
+            }
+            MathNodeContent::Identifier(identifier) => {
+                for (name, script) in [
+                    ("pre_script", &identifier.pre_script),
+                    ("post_script", &identifier.post_script),
+                ] {
+                    if let Some(script) = script {
+                        let base = NodePath::root().field(name);
+                        for (i, subscript) in script.subscripts.iter().enumerate() {
+                            children.push(base.clone().field("subscripts").index(i), subscript);
+                        }
+                        for (i, superscript) in script.superscripts.iter().enumerate() {
+                            children.push(base.clone().field("superscripts").index(i), superscript);
+                        }
+                    }
+                }
+            }
+            MathNodeContent::Unit {
+                original_form,
+                flattened_form,
+            } => {
+                children.field("original_form", original_form);
+                children.field("flattened_form", flattened_form);
+            }
+            MathNodeContent::Relationship { lhs, rhs, .. } => {
+                children.field("lhs", lhs);
+                children.field("rhs", rhs);
+            }
+            MathNodeContent::UnaryRelationship { subject, .. } => {
+                children.field("subject", subject)
+            }
+            MathNodeContent::VariableDefinition { name, definition } => {
+                children.field("name", name);
+                children.optional("definition", definition.as_ref());
+            }
+            MathNodeContent::FunctionDefinition {
+                custom_function,
+                definition,
+                clauses,
+            } => {
+                children.field("custom_function", custom_function);
+                children.optional("definition", definition.as_ref());
+                for (i, clause) in clauses.iter().enumerate() {
+                    let base = NodePath::root().field("clauses").index(i);
+                    for (j, argument) in clause.arguments.iter().enumerate() {
+                        children.push(base.clone().field("arguments").index(j), argument);
+                    }
+                    if let Some(condition) = &clause.condition {
+                        children.push(base.clone().field("condition"), condition);
+                    }
+                    children.push(base.field("definition"), &clause.definition);
+                }
+            }
+            MathNodeContent::Limit {
+                function,
+                approaching_value,
+                ..
+            } => {
+                children.field("function", function);
+                children.field("approaching_value", approaching_value);
+            }
+            MathNodeContent::Differential {
+                target,
+                order,
+                variable,
+                ..
+            } => {
+                children.field("target", target);
+                children.field("order", order);
+                children.optional("variable", variable.as_deref());
+            }
+            MathNodeContent::Integration {
+                integrand,
+                differentials,
+                domain,
+            } => {
+                children.field("integrand", integrand);
+                for (i, (differential, lower_bound, upper_bound)) in
+                    differentials.iter().enumerate()
+                {
+                    let base = NodePath::root().field("differentials").index(i);
+                    children.push(base.clone().index(0), differential);
+                    if let Some(lower_bound) = lower_bound {
+                        children.push(base.clone().index(1), lower_bound);
+                    }
+                    if let Some(upper_bound) = upper_bound {
+                        children.push(base.index(2), upper_bound);
+                    }
+                }
+                children.optional("domain", domain.as_deref());
+            }
+            MathNodeContent::SetExplicit { elements } => children.list("elements", elements),
+            MathNodeContent::SetBuilder {
+                variable,
+                domain,
+                predicate,
+            } => {
+                children.field("variable", variable);
+                children.optional("domain", domain.as_deref());
+                children.field("predicate", predicate);
+            }
+            MathNodeContent::QuantifiedExpression {
+                variables,
+                domain,
+                predicate,
+                ..
+            } => {
+                children.list("variables", variables);
+                children.optional("domain", domain.as_deref());
+                children.optional("predicate", predicate.as_deref());
+            }
+            MathNodeContent::RichTextContent(segments) => {
+                for (i, segment) in segments.iter().enumerate() {
+                    if let MathTextSegment::Math(node) = segment {
+                        children.push(NodePath::root().index(i), node);
+                    }
+                }
+            }
+            MathNodeContent::And(operands) | MathNodeContent::Or(operands) => {
+                for (i, operand) in operands.iter().enumerate() {
+                    children.push(NodePath::root().index(i), operand);
+                }
+            }
+            MathNodeContent::Not(operand) => children.push(NodePath::root().index(0), operand),
+            MathNodeContent::Sequent {
+                context,
+                conclusion,
+                ..
+            } => {
+                children.list("context", context);
+                children.field("conclusion", conclusion);
+            }
+            MathNodeContent::Folded { content, .. } => children.field("content", content),
+        }
+        children.children
+    }
+}
+
+impl MathNode {
+    /// This node and all of its descendants, each with its path from this node, in pre-order.
+    pub fn descendants_with_paths(&self) -> Vec<(NodePath, &MathNode)> {
+        fn collect<'a>(
+            node: &'a MathNode,
+            path: NodePath,
+            descendants: &mut Vec<(NodePath, &'a MathNode)>,
+        ) {
+            for (relative, child) in node.content.children_with_paths() {
+                let child_path = path.join(&relative);
+                descendants.push((child_path.clone(), child));
+                collect(child, child_path, descendants);
+            }
+        }
+
+        let mut descendants = vec![(NodePath::root(), self)];
+        collect(self, NodePath::root(), &mut descendants);
+        descendants
+    }
+
+    /// The node at `path` below this one, if the path exists.
+    pub fn get_at_path(&self, path: &NodePath) -> Option<&MathNode> {
+        let mut node = self;
+        let mut rest = path.segments.as_slice();
+        while !rest.is_empty() {
+            let (relative, child) = child_along(node, rest)?;
+            node = child;
+            rest = &rest[relative.len()..];
+        }
+        Some(node)
+    }
+
+    /// Replaces the node at `path` with `replacement`, returning the node that was there.
+    /// Only the nodes along the path are rebuilt; every other subtree keeps its shared content.
+    pub fn replace_at_path(
+        &mut self,
+        path: &NodePath,
+        replacement: MathNode,
+    ) -> Result<MathNode, NodePathError> {
+        let (replaced, previous) =
+            replace_below(self, &path.segments, replacement).ok_or_else(|| {
+                NodePathError::PathNotFound {
+                    root_id: self.id.clone(),
+                    path: path.clone(),
+                }
+            })?;
+        *self = replaced;
+        Ok(previous)
+    }
+}
+
+/// The child of `node` whose relative path begins `segments`.
+fn child_along<'a>(
+    node: &'a MathNode,
+    segments: &[PathSegment],
+) -> Option<(NodePath, &'a MathNode)> {
+    node.content
+        .children_with_paths()
+        .into_iter()
+        .find(|(relative, _)| segments.starts_with(&relative.segments))
+}
+
+/// `node` with the subtree at `segments` swapped for `replacement`, and the subtree it replaced.
+fn replace_below(
+    node: &MathNode,
+    segments: &[PathSegment],
+    replacement: MathNode,
+) -> Option<(MathNode, MathNode)> {
+    if segments.is_empty() {
+        return Some((replacement, node.clone()));
+    }
+    let children = node.content.children_with_paths();
+    let position = children
+        .iter()
+        .position(|(relative, _)| segments.starts_with(&relative.segments))?;
+    let (relative, child) = &children[position];
+    let (new_child, previous) = replace_below(child, &segments[relative.len()..], replacement)?;
+
+    // `map_children` visits children in the same order as `children_with_paths`.
+    let mut new_child = Some(new_child);
+    let mut index = 0;
+    let content = node.content.map_children(|_| {
+        let mapped = if index == position {
+            new_child.take()
+        } else {
+            None
+        };
+        index += 1;
+        mapped
+    })?;
+    Some((
+        MathNode {
+            id: node.id.clone(),
+            content: Arc::new(content),
+        },
+        previous,
+    ))
+}
+
+impl MathDocument {
+    /// The node at `path` below the embedded expression whose id is `root_id`.
+    /// If several expressions share the id, the first in document order is used.
+    pub fn get_at_path(&self, root_id: &str, path: &NodePath) -> Option<&MathNode> {
+        self.iter_math_nodes()
+            .find(|root| root.id == root_id)?
+            .get_at_path(path)
+    }
+
+    /// Replaces the node at `path` below the expression whose id is `root_id`, returning the
+    /// node that was there. Like `get_at_path`, only the first expression with that id is edited.
+    pub fn replace_at_path(
+        &mut self,
+        root_id: &str,
+        path: &NodePath,
+        replacement: MathNode,
+    ) -> Result<MathNode, NodePathError> {
+        let mut replacer = PathReplacer {
+            root_id,
+            path,
+            replacement: Some(replacement),
+            result: None,
+        };
+        replacer.visit_document_mut(self);
+        replacer.result.unwrap_or_else(|| {
+            Err(NodePathError::UnknownRoot {
+                root_id: root_id.to_string(),
+            })
+        })
+    }
+}
+
+struct PathReplacer<'a> {
+    root_id: &'a str,
+    path: &'a NodePath,
+    replacement: Option<MathNode>,
+    result: Option<Result<MathNode, NodePathError>>,
+}
+
+impl DocumentVisitorMut for PathReplacer<'_> {
+    fn visit_math_node_mut(&mut self, node: &mut MathNode) {
+        if node.id != self.root_id {
+            return;
+        }
+        if let Some(replacement) = self.replacement.take() {
+            self.result = Some(node.replace_at_path(self.path, replacement));
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum NodePathError {
+    UnknownRoot { root_id: String },
+    PathNotFound { root_id: String, path: NodePath },
+}
+
+impl fmt::Display for NodePathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NodePathError::UnknownRoot { root_id } => {
+                write!(f, "no expression with id `{}`", root_id)
+            }
+            NodePathError::PathNotFound { root_id, path } => {
+                write!(f, "expression `{}` has no node at `{}`", root_id, path)
+            }
+        }
+    }
+}
+
+impl std::error::Error for NodePathError {}
+
+#[derive(Default)]
+struct ChildPaths<'a> {
+    children: Vec<(NodePath, &'a MathNode)>,
+}
+
+impl<'a> ChildPaths<'a> {
+    fn push(&mut self, path: NodePath, node: &'a MathNode) {
+        self.children.push((path, node));
+    }
+
+    fn field(&mut self, name: &str, node: &'a MathNode) {
+        self.push(NodePath::root().field(name), node);
+    }
+
+    fn optional(&mut self, name: &str, node: Option<&'a MathNode>) {
+        if let Some(node) = node {
+            self.field(name, node);
+        }
+    }
+
+    fn list(&mut self, name: &str, nodes: impl IntoIterator<Item = &'a MathNode>) {
+        for (i, node) in nodes.into_iter().enumerate() {
+            self.push(NodePath::root().field(name).index(i), node);
+        }
+    }
+}
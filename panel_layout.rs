@@ -0,0 +1,196 @@
+use super::*;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashSet, fmt};
+use ts_rs::TS;
+
+/// Hints that let a frontend defer rendering panels that are offscreen or hidden behind a tab.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct PanelRenderingHints {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub priority: Option<RenderPriority>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lazy_load: Option<bool>, // Render only once the panel becomes visible
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimated_height: Option<String>, // Placeholder size while deferred, e.g. "400px"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimated_width: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimated_node_count: Option<usize>, // Rough amount of content, for deciding what to defer
+}
+
+/// Order in which panels should be rendered; higher priorities first.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, TS,
+)]
+#[ts(export)]
+pub enum RenderPriority {
+    Deferred, // Only when explicitly opened
+    Low,
+    #[default]
+    Normal,
+    High,
+    Critical, // Part of the first paint
+}
+
+impl PanelRenderingHints {
+    pub fn eager(priority: RenderPriority) -> Self {
+        PanelRenderingHints {
+            priority: Some(priority),
+            lazy_load: Some(false),
+            ..Default::default()
+        }
+    }
+
+    pub fn lazy(estimated_height: impl Into<String>) -> Self {
+        PanelRenderingHints {
+            priority: Some(RenderPriority::Low),
+            lazy_load: Some(true),
+            estimated_height: Some(estimated_height.into()),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_node_count(mut self, estimated_node_count: usize) -> Self {
+        self.estimated_node_count = Some(estimated_node_count);
+        self
+    }
+
+    /// Fills every unset hint from `defaults`.
+    pub fn or(&self, defaults: &PanelRenderingHints) -> PanelRenderingHints {
+        PanelRenderingHints {
+            priority: self.priority.or(defaults.priority),
+            lazy_load: self.lazy_load.or(defaults.lazy_load),
+            estimated_height: self
+                .estimated_height
+                .clone()
+                .or_else(|| defaults.estimated_height.clone()),
+            estimated_width: self
+                .estimated_width
+                .clone()
+                .or_else(|| defaults.estimated_width.clone()),
+            estimated_node_count: self.estimated_node_count.or(defaults.estimated_node_count),
+        }
+    }
+}
+
+impl Panel {
+    pub fn priority(&self) -> RenderPriority {
+        self.rendering_hints
+            .as_ref()
+            .and_then(|hints| hints.priority)
+            .unwrap_or_default()
+    }
+
+    pub fn is_lazy(&self) -> bool {
+        self.rendering_hints
+            .as_ref()
+            .and_then(|hints| hints.lazy_load)
+            .unwrap_or(false)
+    }
+}
+
+impl PanelLayout {
+    /// The hints that apply to `panel`: its own, completed by the layout defaults.
+    pub fn effective_hints(&self, panel: &Panel) -> PanelRenderingHints {
+        let own = panel.rendering_hints.clone().unwrap_or_default();
+        match &self.default_rendering_hints {
+            Some(defaults) => own.or(defaults),
+            None => own,
+        }
+    }
+
+    pub fn panel(&self, panel_id: &str) -> Option<&Panel> {
+        self.panels.iter().find(|panel| panel.id == panel_id)
+    }
+
+    /// The panel named by `PanelLayoutType::Sidebar`, if any.
+    pub fn main_panel(&self) -> Option<&Panel> {
+        match &self.layout_type {
+            PanelLayoutType::Sidebar { main_panel_id } => self.panel(main_panel_id),
+            _ => None,
+        }
+    }
+
+    /// Panels ordered for rendering: highest effective priority first, the sidebar's main panel
+    /// before others of equal priority, otherwise in declaration order.
+    pub fn render_order(&self) -> Vec<&Panel> {
+        let main_panel_id = self.main_panel().map(|panel| panel.id.as_str());
+        let mut panels: Vec<&Panel> = self.panels.iter().collect();
+        panels.sort_by_key(|panel| {
+            let priority = self.effective_hints(panel).priority.unwrap_or_default();
+            (
+                std::cmp::Reverse(priority),
+                Some(panel.id.as_str()) != main_panel_id,
+            )
+        });
+        panels
+    }
+
+    /// Checks that panel ids are unique and that the sidebar's `main_panel_id` names one of them.
+    pub fn validate(&self) -> Vec<PanelLayoutError> {
+        let mut errors = vec![];
+        let mut seen = HashSet::new();
+        for panel in &self.panels {
+            if !seen.insert(panel.id.as_str()) {
+                errors.push(PanelLayoutError::DuplicatePanelId {
+                    panel_id: panel.id.clone(),
+                });
+            }
+        }
+        if let PanelLayoutType::Sidebar { main_panel_id } = &self.layout_type
+            && !seen.contains(main_panel_id.as_str())
+        {
+            errors.push(PanelLayoutError::UnknownMainPanel {
+                main_panel_id: main_panel_id.clone(),
+            });
+        }
+        errors
+    }
+}
+
+impl MathDocument {
+    /// Validates every `PanelLayout` in the document, including embedded documents.
+    pub fn validate_panel_layouts(&self) -> Vec<PanelLayoutError> {
+        let mut validator = PanelLayoutValidator::default();
+        validator.visit_document(self);
+        validator.errors
+    }
+}
+
+#[derive(Default)]
+struct PanelLayoutValidator {
+    errors: Vec<PanelLayoutError>,
+}
+
+impl<'doc> DocumentVisitor<'doc> for PanelLayoutValidator {
+    fn visit_content_node(&mut self, node: &'doc SectionContentNode) {
+        if let SectionContentNode::PanelLayout(layout) = node {
+            self.errors.extend(layout.validate());
+        }
+        walk_content_node(self, node);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PanelLayoutError {
+    DuplicatePanelId { panel_id: String },
+    UnknownMainPanel { main_panel_id: String },
+}
+
+impl fmt::Display for PanelLayoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PanelLayoutError::DuplicatePanelId { panel_id } => {
+                write!(f, "panel id `{}` is used more than once", panel_id)
+            }
+            PanelLayoutError::UnknownMainPanel { main_panel_id } => write!(
+                f,
+                "sidebar main panel `{}` is not one of the layout's panels",
+                main_panel_id
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PanelLayoutError {}
@@ -0,0 +1,72 @@
+use super::*;
+use std::thread;
+
+/// Maps `f` over `items` on all available cores, returning the results in the order of `items`.
+///
+/// Items are split into one contiguous chunk per core and each chunk is mapped on its own scoped
+/// thread, so `f` can borrow from the caller. Small inputs, and machines with a single core, are
+/// mapped on the calling thread.
+pub fn par_map<'a, I: Sync, T: Send>(items: &'a [I], f: impl Fn(&'a I) -> T + Sync) -> Vec<T> {
+    let threads = thread::available_parallelism().map_or(1, |n| n.get());
+    if threads == 1 || items.len() < 2 {
+        return items.iter().map(f).collect();
+    }
+    let chunk_size = items.len().div_ceil(threads);
+    let f = &f;
+    thread::scope(|scope| {
+        let handles: Vec<_> = items
+            .chunks(chunk_size)
+            .map(|chunk| scope.spawn(move || chunk.iter().map(f).collect::<Vec<T>>()))
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|handle| match handle.join() {
+                Ok(results) => results,
+                Err(panic) => std::panic::resume_unwind(panic),
+            })
+            .collect()
+    })
+}
+
+impl MathDocument {
+    /// Every section of the document, nested and embedded ones included, in document order.
+    pub fn sections(&self) -> Vec<&Section> {
+        self.fold(vec![], |mut sections, node| {
+            if let FoldNode::Section(section) = node {
+                sections.push(section);
+            }
+            sections
+        })
+    }
+
+    /// Every top-level expression of the document (the nodes `DocumentVisitor::visit_math_node`
+    /// is called with), in document order.
+    pub fn expressions(&self) -> Vec<&MathNode> {
+        let mut collector = ExpressionCollector::default();
+        collector.visit_document(self);
+        collector.expressions
+    }
+
+    /// `f` applied to every section in parallel, see `par_map`. Nested sections are visited on
+    /// their own as well as inside their parents, so `f` should not recurse into subsections
+    /// unless that is what it measures.
+    pub fn par_map_sections<T: Send>(&self, f: impl Fn(&Section) -> T + Sync) -> Vec<T> {
+        par_map(&self.sections(), |section| f(section))
+    }
+
+    /// `f` applied to every top-level expression in parallel, see `par_map`.
+    pub fn par_map_expressions<T: Send>(&self, f: impl Fn(&MathNode) -> T + Sync) -> Vec<T> {
+        par_map(&self.expressions(), |node| f(node))
+    }
+}
+
+#[derive(Default)]
+struct ExpressionCollector<'doc> {
+    expressions: Vec<&'doc MathNode>,
+}
+
+impl<'doc> DocumentVisitor<'doc> for ExpressionCollector<'doc> {
+    fn visit_math_node(&mut self, node: &'doc MathNode) {
+        self.expressions.push(node);
+    }
+}
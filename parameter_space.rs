@@ -0,0 +1,447 @@
+use super::*;
+use std::{collections::BTreeMap, fmt};
+
+/// Values within this distance of each other, relative to their size, count as equal in
+/// `Constraint` formulas.
+const EQUALITY_TOLERANCE: f64 = 1e-9;
+
+impl Parameter {
+    /// Whether `value` is allowed: within the range's bounds, on a step when the range has one,
+    /// whole for integers, 0 or 1 for booleans and an option index for categorical parameters.
+    pub fn contains(&self, value: f64) -> bool {
+        if !value.is_finite() {
+            return false;
+        }
+        let whole = value.fract() == 0.0;
+        match &self.parameter_type {
+            ParameterType::Boolean => return value == 0.0 || value == 1.0,
+            ParameterType::Categorical { options } => {
+                return whole && value >= 0.0 && value < options.len() as f64;
+            }
+            ParameterType::Integer if !whole => return false,
+            _ => {}
+        }
+        let ParameterRange { min, max, step } = &self.range;
+        if min.is_some_and(|min| value < min) || max.is_some_and(|max| value > max) {
+            return false;
+        }
+        match step.filter(|step| *step > 0.0) {
+            Some(step) => {
+                let steps = (value - min.unwrap_or(0.0)) / step;
+                approx_eq(steps, steps.round())
+            }
+            None => true,
+        }
+    }
+}
+
+impl Constraint {
+    /// Evaluates `formula` with the parameters set to `values`.
+    ///
+    /// Formulas compare arithmetic expressions, e.g. `a + b <= 10`, `x^2 + y^2 = 1` or the chain
+    /// `0 < a < b`, and combine comparisons with `and`/`&&` and `or`/`||`. Expressions use
+    /// numbers, parameter names, `+ - * / ^`, parentheses, `pi`, `e` and the functions `abs`,
+    /// `sqrt`, `exp`, `ln`, `log`, `sin`, `cos`, `tan`, `min` and `max`.
+    pub fn evaluate(&self, values: &BTreeMap<String, f64>) -> Result<bool, ParameterSpaceError> {
+        let mut parser = FormulaParser {
+            formula: &self.formula,
+            tokens: tokenize(&self.formula).map_err(|message| self.invalid(message))?,
+            position: 0,
+            values,
+        };
+        let satisfied = parser.disjunction()?;
+        match parser.tokens.get(parser.position) {
+            None => Ok(satisfied),
+            Some(token) => Err(self.invalid(format!("unexpected `{}`", token))),
+        }
+    }
+
+    fn invalid(&self, message: String) -> ParameterSpaceError {
+        ParameterSpaceError::InvalidFormula {
+            formula: self.formula.clone(),
+            message,
+        }
+    }
+}
+
+impl ParameterSpace {
+    pub fn parameter(&self, name: &str) -> Option<&Parameter> {
+        self.parameters
+            .iter()
+            .find(|parameter| parameter.name == name)
+    }
+
+    /// Whether `values` assigns every parameter an allowed value and satisfies every constraint.
+    pub fn accepts(&self, values: &BTreeMap<String, f64>) -> Result<bool, ParameterSpaceError> {
+        let in_range = self.parameters.iter().all(|parameter| {
+            values
+                .get(&parameter.name)
+                .is_some_and(|value| parameter.contains(*value))
+        });
+        if !in_range {
+            return Ok(false);
+        }
+        for constraint in &self.constraints {
+            if !constraint.evaluate(values)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Problems that would show a broken playground: defaults for unknown parameters or outside
+    /// their parameter's range, constraints that cannot be evaluated, and constraints the
+    /// defaults violate. Constraints are only checked against the defaults when every parameter
+    /// has one.
+    pub fn validate(&self) -> Vec<ParameterSpaceError> {
+        let mut errors = vec![];
+        for (name, &value) in &self.default_values {
+            match self.parameter(name) {
+                None => errors.push(ParameterSpaceError::UnknownParameter {
+                    name: name.clone(),
+                    formula: None,
+                }),
+                Some(parameter) if !parameter.contains(value) => {
+                    errors.push(ParameterSpaceError::DefaultOutOfRange {
+                        name: name.clone(),
+                        value,
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+
+        let complete = self
+            .parameters
+            .iter()
+            .all(|parameter| self.default_values.contains_key(&parameter.name));
+        for constraint in &self.constraints {
+            // Without complete defaults, evaluate against placeholders to catch syntax errors.
+            let values = if complete {
+                self.default_values.clone()
+            } else {
+                self.parameters
+                    .iter()
+                    .map(|parameter| (parameter.name.clone(), 0.0))
+                    .chain(self.default_values.clone())
+                    .collect()
+            };
+            match constraint.evaluate(&values) {
+                Ok(false) if complete => errors.push(ParameterSpaceError::DefaultsViolate {
+                    formula: constraint.formula.clone(),
+                }),
+                Ok(_) => {}
+                Err(error) => errors.push(error),
+            }
+        }
+        errors
+    }
+}
+
+fn approx_eq(a: f64, b: f64) -> bool {
+    (a - b).abs() <= EQUALITY_TOLERANCE * a.abs().max(b.abs()).max(1.0)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Name(String),
+    Symbol(&'static str), // operators, comparisons, parentheses and commas
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Number(number) => write!(f, "{}", number),
+            Token::Name(name) => f.write_str(name),
+            Token::Symbol(symbol) => f.write_str(symbol),
+        }
+    }
+}
+
+fn tokenize(formula: &str) -> Result<Vec<Token>, String> {
+    const SYMBOLS: [(&str, &str); 21] = [
+        ("<=", "<="),
+        (">=", ">="),
+        ("==", "="),
+        ("!=", "!="),
+        ("&&", "and"),
+        ("||", "or"),
+        ("≤", "<="),
+        ("≥", ">="),
+        ("≠", "!="),
+        ("<", "<"),
+        (">", ">"),
+        ("=", "="),
+        ("+", "+"),
+        ("-", "-"),
+        ("−", "-"),
+        ("*", "*"),
+        ("/", "/"),
+        ("^", "^"),
+        ("(", "("),
+        (")", ")"),
+        (",", ","),
+    ];
+    let mut tokens = vec![];
+    let mut rest = formula.trim_start();
+    while !rest.is_empty() {
+        let c = rest.chars().next().unwrap_or_default();
+        let length = if c.is_ascii_digit() || c == '.' {
+            let length = rest
+                .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+                .unwrap_or(rest.len());
+            let number = rest[..length]
+                .parse()
+                .map_err(|_| format!("invalid number `{}`", &rest[..length]))?;
+            tokens.push(Token::Number(number));
+            length
+        } else if c.is_alphabetic() || c == '_' {
+            let length = rest
+                .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .unwrap_or(rest.len());
+            tokens.push(match &rest[..length] {
+                "and" => Token::Symbol("and"),
+                "or" => Token::Symbol("or"),
+                name => Token::Name(name.to_string()),
+            });
+            length
+        } else {
+            let (text, symbol) = SYMBOLS
+                .iter()
+                .find(|(text, _)| rest.starts_with(text))
+                .ok_or_else(|| format!("unexpected `{}`", c))?;
+            tokens.push(Token::Symbol(symbol));
+            text.len()
+        };
+        rest = rest[length..].trim_start();
+    }
+    Ok(tokens)
+}
+
+/// Recursive descent evaluator over the tokens of a formula.
+struct FormulaParser<'a> {
+    formula: &'a str,
+    tokens: Vec<Token>,
+    position: usize,
+    values: &'a BTreeMap<String, f64>,
+}
+
+impl FormulaParser<'_> {
+    fn error(&self, message: impl Into<String>) -> ParameterSpaceError {
+        ParameterSpaceError::InvalidFormula {
+            formula: self.formula.to_string(),
+            message: message.into(),
+        }
+    }
+
+    fn eat(&mut self, symbol: &str) -> bool {
+        let found =
+            matches!(self.tokens.get(self.position), Some(Token::Symbol(s)) if *s == symbol);
+        if found {
+            self.position += 1;
+        }
+        found
+    }
+
+    fn disjunction(&mut self) -> Result<bool, ParameterSpaceError> {
+        let mut value = self.conjunction()?;
+        while self.eat("or") {
+            value |= self.conjunction()?;
+        }
+        Ok(value)
+    }
+
+    fn conjunction(&mut self) -> Result<bool, ParameterSpaceError> {
+        let mut value = self.comparison()?;
+        while self.eat("and") {
+            value &= self.comparison()?;
+        }
+        Ok(value)
+    }
+
+    /// A chain of comparisons, `a < b <= c`, holding when every link holds.
+    fn comparison(&mut self) -> Result<bool, ParameterSpaceError> {
+        let mut lhs = self.sum()?;
+        let mut holds = true;
+        let mut compared = false;
+        while let Some(Token::Symbol(operator)) = self.tokens.get(self.position).cloned() {
+            let compare: fn(f64, f64) -> bool = match operator {
+                "<" => |a, b| a < b && !approx_eq(a, b),
+                "<=" => |a, b| a <= b || approx_eq(a, b),
+                ">" => |a, b| a > b && !approx_eq(a, b),
+                ">=" => |a, b| a >= b || approx_eq(a, b),
+                "=" => approx_eq,
+                "!=" => |a, b| !approx_eq(a, b),
+                _ => break,
+            };
+            self.position += 1;
+            let rhs = self.sum()?;
+            holds &= compare(lhs, rhs);
+            compared = true;
+            lhs = rhs;
+        }
+        if !compared {
+            return Err(self.error("expected a comparison such as `<=` or `=`"));
+        }
+        Ok(holds)
+    }
+
+    fn sum(&mut self) -> Result<f64, ParameterSpaceError> {
+        let mut value = self.product()?;
+        loop {
+            if self.eat("+") {
+                value += self.product()?;
+            } else if self.eat("-") {
+                value -= self.product()?;
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    fn product(&mut self) -> Result<f64, ParameterSpaceError> {
+        let mut value = self.unary()?;
+        loop {
+            if self.eat("*") {
+                value *= self.unary()?;
+            } else if self.eat("/") {
+                value /= self.unary()?;
+            } else if matches!(
+                self.tokens.get(self.position),
+                Some(Token::Name(_) | Token::Symbol("("))
+            ) {
+                // Implicit multiplication, as in `2a` or `3(x + 1)`.
+                value *= self.unary()?;
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    fn unary(&mut self) -> Result<f64, ParameterSpaceError> {
+        if self.eat("-") {
+            return Ok(-self.unary()?);
+        }
+        if self.eat("+") {
+            return self.unary();
+        }
+        let base = self.atom()?;
+        if self.eat("^") {
+            return Ok(base.powf(self.unary()?));
+        }
+        Ok(base)
+    }
+
+    fn atom(&mut self) -> Result<f64, ParameterSpaceError> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        match token {
+            Some(Token::Number(number)) => Ok(number),
+            Some(Token::Symbol("(")) => {
+                let value = self.sum()?;
+                if !self.eat(")") {
+                    return Err(self.error("missing `)`"));
+                }
+                Ok(value)
+            }
+            Some(Token::Name(name)) if self.eat("(") => {
+                let mut arguments = vec![self.sum()?];
+                while self.eat(",") {
+                    arguments.push(self.sum()?);
+                }
+                if !self.eat(")") {
+                    return Err(self.error("missing `)`"));
+                }
+                self.call(&name, &arguments)
+            }
+            Some(Token::Name(name)) => match self.values.get(&name) {
+                Some(value) => Ok(*value),
+                None => match name.as_str() {
+                    "pi" | "π" => Ok(std::f64::consts::PI),
+                    "e" => Ok(std::f64::consts::E),
+                    _ => Err(ParameterSpaceError::UnknownParameter {
+                        name,
+                        formula: Some(self.formula.to_string()),
+                    }),
+                },
+            },
+            Some(token) => Err(self.error(format!("unexpected `{}`", token))),
+            None => Err(self.error("unexpected end of formula")),
+        }
+    }
+
+    fn call(&self, name: &str, arguments: &[f64]) -> Result<f64, ParameterSpaceError> {
+        let unary: Option<fn(f64) -> f64> = match name {
+            "abs" => Some(f64::abs),
+            "sqrt" => Some(f64::sqrt),
+            "exp" => Some(f64::exp),
+            "ln" => Some(f64::ln),
+            "log" => Some(f64::log10),
+            "sin" => Some(f64::sin),
+            "cos" => Some(f64::cos),
+            "tan" => Some(f64::tan),
+            _ => None,
+        };
+        match (unary, name, arguments) {
+            (Some(function), _, [x]) => Ok(function(*x)),
+            (None, "min", [first, rest @ ..]) => Ok(rest.iter().fold(*first, |a, b| a.min(*b))),
+            (None, "max", [first, rest @ ..]) => Ok(rest.iter().fold(*first, |a, b| a.max(*b))),
+            (None, "min" | "max", _) | (Some(_), _, _) => {
+                Err(self.error(format!("wrong number of arguments for `{}`", name)))
+            }
+            (None, _, _) => Err(self.error(format!("unknown function `{}`", name))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParameterSpaceError {
+    InvalidFormula {
+        formula: String,
+        message: String,
+    },
+    UnknownParameter {
+        name: String,
+        formula: Option<String>, // the constraint naming it, `None` for a default value
+    },
+    DefaultOutOfRange {
+        name: String,
+        value: f64,
+    },
+    DefaultsViolate {
+        formula: String,
+    },
+}
+
+impl fmt::Display for ParameterSpaceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParameterSpaceError::InvalidFormula { formula, message } => {
+                write!(f, "invalid constraint `{}`: {}", formula, message)
+            }
+            ParameterSpaceError::UnknownParameter {
+                name,
+                formula: Some(formula),
+            } => write!(
+                f,
+                "constraint `{}` uses unknown parameter `{}`",
+                formula, name
+            ),
+            ParameterSpaceError::UnknownParameter {
+                name,
+                formula: None,
+            } => write!(f, "default value given for unknown parameter `{}`", name),
+            ParameterSpaceError::DefaultOutOfRange { name, value } => write!(
+                f,
+                "default value {} of parameter `{}` is outside its range",
+                value, name
+            ),
+            ParameterSpaceError::DefaultsViolate { formula } => {
+                write!(f, "default values violate constraint `{}`", formula)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParameterSpaceError {}
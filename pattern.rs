@@ -0,0 +1,214 @@
+use super::*;
+use std::{collections::HashMap, fmt, sync::Arc};
+
+/// A `MathNode` tree used as a structural pattern.
+///
+/// Identifiers whose body starts with `?` (see `MathNode::wildcard`) match any subtree and bind it
+/// under their name; a wildcard used twice must match canonically equal subtrees, so `?a/?a`
+/// matches `x/x` and `(x+1)/(1+x)` but not `x/y`. Everything else must match node for node,
+/// except that brackets in the matched expression are looked through. Ids are ignored.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pattern {
+    pub pattern: MathNode,
+}
+
+/// Wildcard name to the subtree it matched.
+pub type PatternBindings = HashMap<String, MathNode>;
+
+/// Rewrites subtrees matching `pattern` into `replacement`, with the pattern's wildcards
+/// substituted into the replacement.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RewriteRule {
+    pub pattern: Pattern,
+    pub replacement: MathNode,
+}
+
+impl MathNode {
+    /// A pattern wildcard named `name`, written `?name`.
+    pub fn wildcard(name: &str) -> MathNode {
+        MathNode::identifier(Identifier::new_simple(format!("?{}", name)))
+    }
+
+    /// The name of this wildcard, if it is one.
+    pub fn wildcard_name(&self) -> Option<&str> {
+        match self.content.as_ref() {
+            MathNodeContent::Identifier(Identifier {
+                body,
+                pre_script: None,
+                mid_script: None,
+                post_script: None,
+                primes: 0,
+                ..
+            }) => body.strip_prefix('?').filter(|name| !name.is_empty()),
+            _ => None,
+        }
+    }
+
+    /// Every subtree matching `pattern`, with its path and bindings, in pre-order.
+    pub fn find_pattern(&self, pattern: &Pattern) -> Vec<(NodePath, &MathNode, PatternBindings)> {
+        self.descendants_with_paths()
+            .into_iter()
+            .filter_map(|(path, node)| Some((path, node, pattern.match_node(node)?)))
+            .collect()
+    }
+
+    /// Applies `rules` bottom-up: each node is replaced by the first rule matching it, once.
+    /// Returns the rewritten tree and the number of rewrites.
+    pub fn rewrite(&self, rules: &[RewriteRule]) -> (MathNode, usize) {
+        let mut rewriter = Rewriter { rules, count: 0 };
+        let rewritten = self.transform(&mut rewriter);
+        (rewritten, rewriter.count)
+    }
+}
+
+impl Pattern {
+    pub fn new(pattern: MathNode) -> Self {
+        Pattern { pattern }
+    }
+
+    /// Names of the wildcards in the pattern, in pre-order, without repeats.
+    pub fn wildcards(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = vec![];
+        for (_, node) in self.pattern.descendants_with_paths() {
+            if let Some(name) = node.wildcard_name()
+                && !names.contains(&name)
+            {
+                names.push(name);
+            }
+        }
+        names
+    }
+
+    /// The bindings under which `node` matches this pattern, if it does.
+    pub fn match_node(&self, node: &MathNode) -> Option<PatternBindings> {
+        let mut bindings = PatternBindings::new();
+        match_into(&self.pattern, node, &mut bindings).then_some(bindings)
+    }
+}
+
+fn match_into(pattern: &MathNode, node: &MathNode, bindings: &mut PatternBindings) -> bool {
+    if let Some(name) = pattern.wildcard_name() {
+        return match bindings.get(name) {
+            Some(bound) => bound.canonically_eq(node),
+            None => {
+                bindings.insert(name.to_string(), node.clone());
+                true
+            }
+        };
+    }
+    if let MathNodeContent::Bracketed { inner, .. } = node.content.as_ref()
+        && !matches!(pattern.content.as_ref(), MathNodeContent::Bracketed { .. })
+    {
+        return match_into(pattern, inner, bindings);
+    }
+
+    let pattern_children = pattern.content.children_with_paths();
+    let node_children = node.content.children_with_paths();
+    pattern_children.len() == node_children.len()
+        && shape(pattern) == shape(node)
+        && pattern_children.iter().zip(&node_children).all(
+            |((pattern_path, pattern_child), (node_path, node_child))| {
+                pattern_path == node_path && match_into(pattern_child, node_child, bindings)
+            },
+        )
+}
+
+/// `node`'s content with every child blanked out, so that two nodes have the same shape exactly
+/// when they differ only in their children.
+fn shape(node: &MathNode) -> MathNodeContent {
+    node.content
+        .map_children(|_| Some(MathNode::empty()))
+        .unwrap_or_else(|| node.content.as_ref().clone())
+}
+
+impl RewriteRule {
+    /// Fails if `replacement` uses a wildcard that `pattern` does not bind.
+    pub fn new(pattern: MathNode, replacement: MathNode) -> Result<Self, PatternError> {
+        let pattern = Pattern::new(pattern);
+        let bound = pattern.wildcards();
+        for (_, node) in replacement.descendants_with_paths() {
+            if let Some(name) = node.wildcard_name()
+                && !bound.contains(&name)
+            {
+                return Err(PatternError::UnboundWildcard {
+                    name: name.to_string(),
+                });
+            }
+        }
+        Ok(RewriteRule {
+            pattern,
+            replacement,
+        })
+    }
+
+    /// The rewritten form of `node` if the pattern matches it at the root.
+    pub fn apply(&self, node: &MathNode) -> Option<MathNode> {
+        let bindings = self.pattern.match_node(node)?;
+        let mut substitute = |candidate: &MathNode| {
+            let name = candidate.wildcard_name()?;
+            bindings.get(name).cloned()
+        };
+        let substituted = self.replacement.transform(&mut substitute);
+        Some(MathNode {
+            id: node.id.clone(),
+            content: Arc::clone(&substituted.content),
+        })
+    }
+}
+
+struct Rewriter<'a> {
+    rules: &'a [RewriteRule],
+    count: usize,
+}
+
+impl MathNodeTransformer for Rewriter<'_> {
+    fn transform_node(&mut self, node: &MathNode) -> Option<MathNode> {
+        let rewritten = self.rules.iter().find_map(|rule| rule.apply(node))?;
+        self.count += 1;
+        Some(rewritten)
+    }
+}
+
+impl MathDocument {
+    /// Applies `rules` to every expression in the document, see `MathNode::rewrite`.
+    /// Returns the number of rewrites.
+    pub fn rewrite(&mut self, rules: &[RewriteRule]) -> usize {
+        let mut rewriter = DocumentRewriter { rules, count: 0 };
+        rewriter.visit_document_mut(self);
+        rewriter.count
+    }
+}
+
+struct DocumentRewriter<'a> {
+    rules: &'a [RewriteRule],
+    count: usize,
+}
+
+impl DocumentVisitorMut for DocumentRewriter<'_> {
+    fn visit_math_node_mut(&mut self, node: &mut MathNode) {
+        let (rewritten, count) = node.rewrite(self.rules);
+        if count > 0 {
+            *node = rewritten;
+            self.count += count;
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PatternError {
+    UnboundWildcard { name: String },
+}
+
+impl fmt::Display for PatternError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PatternError::UnboundWildcard { name } => write!(
+                f,
+                "replacement uses wildcard `?{}`, which the pattern does not bind",
+                name
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PatternError {}
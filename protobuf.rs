@@ -0,0 +1,1171 @@
+use super::*;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Number, Value};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use ts_rs::TS;
+
+pub const PROTO_PACKAGE: &str = "turn_render";
+
+/// Protocol Buffers schema of the exported types, derived from their TypeScript declarations
+/// so that it never drifts from the JSON the bindings describe, for gRPC services in other
+/// languages. Written next to the bindings as `turn_render.proto` by `export`.
+///
+/// Structs become messages with their fields numbered in declaration order, enums with data
+/// become messages with a `oneof`, and unit-only enums become proto enums. Unit variants hold
+/// an empty `Unit` message; tuples become messages with fields `item_0`, `item_1`, ...; nested
+/// lists, optional lists and the like get wrapper messages with a single field `value`. All
+/// numbers are `double`s, and untyped JSON values are strings holding their JSON. Field
+/// numbers follow the declarations, so they change with them; check `SchemaManifest`
+/// compatibility as with the bindings.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProtoSchema {
+    messages: BTreeMap<String, ProtoMessage>,
+    enums: BTreeMap<String, Vec<String>>, // enum name -> variants, numbered from 0
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct ProtoMessage {
+    kind: MessageKind,
+    fields: Vec<ProtoField>,
+}
+
+/// How the message appears in JSON.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum MessageKind {
+    Struct,  // an object, by field name
+    Tuple,   // an array, by position
+    OneOf,   // an externally tagged enum
+    Tagged,  // an adjacently tagged enum, see `ENUM_TAG_KEY`
+    Wrapper, // the value of its only field
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct ProtoField {
+    name: String,
+    json_name: String, // the key, or the variant tag in a `oneof`
+    number: u32,
+    label: Label,
+    ty: ProtoType,
+    unit_variant: bool, // written as the bare tag
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Label {
+    Single,
+    Optional,
+    Repeated,
+    Map, // with string keys
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum ProtoType {
+    String,
+    Double,
+    Bool,
+    Json,
+    Unit,
+    Enum(String),
+    Message(String),
+}
+
+const UNIT_MESSAGE: &str = "Unit";
+
+impl ProtoSchema {
+    /// The schema of the types in `SchemaManifest::declarations`.
+    pub fn current() -> ProtoSchema {
+        let declarations = SchemaManifest::declarations()
+            .into_iter()
+            .map(|(name, declaration)| {
+                // A declaration that cannot be read is carried as JSON rather than dropped.
+                let ty = parse_declaration(&declaration)
+                    .unwrap_or_else(|| TsType::Named("unknown".to_string()));
+                (name, ty)
+            })
+            .collect();
+        let mut builder = SchemaBuilder {
+            declarations,
+            schema: ProtoSchema {
+                messages: BTreeMap::new(),
+                enums: BTreeMap::new(),
+            },
+        };
+        for (name, ty) in builder.declarations.clone() {
+            builder.define(&name, &ty);
+        }
+        builder.schema
+    }
+
+    /// The schema as a `.proto` file.
+    pub fn to_proto(&self) -> String {
+        let mut proto = format!(
+            "// Generated from the turn-render types, schema version {}. Do not edit this file manually.\n\nsyntax = \"proto3\";\n\npackage {};\n\nmessage {} {{}}\n",
+            SCHEMA_VERSION, PROTO_PACKAGE, UNIT_MESSAGE
+        );
+        let mut definitions: BTreeMap<&String, String> = BTreeMap::new();
+        for (name, message) in &self.messages {
+            definitions.insert(name, render_message(name, message));
+        }
+        for (name, variants) in &self.enums {
+            definitions.insert(name, render_enum(name, variants));
+        }
+        for definition in definitions.values() {
+            proto.push('\n');
+            proto.push_str(definition);
+        }
+        proto
+    }
+
+    /// Encodes the value as the message of its type.
+    pub fn encode<T: Serialize + TS + ?Sized>(&self, value: &T) -> Result<Vec<u8>, ProtoError> {
+        let value =
+            serde_json::to_value(value).map_err(|error| ProtoError::Serde(error.to_string()))?;
+        let mut bytes = vec![];
+        self.encode_message(&T::ident(), &value, &mut bytes)?;
+        Ok(bytes)
+    }
+
+    /// Decodes a message of `T`'s type. Unknown fields are skipped and missing ones take their
+    /// proto3 defaults, so messages written against an older or newer schema still read.
+    pub fn decode<T: DeserializeOwned + TS>(&self, bytes: &[u8]) -> Result<T, ProtoError> {
+        let value = self.decode_message(&T::ident(), bytes)?;
+        serde_json::from_value(value).map_err(|error| ProtoError::Serde(error.to_string()))
+    }
+
+    /// Writes the current schema as `turn_render.proto` to the directory the bindings are
+    /// exported to: `TS_RS_EXPORT_DIR`, or `./bindings`.
+    pub fn export() -> std::io::Result<PathBuf> {
+        let directory = std::env::var_os("TS_RS_EXPORT_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from("./bindings"));
+        std::fs::create_dir_all(&directory)?;
+        let path = directory.join(format!("{}.proto", PROTO_PACKAGE));
+        std::fs::write(&path, ProtoSchema::current().to_proto())?;
+        Ok(path)
+    }
+
+    fn encode_message(
+        &self,
+        name: &str,
+        value: &Value,
+        out: &mut Vec<u8>,
+    ) -> Result<(), ProtoError> {
+        let message = self.message(name)?;
+        let mismatch = |expected: &str| ProtoError::Mismatch {
+            type_name: name.to_string(),
+            expected: expected.to_string(),
+        };
+        match message.kind {
+            MessageKind::Struct => {
+                let Value::Object(fields) = value else {
+                    return Err(mismatch("an object"));
+                };
+                for field in &message.fields {
+                    if let Some(value) = fields.get(&field.json_name) {
+                        self.encode_field(name, field, value, out)?;
+                    }
+                }
+            }
+            MessageKind::Tuple => {
+                let Value::Array(items) = value else {
+                    return Err(mismatch("an array"));
+                };
+                if items.len() != message.fields.len() {
+                    return Err(mismatch(&format!("{} items", message.fields.len())));
+                }
+                for (field, item) in message.fields.iter().zip(items) {
+                    self.encode_field(name, field, item, out)?;
+                }
+            }
+            MessageKind::OneOf | MessageKind::Tagged => {
+                let variant = match (message.kind, value) {
+                    (MessageKind::OneOf, Value::String(tag)) => Some((tag, &Value::Null)),
+                    (MessageKind::OneOf, Value::Object(variant)) if variant.len() == 1 => {
+                        variant.iter().next()
+                    }
+                    (MessageKind::Tagged, Value::Object(variant)) => {
+                        match variant.get(ENUM_TAG_KEY) {
+                            Some(Value::String(tag)) => {
+                                Some((tag, variant.get(ENUM_CONTENT_KEY).unwrap_or(&Value::Null)))
+                            }
+                            _ => None,
+                        }
+                    }
+                    _ => None,
+                };
+                let Some((tag, payload)) = variant else {
+                    return Err(mismatch("a variant"));
+                };
+                let field = message
+                    .fields
+                    .iter()
+                    .find(|field| field.json_name == *tag)
+                    .ok_or_else(|| ProtoError::UnknownVariant {
+                        type_name: name.to_string(),
+                        variant: tag.clone(),
+                    })?;
+                self.encode_field(name, field, payload, out)?;
+            }
+            MessageKind::Wrapper => self.encode_field(name, &message.fields[0], value, out)?,
+        }
+        Ok(())
+    }
+
+    fn encode_field(
+        &self,
+        message: &str,
+        field: &ProtoField,
+        value: &Value,
+        out: &mut Vec<u8>,
+    ) -> Result<(), ProtoError> {
+        let mismatch = |expected: &str| ProtoError::Mismatch {
+            type_name: format!("{}.{}", message, field.name),
+            expected: expected.to_string(),
+        };
+        match field.label {
+            Label::Single => self.encode_value(field.number, &field.ty, value, out),
+            Label::Optional if value.is_null() => Ok(()),
+            Label::Optional => self.encode_value(field.number, &field.ty, value, out),
+            Label::Repeated => {
+                let Value::Array(items) = value else {
+                    return Err(mismatch("an array"));
+                };
+                if field.ty.is_packable() {
+                    let mut packed = vec![];
+                    for item in items {
+                        self.encode_scalar(&field.ty, item, &mut packed)
+                            .ok_or_else(|| mismatch("an array of scalars"))?;
+                    }
+                    write_len(field.number, &packed, out);
+                } else {
+                    for item in items {
+                        self.encode_value(field.number, &field.ty, item, out)?;
+                    }
+                }
+                Ok(())
+            }
+            Label::Map => {
+                let Value::Object(entries) = value else {
+                    return Err(mismatch("an object"));
+                };
+                for (key, value) in entries {
+                    let mut entry = vec![];
+                    write_len(1, key.as_bytes(), &mut entry);
+                    self.encode_value(2, &field.ty, value, &mut entry)?;
+                    write_len(field.number, &entry, out);
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn encode_value(
+        &self,
+        number: u32,
+        ty: &ProtoType,
+        value: &Value,
+        out: &mut Vec<u8>,
+    ) -> Result<(), ProtoError> {
+        let mismatch = |expected: &str| ProtoError::Mismatch {
+            type_name: ty.name().to_string(),
+            expected: expected.to_string(),
+        };
+        match ty {
+            ProtoType::String => match value {
+                Value::String(text) => write_len(number, text.as_bytes(), out),
+                _ => return Err(mismatch("a string")),
+            },
+            ProtoType::Json => write_len(number, value.to_string().as_bytes(), out),
+            ProtoType::Unit => write_len(number, &[], out),
+            ProtoType::Message(name) => {
+                let mut message = vec![];
+                self.encode_message(name, value, &mut message)?;
+                write_len(number, &message, out);
+            }
+            ProtoType::Double | ProtoType::Bool | ProtoType::Enum(_) => {
+                write_varint((number as u64) << 3 | ty.wire_type() as u64, out);
+                self.encode_scalar(ty, value, out).ok_or_else(|| match ty {
+                    ProtoType::Enum(name) => ProtoError::UnknownVariant {
+                        type_name: name.clone(),
+                        variant: value.to_string(),
+                    },
+                    _ => mismatch("a scalar"),
+                })?;
+            }
+        }
+        Ok(())
+    }
+
+    /// A packable value without its tag; `None` if the value does not fit the type.
+    fn encode_scalar(&self, ty: &ProtoType, value: &Value, out: &mut Vec<u8>) -> Option<()> {
+        match ty {
+            ProtoType::Double => out.extend(value.as_f64()?.to_le_bytes()),
+            ProtoType::Bool => write_varint(value.as_bool()? as u64, out),
+            ProtoType::Enum(name) => {
+                let tag = value.as_str()?;
+                let index = self.enums.get(name)?.iter().position(|v| v == tag)?;
+                write_varint(index as u64, out);
+            }
+            _ => return None,
+        }
+        Some(())
+    }
+
+    fn decode_message(&self, name: &str, bytes: &[u8]) -> Result<Value, ProtoError> {
+        let message = self.message(name)?;
+        let mut slots: Vec<Option<Value>> = vec![None; message.fields.len()];
+        let mut last_variant = None;
+        let mut reader = WireReader { bytes, offset: 0 };
+        while let Some((number, wire)) = reader.read_field()? {
+            let Some(index) = message.fields.iter().position(|f| f.number == number) else {
+                continue;
+            };
+            let field = &message.fields[index];
+            last_variant = Some(index);
+            match field.label {
+                Label::Single | Label::Optional => {
+                    slots[index] = Some(self.decode_value(&field.ty, wire)?);
+                }
+                Label::Repeated => {
+                    let Value::Array(items) =
+                        slots[index].get_or_insert_with(|| Value::Array(vec![]))
+                    else {
+                        continue;
+                    };
+                    match wire {
+                        Wire::Len(packed, offset) if field.ty.is_packable() => {
+                            let mut reader = WireReader {
+                                bytes: packed,
+                                offset: 0,
+                            };
+                            while reader.offset < packed.len() {
+                                let wire =
+                                    reader.read_payload(field.ty.wire_type()).map_err(|_| {
+                                        ProtoError::Malformed {
+                                            offset: offset + reader.offset,
+                                        }
+                                    })?;
+                                items.push(self.decode_value(&field.ty, wire)?);
+                            }
+                        }
+                        wire => items.push(self.decode_value(&field.ty, wire)?),
+                    }
+                }
+                Label::Map => {
+                    let (key, value) = self.decode_entry(&field.ty, wire)?;
+                    if let Value::Object(entries) =
+                        slots[index].get_or_insert_with(|| Value::Object(Map::new()))
+                    {
+                        entries.insert(key, value);
+                    }
+                }
+            }
+        }
+
+        if matches!(message.kind, MessageKind::OneOf | MessageKind::Tagged) {
+            let index = last_variant.ok_or_else(|| ProtoError::MissingVariant {
+                type_name: name.to_string(),
+            })?;
+            let field = &message.fields[index];
+            let tag = field.json_name.clone();
+            let payload =
+                (!field.unit_variant).then(|| slots.swap_remove(index).unwrap_or_default());
+            let mut variant = Map::new();
+            match (message.kind, payload) {
+                (MessageKind::OneOf, None) => return Ok(Value::String(tag)),
+                (MessageKind::OneOf, Some(payload)) => {
+                    variant.insert(tag, payload);
+                }
+                (_, payload) => {
+                    variant.insert(ENUM_TAG_KEY.to_string(), Value::String(tag));
+                    if let Some(payload) = payload {
+                        variant.insert(ENUM_CONTENT_KEY.to_string(), payload);
+                    }
+                }
+            }
+            return Ok(Value::Object(variant));
+        }
+        let mut values = vec![];
+        for (field, slot) in message.fields.iter().zip(slots) {
+            values.push(match slot {
+                Some(value) => value,
+                None => self.default_value(field)?,
+            });
+        }
+        Ok(match message.kind {
+            MessageKind::Tuple => Value::Array(values),
+            MessageKind::Wrapper => values.swap_remove(0),
+            _ => Value::Object(
+                message
+                    .fields
+                    .iter()
+                    .map(|field| field.json_name.clone())
+                    .zip(values)
+                    .collect(),
+            ),
+        })
+    }
+
+    fn decode_entry(&self, ty: &ProtoType, wire: Wire) -> Result<(String, Value), ProtoError> {
+        let Wire::Len(bytes, offset) = wire else {
+            return Err(ProtoError::Malformed {
+                offset: wire.offset(),
+            });
+        };
+        let mut key = String::new();
+        let mut value = None;
+        let mut reader = WireReader { bytes, offset: 0 };
+        while let Some((number, wire)) = reader.read_field().map_err(|_| ProtoError::Malformed {
+            offset: offset + reader.offset,
+        })? {
+            match number {
+                1 => {
+                    if let Value::String(text) = self.decode_value(&ProtoType::String, wire)? {
+                        key = text;
+                    }
+                }
+                2 => value = Some(self.decode_value(ty, wire)?),
+                _ => {}
+            }
+        }
+        let value = match value {
+            Some(value) => value,
+            None => self.default_of(ty)?,
+        };
+        Ok((key, value))
+    }
+
+    fn decode_value(&self, ty: &ProtoType, wire: Wire) -> Result<Value, ProtoError> {
+        let malformed = ProtoError::Malformed {
+            offset: wire.offset(),
+        };
+        Ok(match (ty, wire) {
+            (ProtoType::String, Wire::Len(bytes, _)) => {
+                Value::String(String::from_utf8(bytes.to_vec()).map_err(|_| malformed)?)
+            }
+            (ProtoType::Json, Wire::Len(bytes, _)) => serde_json::from_slice(bytes)
+                .map_err(|error| ProtoError::Serde(error.to_string()))?,
+            (ProtoType::Unit, Wire::Len(..)) => Value::Null,
+            (ProtoType::Message(name), Wire::Len(bytes, _)) => self.decode_message(name, bytes)?,
+            (ProtoType::Double, Wire::Fixed64(bits, _)) => number_value(f64::from_bits(bits)),
+            (ProtoType::Bool, Wire::Varint(value, _)) => Value::Bool(value != 0),
+            (ProtoType::Enum(name), Wire::Varint(index, _)) => {
+                let variant = self
+                    .enums
+                    .get(name)
+                    .and_then(|variants| variants.get(index as usize))
+                    .ok_or_else(|| ProtoError::UnknownVariant {
+                        type_name: name.clone(),
+                        variant: index.to_string(),
+                    })?;
+                Value::String(variant.clone())
+            }
+            _ => return Err(malformed),
+        })
+    }
+
+    /// The value of a field missing from a message.
+    fn default_value(&self, field: &ProtoField) -> Result<Value, ProtoError> {
+        match field.label {
+            Label::Optional => Ok(Value::Null),
+            Label::Repeated => Ok(Value::Array(vec![])),
+            Label::Map => Ok(Value::Object(Map::new())),
+            Label::Single => self.default_of(&field.ty),
+        }
+    }
+
+    fn default_of(&self, ty: &ProtoType) -> Result<Value, ProtoError> {
+        Ok(match ty {
+            ProtoType::String => Value::String(String::new()),
+            ProtoType::Double => Value::from(0),
+            ProtoType::Bool => Value::Bool(false),
+            ProtoType::Json | ProtoType::Unit => Value::Null,
+            ProtoType::Enum(name) => self
+                .enums
+                .get(name)
+                .and_then(|variants| variants.first())
+                .map(|variant| Value::String(variant.clone()))
+                .unwrap_or_default(),
+            ProtoType::Message(name) => self.decode_message(name, &[])?,
+        })
+    }
+
+    fn message(&self, name: &str) -> Result<&ProtoMessage, ProtoError> {
+        self.messages
+            .get(name)
+            .ok_or_else(|| ProtoError::UnknownType {
+                name: name.to_string(),
+            })
+    }
+}
+
+/// Protocol Buffers encoding of the value, see `ProtoSchema`.
+pub fn to_protobuf<T: Serialize + TS + ?Sized>(value: &T) -> Result<Vec<u8>, ProtoError> {
+    shared_schema().encode(value)
+}
+
+pub fn from_protobuf<T: DeserializeOwned + TS>(bytes: &[u8]) -> Result<T, ProtoError> {
+    shared_schema().decode(bytes)
+}
+
+impl MathDocument {
+    pub fn to_protobuf(&self) -> Result<Vec<u8>, ProtoError> {
+        to_protobuf(self)
+    }
+
+    pub fn from_protobuf(bytes: &[u8]) -> Result<MathDocument, ProtoError> {
+        from_protobuf(bytes)
+    }
+}
+
+fn shared_schema() -> &'static ProtoSchema {
+    static SCHEMA: OnceLock<ProtoSchema> = OnceLock::new();
+    SCHEMA.get_or_init(ProtoSchema::current)
+}
+
+impl ProtoType {
+    fn name(&self) -> &str {
+        match self {
+            ProtoType::String | ProtoType::Json => "string",
+            ProtoType::Double => "double",
+            ProtoType::Bool => "bool",
+            ProtoType::Unit => UNIT_MESSAGE,
+            ProtoType::Enum(name) | ProtoType::Message(name) => name,
+        }
+    }
+
+    fn is_packable(&self) -> bool {
+        matches!(
+            self,
+            ProtoType::Double | ProtoType::Bool | ProtoType::Enum(_)
+        )
+    }
+
+    fn wire_type(&self) -> u8 {
+        match self {
+            ProtoType::Double => 1,
+            ProtoType::Bool | ProtoType::Enum(_) => 0,
+            _ => 2,
+        }
+    }
+}
+
+/// Integral doubles as integers, so that they deserialize into integer fields again.
+fn number_value(number: f64) -> Value {
+    if number.fract() == 0.0 && number.abs() <= (1u64 << 53) as f64 {
+        Value::from(number as i64)
+    } else {
+        Number::from_f64(number)
+            .map(Value::Number)
+            .unwrap_or_default()
+    }
+}
+
+// --- Schema derivation ---
+
+/// A TypeScript type as ts-rs declares it.
+#[derive(Debug, Clone, PartialEq)]
+enum TsType {
+    Named(String), // including `string`, `number`, `boolean` and `unknown`
+    Null,
+    Literal(String),
+    Array(Box<TsType>),
+    Tuple(Vec<TsType>),
+    Map(Box<TsType>),
+    Object(Vec<(String, TsType)>),
+    Variant(String, Box<TsType>),        // `{ "Tag": T }`
+    Tagged(String, Option<Box<TsType>>), // `{ "kind": "Tag", "data": T }`
+    Union(Vec<TsType>),
+}
+
+struct SchemaBuilder {
+    declarations: BTreeMap<String, TsType>,
+    schema: ProtoSchema,
+}
+
+impl SchemaBuilder {
+    /// Adds the message or enum for a declared or inline type; aliases need none.
+    fn define(&mut self, name: &str, ty: &TsType) {
+        match ty {
+            TsType::Object(fields) => {
+                let fields = fields
+                    .iter()
+                    .map(|(field, ty)| (field.clone(), field.clone(), ty))
+                    .collect::<Vec<_>>();
+                self.define_message(name, MessageKind::Struct, fields);
+            }
+            TsType::Tuple(items) => {
+                let fields = items
+                    .iter()
+                    .enumerate()
+                    .map(|(index, ty)| (format!("item_{}", index), index.to_string(), ty))
+                    .collect();
+                self.define_message(name, MessageKind::Tuple, fields);
+            }
+            TsType::Literal(_) | TsType::Variant(..) | TsType::Tagged(..) => {
+                self.define(name, &TsType::Union(vec![ty.clone()]))
+            }
+            TsType::Union(members) if is_enum(members) => {
+                let variants = members
+                    .iter()
+                    .filter_map(|member| match member {
+                        TsType::Literal(variant) => Some(variant.clone()),
+                        _ => None,
+                    })
+                    .collect();
+                self.schema.enums.insert(name.to_string(), variants);
+            }
+            TsType::Union(members) if !members.contains(&TsType::Null) => {
+                let mut fields = vec![];
+                for (number, member) in (1..).zip(members) {
+                    let (tag, label, ty, unit_variant) = match member {
+                        TsType::Literal(tag) | TsType::Tagged(tag, None) => {
+                            (tag, Label::Single, ProtoType::Unit, true)
+                        }
+                        TsType::Variant(tag, payload) | TsType::Tagged(tag, Some(payload)) => {
+                            let context = format!("{}_{}", name, tag);
+                            let (label, ty) = self.single_shape(&context, payload);
+                            (tag, label, ty, false)
+                        }
+                        _ => continue,
+                    };
+                    fields.push(ProtoField {
+                        name: snake_case(tag),
+                        json_name: tag.clone(),
+                        number,
+                        label,
+                        ty,
+                        unit_variant,
+                    });
+                }
+                let tagged = members
+                    .iter()
+                    .any(|member| matches!(member, TsType::Tagged(..)));
+                self.schema.messages.insert(
+                    name.to_string(),
+                    ProtoMessage {
+                        kind: if tagged {
+                            MessageKind::Tagged
+                        } else {
+                            MessageKind::OneOf
+                        },
+                        fields,
+                    },
+                );
+            }
+            _ => {}
+        }
+    }
+
+    fn define_message(
+        &mut self,
+        name: &str,
+        kind: MessageKind,
+        fields: Vec<(String, String, &TsType)>,
+    ) {
+        let mut proto_fields = vec![];
+        for (number, (field, json_name, ty)) in (1..).zip(fields) {
+            let (label, ty) = self.shape(&format!("{}_{}", name, field), ty);
+            proto_fields.push(ProtoField {
+                name: field,
+                json_name,
+                number,
+                label,
+                ty,
+                unit_variant: false,
+            });
+        }
+        self.schema.messages.insert(
+            name.to_string(),
+            ProtoMessage {
+                kind,
+                fields: proto_fields,
+            },
+        );
+    }
+
+    /// How a value of type `ty` is held in a field, defining the inline types it needs, named
+    /// after `context`.
+    fn shape(&mut self, context: &str, ty: &TsType) -> (Label, ProtoType) {
+        match ty {
+            TsType::Union(members) if members.contains(&TsType::Null) => {
+                let mut rest: Vec<TsType> = members
+                    .iter()
+                    .filter(|member| **member != TsType::Null)
+                    .cloned()
+                    .collect();
+                let inner = match rest.len() {
+                    1 => rest.remove(0),
+                    _ => TsType::Union(rest),
+                };
+                match self.shape(context, &inner) {
+                    (Label::Single, ty) => (Label::Optional, ty),
+                    shape => (Label::Optional, self.wrapper(context, shape)),
+                }
+            }
+            TsType::Null => (Label::Single, ProtoType::Unit),
+            TsType::Named(name) => match name.as_str() {
+                "string" => (Label::Single, ProtoType::String),
+                "number" => (Label::Single, ProtoType::Double),
+                "boolean" => (Label::Single, ProtoType::Bool),
+                _ => match self.declarations.get(name).cloned() {
+                    Some(declared) if defines_type(&declared) => match declared {
+                        TsType::Literal(_) => (Label::Single, ProtoType::Enum(name.clone())),
+                        TsType::Union(members) if is_enum(&members) => {
+                            (Label::Single, ProtoType::Enum(name.clone()))
+                        }
+                        _ => (Label::Single, ProtoType::Message(name.clone())),
+                    },
+                    Some(aliased) => self.shape(name, &aliased),
+                    None => (Label::Single, ProtoType::Json),
+                },
+            },
+            TsType::Array(item) => {
+                let ty = self.single_shape(&format!("{}_item", context), item);
+                (Label::Repeated, ty.1)
+            }
+            TsType::Map(value) => {
+                let ty = self.single_shape(&format!("{}_value", context), value);
+                (Label::Map, ty.1)
+            }
+            TsType::Literal(_)
+            | TsType::Tuple(_)
+            | TsType::Object(_)
+            | TsType::Variant(..)
+            | TsType::Tagged(..)
+            | TsType::Union(_) => {
+                self.define(context, ty);
+                if self.schema.enums.contains_key(context) {
+                    (Label::Single, ProtoType::Enum(context.to_string()))
+                } else {
+                    (Label::Single, ProtoType::Message(context.to_string()))
+                }
+            }
+        }
+    }
+
+    /// `shape`, wrapped in a message where the type cannot be held directly: as an item of a
+    /// list, a value of a map or a variant of a `oneof`.
+    fn single_shape(&mut self, context: &str, ty: &TsType) -> (Label, ProtoType) {
+        match self.shape(context, ty) {
+            (Label::Single, ty) => (Label::Single, ty),
+            shape => (Label::Single, self.wrapper(context, shape)),
+        }
+    }
+
+    fn wrapper(&mut self, name: &str, (label, ty): (Label, ProtoType)) -> ProtoType {
+        self.schema.messages.insert(
+            name.to_string(),
+            ProtoMessage {
+                kind: MessageKind::Wrapper,
+                fields: vec![ProtoField {
+                    name: "value".to_string(),
+                    json_name: String::new(),
+                    number: 1,
+                    label,
+                    ty,
+                    unit_variant: false,
+                }],
+            },
+        );
+        ProtoType::Message(name.to_string())
+    }
+}
+
+/// Whether a declared type gets a message or enum of its own, rather than being an alias.
+fn defines_type(ty: &TsType) -> bool {
+    match ty {
+        TsType::Object(_)
+        | TsType::Tuple(_)
+        | TsType::Literal(_)
+        | TsType::Variant(..)
+        | TsType::Tagged(..) => true,
+        TsType::Union(members) => !members.contains(&TsType::Null),
+        _ => false,
+    }
+}
+
+fn is_enum(members: &[TsType]) -> bool {
+    members
+        .iter()
+        .all(|member| matches!(member, TsType::Literal(_)))
+}
+
+fn snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    for (index, c) in name.chars().enumerate() {
+        if c.is_uppercase() {
+            if index > 0 {
+                snake.push('_');
+            }
+            snake.extend(c.to_lowercase());
+        } else {
+            snake.push(c);
+        }
+    }
+    snake
+}
+
+fn render_message(name: &str, message: &ProtoMessage) -> String {
+    let field_line = |field: &ProtoField, indent: &str| {
+        let ty = match field.label {
+            Label::Single => field.ty.name().to_string(),
+            Label::Optional => format!("optional {}", field.ty.name()),
+            Label::Repeated => format!("repeated {}", field.ty.name()),
+            Label::Map => format!("map<string, {}>", field.ty.name()),
+        };
+        format!("{}{} {} = {};\n", indent, ty, field.name, field.number)
+    };
+    let mut proto = format!("message {} {{\n", name);
+    if matches!(message.kind, MessageKind::OneOf | MessageKind::Tagged) {
+        let mut oneof = "variant".to_string();
+        while message.fields.iter().any(|field| field.name == oneof) {
+            oneof.push('_');
+        }
+        proto.push_str(&format!("  oneof {} {{\n", oneof));
+        for field in &message.fields {
+            proto.push_str(&field_line(field, "    "));
+        }
+        proto.push_str("  }\n");
+    } else {
+        for field in &message.fields {
+            proto.push_str(&field_line(field, "  "));
+        }
+    }
+    proto.push_str("}\n");
+    proto
+}
+
+fn render_enum(name: &str, variants: &[String]) -> String {
+    let prefix = snake_case(name).to_uppercase();
+    let mut proto = format!("enum {} {{\n", name);
+    for (number, variant) in variants.iter().enumerate() {
+        proto.push_str(&format!(
+            "  {}_{} = {};\n",
+            prefix,
+            snake_case(variant).to_uppercase(),
+            number
+        ));
+    }
+    proto.push_str("}\n");
+    proto
+}
+
+/// Reads a ts-rs declaration, `type Name = ...;`.
+fn parse_declaration(declaration: &str) -> Option<TsType> {
+    let tokens = tokenize(declaration);
+    let start = tokens.iter().position(|token| *token == "=")? + 1;
+    let mut parser = TsParser {
+        tokens: &tokens[start..],
+        position: 0,
+    };
+    let ty = parser.parse_type()?;
+    parser.eat(";");
+    (parser.position == parser.tokens.len()).then_some(ty)
+}
+
+/// Identifiers, string literals and single punctuation characters; comments are dropped.
+fn tokenize(source: &str) -> Vec<&str> {
+    let mut tokens = vec![];
+    let mut rest = source;
+    while let Some(c) = rest.chars().next() {
+        let len = if c.is_whitespace() {
+            rest = &rest[c.len_utf8()..];
+            continue;
+        } else if rest.starts_with("/*") {
+            rest = rest.find("*/").map_or("", |end| &rest[end + 2..]);
+            continue;
+        } else if rest.starts_with("//") {
+            rest = rest.find('\n').map_or("", |end| &rest[end..]);
+            continue;
+        } else if c == '"' {
+            let mut escaped = false;
+            rest[1..]
+                .char_indices()
+                .find(|&(_, c)| {
+                    let end = c == '"' && !escaped;
+                    escaped = c == '\\' && !escaped;
+                    end
+                })
+                .map_or(rest.len(), |(index, _)| index + 2)
+        } else if c.is_alphanumeric() || c == '_' || c == '$' {
+            rest.find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+                .unwrap_or(rest.len())
+        } else {
+            c.len_utf8()
+        };
+        tokens.push(&rest[..len]);
+        rest = &rest[len..];
+    }
+    tokens
+}
+
+struct TsParser<'a> {
+    tokens: &'a [&'a str],
+    position: usize,
+}
+
+impl<'a> TsParser<'a> {
+    fn next(&mut self) -> Option<&'a str> {
+        let token = self.tokens.get(self.position)?;
+        self.position += 1;
+        Some(token)
+    }
+
+    fn peek(&self) -> Option<&'a str> {
+        self.tokens.get(self.position).copied()
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        let found = self.peek() == Some(token);
+        if found {
+            self.position += 1;
+        }
+        found
+    }
+
+    fn expect(&mut self, token: &str) -> Option<()> {
+        self.eat(token).then_some(())
+    }
+
+    fn parse_type(&mut self) -> Option<TsType> {
+        self.eat("|");
+        let mut members = vec![self.parse_primary()?];
+        while self.eat("|") {
+            members.push(self.parse_primary()?);
+        }
+        Some(match members.len() {
+            1 => members.remove(0),
+            _ => TsType::Union(members),
+        })
+    }
+
+    fn parse_primary(&mut self) -> Option<TsType> {
+        let token = self.next()?;
+        Some(match token {
+            "{" if self.peek() == Some("[") => {
+                // `{ [key in string]?: T }`
+                while self.next()? != "]" {}
+                self.eat("?");
+                self.expect(":")?;
+                let value = self.parse_type()?;
+                if !self.eat(";") {
+                    self.eat(",");
+                }
+                self.expect("}")?;
+                TsType::Map(Box::new(value))
+            }
+            "{" => {
+                let mut fields = vec![];
+                let mut quoted = false;
+                while !self.eat("}") {
+                    let key = self.next()?;
+                    quoted = key.starts_with('"');
+                    let key = key.trim_matches('"').to_string();
+                    self.eat("?");
+                    self.expect(":")?;
+                    fields.push((key, self.parse_type()?));
+                    if !self.eat(",") {
+                        self.eat(";");
+                    }
+                }
+                let tag = match fields.as_slice() {
+                    [(key, TsType::Literal(tag)), rest @ ..]
+                        if quoted
+                            && key == ENUM_TAG_KEY
+                            && rest.iter().all(|(key, _)| key == ENUM_CONTENT_KEY) =>
+                    {
+                        Some(tag.clone())
+                    }
+                    _ => None,
+                };
+                match (quoted, fields.len()) {
+                    (true, 1 | 2) if let Some(tag) = tag => {
+                        let payload = fields.pop().filter(|(key, _)| key == ENUM_CONTENT_KEY);
+                        TsType::Tagged(tag, payload.map(|(_, payload)| Box::new(payload)))
+                    }
+                    (true, 1) => {
+                        let (tag, payload) = fields.remove(0);
+                        TsType::Variant(tag, Box::new(payload))
+                    }
+                    _ => TsType::Object(fields),
+                }
+            }
+            "[" => {
+                let mut items = vec![];
+                while !self.eat("]") {
+                    items.push(self.parse_type()?);
+                    self.eat(",");
+                }
+                TsType::Tuple(items)
+            }
+            "null" => TsType::Null,
+            "Array" if self.eat("<") => {
+                let item = self.parse_type()?;
+                self.expect(">")?;
+                TsType::Array(Box::new(item))
+            }
+            literal if literal.starts_with('"') => {
+                TsType::Literal(literal.trim_matches('"').to_string())
+            }
+            name => TsType::Named(name.to_string()),
+        })
+    }
+}
+
+// --- Wire format ---
+
+fn write_varint(mut value: u64, out: &mut Vec<u8>) {
+    while value >= 0x80 {
+        out.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn write_len(number: u32, bytes: &[u8], out: &mut Vec<u8>) {
+    write_varint((number as u64) << 3 | 2, out);
+    write_varint(bytes.len() as u64, out);
+    out.extend(bytes);
+}
+
+/// A field's payload, with its offset for errors.
+#[derive(Debug, Clone, Copy)]
+enum Wire<'a> {
+    Varint(u64, usize),
+    Fixed64(u64, usize),
+    Fixed32(usize), // only skipped, as the schema has no 32-bit fields
+    Len(&'a [u8], usize),
+}
+
+impl Wire<'_> {
+    fn offset(&self) -> usize {
+        match *self {
+            Wire::Varint(_, offset)
+            | Wire::Fixed64(_, offset)
+            | Wire::Fixed32(offset)
+            | Wire::Len(_, offset) => offset,
+        }
+    }
+}
+
+struct WireReader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> WireReader<'a> {
+    fn read_field(&mut self) -> Result<Option<(u32, Wire<'a>)>, ProtoError> {
+        if self.offset >= self.bytes.len() {
+            return Ok(None);
+        }
+        let offset = self.offset;
+        let key = self.read_varint()?;
+        let number = u32::try_from(key >> 3).map_err(|_| ProtoError::Malformed { offset })?;
+        Ok(Some((number, self.read_payload((key & 7) as u8)?)))
+    }
+
+    fn read_payload(&mut self, wire_type: u8) -> Result<Wire<'a>, ProtoError> {
+        let offset = self.offset;
+        Ok(match wire_type {
+            0 => Wire::Varint(self.read_varint()?, offset),
+            1 => Wire::Fixed64(u64::from_le_bytes(self.take_array()?), offset),
+            2 => {
+                let len = self.read_varint()? as usize;
+                Wire::Len(self.take(len)?, self.offset - len)
+            }
+            5 => {
+                self.take(4)?;
+                Wire::Fixed32(offset)
+            }
+            _ => return Err(ProtoError::Malformed { offset }),
+        })
+    }
+
+    fn read_varint(&mut self) -> Result<u64, ProtoError> {
+        let offset = self.offset;
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = *self
+                .bytes
+                .get(self.offset)
+                .ok_or(ProtoError::Malformed { offset })?;
+            self.offset += 1;
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte < 0x80 {
+                return Ok(value);
+            }
+        }
+        Err(ProtoError::Malformed { offset })
+    }
+
+    fn take(&mut self, count: usize) -> Result<&'a [u8], ProtoError> {
+        let offset = self.offset;
+        let end = offset
+            .checked_add(count)
+            .filter(|&end| end <= self.bytes.len())
+            .ok_or(ProtoError::Malformed { offset })?;
+        self.offset = end;
+        Ok(&self.bytes[offset..end])
+    }
+
+    fn take_array<const N: usize>(&mut self) -> Result<[u8; N], ProtoError> {
+        Ok(self.take(N)?.try_into().unwrap_or([0; N]))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ProtoError {
+    /// The type has no message in the schema, e.g. because it is not exported.
+    UnknownType {
+        name: String,
+    },
+    /// The value does not have the shape the schema gives its type.
+    Mismatch {
+        type_name: String,
+        expected: String,
+    },
+    UnknownVariant {
+        type_name: String,
+        variant: String,
+    },
+    /// A message of an enum with data that sets none of its variants.
+    MissingVariant {
+        type_name: String,
+    },
+    /// The data is not valid Protocol Buffers wire format, or does not fit the schema.
+    Malformed {
+        offset: usize,
+    },
+    /// The data does not fit the type, or the type cannot be serialized.
+    Serde(String),
+}
+
+impl fmt::Display for ProtoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProtoError::UnknownType { name } => {
+                write!(f, "type `{}` has no Protocol Buffers message", name)
+            }
+            ProtoError::Mismatch {
+                type_name,
+                expected,
+            } => write!(f, "expected {} for `{}`", expected, type_name),
+            ProtoError::UnknownVariant { type_name, variant } => {
+                write!(f, "`{}` has no variant `{}`", type_name, variant)
+            }
+            ProtoError::MissingVariant { type_name } => {
+                write!(f, "message of `{}` sets no variant", type_name)
+            }
+            ProtoError::Malformed { offset } => {
+                write!(f, "malformed Protocol Buffers data at byte {}", offset)
+            }
+            ProtoError::Serde(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for ProtoError {}
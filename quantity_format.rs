@@ -0,0 +1,296 @@
+use super::*;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+/// How the numbers of `Quantity` nodes are written. Set for a whole document as
+/// `MathDocument::quantity_format`, which every render backend applies to every quantity;
+/// `format_number` is the reference implementation.
+///
+/// The default writes numbers as they are stored.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct QuantityFormat {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub significant_figures: Option<u32>, // Rounded or padded with zeros to this many
+    #[serde(default)]
+    pub notation: NumberNotation,
+    #[serde(default = "default_exponent_style")]
+    pub exponent_style: ScientificNotationStyle, // For scientific and engineering notation
+    #[serde(default)]
+    pub locale: NumberLocale,
+    #[serde(default)]
+    pub group_digits: bool, // Thousands separators, with the locale's group separator
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub enum NumberNotation {
+    #[default]
+    Plain, // 12300, 0.00045
+    Scientific,  // 1.23×10⁴, 4.5×10⁻⁴
+    Engineering, // 12.3×10³, 450×10⁻⁶: exponents are multiples of three
+}
+
+fn default_exponent_style() -> ScientificNotationStyle {
+    ScientificNotationStyle::TimesTenPower
+}
+
+impl Default for QuantityFormat {
+    fn default() -> Self {
+        QuantityFormat {
+            significant_figures: None,
+            notation: NumberNotation::Plain,
+            exponent_style: default_exponent_style(),
+            locale: NumberLocale::default(),
+            group_digits: false,
+        }
+    }
+}
+
+/// Numbers with a larger exponent are left as stored rather than written out in full.
+const MAX_EXPONENT: i64 = 1000;
+
+/// An exact decimal: `digits × 10^exponent`, with `digits` free of leading zeros.
+struct Decimal {
+    negative: bool,
+    digits: Vec<u8>,
+    exponent: i64,
+}
+
+impl Decimal {
+    /// Parses plain and exponent forms such as `-1234.50`, `.5` and `6.02e23`.
+    fn parse(number: &str) -> Option<Decimal> {
+        let number = number.trim();
+        let (negative, unsigned) = match number.strip_prefix(['-', '\u{2212}']) {
+            Some(rest) => (true, rest),
+            None => (false, number.strip_prefix('+').unwrap_or(number)),
+        };
+        let (mantissa, exponent) = match unsigned.split_once(['e', 'E']) {
+            Some((mantissa, exponent)) => (
+                mantissa,
+                exponent
+                    .parse::<i64>()
+                    .ok()
+                    .filter(|exponent| (-MAX_EXPONENT..=MAX_EXPONENT).contains(exponent))?,
+            ),
+            None => (unsigned, 0),
+        };
+        let (integer, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+        if integer.is_empty() && fraction.is_empty() {
+            return None;
+        }
+        let mut digits = vec![];
+        for c in integer.chars().chain(fraction.chars()) {
+            digits.push(c.to_digit(10)? as u8);
+        }
+        let leading_zeros = digits.iter().take_while(|digit| **digit == 0).count();
+        digits.drain(..leading_zeros.min(digits.len() - 1));
+        let mut exponent = exponent - fraction.len() as i64;
+        // Trailing zeros of a whole number are not significant: `12300` has three figures.
+        if !mantissa.contains('.') {
+            while digits.len() > 1 && digits.last() == Some(&0) {
+                digits.pop();
+                exponent += 1;
+            }
+        }
+        Some(Decimal {
+            negative: negative && digits.iter().any(|digit| *digit != 0),
+            digits,
+            exponent,
+        })
+    }
+
+    fn is_zero(&self) -> bool {
+        self.digits.iter().all(|digit| *digit == 0)
+    }
+
+    /// Rounds half away from zero, or pads with zeros, to `figures` significant digits.
+    fn round_to(&mut self, figures: usize) {
+        let figures = figures.max(1);
+        if self.is_zero() {
+            self.exponent -= figures as i64 - self.digits.len() as i64;
+            self.digits = vec![0; figures];
+            return;
+        }
+        if self.digits.len() <= figures {
+            let padding = figures - self.digits.len();
+            self.digits.extend(std::iter::repeat_n(0, padding));
+            self.exponent -= padding as i64;
+            return;
+        }
+        let round_up = self.digits[figures] >= 5;
+        self.exponent += (self.digits.len() - figures) as i64;
+        self.digits.truncate(figures);
+        if round_up {
+            let mut position = figures;
+            loop {
+                if position == 0 {
+                    // 999 → 1000: one more digit, so drop the last to keep `figures` of them
+                    self.digits.insert(0, 1);
+                    self.digits.pop();
+                    self.exponent += 1;
+                    break;
+                }
+                position -= 1;
+                if self.digits[position] == 9 {
+                    self.digits[position] = 0;
+                } else {
+                    self.digits[position] += 1;
+                    break;
+                }
+            }
+        }
+    }
+
+    /// The power of ten of the leading digit, 2 for `123`.
+    fn magnitude(&self) -> i64 {
+        self.digits.len() as i64 - 1 + self.exponent
+    }
+}
+
+/// Writes `number`, a `Quantity` number, in `format`. Returns `None` if it is not a decimal
+/// number, which backends then show as stored.
+pub fn format_number(number: &str, format: &QuantityFormat) -> Option<String> {
+    let mut decimal = Decimal::parse(number)?;
+    if let Some(figures) = format.significant_figures {
+        decimal.round_to(figures as usize);
+    }
+    let power = match format.notation {
+        NumberNotation::Plain => 0,
+        _ if decimal.is_zero() => 0,
+        NumberNotation::Scientific => decimal.magnitude(),
+        NumberNotation::Engineering => {
+            let magnitude = decimal.magnitude();
+            magnitude - magnitude.rem_euclid(3)
+        }
+    };
+    let mut text = String::new();
+    if decimal.negative {
+        text.push('-');
+    }
+    text.push_str(&positional(
+        &decimal.digits,
+        decimal.exponent - power,
+        format,
+    ));
+    if format.notation != NumberNotation::Plain {
+        text.push_str(&exponent(power, &format.exponent_style));
+    }
+    Some(text)
+}
+
+/// `digits × 10^exponent` written out with the locale's separators.
+fn positional(digits: &[u8], exponent: i64, format: &QuantityFormat) -> String {
+    let digit = |d: &u8| char::from(b'0' + d);
+    let (integer, fraction): (String, String) = if exponent >= 0 {
+        let zeros = "0".repeat(exponent as usize);
+        (
+            digits.iter().map(digit).collect::<String>() + &zeros,
+            String::new(),
+        )
+    } else {
+        let point = digits.len() as i64 + exponent;
+        if point <= 0 {
+            let zeros = "0".repeat(-point as usize);
+            (
+                "0".to_string(),
+                zeros + &digits.iter().map(digit).collect::<String>(),
+            )
+        } else {
+            let (integer, fraction) = digits.split_at(point as usize);
+            (
+                integer.iter().map(digit).collect(),
+                fraction.iter().map(digit).collect(),
+            )
+        }
+    };
+    let mut text = String::new();
+    match format
+        .locale
+        .group_separator
+        .filter(|_| format.group_digits)
+    {
+        Some(separator) => {
+            for (index, c) in integer.chars().enumerate() {
+                if index > 0 && (integer.len() - index) % 3 == 0 {
+                    text.push(separator);
+                }
+                text.push(c);
+            }
+        }
+        None => text.push_str(&integer),
+    }
+    if !fraction.is_empty() {
+        text.push(format.locale.decimal_separator);
+        text.push_str(&fraction);
+    }
+    text
+}
+
+fn exponent(power: i64, style: &ScientificNotationStyle) -> String {
+    match style {
+        ScientificNotationStyle::LowerCaseE => format!("e{}", power),
+        ScientificNotationStyle::UpperCaseE => format!("E{}", power),
+        ScientificNotationStyle::TimesTenPower => {
+            let superscript: String = power
+                .to_string()
+                .chars()
+                .map(|c| match c {
+                    '-' => '⁻',
+                    '0' => '⁰',
+                    '1' => '¹',
+                    '2' => '²',
+                    '3' => '³',
+                    '4' => '⁴',
+                    '5' => '⁵',
+                    '6' => '⁶',
+                    '7' => '⁷',
+                    '8' => '⁸',
+                    _ => '⁹',
+                })
+                .collect();
+            format!("×10{}", superscript)
+        }
+    }
+}
+
+impl MathNode {
+    /// The number of a `Quantity` in `format`, with an integer `ScientificNotation` magnitude
+    /// folded in, so that `1.5` × 10³ in plain notation becomes `1500`. `None` for other nodes
+    /// and numbers `format_number` does not read.
+    pub fn format_quantity(&self, format: &QuantityFormat) -> Option<String> {
+        let MathNodeContent::Quantity {
+            number,
+            scientific_notation,
+            ..
+        } = self.content.as_ref()
+        else {
+            return None;
+        };
+        let magnitude = match scientific_notation.as_ref().map(|n| n.content.as_ref()) {
+            None => 0,
+            Some(MathNodeContent::ScientificNotation { magnitude, .. }) => {
+                match magnitude.content.as_ref() {
+                    MathNodeContent::Quantity {
+                        number,
+                        scientific_notation: None,
+                        ..
+                    } => number.trim().parse::<i64>().ok()?,
+                    _ => return None,
+                }
+            }
+            Some(_) => return None,
+        };
+        let mut decimal = Decimal::parse(number)?;
+        decimal.exponent += magnitude.clamp(-MAX_EXPONENT, MAX_EXPONENT);
+        let mut digits: String = decimal
+            .digits
+            .iter()
+            .map(|digit| char::from(b'0' + digit))
+            .collect();
+        if decimal.negative {
+            digits.insert(0, '-');
+        }
+        format_number(&format!("{}e{}", digits, decimal.exponent), format)
+    }
+}
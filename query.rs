@@ -0,0 +1,496 @@
+use super::*;
+use std::{fmt, str::FromStr};
+
+/// A CSS-like selector over the nodes of a document, e.g. `Section[metadata.level=L2] Relationship`.
+///
+/// A selector is a list of steps separated by whitespace; each step must match a descendant of
+/// the node matched by the previous one. A step is a node kind followed by attribute filters:
+///
+/// - kinds are `Section`, `Panel`, the variant names of `SectionContentNode` (`Math`, `Table`, ...)
+///   and of `MathNodeContent` (`Relationship`, `Fraction`, ...), or `*` for any node
+/// - `[name]` requires the attribute to be present; `[name=value]`, `[name^=value]`,
+///   `[name$=value]` and `[name*=value]` compare it exactly, by prefix, suffix or substring
+/// - attributes are `id` (sections, panels and math nodes), `metadata.<key>` (sections),
+///   `operator` (the relation operator of `Relationship` and `UnaryRelationship` nodes, the
+///   turnstile of `Sequent` nodes), `color`, `background` and `emphasis` (`Styled` nodes), as
+///   in `Styled[color=red]`, and `delimiters` (`Matrix` nodes), as in `Matrix[delimiters=Bars]`
+///
+/// Values may be quoted with `"` or `'`. The kind may be omitted: `[id^=thm-]` is `*[id^=thm-]`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Selector {
+    pub steps: Vec<CompoundSelector>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompoundSelector {
+    pub kind: Option<String>, // None matches any kind
+    pub attributes: Vec<AttributeSelector>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AttributeSelector {
+    pub name: String,
+    pub matcher: Option<(AttributeOperator, String)>, // None only checks presence
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttributeOperator {
+    Equals,     // =
+    StartsWith, // ^=
+    EndsWith,   // $=
+    Contains,   // *=
+}
+
+/// A node matched by a `Selector`.
+#[derive(Debug, Clone, Copy)]
+pub enum QueryMatch<'doc> {
+    Section(&'doc Section),
+    Panel(&'doc Panel),
+    ContentNode(&'doc SectionContentNode),
+    MathNode(&'doc MathNode),
+}
+
+impl MathDocument {
+    /// Every node matching `selector`, in document order. See `Selector` for the syntax.
+    pub fn query(&self, selector: &str) -> Result<Vec<QueryMatch<'_>>, QueryError> {
+        Ok(self.select(&selector.parse()?))
+    }
+
+    pub fn select(&self, selector: &Selector) -> Vec<QueryMatch<'_>> {
+        let mut query = Query {
+            selector,
+            ancestors: vec![],
+            matches: vec![],
+        };
+        query.visit_document(self);
+        query.matches
+    }
+}
+
+impl<'doc> QueryMatch<'doc> {
+    pub fn kind_name(&self) -> &'static str {
+        match self {
+            QueryMatch::Section(_) => "Section",
+            QueryMatch::Panel(_) => "Panel",
+            QueryMatch::ContentNode(node) => node.kind_name(),
+            QueryMatch::MathNode(node) => node.content.kind_name(),
+        }
+    }
+
+    pub fn id(&self) -> Option<&'doc str> {
+        match self {
+            QueryMatch::Section(section) => Some(&section.id),
+            QueryMatch::Panel(panel) => Some(&panel.id),
+            QueryMatch::ContentNode(_) => None,
+            QueryMatch::MathNode(node) => Some(&node.id),
+        }
+    }
+
+    /// Value of a selector attribute on this node, if the node has it.
+    pub fn attribute(&self, name: &str) -> Option<String> {
+        if name == "id" {
+            return self.id().map(str::to_string);
+        }
+        match self {
+            QueryMatch::Section(section) => {
+                let key = name.strip_prefix("metadata.")?;
+                section
+                    .metadata
+                    .iter()
+                    .find(|(metadata_key, _)| metadata_key == key)
+                    .map(|(_, value)| value.clone())
+            }
+            QueryMatch::MathNode(node) if name == "operator" => match node.content.as_ref() {
+                MathNodeContent::Relationship { operator, .. } => Some(variant_name(operator)),
+                MathNodeContent::UnaryRelationship { predicate, .. } => {
+                    Some(variant_name(predicate))
+                }
+                MathNodeContent::Sequent { turnstile, .. } => Some(variant_name(turnstile)),
+                _ => None,
+            },
+            QueryMatch::MathNode(node) => match (node.content.as_ref(), name) {
+                (MathNodeContent::Styled { color, .. }, "color") => color.clone(),
+                (MathNodeContent::Styled { background, .. }, "background") => background.clone(),
+                (MathNodeContent::Styled { emphasis, .. }, "emphasis") => {
+                    emphasis.as_ref().map(variant_name)
+                }
+                (MathNodeContent::Matrix { delimiters, .. }, "delimiters") => {
+                    delimiters.as_ref().map(variant_name)
+                }
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    pub fn as_section(&self) -> Option<&'doc Section> {
+        match self {
+            QueryMatch::Section(section) => Some(section),
+            _ => None,
+        }
+    }
+
+    pub fn as_math_node(&self) -> Option<&'doc MathNode> {
+        match self {
+            QueryMatch::MathNode(node) => Some(node),
+            _ => None,
+        }
+    }
+}
+
+// `Custom("≈")` is reported as `Custom`, like the unit variants.
+fn variant_name(value: &impl fmt::Debug) -> String {
+    let debug = format!("{:?}", value);
+    match debug.find(['(', ' ', '{']) {
+        Some(end) => debug[..end].to_string(),
+        None => debug,
+    }
+}
+
+impl Selector {
+    fn matches(&self, node: &QueryMatch<'_>, ancestors: &[QueryMatch<'_>]) -> bool {
+        let Some((last, rest)) = self.steps.split_last() else {
+            return false;
+        };
+        if !last.matches(node) {
+            return false;
+        }
+        // Match the remaining steps against ancestors from the nearest outwards; taking the
+        // nearest match for each step is enough when every combinator is "descendant".
+        let mut ancestors = ancestors.iter().rev();
+        rest.iter()
+            .rev()
+            .all(|step| ancestors.any(|ancestor| step.matches(ancestor)))
+    }
+}
+
+impl CompoundSelector {
+    fn matches(&self, node: &QueryMatch<'_>) -> bool {
+        if let Some(kind) = &self.kind
+            && kind != node.kind_name()
+        {
+            return false;
+        }
+        self.attributes.iter().all(|attribute| {
+            let Some(value) = node.attribute(&attribute.name) else {
+                return false;
+            };
+            match &attribute.matcher {
+                None => true,
+                Some((AttributeOperator::Equals, expected)) => value == *expected,
+                Some((AttributeOperator::StartsWith, expected)) => value.starts_with(expected),
+                Some((AttributeOperator::EndsWith, expected)) => value.ends_with(expected),
+                Some((AttributeOperator::Contains, expected)) => value.contains(expected.as_str()),
+            }
+        })
+    }
+}
+
+struct Query<'s, 'doc> {
+    selector: &'s Selector,
+    ancestors: Vec<QueryMatch<'doc>>,
+    matches: Vec<QueryMatch<'doc>>,
+}
+
+impl<'doc> Query<'_, 'doc> {
+    /// Tests `node`, then runs `descend` with it on the ancestor stack.
+    fn enter(&mut self, node: QueryMatch<'doc>, descend: impl FnOnce(&mut Self)) {
+        if self.selector.matches(&node, &self.ancestors) {
+            self.matches.push(node);
+        }
+        self.ancestors.push(node);
+        descend(self);
+        self.ancestors.pop();
+    }
+
+    fn math_node(&mut self, node: &'doc MathNode) {
+        self.enter(QueryMatch::MathNode(node), |query| {
+            for (_, child) in node.content.children_with_paths() {
+                query.math_node(child);
+            }
+        });
+    }
+}
+
+impl<'doc> DocumentVisitor<'doc> for Query<'_, 'doc> {
+    fn visit_section(&mut self, section: &'doc Section) {
+        self.enter(QueryMatch::Section(section), |query| {
+            walk_section(query, section)
+        });
+    }
+
+    fn visit_panel(&mut self, panel: &'doc Panel) {
+        self.enter(QueryMatch::Panel(panel), |query| walk_panel(query, panel));
+    }
+
+    fn visit_content_node(&mut self, node: &'doc SectionContentNode) {
+        self.enter(QueryMatch::ContentNode(node), |query| {
+            walk_content_node(query, node)
+        });
+    }
+
+    fn visit_math_node(&mut self, node: &'doc MathNode) {
+        self.math_node(node);
+    }
+}
+
+impl FromStr for Selector {
+    type Err = QueryError;
+
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        let mut parser = SelectorParser {
+            chars: source.char_indices().peekable(),
+        };
+        let mut steps = vec![];
+        loop {
+            parser.skip_whitespace();
+            if parser.chars.peek().is_none() {
+                break;
+            }
+            steps.push(parser.compound()?);
+        }
+        if steps.is_empty() {
+            return Err(QueryError::EmptySelector);
+        }
+        Ok(Selector { steps })
+    }
+}
+
+struct SelectorParser<'a> {
+    chars: std::iter::Peekable<std::str::CharIndices<'a>>,
+}
+
+impl SelectorParser<'_> {
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+    }
+
+    fn unexpected(&mut self, expected: &'static str) -> QueryError {
+        match self.chars.peek() {
+            Some(&(position, found)) => QueryError::UnexpectedChar {
+                position,
+                found,
+                expected,
+            },
+            None => QueryError::UnexpectedEnd { expected },
+        }
+    }
+
+    fn expect(&mut self, c: char, expected: &'static str) -> Result<(), QueryError> {
+        match self.chars.next_if(|&(_, found)| found == c) {
+            Some(_) => Ok(()),
+            None => Err(self.unexpected(expected)),
+        }
+    }
+
+    fn word(&mut self, allowed: impl Fn(char) -> bool) -> String {
+        let mut word = String::new();
+        while let Some((_, c)) = self.chars.next_if(|&(_, c)| allowed(c)) {
+            word.push(c);
+        }
+        word
+    }
+
+    fn compound(&mut self) -> Result<CompoundSelector, QueryError> {
+        let kind = if self.chars.next_if(|&(_, c)| c == '*').is_some() {
+            None
+        } else {
+            Some(self.word(|c| c.is_alphanumeric() || c == '_')).filter(|kind| !kind.is_empty())
+        };
+        let mut attributes = vec![];
+        while self.chars.peek().is_some_and(|&(_, c)| c == '[') {
+            attributes.push(self.attribute()?);
+        }
+        if kind.is_none() && attributes.is_empty() && !self.at_step_end() {
+            return Err(self.unexpected("a node kind, `*` or `[`"));
+        }
+        if !self.at_step_end() {
+            return Err(self.unexpected("whitespace or `[`"));
+        }
+        Ok(CompoundSelector { kind, attributes })
+    }
+
+    fn at_step_end(&mut self) -> bool {
+        self.chars.peek().is_none_or(|&(_, c)| c.is_whitespace())
+    }
+
+    fn attribute(&mut self) -> Result<AttributeSelector, QueryError> {
+        self.expect('[', "`[`")?;
+        self.skip_whitespace();
+        let name = self.word(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.'));
+        if name.is_empty() {
+            return Err(self.unexpected("an attribute name"));
+        }
+        self.skip_whitespace();
+        let operator = match self.chars.peek().map(|&(_, c)| c) {
+            Some(']') => None,
+            Some('=') => Some(AttributeOperator::Equals),
+            Some('^') => Some(AttributeOperator::StartsWith),
+            Some('$') => Some(AttributeOperator::EndsWith),
+            Some('*') => Some(AttributeOperator::Contains),
+            _ => return Err(self.unexpected("`]` or a comparison")),
+        };
+        let matcher = match operator {
+            None => None,
+            Some(operator) => {
+                if operator != AttributeOperator::Equals {
+                    self.chars.next();
+                }
+                self.expect('=', "`=`")?;
+                self.skip_whitespace();
+                Some((operator, self.value()?))
+            }
+        };
+        self.skip_whitespace();
+        self.expect(']', "`]`")?;
+        Ok(AttributeSelector { name, matcher })
+    }
+
+    fn value(&mut self) -> Result<String, QueryError> {
+        let quote = self.chars.next_if(|&(_, c)| c == '"' || c == '\'');
+        match quote {
+            Some((_, quote)) => {
+                let value = self.word(|c| c != quote);
+                self.expect(quote, "a closing quote")?;
+                Ok(value)
+            }
+            None => Ok(self.word(|c| c != ']' && !c.is_whitespace())),
+        }
+    }
+}
+
+impl SectionContentNode {
+    /// Variant name, as used for node kinds in selectors.
+    pub fn kind_name(&self) -> &'static str {
+        match self {
+            SectionContentNode::SubSection(_) => "SubSection",
+            SectionContentNode::RichText(_) => "RichText",
+            SectionContentNode::Math(_) => "Math",
+            SectionContentNode::SecondOrderMath(_) => "SecondOrderMath",
+            SectionContentNode::InteractiveDiagram(_) => "InteractiveDiagram",
+            SectionContentNode::Theorem => "Theorem",
+            SectionContentNode::List(_) => "List",
+            SectionContentNode::Table(_) => "Table",
+            SectionContentNode::CodeBlock(_) => "CodeBlock",
+            SectionContentNode::Image(_) => "Image",
+            SectionContentNode::CollapsibleBlock(_) => "CollapsibleBlock",
+            SectionContentNode::Grid(_) => "Grid",
+            SectionContentNode::Columns(_) => "Columns",
+            SectionContentNode::ThematicBreak(_) => "ThematicBreak",
+            SectionContentNode::QuoteBlock { .. } => "QuoteBlock",
+            SectionContentNode::AlertBox { .. } => "AlertBox",
+            SectionContentNode::CustomComponent { .. } => "CustomComponent",
+            SectionContentNode::EmbeddedSectionRef(_) => "EmbeddedSectionRef",
+            SectionContentNode::SideBySideLayout(_) => "SideBySideLayout",
+            SectionContentNode::PanelLayout(_) => "PanelLayout",
+            SectionContentNode::AnnotationOverlay(_) => "AnnotationOverlay",
+            SectionContentNode::InteractiveControls(_) => "InteractiveControls",
+            SectionContentNode::EmbeddedDocument(_) => "EmbeddedDocument",
+            SectionContentNode::BranchingContainer(_) => "BranchingContainer",
+            SectionContentNode::Feedback(_) => "Feedback",
+            SectionContentNode::GenerationError(_) => "GenerationError",
+            SectionContentNode::DerivationChain(_) => "DerivationChain",
+            SectionContentNode::Equation(_) => "Equation",
+        }
+    }
+}
+
+impl MathNodeContent {
+    /// Variant name, as used for node kinds in selectors.
+    pub fn kind_name(&self) -> &'static str {
+        match self {
+            MathNodeContent::Empty => "Empty",
+            MathNodeContent::Text(_) => "Text",
+            MathNodeContent::String(_) => "String",
+            MathNodeContent::Phantom { .. } => "Phantom",
+            MathNodeContent::Space(_) => "Space",
+            MathNodeContent::Bracketed { .. } => "Bracketed",
+            MathNodeContent::Matrix { .. } => "Matrix",
+            MathNodeContent::BinaryOperation { .. } => "BinaryOperation",
+            MathNodeContent::Multiplications { .. } => "Multiplications",
+            MathNodeContent::Additions { .. } => "Additions",
+            MathNodeContent::Division { .. } => "Division",
+            MathNodeContent::SumNotation { .. } => "SumNotation",
+            MathNodeContent::ProductNotation { .. } => "ProductNotation",
+            MathNodeContent::Fraction { .. } => "Fraction",
+            MathNodeContent::Power { .. } => "Power",
+            MathNodeContent::Radical { .. } => "Radical",
+            MathNodeContent::Binomial { .. } => "Binomial",
+            MathNodeContent::Cases { .. } => "Cases",
+            MathNodeContent::Bra { .. } => "Bra",
+            MathNodeContent::Ket { .. } => "Ket",
+            MathNodeContent::BraKet { .. } => "BraKet",
+            MathNodeContent::Expectation { .. } => "Expectation",
+            MathNodeContent::UnaryPostfixOperation { .. } => "UnaryPostfixOperation",
+            MathNodeContent::UnaryPrefixOperation { .. } => "UnaryPrefixOperation",
+            MathNodeContent::Abs { .. } => "Abs",
+            MathNodeContent::Norm { .. } => "Norm",
+            MathNodeContent::Decorated { .. } => "Decorated",
+            MathNodeContent::Brace { .. } => "Brace",
+            MathNodeContent::Styled { .. } => "Styled",
+            MathNodeContent::Cancelled { .. } => "Cancelled",
+            MathNodeContent::AngleMeasure { .. } => "AngleMeasure",
+            MathNodeContent::AngleSymbol { .. } => "AngleSymbol",
+            MathNodeContent::Coordinates { .. } => "Coordinates",
+            MathNodeContent::FunctionCall { .. } => "FunctionCall",
+            MathNodeContent::Quantity { .. } => "Quantity",
+            MathNodeContent::ScientificNotation { .. } => "ScientificNotation",
+            MathNodeContent::Identifier(_) => "Identifier",
+            MathNodeContent::Unit { .. } => "Unit",
+            MathNodeContent::ChemicalExpression(_) => "ChemicalExpression",
+            MathNodeContent::Relationship { .. } => "Relationship",
+            MathNodeContent::UnaryRelationship { .. } => "UnaryRelationship",
+            MathNodeContent::VariableDefinition { .. } => "VariableDefinition",
+            MathNodeContent::FunctionDefinition { .. } => "FunctionDefinition",
+            MathNodeContent::Limit { .. } => "Limit",
+            MathNodeContent::Differential { .. } => "Differential",
+            MathNodeContent::Integration { .. } => "Integration",
+            MathNodeContent::QuantifiedExpression { .. } => "QuantifiedExpression",
+            MathNodeContent::SetExplicit { .. } => "SetExplicit",
+            MathNodeContent::SetBuilder { .. } => "SetBuilder",
+            MathNodeContent::RichTextContent(_) => "RichTextContent",
+            MathNodeContent::And(_) => "And",
+            MathNodeContent::Or(_) => "Or",
+            MathNodeContent::Sequent { .. } => "Sequent",
+            MathNodeContent::Not(_) => "Not",
+            MathNodeContent::True => "True",
+            MathNodeContent::False => "False",
+            MathNodeContent::Folded { .. } => "Folded",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum QueryError {
+    EmptySelector,
+    UnexpectedEnd {
+        expected: &'static str,
+    },
+    UnexpectedChar {
+        position: usize,
+        found: char,
+        expected: &'static str,
+    },
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QueryError::EmptySelector => write!(f, "selector is empty"),
+            QueryError::UnexpectedEnd { expected } => {
+                write!(f, "selector ended early, expected {}", expected)
+            }
+            QueryError::UnexpectedChar {
+                position,
+                found,
+                expected,
+            } => write!(
+                f,
+                "unexpected `{}` at offset {} in selector, expected {}",
+                found, position, expected
+            ),
+        }
+    }
+}
+
+impl std::error::Error for QueryError {}
@@ -0,0 +1,208 @@
+use super::*;
+use std::collections::HashSet;
+
+/// What kind of reference points at a target id.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReferenceKind {
+    CrossReference,  // `CrossReference.target_id`, any element id
+    TocEntry,        // `TocNode.target_id`, a section of the same document
+    Footnote,        // `RichTextSegment::FootnoteReference`, a footnote section
+    BibliographyKey, // `LinkTarget::BibliographyKey`, a `BibEntry` key
+    EmbeddedSection, // `SectionContentNode::EmbeddedSectionRef`, a section
+    InternalPage,    // `LinkTarget::InternalPageId`, a document or section
+    Equation,        // `LinkTarget::Equation`, an equation
+}
+
+impl ReferenceKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ReferenceKind::CrossReference => "cross reference",
+            ReferenceKind::TocEntry => "table of contents entry",
+            ReferenceKind::Footnote => "footnote reference",
+            ReferenceKind::BibliographyKey => "citation",
+            ReferenceKind::EmbeddedSection => "embedded section reference",
+            ReferenceKind::InternalPage => "page link",
+            ReferenceKind::Equation => "equation reference",
+        }
+    }
+}
+
+impl MathDocument {
+    /// The references in the document, embedded and tooltip documents included, whose target
+    /// exists neither in the document nor in one of the `registry` documents. Footnotes,
+    /// bibliography keys and ToC entries always have to resolve within the document.
+    pub fn validate_references<'a>(
+        &self,
+        registry: impl IntoIterator<Item = &'a MathDocument>,
+    ) -> Vec<ValidationError> {
+        let mut local = ReferenceTargets::default();
+        local.add_document(self);
+        let mut targets = local.clone();
+        for document in registry {
+            targets.add_document(document);
+        }
+
+        let resolves = |kind: &ReferenceKind, target: &String, path: &[LocationStep]| match kind {
+            ReferenceKind::CrossReference => targets.ids.contains(target),
+            ReferenceKind::TocEntry => {
+                let document_id = path.iter().rev().find_map(|step| match step {
+                    LocationStep::Document(id) => Some(id.clone()),
+                    _ => None,
+                });
+                let key = (document_id.unwrap_or_default(), target.clone());
+                local.toc_sections.contains(&key)
+            }
+            ReferenceKind::Footnote => local.footnotes.contains(target),
+            ReferenceKind::BibliographyKey => local.bibliography_keys.contains(target),
+            ReferenceKind::EmbeddedSection => targets.sections.contains(target),
+            ReferenceKind::InternalPage => {
+                targets.documents.contains(target) || targets.sections.contains(target)
+            }
+            ReferenceKind::Equation => targets.equations.contains(target),
+        };
+
+        let mut collector = ReferenceCollector::default();
+        collector.visit_document(self);
+        collector
+            .references
+            .into_iter()
+            .filter(|(kind, target, path)| !resolves(kind, target, path))
+            .map(|(kind, target, path)| ValidationError::DanglingReference { kind, target, path })
+            .collect()
+    }
+}
+
+/// The ids references can resolve to.
+#[derive(Debug, Clone, Default)]
+struct ReferenceTargets {
+    ids: HashSet<String>,
+    documents: HashSet<String>,
+    sections: HashSet<String>,
+    equations: HashSet<String>,
+    toc_sections: HashSet<(String, String)>, // (document id, section id), sections a ToC may list
+    footnotes: HashSet<String>,
+    bibliography_keys: HashSet<String>,
+}
+
+impl ReferenceTargets {
+    fn add_document(&mut self, document: &MathDocument) {
+        self.ids
+            .extend(document.defined_ids().into_iter().map(str::to_string));
+        let mut collector = TargetCollector {
+            targets: self,
+            documents: vec![],
+        };
+        collector.visit_document(document);
+    }
+}
+
+struct TargetCollector<'a> {
+    targets: &'a mut ReferenceTargets,
+    documents: Vec<String>,
+}
+
+impl<'doc> DocumentVisitor<'doc> for TargetCollector<'_> {
+    fn visit_document(&mut self, document: &'doc MathDocument) {
+        self.targets.documents.insert(document.id.clone());
+        if let Some(structure) = document.content_type.structure() {
+            let footnotes = structure.footnotes.iter().map(|s| s.id.clone());
+            self.targets.footnotes.extend(footnotes);
+            let keys = structure.bibliography.iter().map(|e| e.key.clone());
+            self.targets.bibliography_keys.extend(keys);
+        }
+        self.documents.push(document.id.clone());
+        walk_document(self, document);
+        self.documents.pop();
+    }
+
+    fn visit_section(&mut self, section: &'doc Section) {
+        self.targets.sections.insert(section.id.clone());
+        let document_id = self.documents.last().cloned().unwrap_or_default();
+        self.targets
+            .toc_sections
+            .insert((document_id, section.id.clone()));
+        walk_section(self, section);
+    }
+
+    fn visit_content_node(&mut self, node: &'doc SectionContentNode) {
+        if let SectionContentNode::Equation(equation) = node {
+            self.targets.equations.insert(equation.id.clone());
+        }
+        walk_content_node(self, node);
+    }
+}
+
+#[derive(Default)]
+struct ReferenceCollector {
+    path: Vec<LocationStep>,
+    references: Vec<(ReferenceKind, String, Vec<LocationStep>)>,
+}
+
+impl ReferenceCollector {
+    fn push(&mut self, kind: ReferenceKind, target: &str) {
+        self.references
+            .push((kind, target.to_string(), self.path.clone()));
+    }
+
+    fn toc(&mut self, toc: &TocNode) {
+        if !toc.target_id.is_empty() {
+            self.push(ReferenceKind::TocEntry, &toc.target_id);
+        }
+        for child in &toc.children {
+            self.toc(child);
+        }
+    }
+}
+
+impl<'doc> DocumentVisitor<'doc> for ReferenceCollector {
+    fn visit_document(&mut self, document: &'doc MathDocument) {
+        self.path.push(LocationStep::Document(document.id.clone()));
+        let content_type = &document.content_type;
+        if let Some(relationships) = content_type.relationships() {
+            for reference in &relationships.cross_references {
+                self.push(ReferenceKind::CrossReference, &reference.target_id);
+            }
+        }
+        if let Some(toc) = content_type
+            .structure()
+            .and_then(|structure| structure.table_of_contents.as_ref())
+        {
+            self.toc(toc);
+        }
+        walk_document(self, document);
+        self.path.pop();
+    }
+
+    fn visit_section(&mut self, section: &'doc Section) {
+        self.path.push(LocationStep::Section(section.id.clone()));
+        walk_section(self, section);
+        self.path.pop();
+    }
+
+    fn visit_panel(&mut self, panel: &'doc Panel) {
+        self.path.push(LocationStep::Panel(panel.id.clone()));
+        walk_panel(self, panel);
+        self.path.pop();
+    }
+
+    fn visit_content_node(&mut self, node: &'doc SectionContentNode) {
+        if let SectionContentNode::EmbeddedSectionRef(section_id) = node {
+            self.push(ReferenceKind::EmbeddedSection, section_id);
+        }
+        walk_content_node(self, node);
+    }
+
+    fn visit_segment(&mut self, segment: &'doc RichTextSegment) {
+        match segment {
+            RichTextSegment::FootnoteReference(id) => self.push(ReferenceKind::Footnote, id),
+            RichTextSegment::Link { target, .. } => match target {
+                LinkTarget::BibliographyKey(key) => self.push(ReferenceKind::BibliographyKey, key),
+                LinkTarget::InternalPageId(id) => self.push(ReferenceKind::InternalPage, id),
+                LinkTarget::Equation(id) => self.push(ReferenceKind::Equation, id),
+                _ => {}
+            },
+            _ => {}
+        }
+        walk_segment(self, segment);
+    }
+}
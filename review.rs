@@ -0,0 +1,369 @@
+use super::*;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+
+/// CSS colors of the changes in a review view.
+pub const INSERTED_COLOR: &str = "#1a7f37";
+pub const DELETED_COLOR: &str = "#cf222e";
+pub const MOVED_COLOR: &str = "#0969da";
+
+impl MathDocument {
+    /// The document after `patch`, marked up for reviewing the patch. Inserted sections get
+    /// underlined titles and their content in a `Success` alert box, removed sections are put
+    /// back where they were with struck-through titles and their content in an `Error` box, and
+    /// moved sections get colored titles. Changed rich text is diffed word by word; other changed
+    /// content is shown old and new, one box each. Removed sections whose list no longer exists
+    /// are left out.
+    pub fn review_view(&self, patch: &DocumentPatch) -> Result<MathDocument, PatchError> {
+        let mut view = self.clone();
+        view.apply(patch)?;
+        let forward = self.diff(&view);
+        let backward = view.diff(self);
+
+        let mut inserted = HashSet::new();
+        let mut moved = HashSet::new();
+        for operation in &forward.operations {
+            match operation {
+                PatchOperation::InsertSection { section, .. } => {
+                    inserted.extend(section_ids(section));
+                }
+                PatchOperation::MoveSection { section_id, .. } => {
+                    moved.insert(section_id.clone());
+                }
+                _ => {}
+            }
+        }
+        let old_shells: HashMap<String, Section> = view
+            .sections()
+            .into_iter()
+            .filter_map(|section| self.find_section(&section.id))
+            .map(|old| (old.id.clone(), old.without_subsections()))
+            .collect();
+
+        let mut removed = HashSet::new();
+        for operation in backward.operations {
+            let PatchOperation::InsertSection {
+                parent,
+                after,
+                section,
+            } = operation
+            else {
+                continue;
+            };
+            let ids = section_ids(&section);
+            if restore(&mut view, parent, after, section) {
+                removed.extend(ids);
+            }
+        }
+
+        let mut marker = ChangeMarker {
+            inserted,
+            removed,
+            moved,
+            old_shells,
+        };
+        walk_document_mut(&mut marker, &mut view);
+        Ok(view)
+    }
+}
+
+/// The ids of the section and the sections nested in it.
+fn section_ids(section: &Section) -> Vec<String> {
+    let mut ids = vec![section.id.clone()];
+    for list in section.subsection_lists() {
+        for nested in list {
+            ids.extend(section_ids(nested));
+        }
+    }
+    ids
+}
+
+/// Puts a removed section back into the view, after the section it followed if that is still
+/// there, first in its list otherwise. An occupied abstract is not replaced.
+fn restore(
+    view: &mut MathDocument,
+    parent: SectionParent,
+    after: Option<String>,
+    section: Section,
+) -> bool {
+    if parent == SectionParent::Abstract
+        && view
+            .content_type
+            .structure()
+            .is_some_and(|structure| structure.abstract_content.is_some())
+    {
+        return false;
+    }
+    let candidates = [after, None];
+    let tries = if candidates[0].is_some() { 2 } else { 1 };
+    candidates.into_iter().take(tries).any(|after| {
+        let patch = DocumentPatch {
+            operations: vec![PatchOperation::InsertSection {
+                parent: parent.clone(),
+                after,
+                section: section.clone(),
+            }],
+        };
+        view.apply(&patch).is_ok()
+    })
+}
+
+struct ChangeMarker {
+    inserted: HashSet<String>,
+    removed: HashSet<String>,
+    moved: HashSet<String>,
+    old_shells: HashMap<String, Section>,
+}
+
+impl DocumentVisitorMut for ChangeMarker {
+    fn visit_document_mut(&mut self, _document: &mut MathDocument) {}
+
+    fn visit_section_mut(&mut self, section: &mut Section) {
+        if self.inserted.contains(&section.id) {
+            mark_whole(section, inserted_styles(), AlertBoxStyle::Success);
+        } else if self.removed.contains(&section.id) {
+            mark_whole(section, deleted_styles(), AlertBoxStyle::Error);
+        } else if let Some(old) = self.old_shells.get(&section.id) {
+            mark_changes(old, section);
+            if self.moved.contains(&section.id)
+                && let Some(title) = &mut section.title
+            {
+                title.segments = restyle(&title.segments, &moved_styles());
+            }
+        }
+        walk_section_mut(self, section);
+    }
+}
+
+fn inserted_styles() -> Vec<TextStyle> {
+    vec![
+        TextStyle::Underline,
+        TextStyle::Color(INSERTED_COLOR.to_string()),
+    ]
+}
+
+fn deleted_styles() -> Vec<TextStyle> {
+    vec![
+        TextStyle::Strikethrough,
+        TextStyle::Color(DELETED_COLOR.to_string()),
+    ]
+}
+
+fn moved_styles() -> Vec<TextStyle> {
+    vec![TextStyle::Color(MOVED_COLOR.to_string())]
+}
+
+fn mark_whole(section: &mut Section, styles: Vec<TextStyle>, style: AlertBoxStyle) {
+    if let Some(title) = &mut section.title {
+        title.segments = restyle(&title.segments, &styles);
+    }
+    let content = std::mem::replace(&mut section.content, SectionContentNode::SubSection(vec![]));
+    section.content = SectionContentNode::AlertBox {
+        style,
+        content: vec![content],
+    };
+}
+
+/// Marks what changed in a kept section since `old`, a copy without its nested sections.
+fn mark_changes(old: &Section, section: &mut Section) {
+    if let (Some(old_title), Some(title)) = (&old.title, &mut section.title) {
+        if to_json(old_title) != to_json(title) {
+            title.segments = diff_segments(&old_title.segments, &title.segments)
+                .unwrap_or_else(|| replaced(&old_title.segments, &title.segments));
+        }
+    } else if let Some(title) = &mut section.title {
+        title.segments = restyle(&title.segments, &inserted_styles());
+    }
+
+    let shell = section.without_subsections();
+    if to_json(&old.content) == to_json(&shell.content) {
+        return;
+    }
+    if let (SectionContentNode::RichText(old_text), SectionContentNode::RichText(text)) =
+        (&old.content, &mut section.content)
+        && let Some(segments) = diff_segments(&old_text.segments, &text.segments)
+    {
+        text.segments = segments;
+        return;
+    }
+    let content = std::mem::replace(&mut section.content, SectionContentNode::SubSection(vec![]));
+    section.content = SectionContentNode::AlertBox {
+        style: AlertBoxStyle::Note,
+        content: vec![
+            SectionContentNode::AlertBox {
+                style: AlertBoxStyle::Error,
+                content: vec![old.content.clone()],
+            },
+            SectionContentNode::AlertBox {
+                style: AlertBoxStyle::Success,
+                content: vec![content],
+            },
+        ],
+    };
+}
+
+/// The old segments struck through, followed by the new ones underlined.
+fn replaced(old: &[RichTextSegment], new: &[RichTextSegment]) -> Vec<RichTextSegment> {
+    let mut segments = restyle(old, &deleted_styles());
+    segments.push(RichTextSegment::Text(" ".to_string()));
+    segments.extend(restyle(new, &inserted_styles()));
+    segments
+}
+
+/// Adds `styles` to the text of the segments, including link texts. Other segments, such as
+/// inline math, cannot carry styles and are kept as they are.
+fn restyle(segments: &[RichTextSegment], styles: &[TextStyle]) -> Vec<RichTextSegment> {
+    segments
+        .iter()
+        .map(|segment| match segment {
+            RichTextSegment::Text(text) => RichTextSegment::StyledText {
+                text: text.clone(),
+                styles: styles.to_vec(),
+            },
+            RichTextSegment::StyledText {
+                text,
+                styles: own_styles,
+            } => RichTextSegment::StyledText {
+                text: text.clone(),
+                styles: own_styles.iter().chain(styles).cloned().collect(),
+            },
+            RichTextSegment::Link {
+                content,
+                target,
+                tooltip,
+            } => RichTextSegment::Link {
+                content: restyle(content, styles),
+                target: target.clone(),
+                tooltip: tooltip.clone(),
+            },
+            segment => segment.clone(),
+        })
+        .collect()
+}
+
+/// A piece of rich text compared as a unit: a word or a run of whitespace of a text segment, or a
+/// whole segment of another kind.
+enum Token {
+    Word {
+        text: String,
+        styles: Vec<TextStyle>,
+    },
+    Other(RichTextSegment),
+}
+
+impl Token {
+    fn key(&self) -> Value {
+        match self {
+            Token::Word { text, styles } => to_json(&(text, styles)),
+            Token::Other(segment) => to_json(segment),
+        }
+    }
+}
+
+fn tokens(segments: &[RichTextSegment]) -> Vec<Token> {
+    let mut tokens = vec![];
+    for segment in segments {
+        let (text, styles) = match segment {
+            RichTextSegment::Text(text) => (text, vec![]),
+            RichTextSegment::StyledText { text, styles } => (text, styles.clone()),
+            segment => {
+                tokens.push(Token::Other(segment.clone()));
+                continue;
+            }
+        };
+        let mut start = 0;
+        for (index, c) in text.char_indices().skip(1) {
+            let previous = text[..index].chars().next_back();
+            if previous.is_some_and(|previous| previous.is_whitespace() != c.is_whitespace()) {
+                tokens.push(Token::Word {
+                    text: text[start..index].to_string(),
+                    styles: styles.clone(),
+                });
+                start = index;
+            }
+        }
+        if start < text.len() {
+            tokens.push(Token::Word {
+                text: text[start..].to_string(),
+                styles,
+            });
+        }
+    }
+    tokens
+}
+
+/// The new segments with the words deleted since `old` put back struck through and the inserted
+/// ones underlined, or `None` when a segment that cannot be styled changed.
+fn diff_segments(old: &[RichTextSegment], new: &[RichTextSegment]) -> Option<Vec<RichTextSegment>> {
+    let (olds, news) = (tokens(old), tokens(new));
+    let old_keys: Vec<Value> = olds.iter().map(Token::key).collect();
+    let new_keys: Vec<Value> = news.iter().map(Token::key).collect();
+    // lengths[i][j]: longest common subsequence of olds[i..] and news[j..]
+    let mut lengths = vec![vec![0usize; news.len() + 1]; olds.len() + 1];
+    for i in (0..olds.len()).rev() {
+        for j in (0..news.len()).rev() {
+            lengths[i][j] = if old_keys[i] == new_keys[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let mut segments: Vec<RichTextSegment> = vec![];
+    let (mut i, mut j) = (0, 0);
+    while i < olds.len() || j < news.len() {
+        let (token, added) = if i < olds.len() && j < news.len() && old_keys[i] == new_keys[j] {
+            i += 1;
+            j += 1;
+            (&news[j - 1], None)
+        } else if j == news.len() || (i < olds.len() && lengths[i + 1][j] >= lengths[i][j + 1]) {
+            i += 1;
+            (&olds[i - 1], Some(deleted_styles()))
+        } else {
+            j += 1;
+            (&news[j - 1], Some(inserted_styles()))
+        };
+        let segment = match (token, added) {
+            (Token::Other(_), Some(_)) => return None,
+            (Token::Other(segment), None) => segment.clone(),
+            (Token::Word { text, styles }, added) => {
+                let styles: Vec<TextStyle> = styles
+                    .iter()
+                    .chain(added.iter().flatten())
+                    .cloned()
+                    .collect();
+                if styles.is_empty() {
+                    RichTextSegment::Text(text.clone())
+                } else {
+                    RichTextSegment::StyledText {
+                        text: text.clone(),
+                        styles,
+                    }
+                }
+            }
+        };
+        push_merged(&mut segments, segment);
+    }
+    Some(segments)
+}
+
+/// Appends the segment, joining it to the previous one if both are text with the same styles.
+fn push_merged(segments: &mut Vec<RichTextSegment>, segment: RichTextSegment) {
+    match (segments.last_mut(), segment) {
+        (Some(RichTextSegment::Text(last)), RichTextSegment::Text(text)) => last.push_str(&text),
+        (
+            Some(RichTextSegment::StyledText {
+                text: last,
+                styles: last_styles,
+            }),
+            RichTextSegment::StyledText { text, styles },
+        ) if *last_styles == styles => last.push_str(&text),
+        (_, segment) => segments.push(segment),
+    }
+}
+
+fn to_json(value: &impl Serialize) -> Value {
+    serde_json::to_value(value).unwrap_or_default()
+}
@@ -0,0 +1,132 @@
+use super::*;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use ts_rs::TS;
+
+/// The recorded edits of a document, oldest first. Revision `n` is the document after the first
+/// `n` edits, so revision 0 is the document as it was before the first one and the document
+/// itself is revision `revisions.len()`. Each edit stores the patch undoing it, so the current
+/// document stays cheap to load and older revisions are rebuilt from it.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct RevisionHistory {
+    pub revisions: Vec<Revision>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct Revision {
+    pub author: Option<String>,
+    pub timestamp: String, // ISO 8601, as in `ContentMetadata`
+    pub summary: Option<String>,
+    /// Turns this revision back into the one before it.
+    pub undo: DocumentPatch,
+}
+
+impl MathDocument {
+    /// The number of the current revision: how many edits the history records.
+    pub fn current_revision(&self) -> usize {
+        self.revision_history
+            .as_ref()
+            .map_or(0, |history| history.revisions.len())
+    }
+
+    /// Applies `patch` as a new revision, see `apply`, and records it with its author, time and
+    /// summary. Returns the number of the new revision.
+    pub fn apply_revision(
+        &mut self,
+        patch: &DocumentPatch,
+        author: Option<String>,
+        timestamp: String,
+        summary: Option<String>,
+    ) -> Result<usize, PatchError> {
+        let undo = self.apply(patch)?;
+        let history = self.revision_history.get_or_insert_with(Default::default);
+        history.revisions.push(Revision {
+            author,
+            timestamp,
+            summary,
+            undo,
+        });
+        Ok(history.revisions.len())
+    }
+
+    /// Records the change from this document to `revised` as a new revision, see
+    /// `apply_revision`. The history of `revised` is ignored.
+    pub fn revise(
+        &mut self,
+        revised: &MathDocument,
+        author: Option<String>,
+        timestamp: String,
+        summary: Option<String>,
+    ) -> Result<usize, PatchError> {
+        self.apply_revision(&self.diff(revised), author, timestamp, summary)
+    }
+
+    /// The document as it was at revision `number`, with the history up to that revision.
+    pub fn revision(&self, number: usize) -> Result<MathDocument, RevisionError> {
+        let current = self.current_revision();
+        if number > current {
+            return Err(RevisionError::UnknownRevision { number, current });
+        }
+        let mut document = self.clone();
+        for revision in (number + 1..=current).rev() {
+            document.undo_revision(revision)?;
+        }
+        Ok(document)
+    }
+
+    /// The patch turning revision `number - 1` into revision `number`: what that edit changed.
+    pub fn revision_changes(&self, number: usize) -> Result<DocumentPatch, RevisionError> {
+        if number == 0 {
+            return Err(RevisionError::UnknownRevision {
+                number,
+                current: self.current_revision(),
+            });
+        }
+        let mut document = self.revision(number)?;
+        document.undo_revision(number)
+    }
+
+    /// Turns the document, at revision `number`, back into the revision before it, returning
+    /// the patch redoing the change.
+    fn undo_revision(&mut self, number: usize) -> Result<DocumentPatch, RevisionError> {
+        let revision = self
+            .revision_history
+            .as_mut()
+            .and_then(|history| history.revisions.pop())
+            .ok_or(RevisionError::UnknownRevision { number, current: 0 })?;
+        self.apply(&revision.undo)
+            .map_err(|error| RevisionError::CorruptRevision { number, error })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RevisionError {
+    UnknownRevision {
+        number: usize,
+        current: usize,
+    },
+    /// The undo patch of revision `number` does not apply to it.
+    CorruptRevision {
+        number: usize,
+        error: PatchError,
+    },
+}
+
+impl fmt::Display for RevisionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RevisionError::UnknownRevision { number, current } => write!(
+                f,
+                "revision {} does not exist; the current revision is {}",
+                number, current
+            ),
+            RevisionError::CorruptRevision { number, error } => {
+                write!(f, "revision {} cannot be undone: {}", number, error)
+            }
+        }
+    }
+}
+
+impl std::error::Error for RevisionError {}
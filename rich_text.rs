@@ -1,6 +1,5 @@
 use super::*;
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
 use ts_rs::TS;
 
 // --- Core Building Blocks for Rich Text ---
@@ -50,7 +49,9 @@ pub enum TextStyle {
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[ts(export)]
 pub struct RichText {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub segments: Vec<RichTextSegment>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub alignment: Option<TextAlignment>,
 }
 
@@ -80,16 +81,17 @@ pub enum LinkTarget {
     InternalPageId(String), // ID of another MathematicalContent or Section within the system
     DefinitionId {
         // Link to a specific defined term or MathNode concept
-        term_id: String,                // Unique ID of the definition or concept
-        theory_context: Option<String>, // e.g., "ZFC", "GroupTheory"
+        term_id: String,                  // Unique ID of the definition or concept
+        theory_context: Option<TheoryId>, // e.g., "ZFC", "GroupTheory"
     },
     DefinitionAspect {
         // Link to a specific aspect/property of a definition
         term_id: String,   // ID of the main definition (e.g., an L2 group instance)
         aspect_id: String, // Identifier for the property (e.g., "Order", "Commutativity")
-        theory_context: Option<String>,
+        theory_context: Option<TheoryId>,
     },
     TheoremId(String), // Link to a specific Theorem, Lemma, etc.
+    Equation(String),  // ID of an `Equation`, shown as its label, e.g. "(3.2)"
     ObjectConstructorTemplate {
         // A page/section acting as a template for creating math objects
         template_id: String, // ID of the page/section that is the template
@@ -98,10 +100,10 @@ pub enum LinkTarget {
         /// Indicates the desired abstraction level (L1-L4) for the constructed object.
         target_abstraction_level: Option<u8>,
     },
-    GlossaryTerm(String),               // Link to a term in a glossary
-    BibliographyKey(String),            // Link to a bibliography entry
+    GlossaryTerm(String),         // Link to a term in a glossary
+    BibliographyKey(String),      // Link to a bibliography entry
     InteractiveElementId(String), // Link to trigger/focus an interactive component on the page
-    TooltipDocument(Arc<MathDocument>), // NEW: Embedded tooltip document
+    TooltipDocument(#[ts(as = "MathDocument")] DocumentHandle), // NEW: Embedded tooltip document
     AnimationTrigger {
         // NEW: Trigger for animations
         animation_id: String,
@@ -0,0 +1,202 @@
+use super::*;
+use serde::{Deserialize, Serialize};
+use std::any::TypeId;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+use std::path::PathBuf;
+use ts_rs::{TS, TypeVisitor};
+
+/// Semantic version of the exported TypeScript types. Bump the major version when a type
+/// changes or disappears, the minor version when types are only added, and the patch version
+/// for anything else that should be picked up by consumers.
+pub const SCHEMA_VERSION: &str = "20.0.0";
+
+/// Keys of the adjacently tagged enums, `MathDocumentType`, `MathNodeContent` and
+/// `SectionContentNode`: `{ "kind": "Tag", "data": ... }`, or `{ "kind": "Tag" }` for a unit
+/// variant, which TypeScript narrows on `kind`. Other enums are externally tagged.
+pub const ENUM_TAG_KEY: &str = "kind";
+pub const ENUM_CONTENT_KEY: &str = "data";
+
+/// The exported TypeScript types with a hash of each declaration, written next to the
+/// bindings as `schema_manifest.json`. A frontend ships the manifest its bindings came with and
+/// compares it with the backend's `SchemaManifest::current()` at startup, rather than failing
+/// somewhere down the line on data of an unexpected shape.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct SchemaManifest {
+    pub version: String,
+    pub types: BTreeMap<String, String>, // type name -> `content_hash` of its declaration
+}
+
+/// A difference between the manifest a consumer expects and the current one.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SchemaMismatch {
+    /// Different major versions, or a minor version newer than the current one.
+    Version {
+        expected: String,
+        current: String,
+    },
+    MissingType {
+        name: String,
+    },
+    ChangedType {
+        name: String,
+    },
+}
+
+impl SchemaManifest {
+    /// The manifest of the types in this build, those of `declarations`.
+    pub fn current() -> SchemaManifest {
+        SchemaManifest {
+            version: SCHEMA_VERSION.to_string(),
+            types: SchemaManifest::declarations()
+                .into_iter()
+                .map(|(name, declaration)| {
+                    let hash = content_hash(&declaration).expect("a string always serializes");
+                    (name, hash)
+                })
+                .collect(),
+        }
+    }
+
+    /// The TypeScript declaration of every exported type, by type name.
+    pub fn declarations() -> BTreeMap<String, String> {
+        let mut collector = TypeCollector::default();
+        SchemaManifest::visit_exported_types(&mut collector);
+        collector.declarations
+    }
+
+    /// Visits the exported types reachable from the documents, patches, collaborative
+    /// operations, diagnostics, the manifest itself and the other standalone types listed here;
+    /// the visitor follows their dependencies itself. New standalone types must be added to the
+    /// list.
+    pub fn visit_exported_types(visitor: &mut impl TypeVisitor) {
+        visitor.visit::<MathDocument>();
+        visitor.visit::<DocumentPatch>();
+        visitor.visit::<CollabOperation>();
+        visitor.visit::<Diagnostic>();
+        visitor.visit::<SchemaManifest>();
+        // Exported types no document refers to
+        visitor.visit::<AbstractionMetadata>();
+        visitor.visit::<AutoFoldOptions>();
+        visitor.visit::<BacklinkIndex>();
+        visitor.visit::<BaseUnitTypeNode>();
+        visitor.visit::<BindingType>();
+        visitor.visit::<CapabilityManifest>();
+        visitor.visit::<ComponentRegistry>();
+        visitor.visit::<FeedbackResponse>();
+        visitor.visit::<InequalityType>();
+        visitor.visit::<IntegralDomain>();
+        visitor.visit::<IntegralType>();
+        visitor.visit::<LmsPackage>();
+        visitor.visit::<MathMetrics>();
+        visitor.visit::<NumberType>();
+        visitor.visit::<OperationType>();
+        visitor.visit::<QuantifiedObject>();
+        visitor.visit::<QuantifierGroup>();
+        visitor.visit::<SectionMetrics>();
+        visitor.visit::<SelectableProperty>();
+        visitor.visit::<SetType>();
+        visitor.visit::<TheoremLikeKind>();
+        visitor.visit::<TheoryEntry>();
+        visitor.visit::<TransformationFlow>();
+        visitor.visit::<TurnTextLineNode>();
+        visitor.visit::<UnitComponent>();
+        visitor.visit::<VariableBinding>();
+        visitor.visit::<VariantAssignment>();
+    }
+
+    /// What keeps a consumer built against `expected` from reading and writing data of this
+    /// version: an incompatible version, or a type it uses that is gone or declared differently.
+    /// Types added since are fine.
+    pub fn check_compatible(&self, expected: &SchemaManifest) -> Vec<SchemaMismatch> {
+        let mut mismatches = vec![];
+        let compatible_version = match (
+            parse_version(&expected.version),
+            parse_version(&self.version),
+        ) {
+            (Some(expected), Some(current)) => expected.0 == current.0 && expected.1 <= current.1,
+            _ => false,
+        };
+        if !compatible_version {
+            mismatches.push(SchemaMismatch::Version {
+                expected: expected.version.clone(),
+                current: self.version.clone(),
+            });
+        }
+        for (name, hash) in &expected.types {
+            match self.types.get(name) {
+                None => mismatches.push(SchemaMismatch::MissingType { name: name.clone() }),
+                Some(current) if current != hash => {
+                    mismatches.push(SchemaMismatch::ChangedType { name: name.clone() })
+                }
+                Some(_) => {}
+            }
+        }
+        mismatches
+    }
+
+    pub fn is_compatible(&self, expected: &SchemaManifest) -> bool {
+        self.check_compatible(expected).is_empty()
+    }
+
+    /// Writes the current manifest as `schema_manifest.json` to the directory the bindings are
+    /// exported to: `TS_RS_EXPORT_DIR`, or `./bindings`.
+    pub fn export() -> std::io::Result<PathBuf> {
+        let directory = std::env::var_os("TS_RS_EXPORT_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from("./bindings"));
+        std::fs::create_dir_all(&directory)?;
+        let path = directory.join("schema_manifest.json");
+        let json = serde_json::to_string_pretty(&SchemaManifest::current())?;
+        std::fs::write(&path, json + "\n")?;
+        Ok(path)
+    }
+}
+
+/// `MAJOR.MINOR.PATCH`, ignoring pre-release and build suffixes.
+fn parse_version(version: &str) -> Option<(u32, u32, u32)> {
+    let core = version.split(['-', '+']).next()?;
+    let mut parts = core.split('.').map(|part| part.parse().ok());
+    let version = (parts.next()??, parts.next()??, parts.next()??);
+    parts.next().is_none().then_some(version)
+}
+
+#[derive(Default)]
+struct TypeCollector {
+    seen: HashSet<TypeId>,
+    declarations: BTreeMap<String, String>,
+}
+
+impl TypeVisitor for TypeCollector {
+    fn visit<T: TS + 'static + ?Sized>(&mut self) {
+        if !self.seen.insert(TypeId::of::<T>()) {
+            return;
+        }
+        // Only exported types have a declaration; others, like `Vec<T>`, are looked through.
+        if T::output_path().is_some() {
+            self.declarations.insert(T::ident(), T::decl());
+        }
+        T::visit_dependencies(self);
+    }
+}
+
+impl fmt::Display for SchemaMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SchemaMismatch::Version { expected, current } => write!(
+                f,
+                "schema version {} is not compatible with the expected {}",
+                current, expected
+            ),
+            SchemaMismatch::MissingType { name } => {
+                write!(f, "type `{}` no longer exists", name)
+            }
+            SchemaMismatch::ChangedType { name } => {
+                write!(f, "type `{}` has a different declaration", name)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SchemaMismatch {}
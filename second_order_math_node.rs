@@ -327,7 +327,7 @@ pub struct InteractiveExpression {
     pub expression: MathNode,
     pub position: ExpressionPosition,
     pub interaction_type: ProofExpressionInteractionType,
-    pub metadata: std::collections::HashMap<String, String>,
+    pub metadata: std::collections::BTreeMap<String, String>,
 }
 
 /// Position of an expression within a proof node
@@ -373,7 +373,7 @@ pub struct PatternMatch {
     pub source_expression: String,
     pub matched_expression: String,
     pub confidence: f64,
-    pub substitution_map: std::collections::HashMap<String, String>,
+    pub substitution_map: std::collections::BTreeMap<String, String>,
 }
 
 /// Represents an instantiation map for variable substitution
@@ -429,7 +429,7 @@ pub enum InteractiveElementType {
 pub struct InteractionHandler {
     pub handler_type: HandlerType,
     pub action: String,
-    pub parameters: std::collections::HashMap<String, String>,
+    pub parameters: std::collections::BTreeMap<String, String>,
 }
 
 /// Types of interaction handlers
@@ -1,9 +1,6 @@
 use super::*;
 use serde::{Deserialize, Serialize};
-use std::{
-    collections::{BTreeMap, HashMap},
-    sync::Arc,
-};
+use std::collections::{BTreeMap, HashMap};
 use ts_rs::TS;
 
 /// Trait for converting mathematical objects into rich SectionNode structures.
@@ -94,6 +91,9 @@ pub trait ToSectionNode {
                     dependency_graph: None,
                 },
             }),
+            variable_context: None,
+            revision_history: None,
+            quantity_format: None,
         }
     }
 }
@@ -105,6 +105,7 @@ pub trait ToSectionNode {
 /// Enum representing the different types of content blocks that can appear in a section.
 /// This is the primary building block for document content.
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(tag = "kind", content = "data")]
 #[ts(export)]
 pub enum SectionContentNode {
     // New variant for subsections
@@ -138,7 +139,7 @@ pub enum SectionContentNode {
     // Placeholder for more complex or custom components
     CustomComponent {
         component_name: String, // Identifier for a specific React/WASM component
-        props: Option<String>,  // Properties to pass to the component
+        props: Option<ComponentProps>, // Properties to pass to the component
         fallback_content: Vec<SectionContentNode>, // Content to show if component fails
     },
     // Embeds another section, useful for transclusion or master documents.
@@ -149,10 +150,22 @@ pub enum SectionContentNode {
     PanelLayout(PanelLayout),           // For resource panels, multi-panel displays
     AnnotationOverlay(AnnotationOverlay), // For type mappings, explanatory overlays
     InteractiveControls(InteractiveControls), // For playgrounds with parameter controls
-    EmbeddedDocument(Arc<MathDocument>), // For nested documents, tooltips
+    EmbeddedDocument(#[ts(as = "MathDocument")] DocumentHandle), // For nested documents, tooltips
 
     // NEW: Abstract branching container for any hierarchical structure
     BranchingContainer(BranchingContainer), // For ProofForest, storyboards, multiverse, etc.
+
+    // Reader feedback prompt ("Was this proof clear?") with a typed response schema
+    Feedback(FeedbackNode),
+
+    // Inline error left by a generation pipeline in place of content it failed to produce
+    GenerationError(GenerationErrorNode),
+
+    // Worked solution written as a chain of related expressions with a reason for each step
+    DerivationChain(DerivationChain),
+
+    // Display math with an equation number or tag that prose can refer to
+    Equation(Equation),
 }
 
 // --- NEW: Abstract Hierarchical Container ---
@@ -164,8 +177,11 @@ pub enum SectionContentNode {
 pub struct BranchingContainer {
     pub container_id: String,
     pub container_type: ContainerType,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub nodes: Vec<BranchingNode>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub layout_config: Option<ContainerLayout>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub container_metadata: Vec<(String, String)>,
 }
 
@@ -188,10 +204,14 @@ pub enum ContainerType {
 #[ts(export)]
 pub struct BranchingNode {
     pub node_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub parent_id: Option<String>, // None for root nodes
     pub node_type: NodeType,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub content: Vec<SectionContentNode>, // Rich content for this node
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub node_metadata: Vec<(String, String)>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub children: Vec<String>, // IDs of child nodes
     pub node_state: NodeState,
 }
@@ -240,9 +260,13 @@ pub enum NodeState {
 pub struct ContainerLayout {
     pub layout_type: LayoutType,
     pub direction: LayoutDirection,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub spacing: Option<String>, // CSS spacing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub alignment: Option<LayoutAlignment>,
-    pub max_depth: Option<usize>,        // Maximum depth to show
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_depth: Option<usize>, // Maximum depth to show
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub collapse_branches: Option<bool>, // Auto-collapse long branches
 }
 
@@ -368,26 +392,39 @@ pub enum LayoutAlignment {
 pub struct SideBySideLayout {
     pub left_panel: Panel,
     pub right_panel: Panel,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub sync_scrolling: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub highlight_correspondence: Option<bool>, // For synchronized highlighting
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub correspondence_groups: Vec<CorrespondenceGroup>, // Which nodes highlight together
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub layout_config: Option<SideBySideConfig>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export)]
 pub struct SideBySideConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub left_width: Option<String>, // e.g., "50%", "300px"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub right_width: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub gap: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub responsive_breakpoint: Option<String>, // Switch to vertical on small screens
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[ts(export)]
 pub struct PanelLayout {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub panels: Vec<Panel>,
     pub layout_type: PanelLayoutType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub panel_controls: Option<PanelControls>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_rendering_hints: Option<PanelRenderingHints>, // Applies to panels without their own hints
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
@@ -404,11 +441,17 @@ pub enum PanelLayoutType {
 #[ts(export)]
 pub struct Panel {
     pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub title: Option<RichText>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub content: Vec<SectionContentNode>,
     pub panel_role: PanelRole,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub initially_visible: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub resizable: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rendering_hints: Option<PanelRenderingHints>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
@@ -429,15 +472,20 @@ pub enum PanelRole {
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export)]
 pub struct PanelControls {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub allow_minimize: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub allow_close: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub allow_reorder: Option<bool>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[ts(export)]
 pub struct AnnotationOverlay {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub base_content: Vec<SectionContentNode>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub annotations: Vec<Annotation>,
     pub overlay_style: OverlayStyle,
 }
@@ -447,12 +495,29 @@ pub struct AnnotationOverlay {
 pub struct Annotation {
     pub id: String,
     pub target_selector: String, // CSS selector or element ID to target
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text_anchor: Option<TextQuoteAnchor>, // Quoted text within the target, for highlights and review comments
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub annotation_content: Vec<RichTextSegment>,
     pub annotation_type: AnnotationType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub position: Option<AnnotationPosition>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub styling: Option<AnnotationStyling>,
 }
 
+/// A passage of text identified by its exact wording, with some surrounding text to tell
+/// repeated occurrences apart.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct TextQuoteAnchor {
+    pub exact: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suffix: Option<String>,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export)]
 pub enum AnnotationType {
@@ -496,16 +561,22 @@ pub enum PositionAnchor {
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export)]
 pub struct AnnotationStyling {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub color: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub background_color: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub border_color: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub opacity: Option<f64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[ts(export)]
 pub struct InteractiveControls {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub controls: Vec<Control>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub target_content_ids: Vec<String>, // IDs of content that these controls affect
     pub layout: ControlLayout,
 }
@@ -524,9 +595,12 @@ pub enum ControlLayout {
 #[ts(export)]
 pub struct Control {
     pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub label: Option<RichText>,
     pub control_type: ControlType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub default_value: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub validation_rules: Vec<String>,
 }
 
@@ -549,12 +623,16 @@ pub enum ControlType {
 #[ts(export)]
 pub struct AbstractionMetadata {
     /// Abstraction level (L1-L4) as per theory_and_render.md
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub level: Option<u8>,
     /// Link to the L1/L2 blueprint or source template for this object/definition.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub source_template_id: Option<String>,
     /// For L2/L3/L4, parameters that have been specified or concretized.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub specified_parameters: Vec<(String, MathNode)>,
     /// For L2, properties that are universally quantified (or "any valid option").
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub universally_quantified_properties: Vec<String>,
 }
 
@@ -563,8 +641,10 @@ pub struct AbstractionMetadata {
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[ts(export)]
 pub struct ListNode {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub items: Vec<ListItemNode>,
     pub style: ListStyle,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub start_index: Option<i32>, // For ordered lists
 }
 
@@ -572,6 +652,7 @@ pub struct ListNode {
 #[ts(export)]
 pub struct ListItemNode {
     /// Content of a list item can be complex, allowing nested structures.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub content: Vec<SectionContentNode>,
 }
 
@@ -604,17 +685,24 @@ pub enum OrderedListStyle {
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[ts(export)]
 pub struct TableNode {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub caption: Option<RichText>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub header_rows: Vec<TableRowNode>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub body_rows: Vec<TableRowNode>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub footer_rows: Vec<TableRowNode>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub column_styles: Vec<ColumnStyle>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub table_style_options: Option<TableStyleOptions>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[ts(export)]
 pub struct TableRowNode {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub cells: Vec<TableCellNode>,
     // pub style: Option<RowStyle>,
 }
@@ -622,10 +710,14 @@ pub struct TableRowNode {
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[ts(export)]
 pub struct TableCellNode {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub content: Vec<SectionContentNode>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub col_span: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub row_span: Option<usize>,
     pub cell_type: TableCellType,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub alignment: Option<TextAlignment>,
     // pub style: Option<CellStyle>,
 }
@@ -640,7 +732,9 @@ pub enum TableCellType {
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export)]
 pub struct ColumnStyle {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub width: Option<String>, // e.g., "20%", "100px"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub alignment: Option<TextAlignment>,
     // Add other column-specific styles
 }
@@ -648,7 +742,9 @@ pub struct ColumnStyle {
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export)]
 pub struct TableStyleOptions {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub borders: Option<bool>, // Show all borders
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub striped_rows: Option<bool>,
     // Add other table-wide styles
 }
@@ -657,10 +753,15 @@ pub struct TableStyleOptions {
 #[ts(export)]
 pub struct CodeBlockNode {
     pub code: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub language: Option<String>, // e.g., "rust", "python", "latex", "lean", "plaintext"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub caption: Option<RichText>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub show_line_numbers: Option<bool>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub highlight_lines: Vec<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub is_executable: Option<bool>, // For interactive code blocks
 }
 
@@ -668,10 +769,15 @@ pub struct CodeBlockNode {
 #[ts(export)]
 pub struct ImageNode {
     pub src: String, // URL or path
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub alt_text: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub caption: Option<RichText>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub width: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub height: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub alignment: Option<HorizontalAlignment>,
 }
 
@@ -688,25 +794,33 @@ pub enum HorizontalAlignment {
 pub struct InteractiveDiagramNode {
     pub diagram_type_id: String, // Identifier for the type of diagram (e.g., "commutative_diagram", "function_plot")
     pub data: String,            // Diagram-specific data
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub caption: Option<RichText>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub config_options: Option<String>, // UI options for the diagram
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[ts(export)]
 pub struct CollapsibleBlockNode {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub summary: Vec<RichTextSegment>, // The visible part when collapsed (clickable)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub details: Vec<SectionContentNode>, // The content shown when expanded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub initially_collapsed: Option<bool>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[ts(export)]
 pub struct GridNode {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub items: Vec<GridItemNode>,
     /// Number of columns, or CSS grid-template-columns string.
     pub column_template: String, // e.g., "3" for 3 equal columns, or "1fr 2fr"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub row_gap: Option<String>, // e.g., "10px"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub column_gap: Option<String>,
 }
 
@@ -714,17 +828,24 @@ pub struct GridNode {
 #[ts(export)]
 pub struct GridItemNode {
     pub content: SectionContentNode,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub col_start: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub col_end: Option<usize>, // Or col_span
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub row_start: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub row_end: Option<usize>, // Or row_span
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[ts(export)]
 pub struct ColumnsNode {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub columns_content: Vec<Vec<SectionContentNode>>, // Each inner Vec is a column
-    pub column_widths: Vec<String>,                    // e.g., ["30%", "70%"] or ["1fr", "2fr"]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub column_widths: Vec<String>, // e.g., ["30%", "70%"] or ["1fr", "2fr"]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub gap: Option<String>,
 }
 
@@ -751,16 +872,24 @@ pub enum AlertBoxStyle {
 #[ts(export)]
 pub struct Section {
     // Renamed from SectionNode to avoid confusion with enum SectionContentNode
-    pub id: String,              // Unique ID for linking, navigation, and referencing
+    pub id: String, // Unique ID for linking, navigation, and referencing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub title: Option<RichText>, // The title of the section
     pub content: SectionContentNode, // Ordered list of content blocks within this section
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub metadata: Vec<(String, String)>, // For tags, abstraction level, visibility, etc.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub display_options: Option<SectionDisplayOptions>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub difficulty: Option<DifficultyAnnotation>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variant_seed: Option<VariantSeed>, // Randomized parameters, see `instantiate_variants`
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
 #[ts(export)]
 pub struct SectionDisplayOptions {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub show_title_numbering: Option<bool>,
     // Add other display-related options
 }
@@ -771,9 +900,13 @@ pub struct SectionDisplayOptions {
 pub struct SelectableProperty {
     pub name: String,
     pub current_variant: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub all_variants: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub variant_descriptions: Option<BTreeMap<String, String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub property_type_def_id: Option<String>,
 }
 
@@ -786,7 +919,9 @@ pub struct QuantifiedObject {
     pub variable: String,
     pub quantification: QuantifierType,
     pub object_type: String, // Could be expanded to a full type system
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub constraints: Vec<MathNode>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
 }
 
@@ -0,0 +1,243 @@
+use super::*;
+use std::{collections::HashMap, fmt};
+
+/// Resolves `SectionContentNode::EmbeddedSectionRef` ids against the sections of a collection of
+/// documents. When several sections share an id, the first one found wins.
+#[derive(Debug, Clone, Default)]
+pub struct SectionResolver<'doc> {
+    sections: HashMap<&'doc str, &'doc Section>,
+}
+
+impl<'doc> SectionResolver<'doc> {
+    pub fn new(documents: impl IntoIterator<Item = &'doc MathDocument>) -> Self {
+        let mut resolver = SectionResolver::default();
+        for document in documents {
+            resolver.add_document(document);
+        }
+        resolver
+    }
+
+    /// Makes every section of `document`, including nested and embedded ones, resolvable.
+    pub fn add_document(&mut self, document: &'doc MathDocument) {
+        let mut collector = SectionCollector::default();
+        collector.visit_document(document);
+        for section in collector.sections {
+            self.sections.entry(&section.id).or_insert(section);
+        }
+    }
+
+    pub fn get(&self, section_id: &str) -> Option<&'doc Section> {
+        self.sections.get(section_id).copied()
+    }
+
+    /// A copy of the section `section_id` with every embedded reference inside it replaced by the
+    /// section it names, recursively.
+    pub fn resolve(&self, section_id: &str) -> Result<Section, SectionRefError> {
+        self.resolve_with_stack(section_id, &mut vec![])
+    }
+
+    /// Inlines the embedded references in `document` in place, each as a `SubSection` holding the
+    /// resolved section. References that cannot be resolved are left as they are and reported.
+    pub fn inline_refs(&self, document: &mut MathDocument) -> Vec<SectionRefError> {
+        let mut inliner = RefInliner {
+            resolver: self,
+            errors: vec![],
+        };
+        inliner.visit_document_mut(document);
+        inliner.errors
+    }
+
+    fn resolve_with_stack(
+        &self,
+        section_id: &str,
+        stack: &mut Vec<String>,
+    ) -> Result<Section, SectionRefError> {
+        if let Some(start) = stack.iter().position(|id| id == section_id) {
+            let mut cycle = stack[start..].to_vec();
+            cycle.push(section_id.to_string());
+            return Err(SectionRefError::Cycle { cycle });
+        }
+        let section = self
+            .get(section_id)
+            .ok_or_else(|| SectionRefError::Unresolved {
+                section_id: section_id.to_string(),
+            })?;
+
+        stack.push(section_id.to_string());
+        let mut resolved = section.clone();
+        let mut inliner = StackInliner {
+            resolver: self,
+            stack,
+            error: None,
+        };
+        inliner.visit_section_mut(&mut resolved);
+        let error = inliner.error;
+        stack.pop();
+        match error {
+            Some(error) => Err(error),
+            None => Ok(resolved),
+        }
+    }
+}
+
+impl MathDocument {
+    /// The section with id `section_id`, searching nested and embedded sections too.
+    pub fn find_section(&self, section_id: &str) -> Option<&Section> {
+        let mut collector = SectionCollector::default();
+        collector.visit_document(self);
+        collector
+            .sections
+            .into_iter()
+            .find(|section| section.id == section_id)
+    }
+
+    /// A standalone copy of the section or panel with id `id`, with its embedded references
+    /// resolved within this document. Panels become a section with the panel's title and content.
+    pub fn extract_section(&self, id: &str) -> Result<Section, SectionRefError> {
+        let resolver = SectionResolver::new([self]);
+        let mut extracted = match self.find_section(id) {
+            Some(_) => resolver.resolve(id)?,
+            None => {
+                let mut finder = PanelFinder { id, found: None };
+                finder.visit_document(self);
+                let panel = finder.found.ok_or_else(|| SectionRefError::Unresolved {
+                    section_id: id.to_string(),
+                })?;
+                let mut section = Section {
+                    id: panel.id.clone(),
+                    title: panel.title.clone(),
+                    content: wrap_content(panel.content.clone()),
+                    metadata: vec![],
+                    display_options: None,
+                    difficulty: None,
+                    variant_seed: None,
+                };
+                let mut inliner = RefInliner {
+                    resolver: &resolver,
+                    errors: vec![],
+                };
+                inliner.visit_section_mut(&mut section);
+                if let Some(error) = inliner.errors.into_iter().next() {
+                    return Err(error);
+                }
+                section
+            }
+        };
+        extracted
+            .metadata
+            .push(("extracted_from".to_string(), self.id.clone()));
+        Ok(extracted)
+    }
+}
+
+/// A single content node holding `nodes`: the node itself when there is one, otherwise an
+/// untitled subsection per node.
+fn wrap_content(mut nodes: Vec<SectionContentNode>) -> SectionContentNode {
+    if nodes.len() == 1 {
+        return nodes.remove(0);
+    }
+    SectionContentNode::SubSection(
+        nodes
+            .into_iter()
+            .enumerate()
+            .map(|(i, content)| Section {
+                id: format!("part-{}", i),
+                title: None,
+                content,
+                metadata: vec![],
+                display_options: None,
+                difficulty: None,
+                variant_seed: None,
+            })
+            .collect(),
+    )
+}
+
+#[derive(Default)]
+struct SectionCollector<'doc> {
+    sections: Vec<&'doc Section>,
+}
+
+impl<'doc> DocumentVisitor<'doc> for SectionCollector<'doc> {
+    fn visit_section(&mut self, section: &'doc Section) {
+        self.sections.push(section);
+        walk_section(self, section);
+    }
+}
+
+struct PanelFinder<'a, 'doc> {
+    id: &'a str,
+    found: Option<&'doc Panel>,
+}
+
+impl<'doc> DocumentVisitor<'doc> for PanelFinder<'_, 'doc> {
+    fn visit_panel(&mut self, panel: &'doc Panel) {
+        if self.found.is_none() && panel.id == self.id {
+            self.found = Some(panel);
+        }
+        walk_panel(self, panel);
+    }
+}
+
+/// Inlines references below a section being resolved, failing on the first error.
+struct StackInliner<'r, 'doc> {
+    resolver: &'r SectionResolver<'doc>,
+    stack: &'r mut Vec<String>,
+    error: Option<SectionRefError>,
+}
+
+impl DocumentVisitorMut for StackInliner<'_, '_> {
+    fn visit_content_node_mut(&mut self, node: &mut SectionContentNode) {
+        if self.error.is_some() {
+            return;
+        }
+        if let SectionContentNode::EmbeddedSectionRef(section_id) = node {
+            match self.resolver.resolve_with_stack(section_id, self.stack) {
+                Ok(section) => *node = SectionContentNode::SubSection(vec![section]),
+                Err(error) => self.error = Some(error),
+            }
+            return;
+        }
+        walk_content_node_mut(self, node);
+    }
+}
+
+/// Inlines every resolvable reference in a document, collecting the failures.
+struct RefInliner<'r, 'doc> {
+    resolver: &'r SectionResolver<'doc>,
+    errors: Vec<SectionRefError>,
+}
+
+impl DocumentVisitorMut for RefInliner<'_, '_> {
+    fn visit_content_node_mut(&mut self, node: &mut SectionContentNode) {
+        if let SectionContentNode::EmbeddedSectionRef(section_id) = node {
+            match self.resolver.resolve(section_id) {
+                Ok(section) => *node = SectionContentNode::SubSection(vec![section]),
+                Err(error) => self.errors.push(error),
+            }
+            return;
+        }
+        walk_content_node_mut(self, node);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SectionRefError {
+    Unresolved { section_id: String },
+    Cycle { cycle: Vec<String> }, // section ids, starting and ending with the same id
+}
+
+impl fmt::Display for SectionRefError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SectionRefError::Unresolved { section_id } => {
+                write!(f, "no section with id `{}`", section_id)
+            }
+            SectionRefError::Cycle { cycle } => {
+                write!(f, "embedded sections form a cycle: {}", cycle.join(" -> "))
+            }
+        }
+    }
+}
+
+impl std::error::Error for SectionRefError {}
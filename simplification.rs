@@ -0,0 +1,328 @@
+use super::*;
+use std::sync::Arc;
+
+/// The built-in rules of a `Simplifier`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum SimplificationRule {
+    MultiplyByOne,  // x·1 → x, 1·x → x, x/1 → x
+    AddZero,        // x+0 → x, 0+x → x, x-0 → x
+    PowerOfOne,     // a^1 → a
+    NestedFraction, // (a/b)/c → a/(b·c), a/(b/c) → (a·c)/b
+}
+
+impl SimplificationRule {
+    pub const ALL: [SimplificationRule; 4] = [
+        SimplificationRule::MultiplyByOne,
+        SimplificationRule::AddZero,
+        SimplificationRule::PowerOfOne,
+        SimplificationRule::NestedFraction,
+    ];
+
+    /// The rule as pattern rewrites, one for each way the expression can be written: with `×`
+    /// or `·`, as a `BinaryOperation` or a legacy `Additions`/`Multiplications`, and so on.
+    /// Only operations of two operands are matched.
+    pub fn rewrite_rules(self) -> Vec<RewriteRule> {
+        let x = || MathNode::wildcard("x");
+        let times = [BinaryOperator::Times, BinaryOperator::Dot];
+        let mul_symbols = [MulSymbol::Times, MulSymbol::Dot, MulSymbol::LittleSpace];
+        let div_symbols = [DivSymbol::Slash, DivSymbol::Divide];
+        let mut rules = vec![];
+        let mut rule = |pattern: MathNodeContent, replacement: MathNode| {
+            let pattern = node("pattern", pattern);
+            rules.push(
+                RewriteRule::new(pattern, replacement)
+                    .expect("built-in rules only use the wildcards they bind"),
+            );
+        };
+        match self {
+            SimplificationRule::MultiplyByOne => {
+                for first in &times {
+                    for second in times
+                        .iter()
+                        .chain(&[BinaryOperator::Slash, BinaryOperator::Divide])
+                    {
+                        rule(product(first, x(), second, number(1)), x());
+                    }
+                    for second in &times {
+                        rule(product(first, number(1), second, x()), x());
+                    }
+                }
+                for symbol in &mul_symbols {
+                    let operator = RefinedMulOrDivOperation::Multiplication(symbol.clone());
+                    rule(legacy_product(x(), operator.clone(), number(1)), x());
+                    rule(legacy_product(number(1), operator, x()), x());
+                }
+                for symbol in &div_symbols {
+                    let operator = RefinedMulOrDivOperation::Division(symbol.clone());
+                    rule(legacy_product(x(), operator, number(1)), x());
+                }
+                rule(fraction(x(), number(1)), x());
+                for style in [
+                    DivisionStyle::Fraction,
+                    DivisionStyle::Inline,
+                    DivisionStyle::Division,
+                ] {
+                    let division = MathNodeContent::Division {
+                        numerator: Arc::new(x()),
+                        denominator: Arc::new(number(1)),
+                        style,
+                    };
+                    rule(division, x());
+                }
+            }
+            SimplificationRule::AddZero => {
+                for second in [BinaryOperator::Plus, BinaryOperator::Minus] {
+                    rule(sum(BinaryOperator::Plus, x(), second, number(0)), x());
+                }
+                rule(
+                    sum(BinaryOperator::Plus, number(0), BinaryOperator::Plus, x()),
+                    x(),
+                );
+                for second in [
+                    RefinedAddOrSubOperator::Addition,
+                    RefinedAddOrSubOperator::Subtraction,
+                ] {
+                    rule(legacy_sum(x(), second, number(0)), x());
+                }
+                rule(
+                    legacy_sum(number(0), RefinedAddOrSubOperator::Addition, x()),
+                    x(),
+                );
+            }
+            SimplificationRule::PowerOfOne => {
+                let power = MathNodeContent::Power {
+                    base: Arc::new(x()),
+                    exponent: Arc::new(number(1)),
+                };
+                rule(power, x());
+            }
+            SimplificationRule::NestedFraction => {
+                let [a, b, c] = ["a", "b", "c"].map(MathNode::wildcard);
+                let times = |first: MathNode, second: MathNode| {
+                    let operator = BinaryOperator::Dot;
+                    node("product", product(&operator, first, &operator, second))
+                };
+                rule(
+                    fraction(node("inner", fraction(a.clone(), b.clone())), c.clone()),
+                    node("fraction", fraction(a.clone(), times(b.clone(), c.clone()))),
+                );
+                rule(
+                    fraction(a.clone(), node("inner", fraction(b.clone(), c.clone()))),
+                    node("fraction", fraction(times(a, c), b)),
+                );
+            }
+        }
+        rules
+    }
+}
+
+/// Opt-in clean-up of machine-generated expressions before rendering, by rewrite rules applied
+/// until none of them matches any more.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Simplifier {
+    pub rules: Vec<RewriteRule>,
+    pub max_passes: usize, // Guards against rules that rewrite each other's results forever
+}
+
+impl Default for Simplifier {
+    /// Every built-in rule.
+    fn default() -> Self {
+        Simplifier::new(&SimplificationRule::ALL)
+    }
+}
+
+impl Simplifier {
+    pub fn new(rules: &[SimplificationRule]) -> Self {
+        Simplifier {
+            rules: rules.iter().flat_map(|rule| rule.rewrite_rules()).collect(),
+            max_passes: 16,
+        }
+    }
+
+    /// Adds a custom rule, tried after the ones already added.
+    pub fn with_rule(mut self, rule: RewriteRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// The simplified expression and the number of rewrites made. Sums that a rewrite leaves as
+    /// an operand of a product are put in brackets.
+    pub fn simplify(&self, node: &MathNode) -> (MathNode, usize) {
+        let mut simplified = node.clone();
+        let mut total = 0;
+        for _ in 0..self.max_passes {
+            let (rewritten, count) = simplified.rewrite(&self.rules);
+            if count == 0 {
+                break;
+            }
+            simplified = rewritten;
+            total += count;
+        }
+        if total > 0 {
+            simplified = simplified.transform(&mut FactorBrackets);
+        }
+        (simplified, total)
+    }
+}
+
+impl MathNode {
+    /// This expression with every built-in simplification applied, see `Simplifier`.
+    pub fn simplify(&self) -> MathNode {
+        Simplifier::default().simplify(self).0
+    }
+}
+
+impl MathDocument {
+    /// Simplifies every expression in the document. Returns the number of rewrites.
+    pub fn simplify(&mut self, simplifier: &Simplifier) -> usize {
+        let mut document_simplifier = DocumentSimplifier {
+            simplifier,
+            count: 0,
+        };
+        document_simplifier.visit_document_mut(self);
+        document_simplifier.count
+    }
+}
+
+struct DocumentSimplifier<'a> {
+    simplifier: &'a Simplifier,
+    count: usize,
+}
+
+impl DocumentVisitorMut for DocumentSimplifier<'_> {
+    fn visit_math_node_mut(&mut self, node: &mut MathNode) {
+        let (simplified, count) = self.simplifier.simplify(node);
+        if count > 0 {
+            *node = simplified;
+            self.count += count;
+        }
+    }
+}
+
+/// Brackets the sums directly inside products.
+struct FactorBrackets;
+
+impl MathNodeTransformer for FactorBrackets {
+    fn transform_node(&mut self, node: &MathNode) -> Option<MathNode> {
+        let bracketed = |term: &MathNode| match term.content.as_ref() {
+            MathNodeContent::Additions { .. }
+            | MathNodeContent::BinaryOperation {
+                operation_type: BinaryOperationType::Addition,
+                ..
+            } => Some(MathNode {
+                id: format!("{}-bracketed", term.id),
+                content: Arc::new(MathNodeContent::Bracketed {
+                    inner: Arc::new(term.clone()),
+                    style: BracketStyle::Round,
+                    size: BracketSize::Normal,
+                }),
+            }),
+            _ => None,
+        };
+        let content = match node.content.as_ref() {
+            MathNodeContent::BinaryOperation {
+                operation_type: BinaryOperationType::Multiplication,
+                terms,
+            } if terms.iter().any(|(_, term)| bracketed(term).is_some()) => {
+                MathNodeContent::BinaryOperation {
+                    operation_type: BinaryOperationType::Multiplication,
+                    terms: terms
+                        .iter()
+                        .map(|(operator, term)| {
+                            (operator.clone(), bracketed(term).unwrap_or(term.clone()))
+                        })
+                        .collect(),
+                }
+            }
+            MathNodeContent::Multiplications { terms }
+                if terms.iter().any(|(_, term)| bracketed(term).is_some()) =>
+            {
+                MathNodeContent::Multiplications {
+                    terms: terms
+                        .iter()
+                        .map(|(operator, term)| {
+                            (operator.clone(), bracketed(term).unwrap_or(term.clone()))
+                        })
+                        .collect(),
+                }
+            }
+            _ => return None,
+        };
+        Some(MathNode {
+            id: node.id.clone(),
+            content: Arc::new(content),
+        })
+    }
+}
+
+fn node(id: &str, content: MathNodeContent) -> MathNode {
+    MathNode {
+        id: format!("simplification-{}", id),
+        content: Arc::new(content),
+    }
+}
+
+fn number(value: i64) -> MathNode {
+    node(
+        &value.to_string(),
+        MathNodeContent::Quantity {
+            number: value.to_string(),
+            scientific_notation: None,
+            unit: None,
+        },
+    )
+}
+
+fn fraction(numerator: MathNode, denominator: MathNode) -> MathNodeContent {
+    MathNodeContent::Fraction {
+        numerator: Arc::new(numerator),
+        denominator: Arc::new(denominator),
+    }
+}
+
+fn product(
+    first_operator: &BinaryOperator,
+    first: MathNode,
+    second_operator: &BinaryOperator,
+    second: MathNode,
+) -> MathNodeContent {
+    MathNodeContent::BinaryOperation {
+        operation_type: BinaryOperationType::Multiplication,
+        terms: vec![
+            (first_operator.clone(), first),
+            (second_operator.clone(), second),
+        ],
+    }
+}
+
+fn sum(
+    first_operator: BinaryOperator,
+    first: MathNode,
+    second_operator: BinaryOperator,
+    second: MathNode,
+) -> MathNodeContent {
+    MathNodeContent::BinaryOperation {
+        operation_type: BinaryOperationType::Addition,
+        terms: vec![(first_operator, first), (second_operator, second)],
+    }
+}
+
+fn legacy_product(
+    first: MathNode,
+    operator: RefinedMulOrDivOperation,
+    second: MathNode,
+) -> MathNodeContent {
+    MathNodeContent::Multiplications {
+        terms: vec![(RefinedMulOrDivOperation::None, first), (operator, second)],
+    }
+}
+
+fn legacy_sum(
+    first: MathNode,
+    operator: RefinedAddOrSubOperator,
+    second: MathNode,
+) -> MathNodeContent {
+    MathNodeContent::Additions {
+        terms: vec![(RefinedAddOrSubOperator::None, first), (operator, second)],
+    }
+}
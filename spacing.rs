@@ -0,0 +1,46 @@
+use super::*;
+use std::sync::Arc;
+
+impl MathNode {
+    /// Room for `inner` in the directions of `extent`, with nothing drawn.
+    pub fn phantom(id: impl Into<String>, inner: MathNode, extent: PhantomExtent) -> MathNode {
+        MathNode {
+            id: id.into(),
+            content: Arc::new(MathNodeContent::Phantom {
+                inner: Arc::new(inner),
+                extent,
+            }),
+        }
+    }
+
+    pub fn space(id: impl Into<String>, space: MathSpace) -> MathNode {
+        MathNode {
+            id: id.into(),
+            content: Arc::new(MathNodeContent::Space(space)),
+        }
+    }
+}
+
+impl MathSpace {
+    /// The width in em, negative for `\!`. A custom width counts only in `em` or in math units,
+    /// `mu`, eighteen to the em; `None` for other lengths, which depend on the font.
+    pub fn width_em(&self) -> Option<f64> {
+        let mu = |mu: f64| mu / 18.0;
+        match self {
+            MathSpace::NegativeThin => Some(mu(-3.0)),
+            MathSpace::Thin => Some(mu(3.0)),
+            MathSpace::Medium => Some(mu(4.0)),
+            MathSpace::Thick => Some(mu(5.0)),
+            MathSpace::Quad => Some(1.0),
+            MathSpace::Qquad => Some(2.0),
+            MathSpace::Custom(length) => {
+                let length = length.trim();
+                if let Some(em) = length.strip_suffix("em") {
+                    em.trim().parse().ok()
+                } else {
+                    length.strip_suffix("mu")?.trim().parse().ok().map(mu)
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,698 @@
+use super::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{collections::HashSet, fmt::Write as _};
+use ts_rs::TS;
+
+/// How numbers are written in a locale, used to recognise numeric table cells and to pick the
+/// number format they get in the exported spreadsheet, and to write quantities (see
+/// `QuantityFormat`).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct NumberLocale {
+    pub decimal_separator: char,
+    pub group_separator: Option<char>,
+}
+
+impl NumberLocale {
+    pub const ENGLISH: NumberLocale = NumberLocale {
+        decimal_separator: '.',
+        group_separator: Some(','),
+    };
+    pub const GERMAN: NumberLocale = NumberLocale {
+        decimal_separator: ',',
+        group_separator: Some('.'),
+    };
+    pub const FRENCH: NumberLocale = NumberLocale {
+        decimal_separator: ',',
+        group_separator: Some('\u{202f}'), // narrow no-break space
+    };
+    pub const SWISS: NumberLocale = NumberLocale {
+        decimal_separator: '.',
+        group_separator: Some('\''),
+    };
+
+    /// Parses `text` as a number written in this locale, e.g. `1.234,5` in German.
+    /// Returns the cell it becomes, or `None` if it is not a number.
+    pub fn parse(&self, text: &str) -> Option<SpreadsheetCell> {
+        let text = text.trim();
+        let (negative, digits) = match text.strip_prefix(['-', '\u{2212}']) {
+            Some(rest) => (true, rest),
+            None => (false, text.strip_prefix('+').unwrap_or(text)),
+        };
+        let (integer, fraction) = match digits.split_once(self.decimal_separator) {
+            Some((integer, fraction)) => (integer, Some(fraction)),
+            None => (digits, None),
+        };
+
+        // Space-like separators are interchangeable, since sources rarely agree on which one.
+        let is_group_separator = |c: char| match self.group_separator {
+            Some(separator) if separator.is_whitespace() => c.is_whitespace(),
+            Some(separator) => c == separator,
+            None => false,
+        };
+        let grouped = integer.contains(is_group_separator);
+        let groups: Vec<&str> = integer.split(is_group_separator).collect();
+        let mut normalized = String::from(if negative { "-" } else { "" });
+        for (i, group) in groups.iter().enumerate() {
+            let well_formed = !group.is_empty()
+                && group.chars().all(|c| c.is_ascii_digit())
+                && (!grouped || i == 0 || group.len() == 3)
+                && (!grouped || i > 0 || group.len() <= 3);
+            if !well_formed {
+                return None;
+            }
+            normalized.push_str(group);
+        }
+        let decimals = match fraction {
+            Some(fraction)
+                if !fraction.is_empty() && fraction.chars().all(|c| c.is_ascii_digit()) =>
+            {
+                normalized.push('.');
+                normalized.push_str(fraction);
+                fraction.len()
+            }
+            Some(_) => return None,
+            None => 0,
+        };
+        Some(SpreadsheetCell::Number {
+            value: normalized.parse().ok()?,
+            decimals,
+            grouped,
+        })
+    }
+}
+
+impl Default for NumberLocale {
+    fn default() -> Self {
+        NumberLocale::ENGLISH
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SpreadsheetCell {
+    Empty,
+    Text(String),
+    Number {
+        value: f64,
+        decimals: usize, // Decimal places shown, as in the source
+        grouped: bool,   // Whether the source used thousands separators
+    },
+}
+
+/// A merged block of cells, anchored at its top-left cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CellMerge {
+    pub row: usize,
+    pub column: usize,
+    pub row_span: usize,
+    pub col_span: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpreadsheetSheet {
+    pub name: String,
+    pub rows: Vec<Vec<SpreadsheetCell>>, // Rectangular; merged-over cells are `Empty`
+    pub header_rows: usize,
+    pub merges: Vec<CellMerge>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SpreadsheetWorkbook {
+    pub sheets: Vec<SpreadsheetSheet>,
+}
+
+impl TableNode {
+    /// The table laid out on a grid, header rows first, then body and footer rows. Cell contents
+    /// are flattened to plain text; cells that read as numbers in `locale` become numbers.
+    pub fn to_sheet(&self, name: &str, locale: &NumberLocale) -> SpreadsheetSheet {
+        let mut placed: Vec<(usize, usize, SpreadsheetCell)> = vec![];
+        let mut occupied: HashSet<(usize, usize)> = HashSet::new();
+        let mut merges = vec![];
+        let rows = self
+            .header_rows
+            .iter()
+            .chain(&self.body_rows)
+            .chain(&self.footer_rows);
+        for (row, table_row) in rows.enumerate() {
+            let mut column = 0;
+            for cell in &table_row.cells {
+                while occupied.contains(&(row, column)) {
+                    column += 1;
+                }
+                let row_span = cell.row_span.unwrap_or(1).max(1);
+                let col_span = cell.col_span.unwrap_or(1).max(1);
+                for r in row..row + row_span {
+                    for c in column..column + col_span {
+                        occupied.insert((r, c));
+                    }
+                }
+                if row_span > 1 || col_span > 1 {
+                    merges.push(CellMerge {
+                        row,
+                        column,
+                        row_span,
+                        col_span,
+                    });
+                }
+                placed.push((row, column, text_cell(&plain_text(&cell.content), locale)));
+                column += col_span;
+            }
+        }
+
+        let height = occupied.iter().map(|(r, _)| r + 1).max().unwrap_or(0);
+        let width = occupied.iter().map(|(_, c)| c + 1).max().unwrap_or(0);
+        let mut grid = vec![vec![SpreadsheetCell::Empty; width]; height];
+        for (row, column, cell) in placed {
+            grid[row][column] = cell;
+        }
+        SpreadsheetSheet {
+            name: name.to_string(),
+            rows: grid,
+            header_rows: self.header_rows.len(),
+            merges,
+        }
+    }
+}
+
+impl InteractiveDiagramNode {
+    /// The diagram's data as a sheet, when `data` is JSON in one of the tabular shapes used by
+    /// plots: an array of rows, an array of records, or an object of columns.
+    pub fn to_sheet(&self, name: &str, locale: &NumberLocale) -> Option<SpreadsheetSheet> {
+        let data: Value = serde_json::from_str(&self.data).ok()?;
+        let json_cell = |value: &Value| match value {
+            Value::Null => SpreadsheetCell::Empty,
+            Value::Number(number) => {
+                let repr = number.to_string();
+                SpreadsheetCell::Number {
+                    value: number.as_f64().unwrap_or_default(),
+                    decimals: repr
+                        .split_once('.')
+                        .map_or(0, |(_, fraction)| fraction.len()),
+                    grouped: false,
+                }
+            }
+            Value::String(text) => text_cell(text, locale),
+            other => SpreadsheetCell::Text(other.to_string()),
+        };
+        let header = |keys: Vec<&String>| -> Vec<SpreadsheetCell> {
+            keys.into_iter()
+                .map(|key| SpreadsheetCell::Text(key.clone()))
+                .collect()
+        };
+
+        let (mut rows, header_rows) = match &data {
+            Value::Array(items) if items.iter().all(Value::is_array) && !items.is_empty() => {
+                let rows: Vec<Vec<SpreadsheetCell>> = items
+                    .iter()
+                    .map(|item| {
+                        item.as_array()
+                            .into_iter()
+                            .flatten()
+                            .map(json_cell)
+                            .collect()
+                    })
+                    .collect();
+                let labelled = |row: &Vec<SpreadsheetCell>| {
+                    row.iter()
+                        .all(|cell| matches!(cell, SpreadsheetCell::Text(_)))
+                };
+                let header_rows = usize::from(rows.len() > 1 && labelled(&rows[0]));
+                (rows, header_rows)
+            }
+            Value::Array(items) if items.iter().all(Value::is_object) && !items.is_empty() => {
+                let keys: Vec<&String> = items[0].as_object()?.keys().collect();
+                let mut rows = vec![header(keys.clone())];
+                for item in items {
+                    rows.push(
+                        keys.iter()
+                            .map(|key| json_cell(item.get(key.as_str()).unwrap_or(&Value::Null)))
+                            .collect(),
+                    );
+                }
+                (rows, 1)
+            }
+            Value::Object(columns)
+                if columns.values().all(Value::is_array) && !columns.is_empty() =>
+            {
+                let height = columns
+                    .values()
+                    .filter_map(Value::as_array)
+                    .map(Vec::len)
+                    .max()?;
+                let mut rows = vec![header(columns.keys().collect())];
+                for i in 0..height {
+                    rows.push(
+                        columns
+                            .values()
+                            .map(|column| json_cell(column.get(i).unwrap_or(&Value::Null)))
+                            .collect(),
+                    );
+                }
+                (rows, 1)
+            }
+            _ => return None,
+        };
+
+        let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+        for row in &mut rows {
+            row.resize(width, SpreadsheetCell::Empty);
+        }
+        Some(SpreadsheetSheet {
+            name: name.to_string(),
+            rows,
+            header_rows,
+            merges: vec![],
+        })
+    }
+}
+
+impl MathDocument {
+    /// Every table and data-bearing diagram in the document, one sheet each, in document order.
+    /// Sheets are named after captions where there are any.
+    pub fn export_tables(&self, locale: &NumberLocale) -> SpreadsheetWorkbook {
+        self.fold(SpreadsheetWorkbook::new(), |mut workbook, node| {
+            let sheet = match node {
+                FoldNode::ContentNode(SectionContentNode::Table(table)) => {
+                    let name = caption_or(&table.caption, "Table", workbook.sheets.len());
+                    Some(table.to_sheet(&name, locale))
+                }
+                FoldNode::ContentNode(SectionContentNode::InteractiveDiagram(diagram)) => {
+                    let name = caption_or(&diagram.caption, "Data", workbook.sheets.len());
+                    diagram.to_sheet(&name, locale)
+                }
+                _ => None,
+            };
+            if let Some(sheet) = sheet {
+                workbook.add_sheet(sheet);
+            }
+            workbook
+        })
+    }
+}
+
+fn caption_or(caption: &Option<RichText>, fallback: &str, index: usize) -> String {
+    caption
+        .as_ref()
+        .map(|caption| plain_text(&[SectionContentNode::RichText(caption.clone())]))
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| format!("{} {}", fallback, index + 1))
+}
+
+fn text_cell(text: &str, locale: &NumberLocale) -> SpreadsheetCell {
+    let text = text.trim();
+    if text.is_empty() {
+        return SpreadsheetCell::Empty;
+    }
+    locale
+        .parse(text)
+        .unwrap_or_else(|| SpreadsheetCell::Text(text.to_string()))
+}
+
+/// The visible text of `content`, with blocks separated by spaces.
+pub fn plain_text(content: &[SectionContentNode]) -> String {
+    let mut collector = TextCollector::default();
+    for node in content {
+        collector.visit_content_node(node);
+    }
+    collector.parts.join(" ")
+}
+
+#[derive(Default)]
+struct TextCollector {
+    parts: Vec<String>,
+}
+
+impl<'doc> DocumentVisitor<'doc> for TextCollector {
+    fn visit_segment(&mut self, segment: &'doc RichTextSegment) {
+        match segment {
+            RichTextSegment::Text(text)
+            | RichTextSegment::StyledText { text, .. }
+            | RichTextSegment::CodeInline(text) => self.parts.push(text.trim().to_string()),
+            RichTextSegment::InteractiveVariable { display_name, .. } => {
+                self.parts.push(display_name.clone())
+            }
+            _ => walk_segment(self, segment),
+        }
+    }
+
+    fn visit_math_node(&mut self, node: &'doc MathNode) {
+        let text = node.fold(String::new(), |mut text, node| {
+            match node.content.as_ref() {
+                MathNodeContent::Quantity { number, .. } => text.push_str(number),
+                MathNodeContent::Identifier(identifier) => text.push_str(&identifier.body),
+                MathNodeContent::Text(leaf) | MathNodeContent::String(leaf) => text.push_str(leaf),
+                MathNodeContent::ChemicalExpression(expression) => {
+                    text.push_str(&expression.to_string())
+                }
+                _ => {}
+            }
+            text
+        });
+        self.parts.push(text);
+    }
+}
+
+impl SpreadsheetWorkbook {
+    pub fn new() -> Self {
+        SpreadsheetWorkbook::default()
+    }
+
+    /// Adds `sheet`, adjusting its name to what spreadsheet applications accept: at most 31
+    /// characters, none of `[]:*?/\`, and unique within the workbook.
+    pub fn add_sheet(&mut self, mut sheet: SpreadsheetSheet) {
+        let cleaned: String = sheet
+            .name
+            .chars()
+            .map(|c| if "[]:*?/\\".contains(c) { ' ' } else { c })
+            .collect();
+        let cleaned = cleaned.split_whitespace().collect::<Vec<_>>().join(" ");
+        let base: String = cleaned.chars().take(31).collect();
+        let base = if base.is_empty() {
+            format!("Sheet {}", self.sheets.len() + 1)
+        } else {
+            base
+        };
+        let mut name = base.clone();
+        let mut suffix = 2;
+        while self
+            .sheets
+            .iter()
+            .any(|existing| existing.name.eq_ignore_ascii_case(&name))
+        {
+            let tail = format!(" ({})", suffix);
+            let head: String = base.chars().take(31 - tail.len()).collect();
+            name = format!("{}{}", head, tail);
+            suffix += 1;
+        }
+        sheet.name = name;
+        self.sheets.push(sheet);
+    }
+
+    /// The workbook as an Office Open XML spreadsheet (`.xlsx`).
+    pub fn to_xlsx(&self) -> Vec<u8> {
+        let formats = self.number_formats();
+        let mut zip = ZipWriter::new();
+
+        let mut content_types = String::from(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Default Extension="xml" ContentType="application/xml"/><Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/><Override PartName="/xl/styles.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.styles+xml"/>"#,
+        );
+        for i in 1..=self.sheets.len() {
+            let _ = write!(
+                content_types,
+                r#"<Override PartName="/xl/worksheets/sheet{}.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/>"#,
+                i
+            );
+        }
+        content_types.push_str("</Types>");
+        zip.add_file("[Content_Types].xml", content_types.as_bytes());
+        zip.add_file(
+            "_rels/.rels",
+            br#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="xl/workbook.xml"/></Relationships>"#,
+        );
+
+        let mut workbook = String::from(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets>"#,
+        );
+        let mut relationships = String::from(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#,
+        );
+        for (i, sheet) in self.sheets.iter().enumerate() {
+            let _ = write!(
+                workbook,
+                r#"<sheet name="{}" sheetId="{}" r:id="rId{}"/>"#,
+                escape_xml(&sheet.name),
+                i + 1,
+                i + 1
+            );
+            let _ = write!(
+                relationships,
+                r#"<Relationship Id="rId{}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet{}.xml"/>"#,
+                i + 1,
+                i + 1
+            );
+        }
+        workbook.push_str("</sheets></workbook>");
+        let _ = write!(
+            relationships,
+            r#"<Relationship Id="rId{}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/styles" Target="styles.xml"/></Relationships>"#,
+            self.sheets.len() + 1
+        );
+        zip.add_file("xl/workbook.xml", workbook.as_bytes());
+        zip.add_file("xl/_rels/workbook.xml.rels", relationships.as_bytes());
+
+        // Style 0 is the default, 1 is bold headers, 2.. are the number formats in order.
+        let mut styles = String::from(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><styleSheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">"#,
+        );
+        let _ = write!(styles, r#"<numFmts count="{}">"#, formats.len());
+        for (i, format) in formats.iter().enumerate() {
+            let _ = write!(
+                styles,
+                r#"<numFmt numFmtId="{}" formatCode="{}"/>"#,
+                164 + i,
+                format.xlsx_code()
+            );
+        }
+        styles.push_str(r#"</numFmts><fonts count="2"><font/><font><b/></font></fonts><fills count="2"><fill><patternFill patternType="none"/></fill><fill><patternFill patternType="gray125"/></fill></fills><borders count="1"><border/></borders><cellStyleXfs count="1"><xf/></cellStyleXfs>"#);
+        let _ = write!(
+            styles,
+            r#"<cellXfs count="{}"><xf/><xf fontId="1" applyFont="1"/>"#,
+            formats.len() + 2
+        );
+        for i in 0..formats.len() {
+            let _ = write!(
+                styles,
+                r#"<xf numFmtId="{}" applyNumberFormat="1"/>"#,
+                164 + i
+            );
+        }
+        styles.push_str("</cellXfs></styleSheet>");
+        zip.add_file("xl/styles.xml", styles.as_bytes());
+
+        for (i, sheet) in self.sheets.iter().enumerate() {
+            let mut xml = String::from(
+                r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData>"#,
+            );
+            for (r, row) in sheet.rows.iter().enumerate() {
+                let _ = write!(xml, r#"<row r="{}">"#, r + 1);
+                for (c, cell) in row.iter().enumerate() {
+                    let reference = cell_reference(r, c);
+                    match cell {
+                        SpreadsheetCell::Empty => {}
+                        SpreadsheetCell::Text(text) => {
+                            let style = if r < sheet.header_rows {
+                                r#" s="1""#
+                            } else {
+                                ""
+                            };
+                            let _ = write!(
+                                xml,
+                                r#"<c r="{}" t="inlineStr"{}><is><t xml:space="preserve">{}</t></is></c>"#,
+                                reference,
+                                style,
+                                escape_xml(text)
+                            );
+                        }
+                        SpreadsheetCell::Number { value, .. } => {
+                            let style = formats
+                                .iter()
+                                .position(|format| Some(*format) == NumberFormat::of(cell))
+                                .map_or(0, |i| i + 2);
+                            let _ = write!(
+                                xml,
+                                r#"<c r="{}" s="{}"><v>{}</v></c>"#,
+                                reference, style, value
+                            );
+                        }
+                    }
+                }
+                xml.push_str("</row>");
+            }
+            xml.push_str("</sheetData>");
+            if !sheet.merges.is_empty() {
+                let _ = write!(xml, r#"<mergeCells count="{}">"#, sheet.merges.len());
+                for merge in &sheet.merges {
+                    let _ = write!(
+                        xml,
+                        r#"<mergeCell ref="{}:{}"/>"#,
+                        cell_reference(merge.row, merge.column),
+                        cell_reference(
+                            merge.row + merge.row_span - 1,
+                            merge.column + merge.col_span - 1
+                        )
+                    );
+                }
+                xml.push_str("</mergeCells>");
+            }
+            xml.push_str("</worksheet>");
+            zip.add_file(&format!("xl/worksheets/sheet{}.xml", i + 1), xml.as_bytes());
+        }
+        zip.finish()
+    }
+
+    /// The workbook as an OpenDocument spreadsheet (`.ods`).
+    pub fn to_ods(&self) -> Vec<u8> {
+        let formats = self.number_formats();
+        let mut zip = ZipWriter::new();
+        // The mimetype entry must come first and be stored uncompressed.
+        zip.add_file(
+            "mimetype",
+            b"application/vnd.oasis.opendocument.spreadsheet",
+        );
+        zip.add_file(
+            "META-INF/manifest.xml",
+            br#"<?xml version="1.0" encoding="UTF-8"?><manifest:manifest xmlns:manifest="urn:oasis:names:tc:opendocument:xmlns:manifest:1.0" manifest:version="1.2"><manifest:file-entry manifest:full-path="/" manifest:version="1.2" manifest:media-type="application/vnd.oasis.opendocument.spreadsheet"/><manifest:file-entry manifest:full-path="content.xml" manifest:media-type="text/xml"/></manifest:manifest>"#,
+        );
+
+        let mut xml = String::from(
+            r#"<?xml version="1.0" encoding="UTF-8"?><office:document-content xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:style="urn:oasis:names:tc:opendocument:xmlns:style:1.0" xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0" xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0" xmlns:number="urn:oasis:names:tc:opendocument:xmlns:datastyle:1.0" xmlns:fo="urn:oasis:names:tc:opendocument:xmlns:xsl-fo-compatible:1.0" office:version="1.2"><office:automatic-styles><style:style style:name="header" style:family="table-cell"><style:text-properties fo:font-weight="bold"/></style:style>"#,
+        );
+        for (i, format) in formats.iter().enumerate() {
+            let _ = write!(
+                xml,
+                r#"<number:number-style style:name="N{i}"><number:number number:decimal-places="{}" number:min-decimal-places="{}" number:min-integer-digits="1" number:grouping="{}"/></number:number-style><style:style style:name="num{i}" style:family="table-cell" style:data-style-name="N{i}"/>"#,
+                format.decimals, format.decimals, format.grouped
+            );
+        }
+        xml.push_str("</office:automatic-styles><office:body><office:spreadsheet>");
+        for sheet in &self.sheets {
+            let covered: HashSet<(usize, usize)> = sheet
+                .merges
+                .iter()
+                .flat_map(|merge| {
+                    (merge.row..merge.row + merge.row_span).flat_map(move |r| {
+                        (merge.column..merge.column + merge.col_span).map(move |c| (r, c))
+                    })
+                })
+                .filter(|cell| {
+                    !sheet
+                        .merges
+                        .iter()
+                        .any(|merge| (merge.row, merge.column) == *cell)
+                })
+                .collect();
+            let _ = write!(
+                xml,
+                r#"<table:table table:name="{}">"#,
+                escape_xml(&sheet.name)
+            );
+            for (r, row) in sheet.rows.iter().enumerate() {
+                xml.push_str("<table:table-row>");
+                for (c, cell) in row.iter().enumerate() {
+                    if covered.contains(&(r, c)) {
+                        xml.push_str("<table:covered-table-cell/>");
+                        continue;
+                    }
+                    let mut attributes = String::new();
+                    if let Some(merge) = sheet
+                        .merges
+                        .iter()
+                        .find(|merge| (merge.row, merge.column) == (r, c))
+                    {
+                        let _ = write!(
+                            attributes,
+                            r#" table:number-rows-spanned="{}" table:number-columns-spanned="{}""#,
+                            merge.row_span, merge.col_span
+                        );
+                    }
+                    match cell {
+                        SpreadsheetCell::Empty => {
+                            let _ = write!(xml, "<table:table-cell{}/>", attributes);
+                        }
+                        SpreadsheetCell::Text(text) => {
+                            if r < sheet.header_rows {
+                                attributes.push_str(r#" table:style-name="header""#);
+                            }
+                            let _ = write!(
+                                xml,
+                                r#"<table:table-cell office:value-type="string"{}><text:p>{}</text:p></table:table-cell>"#,
+                                attributes,
+                                escape_xml(text)
+                            );
+                        }
+                        SpreadsheetCell::Number {
+                            value, decimals, ..
+                        } => {
+                            if let Some(i) = formats
+                                .iter()
+                                .position(|format| Some(*format) == NumberFormat::of(cell))
+                            {
+                                let _ = write!(attributes, r#" table:style-name="num{}""#, i);
+                            }
+                            let _ = write!(
+                                xml,
+                                r#"<table:table-cell office:value-type="float" office:value="{}"{}><text:p>{:.*}</text:p></table:table-cell>"#,
+                                value, attributes, decimals, value
+                            );
+                        }
+                    }
+                }
+                xml.push_str("</table:table-row>");
+            }
+            xml.push_str("</table:table>");
+        }
+        xml.push_str("</office:spreadsheet></office:body></office:document-content>");
+        zip.add_file("content.xml", xml.as_bytes());
+        zip.finish()
+    }
+
+    /// The distinct number formats used in the workbook, in first-use order.
+    fn number_formats(&self) -> Vec<NumberFormat> {
+        let mut formats = vec![];
+        for cell in self
+            .sheets
+            .iter()
+            .flat_map(|sheet| sheet.rows.iter().flatten())
+        {
+            if let Some(format) = NumberFormat::of(cell)
+                && !formats.contains(&format)
+            {
+                formats.push(format);
+            }
+        }
+        formats
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct NumberFormat {
+    decimals: usize,
+    grouped: bool,
+}
+
+impl NumberFormat {
+    fn of(cell: &SpreadsheetCell) -> Option<NumberFormat> {
+        match cell {
+            SpreadsheetCell::Number {
+                decimals, grouped, ..
+            } => Some(NumberFormat {
+                decimals: *decimals,
+                grouped: *grouped,
+            }),
+            _ => None,
+        }
+    }
+
+    /// The format code in the XLSX convention, which uses `,` and `.` whatever the locale;
+    /// spreadsheet applications display it with the reader's own separators.
+    fn xlsx_code(&self) -> String {
+        let integer = if self.grouped { "#,##0" } else { "0" };
+        match self.decimals {
+            0 => integer.to_string(),
+            decimals => format!("{}.{}", integer, "0".repeat(decimals)),
+        }
+    }
+}
+
+/// `A1`-style reference of a zero-based cell position.
+fn cell_reference(row: usize, column: usize) -> String {
+    let mut letters = vec![];
+    let mut column = column + 1;
+    while column > 0 {
+        column -= 1;
+        letters.push((b'A' + (column % 26) as u8) as char);
+        column /= 26;
+    }
+    letters.iter().rev().collect::<String>() + &(row + 1).to_string()
+}
@@ -0,0 +1,206 @@
+use super::section_node::{Control, ControlType};
+use super::*;
+
+impl MathDocument {
+    /// A best-effort static rendition of this document, for print, EPUB and PDF export:
+    ///
+    /// - interactive controls become a list of their default values
+    /// - playgrounds gain a "Parameters" section with their default parameter values
+    /// - animations are frozen at their first keyframe and stop auto-advancing
+    /// - interactive proofs become nested numbered lists of their steps
+    /// - collapsible blocks are expanded
+    ///
+    /// Event handlers, control bindings and interaction points are dropped.
+    pub fn to_static(&self) -> MathDocument {
+        let mut document = self.clone();
+        StaticConverter.visit_document_mut(&mut document);
+        document
+    }
+}
+
+struct StaticConverter;
+
+impl DocumentVisitorMut for StaticConverter {
+    fn visit_document_mut(&mut self, document: &mut MathDocument) {
+        match &mut document.content_type {
+            MathDocumentType::AnimatedPresentation(c) => {
+                c.auto_advance = false;
+                c.control_bindings.clear();
+                c.interaction_points.clear();
+                freeze_interaction_system(&mut c.interaction_system);
+                freeze_timeline(&mut c.animation_timeline);
+            }
+            MathDocumentType::InteractivePlayground(c) => {
+                c.real_time_feedback = false;
+                c.control_bindings.clear();
+                freeze_interaction_system(&mut c.interaction_system);
+                if let Some(timeline) = &mut c.animation_timeline {
+                    freeze_timeline(timeline);
+                }
+                if let Some(section) = parameter_snapshot(&document.id, &c.parameter_space) {
+                    c.base_content.insert(0, section);
+                }
+            }
+            _ => {}
+        }
+        walk_document_mut(self, document);
+    }
+
+    fn visit_content_node_mut(&mut self, node: &mut SectionContentNode) {
+        let replacement = match node {
+            SectionContentNode::InteractiveControls(controls) => Some(controls_snapshot(controls)),
+            SectionContentNode::SecondOrderMath(SecondOrderMathNode::InteractiveProof(proof)) => {
+                Some(flatten_proof(proof))
+            }
+            SectionContentNode::CollapsibleBlock(block) => {
+                block.initially_collapsed = Some(false);
+                None
+            }
+            _ => None,
+        };
+        if let Some(replacement) = replacement {
+            *node = replacement;
+        }
+        walk_content_node_mut(self, node);
+    }
+}
+
+fn freeze_interaction_system(system: &mut InteractionSystem) {
+    system.controls.clear();
+    system.event_handlers.clear();
+}
+
+/// Keeps only the keyframes at the start of the timeline, so the content is shown as it first
+/// appears.
+fn freeze_timeline(timeline: &mut AnimationTimeline) {
+    let start = timeline
+        .keyframes
+        .iter()
+        .map(|keyframe| keyframe.time)
+        .fold(f64::INFINITY, f64::min);
+    timeline.keyframes.retain(|keyframe| keyframe.time <= start);
+    timeline.total_duration = 0.0;
+    timeline.interaction_points.clear();
+    timeline.synchronization_groups.clear();
+}
+
+/// The value a control shows before the user touches it, if it has one.
+fn control_value(control: &Control) -> Option<String> {
+    if let Some(value) = &control.default_value {
+        return Some(value.clone());
+    }
+    match &control.control_type {
+        ControlType::Slider { min, .. } => Some(min.to_string()),
+        ControlType::NumberInput { min, .. } => min.map(|min| min.to_string()),
+        ControlType::Checkbox => Some("false".to_string()),
+        ControlType::RadioGroup { options } | ControlType::Dropdown { options } => {
+            options.first().cloned()
+        }
+        ControlType::TextInput { .. } | ControlType::Button { .. } => None,
+    }
+}
+
+fn controls_snapshot(controls: &InteractiveControls) -> SectionContentNode {
+    let items = controls
+        .controls
+        .iter()
+        .filter_map(|control| {
+            let value = control_value(control)?;
+            let mut segments = match &control.label {
+                Some(label) => label.segments.clone(),
+                None => vec![RichTextSegment::Text(control.id.clone())],
+            };
+            segments.push(RichTextSegment::Text(format!(": {}", value)));
+            Some(list_item(vec![rich_text_node(segments)]))
+        })
+        .collect();
+    SectionContentNode::List(ListNode {
+        items,
+        style: ListStyle::Unordered(UnorderedListStyle::Disc),
+        start_index: None,
+    })
+}
+
+fn parameter_snapshot(document_id: &str, parameter_space: &ParameterSpace) -> Option<Section> {
+    let items: Vec<ListItemNode> = parameter_space
+        .parameters
+        .iter()
+        .filter_map(|parameter| {
+            let value = parameter_space
+                .default_values
+                .get(&parameter.name)
+                .copied()
+                .or(parameter.range.min)?;
+            let text = format!("{} = {}", parameter.name, value);
+            Some(list_item(vec![rich_text_node(vec![
+                RichTextSegment::Text(text),
+            ])]))
+        })
+        .collect();
+    if items.is_empty() {
+        return None;
+    }
+    Some(Section {
+        id: format!("{}-parameters", document_id),
+        title: Some(RichText::text("Parameters".to_string())),
+        content: SectionContentNode::List(ListNode {
+            items,
+            style: ListStyle::Unordered(UnorderedListStyle::Disc),
+            start_index: None,
+        }),
+        metadata: vec![("generated".to_string(), "static".to_string())],
+        display_options: None,
+        difficulty: None,
+        variant_seed: None,
+    })
+}
+
+/// The proof forest as a titled subsection of nested numbered lists, one item per step.
+fn flatten_proof(proof: &InteractiveProofDisplay) -> SectionContentNode {
+    SectionContentNode::SubSection(vec![Section {
+        id: proof.id.clone(),
+        title: Some(RichText::text(proof.title.clone())),
+        content: proof_steps(&proof.proof_forest.root_nodes),
+        metadata: vec![],
+        display_options: None,
+        difficulty: None,
+        variant_seed: None,
+    }])
+}
+
+fn proof_steps(nodes: &[ProofNodeDisplay]) -> SectionContentNode {
+    let items = nodes
+        .iter()
+        .map(|node| {
+            let tactic = &node.tactic_display;
+            let mut heading = format!("Step {}: {}", node.step_number, tactic.tactic_name);
+            if !tactic.description.is_empty() {
+                heading = format!("{}. {}", heading, tactic.description);
+            }
+            let mut content = vec![
+                rich_text_node(vec![RichTextSegment::Text(heading)]),
+                SectionContentNode::Math(node.goal_display.goal_statement.clone()),
+            ];
+            if !node.children.is_empty() {
+                content.push(proof_steps(&node.children));
+            }
+            list_item(content)
+        })
+        .collect();
+    SectionContentNode::List(ListNode {
+        items,
+        style: ListStyle::Ordered(OrderedListStyle::Decimal),
+        start_index: None,
+    })
+}
+
+fn rich_text_node(segments: Vec<RichTextSegment>) -> SectionContentNode {
+    SectionContentNode::RichText(RichText {
+        segments,
+        alignment: None,
+    })
+}
+
+fn list_item(content: Vec<SectionContentNode>) -> ListItemNode {
+    ListItemNode { content }
+}
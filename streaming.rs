@@ -0,0 +1,142 @@
+use super::*;
+use std::{collections::HashSet, fmt};
+
+/// Builds the body of a structured document (wiki page, textbook, paper, notes) one section at
+/// a time, for servers that stream a document to the client while it is being generated.
+///
+/// After every append the document is complete and valid on its own: the table of contents
+/// lists every titled section appended so far, numbered `1.`, `2.`, ... with subsections
+/// numbered `2.1`, `2.2`, ..., so each snapshot can be sent and rendered as is.
+#[derive(Debug, Clone)]
+pub struct StreamingDocumentBuilder {
+    document: MathDocument,
+    section_ids: HashSet<String>,
+    numbered_sections: usize, // top-level sections given a number so far
+    revision: u32,
+}
+
+impl StreamingDocumentBuilder {
+    /// Starts from `document`, usually with an empty body, keeping the sections it already has
+    /// and rebuilding its table of contents. Fails if the document type has no
+    /// `DocumentStructure` to append to.
+    pub fn new(document: MathDocument) -> Result<Self, StreamingError> {
+        if document.content_type.structure().is_none() {
+            return Err(StreamingError::NoStructure {
+                document_id: document.id,
+            });
+        }
+        let section_ids = document
+            .sections()
+            .into_iter()
+            .map(|section| section.id.clone())
+            .collect();
+        let mut builder = StreamingDocumentBuilder {
+            document,
+            section_ids,
+            numbered_sections: 0,
+            revision: 0,
+        };
+        let body = std::mem::take(&mut builder.structure_mut().body);
+        builder.structure_mut().table_of_contents = None;
+        for section in body {
+            builder.push_section(section);
+        }
+        Ok(builder)
+    }
+
+    /// Appends `section` to the body, updating the table of contents, and returns the document
+    /// as it now stands. Fails, leaving the document unchanged, if the section or one of its
+    /// subsections reuses the id of a section already in the document.
+    pub fn append_section(&mut self, section: Section) -> Result<&MathDocument, StreamingError> {
+        let mut ids = vec![];
+        collect_section_ids(&section, &mut ids);
+        for (i, id) in ids.iter().enumerate() {
+            if self.section_ids.contains(*id) || ids[..i].contains(id) {
+                return Err(StreamingError::DuplicateSectionId {
+                    section_id: id.to_string(),
+                });
+            }
+        }
+        self.section_ids.extend(ids.into_iter().map(str::to_string));
+        self.push_section(section);
+        self.revision += 1;
+        Ok(&self.document)
+    }
+
+    /// Replaces the abstract, returning the document as it now stands.
+    pub fn set_abstract(&mut self, section: Section) -> &MathDocument {
+        self.structure_mut().abstract_content = Some(section);
+        self.revision += 1;
+        &self.document
+    }
+
+    /// The document as it stands after the last append.
+    pub fn snapshot(&self) -> &MathDocument {
+        &self.document
+    }
+
+    /// Number of changes made since the builder was created, to order snapshots on the client.
+    pub fn revision(&self) -> u32 {
+        self.revision
+    }
+
+    pub fn finish(self) -> MathDocument {
+        self.document
+    }
+
+    fn structure_mut(&mut self) -> &mut DocumentStructure {
+        self.document
+            .content_type
+            .structure_mut()
+            .expect("checked in `StreamingDocumentBuilder::new`")
+    }
+
+    /// Adds `section` to the body and its entry to the table of contents.
+    fn push_section(&mut self, section: Section) {
+        let number = (self.numbered_sections + 1).to_string();
+        if let Some(entry) = section.toc_entry(&number) {
+            self.numbered_sections += 1;
+            let document_title = self.document.content_type.title().unwrap_or_default();
+            let root = TocNode {
+                title: document_title.to_string(),
+                target_id: String::new(), // the document itself
+                children: vec![],
+            };
+            let toc = self.structure_mut().table_of_contents.get_or_insert(root);
+            toc.children.push(entry);
+        }
+        self.structure_mut().body.push(section);
+    }
+}
+
+fn collect_section_ids<'a>(section: &'a Section, ids: &mut Vec<&'a str>) {
+    ids.push(&section.id);
+    if let SectionContentNode::SubSection(subsections) = &section.content {
+        for subsection in subsections {
+            collect_section_ids(subsection, ids);
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum StreamingError {
+    NoStructure { document_id: String },
+    DuplicateSectionId { section_id: String },
+}
+
+impl fmt::Display for StreamingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StreamingError::NoStructure { document_id } => write!(
+                f,
+                "document `{}` has no structure to append sections to",
+                document_id
+            ),
+            StreamingError::DuplicateSectionId { section_id } => {
+                write!(f, "section id `{}` is already in the document", section_id)
+            }
+        }
+    }
+}
+
+impl std::error::Error for StreamingError {}
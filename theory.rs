@@ -0,0 +1,308 @@
+use super::*;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use ts_rs::TS;
+
+/// Identifier of a mathematical theory, e.g. `GroupTheory` or `ZFC`. Serialized as a plain
+/// string; check it against a `TheoryRegistry` to catch unknown theories.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct TheoryId(pub String);
+
+impl TheoryId {
+    pub fn new(id: impl Into<String>) -> Self {
+        TheoryId(id.into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<&str> for TheoryId {
+    fn from(id: &str) -> Self {
+        TheoryId::new(id)
+    }
+}
+
+impl fmt::Display for TheoryId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// A theory known to a `TheoryRegistry`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct TheoryEntry {
+    pub id: TheoryId,
+    pub name: String,         // Display name, e.g. "Group Theory"
+    pub aliases: Vec<String>, // Other spellings accepted for the id, e.g. "groups"
+}
+
+impl TheoryEntry {
+    pub fn new(id: &str, name: &str, aliases: &[&str]) -> Self {
+        TheoryEntry {
+            id: TheoryId::new(id),
+            name: name.to_string(),
+            aliases: aliases.iter().map(|alias| alias.to_string()).collect(),
+        }
+    }
+}
+
+/// The theories documents may refer to. Ids and aliases are matched ignoring case, spaces,
+/// hyphens and underscores, so `group-theory` resolves to `GroupTheory`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TheoryRegistry {
+    entries: Vec<TheoryEntry>,
+}
+
+impl Default for TheoryRegistry {
+    fn default() -> Self {
+        TheoryRegistry::known()
+    }
+}
+
+impl TheoryRegistry {
+    pub fn empty() -> Self {
+        TheoryRegistry { entries: vec![] }
+    }
+
+    /// The built-in theories.
+    pub fn known() -> Self {
+        let entries = [
+            (
+                "ZFC",
+                "Zermelo–Fraenkel Set Theory",
+                &["SetTheory", "ZF", "sets"][..],
+            ),
+            ("FirstOrderLogic", "First-Order Logic", &["Logic", "FOL"]),
+            ("TypeTheory", "Type Theory", &["HoTT", "HomotopyTypeTheory"]),
+            ("CategoryTheory", "Category Theory", &["categories"]),
+            ("NumberTheory", "Number Theory", &["arithmetic"]),
+            ("GroupTheory", "Group Theory", &["groups"]),
+            ("RingTheory", "Ring Theory", &["rings"]),
+            ("FieldTheory", "Field Theory", &["fields", "GaloisTheory"]),
+            ("LinearAlgebra", "Linear Algebra", &["VectorSpaces"]),
+            (
+                "Topology",
+                "Topology",
+                &["GeneralTopology", "PointSetTopology"],
+            ),
+            ("RealAnalysis", "Real Analysis", &["Analysis", "Calculus"]),
+            ("ComplexAnalysis", "Complex Analysis", &[]),
+            ("MeasureTheory", "Measure Theory", &[]),
+            ("ProbabilityTheory", "Probability Theory", &["Probability"]),
+            ("Statistics", "Statistics", &[]),
+            ("Combinatorics", "Combinatorics", &[]),
+            ("GraphTheory", "Graph Theory", &["graphs"]),
+            (
+                "DifferentialGeometry",
+                "Differential Geometry",
+                &["Geometry"],
+            ),
+            (
+                "DifferentialEquations",
+                "Differential Equations",
+                &["ODE", "PDE"],
+            ),
+        ]
+        .into_iter()
+        .map(|(id, name, aliases)| TheoryEntry::new(id, name, aliases))
+        .collect();
+        TheoryRegistry { entries }
+    }
+
+    pub fn entries(&self) -> &[TheoryEntry] {
+        &self.entries
+    }
+
+    /// Adds a theory. Fails if its id or one of its aliases already names another theory.
+    pub fn register(&mut self, entry: TheoryEntry) -> Result<(), TheoryError> {
+        let names =
+            std::iter::once(entry.id.as_str()).chain(entry.aliases.iter().map(String::as_str));
+        for name in names {
+            if let Some(existing) = self.resolve(name) {
+                return Err(TheoryError::DuplicateName {
+                    name: name.to_string(),
+                    existing: existing.id.clone(),
+                });
+            }
+        }
+        self.entries.push(entry);
+        Ok(())
+    }
+
+    /// The theory named `name` by its id or an alias.
+    pub fn resolve(&self, name: &str) -> Option<&TheoryEntry> {
+        let key = normalize(name);
+        self.entries.iter().find(|entry| {
+            normalize(entry.id.as_str()) == key
+                || entry.aliases.iter().any(|alias| normalize(alias) == key)
+        })
+    }
+
+    pub fn contains(&self, id: &TheoryId) -> bool {
+        self.resolve(id.as_str()).is_some()
+    }
+
+    /// The registered id for `id`, which may be an alias or spelled differently.
+    pub fn canonical(&self, id: &TheoryId) -> Option<&TheoryId> {
+        self.resolve(id.as_str()).map(|entry| &entry.id)
+    }
+
+    /// Every theory referenced by the document that the registry does not know, nested documents
+    /// included: domains, source and target theories, theories involved in comparisons and
+    /// transformations, and the theory context of definition links.
+    pub fn validate_document(&self, document: &MathDocument) -> Vec<TheoryError> {
+        let mut collector = TheoryCollector::default();
+        collector.visit_document(document);
+        collector
+            .references
+            .into_iter()
+            .filter(|(_, theory)| !self.contains(theory))
+            .map(|(document_id, theory)| TheoryError::UnknownTheory {
+                document_id,
+                suggestion: self.suggest(theory),
+                theory: theory.clone(),
+            })
+            .collect()
+    }
+
+    /// The registered id closest to a misspelled one, if any is close enough.
+    fn suggest(&self, id: &TheoryId) -> Option<TheoryId> {
+        let key = normalize(id.as_str());
+        let key = key.as_str();
+        self.entries
+            .iter()
+            .flat_map(|entry| {
+                std::iter::once(entry.id.as_str())
+                    .chain(entry.aliases.iter().map(String::as_str))
+                    .map(move |name| (edit_distance(key, &normalize(name)), &entry.id))
+            })
+            .filter(|(distance, _)| *distance <= 2.max(key.len() / 4))
+            .min_by_key(|(distance, _)| *distance)
+            .map(|(_, id)| id.clone())
+    }
+}
+
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|c| !c.is_whitespace() && !matches!(c, '-' | '_'))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a != *b);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[derive(Default)]
+struct TheoryCollector<'doc> {
+    documents: Vec<&'doc str>,
+    references: Vec<(String, &'doc TheoryId)>, // (enclosing document id, theory)
+}
+
+impl<'doc> TheoryCollector<'doc> {
+    fn push(&mut self, theory: &'doc TheoryId) {
+        let document_id = self.documents.last().copied().unwrap_or_default();
+        self.references.push((document_id.to_string(), theory));
+    }
+}
+
+impl<'doc> DocumentVisitor<'doc> for TheoryCollector<'doc> {
+    fn visit_document(&mut self, document: &'doc MathDocument) {
+        self.documents.push(&document.id);
+        match &document.content_type {
+            MathDocumentType::WikiPage(c) => self.push(&c.theory_domain),
+            MathDocumentType::TypeMappingDisplay(c) => {
+                self.push(&c.source_theory);
+                self.push(&c.target_theory);
+            }
+            MathDocumentType::ComparisonPage(c) => {
+                for theory in &c.theories_involved {
+                    self.push(&theory.theory_id);
+                }
+            }
+            MathDocumentType::TransformationMapping(c) => {
+                self.push(&c.source_theory);
+                self.push(&c.target_theory);
+                for theory in &c.theories_involved {
+                    self.push(&theory.theory_id);
+                }
+            }
+            _ => {}
+        }
+        walk_document(self, document);
+        self.documents.pop();
+    }
+
+    fn visit_segment(&mut self, segment: &'doc RichTextSegment) {
+        if let RichTextSegment::Link {
+            target:
+                LinkTarget::DefinitionId {
+                    theory_context: Some(theory),
+                    ..
+                }
+                | LinkTarget::DefinitionAspect {
+                    theory_context: Some(theory),
+                    ..
+                },
+            ..
+        } = segment
+        {
+            self.push(theory);
+        }
+        walk_segment(self, segment);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TheoryError {
+    UnknownTheory {
+        document_id: String,
+        theory: TheoryId,
+        suggestion: Option<TheoryId>,
+    },
+    DuplicateName {
+        name: String,
+        existing: TheoryId,
+    },
+}
+
+impl fmt::Display for TheoryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TheoryError::UnknownTheory {
+                document_id,
+                theory,
+                suggestion,
+            } => {
+                write!(
+                    f,
+                    "document `{}` refers to unknown theory `{}`",
+                    document_id, theory
+                )?;
+                if let Some(suggestion) = suggestion {
+                    write!(f, " (did you mean `{}`?)", suggestion)?;
+                }
+                Ok(())
+            }
+            TheoryError::DuplicateName { name, existing } => {
+                write!(f, "`{}` already names theory `{}`", name, existing)
+            }
+        }
+    }
+}
+
+impl std::error::Error for TheoryError {}
@@ -0,0 +1,569 @@
+use super::*;
+
+// Every type is plain owned data: shared subtrees are held in `Arc`s, never `Rc`s, and the
+// only interior mutability is the `OnceLock` a `DocumentHandle` loads into, with a loader that
+// must itself be `Send + Sync`. So documents, and everything derived from them, can be shared
+// between the threads of a render server behind an `Arc` or sent to another thread, and the
+// borrowed views can be used from several threads at once. Maps in the types are `BTreeMap`s,
+// so that what a thread serializes does not depend on a per-process hash seed.
+//
+// The assertions below keep it that way: a type that stops being `Send` or `Sync` fails to
+// compile here rather than in a server.
+
+/// Fails to compile unless each of the types is `Send` and `Sync`.
+macro_rules! static_assert_send_sync {
+    ($($ty:ty),* $(,)?) => {
+        const _: fn() = || {
+            fn assert_send_sync<T: Send + Sync + ?Sized>() {}
+            $(assert_send_sync::<$ty>();)*
+        };
+    };
+}
+
+// accessibility
+static_assert_send_sync!(AccessibilityIssue, AccessibilityWarning);
+
+// archive
+static_assert_send_sync!(ZipWriter, ZipError);
+
+// auto_fold
+static_assert_send_sync!(AutoFoldOptions);
+
+// backlinks
+static_assert_send_sync!(BacklinkIndex, Backlink, BacklinkKind);
+
+// bibliography
+static_assert_send_sync!(BibliographyIssue);
+
+// bundle
+static_assert_send_sync!(BundleManifest, DocumentBundle, BundleError);
+
+// canonical
+static_assert_send_sync!(Canonicalizer);
+
+// capabilities
+static_assert_send_sync!(CapabilityManifest);
+
+// chemistry
+static_assert_send_sync!(
+    ChemicalExpression,
+    ReactionStep,
+    ReactionArrow,
+    ChemicalTerm,
+    ChemicalSpecies,
+    FormulaPart,
+    PhysicalState,
+    ChemicalParseError,
+);
+
+// collab
+static_assert_send_sync!(Timestamp, CollabOperation, CollabDocument);
+
+// component_registry
+static_assert_send_sync!(
+    ComponentProps,
+    ComponentSchema,
+    PropSchema,
+    PropType,
+    ComponentRegistry,
+    PropValidationError,
+);
+
+// correspondence
+static_assert_send_sync!(CorrespondenceGroup, CorrespondenceSide, CorrespondenceError);
+
+// dependency_graph
+static_assert_send_sync!(DependencyError);
+
+// derivation_chain
+static_assert_send_sync!(DerivationChain, DerivationStep);
+
+// diagnostic
+static_assert_send_sync!(Severity, Diagnostic);
+
+// difficulty
+static_assert_send_sync!(
+    DifficultyAnnotation,
+    DifficultyLevel,
+    CognitiveLoad,
+    ContentRole,
+    DifficultyProfile,
+);
+
+// differentiation
+static_assert_send_sync!(DifferentiationError);
+
+// dimension_check
+static_assert_send_sync!(DimensionIssue);
+
+// document_patch
+static_assert_send_sync!(SectionParent, DocumentPatch, PatchOperation, PatchError);
+
+// feedback
+static_assert_send_sync!(
+    FeedbackNode,
+    FeedbackResponseKind,
+    FeedbackResponse,
+    FeedbackValue,
+    FeedbackError,
+);
+
+// equation
+static_assert_send_sync!(Equation, EquationNumbering, EquationNumberStyle);
+
+// evaluation
+static_assert_send_sync!(NumericValue, EvaluationError);
+
+// fold
+static_assert_send_sync!(FoldNode<'static>);
+
+// free_identifiers
+static_assert_send_sync!(VariableAnalysis, ShadowingWarning);
+
+// generation_error
+static_assert_send_sync!(GenerationErrorNode);
+
+// hash_consing
+static_assert_send_sync!(HashConsed, HashConsError);
+
+// interaction
+static_assert_send_sync!(InteractionError);
+
+// interning
+static_assert_send_sync!(MathInterner);
+
+// interval
+static_assert_send_sync!(Interval);
+
+// labels
+static_assert_send_sync!(QualifiedLabel, LabelIndex);
+
+// layout_builder
+static_assert_send_sync!(GridSpan, GridNodeBuilder, ColumnsNodeBuilder);
+
+// lazy_document
+static_assert_send_sync!(DocumentHandle, DocumentLoadError);
+
+// links
+static_assert_send_sync!(LinkOccurrence<'static>, LocationStep);
+
+// lms_export
+static_assert_send_sync!(LmsPackage, LmsItem);
+
+// math_arena
+static_assert_send_sync!(NodeIndex, MathArena, ArenaNode);
+
+// math_diff
+static_assert_send_sync!(MathEdit);
+
+// math_document
+static_assert_send_sync!(
+    MathDocument,
+    MathDocumentType,
+    WikiPageContent,
+    TextbookContent,
+    ScientificPaperContent,
+    PersonalNotesContent,
+    MathematicianNotesContent,
+    StudyNotesContent,
+    TooltipSummaryContent,
+    BlogPostContent,
+    AbstractSummaryContent,
+    ConceptMapContent,
+    AnimatedPresentationContent,
+    InteractivePlaygroundContent,
+    TypeMappingDisplayContent,
+    ResourcePanelContent,
+    ComparisonPageContent,
+    TransformationMappingContent,
+    ConceptAlignmentContent,
+    StaticPreviewContent,
+    LiveEmbedContent,
+    ConceptExtractContent,
+    IFrameEmbedContent,
+    CompletenessLevel,
+    ContentMetadata,
+    DocumentStructure,
+    DocumentRelationships,
+    SourceReference,
+    DerivationMethod,
+    DerivationMetadata,
+    AccuracyMetrics,
+    SummarizationLevel,
+    AnimationTimeline,
+    AnimationKeyframe,
+    AnimationType,
+    InteractionPoint,
+    InteractionType,
+    UserAction,
+    ConceptReference,
+    ConceptRelationType,
+    SimplifiedContentStructure,
+    KeyPoint,
+    ImportanceLevel,
+    EssentialDefinition,
+    CourseLevel,
+    WritingStyle,
+    RelationshipType,
+    InteractionLevel,
+    AcademicMetadata,
+    ChapterInfo,
+    PaperType,
+    NoteStyle,
+    InteractionSystem,
+    EventHandler,
+    InteractionAction,
+    StateVariable,
+    StateVariableType,
+    ControlBinding,
+    ValueTransformation,
+    ParameterSpace,
+    Parameter,
+    ParameterType,
+    ParameterRange,
+    Constraint,
+    ConstraintType,
+    VisualizationType,
+    MappingVisualization,
+    MappingArrow,
+    ArrowStyle,
+    ResourceCategory,
+    ResourceItem,
+    ResourceType,
+    SearchCapabilities,
+    SortOption,
+    FilterOption,
+    FilterType,
+    TheoryReference,
+    RelationshipMetadata,
+    ComparisonStructure,
+    ComparisonLayout,
+    ComparisonSection,
+    ComparisonCriterion,
+    TransformationType,
+    AlignmentType,
+    AlignmentPrecision,
+    ConceptCorrespondence,
+    CorrespondenceType,
+    ExtractionMetadata,
+    ViewportConfig,
+    ScrollBehavior,
+    AllowedInteraction,
+    ContextPreservationLevel,
+    SandboxPermission,
+    CoreExample,
+    ConceptRelationship,
+    SyncGroup,
+    SyncType,
+    EasingFunction,
+    EasingType,
+    Direction,
+    DirectionType,
+    CrossReference,
+    CrossReferenceType,
+    DependencyGraph,
+    DependencyNode,
+    DependencyNodeType,
+    DependencyEdge,
+    DependencyType,
+    TransformationStep,
+    AlignmentVisualization,
+    AlignmentArrow,
+    TocNode,
+    BibEntry,
+    AudienceLevel,
+    math_document::Control,
+    math_document::ControlType,
+    PresentationConfig,
+    LayoutStyle,
+    InteractionFeature,
+    FormalityLevel,
+    AnimationConfig,
+);
+
+// math_lint
+static_assert_send_sync!(MathLint);
+
+// math_metrics
+static_assert_send_sync!(MathMetrics, SectionMetrics);
+
+// math_node
+static_assert_send_sync!(
+    MathTextSegment,
+    MathNode,
+    MathNodeContent,
+    Identifier,
+    ScriptNode,
+    FunctionClause,
+    ScientificNotationStyle,
+    DifferentialStyle,
+    DerivativeNotation,
+    IntegralType,
+    IntegralDomain,
+    QuantificationNode,
+    BinaryOperationType,
+    RefinedMulOrDivOperation,
+    RefinedAddOrSubOperator,
+    DivisionStyle,
+    BinomialStyle,
+    VectorDecoration,
+    BracePosition,
+    MathEmphasis,
+    CancelStyle,
+    AngleUnit,
+    Turnstile,
+    PhantomExtent,
+    MathSpace,
+    MatrixDelimiters,
+    MatrixAxis,
+    MatrixHighlight,
+    MulSymbol,
+    DivSymbol,
+    BinaryOperator,
+    BracketStyle,
+    BracketSize,
+    UnitComponent,
+    RelationOperatorNode,
+    UnaryRelationOperatorNode,
+    SpecialMiddleScriptNode,
+    SpecialMiddleScriptContentTypeNode,
+    BaseUnitTypeNode,
+);
+
+// math_search
+static_assert_send_sync!(SubexpressionMatch<'static>);
+
+// math_zipper
+static_assert_send_sync!(MathZipper);
+
+// merge
+static_assert_send_sync!(MergeResult, MergeConflict);
+
+// message_pack
+static_assert_send_sync!(MessagePackError);
+
+// metadata_view
+static_assert_send_sync!(
+    DocumentMetadataView<'static>,
+    ContentTypeView<'static>,
+    ContentMetadataView<'static>,
+);
+
+// node_path
+static_assert_send_sync!(NodePath, PathSegment, NodePathError);
+
+// panel_layout
+static_assert_send_sync!(PanelRenderingHints, RenderPriority, PanelLayoutError);
+
+// parameter_space
+static_assert_send_sync!(ParameterSpaceError);
+
+// pattern
+static_assert_send_sync!(Pattern, RewriteRule, PatternError);
+
+// protobuf
+static_assert_send_sync!(ProtoSchema, ProtoError);
+
+// quantity_format
+static_assert_send_sync!(QuantityFormat, NumberNotation);
+
+// query
+static_assert_send_sync!(
+    Selector,
+    CompoundSelector,
+    AttributeSelector,
+    AttributeOperator,
+    QueryMatch<'static>,
+    QueryError,
+);
+
+// references
+static_assert_send_sync!(ReferenceKind);
+
+// revision_history
+static_assert_send_sync!(RevisionHistory, Revision, RevisionError);
+
+// rich_text
+static_assert_send_sync!(
+    RichTextSegment,
+    TextStyle,
+    RichText,
+    TextAlignment,
+    LinkTarget,
+    AnimationTriggerType,
+);
+
+// schema_manifest
+static_assert_send_sync!(SchemaManifest, SchemaMismatch);
+
+// second_order_math_node
+static_assert_send_sync!(
+    SecondOrderMathNode,
+    LogicalNode,
+    Judgement,
+    QuantifierGroup,
+    VariableDeclaration,
+    QuantifiedVariableDeclarationGroup,
+    second_order_math_node::QuantifierType,
+    TheoremLikeKind,
+    Solution,
+    InteractiveProofDisplay,
+    ProofForestDisplay,
+    ProofNodeDisplay,
+    TacticDisplay,
+    GoalDisplay,
+    ContextVariableDisplay,
+    TransformationDisplay,
+    ProofVisualConfig,
+    ProofInteractionConfig,
+    ProofLayoutType,
+    ProofVisualStyle,
+    ProofNodeVisualState,
+    TacticVisualStyle,
+    GoalVisualStyle,
+    ProofColorScheme,
+    TransformationAnimationConfig,
+    TransformationWorkflowStage,
+    ProofTransformationData,
+    InteractiveExpression,
+    ExpressionPosition,
+    ProofExpressionInteractionType,
+    ContextType,
+    PatternMatch,
+    InstantiationMap,
+    InstantiationDirection,
+    InteractiveElement,
+    InteractiveElementType,
+    InteractionHandler,
+    HandlerType,
+    VisualConnection,
+    TransformationFlow,
+);
+
+// section_node
+static_assert_send_sync!(
+    SectionContentNode,
+    BranchingContainer,
+    ContainerType,
+    BranchingNode,
+    NodeType,
+    NodeState,
+    ContainerLayout,
+    LayoutType,
+    LayoutDirection,
+    LayoutAlignment,
+    SideBySideLayout,
+    SideBySideConfig,
+    PanelLayout,
+    PanelLayoutType,
+    Panel,
+    PanelRole,
+    PanelControls,
+    AnnotationOverlay,
+    Annotation,
+    TextQuoteAnchor,
+    AnnotationType,
+    OverlayStyle,
+    AnnotationPosition,
+    PositionAnchor,
+    AnnotationStyling,
+    InteractiveControls,
+    ControlLayout,
+    section_node::Control,
+    section_node::ControlType,
+    AbstractionMetadata,
+    ListNode,
+    ListItemNode,
+    ListStyle,
+    UnorderedListStyle,
+    OrderedListStyle,
+    TableNode,
+    TableRowNode,
+    TableCellNode,
+    TableCellType,
+    ColumnStyle,
+    TableStyleOptions,
+    CodeBlockNode,
+    ImageNode,
+    HorizontalAlignment,
+    InteractiveDiagramNode,
+    CollapsibleBlockNode,
+    GridNode,
+    GridItemNode,
+    ColumnsNode,
+    ThematicBreakNode,
+    AlertBoxStyle,
+    Section,
+    SectionDisplayOptions,
+    SelectableProperty,
+    QuantifiedObject,
+    section_node::QuantifierType,
+    VariableBinding,
+    BindingType,
+    InequalityType,
+    NumberType,
+    OperationType,
+    SetType,
+);
+
+// section_refs
+static_assert_send_sync!(SectionResolver<'static>, SectionRefError);
+
+// simplification
+static_assert_send_sync!(SimplificationRule, Simplifier);
+
+// spreadsheet_export
+static_assert_send_sync!(
+    NumberLocale,
+    SpreadsheetCell,
+    CellMerge,
+    SpreadsheetSheet,
+    SpreadsheetWorkbook,
+);
+
+// streaming
+static_assert_send_sync!(StreamingDocumentBuilder, StreamingError);
+
+// textline_node
+static_assert_send_sync!(TurnTextLineNode);
+
+// theory
+static_assert_send_sync!(TheoryId, TheoryEntry, TheoryRegistry, TheoryError);
+
+// toc
+static_assert_send_sync!(TocIssue);
+
+// ts_index
+static_assert_send_sync!(TypeScriptIndex, EnumVariant);
+
+// unit_interval
+static_assert_send_sync!(UnitInterval, UnitIntervalError);
+
+// units
+static_assert_send_sync!(Dimension, SiUnit, UnitDefinition, UnitError, UnitRegistry);
+
+// validation
+static_assert_send_sync!(IdKind, IdOccurrence, ValidationError);
+
+// variable_context
+static_assert_send_sync!(
+    VariableContext,
+    InteractiveVariableDeclaration,
+    VariableTypeInfo,
+    VariableResolutionError,
+);
+
+// variant_seed
+static_assert_send_sync!(
+    VariantSeed,
+    VariantParameter,
+    VariantDistribution,
+    VariantValue,
+    VariantAssignment,
+);
+
+// web_annotation
+static_assert_send_sync!(WebAnnotationError);
+
+// well_formed
+static_assert_send_sync!(MathNodeError);
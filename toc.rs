@@ -0,0 +1,226 @@
+use super::*;
+use std::fmt;
+
+impl Section {
+    /// The title as plain text, if the section has a title that is not blank.
+    pub fn title_text(&self) -> Option<String> {
+        let title = self.title.as_ref()?;
+        let text = plain_text(&[SectionContentNode::RichText(title.clone())]);
+        let text = text.trim();
+        (!text.is_empty()).then(|| text.to_string())
+    }
+
+    /// The table of contents entry for this section numbered `number`, e.g. `2` or `2.1`, with
+    /// entries for its titled subsections numbered below it. Untitled sections get no entry.
+    pub fn toc_entry(&self, number: &str) -> Option<TocNode> {
+        let title = self.title_text()?;
+        let title = if number.contains('.') {
+            format!("{} {}", number, title)
+        } else {
+            format!("{}. {}", number, title)
+        };
+        let children = titled_subsections(self)
+            .enumerate()
+            .filter_map(|(i, subsection)| subsection.toc_entry(&format!("{}.{}", number, i + 1)))
+            .collect();
+        Some(TocNode {
+            title,
+            target_id: self.id.clone(),
+            children,
+        })
+    }
+}
+
+impl DocumentStructure {
+    /// A table of contents listing the titled body sections in order, numbered `1.`, `2.`, ...
+    /// and their titled subsections `1.1`, `1.2`, ..., under a root entry titled `title`. `None`
+    /// when no body section has a title.
+    pub fn generate_toc(&self, title: &str) -> Option<TocNode> {
+        let children: Vec<TocNode> = self
+            .body
+            .iter()
+            .filter(|section| section.title_text().is_some())
+            .enumerate()
+            .filter_map(|(i, section)| section.toc_entry(&(i + 1).to_string()))
+            .collect();
+        (!children.is_empty()).then(|| TocNode {
+            title: title.to_string(),
+            target_id: String::new(), // the document itself
+            children,
+        })
+    }
+
+    /// Where the table of contents disagrees with the body: entries whose target is not a body
+    /// section, entries whose title is not the section's (numbering prefixes aside), entries out
+    /// of document order, and titled sections without an entry. A missing table of contents is
+    /// not a problem.
+    pub fn check_toc(&self) -> Vec<TocIssue> {
+        let Some(toc) = &self.table_of_contents else {
+            return vec![];
+        };
+        let mut sections = vec![];
+        for section in &self.body {
+            collect_titled(section, &mut sections);
+        }
+
+        let mut issues = vec![];
+        let mut listed = vec![];
+        let mut last_position = None;
+        for entry in toc_entries(toc) {
+            let Some(position) = sections.iter().position(|s| s.id == entry.target_id) else {
+                issues.push(TocIssue::MissingTarget {
+                    target_id: entry.target_id.clone(),
+                });
+                continue;
+            };
+            let section = sections[position];
+            let section_title = section.title_text().unwrap_or_default();
+            if strip_numbering(&entry.title) != section_title {
+                issues.push(TocIssue::TitleMismatch {
+                    target_id: entry.target_id.clone(),
+                    toc_title: entry.title.clone(),
+                    section_title,
+                });
+            }
+            if last_position.is_some_and(|last| position < last) {
+                issues.push(TocIssue::OutOfOrder {
+                    target_id: entry.target_id.clone(),
+                });
+            }
+            last_position = Some(position);
+            listed.push(position);
+        }
+        for (position, section) in sections.iter().enumerate() {
+            if !listed.contains(&position) {
+                issues.push(TocIssue::MissingEntry {
+                    section_id: section.id.clone(),
+                });
+            }
+        }
+        issues
+    }
+
+    /// Replaces the table of contents with `generate_toc(title)` if `check_toc` finds a problem.
+    /// Returns whether it was replaced.
+    pub fn repair_toc(&mut self, title: &str) -> bool {
+        if self.table_of_contents.is_none() || self.check_toc().is_empty() {
+            return false;
+        }
+        self.table_of_contents = self.generate_toc(title);
+        true
+    }
+}
+
+impl MathDocument {
+    /// `DocumentStructure::check_toc` for structured documents; other documents have no table of
+    /// contents to check.
+    pub fn check_toc(&self) -> Vec<TocIssue> {
+        self.content_type
+            .structure()
+            .map(DocumentStructure::check_toc)
+            .unwrap_or_default()
+    }
+
+    /// `DocumentStructure::repair_toc`, with the document's title as the root entry's.
+    pub fn repair_toc(&mut self) -> bool {
+        let title = self.content_type.title().unwrap_or_default().to_string();
+        self.content_type
+            .structure_mut()
+            .is_some_and(|structure| structure.repair_toc(&title))
+    }
+}
+
+fn titled_subsections(section: &Section) -> impl Iterator<Item = &Section> {
+    let subsections = match &section.content {
+        SectionContentNode::SubSection(subsections) => subsections.as_slice(),
+        _ => &[],
+    };
+    subsections
+        .iter()
+        .filter(|subsection| subsection.title_text().is_some())
+}
+
+/// The titled sections a table of contents lists, in document order.
+fn collect_titled<'a>(section: &'a Section, sections: &mut Vec<&'a Section>) {
+    if section.title_text().is_none() {
+        return;
+    }
+    sections.push(section);
+    for subsection in titled_subsections(section) {
+        collect_titled(subsection, sections);
+    }
+}
+
+/// Every entry below `toc` that targets a section, in pre-order.
+fn toc_entries(toc: &TocNode) -> Vec<&TocNode> {
+    let mut entries = vec![];
+    if !toc.target_id.is_empty() {
+        entries.push(toc);
+    }
+    for child in &toc.children {
+        entries.extend(toc_entries(child));
+    }
+    entries
+}
+
+/// `title` without a leading section number such as `2.` or `2.1`.
+fn strip_numbering(title: &str) -> &str {
+    let title = title.trim();
+    let rest = title.trim_start_matches(|c: char| c.is_ascii_digit() || c == '.');
+    if rest.len() < title.len() && rest.starts_with(char::is_whitespace) {
+        rest.trim_start()
+    } else {
+        title
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TocIssue {
+    MissingTarget {
+        target_id: String,
+    },
+    TitleMismatch {
+        target_id: String,
+        toc_title: String,
+        section_title: String,
+    },
+    OutOfOrder {
+        target_id: String,
+    },
+    MissingEntry {
+        section_id: String,
+    },
+}
+
+impl fmt::Display for TocIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TocIssue::MissingTarget { target_id } => write!(
+                f,
+                "table of contents lists `{}`, which is not a body section",
+                target_id
+            ),
+            TocIssue::TitleMismatch {
+                target_id,
+                toc_title,
+                section_title,
+            } => write!(
+                f,
+                "table of contents entry `{}` for section `{}` is titled `{}` in the body",
+                toc_title, target_id, section_title
+            ),
+            TocIssue::OutOfOrder { target_id } => write!(
+                f,
+                "table of contents lists section `{}` out of document order",
+                target_id
+            ),
+            TocIssue::MissingEntry { section_id } => {
+                write!(
+                    f,
+                    "section `{}` is missing from the table of contents",
+                    section_id
+                )
+            }
+        }
+    }
+}
@@ -0,0 +1,212 @@
+use super::*;
+use std::any::TypeId;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::path::PathBuf;
+use ts_rs::{TS, TypeVisitor};
+
+/// Enums that get type guards in `index.ts`.
+pub const GUARDED_ENUMS: &[&str] = &["MathNodeContent", "SectionContentNode"];
+
+/// `index.ts` of the bindings: every exported type, re-exported from one module so that
+/// consumers import from the bindings rather than from each file, plus tag and type-guard
+/// helpers for the `GUARDED_ENUMS`. Types come after the types they refer to, apart from
+/// mutually recursive ones.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeScriptIndex {
+    pub types: Vec<String>, // in dependency order
+    pub guarded_enums: Vec<(String, Vec<EnumVariant>)>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnumVariant {
+    pub tag: String,
+    pub has_data: bool, // `{ "kind": "Tag", "data": ... }` rather than `{ "kind": "Tag" }`
+}
+
+impl TypeScriptIndex {
+    pub fn current() -> TypeScriptIndex {
+        let mut collector = DependencyCollector::default();
+        SchemaManifest::visit_exported_types(&mut collector);
+
+        let mut types = vec![];
+        let mut visited = HashSet::new();
+        for name in collector.dependencies.keys() {
+            order_dependencies(name, &collector.dependencies, &mut visited, &mut types);
+        }
+        let guarded_enums = GUARDED_ENUMS
+            .iter()
+            .filter_map(|name| {
+                let declaration = collector.declarations.get(*name)?;
+                Some((name.to_string(), enum_variants(declaration)))
+            })
+            .collect();
+        TypeScriptIndex {
+            types,
+            guarded_enums,
+        }
+    }
+
+    pub fn to_typescript(&self) -> String {
+        let mut ts = "// This file was generated by turn-render. Do not edit this file manually.\n"
+            .to_string();
+        for name in &self.types {
+            ts.push_str(&format!(
+                "export type {{ {} }} from \"./{}\";\n",
+                name, name
+            ));
+        }
+        if !self.guarded_enums.is_empty() {
+            ts.push('\n');
+        }
+        for (name, _) in &self.guarded_enums {
+            ts.push_str(&format!(
+                "import type {{ {} }} from \"./{}\";\n",
+                name, name
+            ));
+        }
+        for (name, variants) in &self.guarded_enums {
+            ts.push_str(&enum_helpers(name, variants));
+        }
+        ts
+    }
+
+    /// Writes the index as `index.ts` to the directory the bindings are exported to:
+    /// `TS_RS_EXPORT_DIR`, or `./bindings`.
+    pub fn export() -> std::io::Result<PathBuf> {
+        let directory = std::env::var_os("TS_RS_EXPORT_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from("./bindings"));
+        std::fs::create_dir_all(&directory)?;
+        let path = directory.join("index.ts");
+        std::fs::write(&path, TypeScriptIndex::current().to_typescript())?;
+        Ok(path)
+    }
+}
+
+fn enum_helpers(name: &str, variants: &[EnumVariant]) -> String {
+    let tags = variants
+        .iter()
+        .map(|variant| format!("\n  | \"{}\"", variant.tag))
+        .collect::<String>();
+    let mut ts = format!(
+        "\n/**\n * The variant of a `{name}`.\n */\nexport type {name}Tag ={tags};\n\nexport function {function}Tag(value: {name}): {name}Tag {{\n  return value.{key};\n}}\n",
+        function = lower_first(name),
+        key = ENUM_TAG_KEY,
+    );
+    for variant in variants {
+        ts.push_str(&format!(
+            "\nexport function is{name}{tag}(\n  value: {name},\n): value is Extract<{name}, {{ {key}: \"{tag}\" }}> {{\n  return value.{key} === \"{tag}\";\n}}\n",
+            tag = variant.tag,
+            key = ENUM_TAG_KEY,
+        ));
+    }
+    ts
+}
+
+fn lower_first(name: &str) -> String {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .map(|first| first.to_lowercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+/// Depth-first, each type after its dependencies.
+fn order_dependencies(
+    name: &str,
+    dependencies: &BTreeMap<String, BTreeSet<String>>,
+    visited: &mut HashSet<String>,
+    order: &mut Vec<String>,
+) {
+    if !visited.insert(name.to_string()) {
+        return;
+    }
+    for dependency in dependencies.get(name).into_iter().flatten() {
+        order_dependencies(dependency, dependencies, visited, order);
+    }
+    order.push(name.to_string());
+}
+
+/// The variants of an adjacently tagged enum's declaration,
+/// `{ "kind": "A" } | { "kind": "B", "data": ... } | ...`.
+fn enum_variants(declaration: &str) -> Vec<EnumVariant> {
+    let body = declaration.split_once('=').map_or("", |(_, body)| body);
+    let tag_key = format!("\"{}\"", ENUM_TAG_KEY);
+    let mut variants = vec![];
+    let mut depth = 0;
+    let mut rest = body;
+    while let Some(c) = rest.chars().next() {
+        if rest.starts_with("/*") {
+            rest = rest.find("*/").map_or("", |end| &rest[end + 2..]);
+            continue;
+        }
+        if c == '"' {
+            let end = rest[1..].find('"').map_or(rest.len(), |end| end + 2);
+            rest = &rest[end..];
+            continue;
+        }
+        if c == '{'
+            && depth == 0
+            && let Some(variant) = rest[1..].trim_start().strip_prefix(tag_key.as_str())
+            && let Some(variant) = variant.trim_start().strip_prefix(':')
+            && let Some(variant) = variant.trim_start().strip_prefix('"')
+            && let Some((tag, after)) = variant.split_once('"')
+        {
+            variants.push(EnumVariant {
+                tag: tag.to_string(),
+                has_data: after.trim_start().starts_with(','),
+            });
+        }
+        match c {
+            '{' | '[' | '<' | '(' => depth += 1,
+            '}' | ']' | '>' | ')' => depth -= 1,
+            _ => {}
+        }
+        rest = &rest[c.len_utf8()..];
+    }
+    variants
+}
+
+/// The exported types with the exported types each one refers to directly.
+#[derive(Default)]
+struct DependencyCollector {
+    seen: HashSet<TypeId>,
+    dependencies: BTreeMap<String, BTreeSet<String>>,
+    declarations: BTreeMap<String, String>,
+}
+
+impl TypeVisitor for DependencyCollector {
+    fn visit<T: TS + 'static + ?Sized>(&mut self) {
+        if !self.seen.insert(TypeId::of::<T>()) {
+            return;
+        }
+        if T::output_path().is_some() {
+            let mut direct = DirectDependencies::default();
+            T::visit_dependencies(&mut direct);
+            direct.names.remove(&T::ident());
+            self.dependencies.insert(T::ident(), direct.names);
+            self.declarations.insert(T::ident(), T::decl());
+        }
+        T::visit_dependencies(self);
+    }
+}
+
+/// The exported types among a type's dependencies, looking through others like `Vec<T>`.
+#[derive(Default)]
+struct DirectDependencies {
+    seen: HashSet<TypeId>,
+    names: BTreeSet<String>,
+}
+
+impl TypeVisitor for DirectDependencies {
+    fn visit<T: TS + 'static + ?Sized>(&mut self) {
+        if !self.seen.insert(TypeId::of::<T>()) {
+            return;
+        }
+        if T::output_path().is_some() {
+            self.names.insert(T::ident());
+        } else {
+            T::visit_dependencies(self);
+        }
+    }
+}
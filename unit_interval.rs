@@ -0,0 +1,81 @@
+use serde::{Deserialize, Deserializer, Serialize, de};
+use std::fmt;
+use ts_rs::TS;
+
+/// A number between 0.0 and 1.0 inclusive, for confidences, strengths and similar weights.
+/// Serialized as a plain number; deserializing a number outside the interval fails, so
+/// out-of-range data never reaches the frontend.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default, Serialize, TS)]
+#[ts(export)]
+pub struct UnitInterval(f64);
+
+impl UnitInterval {
+    pub const ZERO: UnitInterval = UnitInterval(0.0);
+    pub const ONE: UnitInterval = UnitInterval(1.0);
+
+    /// Fails for values outside `[0, 1]` and NaN.
+    pub fn new(value: f64) -> Result<Self, UnitIntervalError> {
+        if (0.0..=1.0).contains(&value) {
+            Ok(UnitInterval(value))
+        } else {
+            Err(UnitIntervalError::OutOfRange { value })
+        }
+    }
+
+    /// `value` clamped into `[0, 1]`; NaN becomes 0.
+    pub fn clamped(value: f64) -> Self {
+        if value.is_nan() {
+            UnitInterval::ZERO
+        } else {
+            UnitInterval(value.clamp(0.0, 1.0))
+        }
+    }
+
+    pub fn value(self) -> f64 {
+        self.0
+    }
+}
+
+impl TryFrom<f64> for UnitInterval {
+    type Error = UnitIntervalError;
+
+    fn try_from(value: f64) -> Result<Self, Self::Error> {
+        UnitInterval::new(value)
+    }
+}
+
+impl From<UnitInterval> for f64 {
+    fn from(value: UnitInterval) -> Self {
+        value.0
+    }
+}
+
+impl<'de> Deserialize<'de> for UnitInterval {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = f64::deserialize(deserializer)?;
+        UnitInterval::new(value).map_err(de::Error::custom)
+    }
+}
+
+impl fmt::Display for UnitInterval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum UnitIntervalError {
+    OutOfRange { value: f64 },
+}
+
+impl fmt::Display for UnitIntervalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UnitIntervalError::OutOfRange { value } => {
+                write!(f, "{} is not between 0 and 1", value)
+            }
+        }
+    }
+}
+
+impl std::error::Error for UnitIntervalError {}
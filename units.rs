@@ -0,0 +1,841 @@
+use super::*;
+use std::{
+    collections::BTreeMap,
+    fmt,
+    sync::{Arc, OnceLock},
+};
+
+/// Exponents of the SI base dimensions, e.g. `L M T^-2` for a force.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Dimension {
+    pub length: i32,
+    pub mass: i32,
+    pub time: i32,
+    pub current: i32,
+    pub temperature: i32,
+    pub amount: i32,
+    pub luminous_intensity: i32,
+}
+
+impl Dimension {
+    pub const DIMENSIONLESS: Dimension = Dimension {
+        length: 0,
+        mass: 0,
+        time: 0,
+        current: 0,
+        temperature: 0,
+        amount: 0,
+        luminous_intensity: 0,
+    };
+
+    pub fn is_dimensionless(&self) -> bool {
+        *self == Dimension::DIMENSIONLESS
+    }
+
+    fn exponents(&self) -> [(&'static str, i32); 7] {
+        [
+            ("L", self.length),
+            ("M", self.mass),
+            ("T", self.time),
+            ("I", self.current),
+            ("Θ", self.temperature),
+            ("N", self.amount),
+            ("J", self.luminous_intensity),
+        ]
+    }
+
+    fn combine(self, other: Dimension, times: i32) -> Dimension {
+        Dimension {
+            length: self.length + other.length * times,
+            mass: self.mass + other.mass * times,
+            time: self.time + other.time * times,
+            current: self.current + other.current * times,
+            temperature: self.temperature + other.temperature * times,
+            amount: self.amount + other.amount * times,
+            luminous_intensity: self.luminous_intensity + other.luminous_intensity * times,
+        }
+    }
+}
+
+impl fmt::Display for Dimension {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_dimensionless() {
+            return write!(f, "1");
+        }
+        let factors: Vec<String> = self
+            .exponents()
+            .iter()
+            .filter(|(_, exponent)| *exponent != 0)
+            .map(|(symbol, exponent)| match exponent {
+                1 => symbol.to_string(),
+                exponent => format!("{}^{}", symbol, exponent),
+            })
+            .collect();
+        write!(f, "{}", factors.join(" "))
+    }
+}
+
+/// A unit as a multiple of the coherent SI unit of its dimension: `km` is 1000 m, `eV` is
+/// 1.602176634e-19 J and `g` is 0.001 kg.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SiUnit {
+    pub factor: f64,
+    pub dimension: Dimension,
+}
+
+impl SiUnit {
+    pub const ONE: SiUnit = SiUnit {
+        factor: 1.0,
+        dimension: Dimension::DIMENSIONLESS,
+    };
+
+    /// `value` in this unit, expressed in `target`.
+    pub fn convert(&self, value: f64, target: &SiUnit) -> Result<f64, UnitError> {
+        if self.dimension != target.dimension {
+            return Err(UnitError::Incompatible {
+                from: self.dimension,
+                to: target.dimension,
+            });
+        }
+        Ok(value * self.factor / target.factor)
+    }
+
+    fn multiply(self, other: SiUnit, exponent: i32) -> SiUnit {
+        SiUnit {
+            factor: self.factor * other.factor.powi(exponent),
+            dimension: self.dimension.combine(other.dimension, exponent),
+        }
+    }
+}
+
+impl BaseUnitTypeNode {
+    pub fn symbol(&self) -> &str {
+        match self {
+            BaseUnitTypeNode::Unitless => "",
+            BaseUnitTypeNode::Meter => "m",
+            BaseUnitTypeNode::Second => "s",
+            BaseUnitTypeNode::Gram => "g",
+            BaseUnitTypeNode::Ampere => "A",
+            BaseUnitTypeNode::Mole => "mol",
+            BaseUnitTypeNode::Kelvin => "K",
+            BaseUnitTypeNode::Candela => "cd",
+            BaseUnitTypeNode::Newton => "N",
+            BaseUnitTypeNode::Joul => "J",
+            BaseUnitTypeNode::Hertz => "Hz",
+            BaseUnitTypeNode::Pascal => "Pa",
+            BaseUnitTypeNode::Volt => "V",
+            BaseUnitTypeNode::Ohm => "Ω",
+            BaseUnitTypeNode::Steradian => "sr",
+            BaseUnitTypeNode::Watt => "W",
+            BaseUnitTypeNode::Coulumb => "C",
+            BaseUnitTypeNode::Siemens => "S",
+            BaseUnitTypeNode::Lux => "lx",
+            BaseUnitTypeNode::Lumen => "lm",
+            BaseUnitTypeNode::Weber => "Wb",
+            BaseUnitTypeNode::Tesla => "T",
+            BaseUnitTypeNode::Decibel => "dB",
+            BaseUnitTypeNode::Henry => "H",
+            BaseUnitTypeNode::Hour => "h",
+            BaseUnitTypeNode::Minute => "min",
+            BaseUnitTypeNode::Custom(symbol) => symbol,
+        }
+    }
+
+    pub fn si_unit(&self) -> Result<SiUnit, UnitError> {
+        UnitRegistry::built_in().base_si_unit(self)
+    }
+}
+
+impl UnitComponent {
+    pub fn si_unit(&self) -> Result<SiUnit, UnitError> {
+        UnitRegistry::built_in().component_si_unit(self)
+    }
+}
+
+impl MathNode {
+    /// The unit of a `Quantity`, or the unit this unit expression stands for.
+    ///
+    /// Unit expressions are unit symbols, with an SI prefix where it applies (`km`, `µs`, `MeV`),
+    /// combined by products, divisions, fractions and integer powers, as identifiers, strings or
+    /// a `Unit` node's `flattened_form`. A quantity without a unit is dimensionless.
+    ///
+    /// Symbols are looked up in the built-in units, see `UnitRegistry::si_unit` for others.
+    pub fn si_unit(&self) -> Result<SiUnit, UnitError> {
+        UnitRegistry::built_in().si_unit(self)
+    }
+
+    pub fn dimension(&self) -> Result<Dimension, UnitError> {
+        self.si_unit().map(|unit| unit.dimension)
+    }
+
+    /// The value of a `Quantity` expressed in `unit`, e.g. 1.5 for `1500 m` in `km`.
+    pub fn quantity_value_in(&self, unit: &MathNode) -> Result<f64, UnitError> {
+        UnitRegistry::built_in().quantity_value_in(self, unit)
+    }
+
+    /// A `Unit` node for the unit expression `original_form`, with its `flattened_form` derived:
+    /// a single product of symbols with integer exponents, each symbol once, e.g. `kg·m·s^-2`
+    /// for `kg·m/s²`. Symbols are kept as written; use `si_unit` to compare units.
+    pub fn unit(original_form: MathNode) -> Result<MathNode, UnitError> {
+        let flattened_form = flatten_unit(&original_form)?;
+        Ok(MathNode {
+            id: format!("{}-unit", original_form.id),
+            content: Arc::new(MathNodeContent::Unit {
+                original_form: Arc::new(original_form),
+                flattened_form: Arc::new(flattened_form),
+            }),
+        })
+    }
+}
+
+/// The flattened form of the unit expression `unit`, see `MathNode::unit`.
+pub fn flatten_unit(unit: &MathNode) -> Result<MathNode, UnitError> {
+    let mut factors: Vec<(String, i32)> = vec![];
+    unit_factors(unit, 1, &mut factors)?;
+    let mut merged: Vec<(String, i32)> = vec![];
+    for (symbol, exponent) in factors {
+        match merged.iter_mut().find(|(merged, _)| *merged == symbol) {
+            Some((_, total)) => *total += exponent,
+            None => merged.push((symbol, exponent)),
+        }
+    }
+    merged.retain(|(_, exponent)| *exponent != 0);
+
+    let id = format!("{}-flat", unit.id);
+    let mut terms: Vec<MathNode> = merged
+        .into_iter()
+        .enumerate()
+        .map(|(index, (symbol, exponent))| {
+            let base = MathNode {
+                id: format!("{}-{}", id, index),
+                content: Arc::new(MathNodeContent::Identifier(Identifier::new_simple(symbol))),
+            };
+            if exponent == 1 {
+                return base;
+            }
+            MathNode {
+                id: format!("{}-{}-power", id, index),
+                content: Arc::new(MathNodeContent::Power {
+                    exponent: Arc::new(MathNode {
+                        id: format!("{}-{}-exponent", id, index),
+                        content: Arc::new(MathNodeContent::Quantity {
+                            number: exponent.to_string(),
+                            scientific_notation: None,
+                            unit: None,
+                        }),
+                    }),
+                    base: Arc::new(base),
+                }),
+            }
+        })
+        .collect();
+    let content = match terms.len() {
+        0 => MathNodeContent::Quantity {
+            number: "1".to_string(),
+            scientific_notation: None,
+            unit: None,
+        },
+        1 => return Ok(terms.remove(0)),
+        _ => MathNodeContent::BinaryOperation {
+            operation_type: BinaryOperationType::Multiplication,
+            terms: terms
+                .into_iter()
+                .map(|term| (BinaryOperator::Dot, term))
+                .collect(),
+        },
+    };
+    Ok(MathNode {
+        id,
+        content: Arc::new(content),
+    })
+}
+
+/// The unit symbols in `unit`, each with its exponent, multiplied by `sign`.
+fn unit_factors(
+    unit: &MathNode,
+    sign: i32,
+    factors: &mut Vec<(String, i32)>,
+) -> Result<(), UnitError> {
+    match unit.content.as_ref() {
+        MathNodeContent::Quantity { unit, .. } => {
+            if let Some(unit) = unit {
+                unit_factors(unit, sign, factors)?
+            }
+        }
+        MathNodeContent::Unit { flattened_form, .. } => {
+            unit_factors(flattened_form, sign, factors)?
+        }
+        MathNodeContent::Bracketed { inner, .. }
+        | MathNodeContent::Folded { content: inner, .. } => unit_factors(inner, sign, factors)?,
+        MathNodeContent::Identifier(identifier) if identifier.pre_script.is_none() => {
+            let exponent = match &identifier.post_script {
+                None => 1,
+                Some(script) => {
+                    match (script.subscripts.as_slice(), script.superscripts.as_slice()) {
+                        ([], []) => 1,
+                        ([], [exponent]) => integer_exponent(exponent)?,
+                        _ => return Err(not_a_unit(unit)),
+                    }
+                }
+            };
+            factors.push((identifier.body.clone(), exponent * sign));
+        }
+        MathNodeContent::Text(symbol) | MathNodeContent::String(symbol) => {
+            factors.push((symbol.trim().to_string(), sign))
+        }
+        MathNodeContent::Power { base, exponent } => {
+            let mut base_factors = vec![];
+            unit_factors(base, sign, &mut base_factors)?;
+            let exponent = integer_exponent(exponent)?;
+            factors.extend(
+                base_factors
+                    .into_iter()
+                    .map(|(symbol, power)| (symbol, power * exponent)),
+            );
+        }
+        MathNodeContent::Fraction {
+            numerator,
+            denominator,
+        }
+        | MathNodeContent::Division {
+            numerator,
+            denominator,
+            ..
+        } => {
+            unit_factors(numerator, sign, factors)?;
+            unit_factors(denominator, -sign, factors)?;
+        }
+        MathNodeContent::BinaryOperation { terms, .. } => {
+            for (operator, term) in terms {
+                match operator {
+                    BinaryOperator::Times | BinaryOperator::Dot => {
+                        unit_factors(term, sign, factors)?
+                    }
+                    BinaryOperator::Slash | BinaryOperator::Divide => {
+                        unit_factors(term, -sign, factors)?
+                    }
+                    _ => return Err(not_a_unit(unit)),
+                }
+            }
+        }
+        MathNodeContent::Multiplications { terms } => {
+            for (operator, term) in terms {
+                match operator {
+                    RefinedMulOrDivOperation::Division(_) => unit_factors(term, -sign, factors)?,
+                    RefinedMulOrDivOperation::Multiplication(_)
+                    | RefinedMulOrDivOperation::None => unit_factors(term, sign, factors)?,
+                }
+            }
+        }
+        _ => return Err(not_a_unit(unit)),
+    }
+    Ok(())
+}
+
+fn not_a_unit(node: &MathNode) -> UnitError {
+    UnitError::NotAUnit {
+        kind: node.content.kind_name(),
+    }
+}
+
+fn integer_exponent(exponent: &MathNode) -> Result<i32, UnitError> {
+    match exponent.evaluate(&BTreeMap::new()) {
+        Ok(NumericValue::Rational {
+            numerator,
+            denominator: 1,
+        }) => i32::try_from(numerator).map_err(|_| UnitError::InvalidExponent),
+        Ok(_) => Err(UnitError::InvalidExponent),
+        Err(error) => Err(UnitError::Evaluation(error)),
+    }
+}
+
+const PREFIXES: &[(&str, f64)] = &[
+    ("da", 1e1),
+    ("Q", 1e30),
+    ("R", 1e27),
+    ("Y", 1e24),
+    ("Z", 1e21),
+    ("E", 1e18),
+    ("P", 1e15),
+    ("T", 1e12),
+    ("G", 1e9),
+    ("M", 1e6),
+    ("k", 1e3),
+    ("h", 1e2),
+    ("d", 1e-1),
+    ("c", 1e-2),
+    ("m", 1e-3),
+    ("µ", 1e-6),
+    ("μ", 1e-6),
+    ("u", 1e-6),
+    ("n", 1e-9),
+    ("p", 1e-12),
+    ("f", 1e-15),
+    ("a", 1e-18),
+    ("z", 1e-21),
+    ("y", 1e-24),
+    ("r", 1e-27),
+    ("q", 1e-30),
+];
+
+fn prefix_factor(prefix: &str) -> Option<f64> {
+    PREFIXES
+        .iter()
+        .find(|(symbol, _)| *symbol == prefix)
+        .map(|(_, factor)| *factor)
+}
+
+/// A unit known to a `UnitRegistry`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnitDefinition {
+    pub symbol: String,       // As written in unit expressions, e.g. "pc"
+    pub name: String,         // e.g. "parsec"
+    pub aliases: Vec<String>, // Other symbols accepted for the unit, e.g. "ohm" for "Ω"
+    pub unit: SiUnit,
+    pub prefixes: bool,              // Whether SI prefixes apply, as in `kpc`
+    pub rendering: Option<MathNode>, // How to write the symbol when not as `symbol`, e.g. M_⊙
+}
+
+impl UnitDefinition {
+    /// `factor` times the coherent SI unit of `dimension`, without prefixes. A parsec is
+    /// `new("pc", "parsec", 3.0857e16, Dimension { length: 1, ..Dimension::DIMENSIONLESS })`.
+    pub fn new(symbol: &str, name: &str, factor: f64, dimension: Dimension) -> Self {
+        UnitDefinition {
+            symbol: symbol.to_string(),
+            name: name.to_string(),
+            aliases: vec![],
+            unit: SiUnit { factor, dimension },
+            prefixes: false,
+            rendering: None,
+        }
+    }
+
+    pub fn with_aliases(mut self, aliases: &[&str]) -> Self {
+        self.aliases
+            .extend(aliases.iter().map(|alias| alias.to_string()));
+        self
+    }
+
+    pub fn with_prefixes(mut self) -> Self {
+        self.prefixes = true;
+        self
+    }
+
+    /// Writes the unit as `rendering` wherever it occurs, aliases included. With a prefix, an
+    /// identifier rendering gets the prefix in front of its body; others are left as written.
+    pub fn with_rendering(mut self, rendering: MathNode) -> Self {
+        self.rendering = Some(rendering);
+        self
+    }
+
+    fn symbols(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.symbol.as_str()).chain(self.aliases.iter().map(String::as_str))
+    }
+}
+
+/// The units that unit expressions and `BaseUnitTypeNode`s may use, by symbol. Symbols are
+/// matched exactly, so `mm` and `Mm` are different units.
+///
+/// The default knows the SI units and the units accepted for use with them; register others,
+/// such as astronomical or imperial units, to convert and render them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnitRegistry {
+    definitions: Vec<UnitDefinition>,
+}
+
+impl Default for UnitRegistry {
+    fn default() -> Self {
+        UnitRegistry::known()
+    }
+}
+
+impl UnitRegistry {
+    pub fn empty() -> Self {
+        UnitRegistry {
+            definitions: vec![],
+        }
+    }
+
+    /// The built-in units. Affine temperature scales (°C, °F) and logarithmic units (dB) have no
+    /// factor to convert by and are not included.
+    pub fn known() -> Self {
+        let dimension =
+            |length, mass, time, current, temperature, amount, luminous_intensity| Dimension {
+                length,
+                mass,
+                time,
+                current,
+                temperature,
+                amount,
+                luminous_intensity,
+            };
+        let si = |symbol, name, factor, dimension| {
+            UnitDefinition::new(symbol, name, factor, dimension).with_prefixes()
+        };
+        let definitions = vec![
+            si("m", "metre", 1.0, dimension(1, 0, 0, 0, 0, 0, 0)),
+            si("g", "gram", 1e-3, dimension(0, 1, 0, 0, 0, 0, 0)),
+            si("s", "second", 1.0, dimension(0, 0, 1, 0, 0, 0, 0)),
+            si("A", "ampere", 1.0, dimension(0, 0, 0, 1, 0, 0, 0)),
+            si("K", "kelvin", 1.0, dimension(0, 0, 0, 0, 1, 0, 0)),
+            si("mol", "mole", 1.0, dimension(0, 0, 0, 0, 0, 1, 0)),
+            si("cd", "candela", 1.0, dimension(0, 0, 0, 0, 0, 0, 1)),
+            si("rad", "radian", 1.0, Dimension::DIMENSIONLESS),
+            si("sr", "steradian", 1.0, Dimension::DIMENSIONLESS),
+            si("Hz", "hertz", 1.0, dimension(0, 0, -1, 0, 0, 0, 0)),
+            si("N", "newton", 1.0, dimension(1, 1, -2, 0, 0, 0, 0)),
+            si("Pa", "pascal", 1.0, dimension(-1, 1, -2, 0, 0, 0, 0)),
+            si("J", "joule", 1.0, dimension(2, 1, -2, 0, 0, 0, 0)),
+            si("W", "watt", 1.0, dimension(2, 1, -3, 0, 0, 0, 0)),
+            si("C", "coulomb", 1.0, dimension(0, 0, 1, 1, 0, 0, 0)),
+            si("V", "volt", 1.0, dimension(2, 1, -3, -1, 0, 0, 0)),
+            si("F", "farad", 1.0, dimension(-2, -1, 4, 2, 0, 0, 0)),
+            si("Ω", "ohm", 1.0, dimension(2, 1, -3, -2, 0, 0, 0)).with_aliases(&["ohm"]),
+            si("S", "siemens", 1.0, dimension(-2, -1, 3, 2, 0, 0, 0)),
+            si("Wb", "weber", 1.0, dimension(2, 1, -2, -1, 0, 0, 0)),
+            si("T", "tesla", 1.0, dimension(0, 1, -2, -1, 0, 0, 0)),
+            si("H", "henry", 1.0, dimension(2, 1, -2, -2, 0, 0, 0)),
+            si("lm", "lumen", 1.0, dimension(0, 0, 0, 0, 0, 0, 1)),
+            si("lx", "lux", 1.0, dimension(-2, 0, 0, 0, 0, 0, 1)),
+            si("Bq", "becquerel", 1.0, dimension(0, 0, -1, 0, 0, 0, 0)),
+            si("Gy", "gray", 1.0, dimension(2, 0, -2, 0, 0, 0, 0)),
+            si("Sv", "sievert", 1.0, dimension(2, 0, -2, 0, 0, 0, 0)),
+            si("kat", "katal", 1.0, dimension(0, 0, -1, 0, 0, 1, 0)),
+            UnitDefinition::new("min", "minute", 60.0, dimension(0, 0, 1, 0, 0, 0, 0)),
+            UnitDefinition::new("h", "hour", 3600.0, dimension(0, 0, 1, 0, 0, 0, 0)),
+            UnitDefinition::new("d", "day", 86400.0, dimension(0, 0, 1, 0, 0, 0, 0)),
+            si("L", "litre", 1e-3, dimension(3, 0, 0, 0, 0, 0, 0)).with_aliases(&["l"]),
+            si("t", "tonne", 1e3, dimension(0, 1, 0, 0, 0, 0, 0)),
+            si(
+                "Da",
+                "dalton",
+                1.660_539_066_60e-27,
+                dimension(0, 1, 0, 0, 0, 0, 0),
+            )
+            .with_aliases(&["u"]),
+            si(
+                "eV",
+                "electronvolt",
+                1.602_176_634e-19,
+                dimension(2, 1, -2, 0, 0, 0, 0),
+            ),
+            si("cal", "calorie", 4.184, dimension(2, 1, -2, 0, 0, 0, 0)),
+            si("bar", "bar", 1e5, dimension(-1, 1, -2, 0, 0, 0, 0)),
+            UnitDefinition::new(
+                "atm",
+                "atmosphere",
+                101_325.0,
+                dimension(-1, 1, -2, 0, 0, 0, 0),
+            ),
+            UnitDefinition::new("Å", "ångström", 1e-10, dimension(1, 0, 0, 0, 0, 0, 0)),
+            UnitDefinition::new(
+                "au",
+                "astronomical unit",
+                149_597_870_700.0,
+                dimension(1, 0, 0, 0, 0, 0, 0),
+            ),
+            si(
+                "ly",
+                "light-year",
+                9_460_730_472_580_800.0,
+                dimension(1, 0, 0, 0, 0, 0, 0),
+            ),
+            UnitDefinition::new(
+                "°",
+                "degree",
+                std::f64::consts::PI / 180.0,
+                Dimension::DIMENSIONLESS,
+            ),
+        ];
+        UnitRegistry { definitions }
+    }
+
+    /// The known units, built once and shared.
+    pub fn built_in() -> &'static UnitRegistry {
+        static BUILT_IN: OnceLock<UnitRegistry> = OnceLock::new();
+        BUILT_IN.get_or_init(UnitRegistry::known)
+    }
+
+    pub fn definitions(&self) -> &[UnitDefinition] {
+        &self.definitions
+    }
+
+    /// Adds a unit. Fails if its symbol or one of its aliases already names another unit.
+    pub fn register(&mut self, definition: UnitDefinition) -> Result<(), UnitError> {
+        if let Some(symbol) = definition
+            .symbols()
+            .find(|symbol| self.resolve(symbol).is_some())
+        {
+            return Err(UnitError::DuplicateSymbol {
+                symbol: symbol.to_string(),
+            });
+        }
+        self.definitions.push(definition);
+        Ok(())
+    }
+
+    /// The unit written `symbol`, by its symbol or an alias and without a prefix.
+    pub fn resolve(&self, symbol: &str) -> Option<&UnitDefinition> {
+        self.definitions
+            .iter()
+            .find(|definition| definition.symbols().any(|known| known == symbol))
+    }
+
+    /// The unit written `symbol` with the SI prefix it carries, if any: `k` and the metre for
+    /// `km`. A registered symbol takes precedence over a prefixed reading, so `min` is a minute.
+    pub fn lookup(
+        &self,
+        symbol: &str,
+    ) -> Result<(Option<&'static str>, &UnitDefinition), UnitError> {
+        if let Some(definition) = self.resolve(symbol) {
+            return Ok((None, definition));
+        }
+        PREFIXES
+            .iter()
+            .find_map(|(prefix, _)| {
+                let definition = self.resolve(symbol.strip_prefix(prefix)?)?;
+                definition.prefixes.then_some((Some(*prefix), definition))
+            })
+            .ok_or_else(|| UnitError::UnknownUnit {
+                symbol: symbol.to_string(),
+            })
+    }
+
+    /// The unit written `symbol`, with its prefix applied.
+    pub fn symbol_si_unit(&self, symbol: &str) -> Result<SiUnit, UnitError> {
+        let (prefix, definition) = self.lookup(symbol)?;
+        let scale = prefix.and_then(prefix_factor).unwrap_or(1.0);
+        Ok(SiUnit {
+            factor: definition.unit.factor * scale,
+            ..definition.unit
+        })
+    }
+
+    pub fn base_si_unit(&self, unit: &BaseUnitTypeNode) -> Result<SiUnit, UnitError> {
+        match unit {
+            BaseUnitTypeNode::Unitless => Ok(SiUnit::ONE),
+            unit => self.symbol_si_unit(unit.symbol()),
+        }
+    }
+
+    pub fn component_si_unit(&self, unit: &UnitComponent) -> Result<SiUnit, UnitError> {
+        match unit {
+            UnitComponent::BaseUnit { name, prefix } => {
+                let unit = self.base_si_unit(name)?;
+                let Some(prefix) = prefix.as_deref().filter(|prefix| !prefix.is_empty()) else {
+                    return Ok(unit);
+                };
+                let scale = prefix_factor(prefix)
+                    .filter(|_| {
+                        self.resolve(name.symbol())
+                            .is_some_and(|definition| definition.prefixes)
+                    })
+                    .ok_or_else(|| UnitError::UnknownUnit {
+                        symbol: format!("{}{}", prefix, name.symbol()),
+                    })?;
+                Ok(SiUnit {
+                    factor: unit.factor * scale,
+                    ..unit
+                })
+            }
+            UnitComponent::CompoundUnit { components } => {
+                components
+                    .iter()
+                    .try_fold(SiUnit::ONE, |unit, (component, exponent)| {
+                        Ok(unit.multiply(
+                            self.component_si_unit(component)?,
+                            integer_exponent(exponent)?,
+                        ))
+                    })
+            }
+        }
+    }
+
+    /// The unit of a `Quantity`, or the unit this unit expression stands for, see
+    /// `MathNode::si_unit`.
+    pub fn si_unit(&self, unit: &MathNode) -> Result<SiUnit, UnitError> {
+        let mut factors = vec![];
+        unit_factors(unit, 1, &mut factors)?;
+        factors
+            .into_iter()
+            .try_fold(SiUnit::ONE, |si_unit, (symbol, exponent)| {
+                Ok(si_unit.multiply(self.symbol_si_unit(&symbol)?, exponent))
+            })
+    }
+
+    /// The value of `quantity` expressed in `unit`, see `MathNode::quantity_value_in`.
+    pub fn quantity_value_in(
+        &self,
+        quantity: &MathNode,
+        unit: &MathNode,
+    ) -> Result<f64, UnitError> {
+        let MathNodeContent::Quantity {
+            number,
+            scientific_notation,
+            ..
+        } = quantity.content.as_ref()
+        else {
+            return Err(UnitError::NotAQuantity);
+        };
+        let magnitude = MathNode {
+            id: quantity.id.clone(),
+            content: Arc::new(MathNodeContent::Quantity {
+                number: number.clone(),
+                scientific_notation: scientific_notation.clone(),
+                unit: None,
+            }),
+        };
+        let value = magnitude
+            .evaluate_f64(&BTreeMap::new())
+            .map_err(UnitError::Evaluation)?;
+        self.si_unit(quantity)?.convert(value, &self.si_unit(unit)?)
+    }
+
+    /// `unit`, a unit expression, a `Unit` node or a `Quantity`, with its symbols written the
+    /// way their definitions prefer: aliases as the unit's symbol (`ohm` as `Ω`) and units with a
+    /// rendering as that rendering. Render backends write units through this. Symbols the
+    /// registry does not know are left as written, and so is a `Unit` node's `flattened_form`,
+    /// which stays in plain symbols.
+    pub fn render_unit(&self, unit: &MathNode) -> MathNode {
+        let content = match unit.content.as_ref() {
+            MathNodeContent::Quantity {
+                number,
+                scientific_notation,
+                unit: Some(quantity_unit),
+            } => MathNodeContent::Quantity {
+                number: number.clone(),
+                scientific_notation: scientific_notation.clone(),
+                unit: Some(self.render_unit(quantity_unit)),
+            },
+            MathNodeContent::Unit {
+                original_form,
+                flattened_form,
+            } => MathNodeContent::Unit {
+                original_form: Arc::new(self.render_unit(original_form)),
+                flattened_form: flattened_form.clone(),
+            },
+            _ => return unit.transform(&mut UnitRenderer { registry: self }),
+        };
+        MathNode {
+            id: unit.id.clone(),
+            content: Arc::new(content),
+        }
+    }
+}
+
+/// Rewrites the unit symbols of a unit expression, see `UnitRegistry::render_unit`.
+struct UnitRenderer<'a> {
+    registry: &'a UnitRegistry,
+}
+
+impl MathNodeTransformer for UnitRenderer<'_> {
+    fn transform_node(&mut self, node: &MathNode) -> Option<MathNode> {
+        let (symbol, exponent) = match node.content.as_ref() {
+            MathNodeContent::Identifier(identifier)
+                if identifier.pre_script.is_none() && identifier.mid_script.is_none() =>
+            {
+                let exponent = match &identifier.post_script {
+                    None => None,
+                    Some(script) => {
+                        match (script.subscripts.as_slice(), script.superscripts.as_slice()) {
+                            ([], []) => None,
+                            ([], [exponent]) => Some(exponent.clone()),
+                            _ => return None,
+                        }
+                    }
+                };
+                (identifier.body.as_str(), exponent)
+            }
+            MathNodeContent::Text(symbol) | MathNodeContent::String(symbol) => {
+                (symbol.trim(), None)
+            }
+            _ => return None,
+        };
+        let (prefix, definition) = self.registry.lookup(symbol).ok()?;
+        let prefix = prefix.unwrap_or("");
+        let rendering = match &definition.rendering {
+            None if symbol == format!("{}{}", prefix, definition.symbol) => return None,
+            None => MathNode {
+                id: node.id.clone(),
+                content: Arc::new(MathNodeContent::Identifier(Identifier::new_simple(
+                    format!("{}{}", prefix, definition.symbol),
+                ))),
+            },
+            Some(rendering) => {
+                // Ids of the rendering are made unique to this occurrence of the unit.
+                let rendering = rendering.transform(&mut |rendered: &MathNode| {
+                    Some(MathNode {
+                        id: format!("{}-{}", node.id, rendered.id),
+                        content: rendered.content.clone(),
+                    })
+                });
+                match rendering.content.as_ref() {
+                    _ if prefix.is_empty() => rendering,
+                    MathNodeContent::Identifier(identifier) => MathNode {
+                        id: rendering.id.clone(),
+                        content: Arc::new(MathNodeContent::Identifier(Identifier {
+                            body: format!("{}{}", prefix, identifier.body),
+                            ..identifier.clone()
+                        })),
+                    },
+                    _ => return None,
+                }
+            }
+        };
+        let Some(exponent) = exponent else {
+            return Some(rendering);
+        };
+        Some(MathNode {
+            id: format!("{}-power", node.id),
+            content: Arc::new(MathNodeContent::Power {
+                base: Arc::new(rendering),
+                exponent: Arc::new(exponent),
+            }),
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum UnitError {
+    UnknownUnit {
+        symbol: String,
+    },
+    /// A symbol or alias already taken by a registered unit.
+    DuplicateSymbol {
+        symbol: String,
+    },
+    /// A node that cannot be part of a unit expression, such as a sum.
+    NotAUnit {
+        kind: &'static str, // `MathNodeContent::kind_name` of the node
+    },
+    NotAQuantity,
+    /// An exponent that is not an integer.
+    InvalidExponent,
+    Incompatible {
+        from: Dimension,
+        to: Dimension,
+    },
+    Evaluation(EvaluationError),
+}
+
+impl fmt::Display for UnitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UnitError::UnknownUnit { symbol } => write!(f, "unknown unit `{}`", symbol),
+            UnitError::DuplicateSymbol { symbol } => {
+                write!(f, "unit symbol `{}` is already registered", symbol)
+            }
+            UnitError::NotAUnit { kind } => write!(f, "{} is not a unit", kind),
+            UnitError::NotAQuantity => write!(f, "node is not a quantity"),
+            UnitError::InvalidExponent => write!(f, "unit exponent is not an integer"),
+            UnitError::Incompatible { from, to } => write!(
+                f,
+                "cannot convert a unit of dimension {} to one of dimension {}",
+                from, to
+            ),
+            UnitError::Evaluation(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for UnitError {}
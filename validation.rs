@@ -0,0 +1,302 @@
+use super::*;
+use std::{collections::HashMap, fmt};
+
+/// What kind of element an id belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdKind {
+    Document,
+    Section,
+    Panel,
+    MathNode,
+    Control,
+    Annotation,
+    Feedback,
+    BranchingContainer,
+    BranchingNode,
+    DerivationChain,
+    Equation,
+}
+
+impl IdKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            IdKind::Document => "document",
+            IdKind::Section => "section",
+            IdKind::Panel => "panel",
+            IdKind::MathNode => "math node",
+            IdKind::Control => "control",
+            IdKind::Annotation => "annotation",
+            IdKind::Feedback => "feedback prompt",
+            IdKind::BranchingContainer => "branching container",
+            IdKind::BranchingNode => "branching node",
+            IdKind::DerivationChain => "derivation chain",
+            IdKind::Equation => "equation",
+        }
+    }
+}
+
+/// An element whose id was found, with the documents, sections and panels enclosing it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IdOccurrence {
+    pub kind: IdKind,
+    pub path: Vec<LocationStep>, // outermost first, not including the element itself
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValidationError {
+    DuplicateId {
+        id: String,
+        first: IdOccurrence,
+        duplicate: IdOccurrence,
+    },
+    DuplicateLabel {
+        label: QualifiedLabel,
+        first_section_id: String,
+        duplicate_section_id: String,
+    },
+    DanglingReference {
+        kind: ReferenceKind,
+        target: String,
+        path: Vec<LocationStep>, // where the reference was found, outermost first
+    },
+    MalformedMath {
+        root_id: String, // id of the expression, the root of the error's `NodePath`
+        path: Vec<LocationStep>,
+        error: MathNodeError,
+    },
+    InvalidInteraction {
+        document_id: String,
+        error: InteractionError,
+    },
+    InvalidProps {
+        path: Vec<LocationStep>, // where the custom component was found, outermost first
+        error: PropValidationError,
+    },
+}
+
+impl MathDocument {
+    /// Checks the document, embedded and tooltip documents included, for problems that break
+    /// rendering or linking in the frontend:
+    /// - ids of sections, panels, math nodes, controls, annotations, feedback prompts and
+    ///   branching nodes that are used more than once; all of them share the page's element id
+    ///   namespace, so a section and a math node may not share an id either
+    /// - section labels defined twice in the same document; see `validate_labels` to check them
+    ///   with embedded section references inlined
+    /// - cross references, ToC entries, footnote references, citations, embedded section
+    ///   references and page links whose target does not exist in the document; see
+    ///   `validate_references` to let them point into other documents
+    /// - structurally broken expressions, see `MathNode::validate`
+    /// - control bindings, event handlers and slide navigation pointing nowhere, see
+    ///   `validate_interactions`
+    ///
+    /// Custom component props need the host's schemas, see `validate_with`.
+    pub fn validate(&self) -> Vec<ValidationError> {
+        self.validate_against(None)
+    }
+
+    /// `validate`, also checking the props of every custom component against `registry`, see
+    /// `ComponentRegistry::validate_props`.
+    pub fn validate_with(&self, registry: &ComponentRegistry) -> Vec<ValidationError> {
+        self.validate_against(Some(registry))
+    }
+
+    fn validate_against(&self, registry: Option<&ComponentRegistry>) -> Vec<ValidationError> {
+        let mut validator = DocumentValidator {
+            registry,
+            ..DocumentValidator::default()
+        };
+        validator.visit_document(self);
+        let mut errors = validator.errors;
+        let mut labels: HashMap<&QualifiedLabel, &str> = HashMap::new();
+        let index = self.label_index();
+        for (label, section_id) in index.labels() {
+            match labels.get(label) {
+                Some(first) => errors.push(ValidationError::DuplicateLabel {
+                    label: label.clone(),
+                    first_section_id: first.to_string(),
+                    duplicate_section_id: section_id.to_string(),
+                }),
+                None => {
+                    labels.insert(label, section_id);
+                }
+            }
+        }
+        errors.extend(self.validate_references([]));
+        errors.extend(self.validate_interactions());
+        errors
+    }
+}
+
+#[derive(Default)]
+struct DocumentValidator<'doc> {
+    registry: Option<&'doc ComponentRegistry>,
+    path: Vec<LocationStep>,
+    seen: HashMap<&'doc str, IdOccurrence>,
+    errors: Vec<ValidationError>,
+}
+
+impl<'doc> DocumentValidator<'doc> {
+    fn define(&mut self, id: &'doc str, kind: IdKind) {
+        if id.is_empty() {
+            return;
+        }
+        let occurrence = IdOccurrence {
+            kind,
+            path: self.path.clone(),
+        };
+        match self.seen.get(id) {
+            Some(first) => self.errors.push(ValidationError::DuplicateId {
+                id: id.to_string(),
+                first: first.clone(),
+                duplicate: occurrence,
+            }),
+            None => {
+                self.seen.insert(id, occurrence);
+            }
+        }
+    }
+}
+
+impl<'doc> DocumentVisitor<'doc> for DocumentValidator<'doc> {
+    fn visit_document(&mut self, document: &'doc MathDocument) {
+        self.define(&document.id, IdKind::Document);
+        self.path.push(LocationStep::Document(document.id.clone()));
+        let interaction_system = match &document.content_type {
+            MathDocumentType::AnimatedPresentation(c) => Some(&c.interaction_system),
+            MathDocumentType::InteractivePlayground(c) => Some(&c.interaction_system),
+            _ => None,
+        };
+        for control in interaction_system
+            .iter()
+            .flat_map(|system| &system.controls)
+        {
+            self.define(&control.id, IdKind::Control);
+        }
+        walk_document(self, document);
+        self.path.pop();
+    }
+
+    fn visit_section(&mut self, section: &'doc Section) {
+        self.define(&section.id, IdKind::Section);
+        self.path.push(LocationStep::Section(section.id.clone()));
+        walk_section(self, section);
+        self.path.pop();
+    }
+
+    fn visit_panel(&mut self, panel: &'doc Panel) {
+        self.define(&panel.id, IdKind::Panel);
+        self.path.push(LocationStep::Panel(panel.id.clone()));
+        walk_panel(self, panel);
+        self.path.pop();
+    }
+
+    fn visit_content_node(&mut self, node: &'doc SectionContentNode) {
+        match node {
+            SectionContentNode::AnnotationOverlay(overlay) => {
+                for annotation in &overlay.annotations {
+                    self.define(&annotation.id, IdKind::Annotation);
+                }
+            }
+            SectionContentNode::InteractiveControls(controls) => {
+                for control in &controls.controls {
+                    self.define(&control.id, IdKind::Control);
+                }
+            }
+            SectionContentNode::Feedback(feedback) => self.define(&feedback.id, IdKind::Feedback),
+            SectionContentNode::DerivationChain(chain) => {
+                self.define(&chain.id, IdKind::DerivationChain)
+            }
+            SectionContentNode::Equation(equation) => self.define(&equation.id, IdKind::Equation),
+            SectionContentNode::CustomComponent {
+                component_name,
+                props,
+                ..
+            } => {
+                for error in self
+                    .registry
+                    .iter()
+                    .flat_map(|registry| registry.validate_props(component_name, props.as_ref()))
+                {
+                    self.errors.push(ValidationError::InvalidProps {
+                        path: self.path.clone(),
+                        error,
+                    });
+                }
+            }
+            SectionContentNode::BranchingContainer(container) => {
+                self.define(&container.container_id, IdKind::BranchingContainer);
+                for node in &container.nodes {
+                    self.define(&node.node_id, IdKind::BranchingNode);
+                }
+            }
+            _ => {}
+        }
+        walk_content_node(self, node);
+    }
+
+    fn visit_math_node(&mut self, node: &'doc MathNode) {
+        node.fold((), |_, node| self.define(&node.id, IdKind::MathNode));
+        for error in node.validate() {
+            self.errors.push(ValidationError::MalformedMath {
+                root_id: node.id.clone(),
+                path: self.path.clone(),
+                error,
+            });
+        }
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationError::DuplicateId {
+                id,
+                first,
+                duplicate,
+            } => write!(
+                f,
+                "id `{}` of a {} in `{}` is already used by a {} in `{}`",
+                id,
+                duplicate.kind.name(),
+                LocationStep::path_string(&duplicate.path),
+                first.kind.name(),
+                LocationStep::path_string(&first.path)
+            ),
+            ValidationError::DuplicateLabel {
+                label,
+                first_section_id,
+                duplicate_section_id,
+            } => write!(
+                f,
+                "label `{}` of section `{}` is already used by section `{}`",
+                label, duplicate_section_id, first_section_id
+            ),
+            ValidationError::MalformedMath {
+                root_id,
+                path,
+                error,
+            } => write!(
+                f,
+                "expression `{}` in `{}`: {}",
+                root_id,
+                LocationStep::path_string(path),
+                error
+            ),
+            ValidationError::DanglingReference { kind, target, path } => write!(
+                f,
+                "{} in `{}` points to `{}`, which does not exist",
+                kind.name(),
+                LocationStep::path_string(path),
+                target
+            ),
+            ValidationError::InvalidInteraction { document_id, error } => {
+                write!(f, "document `{}`: {}", document_id, error)
+            }
+            ValidationError::InvalidProps { path, error } => {
+                write!(f, "in `{}`: {}", LocationStep::path_string(path), error)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
@@ -0,0 +1,247 @@
+use super::*;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fmt};
+use ts_rs::TS;
+
+/// Document-level store backing `RichTextSegment::InteractiveVariable`.
+/// Every interactive variable id in the document should resolve to one of `declarations`,
+/// so hovering the same variable shows the same information in every section.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct VariableContext {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub declarations: Vec<InteractiveVariableDeclaration>,
+    pub bindings: BTreeMap<String, MathNode>, // variable_id -> current value
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct InteractiveVariableDeclaration {
+    pub variable_id: String,
+    pub display_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variable_type: Option<VariableTypeInfo>, // e.g. "a real number" or ℝ
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<RichText>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>, // Section id the variable is visible in (with its subsections); None for the whole document
+}
+
+/// The type of an interactive variable, either described in prose or as a math expression.
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub enum VariableTypeInfo {
+    Text(RichText),
+    Math(MathNode),
+}
+
+impl InteractiveVariableDeclaration {
+    pub fn new(variable_id: impl Into<String>, display_name: impl Into<String>) -> Self {
+        InteractiveVariableDeclaration {
+            variable_id: variable_id.into(),
+            display_name: display_name.into(),
+            variable_type: None,
+            description: None,
+            scope: None,
+        }
+    }
+
+    pub fn with_type(mut self, variable_type: VariableTypeInfo) -> Self {
+        self.variable_type = Some(variable_type);
+        self
+    }
+
+    pub fn with_description(mut self, description: RichText) -> Self {
+        self.description = Some(description);
+        self
+    }
+
+    pub fn scoped_to(mut self, section_id: impl Into<String>) -> Self {
+        self.scope = Some(section_id.into());
+        self
+    }
+
+    /// Whether the declaration is visible inside the innermost of `section_path` (outermost first).
+    pub fn is_visible_in(&self, section_path: &[&str]) -> bool {
+        match &self.scope {
+            None => true,
+            Some(scope) => section_path.iter().any(|section_id| section_id == scope),
+        }
+    }
+}
+
+impl VariableContext {
+    pub fn new() -> Self {
+        VariableContext::default()
+    }
+
+    pub fn declare(&mut self, declaration: InteractiveVariableDeclaration) {
+        self.declarations
+            .retain(|existing| existing.variable_id != declaration.variable_id);
+        self.declarations.push(declaration);
+    }
+
+    pub fn bind(&mut self, variable_id: impl Into<String>, value: MathNode) {
+        self.bindings.insert(variable_id.into(), value);
+    }
+
+    pub fn declaration(&self, variable_id: &str) -> Option<&InteractiveVariableDeclaration> {
+        self.declarations
+            .iter()
+            .find(|declaration| declaration.variable_id == variable_id)
+    }
+
+    pub fn binding(&self, variable_id: &str) -> Option<&MathNode> {
+        self.bindings.get(variable_id)
+    }
+
+    /// Looks up `variable_id` as seen from inside `section_path` (outermost section first).
+    pub fn resolve(
+        &self,
+        variable_id: &str,
+        section_path: &[&str],
+    ) -> Option<&InteractiveVariableDeclaration> {
+        self.declaration(variable_id)
+            .filter(|declaration| declaration.is_visible_in(section_path))
+    }
+
+    /// Bindings whose variable id has no declaration.
+    pub fn unknown_bindings(&self) -> Vec<&str> {
+        let mut unknown: Vec<&str> = self
+            .bindings
+            .keys()
+            .filter(|variable_id| self.declaration(variable_id).is_none())
+            .map(String::as_str)
+            .collect();
+        unknown.sort();
+        unknown
+    }
+}
+
+impl MathDocument {
+    /// Checks that every `InteractiveVariable` resolves against the document's variable context.
+    /// Embedded and tooltip documents resolve against their own context first, then the enclosing ones.
+    pub fn validate_variables(&self) -> Vec<VariableResolutionError> {
+        let mut resolver = VariableResolver::default();
+        resolver.visit_document(self);
+        resolver.errors
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum VariableResolutionError {
+    Undeclared {
+        variable_id: String,
+        section_id: Option<String>,
+    },
+    OutOfScope {
+        variable_id: String,
+        section_id: Option<String>,
+        scope: String,
+    },
+    UnknownBinding {
+        variable_id: String,
+    },
+}
+
+impl fmt::Display for VariableResolutionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VariableResolutionError::Undeclared {
+                variable_id,
+                section_id,
+            } => write!(
+                f,
+                "interactive variable `{}` used in section `{}` is not declared",
+                variable_id,
+                section_id.as_deref().unwrap_or("<document>")
+            ),
+            VariableResolutionError::OutOfScope {
+                variable_id,
+                section_id,
+                scope,
+            } => write!(
+                f,
+                "interactive variable `{}` used in section `{}` is only declared in section `{}`",
+                variable_id,
+                section_id.as_deref().unwrap_or("<document>"),
+                scope
+            ),
+            VariableResolutionError::UnknownBinding { variable_id } => {
+                write!(f, "binding for undeclared variable `{}`", variable_id)
+            }
+        }
+    }
+}
+
+impl std::error::Error for VariableResolutionError {}
+
+#[derive(Default)]
+struct VariableResolver<'doc> {
+    contexts: Vec<&'doc VariableContext>, // innermost document last
+    sections: Vec<&'doc str>,
+    errors: Vec<VariableResolutionError>,
+}
+
+impl<'doc> DocumentVisitor<'doc> for VariableResolver<'doc> {
+    fn visit_document(&mut self, document: &'doc MathDocument) {
+        let context = document.variable_context.as_ref();
+        if let Some(context) = context {
+            for variable_id in context.unknown_bindings() {
+                self.errors.push(VariableResolutionError::UnknownBinding {
+                    variable_id: variable_id.to_string(),
+                });
+            }
+            self.contexts.push(context);
+        }
+        // Sections of an embedded document do not nest inside the embedding section.
+        let outer_sections = std::mem::take(&mut self.sections);
+        walk_document(self, document);
+        self.sections = outer_sections;
+        if context.is_some() {
+            self.contexts.pop();
+        }
+    }
+
+    fn visit_section(&mut self, section: &'doc Section) {
+        self.sections.push(&section.id);
+        walk_section(self, section);
+        self.sections.pop();
+    }
+
+    fn visit_segment(&mut self, segment: &'doc RichTextSegment) {
+        if let RichTextSegment::InteractiveVariable { variable_id, .. } = segment {
+            self.resolve(variable_id);
+        }
+        walk_segment(self, segment);
+    }
+}
+
+impl VariableResolver<'_> {
+    fn resolve(&mut self, variable_id: &str) {
+        let section_id = self
+            .sections
+            .last()
+            .map(|section_id| section_id.to_string());
+        let mut out_of_scope = None;
+        for context in self.contexts.iter().rev() {
+            if let Some(declaration) = context.declaration(variable_id) {
+                if declaration.is_visible_in(&self.sections) {
+                    return;
+                }
+                out_of_scope.get_or_insert_with(|| declaration.scope.clone().unwrap_or_default());
+            }
+        }
+        self.errors.push(match out_of_scope {
+            Some(scope) => VariableResolutionError::OutOfScope {
+                variable_id: variable_id.to_string(),
+                section_id,
+                scope,
+            },
+            None => VariableResolutionError::Undeclared {
+                variable_id: variable_id.to_string(),
+                section_id,
+            },
+        });
+    }
+}
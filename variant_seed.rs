@@ -0,0 +1,380 @@
+use super::*;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, sync::Arc};
+use ts_rs::TS;
+
+/// Compact description of a randomized exercise variant: a seed plus how to draw each parameter.
+///
+/// Values are generated deterministically from the document id, the seed and the parameter name,
+/// so the same homework set can be reproduced from `(document id, seed)` alone, and adding a
+/// parameter does not change the values drawn for the others.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct VariantSeed {
+    pub seed: u32,
+    pub parameters: Vec<VariantParameter>,
+}
+
+/// A parameter of a variant; identifiers with this name in the exercise's math are substituted.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct VariantParameter {
+    pub name: String,
+    pub distribution: VariantDistribution,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub enum VariantDistribution {
+    Integer {
+        min: i32,
+        max: i32,          // Inclusive
+        exclude: Vec<i32>, // e.g. 0 for a leading coefficient
+    },
+    Decimal {
+        min: f64,
+        max: f64,
+        decimals: u8,
+    },
+    Choice {
+        options: Vec<String>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub enum VariantValue {
+    Integer(i32),
+    Decimal(f64),
+    Choice(String),
+}
+
+/// The values drawn for one variant, in parameter order.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct VariantAssignment {
+    pub document_id: String,
+    pub seed: u32,
+    pub values: Vec<(String, VariantValue)>,
+}
+
+impl VariantSeed {
+    pub fn new(seed: u32) -> Self {
+        VariantSeed {
+            seed,
+            parameters: vec![],
+        }
+    }
+
+    pub fn with_parameter(
+        mut self,
+        name: impl Into<String>,
+        distribution: VariantDistribution,
+    ) -> Self {
+        self.parameters.push(VariantParameter {
+            name: name.into(),
+            distribution,
+        });
+        self
+    }
+
+    /// The same parameters under a different seed, e.g. one per student.
+    pub fn reseeded(&self, seed: u32) -> Self {
+        VariantSeed {
+            seed,
+            parameters: self.parameters.clone(),
+        }
+    }
+
+    pub fn generate(&self, document_id: &str) -> VariantAssignment {
+        VariantAssignment {
+            document_id: document_id.to_string(),
+            seed: self.seed,
+            values: self
+                .parameters
+                .iter()
+                .map(|parameter| {
+                    let mut rng = VariantRng::new(document_id, self.seed, &parameter.name);
+                    (
+                        parameter.name.clone(),
+                        parameter.distribution.sample(&mut rng),
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+impl VariantDistribution {
+    fn sample(&self, rng: &mut VariantRng) -> VariantValue {
+        match self {
+            VariantDistribution::Integer { min, max, exclude } => {
+                let (low, high) = (*min.min(max) as i64, *min.max(max) as i64);
+                let mut excluded: Vec<i64> = exclude
+                    .iter()
+                    .map(|value| *value as i64)
+                    .filter(|value| (low..=high).contains(value))
+                    .collect();
+                excluded.sort();
+                excluded.dedup();
+                // With everything excluded there is nothing sensible to draw; fall back to `min`.
+                let allowed = (high - low + 1) as u64 - excluded.len() as u64;
+                if allowed == 0 {
+                    return VariantValue::Integer(low as i32);
+                }
+                // Draw the k-th allowed value, stepping over the excluded ones below it.
+                let mut value = low + rng.below(allowed) as i64;
+                for skipped in excluded {
+                    if skipped <= value {
+                        value += 1;
+                    }
+                }
+                VariantValue::Integer(value as i32)
+            }
+            VariantDistribution::Decimal { min, max, decimals } => {
+                let scale = 10f64.powi(*decimals as i32);
+                let value = min + (max - min) * rng.unit();
+                VariantValue::Decimal((value * scale).round() / scale)
+            }
+            VariantDistribution::Choice { options } => VariantValue::Choice(match options.len() {
+                0 => String::new(),
+                len => options[rng.below(len as u64) as usize].clone(),
+            }),
+        }
+    }
+}
+
+impl VariantValue {
+    /// The value as an expression: numbers become quantities, choices identifiers.
+    pub fn to_math_node(&self) -> MathNode {
+        let number = match self {
+            VariantValue::Integer(value) => value.to_string(),
+            VariantValue::Decimal(value) => value.to_string(),
+            VariantValue::Choice(choice) => {
+                return MathNode::identifier(Identifier::new_simple(choice.clone()));
+            }
+        };
+        MathNode {
+            id: number.clone(),
+            content: Arc::new(MathNodeContent::Quantity {
+                number,
+                scientific_notation: None,
+                unit: None,
+            }),
+        }
+    }
+}
+
+impl VariantAssignment {
+    pub fn get(&self, name: &str) -> Option<&VariantValue> {
+        self.values
+            .iter()
+            .find(|(parameter, _)| parameter == name)
+            .map(|(_, value)| value)
+    }
+
+    /// Replaces every plain identifier named after a parameter in `node` by the drawn value.
+    pub fn substitute(&self, node: &MathNode) -> MathNode {
+        let mut substitute = |candidate: &MathNode| match candidate.content.as_ref() {
+            MathNodeContent::Identifier(Identifier {
+                body,
+                pre_script: None,
+                mid_script: None,
+                post_script: None,
+                primes: 0,
+                ..
+            }) => self.get(body).map(VariantValue::to_math_node),
+            _ => None,
+        };
+        node.transform(&mut substitute)
+    }
+
+    /// Substitutes the drawn values into every expression in `section`.
+    pub fn apply_to_section(&self, section: &mut Section) {
+        VariantSubstituter { assignment: self }.visit_section_mut(section);
+    }
+}
+
+/// Draws `ParameterSpace::sample` makes per combination asked for before giving up.
+const SAMPLE_ATTEMPTS: usize = 100;
+
+impl ParameterSpace {
+    /// Default values drawn from each parameter's range under `variant_seed`, falling back to
+    /// `default_values` for unseeded spaces and parameters without a bounded range. The draw is
+    /// the first combination `sample` finds, so it satisfies the constraints when it can.
+    pub fn seeded_defaults(&self, document_id: &str) -> BTreeMap<String, f64> {
+        let Some(seed) = self.variant_seed else {
+            return self.default_values.clone();
+        };
+        match self.sample(document_id, seed, 1) {
+            Ok(mut samples) if !samples.is_empty() => samples.remove(0),
+            _ => self.draw(&mut self.parameter_rngs(document_id, seed)),
+        }
+    }
+
+    /// Up to `count` distinct parameter combinations the space accepts, e.g. for playground
+    /// previews. Values are drawn from each parameter's range, deterministically for the same
+    /// document id and seed, and combinations violating a constraint are rejected; a space whose
+    /// constraints are tight (equalities between continuous parameters) or unsatisfiable yields
+    /// fewer. Parameters without a bounded range keep their default value.
+    pub fn sample(
+        &self,
+        document_id: &str,
+        seed: u32,
+        count: usize,
+    ) -> Result<Vec<BTreeMap<String, f64>>, ParameterSpaceError> {
+        let mut rngs = self.parameter_rngs(document_id, seed);
+        let mut samples: Vec<BTreeMap<String, f64>> = vec![];
+        for _ in 0..count * SAMPLE_ATTEMPTS {
+            if samples.len() == count {
+                break;
+            }
+            let values = self.draw(&mut rngs);
+            if self.accepts(&values)? && !samples.contains(&values) {
+                samples.push(values);
+            }
+        }
+        Ok(samples)
+    }
+
+    /// One generator per parameter, so that adding a parameter leaves the others' draws alone.
+    fn parameter_rngs(&self, document_id: &str, seed: u32) -> Vec<VariantRng> {
+        self.parameters
+            .iter()
+            .map(|parameter| VariantRng::new(document_id, seed, &parameter.name))
+            .collect()
+    }
+
+    fn draw(&self, rngs: &mut [VariantRng]) -> BTreeMap<String, f64> {
+        let mut values = self.default_values.clone();
+        for (parameter, rng) in self.parameters.iter().zip(rngs) {
+            let fallback = || parameter.range.min.or(parameter.range.max).unwrap_or(0.0);
+            let value = parameter
+                .sample(rng)
+                .or_else(|| values.get(&parameter.name).copied())
+                .unwrap_or_else(fallback);
+            values.insert(parameter.name.clone(), value);
+        }
+        values
+    }
+}
+
+impl Parameter {
+    fn sample(&self, rng: &mut VariantRng) -> Option<f64> {
+        let bounded = || Some((self.range.min?, self.range.max?));
+        match &self.parameter_type {
+            ParameterType::Boolean => Some(rng.below(2) as f64),
+            ParameterType::Categorical { options } if !options.is_empty() => {
+                Some(rng.below(options.len() as u64) as f64)
+            }
+            ParameterType::Categorical { .. } => None,
+            ParameterType::Integer => {
+                let (min, max) = bounded()?;
+                let (low, high) = (min.ceil() as i64, max.floor() as i64);
+                (low <= high).then(|| (low + rng.below((high - low + 1) as u64) as i64) as f64)
+            }
+            ParameterType::Continuous | ParameterType::Discrete => {
+                let (min, max) = bounded()?;
+                match self.range.step.filter(|step| *step > 0.0) {
+                    Some(step) => {
+                        let steps = ((max - min) / step).floor() as u64;
+                        Some(min + step * rng.below(steps + 1) as f64)
+                    }
+                    None => Some(min + (max - min) * rng.unit()),
+                }
+            }
+        }
+    }
+}
+
+impl MathDocument {
+    /// Instantiates every seeded exercise section and seeded playground default in place.
+    /// Returns the assignments used for the sections, in document order.
+    pub fn instantiate_variants(&mut self) -> Vec<VariantAssignment> {
+        if let MathDocumentType::InteractivePlayground(playground) = &mut self.content_type {
+            playground.parameter_space.default_values =
+                playground.parameter_space.seeded_defaults(&self.id);
+        }
+        let mut instantiator = VariantInstantiator {
+            document_id: self.id.clone(),
+            assignments: vec![],
+        };
+        instantiator.visit_document_mut(self);
+        instantiator.assignments
+    }
+}
+
+struct VariantSubstituter<'a> {
+    assignment: &'a VariantAssignment,
+}
+
+impl DocumentVisitorMut for VariantSubstituter<'_> {
+    fn visit_math_node_mut(&mut self, node: &mut MathNode) {
+        *node = self.assignment.substitute(node);
+    }
+}
+
+struct VariantInstantiator {
+    document_id: String,
+    assignments: Vec<VariantAssignment>,
+}
+
+impl DocumentVisitorMut for VariantInstantiator {
+    fn visit_section_mut(&mut self, section: &mut Section) {
+        // Nested seeded sections are instantiated first, so their own values take precedence.
+        walk_section_mut(self, section);
+        if let Some(seed) = &section.variant_seed {
+            let assignment = seed.generate(&self.document_id);
+            assignment.apply_to_section(section);
+            self.assignments.push(assignment);
+        }
+    }
+}
+
+/// SplitMix64 seeded with an FNV-1a hash of the variant's inputs. Stable across platforms and
+/// releases, which a hasher from std does not guarantee.
+struct VariantRng {
+    state: u64,
+}
+
+impl VariantRng {
+    fn new(document_id: &str, seed: u32, parameter: &str) -> Self {
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        let bytes = document_id
+            .bytes()
+            .chain([0])
+            .chain(seed.to_le_bytes())
+            .chain([0])
+            .chain(parameter.bytes());
+        for byte in bytes {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+        VariantRng { state: hash }
+    }
+
+    fn next(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in `0..bound`; `bound` must be non-zero.
+    fn below(&mut self, bound: u64) -> u64 {
+        // Rejection sampling keeps the draw unbiased.
+        let zone = u64::MAX - u64::MAX % bound;
+        loop {
+            let value = self.next();
+            if value < zone {
+                return value % bound;
+            }
+        }
+    }
+
+    /// Uniform in `[0, 1)`.
+    fn unit(&mut self) -> f64 {
+        (self.next() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
@@ -0,0 +1,47 @@
+use super::*;
+use std::sync::Arc;
+
+impl MathNode {
+    /// `inner` with a vector mark over or on all of it.
+    pub fn decorated(
+        id: impl Into<String>,
+        inner: MathNode,
+        decoration: VectorDecoration,
+    ) -> MathNode {
+        MathNode {
+            id: id.into(),
+            content: Arc::new(MathNodeContent::Decorated {
+                inner: Arc::new(inner),
+                decoration,
+            }),
+        }
+    }
+
+    /// `‖inner‖`, or `‖inner‖_subscript`.
+    pub fn norm(id: impl Into<String>, inner: MathNode, subscript: Option<MathNode>) -> MathNode {
+        MathNode {
+            id: id.into(),
+            content: Arc::new(MathNodeContent::Norm {
+                inner: Arc::new(inner),
+                subscript: subscript.map(Arc::new),
+            }),
+        }
+    }
+
+    /// The vector mark on this node: a `Decorated` node's, or `Hat` for an identifier whose only
+    /// middle script is a hat, the older way of writing `x̂`.
+    pub fn vector_decoration(&self) -> Option<VectorDecoration> {
+        match self.content.as_ref() {
+            MathNodeContent::Decorated { decoration, .. } => Some(*decoration),
+            MathNodeContent::Identifier(Identifier {
+                mid_script: Some(script),
+                ..
+            }) if script.sub_script.is_empty()
+                && script.super_script == [SpecialMiddleScriptContentTypeNode::Hat] =>
+            {
+                Some(VectorDecoration::Hat)
+            }
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,361 @@
+use super::*;
+use serde_json::{Map, Value, json};
+use std::fmt;
+
+const ANNOTATION_CONTEXT: &str = "http://www.w3.org/ns/anno.jsonld";
+
+/// Prefix of the extension properties that carry what the W3C model has no place for, so that
+/// exported annotations import back unchanged.
+const TURN_PREFIX: &str = "turn";
+const TURN_NAMESPACE: &str = "urn:turn-render:annotation:";
+
+impl Annotation {
+    /// The annotation in the W3C Web Annotation JSON-LD model, targeting the resource `source`
+    /// (usually the document's IRI).
+    ///
+    /// The target selector becomes a `FragmentSelector` when it is a plain element id and a
+    /// `CssSelector` otherwise, refined by a `TextQuoteSelector` for a `text_anchor`. The content
+    /// becomes a plain-text `TextualBody`.
+    pub fn to_web_annotation(&self, source: &str) -> Value {
+        let mut annotation = json!({
+            "@context": [ANNOTATION_CONTEXT, { TURN_PREFIX: TURN_NAMESPACE }],
+            "id": self.id,
+            "type": "Annotation",
+            "motivation": motivation(&self.annotation_type),
+            "target": self.web_annotation_target(source),
+        });
+        let extension = |name: &str| format!("{}:{}", TURN_PREFIX, name);
+        annotation[extension("annotationType")] = json!(self.annotation_type);
+        if !self.annotation_content.is_empty() {
+            let text = plain_text(&[SectionContentNode::RichText(RichText {
+                segments: self.annotation_content.clone(),
+                alignment: None,
+            })]);
+            annotation["body"] = json!({
+                "type": "TextualBody",
+                "value": text,
+                "format": "text/plain",
+            });
+            annotation[extension("content")] = json!(self.annotation_content);
+        }
+        if let Some(position) = &self.position {
+            annotation[extension("position")] = json!(position);
+        }
+        if let Some(styling) = &self.styling {
+            annotation[extension("styling")] = json!(styling);
+        }
+        annotation
+    }
+
+    /// Reads a W3C Web Annotation. Targets are understood through `CssSelector`,
+    /// `FragmentSelector` and `TextQuoteSelector` selectors (on their own or refined by one
+    /// another); a target without a selector annotates the whole resource. Textual bodies become
+    /// the annotation's content.
+    pub fn from_web_annotation(value: &Value) -> Result<Annotation, WebAnnotationError> {
+        let extension = |name: &str| value.get(format!("{}:{}", TURN_PREFIX, name));
+        let id = value
+            .get("id")
+            .or_else(|| value.get("@id"))
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+        if !has_type(value, "Annotation") {
+            return Err(WebAnnotationError::NotAnAnnotation { id });
+        }
+
+        let target = match value.get("target") {
+            Some(Value::Array(targets)) => targets.first(),
+            target => target,
+        };
+        let (target_selector, text_anchor) = match target {
+            None => return Err(WebAnnotationError::MissingTarget { id }),
+            Some(Value::String(_)) => (String::new(), None),
+            Some(target) => match target.get("selector") {
+                None => (String::new(), None),
+                Some(Value::Array(selectors)) => selectors
+                    .iter()
+                    .find_map(read_selector)
+                    .ok_or_else(|| WebAnnotationError::UnsupportedSelector { id: id.clone() })?,
+                Some(selector) => read_selector(selector)
+                    .ok_or_else(|| WebAnnotationError::UnsupportedSelector { id: id.clone() })?,
+            },
+        };
+
+        let annotation_content = match extension("content")
+            .and_then(|content| serde_json::from_value(content.clone()).ok())
+        {
+            Some(content) => content,
+            None => read_bodies(value),
+        };
+        let annotation_type = match extension("annotationType")
+            .and_then(|annotation_type| serde_json::from_value(annotation_type.clone()).ok())
+        {
+            Some(annotation_type) => annotation_type,
+            None => annotation_type(value, annotation_content.is_empty()),
+        };
+        Ok(Annotation {
+            id,
+            target_selector,
+            text_anchor,
+            annotation_content,
+            annotation_type,
+            position: extension("position")
+                .and_then(|position| serde_json::from_value(position.clone()).ok()),
+            styling: extension("styling")
+                .and_then(|styling| serde_json::from_value(styling.clone()).ok()),
+        })
+    }
+
+    fn web_annotation_target(&self, source: &str) -> Value {
+        let quote = self.text_anchor.as_ref().map(|anchor| {
+            let mut selector = json!({ "type": "TextQuoteSelector", "exact": anchor.exact });
+            if let Some(prefix) = &anchor.prefix {
+                selector["prefix"] = json!(prefix);
+            }
+            if let Some(suffix) = &anchor.suffix {
+                selector["suffix"] = json!(suffix);
+            }
+            selector
+        });
+        let element = match self.target_selector.as_str() {
+            "" => None,
+            id if is_element_id(id) => Some(json!({
+                "type": "FragmentSelector",
+                "conformsTo": "http://tools.ietf.org/rfc/rfc3986",
+                "value": id,
+            })),
+            css => Some(json!({ "type": "CssSelector", "value": css })),
+        };
+        let selector = match (element, quote) {
+            (Some(mut element), Some(quote)) => {
+                element["refinedBy"] = quote;
+                element
+            }
+            (Some(selector), None) | (None, Some(selector)) => selector,
+            (None, None) => return json!(source),
+        };
+        json!({ "source": source, "selector": selector })
+    }
+}
+
+impl AnnotationOverlay {
+    pub fn to_web_annotations(&self, source: &str) -> Vec<Value> {
+        self.annotations
+            .iter()
+            .map(|annotation| annotation.to_web_annotation(source))
+            .collect()
+    }
+
+    /// Imports annotations from a single annotation, an array of them, an `AnnotationPage` or an
+    /// `AnnotationCollection` with embedded pages. An imported annotation replaces the one with
+    /// the same id; others are appended. Returns the number of annotations imported, and imports
+    /// none if any of them is invalid.
+    pub fn import_web_annotations(&mut self, value: &Value) -> Result<usize, WebAnnotationError> {
+        let imported = annotation_values(value)
+            .into_iter()
+            .map(Annotation::from_web_annotation)
+            .collect::<Result<Vec<_>, _>>()?;
+        let count = imported.len();
+        for annotation in imported {
+            match self
+                .annotations
+                .iter_mut()
+                .find(|existing| existing.id == annotation.id)
+            {
+                Some(existing) => *existing = annotation,
+                None => self.annotations.push(annotation),
+            }
+        }
+        Ok(count)
+    }
+}
+
+impl MathDocument {
+    /// Every overlay annotation in the document, in document order, as a W3C
+    /// `AnnotationCollection` with a single embedded page. `source` is the IRI the document is
+    /// published at.
+    pub fn web_annotation_collection(&self, source: &str) -> Value {
+        let items = self.fold(vec![], |mut items, node| {
+            if let FoldNode::ContentNode(SectionContentNode::AnnotationOverlay(overlay)) = node {
+                items.extend(overlay.to_web_annotations(source));
+            }
+            items
+        });
+        json!({
+            "@context": ANNOTATION_CONTEXT,
+            "id": format!("{}#annotations", source),
+            "type": "AnnotationCollection",
+            "label": self.content_type.title().unwrap_or(&self.id),
+            "total": items.len(),
+            "first": {
+                "type": "AnnotationPage",
+                "startIndex": 0,
+                "items": items,
+            },
+        })
+    }
+}
+
+fn motivation(annotation_type: &AnnotationType) -> &'static str {
+    match annotation_type {
+        AnnotationType::TypeInfo => "classifying",
+        AnnotationType::Definition | AnnotationType::Step | AnnotationType::Animation => {
+            "describing"
+        }
+        AnnotationType::Explanation => "commenting",
+        AnnotationType::Highlight => "highlighting",
+        AnnotationType::Warning => "assessing",
+    }
+}
+
+/// The closest annotation type for an annotation from another tool, from its motivation.
+fn annotation_type(annotation: &Value, no_content: bool) -> AnnotationType {
+    let motivation = match annotation.get("motivation") {
+        Some(Value::Array(motivations)) => motivations.first().and_then(Value::as_str),
+        motivation => motivation.and_then(Value::as_str),
+    };
+    match motivation.map(|m| m.trim_start_matches("oa:")) {
+        Some("highlighting" | "bookmarking") => AnnotationType::Highlight,
+        Some("classifying" | "tagging") => AnnotationType::TypeInfo,
+        Some("describing" | "identifying") => AnnotationType::Definition,
+        Some("assessing" | "moderating") => AnnotationType::Warning,
+        None if no_content => AnnotationType::Highlight,
+        _ => AnnotationType::Explanation,
+    }
+}
+
+fn has_type(value: &Value, expected: &str) -> bool {
+    let matches = |t: &Value| {
+        t.as_str()
+            .is_some_and(|t| t.trim_start_matches("oa:") == expected)
+    };
+    match value.get("type").or_else(|| value.get("@type")) {
+        Some(Value::Array(types)) => types.iter().any(matches),
+        Some(t) => matches(t),
+        None => false,
+    }
+}
+
+/// Whether `selector` is a bare element id rather than a CSS selector.
+fn is_element_id(selector: &str) -> bool {
+    selector
+        .chars()
+        .all(|c| c.is_alphanumeric() || matches!(c, '-' | '_'))
+}
+
+/// The target selector and text anchor described by a W3C selector, if it is one we understand.
+fn read_selector(selector: &Value) -> Option<(String, Option<TextQuoteAnchor>)> {
+    let text = |name: &str| selector.get(name).and_then(Value::as_str);
+    let refined = || {
+        selector
+            .get("refinedBy")
+            .and_then(read_selector)
+            .and_then(|(_, anchor)| anchor)
+    };
+    if has_type(selector, "CssSelector") {
+        Some((text("value")?.to_string(), refined()))
+    } else if has_type(selector, "FragmentSelector") {
+        let value = text("value")?;
+        Some((value.trim_start_matches('#').to_string(), refined()))
+    } else if has_type(selector, "TextQuoteSelector") {
+        let anchor = TextQuoteAnchor {
+            exact: text("exact")?.to_string(),
+            prefix: text("prefix").map(str::to_string),
+            suffix: text("suffix").map(str::to_string),
+        };
+        Some((String::new(), Some(anchor)))
+    } else {
+        None
+    }
+}
+
+/// The content of textual bodies; bodies given by IRI become links.
+fn read_bodies(annotation: &Value) -> Vec<RichTextSegment> {
+    let mut segments = vec![];
+    if let Some(text) = annotation.get("bodyValue").and_then(Value::as_str) {
+        segments.push(RichTextSegment::Text(text.to_string()));
+    }
+    let bodies = match annotation.get("body") {
+        Some(Value::Array(bodies)) => bodies.iter().collect(),
+        Some(body) => vec![body],
+        None => vec![],
+    };
+    for body in bodies {
+        let segment = match body {
+            Value::String(iri) => RichTextSegment::Link {
+                content: vec![RichTextSegment::Text(iri.clone())],
+                target: LinkTarget::Url(iri.clone()),
+                tooltip: None,
+            },
+            Value::Object(body) => match body.get("value").and_then(Value::as_str) {
+                Some(text) => RichTextSegment::Text(text.to_string()),
+                None => match body.get("id").and_then(Value::as_str) {
+                    Some(iri) => RichTextSegment::Link {
+                        content: vec![RichTextSegment::Text(iri.to_string())],
+                        target: LinkTarget::Url(iri.to_string()),
+                        tooltip: None,
+                    },
+                    None => continue,
+                },
+            },
+            _ => continue,
+        };
+        if !segments.is_empty() {
+            segments.push(RichTextSegment::Text(" ".to_string()));
+        }
+        segments.push(segment);
+    }
+    segments
+}
+
+/// The annotations in a single annotation, an array, a page or a collection with embedded pages.
+fn annotation_values(value: &Value) -> Vec<&Value> {
+    match value {
+        Value::Array(values) => values.iter().flat_map(annotation_values).collect(),
+        Value::Object(object) if has_type(value, "AnnotationCollection") => {
+            let mut values = vec![];
+            let mut page = object.get("first");
+            while let Some(Value::Object(current)) = page {
+                values.extend(page_items(current));
+                page = current.get("next");
+            }
+            values
+        }
+        Value::Object(object) if has_type(value, "AnnotationPage") => page_items(object),
+        value => vec![value],
+    }
+}
+
+fn page_items(page: &Map<String, Value>) -> Vec<&Value> {
+    match page.get("items") {
+        Some(Value::Array(items)) => items.iter().collect(),
+        _ => vec![],
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum WebAnnotationError {
+    NotAnAnnotation { id: String },
+    MissingTarget { id: String },
+    UnsupportedSelector { id: String },
+}
+
+impl fmt::Display for WebAnnotationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WebAnnotationError::NotAnAnnotation { id } => {
+                write!(f, "`{}` is not a web annotation", id)
+            }
+            WebAnnotationError::MissingTarget { id } => {
+                write!(f, "web annotation `{}` has no target", id)
+            }
+            WebAnnotationError::UnsupportedSelector { id } => write!(
+                f,
+                "web annotation `{}` has no CSS, fragment or text quote selector",
+                id
+            ),
+        }
+    }
+}
+
+impl std::error::Error for WebAnnotationError {}
@@ -0,0 +1,335 @@
+use super::*;
+use std::fmt;
+
+/// A structural problem in a `MathNode` that the renderer cannot display sensibly.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MathNodeError {
+    EmptyOperation {
+        path: NodePath,
+        kind: &'static str, // `MathNodeContent::kind_name` of the operation
+    },
+    EmptyMatrix {
+        path: NodePath,
+    },
+    RaggedMatrix {
+        path: NodePath,
+        row: usize,
+        expected: usize, // length of the first row
+        found: usize,
+    },
+    MatrixBarOutOfRange {
+        path: NodePath,
+        after: usize, // the matrix's `augmented_after`
+        columns: usize,
+    },
+    MatrixHighlightOutOfRange {
+        path: NodePath, // the highlight, e.g. `highlights[0]`
+        axis: MatrixAxis,
+        index: usize,
+        size: usize, // rows or columns of the matrix
+    },
+    InvalidNumber {
+        path: NodePath,
+        number: String,
+    },
+    EmptyIdentifier {
+        path: NodePath,
+    },
+    VariableNameNotIdentifier {
+        path: NodePath, // the `name` of the definition
+    },
+    FunctionDefinitionNotCall {
+        path: NodePath, // the `custom_function` of the definition
+    },
+    FunctionClauseArity {
+        path: NodePath,  // the clause, e.g. `clauses[1]`
+        expected: usize, // parameters of the `custom_function`
+        found: usize,
+    },
+    EmptyLimitVariable {
+        path: NodePath,
+    },
+    MissingDifferentials {
+        path: NodePath,
+    },
+}
+
+impl MathNodeError {
+    /// Where the problem is, relative to the expression that was validated.
+    pub fn path(&self) -> &NodePath {
+        match self {
+            MathNodeError::EmptyOperation { path, .. }
+            | MathNodeError::EmptyMatrix { path }
+            | MathNodeError::RaggedMatrix { path, .. }
+            | MathNodeError::MatrixBarOutOfRange { path, .. }
+            | MathNodeError::MatrixHighlightOutOfRange { path, .. }
+            | MathNodeError::InvalidNumber { path, .. }
+            | MathNodeError::EmptyIdentifier { path }
+            | MathNodeError::VariableNameNotIdentifier { path }
+            | MathNodeError::FunctionDefinitionNotCall { path }
+            | MathNodeError::FunctionClauseArity { path, .. }
+            | MathNodeError::EmptyLimitVariable { path }
+            | MathNodeError::MissingDifferentials { path } => path,
+        }
+    }
+}
+
+impl MathNode {
+    /// The structural problems in this expression, in pre-order, each with the path of the node
+    /// at fault:
+    /// - `Additions`, `Multiplications`, `BinaryOperation`, `And` and `Or` without terms
+    /// - matrices without rows, with rows of different lengths, or with an augmentation bar or a
+    ///   highlighted row or column outside the matrix
+    /// - quantities whose `number` is not a finite decimal number, e.g. `1,5` or `NaN`
+    /// - identifiers with an empty body
+    /// - variable definitions naming something other than an identifier, and function
+    ///   definitions whose `custom_function` is not a function call
+    /// - function definition clauses with a different number of arguments than the function has
+    ///   parameters
+    /// - limits without a variable and integrals without differentials
+    pub fn validate(&self) -> Vec<MathNodeError> {
+        let mut errors = vec![];
+        for (path, node) in self.descendants_with_paths() {
+            check_node(&node.content, path, &mut errors);
+        }
+        errors
+    }
+
+    pub fn is_well_formed(&self) -> bool {
+        self.validate().is_empty()
+    }
+}
+
+fn check_node(content: &MathNodeContent, path: NodePath, errors: &mut Vec<MathNodeError>) {
+    let empty = match content {
+        MathNodeContent::Additions { terms } => terms.is_empty(),
+        MathNodeContent::Multiplications { terms } => terms.is_empty(),
+        MathNodeContent::BinaryOperation { terms, .. } => terms.is_empty(),
+        MathNodeContent::And(terms) | MathNodeContent::Or(terms) => terms.is_empty(),
+        _ => false,
+    };
+    if empty {
+        errors.push(MathNodeError::EmptyOperation {
+            kind: content.kind_name(),
+            path,
+        });
+        return;
+    }
+
+    let error = match content {
+        MathNodeContent::Matrix {
+            rows,
+            augmented_after,
+            highlights,
+            ..
+        } => match rows.first() {
+            None => Some(MathNodeError::EmptyMatrix { path }),
+            Some(first) => match rows
+                .iter()
+                .enumerate()
+                .find(|(_, row)| row.len() != first.len())
+            {
+                Some((row, cells)) => Some(MathNodeError::RaggedMatrix {
+                    path,
+                    row,
+                    expected: first.len(),
+                    found: cells.len(),
+                }),
+                None => matrix_decoration_error(
+                    path,
+                    rows.len(),
+                    first.len(),
+                    *augmented_after,
+                    highlights,
+                ),
+            },
+        },
+        MathNodeContent::Quantity { number, .. } if !is_number(number) => {
+            Some(MathNodeError::InvalidNumber {
+                path,
+                number: number.clone(),
+            })
+        }
+        MathNodeContent::Identifier(identifier) if identifier.body.trim().is_empty() => {
+            Some(MathNodeError::EmptyIdentifier { path })
+        }
+        MathNodeContent::VariableDefinition { name, .. }
+            if !matches!(*name.content, MathNodeContent::Identifier(_)) =>
+        {
+            Some(MathNodeError::VariableNameNotIdentifier {
+                path: path.field("name"),
+            })
+        }
+        MathNodeContent::FunctionDefinition {
+            custom_function, ..
+        } if !matches!(
+            *custom_function.content,
+            MathNodeContent::FunctionCall { .. }
+        ) =>
+        {
+            Some(MathNodeError::FunctionDefinitionNotCall {
+                path: path.field("custom_function"),
+            })
+        }
+        MathNodeContent::FunctionDefinition {
+            custom_function,
+            clauses,
+            ..
+        } => match custom_function.content.as_ref() {
+            MathNodeContent::FunctionCall { parameters, .. } => clauses
+                .iter()
+                .enumerate()
+                .find(|(_, clause)| clause.arguments.len() != parameters.len())
+                .map(|(i, clause)| MathNodeError::FunctionClauseArity {
+                    path: path.field("clauses").index(i),
+                    expected: parameters.len(),
+                    found: clause.arguments.len(),
+                }),
+            _ => None,
+        },
+        MathNodeContent::Limit { variable, .. } if variable.trim().is_empty() => {
+            Some(MathNodeError::EmptyLimitVariable { path })
+        }
+        MathNodeContent::Integration { differentials, .. } if differentials.is_empty() => {
+            Some(MathNodeError::MissingDifferentials { path })
+        }
+        _ => None,
+    };
+    errors.extend(error);
+}
+
+/// The augmentation bar or a highlight of a `rows` × `columns` matrix that falls outside it.
+/// A bar has to have columns on either side.
+fn matrix_decoration_error(
+    path: NodePath,
+    rows: usize,
+    columns: usize,
+    augmented_after: Option<usize>,
+    highlights: &[MatrixHighlight],
+) -> Option<MathNodeError> {
+    if let Some(after) = augmented_after
+        && !(1..columns).contains(&after)
+    {
+        return Some(MathNodeError::MatrixBarOutOfRange {
+            path,
+            after,
+            columns,
+        });
+    }
+    highlights.iter().enumerate().find_map(|(i, highlight)| {
+        let size = match highlight.axis {
+            MatrixAxis::Row => rows,
+            MatrixAxis::Column => columns,
+        };
+        (highlight.index >= size).then(|| MathNodeError::MatrixHighlightOutOfRange {
+            path: path.clone().field("highlights").index(i),
+            axis: highlight.axis,
+            index: highlight.index,
+            size,
+        })
+    })
+}
+
+/// Whether `number` is a finite decimal number such as `42`, `-0.5` or `6.02e23`.
+fn is_number(number: &str) -> bool {
+    let number = number.trim();
+    number.starts_with(|c: char| c.is_ascii_digit() || matches!(c, '-' | '+' | '.'))
+        && number.parse::<f64>().is_ok_and(f64::is_finite)
+}
+
+impl fmt::Display for MathNodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let at = |path: &NodePath| {
+            if path.is_root() {
+                "the root".to_string()
+            } else {
+                format!("`{}`", path)
+            }
+        };
+        match self {
+            MathNodeError::EmptyOperation { path, kind } => {
+                write!(f, "{} at {} has no terms", kind, at(path))
+            }
+            MathNodeError::EmptyMatrix { path } => write!(f, "matrix at {} has no rows", at(path)),
+            MathNodeError::RaggedMatrix {
+                path,
+                row,
+                expected,
+                found,
+            } => write!(
+                f,
+                "row {} of the matrix at {} has {} entries, expected {}",
+                row,
+                at(path),
+                found,
+                expected
+            ),
+            MathNodeError::MatrixBarOutOfRange {
+                path,
+                after,
+                columns,
+            } => write!(
+                f,
+                "bar of the matrix at {} is after column {} of {}",
+                at(path),
+                after,
+                columns
+            ),
+            MathNodeError::MatrixHighlightOutOfRange {
+                path,
+                axis,
+                index,
+                size,
+            } => {
+                let axis = match axis {
+                    MatrixAxis::Row => "row",
+                    MatrixAxis::Column => "column",
+                };
+                write!(
+                    f,
+                    "highlight at {} is on {} {}, but the matrix has {}",
+                    at(path),
+                    axis,
+                    index,
+                    size
+                )
+            }
+            MathNodeError::InvalidNumber { path, number } => {
+                write!(
+                    f,
+                    "quantity at {} has invalid number `{}`",
+                    at(path),
+                    number
+                )
+            }
+            MathNodeError::EmptyIdentifier { path } => {
+                write!(f, "identifier at {} is empty", at(path))
+            }
+            MathNodeError::VariableNameNotIdentifier { path } => {
+                write!(f, "variable name at {} is not an identifier", at(path))
+            }
+            MathNodeError::FunctionDefinitionNotCall { path } => {
+                write!(f, "defined function at {} is not a function call", at(path))
+            }
+            MathNodeError::FunctionClauseArity {
+                path,
+                expected,
+                found,
+            } => write!(
+                f,
+                "clause at {} has {} arguments, expected {}",
+                at(path),
+                found,
+                expected
+            ),
+            MathNodeError::EmptyLimitVariable { path } => {
+                write!(f, "limit at {} has no variable", at(path))
+            }
+            MathNodeError::MissingDifferentials { path } => {
+                write!(f, "integral at {} has no differentials", at(path))
+            }
+        }
+    }
+}
+
+impl std::error::Error for MathNodeError {}